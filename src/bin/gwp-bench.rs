@@ -0,0 +1,285 @@
+//! Load generator for GWP servers.
+//!
+//! Opens a pool of sessions, drives a weighted mix of read and write
+//! statements from concurrent workers, and reports throughput and
+//! latency percentiles per statement kind.
+//!
+//! Usage: `gwp-bench [OPTIONS] [ENDPOINT]` (default: `http://localhost:50051`)
+//!
+//! Options:
+//!
+//! - `--sessions N` sessions to open (default: 8)
+//! - `--concurrency N` concurrent workers; workers share sessions
+//!   round-robin when there are more workers than sessions (default: 8)
+//! - `--requests N` total statements to run (default: 10000)
+//! - `--duration SECS` stop after this long instead of after `--requests`
+//! - `--mix READ:WRITE` statement weights (default: `80:20`)
+//! - `--read STATEMENT` read statement (default: `MATCH (n) RETURN n`)
+//! - `--write STATEMENT` write statement
+//!   (default: `INSERT (:Bench {payload: $payload})`)
+//! - `--payload-bytes N` size of the `$payload` string parameter (default: 0)
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+
+use gwp::client::{GqlConnection, GqlSession};
+use gwp::error::GqlError;
+use gwp::types::Value;
+
+/// Benchmark configuration, parsed from the command line.
+#[derive(Debug, Clone)]
+struct Config {
+    endpoint: String,
+    sessions: usize,
+    concurrency: usize,
+    requests: u64,
+    duration: Option<Duration>,
+    read_weight: u64,
+    write_weight: u64,
+    read: String,
+    write: String,
+    payload_bytes: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:50051".to_owned(),
+            sessions: 8,
+            concurrency: 8,
+            requests: 10_000,
+            duration: None,
+            read_weight: 80,
+            write_weight: 20,
+            read: "MATCH (n) RETURN n".to_owned(),
+            write: "INSERT (:Bench {payload: $payload})".to_owned(),
+            payload_bytes: 0,
+        }
+    }
+}
+
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        fn number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+            value
+                .ok_or_else(|| format!("{flag} requires a value"))?
+                .parse()
+                .map_err(|_| format!("{flag} requires a number"))
+        }
+
+        let mut config = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--sessions" => config.sessions = number(&arg, args.next())?,
+                "--concurrency" => config.concurrency = number(&arg, args.next())?,
+                "--requests" => config.requests = number(&arg, args.next())?,
+                "--duration" => {
+                    config.duration = Some(Duration::from_secs_f64(number(&arg, args.next())?));
+                }
+                "--mix" => {
+                    let mix = args.next().unwrap_or_default();
+                    let (read, write) = mix
+                        .split_once(':')
+                        .ok_or("--mix expects READ:WRITE weights")?;
+                    config.read_weight = number("--mix", Some(read.to_owned()))?;
+                    config.write_weight = number("--mix", Some(write.to_owned()))?;
+                }
+                "--read" => config.read = args.next().ok_or("--read requires a statement")?,
+                "--write" => config.write = args.next().ok_or("--write requires a statement")?,
+                "--payload-bytes" => config.payload_bytes = number(&arg, args.next())?,
+                flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+                _ => config.endpoint = arg,
+            }
+        }
+
+        if config.sessions == 0 || config.concurrency == 0 {
+            return Err("--sessions and --concurrency must be at least 1".to_owned());
+        }
+        if config.read_weight + config.write_weight == 0 {
+            return Err("--mix needs a non-zero weight".to_owned());
+        }
+        Ok(config)
+    }
+
+    /// Pick the statement kind for the `n`th request by weighted round-robin.
+    fn kind(&self, n: u64) -> Kind {
+        if n % (self.read_weight + self.write_weight) < self.read_weight {
+            Kind::Read
+        } else {
+            Kind::Write
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Read,
+    Write,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => f.write_str("read"),
+            Self::Write => f.write_str("write"),
+        }
+    }
+}
+
+/// Latencies and error count for one statement kind.
+#[derive(Debug, Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    errors: u64,
+}
+
+impl Samples {
+    fn merge(&mut self, other: Self) {
+        self.latencies.extend(other.latencies);
+        self.errors += other.errors;
+    }
+
+    fn report(&mut self, label: &str, elapsed: Duration) {
+        self.latencies.sort_unstable();
+        let count = self.latencies.len();
+        let percentile = |p: usize| {
+            self.latencies
+                .get((count * p / 100).min(count.saturating_sub(1)))
+                .copied()
+                .unwrap_or_default()
+        };
+        // Sample counts stay far below 2^52, so the conversion is exact.
+        #[allow(clippy::cast_precision_loss)]
+        let throughput = count as f64 / elapsed.as_secs_f64();
+        println!(
+            "{label:<6} {count:>9} {:>7} {:>11.1} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}",
+            self.errors,
+            throughput,
+            percentile(50),
+            percentile(95),
+            percentile(99),
+            self.latencies.last().copied().unwrap_or_default(),
+        );
+    }
+}
+
+/// Run one statement to completion, draining every row.
+async fn run(
+    session: &Mutex<GqlSession>,
+    statement: &str,
+    parameters: HashMap<String, Value>,
+) -> Result<bool, GqlError> {
+    let mut session = session.lock().await;
+    let mut cursor = session.execute(statement, parameters).await?;
+    while cursor.next_row().await?.is_some() {}
+    cursor.is_success().await
+}
+
+async fn worker(
+    config: Arc<Config>,
+    session: Arc<Mutex<GqlSession>>,
+    counter: Arc<AtomicU64>,
+    deadline: Option<Instant>,
+) -> HashMap<Kind, Samples> {
+    let mut parameters = HashMap::new();
+    if config.payload_bytes > 0 {
+        parameters.insert(
+            "payload".to_owned(),
+            Value::String("x".repeat(config.payload_bytes)),
+        );
+    }
+
+    let mut samples: HashMap<Kind, Samples> = HashMap::new();
+    loop {
+        let n = counter.fetch_add(1, Ordering::Relaxed);
+        let done = match deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => n >= config.requests,
+        };
+        if done {
+            return samples;
+        }
+
+        let kind = config.kind(n);
+        let statement = match kind {
+            Kind::Read => &config.read,
+            Kind::Write => &config.write,
+        };
+        let start = Instant::now();
+        let ok = matches!(run(&session, statement, parameters.clone()).await, Ok(true));
+        let entry = samples.entry(kind).or_default();
+        if ok {
+            entry.latencies.push(start.elapsed());
+        } else {
+            entry.errors += 1;
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Arc::new(Config::from_args(std::env::args().skip(1))?);
+
+    let conn = GqlConnection::connect(&config.endpoint).await?;
+    let mut sessions = Vec::with_capacity(config.sessions);
+    for _ in 0..config.sessions {
+        sessions.push(Arc::new(Mutex::new(conn.create_session().await?)));
+    }
+
+    eprintln!(
+        "gwp-bench: {} sessions, {} workers, mix {}:{} against {}",
+        config.sessions,
+        config.concurrency,
+        config.read_weight,
+        config.write_weight,
+        config.endpoint
+    );
+
+    let counter = Arc::new(AtomicU64::new(0));
+    let start = Instant::now();
+    let deadline = config.duration.map(|d| start + d);
+    let mut workers = JoinSet::new();
+    for i in 0..config.concurrency {
+        workers.spawn(worker(
+            Arc::clone(&config),
+            Arc::clone(&sessions[i % sessions.len()]),
+            Arc::clone(&counter),
+            deadline,
+        ));
+    }
+
+    let mut totals: HashMap<Kind, Samples> = HashMap::new();
+    while let Some(result) = workers.join_next().await {
+        for (kind, samples) in result? {
+            totals.entry(kind).or_default().merge(samples);
+        }
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<6} {:>9} {:>7} {:>11} {:>10} {:>10} {:>10} {:>10}",
+        "kind", "ok", "errors", "ops/s", "p50", "p95", "p99", "max"
+    );
+    let mut all = Samples::default();
+    for kind in [Kind::Read, Kind::Write] {
+        if let Some(mut samples) = totals.remove(&kind) {
+            samples.report(&kind.to_string(), elapsed);
+            all.merge(samples);
+        }
+    }
+    all.report("total", elapsed);
+    println!("elapsed {elapsed:.2?}");
+
+    for session in sessions {
+        if let Ok(session) = Arc::try_unwrap(session) {
+            session.into_inner().close().await?;
+        }
+    }
+    Ok(())
+}
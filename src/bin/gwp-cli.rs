@@ -0,0 +1,257 @@
+//! Interactive GQL shell for GWP servers.
+//!
+//! Connects to a server, opens a session, and reads statements from
+//! standard input. Statements end with `;` and may span lines. Lines
+//! starting with `\` are shell commands; `\?` lists them.
+//!
+//! Usage: `gwp-cli [ENDPOINT]` (default: `http://localhost:50051`)
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use gwp::client::{BindingTable, CatalogClient, GqlConnection, GqlSession, Transaction};
+use gwp::error::GqlError;
+use gwp::status;
+use gwp::types::Value;
+
+const HELP: &str = "\
+Statements end with `;` and may span several lines.
+
+  \\set [NAME VALUE]   set a statement parameter, or list parameters
+  \\unset NAME         remove a statement parameter
+  \\begin              begin a transaction
+  \\commit             commit the current transaction
+  \\rollback           roll back the current transaction
+  \\dn                 list schemas
+  \\dg [SCHEMA]        list graphs in a schema
+  \\d GRAPH            describe a graph
+  \\schema NAME        set the session schema
+  \\use GRAPH          set the session graph
+  \\?                  show this help
+  \\q                  quit";
+
+/// Shell state: the session, an optional open transaction, and the
+/// parameters bound to every statement.
+struct Shell {
+    session: GqlSession,
+    catalog: CatalogClient,
+    transaction: Option<Transaction>,
+    parameters: HashMap<String, Value>,
+    schema: String,
+}
+
+impl Shell {
+    async fn execute(&mut self, statement: &str) -> Result<(), GqlError> {
+        let parameters = self.parameters.clone();
+        let mut cursor = match self.transaction.as_mut() {
+            Some(tx) => tx.execute(statement, parameters).await?,
+            None => self.session.execute(statement, parameters).await?,
+        };
+
+        let table = BindingTable::from_cursor(&mut cursor).await?;
+        let summary = cursor.summary().await?.cloned().unwrap_or_default();
+        match summary.status {
+            Some(s) if status::is_exception(&s.code) => {
+                println!("ERROR {}: {}", s.code, s.message);
+            }
+            _ if !table.columns.is_empty() => println!("{table}"),
+            _ => println!("OK ({} rows affected)", summary.rows_affected),
+        }
+        Ok(())
+    }
+
+    /// Run a `\` command. Returns `false` when the shell should exit.
+    async fn command(&mut self, line: &str) -> Result<bool, GqlError> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let arg = words.next();
+        match (command, arg) {
+            ("\\q", _) => return Ok(false),
+            ("\\?", _) => println!("{HELP}"),
+            ("\\set", None) => {
+                let mut names: Vec<_> = self.parameters.keys().collect();
+                names.sort();
+                for name in names {
+                    println!("{name} = {}", self.parameters[name]);
+                }
+            }
+            ("\\set", Some(name)) => {
+                let raw = line
+                    .splitn(3, char::is_whitespace)
+                    .nth(2)
+                    .unwrap_or_default()
+                    .trim();
+                self.parameters.insert(name.to_owned(), parse_value(raw));
+            }
+            ("\\unset", Some(name)) => {
+                self.parameters.remove(name);
+            }
+            ("\\begin", _) => {
+                if self.transaction.is_some() {
+                    println!("a transaction is already open");
+                } else {
+                    self.transaction = Some(self.session.begin_transaction().await?);
+                    println!("BEGIN");
+                }
+            }
+            ("\\commit", _) => match self.transaction.take() {
+                Some(tx) => {
+                    tx.commit().await?;
+                    println!("COMMIT");
+                }
+                None => println!("no open transaction"),
+            },
+            ("\\rollback", _) => match self.transaction.take() {
+                Some(tx) => {
+                    tx.rollback().await?;
+                    println!("ROLLBACK");
+                }
+                None => println!("no open transaction"),
+            },
+            ("\\dn", _) => self.list_schemas().await?,
+            ("\\dg", schema) => {
+                let schema = schema.unwrap_or(&self.schema).to_owned();
+                self.list_graphs(&schema).await?;
+            }
+            ("\\d", Some(graph)) => self.describe_graph(graph).await?,
+            ("\\schema", Some(schema)) => {
+                self.session.set_schema(schema).await?;
+                schema.clone_into(&mut self.schema);
+            }
+            ("\\use", Some(graph)) => self.session.set_graph(graph).await?,
+            _ => println!("unknown command or missing argument: {line} (\\? for help)"),
+        }
+        Ok(true)
+    }
+
+    async fn list_schemas(&mut self) -> Result<(), GqlError> {
+        let rows = self
+            .catalog
+            .list_schemas()
+            .await?
+            .into_iter()
+            .map(|s| {
+                vec![
+                    Value::from(s.name),
+                    Value::from(s.graph_count),
+                    Value::from(s.graph_type_count),
+                ]
+            })
+            .collect();
+        print_table(&["schema", "graphs", "graph_types"], rows);
+        Ok(())
+    }
+
+    async fn list_graphs(&mut self, schema: &str) -> Result<(), GqlError> {
+        let rows = self
+            .catalog
+            .list_graphs(schema)
+            .await?
+            .into_iter()
+            .map(|g| {
+                vec![
+                    Value::from(g.name),
+                    Value::from(g.node_count),
+                    Value::from(g.edge_count),
+                    Value::from(g.storage_mode),
+                ]
+            })
+            .collect();
+        print_table(&["graph", "nodes", "edges", "storage"], rows);
+        Ok(())
+    }
+
+    async fn describe_graph(&mut self, graph: &str) -> Result<(), GqlError> {
+        let info = self.catalog.get_graph_info(&self.schema, graph).await?;
+        let optional = |v: Option<String>| v.map_or(Value::Null, Value::from);
+        let rows = vec![
+            vec![Value::from("schema"), Value::from(info.schema)],
+            vec![Value::from("name"), Value::from(info.name)],
+            vec![Value::from("nodes"), Value::from(info.node_count)],
+            vec![Value::from("edges"), Value::from(info.edge_count)],
+            vec![Value::from("graph_type"), Value::from(info.graph_type)],
+            vec![Value::from("storage"), Value::from(info.storage_mode)],
+            vec![
+                Value::from("memory_limit_bytes"),
+                optional(info.memory_limit_bytes.map(|b| b.to_string())),
+            ],
+            vec![
+                Value::from("threads"),
+                optional(info.threads.map(|t| t.to_string())),
+            ],
+        ];
+        print_table(&["property", "value"], rows);
+        Ok(())
+    }
+}
+
+fn print_table(columns: &[&str], rows: Vec<Vec<Value>>) {
+    let columns = columns.iter().map(|c| (*c).to_owned()).collect();
+    println!("{}", BindingTable::new(columns, rows));
+}
+
+/// Parse a parameter value as a GQL literal, such as `42`, `'text'`, or
+/// `DATE '2026-01-01'`. Anything else is taken as a bare string.
+fn parse_value(raw: &str) -> Value {
+    Value::parse_literal(raw).unwrap_or_else(|_| Value::from(raw))
+}
+
+fn prompt(continuation: bool) {
+    print!("{}", if continuation { "...> " } else { "gwp> " });
+    let _ = io::stdout().flush();
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "http://localhost:50051".to_owned());
+
+    let conn = GqlConnection::connect(&endpoint).await?;
+    let session = conn.create_session().await?;
+    eprintln!(
+        "connected to {endpoint} (session {}); \\? for help",
+        session.session_id()
+    );
+
+    let mut shell = Shell {
+        schema: session.default_schema().unwrap_or("default").to_owned(),
+        session,
+        catalog: conn.create_catalog_client(),
+        transaction: None,
+        parameters: HashMap::new(),
+    };
+
+    let stdin = io::stdin();
+    let mut buffer = String::new();
+    prompt(false);
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let trimmed = line.trim();
+
+        if buffer.is_empty() && trimmed.starts_with('\\') {
+            match shell.command(trimmed).await {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => println!("{e}"),
+            }
+        } else if !trimmed.is_empty() {
+            buffer.push_str(&line);
+            buffer.push('\n');
+            if let Some(statement) = buffer.trim_end().strip_suffix(';') {
+                let statement = statement.to_owned();
+                buffer.clear();
+                if let Err(e) = shell.execute(&statement).await {
+                    println!("{e}");
+                }
+            }
+        }
+        prompt(!buffer.is_empty());
+    }
+
+    if let Some(tx) = shell.transaction.take() {
+        tx.rollback().await?;
+    }
+    shell.session.close().await?;
+    Ok(())
+}
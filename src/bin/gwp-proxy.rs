@@ -0,0 +1,718 @@
+//! Frame-logging debug proxy for GWP.
+//!
+//! Accepts GWP traffic, forwards every call to an upstream server, and
+//! prints each decoded request, response, and result frame. Useful for
+//! debugging interop issues with drivers in other languages.
+//!
+//! Credentials and resumption tokens in handshakes are always redacted. With `--redact-values`,
+//! statement parameters and row values are redacted too.
+//!
+//! Usage: `gwp-proxy [--listen ADDR] [--compact] [--redact-values] [UPSTREAM]`
+//! (defaults: `127.0.0.1:50052`, `http://localhost:50051`)
+
+use std::fmt::Debug;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::{Channel, Endpoint, Server};
+use tonic::{Request, Response, Status, Streaming};
+
+use gwp::proto;
+use gwp::proto::admin_service_client::AdminServiceClient;
+use gwp::proto::admin_service_server::{AdminService, AdminServiceServer};
+use gwp::proto::catalog_service_client::CatalogServiceClient;
+use gwp::proto::catalog_service_server::{CatalogService, CatalogServiceServer};
+use gwp::proto::gql_service_client::GqlServiceClient;
+use gwp::proto::gql_service_server::{GqlService, GqlServiceServer};
+use gwp::proto::notification_service_client::NotificationServiceClient;
+use gwp::proto::notification_service_server::{NotificationService, NotificationServiceServer};
+use gwp::proto::search_service_client::SearchServiceClient;
+use gwp::proto::search_service_server::{SearchService, SearchServiceServer};
+use gwp::proto::session_service_client::SessionServiceClient;
+use gwp::proto::session_service_server::{SessionService, SessionServiceServer};
+use gwp::types::Value;
+
+const REDACTED: &str = "<redacted>";
+
+type MessageStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+type ExecuteStream = MessageStream<proto::ExecuteResponse>;
+type SubscribeStream = MessageStream<proto::Notification>;
+type BulkLoadStream = MessageStream<proto::BulkLoadProgress>;
+type ExecuteBatchStream = MessageStream<proto::ExecuteBatchResponse>;
+type ServerEventsStream = MessageStream<proto::ServerEvent>;
+type IndexBuildProgressStream = MessageStream<proto::IndexBuildProgressUpdate>;
+type BackupDatabaseStream = MessageStream<proto::BackupChunk>;
+type ExportGraphStream = MessageStream<proto::ExportChunk>;
+
+/// Prints numbered, timestamped calls and frames.
+struct Log {
+    start: Instant,
+    next_id: AtomicU64,
+    compact: bool,
+    redact_values: bool,
+}
+
+impl Log {
+    fn print(&self, id: u64, direction: &str, method: &str, body: &dyn Debug) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        if self.compact {
+            println!("[{elapsed:>9.3}s] #{id} {direction} {method} {body:?}");
+        } else {
+            println!("[{elapsed:>9.3}s] #{id} {direction} {method}\n{body:#?}");
+        }
+    }
+
+    fn request(&self, method: &str, body: &dyn Debug) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.print(id, "->", method, body);
+        id
+    }
+
+    fn response<T: Debug>(&self, id: u64, method: &str, result: &Result<Response<T>, Status>) {
+        match result {
+            Ok(response) => self.print(id, "<-", method, response.get_ref()),
+            Err(status) => self.print(id, "<!", method, status),
+        }
+    }
+
+    fn redact(&self, value: &mut proto::Value) {
+        if self.redact_values {
+            *value = Value::from(REDACTED).into();
+        }
+    }
+}
+
+/// Hide passwords, bearer tokens, and resumption tokens.
+fn redact_credentials(request: &proto::HandshakeRequest) -> proto::HandshakeRequest {
+    let mut request = request.clone();
+    if let Some(token) = request.resumption_token.as_mut() {
+        REDACTED.clone_into(token);
+    }
+    redact_secrets(request.credentials.as_mut());
+    request
+}
+
+/// Hide the resumption token and credentials in a resume request.
+fn redact_resume_request(request: &proto::ResumeRequest) -> proto::ResumeRequest {
+    let mut request = request.clone();
+    REDACTED.clone_into(&mut request.resumption_token);
+    redact_secrets(request.credentials.as_mut());
+    request
+}
+
+/// Hide the password or bearer token in `credentials`.
+fn redact_secrets(credentials: Option<&mut proto::AuthCredentials>) {
+    match credentials.and_then(|c| c.method.as_mut()) {
+        Some(proto::auth_credentials::Method::BearerToken(token)) => {
+            REDACTED.clone_into(token);
+        }
+        Some(proto::auth_credentials::Method::Basic(basic)) => {
+            REDACTED.clone_into(&mut basic.password);
+        }
+        None => {}
+    }
+}
+
+/// Hide the resumption token issued by the server.
+fn redact_resumption_token(
+    result: &Result<Response<proto::HandshakeResponse>, Status>,
+) -> Result<Response<proto::HandshakeResponse>, Status> {
+    match result {
+        Ok(response) => {
+            let mut response = response.get_ref().clone();
+            if let Some(token) = response.resumption_token.as_mut() {
+                REDACTED.clone_into(token);
+            }
+            Ok(Response::new(response))
+        }
+        Err(status) => Err(status.clone()),
+    }
+}
+
+struct Proxy {
+    upstream: Channel,
+    log: Arc<Log>,
+}
+
+impl Proxy {
+    /// Log a unary call, forward it upstream, and log the outcome.
+    async fn unary<Req, Resp, Fut>(
+        &self,
+        method: &str,
+        request: Request<Req>,
+        call: impl FnOnce(Request<Req>) -> Fut,
+    ) -> Result<Response<Resp>, Status>
+    where
+        Req: Debug,
+        Resp: Debug,
+        Fut: Future<Output = Result<Response<Resp>, Status>>,
+    {
+        let id = self.log.request(method, request.get_ref());
+        let result = call(request).await;
+        self.log.response(id, method, &result);
+        result
+    }
+
+    /// Relay the messages of a client stream to a channel for the
+    /// upstream call, logging each as `show` leaves it.
+    fn relay<T>(
+        &self,
+        id: u64,
+        method: &'static str,
+        mut requests: Streaming<T>,
+        show: fn(&Log, &mut T),
+    ) -> ReceiverStream<T>
+    where
+        T: Debug + Clone + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let log = Arc::clone(&self.log);
+        tokio::spawn(async move {
+            loop {
+                let message = match requests.message().await {
+                    Ok(Some(message)) => message,
+                    Ok(None) => return,
+                    Err(status) => {
+                        log.print(id, "!>", method, &status);
+                        return;
+                    }
+                };
+                let mut shown = message.clone();
+                show(&log, &mut shown);
+                log.print(id, "->", method, &shown);
+                if tx.send(message).await.is_err() {
+                    return;
+                }
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+
+    /// Log the messages of a streaming response as they pass through,
+    /// each as `show` leaves it.
+    fn messages<T>(
+        &self,
+        id: u64,
+        method: &'static str,
+        response: Result<Response<Streaming<T>>, Status>,
+        show: fn(&Log, &mut T),
+    ) -> Result<Response<MessageStream<T>>, Status>
+    where
+        T: Debug + Clone + Send + 'static,
+    {
+        let response = match response {
+            Ok(response) => response,
+            Err(status) => {
+                self.log.print(id, "<!", method, &status);
+                return Err(status);
+            }
+        };
+        let log = Arc::clone(&self.log);
+        let messages = response.into_inner().map(move |message| {
+            match &message {
+                Ok(message) => {
+                    let mut shown = message.clone();
+                    show(&log, &mut shown);
+                    log.print(id, "<-", method, &shown);
+                }
+                Err(status) => log.print(id, "<!", method, status),
+            }
+            message
+        });
+        Ok(Response::new(Box::pin(messages)))
+    }
+
+    /// Log the frames of an execute response as they pass through.
+    fn frames(
+        &self,
+        id: u64,
+        method: &'static str,
+        response: Result<Response<Streaming<proto::ExecuteResponse>>, Status>,
+    ) -> Result<Response<ExecuteStream>, Status> {
+        let response = match response {
+            Ok(response) => response,
+            Err(status) => {
+                self.log.print(id, "<!", method, &status);
+                return Err(status);
+            }
+        };
+
+        let log = Arc::clone(&self.log);
+        let frames = response.into_inner().map(move |frame| {
+            match &frame {
+                Ok(frame) => {
+                    let mut shown = frame.clone();
+                    if let Some(proto::execute_response::Frame::RowBatch(batch)) =
+                        shown.frame.as_mut()
+                    {
+                        // Redact columnar batches by row, like any other.
+                        let _ = gwp::columnar::decode(batch);
+                        for row in &mut batch.rows {
+                            row.values.iter_mut().for_each(|v| log.redact(v));
+                        }
+                    }
+                    log.print(id, "<-", method, &shown);
+                }
+                Err(status) => log.print(id, "<!", method, status),
+            }
+            frame
+        });
+        Ok(Response::new(Box::pin(frames)))
+    }
+}
+
+/// Implement `$service` for `Proxy`, forwarding the listed unary methods
+/// to `$client`. Methods that need special handling go in the leading
+/// braces.
+macro_rules! forward {
+    (
+        $service:ident via $client:ident { $($extra:tt)* }
+        $($method:ident($req:ident) -> $resp:ident;)*
+    ) => {
+        #[tonic::async_trait]
+        impl $service for Proxy {
+            $($extra)*
+
+            $(
+                async fn $method(
+                    &self,
+                    request: Request<proto::$req>,
+                ) -> Result<Response<proto::$resp>, Status> {
+                    let mut client = $client::new(self.upstream.clone());
+                    self.unary(
+                        concat!(stringify!($service), "/", stringify!($method)),
+                        request,
+                        |r| async move { client.$method(r).await },
+                    )
+                    .await
+                }
+            )*
+        }
+    };
+}
+
+forward! {
+    SessionService via SessionServiceClient {
+        async fn handshake(
+            &self,
+            request: Request<proto::HandshakeRequest>,
+        ) -> Result<Response<proto::HandshakeResponse>, Status> {
+            let method = "SessionService/handshake";
+            let id = self
+                .log
+                .request(method, &redact_credentials(request.get_ref()));
+            let result = SessionServiceClient::new(self.upstream.clone())
+                .handshake(request)
+                .await;
+            self.log
+                .response(id, method, &redact_resumption_token(&result));
+            result
+        }
+
+        async fn resume(
+            &self,
+            request: Request<proto::ResumeRequest>,
+        ) -> Result<Response<proto::HandshakeResponse>, Status> {
+            let method = "SessionService/resume";
+            let id = self
+                .log
+                .request(method, &redact_resume_request(request.get_ref()));
+            let result = SessionServiceClient::new(self.upstream.clone())
+                .resume(request)
+                .await;
+            self.log
+                .response(id, method, &redact_resumption_token(&result));
+            result
+        }
+
+        async fn refresh_credentials(
+            &self,
+            request: Request<proto::RefreshCredentialsRequest>,
+        ) -> Result<Response<proto::RefreshCredentialsResponse>, Status> {
+            let method = "SessionService/refresh_credentials";
+            let mut shown = request.get_ref().clone();
+            redact_secrets(shown.credentials.as_mut());
+            let id = self.log.request(method, &shown);
+            let result = SessionServiceClient::new(self.upstream.clone())
+                .refresh_credentials(request)
+                .await;
+            self.log.response(id, method, &result);
+            result
+        }
+
+        type ServerEventsStream = ServerEventsStream;
+
+        async fn server_events(
+            &self,
+            request: Request<proto::ServerEventsRequest>,
+        ) -> Result<Response<Self::ServerEventsStream>, Status> {
+            let method = "SessionService/server_events";
+            let id = self.log.request(method, request.get_ref());
+            let response = match SessionServiceClient::new(self.upstream.clone())
+                .server_events(request)
+                .await
+            {
+                Ok(response) => response,
+                Err(status) => {
+                    self.log.print(id, "<!", method, &status);
+                    return Err(status);
+                }
+            };
+            let log = Arc::clone(&self.log);
+            let events = response.into_inner().map(move |event| {
+                match &event {
+                    Ok(event) => log.print(id, "<-", method, event),
+                    Err(status) => log.print(id, "<!", method, status),
+                }
+                event
+            });
+            Ok(Response::new(Box::pin(events)))
+        }
+    }
+    configure(ConfigureRequest) -> ConfigureResponse;
+    reset(ResetRequest) -> ResetResponse;
+    get_session_state(GetSessionStateRequest) -> GetSessionStateResponse;
+    close(CloseRequest) -> CloseResponse;
+    ping(PingRequest) -> PongResponse;
+}
+
+forward! {
+    GqlService via GqlServiceClient {
+        type ExecuteStream = ExecuteStream;
+
+        async fn execute(
+            &self,
+            request: Request<proto::ExecuteRequest>,
+        ) -> Result<Response<Self::ExecuteStream>, Status> {
+            let method = "GqlService/execute";
+            let mut shown = request.get_ref().clone();
+            shown
+                .parameters
+                .values_mut()
+                .for_each(|v| self.log.redact(v));
+            let id = self.log.request(method, &shown);
+            let response = GqlServiceClient::new(self.upstream.clone())
+                .execute(request)
+                .await;
+            self.frames(id, method, response)
+        }
+
+        type ExecuteBatchStream = ExecuteBatchStream;
+
+        async fn execute_batch(
+            &self,
+            request: Request<proto::ExecuteBatchRequest>,
+        ) -> Result<Response<Self::ExecuteBatchStream>, Status> {
+            let method = "GqlService/execute_batch";
+            let mut shown = request.get_ref().clone();
+            for statement in &mut shown.statements {
+                statement
+                    .parameters
+                    .values_mut()
+                    .for_each(|v| self.log.redact(v));
+            }
+            let id = self.log.request(method, &shown);
+            let response = match GqlServiceClient::new(self.upstream.clone())
+                .execute_batch(request)
+                .await
+            {
+                Ok(response) => response,
+                Err(status) => {
+                    self.log.print(id, "<!", method, &status);
+                    return Err(status);
+                }
+            };
+            let log = Arc::clone(&self.log);
+            let messages = response.into_inner().map(move |message| {
+                match &message {
+                    Ok(message) => {
+                        let mut shown = message.clone();
+                        if let Some(proto::execute_batch_response::Frame::Result(
+                            proto::ExecuteResponse {
+                                frame: Some(proto::execute_response::Frame::RowBatch(batch)),
+                            },
+                        )) = shown.frame.as_mut()
+                        {
+                            let _ = gwp::columnar::decode(batch);
+                            for row in &mut batch.rows {
+                                row.values.iter_mut().for_each(|v| log.redact(v));
+                            }
+                        }
+                        log.print(id, "<-", method, &shown);
+                    }
+                    Err(status) => log.print(id, "<!", method, status),
+                }
+                message
+            });
+            Ok(Response::new(Box::pin(messages)))
+        }
+
+        type BulkLoadStream = BulkLoadStream;
+
+        async fn bulk_load(
+            &self,
+            request: Request<Streaming<proto::BulkLoadRequest>>,
+        ) -> Result<Response<Self::BulkLoadStream>, Status> {
+            let method = "GqlService/bulk_load";
+            let id = self.log.request(method, &"<stream>");
+            let chunks = self.relay(id, method, request.into_inner(), |log, chunk| {
+                for node in &mut chunk.nodes {
+                    node.properties.values_mut().for_each(|v| log.redact(v));
+                }
+                for edge in &mut chunk.edges {
+                    edge.properties.values_mut().for_each(|v| log.redact(v));
+                }
+            });
+            let response = GqlServiceClient::new(self.upstream.clone())
+                .bulk_load(chunks)
+                .await;
+            self.messages(id, method, response, |_, _| {})
+        }
+
+        type ExecutePreparedStream = Self::ExecuteStream;
+
+        async fn execute_prepared(
+            &self,
+            request: Request<proto::ExecutePreparedRequest>,
+        ) -> Result<Response<Self::ExecuteStream>, Status> {
+            let method = "GqlService/execute_prepared";
+            let mut shown = request.get_ref().clone();
+            shown
+                .parameters
+                .values_mut()
+                .for_each(|v| self.log.redact(v));
+            let id = self.log.request(method, &shown);
+            let response = GqlServiceClient::new(self.upstream.clone())
+                .execute_prepared(request)
+                .await;
+            self.frames(id, method, response)
+        }
+
+        async fn explain(
+            &self,
+            request: Request<proto::ExplainRequest>,
+        ) -> Result<Response<proto::ExplainResponse>, Status> {
+            let method = "GqlService/explain";
+            let mut shown = request.get_ref().clone();
+            shown
+                .parameters
+                .values_mut()
+                .for_each(|v| self.log.redact(v));
+            let id = self.log.request(method, &shown);
+            let result = GqlServiceClient::new(self.upstream.clone())
+                .explain(request)
+                .await;
+            self.log.response(id, method, &result);
+            result
+        }
+    }
+    prepare(PrepareRequest) -> PrepareResponse;
+    close_prepared(ClosePreparedRequest) -> ClosePreparedResponse;
+    begin_transaction(BeginRequest) -> BeginResponse;
+    commit(CommitRequest) -> CommitResponse;
+    rollback(RollbackRequest) -> RollbackResponse;
+    prepare_commit(PrepareCommitRequest) -> PrepareCommitResponse;
+    commit_prepared(CommitPreparedRequest) -> CommitResponse;
+    rollback_prepared(RollbackPreparedRequest) -> RollbackResponse;
+    savepoint(SavepointRequest) -> SavepointResponse;
+    release_savepoint(ReleaseSavepointRequest) -> SavepointResponse;
+    rollback_to_savepoint(RollbackToSavepointRequest) -> SavepointResponse;
+}
+
+forward! {
+    CatalogService via CatalogServiceClient {}
+    list_schemas(ListSchemasRequest) -> ListSchemasResponse;
+    create_schema(CreateSchemaRequest) -> CreateSchemaResponse;
+    drop_schema(DropSchemaRequest) -> DropSchemaResponse;
+    create_database(CreateDatabaseRequest) -> CreateDatabaseResponse;
+    alter_database(AlterDatabaseRequest) -> AlterDatabaseResponse;
+    get_database_info(GetDatabaseInfoRequest) -> GetDatabaseInfoResponse;
+    list_graphs(ListGraphsRequest) -> ListGraphsResponse;
+    create_graph(CreateGraphRequest) -> CreateGraphResponse;
+    drop_graph(DropGraphRequest) -> DropGraphResponse;
+    get_graph_info(GetGraphInfoRequest) -> GetGraphInfoResponse;
+    clone_graph(CloneGraphRequest) -> CloneGraphResponse;
+    list_graph_types(ListGraphTypesRequest) -> ListGraphTypesResponse;
+    create_graph_type(CreateGraphTypeRequest) -> CreateGraphTypeResponse;
+    drop_graph_type(DropGraphTypeRequest) -> DropGraphTypeResponse;
+}
+
+forward! {
+    AdminService via AdminServiceClient {
+        type IndexBuildProgressStream = IndexBuildProgressStream;
+
+        async fn index_build_progress(
+            &self,
+            request: Request<proto::IndexBuildProgressRequest>,
+        ) -> Result<Response<Self::IndexBuildProgressStream>, Status> {
+            let method = "AdminService/index_build_progress";
+            let id = self.log.request(method, request.get_ref());
+            let response = AdminServiceClient::new(self.upstream.clone())
+                .index_build_progress(request)
+                .await;
+            self.messages(id, method, response, |_, _| {})
+        }
+
+        type BackupDatabaseStream = BackupDatabaseStream;
+
+        async fn backup_database(
+            &self,
+            request: Request<proto::BackupDatabaseRequest>,
+        ) -> Result<Response<Self::BackupDatabaseStream>, Status> {
+            let method = "AdminService/backup_database";
+            let id = self.log.request(method, request.get_ref());
+            let response = AdminServiceClient::new(self.upstream.clone())
+                .backup_database(request)
+                .await;
+            // Backup chunks are opaque and large; log them without data.
+            self.messages(id, method, response, |_, chunk| chunk.data.clear())
+        }
+
+        async fn restore_database(
+            &self,
+            request: Request<Streaming<proto::RestoreDatabaseRequest>>,
+        ) -> Result<Response<proto::RestoreDatabaseResponse>, Status> {
+            let method = "AdminService/restore_database";
+            let id = self.log.request(method, &"<stream>");
+            let chunks = self.relay(id, method, request.into_inner(), |_, chunk| {
+                chunk.data.clear();
+            });
+            let result = AdminServiceClient::new(self.upstream.clone())
+                .restore_database(chunks)
+                .await;
+            self.log.response(id, method, &result);
+            result
+        }
+
+        type ExportGraphStream = ExportGraphStream;
+
+        async fn export_graph(
+            &self,
+            request: Request<proto::ExportGraphRequest>,
+        ) -> Result<Response<Self::ExportGraphStream>, Status> {
+            let method = "AdminService/export_graph";
+            let id = self.log.request(method, request.get_ref());
+            let response = AdminServiceClient::new(self.upstream.clone())
+                .export_graph(request)
+                .await;
+            // Exports hold property values; log them without data.
+            self.messages(id, method, response, |_, chunk| chunk.data.clear())
+        }
+
+        async fn import_graph(
+            &self,
+            request: Request<Streaming<proto::ImportGraphRequest>>,
+        ) -> Result<Response<proto::ImportGraphResponse>, Status> {
+            let method = "AdminService/import_graph";
+            let id = self.log.request(method, &"<stream>");
+            let chunks = self.relay(id, method, request.into_inner(), |_, chunk| {
+                chunk.data.clear();
+            });
+            let result = AdminServiceClient::new(self.upstream.clone())
+                .import_graph(chunks)
+                .await;
+            self.log.response(id, method, &result);
+            result
+        }
+    }
+    get_graph_stats(GetGraphStatsRequest) -> GetGraphStatsResponse;
+    get_label_stats(GetLabelStatsRequest) -> GetLabelStatsResponse;
+    get_graph_config(GetGraphConfigRequest) -> GetGraphConfigResponse;
+    set_graph_config(SetGraphConfigRequest) -> SetGraphConfigResponse;
+    get_query_stats(GetQueryStatsRequest) -> GetQueryStatsResponse;
+    reset_query_stats(ResetQueryStatsRequest) -> ResetQueryStatsResponse;
+    list_sessions(ListSessionsRequest) -> ListSessionsResponse;
+    kill_session(KillSessionRequest) -> KillSessionResponse;
+    wal_status(WalStatusRequest) -> WalStatusResponse;
+    wal_checkpoint(WalCheckpointRequest) -> WalCheckpointResponse;
+    validate(ValidateRequest) -> ValidateResponse;
+    create_index(CreateIndexRequest) -> CreateIndexResponse;
+    drop_index(DropIndexRequest) -> DropIndexResponse;
+    list_indexes(ListIndexesRequest) -> ListIndexesResponse;
+    submit_job(SubmitJobRequest) -> SubmitJobResponse;
+    get_job_status(GetJobStatusRequest) -> GetJobStatusResponse;
+    cancel_job(CancelJobRequest) -> CancelJobResponse;
+    list_jobs(ListJobsRequest) -> ListJobsResponse;
+}
+
+forward! {
+    SearchService via SearchServiceClient {}
+    vector_search(VectorSearchRequest) -> VectorSearchResponse;
+    text_search(TextSearchRequest) -> TextSearchResponse;
+    hybrid_search(HybridSearchRequest) -> HybridSearchResponse;
+}
+
+forward! {
+    NotificationService via NotificationServiceClient {
+        type SubscribeStream = SubscribeStream;
+
+        async fn subscribe(
+            &self,
+            request: Request<Streaming<proto::SubscribeRequest>>,
+        ) -> Result<Response<Self::SubscribeStream>, Status> {
+            let method = "NotificationService/subscribe";
+            let id = self.log.request(method, &"<stream>");
+            let requests = self.relay(id, method, request.into_inner(), |_, _| {});
+            let response = NotificationServiceClient::new(self.upstream.clone())
+                .subscribe(requests)
+                .await;
+            self.messages(id, method, response, |log, notification| {
+                notification
+                    .attributes
+                    .values_mut()
+                    .for_each(|v| log.redact(v));
+            })
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut listen: SocketAddr = "127.0.0.1:50052".parse()?;
+    let mut upstream = "http://localhost:50051".to_owned();
+    let mut compact = false;
+    let mut redact_values = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--listen" => listen = args.next().ok_or("--listen requires an address")?.parse()?,
+            "--compact" => compact = true,
+            "--redact-values" => redact_values = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}").into()),
+            _ => upstream = arg,
+        }
+    }
+
+    let log = Arc::new(Log {
+        start: Instant::now(),
+        next_id: AtomicU64::new(1),
+        compact,
+        redact_values,
+    });
+    let upstream_channel = Endpoint::from_shared(upstream.clone())?.connect_lazy();
+    let proxy = || Proxy {
+        upstream: upstream_channel.clone(),
+        log: Arc::clone(&log),
+    };
+
+    eprintln!("GWP proxy listening on {listen}, forwarding to {upstream}");
+    Server::builder()
+        .add_service(SessionServiceServer::new(proxy()))
+        .add_service(GqlServiceServer::new(proxy()))
+        .add_service(CatalogServiceServer::new(proxy()))
+        .add_service(AdminServiceServer::new(proxy()))
+        .add_service(SearchServiceServer::new(proxy()))
+        .add_service(NotificationServiceServer::new(proxy()))
+        .serve_with_shutdown(listen, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
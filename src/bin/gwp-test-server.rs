@@ -3,25 +3,198 @@
 //! Starts a gRPC server with `MockBackend` on the specified port.
 //! Used by all language bindings for integration tests.
 //!
-//! Usage: `gwp-test-server [PORT]` (default: 50051)
+//! Usage: `gwp-test-server [OPTIONS] [PORT]` (default: 50051)
+//!
+//! Every option can also be set through the environment variable shown
+//! in brackets; flags take precedence.
+//!
+//! - `--port N` [`GWP_TEST_PORT`] port to listen on
+//! - `--auth MODE` [`GWP_TEST_AUTH`] `none` (default), `token:TOKEN`, or
+//!   `basic:USER:PASSWORD`
+//! - `--tls-cert PATH` / `--tls-key PATH` [`GWP_TEST_TLS_CERT`,
+//!   `GWP_TEST_TLS_KEY`] serve TLS with a PEM certificate and key
+//!   (requires the `tls` feature)
+//! - `--idle-timeout SECS` [`GWP_TEST_IDLE_TIMEOUT`] close idle sessions
+//! - `--max-sessions N` [`GWP_TEST_MAX_SESSIONS`] limit concurrent sessions
+//! - `--latency-ms MS` [`GWP_TEST_LATENCY_MS`] delay before each result and commit
+//! - `--jitter-ms MS` [`GWP_TEST_JITTER_MS`] uniform random delay added to
+//!   `--latency-ms`
+//! - `--rows N` [`GWP_TEST_ROWS`] rows returned by `MATCH`/`RETURN` statements
+//! - `--echo true` [`GWP_TEST_ECHO`] answer every statement with its text
+//!   and parameters, for checking value encoding
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::time::Duration;
 
-use gwp::server::GqlServer;
-use gwp::server::mock_backend::MockBackend;
+use gwp::error::GqlError;
+use gwp::proto;
+use gwp::proto::auth_credentials::Method;
+use gwp::server::mock_backend::{Jitter, Latency, MockBackend, MockOperation};
+use gwp::server::{AuthValidator, GqlServer};
+use gwp::types::Value;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let port: u16 = std::env::args()
-        .nth(1)
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(50051);
+type BoxError = Box<dyn std::error::Error>;
 
-    let addr: SocketAddr = format!("0.0.0.0:{port}").parse()?;
+/// Command-line flags, with environment variables as fallback.
+struct Options(HashMap<String, String>);
+
+impl Options {
+    fn parse() -> Result<Self, BoxError> {
+        let mut flags = HashMap::new();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if let Some(flag) = arg.strip_prefix("--") {
+                let value = args.next().ok_or(format!("--{flag} requires a value"))?;
+                flags.insert(flag.to_owned(), value);
+            } else {
+                flags.insert("port".to_owned(), arg);
+            }
+        }
+        Ok(Self(flags))
+    }
+
+    fn get(&self, flag: &str) -> Option<String> {
+        self.0.get(flag).cloned().or_else(|| {
+            let var = format!("GWP_TEST_{}", flag.to_uppercase().replace('-', "_"));
+            std::env::var(var).ok()
+        })
+    }
+
+    fn number<T>(&self, flag: &str) -> Result<Option<T>, BoxError>
+    where
+        T: std::str::FromStr,
+        T::Err: std::error::Error + 'static,
+    {
+        Ok(self.get(flag).map(|v| v.parse()).transpose()?)
+    }
+}
+
+/// Accepts a single bearer token or username/password pair.
+enum StaticAuth {
+    Token(String),
+    Basic { username: String, password: String },
+}
+
+impl StaticAuth {
+    fn parse(mode: &str) -> Result<Option<Self>, BoxError> {
+        if mode == "none" {
+            return Ok(None);
+        }
+        if let Some(token) = mode.strip_prefix("token:") {
+            return Ok(Some(Self::Token(token.to_owned())));
+        }
+        if let Some((username, password)) = mode
+            .strip_prefix("basic:")
+            .and_then(|rest| rest.split_once(':'))
+        {
+            return Ok(Some(Self::Basic {
+                username: username.to_owned(),
+                password: password.to_owned(),
+            }));
+        }
+        Err(format!("unknown auth mode {mode:?}").into())
+    }
+}
+
+#[tonic::async_trait]
+impl AuthValidator for StaticAuth {
+    async fn validate(&self, credentials: &proto::AuthCredentials) -> Result<(), GqlError> {
+        let accepted = match (self, &credentials.method) {
+            (Self::Token(expected), Some(Method::BearerToken(token))) => token == expected,
+            (Self::Basic { username, password }, Some(Method::Basic(basic))) => {
+                &basic.username == username && &basic.password == password
+            }
+            _ => false,
+        };
+        if accepted {
+            Ok(())
+        } else {
+            Err(GqlError::Session("invalid credentials".to_owned()))
+        }
+    }
+}
+
+fn configure_backend(options: &Options) -> Result<MockBackend, BoxError> {
     let backend = MockBackend::new();
 
+    let latency = options.number::<u64>("latency-ms")?.unwrap_or(0);
+    let jitter = options.number::<u64>("jitter-ms")?.unwrap_or(0);
+    if latency > 0 || jitter > 0 {
+        let latency = Latency::fixed(Duration::from_millis(latency))
+            .with_jitter(Jitter::Uniform(Duration::from_millis(jitter)));
+        backend.set_latency(MockOperation::ExecuteFirstFrame, latency);
+        backend.set_latency(MockOperation::Commit, latency);
+    }
+
+    if let Some(count) = options.number::<i64>("rows")? {
+        let rows: Vec<Vec<Value>> = (0..count)
+            .map(|i| vec![Value::from(format!("person-{i}")), Value::from(20 + i % 60)])
+            .collect();
+        for prefix in ["MATCH", "RETURN"] {
+            backend
+                .on_statement_prefix(prefix)
+                .respond_rows(["name", "age"], rows.clone());
+        }
+    }
+
+    if options.number::<bool>("echo")?.unwrap_or(false) {
+        backend.set_echo(true);
+    }
+
+    Ok(backend)
+}
+
+#[cfg(feature = "tls")]
+fn configure_tls(
+    server: GqlServer<MockBackend>,
+    cert: &str,
+    key: &str,
+) -> Result<GqlServer<MockBackend>, BoxError> {
+    let identity = tonic::transport::Identity::from_pem(std::fs::read(cert)?, std::fs::read(key)?);
+    Ok(server.tls(tonic::transport::ServerTlsConfig::new().identity(identity)))
+}
+
+#[cfg(not(feature = "tls"))]
+fn configure_tls(
+    _server: GqlServer<MockBackend>,
+    _cert: &str,
+    _key: &str,
+) -> Result<GqlServer<MockBackend>, BoxError> {
+    Err("TLS requires building with the `tls` feature".into())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), BoxError> {
+    let options = Options::parse()?;
+    let port: u16 = options.number("port")?.unwrap_or(50051);
+    let addr: SocketAddr = format!("0.0.0.0:{port}").parse()?;
+
+    let mut server = GqlServer::builder(configure_backend(&options)?);
+    if let Some(auth) =
+        StaticAuth::parse(&options.get("auth").unwrap_or_else(|| "none".to_owned()))?
+    {
+        server = server.auth(auth);
+    }
+    match (options.get("tls-cert"), options.get("tls-key")) {
+        (Some(cert), Some(key)) => server = configure_tls(server, &cert, &key)?,
+        (None, None) => {}
+        _ => return Err("--tls-cert and --tls-key must be given together".into()),
+    }
+    if let Some(secs) = options.number::<u64>("idle-timeout")? {
+        server = server.idle_timeout(Duration::from_secs(secs));
+    }
+    if let Some(limit) = options.number::<usize>("max-sessions")? {
+        server = server.max_sessions(limit);
+    }
+
     eprintln!("GWP test server listening on {addr}");
-    GqlServer::start(backend, addr).await?;
+    server
+        .shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .serve(addr)
+        .await?;
 
     Ok(())
 }
@@ -0,0 +1,234 @@
+//! Row batch integrity checksums.
+//!
+//! Sessions that negotiate checksums at handshake receive every
+//! `RowBatch` stamped with a CRC32C of its rows, which the client cursor
+//! verifies. This catches corruption introduced between server and
+//! client, for example by a misbehaving proxy or interceptor.
+//!
+//! The checksum covers a canonical encoding of the row values rather than
+//! their protobuf bytes, because property maps have no defined order on
+//! the wire.
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::proto::value::Kind;
+
+/// CRC32C (Castagnoli) polynomial, reflected.
+const POLYNOMIAL: u32 = 0x82F6_3B78;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i: u32 = 0;
+    while i < 256 {
+        let mut crc = i;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// The checksum of a row batch's rows.
+#[must_use]
+pub fn row_batch_checksum(rows: &[proto::Row]) -> u32 {
+    let mut crc = Crc32c::default();
+    crc.len(rows.len());
+    for row in rows {
+        crc.len(row.values.len());
+        for value in &row.values {
+            crc.value(value);
+        }
+    }
+    crc.finish()
+}
+
+/// Whether `batch` carries a checksum that does not match its rows.
+#[must_use]
+pub fn is_corrupt(batch: &proto::RowBatch) -> bool {
+    batch
+        .checksum
+        .is_some_and(|checksum| checksum != row_batch_checksum(&batch.rows))
+}
+
+/// Incremental CRC32C over a canonical encoding of values.
+struct Crc32c {
+    state: u32,
+    scratch: Vec<u8>,
+}
+
+impl Default for Crc32c {
+    fn default() -> Self {
+        Self {
+            state: !0,
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl Crc32c {
+    fn finish(&self) -> u32 {
+        !self.state
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            let index = (self.state ^ u32::from(byte)) & 0xFF;
+            self.state = TABLE[index as usize] ^ (self.state >> 8);
+        }
+    }
+
+    fn len(&mut self, len: usize) {
+        self.bytes(&u64::try_from(len).unwrap_or(u64::MAX).to_le_bytes());
+    }
+
+    /// Length-prefixed bytes, so adjacent fields cannot run together.
+    fn field(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.bytes(bytes);
+    }
+
+    fn value(&mut self, value: &proto::Value) {
+        match &value.kind {
+            None => self.bytes(&[0]),
+            Some(Kind::ListValue(list)) => {
+                self.bytes(&[17]);
+                self.len(list.elements.len());
+                for element in &list.elements {
+                    self.value(element);
+                }
+            }
+            Some(Kind::RecordValue(record)) => {
+                self.bytes(&[18]);
+                self.len(record.fields.len());
+                for field in &record.fields {
+                    self.field(field.name.as_bytes());
+                    match &field.value {
+                        Some(value) => self.value(value),
+                        None => self.bytes(&[0]),
+                    }
+                }
+            }
+            Some(Kind::NodeValue(node)) => {
+                self.bytes(&[19]);
+                self.node(node);
+            }
+            Some(Kind::EdgeValue(edge)) => {
+                self.bytes(&[20]);
+                self.edge(edge);
+            }
+            Some(Kind::PathValue(path)) => {
+                self.bytes(&[21]);
+                self.len(path.nodes.len());
+                for node in &path.nodes {
+                    self.node(node);
+                }
+                self.len(path.edges.len());
+                for edge in &path.edges {
+                    self.edge(edge);
+                }
+            }
+            // Scalars have a deterministic protobuf encoding.
+            Some(scalar) => {
+                let mut scratch = std::mem::take(&mut self.scratch);
+                scratch.clear();
+                scalar.encode(&mut scratch);
+                self.field(&scratch);
+                self.scratch = scratch;
+            }
+        }
+    }
+
+    fn labels(&mut self, labels: &[String]) {
+        self.len(labels.len());
+        for label in labels {
+            self.field(label.as_bytes());
+        }
+    }
+
+    fn properties(&mut self, properties: &HashMap<String, proto::Value>) {
+        let mut entries: Vec<_> = properties.iter().collect();
+        entries.sort_unstable_by_key(|(name, _)| *name);
+        self.len(entries.len());
+        for (name, value) in entries {
+            self.field(name.as_bytes());
+            self.value(value);
+        }
+    }
+
+    fn node(&mut self, node: &proto::Node) {
+        self.field(&node.id);
+        self.labels(&node.labels);
+        self.properties(&node.properties);
+    }
+
+    fn edge(&mut self, edge: &proto::Edge) {
+        self.field(&edge.id);
+        self.labels(&edge.labels);
+        self.field(&edge.source_node_id);
+        self.field(&edge.target_node_id);
+        self.bytes(&[u8::from(edge.undirected)]);
+        self.properties(&edge.properties);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Value;
+
+    #[test]
+    fn matches_the_crc32c_check_value() {
+        let mut crc = Crc32c::default();
+        crc.bytes(b"123456789");
+        assert_eq!(crc.finish(), 0xE306_9283);
+    }
+
+    #[test]
+    fn ignores_property_order_and_detects_changes() {
+        let node = |names: &[&str]| {
+            let properties = names
+                .iter()
+                .map(|name| ((*name).to_owned(), proto::Value::from(Value::from(*name))))
+                .collect();
+            proto::Value {
+                kind: Some(Kind::NodeValue(proto::Node {
+                    id: vec![1],
+                    labels: vec!["Person".to_owned()],
+                    properties,
+                    label_refs: Vec::new(),
+                    property_refs: HashMap::new(),
+                })),
+            }
+        };
+        let rows = |value: proto::Value| {
+            vec![proto::Row {
+                values: vec![value, Value::Integer(7).into()],
+            }]
+        };
+
+        let a = row_batch_checksum(&rows(node(&["a", "b", "c", "d"])));
+        assert_eq!(a, row_batch_checksum(&rows(node(&["d", "c", "b", "a"]))));
+        assert_ne!(a, row_batch_checksum(&rows(node(&["a", "b", "c"]))));
+
+        let mut batch = proto::RowBatch {
+            rows: rows(node(&["a"])),
+            checksum: None,
+            dictionary: Vec::new(),
+            columns: None,
+        };
+        assert!(!is_corrupt(&batch));
+        batch.checksum = Some(row_batch_checksum(&batch.rows));
+        assert!(!is_corrupt(&batch));
+        batch.rows[0].values[1] = Value::Integer(8).into();
+        assert!(is_corrupt(&batch));
+    }
+}
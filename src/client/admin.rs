@@ -1,28 +1,56 @@
 //! Client-side wrapper for the `AdminService` gRPC service.
 
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Channel;
 
 use crate::error::GqlError;
 use crate::proto;
 use crate::proto::admin_service_client::AdminServiceClient;
 use crate::server::{
-    AdminStats, AdminValidationResult, AdminWalStatus, IndexDefinition, ValidationDiagnostic,
+    AdminStats, AdminValidationResult, AdminWalStatus, GraphConfig, GraphFormat, ImportReport,
+    IndexBuildStatus, IndexDefinition, IndexInfo, IndexState, Job, JobStatus, LabelCount,
+    LabelStats, MemoryBreakdown, PropertyCardinality, QueryStatistics, SessionInfo,
 };
 
+use super::connection::SessionTag;
+
+/// Bytes per chunk when uploading a backup or graph import.
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Upload chunks read ahead of the server.
+const CHUNK_BUFFER: usize = 4;
+
 /// A client for admin operations (stats, WAL, validation, indexes) on a GQL server.
 ///
 /// Wraps the raw `AdminServiceClient` gRPC stub with ergonomic
 /// methods that return domain types instead of proto messages.
 pub struct AdminClient {
-    client: AdminServiceClient<Channel>,
+    client: AdminServiceClient<InterceptedService<Channel, SessionTag>>,
 }
 
 impl AdminClient {
     /// Create a new admin client from an existing tonic channel.
     #[must_use]
     pub fn new(channel: Channel) -> Self {
+        Self::tagged(channel, SessionTag::default())
+    }
+
+    /// Create an admin client whose requests name the session they are made
+    /// for, so the server authorizes them as that session's principal.
+    #[must_use]
+    pub fn for_session(channel: Channel, session_id: &str) -> Self {
+        Self::tagged(channel, SessionTag::new(session_id))
+    }
+
+    fn tagged(channel: Channel, tag: SessionTag) -> Self {
         Self {
-            client: AdminServiceClient::new(channel),
+            client: AdminServiceClient::with_interceptor(channel, tag),
         }
     }
 
@@ -49,9 +77,151 @@ impl AdminClient {
             index_count: resp.index_count,
             memory_bytes: resp.memory_bytes,
             disk_bytes: resp.disk_bytes,
+            memory_breakdown: resp.memory_breakdown.map(MemoryBreakdown::from),
         })
     }
 
+    /// Get per-label node counts, per-edge-type counts, and property
+    /// cardinality estimates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph is not found or admin is not supported.
+    pub async fn get_label_stats(&mut self, graph: &str) -> Result<LabelStats, GqlError> {
+        let resp = self
+            .client
+            .get_label_stats(proto::GetLabelStatsRequest {
+                graph: graph.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        let from_proto_count = |c: proto::LabelCount| LabelCount {
+            label: c.label,
+            count: c.count,
+        };
+
+        Ok(LabelStats {
+            node_labels: resp.node_labels.into_iter().map(from_proto_count).collect(),
+            edge_types: resp.edge_types.into_iter().map(from_proto_count).collect(),
+            properties: resp
+                .properties
+                .into_iter()
+                .map(|p| PropertyCardinality {
+                    label: p.label,
+                    property: p.property,
+                    non_null_count: p.non_null_count,
+                    distinct_estimate: p.distinct_estimate,
+                })
+                .collect(),
+        })
+    }
+
+    /// Get the runtime-changeable configuration of a graph.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph is not found or admin is not supported.
+    pub async fn get_config(&mut self, graph: &str) -> Result<GraphConfig, GqlError> {
+        let resp = self
+            .client
+            .get_graph_config(proto::GetGraphConfigRequest {
+                graph: graph.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        Ok(resp.config.map(GraphConfig::from).unwrap_or_default())
+    }
+
+    /// Change runtime options on a graph. Only the fields set in `changes`
+    /// are applied. Returns the effective configuration after the change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph is not found, an option value is
+    /// rejected, or admin is not supported.
+    pub async fn set_config(
+        &mut self,
+        graph: &str,
+        changes: GraphConfig,
+    ) -> Result<GraphConfig, GqlError> {
+        let resp = self
+            .client
+            .set_graph_config(proto::SetGraphConfigRequest {
+                graph: graph.to_owned(),
+                config: Some(changes.into()),
+            })
+            .await?
+            .into_inner();
+
+        Ok(resp.config.map(GraphConfig::from).unwrap_or_default())
+    }
+
+    /// Get server-side statement statistics, ordered by total execution
+    /// time. A `limit` of 0 returns every tracked statement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if query statistics are not enabled on the server.
+    pub async fn get_query_stats(&mut self, limit: u32) -> Result<Vec<QueryStatistics>, GqlError> {
+        let resp = self
+            .client
+            .get_query_stats(proto::GetQueryStatsRequest { limit })
+            .await?
+            .into_inner();
+
+        Ok(resp
+            .statements
+            .into_iter()
+            .map(QueryStatistics::from)
+            .collect())
+    }
+
+    /// Discard all server-side statement statistics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if query statistics are not enabled on the server.
+    pub async fn reset_query_stats(&mut self) -> Result<(), GqlError> {
+        self.client
+            .reset_query_stats(proto::ResetQueryStatsRequest {})
+            .await?;
+        Ok(())
+    }
+
+    /// List the sessions the server holds, ordered by session ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if session administration is not enabled on the
+    /// server.
+    pub async fn list_sessions(&mut self) -> Result<Vec<SessionInfo>, GqlError> {
+        let resp = self
+            .client
+            .list_sessions(proto::ListSessionsRequest {})
+            .await?
+            .into_inner();
+
+        Ok(resp.sessions.into_iter().map(SessionInfo::from).collect())
+    }
+
+    /// Close a session, rolling back its open transactions. Results the
+    /// session is still streaming are not interrupted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist or session
+    /// administration is not enabled on the server.
+    pub async fn kill_session(&mut self, session_id: &str) -> Result<(), GqlError> {
+        self.client
+            .kill_session(proto::KillSessionRequest {
+                session_id: session_id.to_owned(),
+            })
+            .await?;
+        Ok(())
+    }
+
     /// Get WAL status for a graph.
     ///
     /// # Errors
@@ -104,27 +274,7 @@ impl AdminClient {
             .await?
             .into_inner();
 
-        Ok(AdminValidationResult {
-            valid: resp.valid,
-            errors: resp
-                .errors
-                .into_iter()
-                .map(|e| ValidationDiagnostic {
-                    code: e.code,
-                    message: e.message,
-                    context: e.context,
-                })
-                .collect(),
-            warnings: resp
-                .warnings
-                .into_iter()
-                .map(|w| ValidationDiagnostic {
-                    code: w.code,
-                    message: w.message,
-                    context: w.context,
-                })
-                .collect(),
-        })
+        Ok(resp.into())
     }
 
     /// Create an index on a graph.
@@ -137,44 +287,62 @@ impl AdminClient {
         graph: &str,
         index: IndexDefinition,
     ) -> Result<(), GqlError> {
-        let index_proto = match index {
-            IndexDefinition::Property { property } => {
-                proto::create_index_request::Index::PropertyIndex(proto::PropertyIndexDef {
-                    property,
-                })
-            }
-            IndexDefinition::Vector {
-                label,
-                property,
-                dimensions,
-                metric,
-                m,
-                ef_construction,
-            } => proto::create_index_request::Index::VectorIndex(proto::VectorIndexDef {
-                label,
-                property,
-                dimensions,
-                metric,
-                m,
-                ef_construction,
-            }),
-            IndexDefinition::Text { label, property } => {
-                proto::create_index_request::Index::TextIndex(proto::TextIndexDef {
-                    label,
-                    property,
-                })
-            }
-        };
-
         self.client
             .create_index(proto::CreateIndexRequest {
                 graph: graph.to_owned(),
-                index: Some(index_proto),
+                index: Some(index.into()),
+                background: false,
             })
             .await?;
         Ok(())
     }
 
+    /// Start building an index on a graph in the background, returning
+    /// the build's job ID for
+    /// [`watch_index_build`](Self::watch_index_build).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails. Errors during the build are
+    /// reported through its progress instead.
+    pub async fn create_index_in_background(
+        &mut self,
+        graph: &str,
+        index: IndexDefinition,
+    ) -> Result<String, GqlError> {
+        let resp = self
+            .client
+            .create_index(proto::CreateIndexRequest {
+                graph: graph.to_owned(),
+                index: Some(index.into()),
+                background: true,
+            })
+            .await?
+            .into_inner();
+
+        resp.job_id
+            .ok_or_else(|| GqlError::Protocol("server returned no index build job ID".to_owned()))
+    }
+
+    /// Follow the progress of a background index build.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no build has the job ID.
+    pub async fn watch_index_build(
+        &mut self,
+        job_id: &str,
+    ) -> Result<IndexBuildProgress, GqlError> {
+        let stream = self
+            .client
+            .index_build_progress(proto::IndexBuildProgressRequest {
+                job_id: job_id.to_owned(),
+            })
+            .await?
+            .into_inner();
+        Ok(IndexBuildProgress { stream })
+    }
+
     /// Drop an index from a graph. Returns whether the index existed.
     ///
     /// # Errors
@@ -216,4 +384,272 @@ impl AdminClient {
             .into_inner();
         Ok(resp.existed)
     }
+
+    /// List the indexes on a graph with their size and build state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph is not found or admin is not supported.
+    pub async fn list_indexes(&mut self, graph: &str) -> Result<Vec<IndexInfo>, GqlError> {
+        let resp = self
+            .client
+            .list_indexes(proto::ListIndexesRequest {
+                graph: graph.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        resp.indexes.into_iter().map(IndexInfo::try_from).collect()
+    }
+
+    /// Start a long-running admin operation as a background job, returning
+    /// its job ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails. Errors while the job runs
+    /// are reported through its [`JobStatus`] instead.
+    pub async fn submit_job(&mut self, job: Job) -> Result<String, GqlError> {
+        let resp = self
+            .client
+            .submit_job(proto::SubmitJobRequest {
+                job: Some(job.into()),
+            })
+            .await?
+            .into_inner();
+        Ok(resp.job_id)
+    }
+
+    /// Get the state and progress of a background job.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no job has the ID.
+    pub async fn job_status(&mut self, job_id: &str) -> Result<JobStatus, GqlError> {
+        let resp = self
+            .client
+            .get_job_status(proto::GetJobStatusRequest {
+                job_id: job_id.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        resp.status
+            .ok_or_else(|| GqlError::Protocol("server returned no job status".to_owned()))?
+            .try_into()
+    }
+
+    /// Cancel a running background job. Returns `false` if the job had
+    /// already finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no job has the ID.
+    pub async fn cancel_job(&mut self, job_id: &str) -> Result<bool, GqlError> {
+        let resp = self
+            .client
+            .cancel_job(proto::CancelJobRequest {
+                job_id: job_id.to_owned(),
+            })
+            .await?
+            .into_inner();
+        Ok(resp.cancelled)
+    }
+
+    /// List running and recently finished background jobs, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn list_jobs(&mut self) -> Result<Vec<JobStatus>, GqlError> {
+        let resp = self
+            .client
+            .list_jobs(proto::ListJobsRequest {})
+            .await?
+            .into_inner();
+
+        resp.jobs.into_iter().map(JobStatus::try_from).collect()
+    }
+
+    /// Back up the whole database to the file at `path`, replacing it.
+    /// Returns the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend does not support backups or the
+    /// backup or file fails. A partly written file is removed.
+    pub async fn backup_to_file(&mut self, path: impl AsRef<Path>) -> Result<u64, GqlError> {
+        let path = path.as_ref();
+        let mut stream = self
+            .client
+            .backup_database(proto::BackupDatabaseRequest {})
+            .await?
+            .into_inner();
+        let mut file = BufWriter::new(File::create(path)?);
+        let written = write_stream(&mut stream, |chunk| chunk.data, &mut file).await;
+        drop(file);
+        if written.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        written
+    }
+
+    /// Replace the whole database with the backup in the file at `path`.
+    /// Returns the number of bytes restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend does not support restores or the
+    /// restore or file fails. If the file cannot be read to the end, the
+    /// restore is abandoned before the server sees the whole backup.
+    pub async fn restore_from_file(&mut self, path: impl AsRef<Path>) -> Result<u64, GqlError> {
+        let file = File::open(path)?;
+        let (tx, rx) = mpsc::channel(CHUNK_BUFFER);
+        let mut call = std::pin::pin!(self.client.restore_database(ReceiverStream::new(rx)));
+        tokio::select! {
+            // Returning early drops the call before `tx`, so the server
+            // sees the stream reset rather than end.
+            sent = send_chunks(file, |data| proto::RestoreDatabaseRequest { data }, &tx) => sent?,
+            response = &mut call => return Ok(response?.into_inner().bytes_restored),
+        }
+        drop(tx);
+        Ok(call.await?.into_inner().bytes_restored)
+    }
+
+    /// Export a graph's nodes and edges to `out` in `format`. Returns the
+    /// number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph is not found, the backend does not
+    /// support exports, a property value has no literal form, or writing
+    /// fails.
+    pub async fn export_graph(
+        &mut self,
+        graph: &str,
+        format: GraphFormat,
+        out: &mut impl Write,
+    ) -> Result<u64, GqlError> {
+        let mut stream = self
+            .client
+            .export_graph(proto::ExportGraphRequest {
+                graph: graph.to_owned(),
+                format: proto::GraphFormat::from(format).into(),
+            })
+            .await?
+            .into_inner();
+        write_stream(&mut stream, |chunk| chunk.data, out).await
+    }
+
+    /// Import nodes and edges read from `input` in `format` into a graph,
+    /// as written by [`export_graph`](Self::export_graph). Elements that
+    /// could not be imported are listed in the report's errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph is not found, the backend does not
+    /// support imports or `format`, or reading `input` fails. If `input`
+    /// cannot be read to the end, the import is abandoned.
+    pub async fn import_graph(
+        &mut self,
+        graph: &str,
+        format: GraphFormat,
+        input: impl Read,
+    ) -> Result<ImportReport, GqlError> {
+        let (tx, rx) = mpsc::channel(CHUNK_BUFFER);
+        // The graph and format go in a message of their own, ahead of
+        // the data.
+        let _ = tx
+            .send(proto::ImportGraphRequest {
+                graph: graph.to_owned(),
+                format: proto::GraphFormat::from(format).into(),
+                data: Vec::new(),
+            })
+            .await;
+        let mut call = std::pin::pin!(self.client.import_graph(ReceiverStream::new(rx)));
+        let chunk = |data| proto::ImportGraphRequest {
+            data,
+            ..Default::default()
+        };
+        tokio::select! {
+            sent = send_chunks(input, chunk, &tx) => sent?,
+            response = &mut call => return Ok(response?.into_inner().into()),
+        }
+        drop(tx);
+        Ok(call.await?.into_inner().into())
+    }
+}
+
+/// Write the data of each message of `stream` to `out`, returning the
+/// number of bytes written.
+async fn write_stream<T>(
+    stream: &mut tonic::Streaming<T>,
+    data: fn(T) -> Vec<u8>,
+    out: &mut impl Write,
+) -> Result<u64, GqlError> {
+    let mut written = 0;
+    while let Some(message) = stream.message().await? {
+        let data = data(message);
+        out.write_all(&data)?;
+        written += data.len() as u64;
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+/// Send the contents of `input` as upload chunks, stopping early if the
+/// call ends.
+async fn send_chunks<T>(
+    mut input: impl Read,
+    chunk: fn(Vec<u8>) -> T,
+    chunks: &mpsc::Sender<T>,
+) -> Result<(), GqlError> {
+    let mut buf = vec![0; UPLOAD_CHUNK_SIZE];
+    loop {
+        let n = input.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        if chunks.send(chunk(buf[..n].to_vec())).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// The progress of a background index build, from
+/// [`AdminClient::watch_index_build`].
+pub struct IndexBuildProgress {
+    stream: tonic::Streaming<proto::IndexBuildProgressUpdate>,
+}
+
+impl IndexBuildProgress {
+    /// Wait for the next update, or `None` once the build has finished
+    /// and its final status was delivered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails.
+    pub async fn next(&mut self) -> Result<Option<IndexBuildStatus>, GqlError> {
+        Ok(self.stream.message().await?.map(IndexBuildStatus::from))
+    }
+
+    /// Wait for the build to finish, returning its final status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails or ends before the build does.
+    pub async fn finish(mut self) -> Result<IndexBuildStatus, GqlError> {
+        let mut last = None;
+        while let Some(status) = self.next().await? {
+            last = Some(status);
+        }
+        last.filter(|s| s.state != IndexState::Building)
+            .ok_or_else(|| GqlError::Protocol("index build progress ended early".to_owned()))
+    }
+}
+
+impl std::fmt::Debug for IndexBuildProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexBuildProgress").finish_non_exhaustive()
+    }
 }
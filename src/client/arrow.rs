@@ -0,0 +1,625 @@
+//! Conversion of results to Apache Arrow record batches.
+//!
+//! The schema follows the column types in the result header. GQL types
+//! map to their Arrow counterparts:
+//!
+//! | GQL | Arrow |
+//! |-----|-------|
+//! | `BOOLEAN` | `Boolean` |
+//! | `INT8` .. `INT64`, `UINT8` .. `UINT64` | `Int8` .. `Int64`, `UInt8` .. `UInt64` |
+//! | `INT128`, `INT256`, `UINT128`, `UINT256` | `Decimal256(76, 0)` |
+//! | `FLOAT16`, `FLOAT32`, `FLOAT64` | `Float32`, `Float32`, `Float64` |
+//! | `FLOAT128`, `FLOAT256` | `FixedSizeBinary`, the IEEE 754 encoding |
+//! | `DECIMAL(p, s)` | `Decimal128(p, s)`, or `Decimal256` beyond 38 digits |
+//! | `STRING`, `BYTES` | `Utf8`, `Binary` |
+//! | `DATE` | `Date32` |
+//! | `LOCAL TIME`, `ZONED TIME` | `Time64(Nanosecond)`, zoned times in UTC |
+//! | `LOCAL DATETIME` | `Timestamp(Nanosecond, None)` |
+//! | `ZONED DATETIME` | `Timestamp(Nanosecond, "+00:00")` |
+//! | `DURATION` | `Interval(MonthDayNano)` |
+//! | `LIST<T>` | `List<T>` |
+//! | `RECORD` with declared fields | `Struct` |
+//! | `NULL`, `NOTHING` | `Null` |
+//!
+//! Decimals without a declared precision or scale use Arrow's defaults.
+//! Columns of any other type, such as nodes, paths, and dynamic values,
+//! hold each value's text.
+
+// Conversions report errors as the `GqlError` the cursor returns them in.
+#![allow(clippy::result_large_err)]
+
+use std::sync::Arc;
+
+use arrow_array::types::IntervalMonthDayNano;
+use arrow_array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Decimal256Array,
+    FixedSizeBinaryArray, Float32Array, Float64Array, Int8Array, Int16Array, Int32Array,
+    Int64Array, IntervalMonthDayNanoArray, ListArray, NullArray, RecordBatch, RecordBatchOptions,
+    StringArray, StructArray, Time64NanosecondArray, TimestampNanosecondArray, UInt8Array,
+    UInt16Array, UInt32Array, UInt64Array,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer, i256};
+use arrow_schema::{
+    DECIMAL_DEFAULT_SCALE, DECIMAL128_MAX_PRECISION, DECIMAL256_MAX_PRECISION, DataType, Field,
+    IntervalUnit, Schema, SchemaRef, TimeUnit,
+};
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::proto::GqlType;
+use crate::types::{Date, LocalTime, Value};
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_DAY: i64 = 24 * 60 * NANOS_PER_MINUTE;
+
+/// The Arrow schema of a result with `header`.
+pub(crate) fn schema(header: Option<&proto::ResultHeader>) -> SchemaRef {
+    let fields: Vec<Field> = header
+        .map(|header| {
+            header
+                .columns
+                .iter()
+                .map(|column| field(&column.name, column.r#type.as_ref()))
+                .collect()
+        })
+        .unwrap_or_default();
+    Arc::new(Schema::new(fields))
+}
+
+/// Convert `rows` to a record batch of `schema`.
+pub(crate) fn record_batch(
+    schema: &SchemaRef,
+    rows: &[Vec<Value>],
+) -> Result<RecordBatch, GqlError> {
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let values: Vec<&Value> = rows
+                .iter()
+                .map(|row| row.get(i).unwrap_or(&Value::Null))
+                .collect();
+            array(field.data_type(), &values).map_err(|message| {
+                GqlError::Protocol(format!("column `{}`: {message}", field.name()))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(Arc::clone(schema), columns, &options)
+        .map_err(|e| GqlError::Protocol(e.to_string()))
+}
+
+// ============================================================================
+// Schema
+// ============================================================================
+
+fn field(name: &str, descriptor: Option<&proto::TypeDescriptor>) -> Field {
+    Field::new(name, data_type(descriptor), true)
+}
+
+fn data_type(descriptor: Option<&proto::TypeDescriptor>) -> DataType {
+    let Some(descriptor) = descriptor else {
+        return DataType::Utf8;
+    };
+    match descriptor.r#type() {
+        GqlType::TypeNull | GqlType::TypeEmpty => DataType::Null,
+        GqlType::TypeBoolean => DataType::Boolean,
+        GqlType::TypeInt8 => DataType::Int8,
+        GqlType::TypeInt16 => DataType::Int16,
+        GqlType::TypeInt32 => DataType::Int32,
+        GqlType::TypeInt64 => DataType::Int64,
+        GqlType::TypeUint8 => DataType::UInt8,
+        GqlType::TypeUint16 => DataType::UInt16,
+        GqlType::TypeUint32 => DataType::UInt32,
+        GqlType::TypeUint64 => DataType::UInt64,
+        GqlType::TypeInt128 | GqlType::TypeInt256 | GqlType::TypeUint128 | GqlType::TypeUint256 => {
+            DataType::Decimal256(DECIMAL256_MAX_PRECISION, 0)
+        }
+        GqlType::TypeFloat16 | GqlType::TypeFloat32 => DataType::Float32,
+        GqlType::TypeFloat64 => DataType::Float64,
+        GqlType::TypeFloat128 => DataType::FixedSizeBinary(16),
+        GqlType::TypeFloat256 => DataType::FixedSizeBinary(32),
+        GqlType::TypeDecimal => decimal(descriptor.precision, descriptor.scale),
+        GqlType::TypeBytes => DataType::Binary,
+        GqlType::TypeDate => DataType::Date32,
+        GqlType::TypeLocalTime | GqlType::TypeZonedTime => DataType::Time64(TimeUnit::Nanosecond),
+        GqlType::TypeLocalDatetime => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        GqlType::TypeZonedDatetime => {
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("+00:00".into()))
+        }
+        GqlType::TypeDuration | GqlType::TypeYearMonthDuration | GqlType::TypeDayTimeDuration => {
+            DataType::Interval(IntervalUnit::MonthDayNano)
+        }
+        GqlType::TypeList => DataType::List(Arc::new(Field::new_list_field(
+            data_type(descriptor.element_type.as_deref()),
+            true,
+        ))),
+        GqlType::TypeRecord if !descriptor.fields.is_empty() => DataType::Struct(
+            descriptor
+                .fields
+                .iter()
+                .map(|f| field(&f.name, f.r#type.as_ref()))
+                .collect(),
+        ),
+        _ => DataType::Utf8,
+    }
+}
+
+fn decimal(precision: Option<u32>, scale: Option<u32>) -> DataType {
+    let precision = precision
+        .and_then(|p| u8::try_from(p).ok())
+        .unwrap_or(DECIMAL128_MAX_PRECISION)
+        .clamp(1, DECIMAL256_MAX_PRECISION);
+    let scale = scale
+        .and_then(|s| i8::try_from(s).ok())
+        .unwrap_or(DECIMAL_DEFAULT_SCALE)
+        .min(i8::try_from(precision).unwrap_or(i8::MAX));
+    if precision <= DECIMAL128_MAX_PRECISION {
+        DataType::Decimal128(precision, scale)
+    } else {
+        DataType::Decimal256(precision, scale)
+    }
+}
+
+// ============================================================================
+// Arrays
+// ============================================================================
+
+/// Build an array of `data_type` from `values`, failing with a message if
+/// a value does not convert.
+#[allow(clippy::too_many_lines)]
+fn array(data_type: &DataType, values: &[&Value]) -> Result<ArrayRef, String> {
+    Ok(match data_type {
+        DataType::Null => Arc::new(NullArray::new(values.len())),
+        DataType::Boolean => Arc::new(BooleanArray::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::Boolean(b) => Some(*b),
+                _ => None,
+            },
+        )?)),
+        DataType::Int8 => Arc::new(Int8Array::from(convert(values, data_type, integer)?)),
+        DataType::Int16 => Arc::new(Int16Array::from(convert(values, data_type, integer)?)),
+        DataType::Int32 => Arc::new(Int32Array::from(convert(values, data_type, integer)?)),
+        DataType::Int64 => Arc::new(Int64Array::from(convert(values, data_type, integer)?)),
+        DataType::UInt8 => Arc::new(UInt8Array::from(convert(values, data_type, integer)?)),
+        DataType::UInt16 => Arc::new(UInt16Array::from(convert(values, data_type, integer)?)),
+        DataType::UInt32 => Arc::new(UInt32Array::from(convert(values, data_type, integer)?)),
+        DataType::UInt64 => Arc::new(UInt64Array::from(convert(values, data_type, integer)?)),
+        DataType::Float32 => Arc::new(Float32Array::from(convert(values, data_type, |v| {
+            match v {
+                // Columns declared FLOAT32 or narrower hold values that fit.
+                #[allow(clippy::cast_possible_truncation)]
+                Value::Float(f) => Some(*f as f32),
+                _ => None,
+            }
+        })?)),
+        DataType::Float64 => Arc::new(Float64Array::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::Float(f) => Some(*f),
+                _ => None,
+            },
+        )?)),
+        DataType::Decimal128(precision, scale) => {
+            let decimals = convert(values, data_type, |v| {
+                decimal_value(v, *scale).and_then(i256::to_i128)
+            })?;
+            let array = Decimal128Array::from(decimals)
+                .with_precision_and_scale(*precision, *scale)
+                .map_err(|e| e.to_string())?;
+            array
+                .validate_decimal_precision(*precision)
+                .map_err(|e| e.to_string())?;
+            Arc::new(array)
+        }
+        DataType::Decimal256(precision, scale) => {
+            let decimals = convert(values, data_type, |v| decimal_value(v, *scale))?;
+            let array = Decimal256Array::from(decimals)
+                .with_precision_and_scale(*precision, *scale)
+                .map_err(|e| e.to_string())?;
+            array
+                .validate_decimal_precision(*precision)
+                .map_err(|e| e.to_string())?;
+            Arc::new(array)
+        }
+        DataType::FixedSizeBinary(size) => {
+            let floats = convert(values, data_type, |v| match v {
+                Value::BigFloat { value, .. }
+                    if usize::try_from(*size).is_ok_and(|size| size == value.len()) =>
+                {
+                    Some(value.as_slice())
+                }
+                _ => None,
+            })?;
+            Arc::new(
+                FixedSizeBinaryArray::try_from_sparse_iter_with_size(floats.into_iter(), *size)
+                    .map_err(|e| e.to_string())?,
+            )
+        }
+        DataType::Binary => Arc::new(BinaryArray::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::Bytes(b) => Some(b.as_slice()),
+                _ => None,
+            },
+        )?)),
+        DataType::Date32 => Arc::new(Date32Array::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::Date(d) => i32::try_from(days_since_epoch(d)).ok(),
+                _ => None,
+            },
+        )?)),
+        DataType::Time64(_) => Arc::new(Time64NanosecondArray::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::LocalTime(t) => Some(nanos_of_day(t)),
+                Value::ZonedTime(t) => Some(
+                    (nanos_of_day(&t.time) - i64::from(t.offset_minutes) * NANOS_PER_MINUTE)
+                        .rem_euclid(NANOS_PER_DAY),
+                ),
+                _ => None,
+            },
+        )?)),
+        DataType::Timestamp(_, zone) => {
+            let timestamps = convert(values, data_type, |v| match (v, zone) {
+                (Value::LocalDateTime(dt), None) => timestamp(&dt.date, &dt.time, 0),
+                (Value::ZonedDateTime(dt), Some(_)) => {
+                    timestamp(&dt.date, &dt.time, dt.offset_minutes)
+                }
+                _ => None,
+            })?;
+            Arc::new(TimestampNanosecondArray::from(timestamps).with_timezone_opt(zone.clone()))
+        }
+        DataType::Interval(_) => Arc::new(IntervalMonthDayNanoArray::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::Duration(d) => Some(IntervalMonthDayNano::new(
+                    i32::try_from(d.months).ok()?,
+                    0,
+                    d.nanoseconds,
+                )),
+                _ => None,
+            },
+        )?)),
+        DataType::List(element) => {
+            let lists = convert(values, data_type, |v| match v {
+                Value::List(elements) => Some(elements),
+                _ => None,
+            })?;
+            let elements: Vec<&Value> = lists.iter().flatten().flat_map(|l| l.iter()).collect();
+            let offsets = OffsetBuffer::from_lengths(lists.iter().map(|l| l.map_or(0, Vec::len)));
+            Arc::new(
+                ListArray::try_new(
+                    Arc::clone(element),
+                    offsets,
+                    array(element.data_type(), &elements)?,
+                    nulls(&lists),
+                )
+                .map_err(|e| e.to_string())?,
+            )
+        }
+        DataType::Struct(fields) => {
+            let records = convert(values, data_type, |v| match v {
+                Value::Record(record) => Some(record),
+                _ => None,
+            })?;
+            let columns = fields
+                .iter()
+                .map(|field| {
+                    let values: Vec<&Value> = records
+                        .iter()
+                        .map(|r| r.and_then(|r| r.get(field.name())).unwrap_or(&Value::Null))
+                        .collect();
+                    array(field.data_type(), &values)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Arc::new(
+                StructArray::try_new(fields.clone(), columns, nulls(&records))
+                    .map_err(|e| e.to_string())?,
+            )
+        }
+        _ => Arc::new(StringArray::from(convert(
+            values,
+            data_type,
+            |v| match v {
+                Value::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            },
+        )?)),
+    })
+}
+
+/// Convert each non-null value with `f`, failing on the first it rejects.
+fn convert<'a, T>(
+    values: &[&'a Value],
+    data_type: &DataType,
+    f: impl Fn(&'a Value) -> Option<T>,
+) -> Result<Vec<Option<T>>, String> {
+    values
+        .iter()
+        .map(|value| match value {
+            Value::Null => Ok(None),
+            value => f(value)
+                .map(Some)
+                .ok_or_else(|| format!("cannot convert {value} to {data_type}")),
+        })
+        .collect()
+}
+
+/// The validity of `values`, if any is null.
+fn nulls<T>(values: &[Option<T>]) -> Option<NullBuffer> {
+    values
+        .iter()
+        .any(Option::is_none)
+        .then(|| NullBuffer::from(values.iter().map(Option::is_some).collect::<Vec<_>>()))
+}
+
+fn integer<T: TryFrom<i64> + TryFrom<u64>>(value: &Value) -> Option<T> {
+    match value {
+        Value::Integer(i) => T::try_from(*i).ok(),
+        Value::UnsignedInteger(u) => T::try_from(*u).ok(),
+        _ => None,
+    }
+}
+
+/// The unscaled value of a numeric `value` at `scale`, if it is exact.
+fn decimal_value(value: &Value, scale: i8) -> Option<i256> {
+    let (unscaled, from) = match value {
+        Value::Decimal { unscaled, scale } => (from_be_bytes(unscaled, true)?, *scale),
+        Value::BigInteger { value, is_signed } => (from_be_bytes(value, *is_signed)?, 0),
+        Value::Integer(i) => (i256::from_i128(i128::from(*i)), 0),
+        Value::UnsignedInteger(u) => (i256::from_i128(i128::from(*u)), 0),
+        _ => return None,
+    };
+    let to = i32::from(scale);
+    let factor = |digits: i32| i256::from_i128(10).checked_pow(digits.unsigned_abs());
+    if to >= from {
+        unscaled.checked_mul(factor(to - from)?)
+    } else {
+        let factor = factor(from - to)?;
+        (unscaled.checked_rem(factor)? == i256::ZERO).then(|| unscaled.checked_div(factor))?
+    }
+}
+
+/// A big-endian integer of at most 32 bytes, two's complement if `signed`.
+fn from_be_bytes(bytes: &[u8], signed: bool) -> Option<i256> {
+    if bytes.len() > 32 {
+        return None;
+    }
+    let negative = signed && bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut buffer = [if negative { 0xff } else { 0 }; 32];
+    buffer[32 - bytes.len()..].copy_from_slice(bytes);
+    let value = i256::from_be_bytes(buffer);
+    // An unsigned 256-bit value with the top bit set does not fit.
+    (signed || value >= i256::ZERO).then_some(value)
+}
+
+/// Days from 1970-01-01 to `date` in the proleptic Gregorian calendar.
+fn days_since_epoch(date: &Date) -> i64 {
+    let year = i64::from(date.year) - i64::from(date.month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(date.month);
+    let day_of_year =
+        (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(date.day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn nanos_of_day(time: &LocalTime) -> i64 {
+    (i64::from(time.hour) * 60 + i64::from(time.minute)) * NANOS_PER_MINUTE
+        + i64::from(time.second) * NANOS_PER_SECOND
+        + i64::from(time.nanosecond)
+}
+
+/// Nanoseconds since the epoch of `date` and `time` at `offset_minutes`
+/// from UTC.
+fn timestamp(date: &Date, time: &LocalTime, offset_minutes: i32) -> Option<i64> {
+    days_since_epoch(date)
+        .checked_mul(NANOS_PER_DAY)?
+        .checked_add(nanos_of_day(time))?
+        .checked_sub(i64::from(offset_minutes) * NANOS_PER_MINUTE)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+
+    use super::*;
+    use crate::types::{Duration, Record, ZonedDateTime};
+
+    fn column(name: &str, r#type: GqlType) -> proto::ColumnDescriptor {
+        proto::ColumnDescriptor {
+            name: name.to_owned(),
+            r#type: Some(proto::TypeDescriptor {
+                r#type: r#type.into(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn header(columns: Vec<proto::ColumnDescriptor>) -> proto::ResultHeader {
+        proto::ResultHeader {
+            columns,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_scalars_and_temporals() {
+        let schema = schema(Some(&header(vec![
+            column("id", GqlType::TypeInt32),
+            column("name", GqlType::TypeString),
+            column("born", GqlType::TypeDate),
+            column("seen", GqlType::TypeZonedDatetime),
+            column("ttl", GqlType::TypeDuration),
+            column("node", GqlType::TypeNode),
+        ])));
+        let date = Date {
+            year: 2024,
+            month: 3,
+            day: 1,
+        };
+        let time = LocalTime {
+            hour: 2,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+        };
+        let rows = vec![
+            vec![
+                Value::Integer(1),
+                Value::from("Alice"),
+                Value::Date(date),
+                Value::ZonedDateTime(ZonedDateTime {
+                    date,
+                    time,
+                    offset_minutes: 120,
+                }),
+                Value::Duration(Duration {
+                    months: 1,
+                    nanoseconds: 5,
+                }),
+                Value::Boolean(true),
+            ],
+            vec![Value::Null; 6],
+        ];
+        let batch = record_batch(&schema, &rows).unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+        let ids = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(ids.value(0), 1);
+        assert!(ids.is_null(1));
+        let born = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(born.value(0), 19_783);
+        let seen = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(seen.value(0), 19_783 * NANOS_PER_DAY);
+        let ttl = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<IntervalMonthDayNanoArray>()
+            .unwrap();
+        assert_eq!(ttl.value(0), IntervalMonthDayNano::new(1, 0, 5));
+        let text = batch
+            .column(5)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(text.value(0), "true");
+    }
+
+    #[test]
+    fn converts_decimals_lists_and_records() {
+        let mut price = column("price", GqlType::TypeDecimal);
+        let descriptor = price.r#type.as_mut().unwrap();
+        descriptor.precision = Some(10);
+        descriptor.scale = Some(2);
+        let mut tags = column("tags", GqlType::TypeList);
+        tags.r#type.as_mut().unwrap().element_type = Some(Box::new(proto::TypeDescriptor {
+            r#type: GqlType::TypeInt64.into(),
+            ..Default::default()
+        }));
+        let mut point = column("point", GqlType::TypeRecord);
+        point.r#type.as_mut().unwrap().fields = vec![proto::FieldDescriptor {
+            name: "x".to_owned(),
+            r#type: Some(proto::TypeDescriptor {
+                r#type: GqlType::TypeFloat64.into(),
+                ..Default::default()
+            }),
+        }];
+        let schema = schema(Some(&header(vec![price, tags, point])));
+        assert_eq!(schema.field(0).data_type(), &DataType::Decimal128(10, 2));
+
+        let rows = vec![
+            vec![
+                // 12.5
+                Value::Decimal {
+                    unscaled: vec![125],
+                    scale: 1,
+                },
+                Value::List(vec![Value::Integer(1), Value::Integer(2)]),
+                Value::Record(Record::new().with_field("x", 1.5)),
+            ],
+            vec![Value::Integer(-3), Value::Null, Value::Null],
+        ];
+        let batch = record_batch(&schema, &rows).unwrap();
+
+        let prices = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(prices.value(0), 1250);
+        assert_eq!(prices.value(1), -300);
+        let tags = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        assert_eq!(tags.value_length(0), 2);
+        assert!(tags.is_null(1));
+        let points = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .unwrap();
+        assert!(points.is_null(1));
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        let narrow = schema(Some(&header(vec![column("n", GqlType::TypeInt8)])));
+        let err = record_batch(&narrow, &[vec![Value::Integer(1_000)]]).unwrap_err();
+        assert!(err.to_string().contains("column `n`"), "{err}");
+
+        let local = schema(Some(&header(vec![column(
+            "at",
+            GqlType::TypeLocalDatetime,
+        )])));
+        let zoned = Value::ZonedDateTime(ZonedDateTime {
+            date: Date {
+                year: 1970,
+                month: 1,
+                day: 1,
+            },
+            time: LocalTime {
+                hour: 0,
+                minute: 0,
+                second: 0,
+                nanosecond: 0,
+            },
+            offset_minutes: 0,
+        });
+        assert!(record_batch(&local, &[vec![zoned]]).is_err());
+    }
+
+    #[test]
+    fn counts_days_from_the_epoch() {
+        let day = |year, month, day| days_since_epoch(&Date { year, month, day });
+        assert_eq!(day(1970, 1, 1), 0);
+        assert_eq!(day(2000, 3, 1), 11_017);
+        assert_eq!(day(1969, 12, 31), -1);
+    }
+}
@@ -0,0 +1,211 @@
+//! Results of statements executed as one batch.
+
+use crate::checksum;
+use crate::columnar;
+use crate::dictionary;
+use crate::error::GqlError;
+use crate::proto;
+use crate::status;
+use crate::types::Value;
+
+use super::encryption::{self, FieldEncryption};
+use super::warning::ServerWarning;
+
+/// The result of one statement of a batch.
+#[derive(Debug, Clone, Default)]
+pub struct BatchResult {
+    /// Position of the statement in the batch, from 0.
+    pub index: usize,
+    /// Column metadata, if the statement returned a header.
+    pub header: Option<proto::ResultHeader>,
+    /// Rows the statement returned.
+    pub rows: Vec<Vec<Value>>,
+    /// Completion status and counts, if the statement finished.
+    pub summary: Option<proto::ResultSummary>,
+    /// Warnings the statement raised, mid-stream and in its summary.
+    pub warnings: Vec<ServerWarning>,
+}
+
+impl BatchResult {
+    /// Whether the statement completed successfully.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.summary
+            .as_ref()
+            .and_then(|s| s.status.as_ref())
+            .is_some_and(|s| status::is_success(&s.code))
+    }
+
+    /// Number of rows the statement affected.
+    #[must_use]
+    pub fn rows_affected(&self) -> i64 {
+        self.summary.as_ref().map_or(0, |s| s.rows_affected)
+    }
+}
+
+/// A cursor over the results of a batch, from
+/// [`GqlSession::execute_batch`](super::GqlSession::execute_batch).
+///
+/// Each statement's result is read whole, so batches suit statements
+/// with small results, such as writes. The server stops at the first
+/// statement that fails; [`status`](Self::status) reports how the batch
+/// ended.
+pub struct BatchCursor {
+    stream: tonic::Streaming<proto::ExecuteBatchResponse>,
+    require_checksums: bool,
+    encryption: Option<FieldEncryption>,
+    status: Option<(usize, proto::GqlStatus)>,
+    done: bool,
+}
+
+impl BatchCursor {
+    pub(crate) fn new(stream: tonic::Streaming<proto::ExecuteBatchResponse>) -> Self {
+        Self {
+            stream,
+            require_checksums: false,
+            encryption: None,
+            status: None,
+            done: false,
+        }
+    }
+
+    /// Reject row batches without a checksum. Batches that carry one are
+    /// always verified.
+    pub(crate) fn with_required_checksums(mut self, required: bool) -> Self {
+        self.require_checksums = required;
+        self
+    }
+
+    /// Decrypt encrypted values in rows.
+    pub(crate) fn with_encryption(mut self, encryption: Option<FieldEncryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Read the result of the next statement, or `None` once the batch
+    /// has ended.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails or a row batch is corrupt.
+    pub async fn next_result(&mut self) -> Result<Option<BatchResult>, GqlError> {
+        let mut result: Option<BatchResult> = None;
+        while !self.done {
+            let Some(message) = self.stream.message().await? else {
+                self.done = true;
+                break;
+            };
+            let index = usize::try_from(message.statement_index).unwrap_or(usize::MAX);
+            match message.frame {
+                Some(proto::execute_batch_response::Frame::Result(response)) => {
+                    let current = result.get_or_insert_with(|| BatchResult {
+                        index,
+                        ..BatchResult::default()
+                    });
+                    if self.apply(current, response)? {
+                        return Ok(result);
+                    }
+                }
+                Some(proto::execute_batch_response::Frame::BatchStatus(status)) => {
+                    self.status = Some((index, status));
+                    self.done = true;
+                }
+                None => {}
+            }
+        }
+        Ok(result)
+    }
+
+    /// Read the remaining results and return the status the batch ended
+    /// with: success, or the status of the statement or commit that
+    /// failed. `None` if the stream ended without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails.
+    pub async fn status(&mut self) -> Result<Option<&proto::GqlStatus>, GqlError> {
+        while self.next_result().await?.is_some() {}
+        Ok(self.status.as_ref().map(|(_, status)| status))
+    }
+
+    /// Read the remaining results and check the batch ran to the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails.
+    pub async fn is_success(&mut self) -> Result<bool, GqlError> {
+        let status = self.status().await?;
+        Ok(status.is_some_and(|s| status::is_success(&s.code)))
+    }
+
+    /// The index of the statement the batch failed at, once
+    /// [`status`](Self::status) is known. Equal to the number of
+    /// statements if committing an atomic batch failed.
+    #[must_use]
+    pub fn failed_statement(&self) -> Option<usize> {
+        self.status
+            .as_ref()
+            .filter(|(_, status)| status::is_exception(&status.code))
+            .map(|(index, _)| *index)
+    }
+
+    /// Add a frame to `result`, returning whether it was the last.
+    #[allow(clippy::result_large_err)]
+    fn apply(
+        &self,
+        result: &mut BatchResult,
+        response: proto::ExecuteResponse,
+    ) -> Result<bool, GqlError> {
+        match response.frame {
+            Some(proto::execute_response::Frame::Header(header)) => {
+                result.header = Some(header);
+            }
+            Some(proto::execute_response::Frame::RowBatch(mut batch)) => {
+                // Checksums cover the decoded rows.
+                if !columnar::decode(&mut batch) {
+                    return Err(GqlError::Protocol(
+                        "columnar row batch malformed".to_owned(),
+                    ));
+                }
+                if !dictionary::decode(&mut batch) {
+                    return Err(GqlError::Protocol(
+                        "row batch dictionary reference out of range".to_owned(),
+                    ));
+                }
+                if checksum::is_corrupt(&batch) {
+                    return Err(GqlError::Protocol("row batch checksum mismatch".to_owned()));
+                }
+                if self.require_checksums && batch.checksum.is_none() {
+                    return Err(GqlError::Protocol("row batch checksum missing".to_owned()));
+                }
+                for row in batch.rows {
+                    let row: Vec<Value> = row.values.into_iter().map(Value::from).collect();
+                    result.rows.push(match &self.encryption {
+                        Some(encryption) => {
+                            encryption.decrypt_row(row).map_err(encryption::failed)?
+                        }
+                        None => row,
+                    });
+                }
+            }
+            Some(proto::execute_response::Frame::Summary(summary)) => {
+                result
+                    .warnings
+                    .extend(summary.warnings.iter().cloned().map(ServerWarning::from));
+                result.summary = Some(summary);
+                return Ok(true);
+            }
+            Some(proto::execute_response::Frame::Warning(warning)) => {
+                result.warnings.push(warning.into());
+            }
+            None => {}
+        }
+        Ok(false)
+    }
+}
+
+impl std::fmt::Debug for BatchCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchCursor").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,147 @@
+//! Streaming nodes and edges to the server in bulk.
+
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::proto::gql_service_client::GqlServiceClient;
+use crate::server::BulkLoadStats;
+use crate::status;
+use crate::types::{Edge, Node};
+
+use super::encryption::{self, FieldEncryption};
+use super::trace::TracedChannel;
+
+/// Chunks queued ahead of what the server has received.
+const CHUNK_BUFFER: usize = 4;
+
+/// A bulk load in progress, from
+/// [`GqlSession::bulk_load`](super::GqlSession::bulk_load).
+///
+/// Send nodes and edges in chunks of a few thousand with
+/// [`send`](Self::send), then [`finish`](Self::finish) to wait for the
+/// server to load them all. Dropping the loader without finishing
+/// leaves what was loaded so far in place.
+pub struct BulkLoader {
+    session_id: String,
+    chunks: mpsc::Sender<proto::BulkLoadRequest>,
+    progress: watch::Receiver<BulkLoadStats>,
+    outcome: JoinHandle<Result<(BulkLoadStats, Option<proto::GqlStatus>), GqlError>>,
+    encryption: Option<FieldEncryption>,
+}
+
+impl BulkLoader {
+    pub(crate) async fn start(
+        session_id: String,
+        mut client: GqlServiceClient<TracedChannel>,
+        encryption: Option<FieldEncryption>,
+    ) -> Result<Self, GqlError> {
+        let (chunks, rx) = mpsc::channel(CHUNK_BUFFER);
+        // The server reads the session from the first message before it
+        // responds, so send it up front.
+        let first = proto::BulkLoadRequest {
+            session_id: session_id.clone(),
+            ..proto::BulkLoadRequest::default()
+        };
+        chunks
+            .send(first)
+            .await
+            .map_err(|_| GqlError::Protocol("bulk load closed".to_owned()))?;
+        let mut responses = client
+            .bulk_load(ReceiverStream::new(rx))
+            .await?
+            .into_inner();
+
+        let (report, progress) = watch::channel(BulkLoadStats::default());
+        let outcome = tokio::spawn(async move {
+            while let Some(message) = responses.message().await? {
+                let status = message.status.clone();
+                let loaded = BulkLoadStats::from(message);
+                report.send_replace(loaded);
+                if status.is_some() {
+                    return Ok((loaded, status));
+                }
+            }
+            Ok((*report.borrow(), None))
+        });
+        Ok(Self {
+            session_id,
+            chunks,
+            progress,
+            outcome,
+            encryption,
+        })
+    }
+
+    /// Send a chunk of nodes and edges, waiting while too many chunks
+    /// are already on their way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has ended the load, such as after
+    /// a failure; [`finish`](Self::finish) reports why.
+    pub async fn send(
+        &mut self,
+        mut nodes: Vec<Node>,
+        mut edges: Vec<Edge>,
+    ) -> Result<(), GqlError> {
+        if let Some(encryption) = &self.encryption {
+            let properties = nodes
+                .iter_mut()
+                .map(|node| &mut node.properties)
+                .chain(edges.iter_mut().map(|edge| &mut edge.properties));
+            for properties in properties {
+                *properties = encryption
+                    .encrypt_properties(std::mem::take(properties))
+                    .map_err(encryption::failed)?;
+            }
+        }
+        let chunk = proto::BulkLoadRequest {
+            session_id: self.session_id.clone(),
+            nodes: nodes.into_iter().map(proto::Node::from).collect(),
+            edges: edges.into_iter().map(proto::Edge::from).collect(),
+        };
+        self.chunks
+            .send(chunk)
+            .await
+            .map_err(|_| GqlError::Protocol("bulk load ended by the server".to_owned()))
+    }
+
+    /// The progress the server last reported.
+    #[must_use]
+    pub fn progress(&self) -> BulkLoadStats {
+        *self.progress.borrow()
+    }
+
+    /// Stop sending and wait for the server to load what it has
+    /// received, returning the totals.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the load failed or the stream broke.
+    pub async fn finish(self) -> Result<BulkLoadStats, GqlError> {
+        drop(self.chunks);
+        let (totals, status) = self
+            .outcome
+            .await
+            .map_err(|err| GqlError::Protocol(format!("bulk load task failed: {err}")))??;
+        match status {
+            Some(s) if status::is_exception(&s.code) => Err(GqlError::Status { status: s }),
+            Some(_) => Ok(totals),
+            None => Err(GqlError::Protocol(
+                "bulk load ended without a status".to_owned(),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Debug for BulkLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkLoader")
+            .field("session_id", &self.session_id)
+            .field("progress", &self.progress())
+            .finish_non_exhaustive()
+    }
+}
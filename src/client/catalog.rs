@@ -1,26 +1,43 @@
 //! Client-side wrapper for the `CatalogService` gRPC service.
 
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Channel;
 
 use crate::error::GqlError;
 use crate::proto;
 use crate::proto::catalog_service_client::CatalogServiceClient;
-use crate::server::{CreateGraphConfig, GraphInfo, GraphTypeInfo, GraphTypeSpec, SchemaInfo};
+use crate::server::{
+    CloneGraphConfig, CreateDatabaseConfig, CreateGraphConfig, DatabaseInfo, GraphInfo,
+    GraphTypeInfo, GraphTypeSpec, SchemaInfo, SessionDefaults,
+};
+
+use super::connection::SessionTag;
 
 /// A client for managing the catalog (schemas, graphs, graph types) on a GQL server.
 ///
 /// Wraps the raw `CatalogServiceClient` gRPC stub with ergonomic
 /// methods that return domain types instead of proto messages.
 pub struct CatalogClient {
-    client: CatalogServiceClient<Channel>,
+    client: CatalogServiceClient<InterceptedService<Channel, SessionTag>>,
 }
 
 impl CatalogClient {
     /// Create a new catalog client from an existing tonic channel.
     #[must_use]
     pub fn new(channel: Channel) -> Self {
+        Self::tagged(channel, SessionTag::default())
+    }
+
+    /// Create a catalog client whose requests name the session they are made
+    /// for, so the server authorizes them as that session's principal.
+    #[must_use]
+    pub fn for_session(channel: Channel, session_id: &str) -> Self {
+        Self::tagged(channel, SessionTag::new(session_id))
+    }
+
+    fn tagged(channel: Channel, tag: SessionTag) -> Self {
         Self {
-            client: CatalogServiceClient::new(channel),
+            client: CatalogServiceClient::with_interceptor(channel, tag),
         }
     }
 
@@ -86,6 +103,74 @@ impl CatalogClient {
         Ok(resp.existed)
     }
 
+    // =========================================================================
+    // Database operations
+    // =========================================================================
+
+    /// Create a database, whose sessions start on `config.defaults`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database already exists (unless
+    /// `if_not_exists`), a default schema or graph does not exist, or the
+    /// request fails.
+    pub async fn create_database(
+        &mut self,
+        config: CreateDatabaseConfig,
+    ) -> Result<DatabaseInfo, GqlError> {
+        let resp = self
+            .client
+            .create_database(proto::CreateDatabaseRequest {
+                name: config.name,
+                if_not_exists: config.if_not_exists,
+                defaults: Some(config.defaults.into()),
+            })
+            .await?
+            .into_inner();
+        Ok(resp.database.map(DatabaseInfo::from).unwrap_or_default())
+    }
+
+    /// Replace the default schema and graph that sessions on a database
+    /// start with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database, schema, or graph does not exist
+    /// or the request fails.
+    pub async fn alter_database(
+        &mut self,
+        name: &str,
+        defaults: SessionDefaults,
+    ) -> Result<DatabaseInfo, GqlError> {
+        let resp = self
+            .client
+            .alter_database(proto::AlterDatabaseRequest {
+                name: name.to_owned(),
+                defaults: Some(defaults.into()),
+            })
+            .await?
+            .into_inner();
+        Ok(resp.database.map(DatabaseInfo::from).unwrap_or_default())
+    }
+
+    /// Get information about a database, including its default schema
+    /// and graph.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database does not exist or the request
+    /// fails.
+    pub async fn get_database_info(&mut self, name: &str) -> Result<DatabaseInfo, GqlError> {
+        let resp = self
+            .client
+            .get_database_info(proto::GetDatabaseInfoRequest {
+                name: name.to_owned(),
+            })
+            .await?
+            .into_inner();
+        Ok(resp.database.map(DatabaseInfo::from).unwrap_or_default())
+    }
+
     // =========================================================================
     // Graph operations
     // =========================================================================
@@ -232,6 +317,44 @@ impl CatalogClient {
         })
     }
 
+    /// Clone a graph under a new name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source graph is not found, the target
+    /// already exists (and `if_not_exists` is false), or the request fails.
+    pub async fn clone_graph(&mut self, config: CloneGraphConfig) -> Result<GraphInfo, GqlError> {
+        let resp = self
+            .client
+            .clone_graph(proto::CloneGraphRequest {
+                source_schema: config.source_schema,
+                source_name: config.source_name,
+                target_schema: config.target_schema,
+                target_name: config.target_name,
+                options: Some(proto::CloneGraphOptions {
+                    if_not_exists: config.if_not_exists,
+                    include_indexes: config.include_indexes,
+                    storage_mode: config.storage_mode,
+                }),
+            })
+            .await?
+            .into_inner();
+
+        resp.graph
+            .map(|g| GraphInfo {
+                schema: g.schema,
+                name: g.name,
+                node_count: g.node_count,
+                edge_count: g.edge_count,
+                graph_type: g.graph_type,
+                storage_mode: String::new(),
+                memory_limit_bytes: None,
+                backward_edges: None,
+                threads: None,
+            })
+            .ok_or_else(|| GqlError::Protocol("server returned empty response".into()))
+    }
+
     // =========================================================================
     // Graph type operations
     // =========================================================================
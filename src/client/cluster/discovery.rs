@@ -0,0 +1,115 @@
+//! DNS-based endpoint discovery.
+//!
+//! Endpoints written as `gwp+dns://host:port` stand for every address the
+//! host name resolves to, which is how headless services expose their
+//! pods. They are resolved when the connection is created and again on
+//! every refresh.
+
+use std::net::SocketAddr;
+use std::sync::{RwLock, Weak};
+use std::time::Duration;
+
+use super::{Members, Topology};
+
+/// Scheme prefix of DNS-discovered endpoints.
+const DNS_SCHEME: &str = "gwp+dns://";
+
+/// Split `gwp+dns://host:port` into host and port.
+fn parse(endpoint: &str) -> Option<(&str, u16)> {
+    let authority = endpoint.strip_prefix(DNS_SCHEME)?.trim_end_matches('/');
+    let (host, port) = authority.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Some((host, port.parse().ok()?))
+}
+
+/// Whether any endpoint in `topology` uses DNS discovery.
+pub(super) fn uses_dns(topology: &Topology) -> bool {
+    std::iter::once(&topology.writer)
+        .chain(&topology.readers)
+        .any(|e| e.starts_with(DNS_SCHEME))
+}
+
+async fn lookup(host: &str, port: u16) -> std::io::Result<Vec<String>> {
+    let mut addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+    addrs.sort_unstable();
+    addrs.dedup();
+    Ok(addrs.into_iter().map(|a| format!("http://{a}")).collect())
+}
+
+/// Expand DNS endpoints in `spec` into one endpoint per resolved address.
+///
+/// A DNS writer resolves to its lowest address, so the choice is stable
+/// across refreshes.
+pub(super) async fn resolve(spec: &Topology) -> std::io::Result<Topology> {
+    let writer = match parse(&spec.writer) {
+        Some((host, port)) => lookup(host, port)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| std::io::Error::other(format!("{} has no addresses", spec.writer)))?,
+        None => spec.writer.clone(),
+    };
+
+    let mut readers: Vec<String> = Vec::new();
+    for endpoint in &spec.readers {
+        let resolved = match parse(endpoint) {
+            Some((host, port)) => lookup(host, port).await?,
+            None => vec![endpoint.clone()],
+        };
+        for endpoint in resolved {
+            if !readers.contains(&endpoint) {
+                readers.push(endpoint);
+            }
+        }
+    }
+
+    Ok(Topology { writer, readers })
+}
+
+/// Re-resolve `spec` every `interval` and apply changes to `members`,
+/// until the connection is dropped.
+pub(super) async fn refresh(spec: Topology, interval: Duration, members: Weak<RwLock<Members>>) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let resolved = resolve(&spec).await;
+        let Some(members) = members.upgrade() else {
+            return;
+        };
+        match resolved {
+            Ok(topology) => {
+                if let Err(e) = super::apply(&members, &topology) {
+                    tracing::warn!("ignoring discovered topology: {e}");
+                }
+            }
+            Err(e) => tracing::warn!("DNS refresh failed, keeping current topology: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dns_endpoints() {
+        assert_eq!(parse("gwp+dns://db.svc:50051"), Some(("db.svc", 50051)));
+        assert_eq!(parse("gwp+dns://[::1]:7000/"), Some(("::1", 7000)));
+        assert_eq!(parse("gwp+dns://db.svc"), None);
+        assert_eq!(parse("http://db.svc:50051"), None);
+    }
+
+    #[tokio::test]
+    async fn resolves_localhost_readers() {
+        let spec = Topology {
+            writer: "http://writer:1".to_owned(),
+            readers: vec!["gwp+dns://localhost:7000".to_owned()],
+        };
+        let topology = resolve(&spec).await.unwrap();
+        assert_eq!(topology.writer, "http://writer:1");
+        assert!(
+            topology
+                .readers
+                .contains(&"http://127.0.0.1:7000".to_owned())
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Per-endpoint health tracking and circuit breaking.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tonic::transport::Channel;
+use tonic_health::pb::HealthCheckRequest;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+
+use crate::proto::gql_service_server::SERVICE_NAME;
+
+use super::super::GqlConnection;
+
+/// Weight of the newest sample in the failure-rate and latency averages.
+const SMOOTHING: f64 = 0.2;
+
+/// Circuit-breaker settings for a [`ClusterConnection`](super::ClusterConnection).
+///
+/// After `failure_threshold` consecutive unavailable errors an endpoint's
+/// circuit opens and the endpoint is skipped. Once `open_duration` has
+/// passed, the next request probes it with a gRPC health check; the
+/// circuit closes if the probe succeeds and opens again otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// Consecutive failures that open the circuit.
+    pub failure_threshold: u32,
+    /// How long an open circuit rejects requests before probing.
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// State of an endpoint's circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests are rejected until the open duration has passed.
+    Open,
+    /// The open duration has passed; the next request probes the endpoint.
+    HalfOpen,
+}
+
+/// Health of one cluster endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointHealth {
+    /// The endpoint.
+    pub endpoint: String,
+    /// State of its circuit.
+    pub state: CircuitState,
+    /// Exponentially weighted share of recent requests that failed, from
+    /// 0.0 to 1.0.
+    pub failure_rate: f64,
+    /// Exponentially weighted latency of recent successful requests.
+    pub latency: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    consecutive_failures: u32,
+    failure_rate: f64,
+    latency: Option<Duration>,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// Whether a request may use an endpoint.
+enum Admission {
+    Allow,
+    Probe,
+    Reject,
+}
+
+/// Health of one endpoint, shared by every member for that endpoint.
+#[derive(Debug, Default)]
+pub(super) struct Health(Mutex<State>);
+
+impl Health {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(super) fn record_success(&self, latency: Duration) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+        state.failure_rate *= 1.0 - SMOOTHING;
+        state.latency = Some(match state.latency {
+            Some(average) => average.mul_f64(1.0 - SMOOTHING) + latency.mul_f64(SMOOTHING),
+            None => latency,
+        });
+        state.opened_at = None;
+        state.probing = false;
+    }
+
+    pub(super) fn record_failure(&self, breaker: &CircuitBreaker) {
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        state.failure_rate = state.failure_rate * (1.0 - SMOOTHING) + SMOOTHING;
+        state.probing = false;
+        if state.opened_at.is_some() || state.consecutive_failures >= breaker.failure_threshold {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+
+    pub(super) fn state(&self, breaker: &CircuitBreaker) -> CircuitState {
+        match self.lock().opened_at {
+            None => CircuitState::Closed,
+            Some(at) if at.elapsed() < breaker.open_duration => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    pub(super) fn report(&self, endpoint: &str, breaker: &CircuitBreaker) -> EndpointHealth {
+        let state = self.state(breaker);
+        let inner = self.lock();
+        EndpointHealth {
+            endpoint: endpoint.to_owned(),
+            state,
+            failure_rate: inner.failure_rate,
+            latency: inner.latency,
+        }
+    }
+
+    fn admit(&self, breaker: &CircuitBreaker) -> Admission {
+        let mut state = self.lock();
+        match state.opened_at {
+            None => Admission::Allow,
+            Some(at) if at.elapsed() < breaker.open_duration || state.probing => Admission::Reject,
+            Some(_) => {
+                state.probing = true;
+                Admission::Probe
+            }
+        }
+    }
+
+    /// Whether a request may use the endpoint, probing it first if its
+    /// circuit is half-open. Concurrent requests are rejected while a
+    /// probe is in flight.
+    pub(super) async fn ready(&self, connection: &GqlConnection, breaker: &CircuitBreaker) -> bool {
+        match self.admit(breaker) {
+            Admission::Allow => true,
+            Admission::Reject => false,
+            Admission::Probe => {
+                let started = Instant::now();
+                if probe(connection.channel()).await {
+                    self.record_success(started.elapsed());
+                    true
+                } else {
+                    self.record_failure(breaker);
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// Whether the endpoint reports the GQL service as serving.
+pub(in crate::client) async fn probe(channel: &Channel) -> bool {
+    let mut client = HealthClient::new(channel.clone());
+    let request = HealthCheckRequest {
+        service: SERVICE_NAME.to_owned(),
+    };
+    match client.check(request).await {
+        Ok(response) => response.into_inner().status() == ServingStatus::Serving,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BREAKER: CircuitBreaker = CircuitBreaker {
+        failure_threshold: 2,
+        open_duration: Duration::from_secs(10),
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_opens_after_threshold_and_half_opens_later() {
+        let health = Health::default();
+        health.record_failure(&BREAKER);
+        assert_eq!(health.state(&BREAKER), CircuitState::Closed);
+        health.record_failure(&BREAKER);
+        assert_eq!(health.state(&BREAKER), CircuitState::Open);
+        assert!(matches!(health.admit(&BREAKER), Admission::Reject));
+
+        tokio::time::advance(BREAKER.open_duration).await;
+        assert_eq!(health.state(&BREAKER), CircuitState::HalfOpen);
+        assert!(matches!(health.admit(&BREAKER), Admission::Probe));
+        assert!(matches!(health.admit(&BREAKER), Admission::Reject));
+
+        health.record_success(Duration::from_millis(4));
+        assert_eq!(health.state(&BREAKER), CircuitState::Closed);
+        let report = health.report("http://a", &BREAKER);
+        assert_eq!(report.latency, Some(Duration::from_millis(4)));
+        assert!(report.failure_rate > 0.0 && report.failure_rate < 0.5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_probe_reopens_circuit() {
+        let health = Health::default();
+        health.record_failure(&BREAKER);
+        health.record_failure(&BREAKER);
+        tokio::time::advance(BREAKER.open_duration).await;
+        assert!(matches!(health.admit(&BREAKER), Admission::Probe));
+
+        health.record_failure(&BREAKER);
+        assert_eq!(health.state(&BREAKER), CircuitState::Open);
+    }
+}
@@ -0,0 +1,502 @@
+//! Multi-endpoint client with read-replica routing.
+
+mod discovery;
+mod health;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use tonic::transport::Endpoint;
+
+use crate::error::GqlError;
+use crate::server::is_read_only_statement;
+use crate::types::Value;
+
+use super::{GqlConnection, GqlSession, ResultCursor, Transaction};
+
+use health::Health;
+pub(super) use health::probe;
+pub use health::{CircuitBreaker, CircuitState, EndpointHealth};
+
+/// Endpoints of a cluster with one writer and any number of read replicas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topology {
+    /// Endpoint that accepts writes.
+    pub writer: String,
+    /// Read-replica endpoints.
+    pub readers: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Member {
+    endpoint: String,
+    connection: GqlConnection,
+    health: Arc<Health>,
+}
+
+#[derive(Debug)]
+struct Members {
+    generation: u64,
+    writer: Member,
+    readers: Vec<Member>,
+}
+
+/// A connection to a writer and its read replicas.
+///
+/// Writes and read-write transactions go to the writer. Read-only
+/// statements and transactions go to a reader, chosen round-robin per
+/// session. When a reader is unreachable the session fails over to the
+/// next one, and to the writer when no reader is reachable.
+///
+/// The topology is replaced with [`set_topology`](Self::set_topology);
+/// sessions pinned to a removed reader move to a current one on their
+/// next read. Channels connect lazily, so an endpoint that is down does
+/// not prevent the connection from being created.
+///
+/// Endpoints may be written as `gwp+dns://host:port` to discover them
+/// through DNS; see [`discover`](Self::discover). They may also be taken
+/// from the routing table a server advertises; see
+/// [`route`](Self::route).
+///
+/// Each endpoint has a circuit breaker, configured with
+/// [`with_circuit_breaker`](Self::with_circuit_breaker). Readers with an
+/// open circuit are skipped, and requests to a writer with an open
+/// circuit fail fast with `UNAVAILABLE`.
+#[derive(Debug, Clone)]
+pub struct ClusterConnection {
+    members: Arc<RwLock<Members>>,
+    next_reader: Arc<AtomicUsize>,
+    refresh: Option<Arc<RefreshTask>>,
+    breaker: CircuitBreaker,
+}
+
+/// Stops DNS refresh when the last connection clone is dropped.
+#[derive(Debug)]
+struct RefreshTask(tokio::task::JoinHandle<()>);
+
+impl Drop for RefreshTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl ClusterConnection {
+    /// Create a connection to the given topology.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an endpoint is not a valid URI.
+    pub fn new(topology: &Topology) -> Result<Self, tonic::transport::Error> {
+        let members = Members {
+            generation: 0,
+            writer: member(&topology.writer, &[])?,
+            readers: topology
+                .readers
+                .iter()
+                .map(|endpoint| member(endpoint, &[]))
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Self {
+            members: Arc::new(RwLock::new(members)),
+            next_reader: Arc::new(AtomicUsize::new(0)),
+            refresh: None,
+            breaker: CircuitBreaker::default(),
+        })
+    }
+
+    /// Set the circuit-breaker settings used for every endpoint.
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Create a connection whose `gwp+dns://host:port` endpoints are
+    /// resolved now and re-resolved every `interval`.
+    ///
+    /// Each reader entry expands to one reader per resolved address, and
+    /// a DNS writer resolves to its lowest address. On refresh, readers
+    /// that appeared are added and readers that disappeared are removed;
+    /// sessions reading from a removed reader close it and move to a
+    /// current one on their next read. Refreshing stops when the last
+    /// clone of the connection is dropped. Endpoints without the
+    /// `gwp+dns` scheme are used as given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial resolution fails or an endpoint is
+    /// not a valid URI.
+    pub async fn discover(spec: &Topology, interval: Duration) -> Result<Self, GqlError> {
+        let topology = discovery::resolve(spec)
+            .await
+            .map_err(|e| GqlError::Protocol(format!("endpoint discovery failed: {e}")))?;
+        let mut conn = Self::new(&topology)?;
+        if discovery::uses_dns(spec) {
+            let task = tokio::spawn(discovery::refresh(
+                spec.clone(),
+                interval,
+                Arc::downgrade(&conn.members),
+            ));
+            conn.refresh = Some(Arc::new(RefreshTask(task)));
+        }
+        Ok(conn)
+    }
+
+    /// Create a connection to the endpoints in the routing table that
+    /// `seed` advertises at handshake. A server without a routing table
+    /// becomes the writer, with no readers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake with `seed` fails or an
+    /// advertised endpoint is not a valid URI.
+    pub async fn route(seed: &str) -> Result<Self, GqlError> {
+        let seed = member(seed, &[])?;
+        let topology = routing_table(&seed).await?;
+        Ok(Self::new(&topology)?)
+    }
+
+    /// Fetch the routing table from the writer and apply it with
+    /// [`set_topology`](Self::set_topology), for example after a
+    /// failover moved the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake with the writer fails or an
+    /// advertised endpoint is not a valid URI.
+    pub async fn refresh_routing(&self) -> Result<(), GqlError> {
+        let writer = self.read().writer.clone();
+        let topology = routing_table(&writer).await?;
+        Ok(self.set_topology(&topology)?)
+    }
+
+    /// The current topology.
+    #[must_use]
+    pub fn topology(&self) -> Topology {
+        let members = self.read();
+        Topology {
+            writer: members.writer.endpoint.clone(),
+            readers: members.readers.iter().map(|m| m.endpoint.clone()).collect(),
+        }
+    }
+
+    /// Health of the writer followed by each reader.
+    #[must_use]
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        let members = self.read();
+        std::iter::once(&members.writer)
+            .chain(&members.readers)
+            .map(|m| m.health.report(&m.endpoint, &self.breaker))
+            .collect()
+    }
+
+    /// Replace the topology. Channels to endpoints present before and
+    /// after the change are kept. With DNS discovery, the next refresh
+    /// replaces the topology again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an endpoint is not a valid URI; the topology
+    /// is left unchanged.
+    pub fn set_topology(&self, topology: &Topology) -> Result<(), tonic::transport::Error> {
+        apply(&self.members, topology)
+    }
+
+    /// Open a session on the writer. A reader session is opened on
+    /// first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake with the writer fails.
+    pub async fn create_session(&self) -> Result<ClusterSession, GqlError> {
+        let writer = self.read().writer.clone();
+        let session = self.on_writer(&writer, writer.connection.create_session());
+        Ok(ClusterSession {
+            cluster: self.clone(),
+            writer: session.await?,
+            writer_member: writer,
+            reader: None,
+        })
+    }
+
+    /// Run `request` on the writer unless its circuit is open.
+    async fn on_writer<T>(
+        &self,
+        writer: &Member,
+        request: impl Future<Output = Result<T, GqlError>>,
+    ) -> Result<T, GqlError> {
+        if !writer.health.ready(&writer.connection, &self.breaker).await {
+            return Err(GqlError::Grpc(tonic::Status::unavailable(format!(
+                "circuit open for {}",
+                writer.endpoint
+            ))));
+        }
+        track(&writer.health, &self.breaker, request).await
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Members> {
+        self.members.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The topology generation and the readers, rotated so that
+    /// successive calls start at different readers.
+    fn readers(&self) -> (u64, Vec<Member>) {
+        let members = self.read();
+        let mut readers = members.readers.clone();
+        if !readers.is_empty() {
+            let start = self.next_reader.fetch_add(1, Ordering::Relaxed) % readers.len();
+            readers.rotate_left(start);
+        }
+        // Try readers with a closed circuit before probing the others.
+        readers.sort_by_key(|m| m.health.state(&self.breaker) != CircuitState::Closed);
+        (members.generation, readers)
+    }
+}
+
+/// Replace the members with `topology` unless it is unchanged.
+fn apply(members: &RwLock<Members>, topology: &Topology) -> Result<(), tonic::transport::Error> {
+    let mut members = members.write().unwrap_or_else(PoisonError::into_inner);
+    let unchanged = members.writer.endpoint == topology.writer
+        && members
+            .readers
+            .iter()
+            .map(|m| &m.endpoint)
+            .eq(&topology.readers);
+    if unchanged {
+        return Ok(());
+    }
+
+    let mut existing: Vec<Member> = members.readers.clone();
+    existing.push(members.writer.clone());
+    let writer = member(&topology.writer, &existing)?;
+    let readers = topology
+        .readers
+        .iter()
+        .map(|endpoint| member(endpoint, &existing))
+        .collect::<Result<_, _>>()?;
+
+    members.writer = writer;
+    members.readers = readers;
+    members.generation += 1;
+    Ok(())
+}
+
+/// The routing table `server` advertises, or `server` alone if it has none.
+async fn routing_table(server: &Member) -> Result<Topology, GqlError> {
+    let session = server.connection.create_session().await?;
+    let topology = session.routing_table().cloned();
+    let _ = session.close().await;
+    Ok(topology.unwrap_or_else(|| Topology {
+        writer: server.endpoint.clone(),
+        readers: Vec::new(),
+    }))
+}
+
+/// Reuse the connection for `endpoint` from `existing`, or open a lazy one.
+fn member(endpoint: &str, existing: &[Member]) -> Result<Member, tonic::transport::Error> {
+    if let Some(m) = existing.iter().find(|m| m.endpoint == endpoint) {
+        return Ok(m.clone());
+    }
+    let channel = Endpoint::try_from(endpoint.to_owned())?.connect_lazy();
+    Ok(Member {
+        endpoint: endpoint.to_owned(),
+        connection: GqlConnection::from_channel(channel),
+        health: Arc::default(),
+    })
+}
+
+/// Await `request`, recording its outcome and latency in `health`.
+async fn track<T>(
+    health: &Health,
+    breaker: &CircuitBreaker,
+    request: impl Future<Output = Result<T, GqlError>>,
+) -> Result<T, GqlError> {
+    let started = Instant::now();
+    let result = request.await;
+    match &result {
+        Err(err) if is_unavailable(err) => health.record_failure(breaker),
+        _ => health.record_success(started.elapsed()),
+    }
+    result
+}
+
+/// Whether an error means the endpoint could not be reached.
+pub(super) fn is_unavailable(err: &GqlError) -> bool {
+    match err {
+        GqlError::Transport(_) => true,
+        GqlError::Grpc(status) => status.code() == tonic::Code::Unavailable,
+        _ => false,
+    }
+}
+
+struct ReaderSession {
+    member: Member,
+    generation: u64,
+    session: GqlSession,
+}
+
+/// A session on a [`ClusterConnection`], pairing a writer session with a
+/// lazily opened reader session.
+///
+/// Configuration made through [`writer`](Self::writer), such as
+/// [`GqlSession::set_graph`], applies to the writer session only; reader
+/// sessions start from the server defaults.
+pub struct ClusterSession {
+    cluster: ClusterConnection,
+    writer: GqlSession,
+    writer_member: Member,
+    reader: Option<ReaderSession>,
+}
+
+impl ClusterSession {
+    /// The writer session.
+    pub fn writer(&mut self) -> &mut GqlSession {
+        &mut self.writer
+    }
+
+    /// The endpoint currently serving reads, if a reader session is open.
+    #[must_use]
+    pub fn reader_endpoint(&self) -> Option<&str> {
+        self.reader.as_ref().map(|r| r.member.endpoint.as_str())
+    }
+
+    /// Execute a statement, on a reader if it only reads data (see
+    /// [`is_read_only_statement`]) and on the writer otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request.
+    pub async fn execute(
+        &mut self,
+        statement: &str,
+        parameters: HashMap<String, Value>,
+    ) -> Result<ResultCursor, GqlError> {
+        if is_read_only_statement(statement) {
+            return self.execute_read(statement, parameters).await;
+        }
+        let request = self.writer.execute(statement, parameters);
+        self.cluster.on_writer(&self.writer_member, request).await
+    }
+
+    /// Execute a read-only statement on a reader, failing over to other
+    /// readers and finally the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the serving endpoint rejects the request.
+    pub async fn execute_read(
+        &mut self,
+        statement: &str,
+        parameters: HashMap<String, Value>,
+    ) -> Result<ResultCursor, GqlError> {
+        self.on_reader(async |session| session.execute(statement, parameters.clone()).await)
+            .await
+    }
+
+    /// Begin a read-write transaction on the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    pub async fn begin_transaction(&mut self) -> Result<Transaction, GqlError> {
+        let request = self.writer.begin_transaction();
+        self.cluster.on_writer(&self.writer_member, request).await
+    }
+
+    /// Begin a read-only transaction on a reader, failing over like
+    /// [`execute_read`](Self::execute_read).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    pub async fn begin_read_only_transaction(&mut self) -> Result<Transaction, GqlError> {
+        self.on_reader(async |session| session.begin_read_only_transaction().await)
+            .await
+    }
+
+    /// Close the writer and reader sessions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if closing the writer session fails.
+    pub async fn close(self) -> Result<(), GqlError> {
+        if let Some(reader) = self.reader {
+            let _ = reader.session.close().await;
+        }
+        self.writer.close().await
+    }
+
+    /// Run `op` on a reader session, opening or replacing it as needed.
+    async fn on_reader<T>(
+        &mut self,
+        mut op: impl AsyncFnMut(&mut GqlSession) -> Result<T, GqlError>,
+    ) -> Result<T, GqlError> {
+        let (generation, candidates) = self.cluster.readers();
+        self.release_removed_reader(generation, &candidates).await;
+
+        let breaker = self.cluster.breaker;
+        let mut failed = None;
+        if let Some(reader) = self.reader.as_mut() {
+            let member = &reader.member;
+            let result = if member.health.ready(&member.connection, &breaker).await {
+                track(&member.health, &breaker, op(&mut reader.session)).await
+            } else {
+                Err(GqlError::Grpc(tonic::Status::unavailable("circuit open")))
+            };
+            match result {
+                Err(err) if is_unavailable(&err) => {
+                    failed = self.reader.take().map(|r| r.member.endpoint);
+                }
+                result => return result,
+            }
+        }
+
+        for member in candidates {
+            if failed.as_deref() == Some(member.endpoint.as_str())
+                || !member.health.ready(&member.connection, &breaker).await
+            {
+                continue;
+            }
+            let created = member.connection.create_session();
+            let mut session = match track(&member.health, &breaker, created).await {
+                Ok(session) => session,
+                Err(err) if is_unavailable(&err) => continue,
+                Err(err) => return Err(err),
+            };
+            match track(&member.health, &breaker, op(&mut session)).await {
+                Err(err) if is_unavailable(&err) => {}
+                result => {
+                    self.reader = Some(ReaderSession {
+                        member,
+                        generation,
+                        session,
+                    });
+                    return result;
+                }
+            }
+        }
+
+        // No reader is reachable: serve the read from the writer.
+        let request = op(&mut self.writer);
+        self.cluster.on_writer(&self.writer_member, request).await
+    }
+
+    /// Close the reader session if its endpoint left the topology.
+    async fn release_removed_reader(&mut self, generation: u64, current: &[Member]) {
+        let Some(reader) = self.reader.as_mut() else {
+            return;
+        };
+        if reader.generation == generation {
+            return;
+        }
+        if current.iter().any(|m| m.endpoint == reader.member.endpoint) {
+            reader.generation = generation;
+        } else if let Some(removed) = self.reader.take() {
+            let _ = removed.session.close().await;
+        }
+    }
+}
@@ -1,12 +1,23 @@
 //! gRPC connection management.
 
+use std::time::Duration;
+
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
 use tonic::transport::Channel;
+use tonic_health::pb::HealthCheckRequest;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
 
 use crate::error::GqlError;
+use crate::proto;
+use crate::server::HealthStatus;
 
 use super::GqlSession;
 use super::admin::AdminClient;
 use super::catalog::CatalogClient;
+use super::endpoint::EndpointConfig;
+use super::retry::RetryPolicy;
 use super::search::SearchClient;
 
 /// A connection to a GQL wire protocol server.
@@ -15,6 +26,16 @@ use super::search::SearchClient;
 #[derive(Debug, Clone)]
 pub struct GqlConnection {
     channel: Channel,
+    credentials: Option<proto::AuthCredentials>,
+    database: Option<String>,
+    row_batch_checksums: bool,
+    dictionary_encoding: bool,
+    columnar_batches: bool,
+    compression: Option<proto::Compression>,
+    max_message_size: Option<usize>,
+    keepalive: Option<Duration>,
+    close_timeout: Option<Duration>,
+    retry: RetryPolicy,
 }
 
 impl GqlConnection {
@@ -40,13 +61,156 @@ impl GqlConnection {
             .connect()
             .await?;
 
-        Ok(Self { channel })
+        Ok(Self::from_channel(channel))
+    }
+
+    /// Connect to a GQL server at the given endpoint, with the connect
+    /// timeout, keepalives, and flow control set in `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection cannot be established.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    ///
+    /// use gwp::client::{EndpointConfig, GqlConnection};
+    ///
+    /// let config = EndpointConfig::default()
+    ///     .with_connect_timeout(Duration::from_secs(5))
+    ///     .with_http2_keepalive(Duration::from_secs(30), Duration::from_secs(10), true);
+    /// let conn = GqlConnection::connect_with("http://localhost:50051", &config).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_with(endpoint: &str, config: &EndpointConfig) -> Result<Self, GqlError> {
+        let endpoint = tonic::transport::Endpoint::from_shared(endpoint.to_owned())
+            .map_err(|e| GqlError::Protocol(e.to_string()))?;
+        let channel = config.apply(endpoint).connect().await?;
+
+        Ok(Self::from_channel(channel))
     }
 
     /// Create a connection from an existing tonic channel.
     #[must_use]
     pub fn from_channel(channel: Channel) -> Self {
-        Self { channel }
+        Self {
+            channel,
+            credentials: None,
+            database: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
+            compression: None,
+            max_message_size: None,
+            keepalive: None,
+            close_timeout: None,
+            retry: RetryPolicy::none(),
+        }
+    }
+
+    /// Authenticate sessions created from this connection with
+    /// `credentials`, for servers that require authentication.
+    #[must_use]
+    pub fn with_credentials(mut self, credentials: proto::AuthCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Route the statements of sessions created from this connection to
+    /// `database`, for servers hosting several databases; see
+    /// [`GqlSession::set_database`].
+    #[must_use]
+    pub fn with_database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// Ask the server to checksum every row batch in sessions created
+    /// from this connection, and verify the checksums in result cursors.
+    ///
+    /// Detects corruption introduced between server and client, such as
+    /// by a faulty proxy. If the server agrees, a batch with a missing or
+    /// wrong checksum fails the cursor with a protocol error; see
+    /// [`GqlSession::row_batch_checksums`].
+    #[must_use]
+    pub fn with_row_batch_checksums(mut self) -> Self {
+        self.row_batch_checksums = true;
+        self
+    }
+
+    /// Ask the server to dictionary-encode row batches in sessions
+    /// created from this connection.
+    ///
+    /// Repeated labels, property keys, and string values are then sent
+    /// once per batch, which shrinks label-heavy graph results. Result
+    /// cursors decode batches transparently; see
+    /// [`GqlSession::dictionary_encoding`].
+    #[must_use]
+    pub fn with_dictionary_encoding(mut self) -> Self {
+        self.dictionary_encoding = true;
+        self
+    }
+
+    /// Ask the server to send row batches in sessions created from this
+    /// connection by column rather than by row.
+    ///
+    /// Each column's values then travel as one packed vector, with nulls
+    /// in a bitmap, which is several times smaller for wide numeric
+    /// results. Result cursors turn batches back into rows transparently;
+    /// see [`GqlSession::columnar_batches`].
+    #[must_use]
+    pub fn with_columnar_batches(mut self) -> Self {
+        self.columnar_batches = true;
+        self
+    }
+
+    /// Ask the server to compress the result streams of sessions created
+    /// from this connection with `compression`; see
+    /// [`GqlSession::set_compression`].
+    #[must_use]
+    pub fn with_compression(mut self, compression: proto::Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Accept and send messages of up to `max` bytes in sessions created
+    /// from this connection (default: tonic's 4 MiB for received
+    /// messages). Servers split result batches to their own limit, set
+    /// with [`GqlServer::max_message_size`](crate::server::GqlServer::max_message_size),
+    /// so this should be at least as large.
+    #[must_use]
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = Some(max);
+        self
+    }
+
+    /// Keep sessions created from this connection alive by pinging the
+    /// server every `interval`; see [`GqlSession::set_keepalive`].
+    #[must_use]
+    pub fn with_session_keepalive(mut self, interval: Duration) -> Self {
+        self.keepalive = Some(interval);
+        self
+    }
+
+    /// Give up closing sessions created from this connection after
+    /// `timeout`; see [`GqlSession::set_close_timeout`].
+    #[must_use]
+    pub fn with_close_timeout(mut self, timeout: Duration) -> Self {
+        self.close_timeout = Some(timeout);
+        self
+    }
+
+    /// Retry idempotent calls of sessions created from this connection
+    /// that fail with a transient error, such as `UNAVAILABLE` while the
+    /// server restarts. By default nothing is retried.
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
     }
 
     /// Perform a handshake and return a session.
@@ -55,7 +219,86 @@ impl GqlConnection {
     ///
     /// Returns an error if the handshake fails.
     pub async fn create_session(&self) -> Result<GqlSession, GqlError> {
-        GqlSession::new(self.channel.clone()).await
+        let mut session = GqlSession::new(
+            self.channel.clone(),
+            self.credentials.clone(),
+            None,
+            self.database.clone(),
+            self.row_batch_checksums,
+            self.dictionary_encoding,
+            self.columnar_batches,
+        )
+        .await?;
+        self.configure(&mut session);
+        Ok(session)
+    }
+
+    /// Perform a handshake that reattaches to the session `token` was
+    /// issued for, as returned by [`GqlSession::resumption_token`].
+    ///
+    /// The session keeps its schema, graph, and parameters, but any
+    /// transaction it had open is rolled back. If the server no longer
+    /// has the session, the token has expired or was already used, or
+    /// resumption is disabled, a new session is created instead; check
+    /// [`GqlSession::is_resumed`]. Use [`resume`](Self::resume) to keep
+    /// the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake fails.
+    pub async fn resume_session(&self, token: &str) -> Result<GqlSession, GqlError> {
+        let mut session = GqlSession::new(
+            self.channel.clone(),
+            self.credentials.clone(),
+            Some(token.to_owned()),
+            self.database.clone(),
+            self.row_batch_checksums,
+            self.dictionary_encoding,
+            self.columnar_batches,
+        )
+        .await?;
+        self.configure(&mut session);
+        Ok(session)
+    }
+
+    /// Reattach to the session `token` was issued for, keeping the
+    /// transaction it had open; take it with
+    /// [`GqlSession::take_resumed_transaction`].
+    ///
+    /// Meant for reconnecting after the transport fails: statements that
+    /// were streaming when the connection dropped are lost, but the
+    /// session and its transaction are not.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if resumption is disabled on the server, or the
+    /// session cannot be resumed because it is gone or the token has
+    /// expired or was already used.
+    pub async fn resume(&self, token: &str) -> Result<GqlSession, GqlError> {
+        let mut session = GqlSession::resume(
+            self.channel.clone(),
+            self.credentials.clone(),
+            token.to_owned(),
+            self.row_batch_checksums,
+            self.dictionary_encoding,
+            self.columnar_batches,
+        )
+        .await?;
+        self.configure(&mut session);
+        Ok(session)
+    }
+
+    /// Apply this connection's settings to a new `session`.
+    fn configure(&self, session: &mut GqlSession) {
+        session.set_retry_policy(self.retry.clone());
+        session.set_compression(self.compression);
+        if let Some(max) = self.max_message_size {
+            session.set_max_message_size(max);
+        }
+        if self.keepalive.is_some() {
+            session.set_keepalive(self.keepalive);
+        }
+        session.set_close_timeout(self.close_timeout);
     }
 
     /// Create a catalog management client (schemas, graphs, graph types).
@@ -76,6 +319,27 @@ impl GqlConnection {
         SearchClient::new(self.channel.clone())
     }
 
+    /// Create a catalog client whose calls the server authorizes as
+    /// `session`'s principal.
+    #[must_use]
+    pub fn create_catalog_client_for(&self, session: &GqlSession) -> CatalogClient {
+        CatalogClient::for_session(self.channel.clone(), session.session_id())
+    }
+
+    /// Create an admin client whose calls the server authorizes as
+    /// `session`'s principal.
+    #[must_use]
+    pub fn create_admin_client_for(&self, session: &GqlSession) -> AdminClient {
+        AdminClient::for_session(self.channel.clone(), session.session_id())
+    }
+
+    /// Create a search client whose calls the server authorizes as
+    /// `session`'s principal.
+    #[must_use]
+    pub fn create_search_client_for(&self, session: &GqlSession) -> SearchClient {
+        SearchClient::for_session(self.channel.clone(), session.session_id())
+    }
+
     /// Connect to a GQL server with TLS.
     ///
     /// Requires the `tls` feature to be enabled.
@@ -95,7 +359,53 @@ impl GqlConnection {
             .connect()
             .await?;
 
-        Ok(Self { channel })
+        Ok(Self::from_channel(channel))
+    }
+
+    /// Connect to a GQL server listening on the Unix domain socket at
+    /// `path`, such as one started with
+    /// [`GqlServer::serve_uds`](crate::server::GqlServer::serve_uds).
+    ///
+    /// The channel reconnects through the same socket if the connection
+    /// drops.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket cannot be connected to.
+    #[cfg(unix)]
+    pub async fn connect_uds(path: impl AsRef<std::path::Path>) -> Result<Self, GqlError> {
+        let path = path.as_ref().to_path_buf();
+        // The URI is required but unused: every connection goes to `path`.
+        let channel = tonic::transport::Endpoint::from_static("http://localhost")
+            .connect_with_connector(tower::service_fn(move |_: tonic::transport::Uri| {
+                let path = path.clone();
+                async move {
+                    let stream = tokio::net::UnixStream::connect(path).await?;
+                    Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+                }
+            }))
+            .await?;
+
+        Ok(Self::from_channel(channel))
+    }
+
+    /// Ask the server's gRPC health service whether it can serve requests,
+    /// which it cannot while its backend reports
+    /// [`NotServing`](HealthStatus::NotServing).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the health check fails.
+    pub async fn server_health(&self) -> Result<HealthStatus, GqlError> {
+        let mut client = HealthClient::new(self.channel.clone());
+        let request = HealthCheckRequest {
+            service: proto::gql_service_server::SERVICE_NAME.to_owned(),
+        };
+        let response = client.check(request).await?.into_inner();
+        Ok(match response.status() {
+            ServingStatus::Serving => HealthStatus::Serving,
+            _ => HealthStatus::NotServing,
+        })
     }
 
     /// Get the underlying tonic channel.
@@ -104,3 +414,28 @@ impl GqlConnection {
         &self.channel
     }
 }
+
+/// Names the session a catalog, admin, or search request is made for in
+/// its metadata.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SessionTag(Option<MetadataValue<Ascii>>);
+
+impl SessionTag {
+    pub(crate) fn new(session_id: &str) -> Self {
+        Self(MetadataValue::try_from(session_id).ok())
+    }
+}
+
+impl Interceptor for SessionTag {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        if let Some(session_id) = &self.0 {
+            request
+                .metadata_mut()
+                .insert(proto::SESSION_ID_METADATA, session_id.clone());
+        }
+        Ok(request)
+    }
+}
@@ -0,0 +1,197 @@
+//! Opt-in client-side field-level encryption.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::error::GqlError;
+use crate::types::{CipherError, Field, Record, Value, ValueCipher};
+
+/// Encrypts marked parameters and record fields before they leave the
+/// client, and decrypts encrypted values in results.
+///
+/// Install it with
+/// [`GqlSession::set_field_encryption`](super::GqlSession::set_field_encryption);
+/// transactions begun from the session use the same settings. The server
+/// only ever sees [`Value::Encrypted`] envelopes for marked values, and
+/// query logs record the envelopes rather than the plaintext.
+#[derive(Clone)]
+pub struct FieldEncryption {
+    cipher: Arc<dyn ValueCipher>,
+    parameters: HashSet<String>,
+    fields: HashSet<String>,
+}
+
+impl FieldEncryption {
+    /// Encrypt and decrypt with `cipher`. Nothing is encrypted until
+    /// parameters or fields are marked; results are always decrypted.
+    pub fn new(cipher: impl ValueCipher) -> Self {
+        Self {
+            cipher: Arc::new(cipher),
+            parameters: HashSet::new(),
+            fields: HashSet::new(),
+        }
+    }
+
+    /// Encrypt the parameter `name`.
+    #[must_use]
+    pub fn encrypt_parameter(mut self, name: impl Into<String>) -> Self {
+        self.parameters.insert(name.into());
+        self
+    }
+
+    /// Encrypt record fields called `name`, at any depth within
+    /// parameters.
+    #[must_use]
+    pub fn encrypt_field(mut self, name: impl Into<String>) -> Self {
+        self.fields.insert(name.into());
+        self
+    }
+
+    /// Encrypt the marked parameters and fields in `parameters`.
+    pub(crate) fn encrypt_parameters(
+        &self,
+        parameters: HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>, CipherError> {
+        parameters
+            .into_iter()
+            .map(|(name, value)| {
+                let value = if self.parameters.contains(&name) {
+                    self.encrypt(&value)
+                } else {
+                    self.encrypt_fields(value)
+                };
+                value.map(|value| (name, value))
+            })
+            .collect()
+    }
+
+    /// Encrypt the marked fields among the properties of a node or edge,
+    /// treating each property as a record field.
+    pub(crate) fn encrypt_properties(
+        &self,
+        properties: HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>, CipherError> {
+        properties
+            .into_iter()
+            .map(|(name, value)| {
+                let value = if self.fields.contains(&name) {
+                    self.encrypt(&value)
+                } else {
+                    self.encrypt_fields(value)
+                };
+                value.map(|value| (name, value))
+            })
+            .collect()
+    }
+
+    /// Decrypt every encrypted value in `row` the cipher holds the key for.
+    pub(crate) fn decrypt_row(&self, row: Vec<Value>) -> Result<Vec<Value>, CipherError> {
+        row.into_iter()
+            .map(|value| value.decrypt_with(&*self.cipher))
+            .collect()
+    }
+
+    fn encrypt(&self, value: &Value) -> Result<Value, CipherError> {
+        match value {
+            Value::Null | Value::Encrypted(_) => Ok(value.clone()),
+            _ => self.cipher.encrypt(value).map(Value::Encrypted),
+        }
+    }
+
+    fn encrypt_fields(&self, value: Value) -> Result<Value, CipherError> {
+        if self.fields.is_empty() {
+            return Ok(value);
+        }
+        Ok(match value {
+            Value::List(elements) => Value::List(
+                elements
+                    .into_iter()
+                    .map(|v| self.encrypt_fields(v))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Record(record) => {
+                let mut fields = Vec::with_capacity(record.fields.len());
+                for field in record.fields {
+                    let value = if self.fields.contains(&field.name) {
+                        self.encrypt(&field.value)?
+                    } else {
+                        self.encrypt_fields(field.value)?
+                    };
+                    fields.push(Field {
+                        name: field.name,
+                        value,
+                    });
+                }
+                Value::Record(Record { fields })
+            }
+            other => other,
+        })
+    }
+}
+
+/// Surface a cipher failure as a client error.
+pub(crate) fn failed(err: impl std::fmt::Display) -> GqlError {
+    GqlError::Protocol(format!("field encryption failed: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EncryptedValue;
+
+    /// Stores the value's display form under key "k1".
+    struct Plain;
+
+    impl ValueCipher for Plain {
+        fn encrypt(&self, value: &Value) -> Result<EncryptedValue, CipherError> {
+            Ok(EncryptedValue {
+                key_id: "k1".to_owned(),
+                algorithm: "plain".to_owned(),
+                ciphertext: value.to_string().into_bytes(),
+            })
+        }
+
+        fn decrypt(&self, value: &EncryptedValue) -> Result<Option<Value>, CipherError> {
+            Ok(Some(Value::String(String::from_utf8(
+                value.ciphertext.clone(),
+            )?)))
+        }
+    }
+
+    #[test]
+    fn encrypts_marked_parameters_and_nested_fields() {
+        let encryption = FieldEncryption::new(Plain)
+            .encrypt_parameter("ssn")
+            .encrypt_field("email");
+        let person = Value::Record(Record {
+            fields: vec![
+                Field {
+                    name: "name".to_owned(),
+                    value: Value::from("Alice"),
+                },
+                Field {
+                    name: "email".to_owned(),
+                    value: Value::from("alice@example.com"),
+                },
+            ],
+        });
+        let parameters = HashMap::from([
+            ("ssn".to_owned(), Value::from("123-45-6789")),
+            ("missing".to_owned(), Value::Null),
+            ("people".to_owned(), Value::List(vec![person])),
+        ]);
+
+        let encrypted = encryption.encrypt_parameters(parameters).unwrap();
+        assert!(encrypted["ssn"].as_encrypted().is_some());
+        assert_eq!(encrypted["missing"], Value::Null);
+        let people = encrypted["people"].as_list().unwrap();
+        let person = people[0].as_record().unwrap();
+        assert_eq!(person.get("name"), Some(&Value::from("Alice")));
+        assert!(person.get("email").unwrap().as_encrypted().is_some());
+
+        let row = encryption
+            .decrypt_row(vec![encrypted["ssn"].clone()])
+            .unwrap();
+        assert_eq!(row, vec![Value::from("123-45-6789")]);
+    }
+}
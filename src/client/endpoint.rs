@@ -0,0 +1,93 @@
+//! Channel tuning for connections.
+
+use std::time::Duration;
+
+use tonic::transport::Endpoint;
+
+/// How a connection's channel connects and keeps itself alive, for
+/// [`GqlConnection::connect_with`](super::GqlConnection::connect_with).
+///
+/// The default leaves every setting to tonic: no connect timeout, no TCP
+/// or HTTP/2 keepalive, a fixed HTTP/2 flow-control window, and no limit
+/// on concurrent requests. On flaky networks, keepalives detect a dead
+/// connection before a request waits on it, and a connect timeout stops
+/// an unreachable server from holding up the first request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointConfig {
+    connect_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    http2_keepalive_interval: Option<Duration>,
+    http2_keepalive_timeout: Option<Duration>,
+    keepalive_while_idle: bool,
+    adaptive_window: bool,
+    concurrency_limit: Option<usize>,
+}
+
+impl EndpointConfig {
+    /// Give up connecting after `timeout`.
+    #[must_use]
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Send TCP keepalive probes after the connection has been idle for
+    /// `idle`.
+    #[must_use]
+    pub fn with_tcp_keepalive(mut self, idle: Duration) -> Self {
+        self.tcp_keepalive = Some(idle);
+        self
+    }
+
+    /// Send an HTTP/2 ping every `interval`, closing the connection if
+    /// one is not acknowledged within `timeout`. Pings are sent only
+    /// while requests are open unless `while_idle` is set.
+    #[must_use]
+    pub fn with_http2_keepalive(
+        mut self,
+        interval: Duration,
+        timeout: Duration,
+        while_idle: bool,
+    ) -> Self {
+        self.http2_keepalive_interval = Some(interval);
+        self.http2_keepalive_timeout = Some(timeout);
+        self.keepalive_while_idle = while_idle;
+        self
+    }
+
+    /// Size the HTTP/2 flow-control window to the measured bandwidth and
+    /// latency, which speeds up large results over long-distance links.
+    #[must_use]
+    pub fn with_adaptive_window(mut self) -> Self {
+        self.adaptive_window = true;
+        self
+    }
+
+    /// Allow at most `limit` requests in flight on the channel at once;
+    /// later requests wait for one to finish.
+    #[must_use]
+    pub fn with_concurrency_limit(mut self, limit: usize) -> Self {
+        self.concurrency_limit = Some(limit);
+        self
+    }
+
+    /// Apply the settings to `endpoint`.
+    pub(super) fn apply(&self, mut endpoint: Endpoint) -> Endpoint {
+        if let Some(timeout) = self.connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        endpoint = endpoint.tcp_keepalive(self.tcp_keepalive);
+        if let Some(interval) = self.http2_keepalive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.http2_keepalive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        if let Some(limit) = self.concurrency_limit {
+            endpoint = endpoint.concurrency_limit(limit);
+        }
+        endpoint
+            .keep_alive_while_idle(self.keepalive_while_idle)
+            .http2_adaptive_window(self.adaptive_window)
+    }
+}
@@ -0,0 +1,37 @@
+//! Notifications the server pushes to a session.
+
+use crate::error::GqlError;
+use crate::proto;
+
+/// A session's stream of server events, from
+/// [`GqlSession::server_events`](super::GqlSession::server_events).
+pub struct ServerEvents {
+    stream: tonic::Streaming<proto::ServerEvent>,
+}
+
+impl ServerEvents {
+    pub(crate) fn new(stream: tonic::Streaming<proto::ServerEvent>) -> Self {
+        Self { stream }
+    }
+
+    /// Wait for the next event, or `None` once the server ends the
+    /// stream. Events of kinds this client does not know are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails.
+    pub async fn next(&mut self) -> Result<Option<proto::server_event::Event>, GqlError> {
+        while let Some(event) = self.stream.message().await? {
+            if let Some(event) = event.event {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl std::fmt::Debug for ServerEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerEvents").finish_non_exhaustive()
+    }
+}
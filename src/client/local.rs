@@ -0,0 +1,98 @@
+//! In-process connections to a server running in the same process.
+
+use tokio::sync::mpsc;
+use tonic::transport::{Endpoint, Uri};
+
+use crate::error::GqlError;
+use crate::server::{GqlBackend, GqlServer};
+
+use super::{GqlConnection, GqlSession};
+
+/// Bytes buffered in each direction of an in-memory connection.
+const DUPLEX_BUFFER: usize = 64 * 1024;
+
+/// Connections waiting for the server to accept them.
+const PENDING_CONNECTIONS: usize = 16;
+
+/// A connection to a server running in the same process, for tests and
+/// embedded deployments that want the client API without opening a
+/// socket.
+///
+/// [`start`](Self::start) runs the server on a background task and
+/// connects to it over in-memory streams. The server keeps running until
+/// its shutdown signal fires or every connection cloned from this one is
+/// dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use gwp::client::LocalConnection;
+/// use gwp::server::GqlServer;
+/// use gwp::server::mock_backend::MockBackend;
+///
+/// let local = LocalConnection::start(GqlServer::builder(MockBackend::new()));
+/// let mut session = local.create_session().await?;
+/// session.execute_simple("MATCH (n) RETURN n").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalConnection {
+    connection: GqlConnection,
+}
+
+impl LocalConnection {
+    /// Run `server` on a background task and connect to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    #[must_use]
+    pub fn start<B: GqlBackend>(server: GqlServer<B>) -> Self {
+        let (accept, incoming) = mpsc::channel(PENDING_CONNECTIONS);
+        tokio::spawn(async move {
+            if let Err(err) = server.serve_local(incoming).await {
+                tracing::error!(error = %err, "in-process GWP server failed");
+            }
+        });
+        // The URI is required but unused: every connection is a new pair
+        // of in-memory streams handed to the server.
+        let channel = Endpoint::from_static("http://localhost").connect_with_connector_lazy(
+            tower::service_fn(move |_: Uri| {
+                let accept = accept.clone();
+                async move {
+                    let (client, server) = tokio::io::duplex(DUPLEX_BUFFER);
+                    accept.send(server).await.map_err(|_| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::ConnectionRefused,
+                            "in-process server stopped",
+                        )
+                    })?;
+                    Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(client))
+                }
+            }),
+        );
+        Self {
+            connection: GqlConnection::from_channel(channel),
+        }
+    }
+
+    /// A connection to the server, with the same API as one over the
+    /// network.
+    ///
+    /// Connections share one underlying channel, so cloning is cheap.
+    #[must_use]
+    pub fn connection(&self) -> GqlConnection {
+        self.connection.clone()
+    }
+
+    /// Perform a handshake and return a new session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake fails.
+    pub async fn create_session(&self) -> Result<GqlSession, GqlError> {
+        self.connection.create_session().await
+    }
+}
@@ -3,17 +3,58 @@
 //! Wraps the raw tonic gRPC stubs with a typed, session-oriented API.
 
 mod admin;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod batch;
+mod bulk;
 mod catalog;
+mod cluster;
 mod connection;
+mod encryption;
+mod endpoint;
+mod events;
+mod local;
+mod notifications;
+mod pool;
+mod prepared;
+mod query_log;
 mod result;
+mod retry;
+mod row;
 mod search;
 mod session;
+mod spill;
+mod summary;
+mod table;
+mod trace;
 mod transaction;
+mod typed;
+mod warning;
 
-pub use admin::AdminClient;
+pub use admin::{AdminClient, IndexBuildProgress};
+pub use batch::{BatchCursor, BatchResult};
+pub use bulk::BulkLoader;
 pub use catalog::CatalogClient;
+pub use cluster::{
+    CircuitBreaker, CircuitState, ClusterConnection, ClusterSession, EndpointHealth, Topology,
+};
 pub use connection::GqlConnection;
-pub use result::ResultCursor;
+pub use encryption::FieldEncryption;
+pub use endpoint::EndpointConfig;
+pub use events::ServerEvents;
+pub use local::LocalConnection;
+pub use notifications::NotificationStream;
+pub use pool::GqlPool;
+pub use prepared::PreparedStatement;
+pub use query_log::{QueryLog, QueryRecord, QuerySink, REDACTED};
+pub use result::{ResultCursor, TypedRows};
+pub use retry::RetryPolicy;
+pub use row::Row;
 pub use search::SearchClient;
 pub use session::GqlSession;
-pub use transaction::Transaction;
+pub use spill::SpilledRows;
+pub use summary::Summary;
+pub use table::BindingTable;
+pub use transaction::{Transaction, TxOptions};
+pub use typed::{FromRow, FromValue, Params, column};
+pub use warning::{ServerWarning, Severity};
@@ -0,0 +1,114 @@
+//! Change events a backend publishes to subscribed sessions.
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::proto::notification_service_client::NotificationServiceClient;
+use crate::server::ChangeEvent;
+use crate::status;
+
+use super::trace::TracedChannel;
+
+/// Subscribe and unsubscribe requests queued ahead of the server.
+const REQUEST_BUFFER: usize = 8;
+
+/// A session's subscriptions to change events, from
+/// [`GqlSession::notifications`](super::GqlSession::notifications).
+///
+/// Topics are added and removed with [`subscribe`](Self::subscribe) and
+/// [`unsubscribe`](Self::unsubscribe) while the stream is open, and the
+/// events of all of them arrive through [`next`](Self::next). Dropping
+/// the stream ends every subscription.
+pub struct NotificationStream {
+    session_id: String,
+    requests: mpsc::Sender<proto::SubscribeRequest>,
+    stream: tonic::Streaming<proto::Notification>,
+}
+
+impl NotificationStream {
+    pub(crate) async fn open(
+        session_id: String,
+        mut client: NotificationServiceClient<TracedChannel>,
+    ) -> Result<Self, GqlError> {
+        let (requests, rx) = mpsc::channel(REQUEST_BUFFER);
+        // The server reads the session from the first message before it
+        // responds, so send it up front.
+        let first = proto::SubscribeRequest {
+            session_id: session_id.clone(),
+            action: None,
+        };
+        requests
+            .send(first)
+            .await
+            .map_err(|_| GqlError::Protocol("notification stream closed".to_owned()))?;
+        let stream = client
+            .subscribe(ReceiverStream::new(rx))
+            .await?
+            .into_inner();
+        Ok(Self {
+            session_id,
+            requests,
+            stream,
+        })
+    }
+
+    /// Start receiving the events published to `topic`. A topic the
+    /// server cannot subscribe to is reported by [`next`](Self::next).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has ended the stream.
+    pub async fn subscribe(&self, topic: impl Into<String>) -> Result<(), GqlError> {
+        self.send(proto::subscribe_request::Action::Subscribe(topic.into()))
+            .await
+    }
+
+    /// Stop receiving the events published to `topic`. Events already on
+    /// their way may still arrive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has ended the stream.
+    pub async fn unsubscribe(&self, topic: impl Into<String>) -> Result<(), GqlError> {
+        self.send(proto::subscribe_request::Action::Unsubscribe(topic.into()))
+            .await
+    }
+
+    async fn send(&self, action: proto::subscribe_request::Action) -> Result<(), GqlError> {
+        let request = proto::SubscribeRequest {
+            session_id: self.session_id.clone(),
+            action: Some(action),
+        };
+        self.requests
+            .send(request)
+            .await
+            .map_err(|_| GqlError::Protocol("notification stream ended by the server".to_owned()))
+    }
+
+    /// Wait for the next event, or `None` once the server ends the
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GqlError::Status`] if a subscription failed, leaving the
+    /// others in place, or an error if the stream fails.
+    pub async fn next(&mut self) -> Result<Option<ChangeEvent>, GqlError> {
+        let Some(notification) = self.stream.message().await? else {
+            return Ok(None);
+        };
+        match notification.status {
+            Some(s) if status::is_exception(&s.code) => Err(GqlError::Status { status: s }),
+            _ => Ok(Some(notification.into())),
+        }
+    }
+}
+
+impl std::fmt::Debug for NotificationStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotificationStream")
+            .field("session_id", &self.session_id)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,267 @@
+//! A pool of channels to one server.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, Weak};
+use std::time::Duration;
+
+use tonic::transport::{Channel, Endpoint};
+
+use crate::error::GqlError;
+use crate::proto;
+
+use super::cluster::{is_unavailable, probe};
+use super::{GqlConnection, GqlSession, RetryPolicy};
+
+/// A fixed number of gRPC channels to one server, shared by many
+/// sessions.
+///
+/// Each channel is an HTTP/2 connection that multiplexes any number of
+/// sessions, so a small pool serves a large number of sessions without a
+/// socket each. [`create_session`](Self::create_session) picks channels
+/// round-robin. A channel whose handshake fails because the server is
+/// unreachable is replaced by a fresh one and the next channel is tried;
+/// [`health_check`](Self::health_check) and
+/// [`with_health_checks`](Self::with_health_checks) replace channels
+/// whose server stops reporting the GQL service as serving.
+///
+/// Clones share the channels. Background health checks stop when the
+/// last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct GqlPool {
+    channels: Arc<Channels>,
+    health_checks: Option<Arc<HealthCheckTask>>,
+    credentials: Option<proto::AuthCredentials>,
+    database: Option<String>,
+    row_batch_checksums: bool,
+    dictionary_encoding: bool,
+    columnar_batches: bool,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug)]
+struct Channels {
+    endpoint: Endpoint,
+    slots: Vec<RwLock<Channel>>,
+    next: AtomicUsize,
+}
+
+/// Stops health checks when the last pool clone is dropped.
+#[derive(Debug)]
+struct HealthCheckTask(tokio::task::JoinHandle<()>);
+
+impl Drop for HealthCheckTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl GqlPool {
+    /// Open `size` channels to the server at `endpoint`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `endpoint` is not a valid URI, `size` is zero,
+    /// or a channel cannot be established.
+    pub async fn connect(endpoint: &str, size: usize) -> Result<Self, GqlError> {
+        if size == 0 {
+            return Err(GqlError::Protocol("pool size must be at least 1".into()));
+        }
+        let endpoint = Endpoint::from_shared(endpoint.to_owned())
+            .map_err(|e| GqlError::Protocol(e.to_string()))?;
+        let mut slots = Vec::with_capacity(size);
+        for _ in 0..size {
+            slots.push(RwLock::new(endpoint.connect().await?));
+        }
+        Ok(Self {
+            channels: Arc::new(Channels {
+                endpoint,
+                slots,
+                next: AtomicUsize::new(0),
+            }),
+            health_checks: None,
+            credentials: None,
+            database: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
+            retry: RetryPolicy::none(),
+        })
+    }
+
+    /// Check the health of every channel every `interval` in the
+    /// background; see [`health_check`](Self::health_check).
+    #[must_use]
+    pub fn with_health_checks(mut self, interval: Duration) -> Self {
+        let channels = Arc::downgrade(&self.channels);
+        let task = tokio::spawn(check_periodically(channels, interval));
+        self.health_checks = Some(Arc::new(HealthCheckTask(task)));
+        self
+    }
+
+    /// Authenticate sessions created from this pool with `credentials`;
+    /// see [`GqlConnection::with_credentials`].
+    #[must_use]
+    pub fn with_credentials(mut self, credentials: proto::AuthCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Route the statements of sessions created from this pool to
+    /// `database`; see [`GqlConnection::with_database`].
+    #[must_use]
+    pub fn with_database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// Ask for row batch checksums in sessions created from this pool;
+    /// see [`GqlConnection::with_row_batch_checksums`].
+    #[must_use]
+    pub fn with_row_batch_checksums(mut self) -> Self {
+        self.row_batch_checksums = true;
+        self
+    }
+
+    /// Ask for dictionary-encoded row batches in sessions created from
+    /// this pool; see [`GqlConnection::with_dictionary_encoding`].
+    #[must_use]
+    pub fn with_dictionary_encoding(mut self) -> Self {
+        self.dictionary_encoding = true;
+        self
+    }
+
+    /// Ask for columnar row batches in sessions created from this pool;
+    /// see [`GqlConnection::with_columnar_batches`].
+    #[must_use]
+    pub fn with_columnar_batches(mut self) -> Self {
+        self.columnar_batches = true;
+        self
+    }
+
+    /// Retry idempotent calls of sessions created from this pool; see
+    /// [`GqlConnection::with_retry_policy`].
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Number of channels in the pool.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.channels.slots.len()
+    }
+
+    /// Perform a handshake on the next channel and return a session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake fails, or if the server is
+    /// unreachable on every channel.
+    pub async fn create_session(&self) -> Result<GqlSession, GqlError> {
+        let mut last_error = None;
+        for _ in 0..self.size() {
+            let index = self.channels.next_index();
+            let channel = self.channels.get(index);
+            match GqlSession::new(
+                channel,
+                self.credentials.clone(),
+                None,
+                self.database.clone(),
+                self.row_batch_checksums,
+                self.dictionary_encoding,
+                self.columnar_batches,
+            )
+            .await
+            {
+                Ok(mut session) => {
+                    session.set_retry_policy(self.retry.clone());
+                    return Ok(session);
+                }
+                Err(err) if is_unavailable(&err) => {
+                    tracing::debug!(index, error = %err, "replacing unreachable pool channel");
+                    self.channels.replace(index);
+                    last_error = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| GqlError::Protocol("pool has no channels".into())))
+    }
+
+    /// A connection over the next channel, for catalog, admin, and search
+    /// clients.
+    #[must_use]
+    pub fn connection(&self) -> GqlConnection {
+        let mut connection =
+            GqlConnection::from_channel(self.channels.get(self.channels.next_index()));
+        if let Some(credentials) = &self.credentials {
+            connection = connection.with_credentials(credentials.clone());
+        }
+        if let Some(database) = &self.database {
+            connection = connection.with_database(database.clone());
+        }
+        if self.row_batch_checksums {
+            connection = connection.with_row_batch_checksums();
+        }
+        if self.dictionary_encoding {
+            connection = connection.with_dictionary_encoding();
+        }
+        if self.columnar_batches {
+            connection = connection.with_columnar_batches();
+        }
+        connection.with_retry_policy(self.retry.clone())
+    }
+
+    /// Probe every channel with a gRPC health check, replacing those
+    /// whose server does not report the GQL service as serving. Returns
+    /// the number of healthy channels.
+    pub async fn health_check(&self) -> usize {
+        self.channels.health_check().await
+    }
+}
+
+impl Channels {
+    fn next_index(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len()
+    }
+
+    fn get(&self, index: usize) -> Channel {
+        self.slots[index]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Swap the channel at `index` for a new one that connects on first
+    /// use.
+    fn replace(&self, index: usize) {
+        let channel = self.endpoint.connect_lazy();
+        *self.slots[index]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = channel;
+    }
+
+    async fn health_check(&self) -> usize {
+        let mut healthy = 0;
+        for index in 0..self.slots.len() {
+            if probe(&self.get(index)).await {
+                healthy += 1;
+            } else {
+                tracing::debug!(index, "replacing unhealthy pool channel");
+                self.replace(index);
+            }
+        }
+        healthy
+    }
+}
+
+async fn check_periodically(channels: Weak<Channels>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let Some(channels) = channels.upgrade() else {
+            return;
+        };
+        channels.health_check().await;
+    }
+}
@@ -0,0 +1,101 @@
+//! Client-side prepared statements.
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::proto::gql_service_client::GqlServiceClient;
+use crate::status;
+
+use super::trace::TracedChannel;
+
+/// A statement prepared with
+/// [`GqlSession::prepare`](super::GqlSession::prepare).
+///
+/// Execute it with
+/// [`GqlSession::execute_prepared`](super::GqlSession::execute_prepared)
+/// or [`Transaction::execute_prepared`](super::Transaction::execute_prepared)
+/// in the session that prepared it; only its ID is sent. It stays
+/// prepared on the server until [`close`](Self::close)d or the session
+/// ends.
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    session_id: String,
+    id: String,
+    statement: String,
+    client: GqlServiceClient<TracedChannel>,
+}
+
+impl PreparedStatement {
+    /// Prepare `statement` (called by `GqlSession`).
+    pub(crate) async fn prepare(
+        session_id: String,
+        mut client: GqlServiceClient<TracedChannel>,
+        statement: &str,
+    ) -> Result<Self, GqlError> {
+        let resp = client
+            .prepare(proto::PrepareRequest {
+                session_id: session_id.clone(),
+                statement: statement.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        if let Some(s) = resp.status {
+            if status::is_exception(&s.code) {
+                return Err(GqlError::Status { status: s });
+            }
+        }
+        if resp.statement_id.is_empty() {
+            return Err(GqlError::Protocol(
+                "server returned empty statement ID".to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            session_id,
+            id: resp.statement_id,
+            statement: statement.to_owned(),
+            client,
+        })
+    }
+
+    /// The ID the server issued for the statement.
+    #[must_use]
+    pub fn statement_id(&self) -> &str {
+        &self.id
+    }
+
+    /// The statement text.
+    #[must_use]
+    pub fn statement(&self) -> &str {
+        &self.statement
+    }
+
+    /// The session the statement was prepared in.
+    pub(crate) fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Release the statement on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement is no longer prepared or the
+    /// server fails to release it.
+    pub async fn close(mut self) -> Result<(), GqlError> {
+        let resp = self
+            .client
+            .close_prepared(proto::ClosePreparedRequest {
+                session_id: self.session_id,
+                statement_id: self.id,
+            })
+            .await?
+            .into_inner();
+
+        if let Some(s) = resp.status {
+            if status::is_exception(&s.code) {
+                return Err(GqlError::Status { status: s });
+            }
+        }
+        Ok(())
+    }
+}
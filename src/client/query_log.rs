@@ -0,0 +1,228 @@
+//! Opt-in client-side query logging.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::types::Value;
+
+/// Value recorded in place of a redacted parameter.
+pub const REDACTED: &str = "<redacted>";
+
+/// A statement executed through a session or transaction with a
+/// [`QueryLog`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryRecord {
+    /// Session the statement ran in.
+    pub session_id: String,
+    /// Transaction the statement ran in, if any.
+    pub transaction_id: Option<String>,
+    /// The statement text.
+    pub statement: String,
+    /// Parameters, with redacted values replaced by [`REDACTED`].
+    pub parameters: HashMap<String, Value>,
+    /// Time from sending the request until the result completed, failed,
+    /// or was dropped.
+    pub duration: Duration,
+    /// GQLSTATUS code of the result summary, if one was received.
+    pub status: Option<String>,
+    /// The error that ended the request, if any.
+    pub error: Option<String>,
+    /// Rows received by the cursor.
+    pub rows: u64,
+    /// Rows affected, as reported by the summary.
+    pub rows_affected: i64,
+}
+
+/// Receives a [`QueryRecord`] for each logged statement.
+///
+/// Implemented for closures taking `&QueryRecord`.
+pub trait QuerySink: Send + Sync + 'static {
+    /// Record a completed statement.
+    fn record(&self, record: &QueryRecord);
+}
+
+impl<F> QuerySink for F
+where
+    F: Fn(&QueryRecord) + Send + Sync + 'static,
+{
+    fn record(&self, record: &QueryRecord) {
+        self(record);
+    }
+}
+
+/// Logs statements to a [`QuerySink`], redacting parameters by name.
+///
+/// Install it with [`GqlSession::set_query_log`](super::GqlSession::set_query_log);
+/// transactions begun from the session use the same log. A statement is
+/// recorded once its result has been fully consumed, has failed, or its
+/// cursor has been dropped.
+#[derive(Clone)]
+pub struct QueryLog {
+    sink: Arc<dyn QuerySink>,
+    redact_all: bool,
+    redacted: HashSet<String>,
+}
+
+impl QueryLog {
+    /// Log to `sink` without redaction.
+    pub fn new(sink: impl QuerySink) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            redact_all: false,
+            redacted: HashSet::new(),
+        }
+    }
+
+    /// Log to `tracing` at debug level under the `gwp::query` target.
+    #[must_use]
+    pub fn to_tracing() -> Self {
+        Self::new(|record: &QueryRecord| {
+            tracing::debug!(
+                target: "gwp::query",
+                session_id = %record.session_id,
+                transaction_id = record.transaction_id.as_deref(),
+                statement = %record.statement,
+                parameters = ?record.parameters,
+                duration_ms = record.duration.as_secs_f64() * 1000.0,
+                status = record.status.as_deref(),
+                error = record.error.as_deref(),
+                rows = record.rows,
+                rows_affected = record.rows_affected,
+                "query"
+            );
+        })
+    }
+
+    /// Redact the parameter called `name`.
+    #[must_use]
+    pub fn redact_parameter(mut self, name: impl Into<String>) -> Self {
+        self.redacted.insert(name.into());
+        self
+    }
+
+    /// Redact every parameter value, keeping only the names.
+    #[must_use]
+    pub fn redact_all_parameters(mut self) -> Self {
+        self.redact_all = true;
+        self
+    }
+
+    /// Start timing a statement.
+    pub(crate) fn start(
+        &self,
+        session_id: &str,
+        transaction_id: Option<&str>,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+    ) -> PendingQuery {
+        let parameters = parameters
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.redact_all || self.redacted.contains(name) {
+                    Value::String(REDACTED.to_owned())
+                } else {
+                    value.clone()
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        PendingQuery {
+            log: self.clone(),
+            started: Instant::now(),
+            record: QueryRecord {
+                session_id: session_id.to_owned(),
+                transaction_id: transaction_id.map(str::to_owned),
+                statement: statement.to_owned(),
+                parameters,
+                duration: Duration::ZERO,
+                status: None,
+                error: None,
+                rows: 0,
+                rows_affected: 0,
+            },
+        }
+    }
+}
+
+impl fmt::Debug for QueryLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryLog")
+            .field("redact_all", &self.redact_all)
+            .field("redacted", &self.redacted)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A statement whose record is emitted when its result ends.
+pub(crate) struct PendingQuery {
+    log: QueryLog,
+    started: Instant,
+    record: QueryRecord,
+}
+
+impl PendingQuery {
+    /// Account for a frame received by the cursor.
+    pub(crate) fn observe(&mut self, frame: Option<&proto::execute_response::Frame>) {
+        match frame {
+            Some(proto::execute_response::Frame::RowBatch(batch)) => {
+                self.record.rows += batch.rows.len() as u64;
+            }
+            Some(proto::execute_response::Frame::Summary(summary)) => {
+                self.record.status = summary.status.as_ref().map(|s| s.code.clone());
+                self.record.rows_affected = summary.rows_affected;
+            }
+            _ => {}
+        }
+    }
+
+    /// Emit the record, noting `error` if the request failed.
+    pub(crate) fn finish(mut self, error: Option<&GqlError>) {
+        self.record.duration = self.started.elapsed();
+        self.record.error = error.map(ToString::to_string);
+        self.log.sink.record(&self.record);
+    }
+}
+
+/// Emit `query` for a request that failed before returning a cursor.
+pub(crate) fn failed(query: Option<PendingQuery>, err: GqlError) -> GqlError {
+    if let Some(query) = query {
+        query.finish(Some(&err));
+    }
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn redacts_named_and_all_parameters() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&records);
+        let log = QueryLog::new(move |r: &QueryRecord| sink.lock().unwrap().push(r.clone()))
+            .redact_parameter("password");
+        let parameters = HashMap::from([
+            ("name".to_owned(), Value::from("alice")),
+            ("password".to_owned(), Value::from("hunter2")),
+        ]);
+
+        log.start("s1", None, "MATCH (n)", &parameters).finish(None);
+        log.redact_all_parameters()
+            .start("s1", Some("t1"), "MATCH (n)", &parameters)
+            .finish(None);
+
+        let records = records.lock().unwrap();
+        assert_eq!(records[0].parameters["name"], Value::from("alice"));
+        assert_eq!(records[0].parameters["password"], Value::from(REDACTED));
+        assert_eq!(records[1].parameters["name"], Value::from(REDACTED));
+        assert_eq!(records[1].transaction_id.as_deref(), Some("t1"));
+    }
+}
@@ -1,21 +1,69 @@
 //! Result cursor for iterating over streaming query results.
 
 use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
 
+use tokio_stream::Stream;
+
+use crate::checksum;
+use crate::columnar;
+use crate::dictionary;
 use crate::error::GqlError;
 use crate::proto;
 use crate::status;
 use crate::types::Value;
 
+use super::encryption::{self, FieldEncryption};
+use super::query_log::PendingQuery;
+use super::row::{Columns, Row};
+use super::spill::{SpillWriter, SpilledRows};
+use super::summary::Summary;
+use super::typed::FromRow;
+use super::warning::ServerWarning;
+
 /// A cursor over the streaming results from a GQL statement.
 ///
 /// Provides access to column metadata, rows, and the final summary.
+/// [`buffered`](Self::buffered) cursors can also [`rewind`](Self::rewind)
+/// and replay the rows they have returned.
+///
+/// Rows are [`Row`]s, readable by column name. The cursor is also a
+/// [`Stream`] of the rows
+/// [`next_row`](Self::next_row) returns, and
+/// [`into_stream`](Self::into_stream) turns it into a stream of rows
+/// converted with [`FromRow`].
 pub struct ResultCursor {
     stream: tonic::Streaming<proto::ExecuteResponse>,
     header: Option<proto::ResultHeader>,
+    /// Column names of the header, shared by the rows returned.
+    columns: Option<Arc<Columns>>,
     summary: Option<proto::ResultSummary>,
+    /// Warnings sent mid-stream, then those of the summary.
+    warnings: Vec<ServerWarning>,
     buffered_rows: VecDeque<Vec<Value>>,
     done: bool,
+    query: Option<PendingQuery>,
+    span: tracing::Span,
+    /// Span of the frame being received, kept across polls.
+    fetch_span: Option<tracing::Span>,
+    require_checksums: bool,
+    encryption: Option<FieldEncryption>,
+    /// A row fetched by `peek_row` but not yet returned.
+    peeked: Option<Vec<Value>>,
+    /// Rows returned by `next_row`, counting replays.
+    position: usize,
+    replay: Option<Replay>,
+}
+
+/// Rows kept by a buffered cursor for replay.
+struct Replay {
+    /// Position at which buffering started.
+    start: usize,
+    rows: Vec<Vec<Value>>,
 }
 
 impl ResultCursor {
@@ -23,12 +71,48 @@ impl ResultCursor {
         Self {
             stream,
             header: None,
+            columns: None,
             summary: None,
+            warnings: Vec::new(),
             buffered_rows: VecDeque::new(),
             done: false,
+            query: None,
+            span: tracing::Span::none(),
+            fetch_span: None,
+            require_checksums: false,
+            encryption: None,
+            peeked: None,
+            position: 0,
+            replay: None,
         }
     }
 
+    /// Reject row batches without a checksum. Batches that carry one are
+    /// always verified.
+    pub(crate) fn with_required_checksums(mut self, required: bool) -> Self {
+        self.require_checksums = required;
+        self
+    }
+
+    /// Decrypt encrypted values in rows.
+    pub(crate) fn with_encryption(mut self, encryption: Option<FieldEncryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Parent fetch spans to the span of the request that opened the
+    /// cursor.
+    pub(crate) fn with_span(mut self, span: tracing::Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Record the result in a query log once it ends.
+    pub(crate) fn with_query(mut self, query: Option<PendingQuery>) -> Self {
+        self.query = query;
+        self
+    }
+
     /// Get the result header (column metadata).
     ///
     /// Consumes frames until the header is found. Returns `None` if
@@ -53,11 +137,30 @@ impl ResultCursor {
     /// Returns a transport error if the gRPC stream fails.
     pub async fn column_names(&mut self) -> Result<Vec<String>, GqlError> {
         self.header().await?;
-        Ok(self
-            .header
+        Ok(self.header_columns())
+    }
+
+    /// Column names of the header received so far.
+    fn header_columns(&self) -> Vec<String> {
+        self.header
             .as_ref()
             .map(|h| h.columns.iter().map(|c| c.name.clone()).collect())
-            .unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    /// Keep every row returned from here on, so the cursor can
+    /// [`rewind`](Self::rewind) and replay them.
+    ///
+    /// Buffered rows stay in memory until the cursor is dropped.
+    #[must_use]
+    pub fn buffered(mut self) -> Self {
+        if self.replay.is_none() {
+            self.replay = Some(Replay {
+                start: self.position,
+                rows: Vec::new(),
+            });
+        }
+        self
     }
 
     /// Get the next row of results.
@@ -67,47 +170,91 @@ impl ResultCursor {
     /// # Errors
     ///
     /// Returns a transport error if the gRPC stream fails.
-    pub async fn next_row(&mut self) -> Result<Option<Vec<Value>>, GqlError> {
-        // Drain buffered rows first
-        if let Some(row) = self.buffered_rows.pop_front() {
-            return Ok(Some(row));
-        }
+    pub async fn next_row(&mut self) -> Result<Option<Row>, GqlError> {
+        poll_fn(|cx| self.poll_row(cx)).await
+    }
 
-        if self.done {
-            return Ok(None);
-        }
+    fn poll_row(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Row>, GqlError>> {
+        let row = ready!(self.poll_values(cx))?;
+        Poll::Ready(Ok(row.map(|values| Row::new(self.columns(), values))))
+    }
 
-        // Fetch more frames
-        loop {
-            if let Some(response) = self.stream.message().await? {
-                match response.frame {
-                    Some(proto::execute_response::Frame::Header(h)) => {
-                        self.header = Some(h);
-                    }
-                    Some(proto::execute_response::Frame::RowBatch(batch)) => {
-                        let mut rows: VecDeque<Vec<Value>> = batch
-                            .rows
-                            .into_iter()
-                            .map(|r| r.values.into_iter().map(Value::from).collect())
-                            .collect();
+    /// The column index shared by rows. The header precedes the first row.
+    fn columns(&mut self) -> Arc<Columns> {
+        if let Some(columns) = &self.columns {
+            return Arc::clone(columns);
+        }
+        let columns = Arc::new(Columns::new(self.header_columns()));
+        self.columns = Some(Arc::clone(&columns));
+        columns
+    }
 
-                        if let Some(first) = rows.pop_front() {
-                            self.buffered_rows = rows;
-                            return Ok(Some(first));
-                        }
-                    }
-                    Some(proto::execute_response::Frame::Summary(s)) => {
-                        self.summary = Some(s);
-                        self.done = true;
-                        return Ok(None);
-                    }
-                    None => {}
-                }
-            } else {
-                self.done = true;
-                return Ok(None);
+    /// Get the values of the next row.
+    fn poll_values(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Vec<Value>>, GqlError>> {
+        if let Some(row) = self.replayed_row() {
+            let row = row.to_vec();
+            self.position += 1;
+            return Poll::Ready(Ok(Some(row)));
+        }
+        let row = match self.peeked.take() {
+            Some(row) => Some(row),
+            None => ready!(self.poll_fetch_row(cx))?,
+        };
+        if let Some(row) = &row {
+            self.position += 1;
+            if let Some(replay) = self.replay.as_mut() {
+                replay.rows.push(row.clone());
             }
         }
+        Poll::Ready(Ok(row))
+    }
+
+    /// Get the row the next call to [`next_row`](Self::next_row) will
+    /// return, without consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the gRPC stream fails.
+    pub async fn peek_row(&mut self) -> Result<Option<&[Value]>, GqlError> {
+        if self.replayed_row().is_some() {
+            return Ok(self.replayed_row());
+        }
+        if self.peeked.is_none() {
+            let row = poll_fn(|cx| self.poll_fetch_row(cx)).await?;
+            self.peeked = row;
+        }
+        Ok(self.peeked.as_deref())
+    }
+
+    /// Number of rows [`next_row`](Self::next_row) has returned, less any
+    /// rewound.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Move back to where buffering started, so that
+    /// [`next_row`](Self::next_row) replays the rows returned since before
+    /// continuing with the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is not [`buffered`](Self::buffered).
+    pub fn rewind(&mut self) {
+        let replay = self
+            .replay
+            .as_ref()
+            .expect("rewind requires a buffered cursor");
+        self.position = replay.start;
+    }
+
+    /// The buffered row at the current position, if replaying.
+    fn replayed_row(&self) -> Option<&[Value]> {
+        let replay = self.replay.as_ref()?;
+        replay
+            .rows
+            .get(self.position - replay.start)
+            .map(Vec::as_slice)
     }
 
     /// Collect all remaining rows into a vector.
@@ -115,7 +262,7 @@ impl ResultCursor {
     /// # Errors
     ///
     /// Returns a transport error if the gRPC stream fails.
-    pub async fn collect_rows(&mut self) -> Result<Vec<Vec<Value>>, GqlError> {
+    pub async fn collect_rows(&mut self) -> Result<Vec<Row>, GqlError> {
         let mut all_rows = Vec::new();
         while let Some(row) = self.next_row().await? {
             all_rows.push(row);
@@ -123,6 +270,102 @@ impl ResultCursor {
         Ok(all_rows)
     }
 
+    /// Turn the cursor into a [`Stream`] of the remaining rows, converted
+    /// with [`FromRow`].
+    ///
+    /// Like [`collect_as`](Self::collect_as), the stream ends with the
+    /// statement's GQLSTATUS if it failed.
+    #[must_use]
+    pub fn into_stream<T: FromRow>(self) -> TypedRows<T> {
+        TypedRows {
+            cursor: self,
+            columns: None,
+            finished: false,
+            row: PhantomData,
+        }
+    }
+
+    /// Collect all remaining rows, converted with [`FromRow`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the gRPC stream fails, the statement's
+    /// GQLSTATUS if it failed, or a protocol error if a row does not
+    /// convert.
+    pub async fn collect_as<T: FromRow>(&mut self) -> Result<Vec<T>, GqlError> {
+        let columns = self.column_names().await?;
+        let mut rows = Vec::new();
+        while let Some(row) = self.next_row().await? {
+            rows.push(T::from_row(&columns, row.into_values())?);
+        }
+        self.summary().await?;
+        match self.failure() {
+            Some(err) => Err(err),
+            None => Ok(rows),
+        }
+    }
+
+    /// Collect all remaining rows, keeping the first `memory_budget` bytes
+    /// of them in memory and writing the rest to a temporary file that is
+    /// read back lazily as the returned rows are iterated.
+    ///
+    /// The budget is measured in the rows' encoded size. Rows are written
+    /// as [`next_row`](Self::next_row) returns them, so values decrypted
+    /// by field-level encryption are spilled in plaintext.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the gRPC stream fails, or an I/O error
+    /// if the temporary file cannot be written.
+    pub async fn collect_spilling(
+        &mut self,
+        memory_budget: usize,
+    ) -> Result<SpilledRows, GqlError> {
+        let mut rows = SpillWriter::new(memory_budget);
+        while let Some(row) = self.next_row().await? {
+            rows.push(row.into_values())?;
+        }
+        Ok(rows.finish()?)
+    }
+
+    /// Collect all remaining rows as Apache Arrow record batches, one per
+    /// row batch the server sent, for analytics engines such as
+    /// `DataFusion` or Polars.
+    ///
+    /// The schema follows the column types in the result header: GQL
+    /// integers, floats, decimals, strings, temporals, lists, and records
+    /// map to their Arrow counterparts, and other values to their text.
+    /// Requires the `arrow` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the gRPC stream fails, the statement's
+    /// GQLSTATUS if it failed, or a protocol error if a value does not
+    /// convert to its column's Arrow type.
+    #[cfg(feature = "arrow")]
+    pub async fn to_arrow(&mut self) -> Result<Vec<arrow_array::RecordBatch>, GqlError> {
+        self.header().await?;
+        let schema = super::arrow::schema(self.header.as_ref());
+        let mut batches = Vec::new();
+        let mut rows = Vec::new();
+        while let Some(row) = self.next_row().await? {
+            rows.push(row.into_values());
+            // The rows of one server batch make one record batch.
+            if self.buffered_rows.is_empty() && self.replayed_row().is_none() {
+                batches.push(super::arrow::record_batch(&schema, &rows)?);
+                rows.clear();
+            }
+        }
+        if !rows.is_empty() {
+            batches.push(super::arrow::record_batch(&schema, &rows)?);
+        }
+        self.summary().await?;
+        match self.failure() {
+            Some(err) => Err(err),
+            None => Ok(batches),
+        }
+    }
+
     /// Get the result summary (available after all rows consumed).
     ///
     /// Consumes remaining frames if needed.
@@ -143,6 +386,35 @@ impl ResultCursor {
         Ok(self.summary.as_ref())
     }
 
+    /// Get what the statement changed and the timings the server reported.
+    ///
+    /// Consumes remaining frames if needed. Counters are zero if the
+    /// stream ended without a summary.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the gRPC stream fails.
+    pub async fn stats(&mut self) -> Result<Summary, GqlError> {
+        let summary = self.summary().await?;
+        Ok(summary.map(Summary::from).unwrap_or_default())
+    }
+
+    /// Warnings received so far: those the server sent as they arose, and
+    /// once the rows are consumed, those in the summary.
+    #[must_use]
+    pub fn warnings(&self) -> &[ServerWarning] {
+        &self.warnings
+    }
+
+    /// The statement's GQLSTATUS as an error, if the summary received so
+    /// far reports an exception.
+    fn failure(&self) -> Option<GqlError> {
+        let status = self.summary.as_ref()?.status.as_ref()?;
+        status::is_exception(&status.code).then(|| GqlError::Status {
+            status: status.clone(),
+        })
+    }
+
     /// Check if the result completed successfully.
     ///
     /// Consumes remaining frames if needed.
@@ -169,10 +441,144 @@ impl ResultCursor {
         Ok(summary.map_or(0, |s| s.rows_affected))
     }
 
+    /// Receive the next row from the stream.
+    fn poll_fetch_row(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Vec<Value>>, GqlError>> {
+        // Drain buffered rows first
+        if let Some(row) = self.buffered_rows.pop_front() {
+            return Poll::Ready(self.decrypt(row).map(Some));
+        }
+
+        if self.done {
+            return Poll::Ready(Ok(None));
+        }
+
+        // Fetch more frames
+        loop {
+            if let Some(response) = ready!(self.poll_message(cx))? {
+                match response.frame {
+                    Some(proto::execute_response::Frame::Header(h)) => {
+                        self.header = Some(h);
+                    }
+                    Some(proto::execute_response::Frame::RowBatch(batch)) => {
+                        let mut rows: VecDeque<Vec<Value>> = batch
+                            .rows
+                            .into_iter()
+                            .map(|r| r.values.into_iter().map(Value::from).collect())
+                            .collect();
+
+                        if let Some(first) = rows.pop_front() {
+                            self.buffered_rows = rows;
+                            return Poll::Ready(self.decrypt(first).map(Some));
+                        }
+                    }
+                    Some(proto::execute_response::Frame::Summary(s)) => {
+                        self.receive_summary(s);
+                        return Poll::Ready(Ok(None));
+                    }
+                    Some(proto::execute_response::Frame::Warning(w)) => {
+                        self.warnings.push(w.into());
+                    }
+                    None => {}
+                }
+            } else {
+                self.done = true;
+                return Poll::Ready(Ok(None));
+            }
+        }
+    }
+
+    /// Keep the summary that ends the stream, and its warnings.
+    fn receive_summary(&mut self, summary: proto::ResultSummary) {
+        self.warnings
+            .extend(summary.warnings.iter().cloned().map(ServerWarning::from));
+        self.summary = Some(summary);
+        self.done = true;
+    }
+
+    /// Decrypt encrypted values in `row`.
+    #[allow(clippy::result_large_err)]
+    fn decrypt(&self, row: Vec<Value>) -> Result<Vec<Value>, GqlError> {
+        match &self.encryption {
+            Some(encryption) => encryption.decrypt_row(row).map_err(encryption::failed),
+            None => Ok(row),
+        }
+    }
+
+    /// Receive the next frame, keeping the query log up to date.
+    async fn message(&mut self) -> Result<Option<proto::ExecuteResponse>, GqlError> {
+        poll_fn(|cx| self.poll_message(cx)).await
+    }
+
+    fn poll_message(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<proto::ExecuteResponse>, GqlError>> {
+        let span = self
+            .fetch_span
+            .get_or_insert_with(|| {
+                tracing::debug_span!(parent: &self.span, "gwp.fetch", rows = tracing::field::Empty)
+            })
+            .clone();
+        let next = ready!(span.in_scope(|| Pin::new(&mut self.stream).poll_next(cx)));
+        self.fetch_span = None;
+        let mut result = next.transpose().map_err(GqlError::from);
+        if let Ok(Some(proto::ExecuteResponse {
+            frame: Some(proto::execute_response::Frame::RowBatch(batch)),
+        })) = &mut result
+        {
+            // Checksums cover the decoded rows.
+            let problem = if !columnar::decode(batch) {
+                Some("columnar row batch malformed")
+            } else if !dictionary::decode(batch) {
+                Some("row batch dictionary reference out of range")
+            } else if checksum::is_corrupt(batch) {
+                Some("row batch checksum mismatch")
+            } else if self.require_checksums && batch.checksum.is_none() {
+                Some("row batch checksum missing")
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                self.done = true;
+                result = Err(GqlError::Protocol(problem.to_owned()));
+            }
+        }
+        if let Ok(Some(proto::ExecuteResponse {
+            frame: Some(proto::execute_response::Frame::RowBatch(batch)),
+        })) = &result
+        {
+            span.record("rows", batch.rows.len());
+        }
+        match (&result, self.query.as_mut()) {
+            (Ok(Some(response)), Some(query)) => {
+                query.observe(response.frame.as_ref());
+                if matches!(
+                    response.frame,
+                    Some(proto::execute_response::Frame::Summary(_))
+                ) {
+                    self.finish_query(None);
+                }
+            }
+            (Ok(None), _) => self.finish_query(None),
+            (Err(err), _) => self.finish_query(Some(err)),
+            (Ok(Some(_)), None) => {}
+        }
+        Poll::Ready(result)
+    }
+
+    fn finish_query(&mut self, error: Option<&GqlError>) {
+        if let Some(query) = self.query.take() {
+            query.finish(error);
+        }
+    }
+
     /// Advance the stream until we find the header.
     async fn advance_to_header(&mut self) -> Result<(), GqlError> {
         while !self.done {
-            if let Some(response) = self.stream.message().await? {
+            if let Some(response) = self.message().await? {
                 match response.frame {
                     Some(proto::execute_response::Frame::Header(h)) => {
                         self.header = Some(h);
@@ -187,10 +593,12 @@ impl ResultCursor {
                         self.buffered_rows.extend(rows);
                     }
                     Some(proto::execute_response::Frame::Summary(s)) => {
-                        self.summary = Some(s);
-                        self.done = true;
+                        self.receive_summary(s);
                         return Ok(());
                     }
+                    Some(proto::execute_response::Frame::Warning(w)) => {
+                        self.warnings.push(w.into());
+                    }
                     None => {}
                 }
             } else {
@@ -201,3 +609,62 @@ impl ResultCursor {
         Ok(())
     }
 }
+
+impl Drop for ResultCursor {
+    fn drop(&mut self) {
+        self.finish_query(None);
+    }
+}
+
+impl Stream for ResultCursor {
+    type Item = Result<Row, GqlError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_row(cx).map(Result::transpose)
+    }
+}
+
+/// A [`Stream`] of result rows converted with [`FromRow`], from
+/// [`ResultCursor::into_stream`].
+pub struct TypedRows<T> {
+    cursor: ResultCursor,
+    columns: Option<Vec<String>>,
+    finished: bool,
+    row: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedRows<T> {
+    /// The cursor the rows come from, for its header and summary.
+    pub fn cursor(&mut self) -> &mut ResultCursor {
+        &mut self.cursor
+    }
+}
+
+impl<T: FromRow> Stream for TypedRows<T> {
+    type Item = Result<T, GqlError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(None);
+        }
+        let item = match ready!(this.cursor.poll_values(cx)) {
+            Ok(Some(row)) => {
+                // The header precedes the first row.
+                let columns = this
+                    .columns
+                    .get_or_insert_with(|| this.cursor.header_columns());
+                Some(T::from_row(columns, row))
+            }
+            Ok(None) => {
+                this.finished = true;
+                this.cursor.failure().map(Err)
+            }
+            Err(err) => {
+                this.finished = true;
+                Some(Err(err))
+            }
+        };
+        Poll::Ready(item)
+    }
+}
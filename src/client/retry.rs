@@ -0,0 +1,244 @@
+//! Retrying calls that fail with transient errors.
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::error::GqlError;
+use crate::status;
+
+/// When and how often to retry calls that fail with a transient gRPC
+/// error, such as `UNAVAILABLE` while a server restarts.
+///
+/// Set on a connection with
+/// [`GqlConnection::with_retry_policy`](super::GqlConnection::with_retry_policy),
+/// it is applied to the idempotent calls of its sessions: pings, session
+/// configuration, and read-only statements run outside a transaction.
+/// Other calls are retried only when asked to, with
+/// [`GqlSession::execute_idempotent`](super::GqlSession::execute_idempotent)
+/// or [`run`](Self::run), and whole transactions with
+/// [`GqlSession::run_in_transaction_with`](super::GqlSession::run_in_transaction_with).
+///
+/// Each retry waits for a backoff that starts at the initial backoff and
+/// doubles up to the maximum, shortened at random by up to the jitter
+/// fraction so clients do not retry in lockstep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: f64,
+    retryable_codes: Vec<tonic::Code>,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Make at most `attempts` attempts, including the first. At least
+    /// one attempt is always made.
+    #[must_use]
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Wait `initial` before the first retry, doubling the wait for each
+    /// later one up to `max`.
+    #[must_use]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Shorten each backoff at random by up to `fraction` of it, from
+    /// `0.0` (no jitter) to `1.0`.
+    #[must_use]
+    pub fn with_jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Retry calls that fail with any of `codes`, instead of only
+    /// `UNAVAILABLE`.
+    #[must_use]
+    pub fn with_retryable_codes(mut self, codes: impl IntoIterator<Item = tonic::Code>) -> Self {
+        self.retryable_codes = codes.into_iter().collect();
+        self
+    }
+
+    /// The most attempts a call gets, including the first.
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether `err` is a transient failure worth retrying.
+    #[must_use]
+    pub fn is_retryable(&self, err: &GqlError) -> bool {
+        matches!(err, GqlError::Grpc(status) if self.retryable_codes.contains(&status.code()))
+    }
+
+    /// Whether a transaction that failed with `err` is worth running
+    /// again from the start: the server rolled it back with a GQLSTATUS
+    /// of class 40, such as a serialization failure, or it failed with a
+    /// retryable gRPC error. Statement timeouts and commits whose outcome
+    /// is unknown are not retried.
+    #[must_use]
+    pub fn is_transaction_retryable(&self, err: &GqlError) -> bool {
+        match err.gql_status() {
+            Some(s) => {
+                status::class(&s.code) == "40"
+                    && s.code != status::COMPLETION_UNKNOWN
+                    && s.code != status::STATEMENT_TIMEOUT
+            }
+            None => self.is_retryable(err),
+        }
+    }
+
+    /// How long to wait before retry number `retry`, counting from 1,
+    /// before jitter.
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Run `call`, calling it again after a backoff while it fails with
+    /// a retryable error and attempts remain.
+    ///
+    /// Only use this for calls that are safe to repeat: a call that
+    /// failed may still have taken effect on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt.
+    pub async fn run<T, F, Fut>(&self, mut call: F) -> Result<T, GqlError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GqlError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Err(err) if attempt < self.max_attempts && self.is_retryable(&err) => {
+                    let delay = self.jittered(self.backoff(attempt));
+                    tracing::debug!(attempt, ?delay, error = %err, "retrying call");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run `call` under this policy if `idempotent` is set, and once
+    /// otherwise.
+    pub(crate) async fn run_if<T, F, Fut>(
+        &self,
+        idempotent: bool,
+        mut call: F,
+    ) -> Result<T, GqlError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GqlError>>,
+    {
+        if idempotent {
+            self.run(call).await
+        } else {
+            call().await
+        }
+    }
+
+    pub(crate) fn jittered(&self, delay: Duration) -> Duration {
+        let mut bytes = [0u8; 4];
+        if self.jitter == 0.0 || getrandom::fill(&mut bytes).is_err() {
+            return delay;
+        }
+        let random = f64::from(u32::from_le_bytes(bytes)) / f64::from(u32::MAX);
+        delay.mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+/// Three attempts, backing off from 50 ms up to 2 s with 50% jitter, on
+/// `UNAVAILABLE`.
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            jitter: 0.5,
+            retryable_codes: vec![tonic::Code::Unavailable],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(350));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn only_listed_codes_are_retried() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable(&tonic::Status::unavailable("down").into()));
+        assert!(!policy.is_retryable(&tonic::Status::not_found("gone").into()));
+        assert!(!policy.is_retryable(&GqlError::Session("closed".to_owned())));
+    }
+
+    #[test]
+    fn rolled_back_transactions_are_retried() {
+        let policy = RetryPolicy::default();
+        let failed = |code| GqlError::status(code, "failed");
+        assert!(policy.is_transaction_retryable(&failed(status::SERIALIZATION_FAILURE)));
+        assert!(policy.is_transaction_retryable(&failed(status::TRANSACTION_ROLLBACK)));
+        assert!(!policy.is_transaction_retryable(&failed(status::COMPLETION_UNKNOWN)));
+        assert!(!policy.is_transaction_retryable(&failed(status::STATEMENT_TIMEOUT)));
+        assert!(!policy.is_transaction_retryable(&failed(status::INVALID_SYNTAX)));
+        assert!(policy.is_transaction_retryable(&tonic::Status::unavailable("down").into()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_until_attempts_run_out() {
+        let policy = RetryPolicy::default().with_max_attempts(3);
+        let mut calls = 0;
+        let result: Result<(), _> = policy
+            .run(|| {
+                calls += 1;
+                async { Err(tonic::Status::unavailable("down").into()) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+
+        calls = 0;
+        let result = RetryPolicy::none()
+            .run(|| {
+                calls += 1;
+                async { Err::<(), _>(tonic::Status::unavailable("down").into()) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! Result rows with access by column name.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::error::GqlError;
+use crate::types::Value;
+
+use super::typed::{FromValue, context};
+
+/// The column names of a result, indexed once and shared by its rows.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Columns {
+    names: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl Columns {
+    pub(super) fn new(names: Vec<String>) -> Self {
+        let mut positions = HashMap::with_capacity(names.len());
+        for (i, name) in names.iter().enumerate() {
+            // A repeated name refers to its first column.
+            positions.entry(name.clone()).or_insert(i);
+        }
+        Self { names, positions }
+    }
+}
+
+/// A row of results, from [`ResultCursor`](super::ResultCursor).
+///
+/// Values are read by column name with [`get`](Self::get), or by position
+/// through the `[Value]` slice the row dereferences to. Rows compare equal
+/// to the `Vec<Value>` of their values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    columns: Arc<Columns>,
+    values: Vec<Value>,
+}
+
+impl Row {
+    pub(super) fn new(columns: Arc<Columns>, values: Vec<Value>) -> Self {
+        Self { columns, values }
+    }
+
+    /// Convert the value of the column called `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such column or its value does not
+    /// convert, naming the column.
+    #[allow(clippy::result_large_err)]
+    pub fn get<T: FromValue>(&self, name: &str) -> Result<T, GqlError> {
+        let value = self
+            .value(name)
+            .ok_or_else(|| GqlError::Protocol(format!("no column `{name}` in result")))?;
+        T::from_value(value.clone()).map_err(|e| context(&format!("column `{name}`"), e))
+    }
+
+    /// The value of the column called `name`, if there is one.
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.columns
+            .positions
+            .get(name)
+            .and_then(|&i| self.values.get(i))
+    }
+
+    /// The column names, in order.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.columns.names
+    }
+
+    /// The values, in column order.
+    #[must_use]
+    pub fn into_values(self) -> Vec<Value> {
+        self.values
+    }
+}
+
+impl Deref for Row {
+    type Target = [Value];
+
+    fn deref(&self) -> &[Value] {
+        &self.values
+    }
+}
+
+impl IntoIterator for Row {
+    type Item = Value;
+    type IntoIter = std::vec::IntoIter<Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl From<Row> for Vec<Value> {
+    fn from(row: Row) -> Self {
+        row.values
+    }
+}
+
+impl PartialEq<Vec<Value>> for Row {
+    fn eq(&self, other: &Vec<Value>) -> bool {
+        self.values == *other
+    }
+}
+
+impl PartialEq<Row> for Vec<Value> {
+    fn eq(&self, other: &Row) -> bool {
+        *self == other.values
+    }
+}
@@ -2,28 +2,44 @@
 
 use std::collections::HashMap;
 
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Channel;
 
 use crate::error::GqlError;
 use crate::proto;
 use crate::proto::search_service_client::SearchServiceClient;
-use crate::server::{HybridSearchParams, SearchHit, TextSearchParams, VectorSearchParams};
-use crate::types::Value;
+use crate::server::{
+    HybridSearchParams, SearchHit, SearchResults, TextSearchParams, VectorSearchParams,
+};
+use crate::types::{Edge, Node, Value};
+
+use super::connection::SessionTag;
 
 /// A client for search operations (vector, text, hybrid) on a GQL server.
 ///
 /// Wraps the raw `SearchServiceClient` gRPC stub with ergonomic
 /// methods that return domain types instead of proto messages.
 pub struct SearchClient {
-    client: SearchServiceClient<Channel>,
+    client: SearchServiceClient<InterceptedService<Channel, SessionTag>>,
 }
 
 impl SearchClient {
     /// Create a new search client from an existing tonic channel.
     #[must_use]
     pub fn new(channel: Channel) -> Self {
+        Self::tagged(channel, SessionTag::default())
+    }
+
+    /// Create a search client whose requests name the session they are made
+    /// for, so the server authorizes them as that session's principal.
+    #[must_use]
+    pub fn for_session(channel: Channel, session_id: &str) -> Self {
+        Self::tagged(channel, SessionTag::new(session_id))
+    }
+
+    fn tagged(channel: Channel, tag: SessionTag) -> Self {
         Self {
-            client: SearchServiceClient::new(channel),
+            client: SearchServiceClient::with_interceptor(channel, tag),
         }
     }
 
@@ -35,7 +51,7 @@ impl SearchClient {
     pub async fn vector_search(
         &mut self,
         params: VectorSearchParams,
-    ) -> Result<Vec<SearchHit>, GqlError> {
+    ) -> Result<SearchResults, GqlError> {
         let filters: HashMap<String, proto::Value> = params
             .filters
             .into_iter()
@@ -52,11 +68,19 @@ impl SearchClient {
                 k: params.k,
                 ef: params.ef,
                 filters,
+                offset: params.offset,
+                page_token: params.page_token,
+                include_elements: params.include_elements,
+                target: proto::SearchTarget::from(params.target).into(),
             })
             .await?
             .into_inner();
 
-        Ok(resp.hits.into_iter().map(into_hit).collect())
+        Ok(into_results(
+            resp.hits,
+            resp.next_page_token,
+            resp.total_hits_estimate,
+        ))
     }
 
     /// Full-text search (BM25 scoring).
@@ -67,7 +91,7 @@ impl SearchClient {
     pub async fn text_search(
         &mut self,
         params: TextSearchParams,
-    ) -> Result<Vec<SearchHit>, GqlError> {
+    ) -> Result<SearchResults, GqlError> {
         let resp = self
             .client
             .text_search(proto::TextSearchRequest {
@@ -76,11 +100,19 @@ impl SearchClient {
                 property: params.property,
                 query: params.query,
                 k: params.k,
+                offset: params.offset,
+                page_token: params.page_token,
+                include_elements: params.include_elements,
+                target: proto::SearchTarget::from(params.target).into(),
             })
             .await?
             .into_inner();
 
-        Ok(resp.hits.into_iter().map(into_hit).collect())
+        Ok(into_results(
+            resp.hits,
+            resp.next_page_token,
+            resp.total_hits_estimate,
+        ))
     }
 
     /// Hybrid search combining text and vector results with rank fusion.
@@ -91,7 +123,7 @@ impl SearchClient {
     pub async fn hybrid_search(
         &mut self,
         params: HybridSearchParams,
-    ) -> Result<Vec<SearchHit>, GqlError> {
+    ) -> Result<SearchResults, GqlError> {
         let resp = self
             .client
             .hybrid_search(proto::HybridSearchRequest {
@@ -102,11 +134,32 @@ impl SearchClient {
                 query_text: params.query_text,
                 query_vector: params.query_vector,
                 k: params.k,
+                offset: params.offset,
+                page_token: params.page_token,
+                include_elements: params.include_elements,
+                target: proto::SearchTarget::from(params.target).into(),
             })
             .await?
             .into_inner();
 
-        Ok(resp.hits.into_iter().map(into_hit).collect())
+        Ok(into_results(
+            resp.hits,
+            resp.next_page_token,
+            resp.total_hits_estimate,
+        ))
+    }
+}
+
+/// Assemble a `SearchResults` page from the fields shared by all search responses.
+fn into_results(
+    hits: Vec<proto::SearchHit>,
+    next_page_token: Option<String>,
+    total_hits_estimate: Option<u64>,
+) -> SearchResults {
+    SearchResults {
+        hits: hits.into_iter().map(into_hit).collect(),
+        next_page_token,
+        total_hits_estimate,
     }
 }
 
@@ -120,5 +173,9 @@ fn into_hit(hit: proto::SearchHit) -> SearchHit {
             .into_iter()
             .map(|(k, v)| (k, Value::from(v)))
             .collect(),
+        node: hit.node.map(Node::from),
+        edge: hit.edge.map(Edge::from),
+        source_node_id: hit.source_node_id,
+        target_node_id: hit.target_node_id,
     }
 }
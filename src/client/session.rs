@@ -1,49 +1,237 @@
 //! Client-side session wrapper.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use tonic::transport::Channel;
 
+use crate::columnar;
 use crate::error::GqlError;
 use crate::proto;
 use crate::proto::gql_service_client::GqlServiceClient;
+use crate::proto::notification_service_client::NotificationServiceClient;
 use crate::proto::session_service_client::SessionServiceClient;
+use crate::server::{QueryPlan, ResultLimits, SessionSnapshot, is_read_only_statement};
+use crate::status;
+use crate::trace_context::TraceContext;
 use crate::types::Value;
 
+use super::batch::BatchCursor;
+use super::bulk::BulkLoader;
+use super::cluster::Topology;
+use super::encryption::{self, FieldEncryption};
+use super::events::ServerEvents;
+use super::notifications::NotificationStream;
+use super::prepared::PreparedStatement;
+use super::query_log::{self, QueryLog};
 use super::result::ResultCursor;
-use super::transaction::Transaction;
+use super::retry::RetryPolicy;
+use super::trace::{self, TraceInterceptor, TracedChannel};
+use super::transaction::{Transaction, TxOptions};
+use super::typed::FromRow;
 
 /// An active session with a GQL server.
 ///
 /// Wraps the handshake response and provides typed methods for
 /// executing statements, managing transactions, and configuring
 /// session state.
+///
+/// Dropping a session that was not closed closes it in the background;
+/// use [`detach`](Self::detach) to leave it open on the server instead.
+#[allow(clippy::struct_excessive_bools)]
 pub struct GqlSession {
     session_id: String,
-    session_client: SessionServiceClient<Channel>,
-    gql_client: GqlServiceClient<Channel>,
+    default_schema: Option<String>,
+    default_graph: Option<String>,
+    session_client: SessionServiceClient<TracedChannel>,
+    gql_client: GqlServiceClient<TracedChannel>,
+    notification_client: NotificationServiceClient<TracedChannel>,
+    trace: TraceInterceptor,
+    query_log: Option<QueryLog>,
+    encryption: Option<FieldEncryption>,
+    resumption_token: Option<String>,
+    resumed: bool,
+    resumed_transaction: Option<String>,
+    row_batch_checksums: bool,
+    dictionary_encoding: bool,
+    columnar_batches: bool,
+    server_features: Vec<String>,
+    routing_table: Option<Topology>,
+    execute_options: ExecuteOptions,
+    retry: RetryPolicy,
+    keepalive: Option<KeepaliveTask>,
+    close_timeout: Option<Duration>,
+    closed: bool,
 }
 
-impl GqlSession {
-    /// Create a new session by performing a handshake.
-    pub(crate) async fn new(channel: Channel) -> Result<Self, GqlError> {
-        let mut session_client = SessionServiceClient::new(channel.clone());
-        let gql_client = GqlServiceClient::new(channel);
+/// Pings the server for a session until dropped.
+#[derive(Debug)]
+struct KeepaliveTask(tokio::task::JoinHandle<()>);
+
+impl Drop for KeepaliveTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Per-statement settings a session and its transactions send with each
+/// execute request.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ExecuteOptions {
+    fetch_size: Option<u32>,
+    timeout: Option<Duration>,
+    compression: Option<proto::Compression>,
+    result_limits: ResultLimits,
+}
+
+impl ExecuteOptions {
+    pub(crate) fn apply(self, request: &mut proto::ExecuteRequest) {
+        request.fetch_size = self.fetch_size;
+        request.timeout_ms = self.timeout_ms();
+        request.compression = self.compression.map(i32::from);
+        request.max_rows = self.result_limits.max_rows;
+        request.max_bytes = self.result_limits.max_bytes;
+    }
+
+    pub(crate) fn apply_prepared(self, request: &mut proto::ExecutePreparedRequest) {
+        request.fetch_size = self.fetch_size;
+        request.timeout_ms = self.timeout_ms();
+        request.compression = self.compression.map(i32::from);
+        request.max_rows = self.result_limits.max_rows;
+        request.max_bytes = self.result_limits.max_bytes;
+    }
 
+    /// `client`, accepting result streams compressed the way these
+    /// options ask for.
+    pub(crate) fn accepting(
+        self,
+        client: GqlServiceClient<TracedChannel>,
+    ) -> GqlServiceClient<TracedChannel> {
+        match self.compression.and_then(proto::Compression::encoding) {
+            Some(encoding) => client.accept_compressed(encoding),
+            None => client,
+        }
+    }
+
+    fn timeout_ms(self) -> Option<u32> {
+        self.timeout.map(|timeout| {
+            u32::try_from(timeout.as_millis())
+                .unwrap_or(u32::MAX)
+                .max(1)
+        })
+    }
+}
+
+impl GqlSession {
+    /// Create a new session by performing a handshake with `credentials`,
+    /// resuming the session `resumption_token` was issued for if the
+    /// server allows it, routed to `database` if set,
+    /// asking for row batch checksums if `row_batch_checksums` is set,
+    /// for dictionary-encoded row batches if `dictionary_encoding` is set,
+    /// and for columnar row batches if `columnar_batches` is set.
+    #[tracing::instrument(name = "gwp.handshake", level = "debug", skip_all, fields(session_id))]
+    #[allow(clippy::fn_params_excessive_bools)]
+    pub(crate) async fn new(
+        channel: Channel,
+        credentials: Option<proto::AuthCredentials>,
+        resumption_token: Option<String>,
+        database: Option<String>,
+        row_batch_checksums: bool,
+        dictionary_encoding: bool,
+        columnar_batches: bool,
+    ) -> Result<Self, GqlError> {
+        let trace = TraceInterceptor::new()?;
+        let mut session_client =
+            SessionServiceClient::with_interceptor(channel.clone(), trace.clone());
         let resp = session_client
             .handshake(proto::HandshakeRequest {
                 protocol_version: 1,
-                credentials: None,
+                credentials,
                 client_info: HashMap::new(),
+                resumption_token,
+                row_batch_checksums,
+                dictionary_encoding,
+                columnar_batches,
+                database,
             })
             .await?
             .into_inner();
+        tracing::Span::current().record("session_id", &resp.session_id);
 
-        Ok(Self {
+        Ok(Self::from_handshake(resp, session_client, channel, trace))
+    }
+
+    /// Reattach to the session `resumption_token` was issued for with the
+    /// `Resume` RPC, keeping its active transaction.
+    #[tracing::instrument(name = "gwp.resume", level = "debug", skip_all, fields(session_id))]
+    #[allow(clippy::fn_params_excessive_bools)]
+    pub(crate) async fn resume(
+        channel: Channel,
+        credentials: Option<proto::AuthCredentials>,
+        resumption_token: String,
+        row_batch_checksums: bool,
+        dictionary_encoding: bool,
+        columnar_batches: bool,
+    ) -> Result<Self, GqlError> {
+        let trace = TraceInterceptor::new()?;
+        let mut session_client =
+            SessionServiceClient::with_interceptor(channel.clone(), trace.clone());
+        let resp = session_client
+            .resume(proto::ResumeRequest {
+                resumption_token,
+                credentials,
+                row_batch_checksums,
+                dictionary_encoding,
+                columnar_batches,
+            })
+            .await?
+            .into_inner();
+        tracing::Span::current().record("session_id", &resp.session_id);
+
+        Ok(Self::from_handshake(resp, session_client, channel, trace))
+    }
+
+    fn from_handshake(
+        resp: proto::HandshakeResponse,
+        session_client: SessionServiceClient<TracedChannel>,
+        channel: Channel,
+        trace: TraceInterceptor,
+    ) -> Self {
+        let server_features = resp
+            .server_info
+            .map(|info| info.features)
+            .unwrap_or_default();
+        let columnar_batches = server_features.iter().any(|f| f == columnar::FEATURE);
+        Self {
             session_id: resp.session_id,
+            default_schema: resp.default_schema,
+            default_graph: resp.default_graph,
             session_client,
-            gql_client,
-        })
+            gql_client: GqlServiceClient::with_interceptor(channel.clone(), trace.clone()),
+            notification_client: NotificationServiceClient::with_interceptor(
+                channel,
+                trace.clone(),
+            ),
+            trace,
+            query_log: None,
+            encryption: None,
+            resumption_token: resp.resumption_token,
+            resumed: resp.resumed,
+            resumed_transaction: resp.transaction_id,
+            row_batch_checksums: resp.row_batch_checksums,
+            dictionary_encoding: resp.dictionary_encoding,
+            columnar_batches,
+            server_features,
+            routing_table: resp.routing_table.map(|table| Topology {
+                writer: table.writer,
+                readers: table.readers,
+            }),
+            execute_options: ExecuteOptions::default(),
+            retry: RetryPolicy::none(),
+            keepalive: None,
+            close_timeout: None,
+            closed: false,
+        }
     }
 
     /// Get the session ID.
@@ -52,8 +240,201 @@ impl GqlSession {
         &self.session_id
     }
 
+    /// Token for resuming this session on a new connection with
+    /// [`GqlConnection::resume_session`](super::GqlConnection::resume_session),
+    /// if the server issued one. Each token can be used once.
+    #[must_use]
+    pub fn resumption_token(&self) -> Option<&str> {
+        self.resumption_token.as_deref()
+    }
+
+    /// Whether the handshake resumed an existing session rather than
+    /// creating a new one.
+    #[must_use]
+    pub fn is_resumed(&self) -> bool {
+        self.resumed
+    }
+
+    /// Take the transaction that was open when this session was resumed
+    /// with [`GqlConnection::resume`](super::GqlConnection::resume), so it
+    /// can be finished. Returns `None` if there was none, or it was
+    /// already taken.
+    ///
+    /// The transaction starts with this session's query log, field
+    /// encryption, fetch size, and statement timeout.
+    pub fn take_resumed_transaction(&mut self) -> Option<Transaction> {
+        let id = self.resumed_transaction.take()?;
+        Some(Transaction::attach(
+            self.session_id.clone(),
+            id,
+            self.gql_client.clone(),
+            self.query_log.clone(),
+            self.encryption.clone(),
+            self.row_batch_checksums,
+            self.execute_options,
+        ))
+    }
+
+    /// Whether the server agreed to checksum row batches, in which case
+    /// cursors reject batches whose checksum is missing or wrong.
+    #[must_use]
+    pub fn row_batch_checksums(&self) -> bool {
+        self.row_batch_checksums
+    }
+
+    /// Whether the server agreed to dictionary-encode row batches.
+    /// Cursors decode them either way.
+    #[must_use]
+    pub fn dictionary_encoding(&self) -> bool {
+        self.dictionary_encoding
+    }
+
+    /// Whether the server agreed to send row batches by column, listing
+    /// the `columnar_batches` feature in its handshake response. Cursors
+    /// turn them back into rows either way.
+    #[must_use]
+    pub fn columnar_batches(&self) -> bool {
+        self.columnar_batches
+    }
+
+    /// The schema this session started in, as reported by the server.
+    ///
+    /// For a resumed session, the schema it was in when resumed; after
+    /// [`set_database`](Self::set_database), the new database's default.
+    #[must_use]
+    pub fn default_schema(&self) -> Option<&str> {
+        self.default_schema.as_deref()
+    }
+
+    /// The graph this session started on, as reported by the server.
+    ///
+    /// For a resumed session, the graph it was on when resumed; after
+    /// [`set_database`](Self::set_database), the new database's default.
+    #[must_use]
+    pub fn default_graph(&self) -> Option<&str> {
+        self.default_graph.as_deref()
+    }
+
+    /// The features the server listed at handshake, such as
+    /// [`ADMIN_FEATURE`](crate::server::ADMIN_FEATURE) and
+    /// [`SEARCH_FEATURE`](crate::server::SEARCH_FEATURE) for the optional
+    /// services it runs.
+    #[must_use]
+    pub fn server_features(&self) -> &[String] {
+        &self.server_features
+    }
+
+    /// The writer and reader endpoints the server advertised at
+    /// handshake, if it has a routing table.
+    #[must_use]
+    pub fn routing_table(&self) -> Option<&Topology> {
+        self.routing_table.as_ref()
+    }
+
+    /// The trace context this session's requests are sent under.
+    ///
+    /// Each request carries a W3C `traceparent` naming a new child span
+    /// of it, so server and backend spans join the same trace. A session
+    /// starts a new trace of its own.
+    #[must_use]
+    pub fn trace_context(&self) -> TraceContext {
+        self.trace.context()
+    }
+
+    /// Send later requests of this session and its transactions as part
+    /// of `context`, such as the application's current span.
+    pub fn set_trace_context(&mut self, context: TraceContext) {
+        self.trace.set_context(context);
+    }
+
+    /// Log statements run in this session and its transactions to `log`,
+    /// or stop logging with `None`. Transactions already begun keep the
+    /// log they started with.
+    pub fn set_query_log(&mut self, log: Option<QueryLog>) {
+        self.query_log = log;
+    }
+
+    /// Encrypt marked parameters and decrypt results with `encryption`,
+    /// or stop with `None`. Transactions already begun keep the settings
+    /// they started with.
+    pub fn set_field_encryption(&mut self, encryption: Option<FieldEncryption>) {
+        self.encryption = encryption;
+    }
+
+    /// Ask the server for row batches of at most `rows` rows, or for
+    /// batches as the backend produces them with `None`. Smaller batches
+    /// bound the memory a cursor holds at once. Transactions already begun
+    /// keep the fetch size they started with.
+    pub fn set_fetch_size(&mut self, rows: Option<u32>) {
+        self.execute_options.fetch_size = rows;
+    }
+
+    /// Ask the server to abort statements that run longer than `timeout`,
+    /// or to apply only its own timeout with `None`. A timed-out
+    /// statement fails with
+    /// [`STATEMENT_TIMEOUT`](crate::status::STATEMENT_TIMEOUT). The server's
+    /// timeout applies if it is shorter. Transactions already begun keep
+    /// the timeout they started with.
+    pub fn set_statement_timeout(&mut self, timeout: Option<Duration>) {
+        self.execute_options.timeout = timeout;
+    }
+
+    /// Ask the server to compress result streams with `compression`, or
+    /// to send them uncompressed with `None`, for large text- or
+    /// byte-heavy results on constrained networks. Results arrive
+    /// uncompressed from servers that have not enabled the encoding, and
+    /// from any server if this build lacks its `gzip` or `zstd` feature.
+    /// Sessions start with their connection's compression; transactions
+    /// already begun keep the compression they started with.
+    pub fn set_compression(&mut self, compression: Option<proto::Compression>) {
+        self.execute_options.compression = compression;
+    }
+
+    /// Accept and send messages of up to `max` bytes.
+    pub(crate) fn set_max_message_size(&mut self, max: usize) {
+        self.session_client = self
+            .session_client
+            .clone()
+            .max_decoding_message_size(max)
+            .max_encoding_message_size(max);
+        self.gql_client = self
+            .gql_client
+            .clone()
+            .max_decoding_message_size(max)
+            .max_encoding_message_size(max);
+        self.notification_client = self
+            .notification_client
+            .clone()
+            .max_decoding_message_size(max)
+            .max_encoding_message_size(max);
+    }
+
+    /// Ask the server to cut off results past `limits`, ending them with
+    /// a [`RESULT_TOO_LARGE`](crate::status::RESULT_TOO_LARGE) summary.
+    /// The server's limits apply where they are lower. Transactions
+    /// already begun keep the limits they started with.
+    pub fn set_result_limits(&mut self, limits: ResultLimits) {
+        self.execute_options.result_limits = limits;
+    }
+
+    /// Retry idempotent calls that fail with a transient error under
+    /// `policy`; see [`RetryPolicy`]. Sessions start with their
+    /// connection's policy.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// The policy idempotent calls are retried under.
+    #[must_use]
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
     /// Execute a GQL statement and return a cursor over the results.
     ///
+    /// Read-only statements are retried under the session's
+    /// [`RetryPolicy`] if the server is transiently unavailable.
+    ///
     /// # Errors
     ///
     /// Returns an error if the server rejects the request.
@@ -62,23 +443,307 @@ impl GqlSession {
         statement: &str,
         parameters: HashMap<String, Value>,
     ) -> Result<ResultCursor, GqlError> {
+        let idempotent = is_read_only_statement(statement);
+        self.execute_with(statement, parameters, idempotent).await
+    }
+
+    /// Execute a statement the caller knows is safe to repeat, retrying
+    /// it under the session's [`RetryPolicy`] even if it writes, such as
+    /// an upsert of fixed values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request.
+    pub async fn execute_idempotent(
+        &mut self,
+        statement: &str,
+        parameters: HashMap<String, Value>,
+    ) -> Result<ResultCursor, GqlError> {
+        self.execute_with(statement, parameters, true).await
+    }
+
+    #[tracing::instrument(
+        name = "gwp.execute",
+        level = "debug",
+        skip_all,
+        fields(session_id = %self.session_id, query_id, statement = trace::statement_field(statement))
+    )]
+    async fn execute_with(
+        &mut self,
+        statement: &str,
+        parameters: HashMap<String, Value>,
+        idempotent: bool,
+    ) -> Result<ResultCursor, GqlError> {
+        let parameters = match &self.encryption {
+            Some(encryption) => encryption
+                .encrypt_parameters(parameters)
+                .map_err(encryption::failed)?,
+            None => parameters,
+        };
+        let query = self
+            .query_log
+            .as_ref()
+            .map(|log| log.start(&self.session_id, None, statement, &parameters));
         let proto_params: HashMap<String, proto::Value> = parameters
             .into_iter()
             .map(|(k, v)| (k, proto::Value::from(v)))
             .collect();
 
-        let stream = self
-            .gql_client
-            .execute(proto::ExecuteRequest {
-                session_id: self.session_id.clone(),
-                statement: statement.to_owned(),
-                parameters: proto_params,
-                transaction_id: None,
+        let query_id = trace::next_query_id(&self.session_id);
+        tracing::Span::current().record("query_id", &query_id);
+        let mut request = proto::ExecuteRequest {
+            session_id: self.session_id.clone(),
+            statement: statement.to_owned(),
+            parameters: proto_params,
+            transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        self.execute_options.apply(&mut request);
+        let response = self
+            .retry
+            .run_if(idempotent, || {
+                let mut client = self.execute_options.accepting(self.gql_client.clone());
+                let request = trace::with_query_id(request.clone(), &query_id);
+                async move { client.execute(request).await.map_err(GqlError::from) }
+            })
+            .await;
+
+        match response {
+            Ok(response) => Ok(ResultCursor::new(response.into_inner())
+                .with_required_checksums(self.row_batch_checksums)
+                .with_encryption(self.encryption.clone())
+                .with_query(query)
+                .with_span(tracing::Span::current())),
+            Err(err) => Err(query_log::failed(query, err)),
+        }
+    }
+
+    /// Prepare a statement for repeated execution with
+    /// [`execute_prepared`](Self::execute_prepared).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the statement.
+    pub async fn prepare(&mut self, statement: &str) -> Result<PreparedStatement, GqlError> {
+        PreparedStatement::prepare(self.session_id.clone(), self.gql_client.clone(), statement)
+            .await
+    }
+
+    /// Execute statements one after another in a single round trip,
+    /// stopping at the first that fails. Each statement commits on its
+    /// own, so those before a failure keep their effects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request.
+    pub async fn execute_batch(
+        &mut self,
+        statements: &[(&str, HashMap<String, Value>)],
+    ) -> Result<BatchCursor, GqlError> {
+        self.batch_with(statements, false).await
+    }
+
+    /// Execute statements as [`execute_batch`](Self::execute_batch) does,
+    /// in one implicit transaction that commits after the last statement
+    /// and rolls back if any fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request.
+    pub async fn execute_batch_atomic(
+        &mut self,
+        statements: &[(&str, HashMap<String, Value>)],
+    ) -> Result<BatchCursor, GqlError> {
+        self.batch_with(statements, true).await
+    }
+
+    #[tracing::instrument(
+        name = "gwp.execute_batch",
+        level = "debug",
+        skip_all,
+        fields(session_id = %self.session_id, statements = statements.len(), atomic)
+    )]
+    async fn batch_with(
+        &mut self,
+        statements: &[(&str, HashMap<String, Value>)],
+        atomic: bool,
+    ) -> Result<BatchCursor, GqlError> {
+        let mut batch = Vec::with_capacity(statements.len());
+        for (statement, parameters) in statements {
+            let parameters = match &self.encryption {
+                Some(encryption) => encryption
+                    .encrypt_parameters(parameters.clone())
+                    .map_err(encryption::failed)?,
+                None => parameters.clone(),
+            };
+            batch.push(proto::BatchStatement {
+                statement: (*statement).to_owned(),
+                parameters: parameters
+                    .into_iter()
+                    .map(|(k, v)| (k, proto::Value::from(v)))
+                    .collect(),
+            });
+        }
+        let request = proto::ExecuteBatchRequest {
+            session_id: self.session_id.clone(),
+            statements: batch,
+            transaction_id: None,
+            atomic,
+        };
+        let response = self.gql_client.execute_batch(request).await?;
+        Ok(BatchCursor::new(response.into_inner())
+            .with_required_checksums(self.row_batch_checksums)
+            .with_encryption(self.encryption.clone()))
+    }
+
+    /// Start loading nodes and edges into the session's current graph,
+    /// streamed to the server in chunks rather than as statements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request.
+    pub async fn bulk_load(&mut self) -> Result<BulkLoader, GqlError> {
+        BulkLoader::start(
+            self.session_id.clone(),
+            self.gql_client.clone(),
+            self.encryption.clone(),
+        )
+        .await
+    }
+
+    /// Ask the server how it would run a statement, without running it.
+    ///
+    /// Explaining never changes data, so it is retried under the session's
+    /// [`RetryPolicy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the statement or its
+    /// backend cannot explain statements.
+    pub async fn explain(
+        &mut self,
+        statement: &str,
+        parameters: HashMap<String, Value>,
+    ) -> Result<QueryPlan, GqlError> {
+        let parameters = match &self.encryption {
+            Some(encryption) => encryption
+                .encrypt_parameters(parameters)
+                .map_err(encryption::failed)?,
+            None => parameters,
+        };
+        let request = proto::ExplainRequest {
+            session_id: self.session_id.clone(),
+            statement: statement.to_owned(),
+            parameters: parameters
+                .into_iter()
+                .map(|(k, v)| (k, proto::Value::from(v)))
+                .collect(),
+        };
+        let resp = self
+            .retry
+            .run(|| {
+                let mut client = self.gql_client.clone();
+                let request = request.clone();
+                async move { client.explain(request).await.map_err(GqlError::from) }
             })
             .await?
             .into_inner();
 
-        Ok(ResultCursor::new(stream))
+        if let Some(s) = resp.status {
+            if status::is_exception(&s.code) {
+                return Err(GqlError::Status { status: s });
+            }
+        }
+        resp.plan
+            .map(QueryPlan::from)
+            .ok_or_else(|| GqlError::Protocol("server returned no plan".to_owned()))
+    }
+
+    /// Execute a statement prepared in this session and return a cursor
+    /// over the results. Only the statement ID is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement was prepared in another session
+    /// or the server rejects the request. A statement no longer prepared
+    /// fails the cursor with
+    /// [`PREPARED_STATEMENT_NOT_FOUND`](crate::status::PREPARED_STATEMENT_NOT_FOUND).
+    #[tracing::instrument(
+        name = "gwp.execute",
+        level = "debug",
+        skip_all,
+        fields(
+            session_id = %self.session_id,
+            query_id,
+            statement = trace::statement_field(prepared.statement()),
+        )
+    )]
+    pub async fn execute_prepared(
+        &mut self,
+        prepared: &PreparedStatement,
+        parameters: HashMap<String, Value>,
+    ) -> Result<ResultCursor, GqlError> {
+        if prepared.session_id() != self.session_id {
+            return Err(GqlError::Session(
+                "statement was prepared in another session".to_owned(),
+            ));
+        }
+        let parameters = match &self.encryption {
+            Some(encryption) => encryption
+                .encrypt_parameters(parameters)
+                .map_err(encryption::failed)?,
+            None => parameters,
+        };
+        let query = self
+            .query_log
+            .as_ref()
+            .map(|log| log.start(&self.session_id, None, prepared.statement(), &parameters));
+        let proto_params: HashMap<String, proto::Value> = parameters
+            .into_iter()
+            .map(|(k, v)| (k, proto::Value::from(v)))
+            .collect();
+
+        let query_id = trace::next_query_id(&self.session_id);
+        tracing::Span::current().record("query_id", &query_id);
+        let mut request = proto::ExecutePreparedRequest {
+            session_id: self.session_id.clone(),
+            statement_id: prepared.statement_id().to_owned(),
+            parameters: proto_params,
+            transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        self.execute_options.apply_prepared(&mut request);
+        let idempotent = is_read_only_statement(prepared.statement());
+        let response = self
+            .retry
+            .run_if(idempotent, || {
+                let mut client = self.execute_options.accepting(self.gql_client.clone());
+                let request = trace::with_query_id(request.clone(), &query_id);
+                async move {
+                    client
+                        .execute_prepared(request)
+                        .await
+                        .map_err(GqlError::from)
+                }
+            })
+            .await;
+
+        match response {
+            Ok(response) => Ok(ResultCursor::new(response.into_inner())
+                .with_required_checksums(self.row_batch_checksums)
+                .with_encryption(self.encryption.clone())
+                .with_query(query)
+                .with_span(tracing::Span::current())),
+            Err(err) => Err(query_log::failed(query, err)),
+        }
     }
 
     /// Execute a GQL statement with no parameters.
@@ -92,18 +757,33 @@ impl GqlSession {
         self.execute(statement, HashMap::new()).await
     }
 
+    /// Execute a GQL statement and convert every result row with
+    /// [`FromRow`].
+    ///
+    /// Parameters are usually built with [`params!`](crate::params).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request, the statement
+    /// fails, or a row does not convert.
+    pub async fn execute_typed<T: FromRow>(
+        &mut self,
+        statement: &str,
+        parameters: impl Into<HashMap<String, Value>>,
+    ) -> Result<Vec<T>, GqlError> {
+        self.execute(statement, parameters.into())
+            .await?
+            .collect_as()
+            .await
+    }
+
     /// Begin an explicit transaction.
     ///
     /// # Errors
     ///
     /// Returns an error if the transaction cannot be started.
     pub async fn begin_transaction(&mut self) -> Result<Transaction, GqlError> {
-        Transaction::begin(
-            self.session_id.clone(),
-            self.gql_client.clone(),
-            proto::TransactionMode::ReadWrite,
-        )
-        .await
+        self.begin_transaction_with(TxOptions::new()).await
     }
 
     /// Begin a read-only transaction.
@@ -112,27 +792,103 @@ impl GqlSession {
     ///
     /// Returns an error if the transaction cannot be started.
     pub async fn begin_read_only_transaction(&mut self) -> Result<Transaction, GqlError> {
+        self.begin_transaction_with(TxOptions::new().read_only())
+            .await
+    }
+
+    /// Begin a transaction with the given characteristics, such as an
+    /// isolation level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started, including
+    /// when the server does not support the requested isolation level.
+    pub async fn begin_transaction_with(
+        &mut self,
+        options: TxOptions,
+    ) -> Result<Transaction, GqlError> {
         Transaction::begin(
             self.session_id.clone(),
             self.gql_client.clone(),
-            proto::TransactionMode::ReadOnly,
+            options,
+            self.query_log.clone(),
+            self.encryption.clone(),
+            self.row_batch_checksums,
+            self.execute_options,
         )
         .await
     }
 
+    /// Run `work` in a transaction and commit it, running it again in a
+    /// fresh transaction if the server rolls it back with a conflict such
+    /// as a serialization failure. Retries follow
+    /// [`RetryPolicy::default`]; see
+    /// [`run_in_transaction_with`](Self::run_in_transaction_with).
+    ///
+    /// `work` may run several times, so it should not have effects
+    /// outside the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt, from `work` or the commit.
+    pub async fn run_in_transaction<T>(
+        &mut self,
+        work: impl AsyncFnMut(&mut Transaction) -> Result<T, GqlError>,
+    ) -> Result<T, GqlError> {
+        self.run_in_transaction_with(TxOptions::new(), &RetryPolicy::default(), work)
+            .await
+    }
+
+    /// Run `work` in a transaction begun with `options` and commit it,
+    /// running it again with the attempts and backoff of `policy` while
+    /// it fails in a way
+    /// [`is_transaction_retryable`](RetryPolicy::is_transaction_retryable).
+    /// A failed attempt is rolled back before the next one begins.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt, from `work` or the commit.
+    pub async fn run_in_transaction_with<T>(
+        &mut self,
+        options: TxOptions,
+        policy: &RetryPolicy,
+        mut work: impl AsyncFnMut(&mut Transaction) -> Result<T, GqlError>,
+    ) -> Result<T, GqlError> {
+        let mut attempt = 1;
+        loop {
+            let err = match self.begin_transaction_with(options).await {
+                Ok(mut tx) => match work(&mut tx).await {
+                    Ok(value) => match tx.commit().await {
+                        Ok(()) => return Ok(value),
+                        Err(err) => err,
+                    },
+                    Err(err) => {
+                        // Free the session for the next attempt; the server
+                        // may already have rolled the transaction back.
+                        let _ = tx.rollback().await;
+                        err
+                    }
+                },
+                Err(err) => err,
+            };
+            if attempt >= policy.max_attempts() || !policy.is_transaction_retryable(&err) {
+                return Err(err);
+            }
+            let delay = policy.jittered(policy.backoff(attempt));
+            tracing::debug!(attempt, ?delay, error = %err, "retrying transaction");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
     /// Set the current graph for this session.
     ///
     /// # Errors
     ///
     /// Returns an error if the server rejects the configuration.
     pub async fn set_graph(&mut self, graph: &str) -> Result<(), GqlError> {
-        self.session_client
-            .configure(proto::ConfigureRequest {
-                session_id: self.session_id.clone(),
-                property: Some(proto::configure_request::Property::Graph(graph.to_owned())),
-            })
-            .await?;
-        Ok(())
+        self.configure(proto::configure_request::Property::Graph(graph.to_owned()))
+            .await
     }
 
     /// Set the current schema for this session.
@@ -141,32 +897,90 @@ impl GqlSession {
     ///
     /// Returns an error if the server rejects the configuration.
     pub async fn set_schema(&mut self, schema: &str) -> Result<(), GqlError> {
-        self.session_client
-            .configure(proto::ConfigureRequest {
-                session_id: self.session_id.clone(),
-                property: Some(proto::configure_request::Property::Schema(
-                    schema.to_owned(),
-                )),
-            })
+        self.configure(proto::configure_request::Property::Schema(
+            schema.to_owned(),
+        ))
+        .await
+    }
+
+    /// Route this session's statements to another database on the
+    /// server, as `USE database` does. The session moves to that
+    /// database's default schema and graph. Resetting the session keeps
+    /// the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the configuration, such as
+    /// for an unknown database.
+    pub async fn set_database(&mut self, database: &str) -> Result<(), GqlError> {
+        let resp = self
+            .configure_with_response(proto::configure_request::Property::Database(
+                database.to_owned(),
+            ))
             .await?;
+        self.default_schema = resp.default_schema;
+        self.default_graph = resp.default_graph;
         Ok(())
     }
 
+    /// Set a session parameter, passed with every later statement that
+    /// does not set a parameter of the same name itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the configuration.
+    pub async fn set_parameter(
+        &mut self,
+        name: &str,
+        value: impl Into<Value>,
+    ) -> Result<(), GqlError> {
+        self.configure(proto::configure_request::Property::Parameter(
+            proto::SessionParameter {
+                name: name.to_owned(),
+                value: Some(value.into().into()),
+            },
+        ))
+        .await
+    }
+
     /// Set the timezone offset for this session.
     ///
     /// # Errors
     ///
     /// Returns an error if the server rejects the configuration.
     pub async fn set_time_zone(&mut self, offset_minutes: i32) -> Result<(), GqlError> {
-        self.session_client
-            .configure(proto::ConfigureRequest {
-                session_id: self.session_id.clone(),
-                property: Some(proto::configure_request::Property::TimeZoneOffsetMinutes(
-                    offset_minutes,
-                )),
+        self.configure(proto::configure_request::Property::TimeZoneOffsetMinutes(
+            offset_minutes,
+        ))
+        .await
+    }
+
+    /// Set a session property, retrying under the session's policy.
+    async fn configure(
+        &mut self,
+        property: proto::configure_request::Property,
+    ) -> Result<(), GqlError> {
+        self.configure_with_response(property).await?;
+        Ok(())
+    }
+
+    async fn configure_with_response(
+        &mut self,
+        property: proto::configure_request::Property,
+    ) -> Result<proto::ConfigureResponse, GqlError> {
+        let request = proto::ConfigureRequest {
+            session_id: self.session_id.clone(),
+            property: Some(property),
+        };
+        let resp = self
+            .retry
+            .run(|| {
+                let mut client = self.session_client.clone();
+                let request = request.clone();
+                async move { client.configure(request).await.map_err(GqlError::from) }
             })
             .await?;
-        Ok(())
+        Ok(resp.into_inner())
     }
 
     /// Reset all session state to defaults.
@@ -175,43 +989,281 @@ impl GqlSession {
     ///
     /// Returns an error if the server rejects the request.
     pub async fn reset(&mut self) -> Result<(), GqlError> {
+        let request = proto::ResetRequest {
+            session_id: self.session_id.clone(),
+            target: proto::ResetTarget::ResetAll.into(),
+        };
+        self.retry
+            .run(|| {
+                let mut client = self.session_client.clone();
+                let request = request.clone();
+                async move { client.reset(request).await.map_err(GqlError::from) }
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Commit a transaction prepared with
+    /// [`Transaction::prepare_commit`], possibly from another session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction is not prepared or the commit
+    /// fails. A failed commit leaves the transaction prepared, so it can
+    /// be retried.
+    pub async fn commit_prepared(&mut self, transaction_id: &str) -> Result<(), GqlError> {
+        let resp = self
+            .gql_client
+            .commit_prepared(proto::CommitPreparedRequest {
+                session_id: self.session_id.clone(),
+                transaction_id: transaction_id.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        if let Some(s) = resp.status {
+            if status::is_exception(&s.code) {
+                return Err(GqlError::Status { status: s });
+            }
+        }
+        Ok(())
+    }
+
+    /// Roll back a transaction prepared with
+    /// [`Transaction::prepare_commit`], possibly from another session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction is not prepared or the rollback
+    /// fails.
+    pub async fn rollback_prepared(&mut self, transaction_id: &str) -> Result<(), GqlError> {
+        let resp = self
+            .gql_client
+            .rollback_prepared(proto::RollbackPreparedRequest {
+                session_id: self.session_id.clone(),
+                transaction_id: transaction_id.to_owned(),
+            })
+            .await?
+            .into_inner();
+
+        if let Some(s) = resp.status {
+            if status::is_exception(&s.code) {
+                return Err(GqlError::Status { status: s });
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the credentials this session authenticated with, such as a
+    /// bearer token about to expire, keeping the session and any open
+    /// transaction.
+    ///
+    /// Later sessions from the same connection still use the
+    /// connection's credentials; see
+    /// [`GqlConnection::with_credentials`](super::GqlConnection::with_credentials).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the credentials, or if they
+    /// authenticate a different principal than the session's.
+    #[tracing::instrument(name = "gwp.refresh_auth", level = "debug", skip_all, fields(session_id = %self.session_id))]
+    pub async fn refresh_auth(
+        &mut self,
+        credentials: proto::AuthCredentials,
+    ) -> Result<(), GqlError> {
         self.session_client
-            .reset(proto::ResetRequest {
+            .refresh_credentials(proto::RefreshCredentialsRequest {
                 session_id: self.session_id.clone(),
-                target: proto::ResetTarget::ResetAll.into(),
+                credentials: Some(credentials),
             })
             .await?;
         Ok(())
     }
 
+    /// Read back the session's current schema, graph, timezone,
+    /// parameters, database, and active transaction, as the server sees
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session no longer exists on the server.
+    pub async fn current_state(&mut self) -> Result<SessionSnapshot, GqlError> {
+        let request = proto::GetSessionStateRequest {
+            session_id: self.session_id.clone(),
+        };
+        let resp = self
+            .retry
+            .run(|| {
+                let mut client = self.session_client.clone();
+                let request = request.clone();
+                async move {
+                    client
+                        .get_session_state(request)
+                        .await
+                        .map_err(GqlError::from)
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(resp.into())
+    }
+
     /// Ping the server to check connectivity.
     ///
     /// # Errors
     ///
     /// Returns an error if the server is unreachable.
     pub async fn ping(&mut self) -> Result<i64, GqlError> {
+        let request = proto::PingRequest {
+            session_id: self.session_id.clone(),
+        };
         let resp = self
+            .retry
+            .run(|| {
+                let mut client = self.session_client.clone();
+                let request = request.clone();
+                async move { client.ping(request).await.map_err(GqlError::from) }
+            })
+            .await?
+            .into_inner();
+
+        Ok(resp.timestamp)
+    }
+
+    /// Ping the server every `interval` from a background task, so the
+    /// session is not reaped as idle during long gaps between requests,
+    /// or stop pinging with `None`. Pinging stops when the session is
+    /// closed, detached, or dropped, or once the server no longer knows
+    /// the session.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an interval outside a Tokio runtime.
+    pub fn set_keepalive(&mut self, interval: Option<Duration>) {
+        self.keepalive = interval.map(|interval| {
+            let mut client = self.session_client.clone();
+            let session_id = self.session_id.clone();
+            KeepaliveTask(tokio::spawn(async move {
+                let mut ticks =
+                    tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    let request = proto::PingRequest {
+                        session_id: session_id.clone(),
+                    };
+                    match client.ping(request).await {
+                        Err(status) if status.code() == tonic::Code::NotFound => break,
+                        Err(status) => tracing::debug!(%status, "keepalive ping failed"),
+                        Ok(_) => {}
+                    }
+                }
+            }))
+        });
+    }
+
+    /// Give up closing the session after `timeout`, whether closed with
+    /// [`close`](Self::close) or by dropping it, or wait as long as the
+    /// server takes with `None` (the default). A server that does not
+    /// answer in time reaps the session once it is idle.
+    pub fn set_close_timeout(&mut self, timeout: Option<Duration>) {
+        self.close_timeout = timeout;
+    }
+
+    /// Subscribe to notifications the server sends this session, such as
+    /// a [`DrainNotice`](proto::DrainNotice) announcing that it will
+    /// close the session once a grace period ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist.
+    pub async fn server_events(&mut self) -> Result<ServerEvents, GqlError> {
+        let stream = self
             .session_client
-            .ping(proto::PingRequest {
+            .server_events(proto::ServerEventsRequest {
                 session_id: self.session_id.clone(),
             })
             .await?
             .into_inner();
+        Ok(ServerEvents::new(stream))
+    }
 
-        Ok(resp.timestamp)
+    /// Open a stream of the change events the backend publishes, then
+    /// [`subscribe`](NotificationStream::subscribe) it to topics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist.
+    pub async fn notifications(&mut self) -> Result<NotificationStream, GqlError> {
+        NotificationStream::open(self.session_id.clone(), self.notification_client.clone()).await
     }
 
     /// Close this session.
     ///
     /// # Errors
     ///
-    /// Returns an error if the server rejects the request.
+    /// Returns an error if the server rejects the request, or
+    /// `DEADLINE_EXCEEDED` if it does not answer within the
+    /// [close timeout](Self::set_close_timeout).
     pub async fn close(mut self) -> Result<(), GqlError> {
-        self.session_client
-            .close(proto::CloseRequest {
-                session_id: self.session_id.clone(),
-            })
-            .await?;
-        Ok(())
+        self.closed = true;
+        self.keepalive = None;
+        close_session(
+            self.session_client.clone(),
+            self.session_id.clone(),
+            self.close_timeout,
+        )
+        .await
+    }
+
+    /// Drop this handle without closing the session on the server.
+    ///
+    /// The session stays open until the server reaps it as idle, or it is
+    /// resumed with its [`resumption_token`](Self::resumption_token).
+    pub fn detach(mut self) {
+        self.closed = true;
+    }
+}
+
+impl Drop for GqlSession {
+    fn drop(&mut self) {
+        if !self.closed {
+            // Fire-and-forget close on drop, if a runtime is still around
+            // to run it.
+            let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                return;
+            };
+            let close = close_session(
+                self.session_client.clone(),
+                self.session_id.clone(),
+                self.close_timeout,
+            );
+            runtime.spawn(async move {
+                let _ = close.await;
+            });
+        }
+    }
+}
+
+/// Ask the server to close `session_id`, giving up after `timeout`.
+async fn close_session(
+    mut client: SessionServiceClient<TracedChannel>,
+    session_id: String,
+    timeout: Option<Duration>,
+) -> Result<(), GqlError> {
+    let request = client.close(proto::CloseRequest { session_id });
+    match timeout {
+        Some(timeout) => {
+            tokio::time::timeout(timeout, request).await.map_err(|_| {
+                GqlError::Grpc(tonic::Status::deadline_exceeded(format!(
+                    "session close timed out after {timeout:?}"
+                )))
+            })??;
+        }
+        None => {
+            request.await?;
+        }
     }
+    Ok(())
 }
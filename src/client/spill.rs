@@ -0,0 +1,231 @@
+//! Spilling oversized results to disk.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use prost::Message;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::types::Value;
+
+/// Rows collected by
+/// [`ResultCursor::collect_spilling`](super::ResultCursor::collect_spilling).
+///
+/// The first rows are held in memory; rows beyond the memory budget are
+/// read back lazily from a temporary file, which is deleted when this is
+/// dropped. Iterating yields the rows in result order.
+pub struct SpilledRows {
+    memory: std::vec::IntoIter<Vec<Value>>,
+    spill: Option<SpillFile>,
+    len: usize,
+}
+
+impl SpilledRows {
+    /// Total number of rows not yet iterated.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether all rows have been iterated.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether any rows were written to disk.
+    #[must_use]
+    pub fn is_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+}
+
+impl Iterator for SpilledRows {
+    type Item = Result<Vec<Value>, GqlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = match self.memory.next() {
+            Some(row) => Ok(row),
+            None => match self.spill.as_mut()?.read_row() {
+                Ok(Some(row)) => Ok(row),
+                Ok(None) => return None,
+                Err(err) => {
+                    // The file is unreadable from here on.
+                    self.len = 0;
+                    self.spill = None;
+                    Err(err.into())
+                }
+            },
+        };
+        self.len = self.len.saturating_sub(1);
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+/// Buffers rows in memory up to a budget, then appends them to a
+/// temporary file.
+pub(crate) struct SpillWriter {
+    memory: Vec<Vec<Value>>,
+    budget: usize,
+    used: usize,
+    spill: Option<(PathBuf, BufWriter<File>)>,
+    len: usize,
+}
+
+impl SpillWriter {
+    /// Keep up to `budget` bytes of rows, in their encoded size, in
+    /// memory.
+    pub(crate) fn new(budget: usize) -> Self {
+        Self {
+            memory: Vec::new(),
+            budget,
+            used: 0,
+            spill: None,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, row: Vec<Value>) -> io::Result<()> {
+        self.len += 1;
+        let encoded = encode(&row);
+        if self.spill.is_none() {
+            let size = encoded.encoded_len();
+            if self.used + size <= self.budget {
+                self.used += size;
+                self.memory.push(row);
+                return Ok(());
+            }
+        }
+        let spill = match self.spill.take() {
+            Some(spill) => spill,
+            None => create_spill_file()?,
+        };
+        let (_, writer) = self.spill.insert(spill);
+        let bytes = encoded.encode_to_vec();
+        let len = u32::try_from(bytes.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "row too large to spill"))?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(&bytes)
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<SpilledRows> {
+        let spill = match self.spill.take() {
+            Some((path, writer)) => {
+                // The guard removes the file even if reopening it fails.
+                let mut file = SpillFile { reader: None, path };
+                writer
+                    .into_inner()
+                    .map_err(io::IntoInnerError::into_error)?;
+                file.reader = Some(BufReader::new(File::open(&file.path)?));
+                Some(file)
+            }
+            None => None,
+        };
+        Ok(SpilledRows {
+            memory: std::mem::take(&mut self.memory).into_iter(),
+            spill,
+            len: self.len,
+        })
+    }
+}
+
+impl Drop for SpillWriter {
+    fn drop(&mut self) {
+        if let Some((path, _)) = &self.spill {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// A spill file being read back, removed on drop.
+struct SpillFile {
+    reader: Option<BufReader<File>>,
+    path: PathBuf,
+}
+
+impl SpillFile {
+    fn read_row(&mut self) -> io::Result<Option<Vec<Value>>> {
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(None);
+        };
+        let mut len = [0u8; 4];
+        match reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+        reader.read_exact(&mut bytes)?;
+        let row = proto::Row::decode(bytes.as_slice())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Some(row.values.into_iter().map(Value::from).collect()))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        self.reader = None;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn encode(row: &[Value]) -> proto::Row {
+    proto::Row {
+        values: row.iter().cloned().map(proto::Value::from).collect(),
+    }
+}
+
+fn create_spill_file() -> io::Result<(PathBuf, BufWriter<File>)> {
+    let mut suffix = [0u8; 8];
+    getrandom::fill(&mut suffix).map_err(|e| io::Error::other(e.to_string()))?;
+    let path = std::env::temp_dir().join(format!(
+        "gwp-spill-{}-{:016x}",
+        std::process::id(),
+        u64::from_le_bytes(suffix)
+    ));
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok((path, BufWriter::new(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spills_rows_beyond_the_budget_and_cleans_up() {
+        let rows: Vec<Vec<Value>> = (0..100)
+            .map(|i| vec![Value::Integer(i), Value::from(format!("row {i}"))])
+            .collect();
+        let mut writer = SpillWriter::new(encode(&rows[0]).encoded_len() * 10);
+        for row in rows.clone() {
+            writer.push(row).unwrap();
+        }
+        let spilled = writer.finish().unwrap();
+        assert!(spilled.is_spilled());
+        assert_eq!(spilled.len(), 100);
+        let path = spilled.spill.as_ref().unwrap().path.clone();
+        assert!(path.exists());
+
+        let collected: Vec<Vec<Value>> = spilled.map(Result::unwrap).collect();
+        assert_eq!(collected, rows);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn small_results_stay_in_memory() {
+        let mut writer = SpillWriter::new(1024);
+        writer.push(vec![Value::Integer(1)]).unwrap();
+        let spilled = writer.finish().unwrap();
+        assert!(!spilled.is_spilled());
+        assert_eq!(spilled.collect::<Result<Vec<_>, _>>().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! Typed statistics from a result summary.
+
+use std::time::Duration;
+
+use crate::proto;
+
+/// What a statement changed and how long it took, from
+/// [`ResultCursor::stats`](super::ResultCursor::stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Rows affected, as reported by the summary.
+    pub rows_affected: i64,
+    /// Nodes created.
+    pub nodes_created: i64,
+    /// Nodes deleted.
+    pub nodes_deleted: i64,
+    /// Edges created.
+    pub edges_created: i64,
+    /// Edges deleted.
+    pub edges_deleted: i64,
+    /// Properties set.
+    pub properties_set: i64,
+    /// Labels added.
+    pub labels_added: i64,
+    /// Time until the backend opened the result stream, if the server
+    /// reported timings.
+    pub planning: Option<Duration>,
+    /// Time from then until the first result frame.
+    pub execution: Option<Duration>,
+    /// Time from the first result frame to the summary.
+    pub streaming: Option<Duration>,
+}
+
+impl From<&proto::ResultSummary> for Summary {
+    /// Counters come from the typed `update_counters`, or from the
+    /// `counters` map of the same names if the server sent none.
+    fn from(summary: &proto::ResultSummary) -> Self {
+        let counters = summary.update_counters.unwrap_or_else(|| {
+            let counter = |name: &str| summary.counters.get(name).copied().unwrap_or(0);
+            proto::UpdateCounters {
+                nodes_created: counter("nodes_created"),
+                nodes_deleted: counter("nodes_deleted"),
+                edges_created: counter("edges_created"),
+                edges_deleted: counter("edges_deleted"),
+                properties_set: counter("properties_set"),
+                labels_added: counter("labels_added"),
+            }
+        });
+        let timing = |micros: fn(&proto::QueryTimings) -> u64| {
+            summary
+                .timings
+                .as_ref()
+                .map(|t| Duration::from_micros(micros(t)))
+        };
+        Self {
+            rows_affected: summary.rows_affected,
+            nodes_created: counters.nodes_created,
+            nodes_deleted: counters.nodes_deleted,
+            edges_created: counters.edges_created,
+            edges_deleted: counters.edges_deleted,
+            properties_set: counters.properties_set,
+            labels_added: counters.labels_added,
+            planning: timing(|t| t.planning_micros),
+            execution: timing(|t| t.execution_micros),
+            streaming: timing(|t| t.streaming_micros),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn counters_fall_back_to_the_map() {
+        let summary = proto::ResultSummary {
+            rows_affected: 2,
+            counters: HashMap::from([
+                ("nodes_created".to_owned(), 2),
+                ("labels_added".to_owned(), 1),
+            ]),
+            ..Default::default()
+        };
+        let stats = Summary::from(&summary);
+        assert_eq!(stats.rows_affected, 2);
+        assert_eq!((stats.nodes_created, stats.labels_added), (2, 1));
+        assert_eq!(stats.edges_created, 0);
+        assert_eq!(stats.planning, None);
+    }
+
+    #[test]
+    fn typed_counters_and_timings() {
+        let summary = proto::ResultSummary {
+            counters: HashMap::from([("nodes_created".to_owned(), 9)]),
+            update_counters: Some(proto::UpdateCounters {
+                nodes_created: 1,
+                properties_set: 3,
+                ..Default::default()
+            }),
+            timings: Some(proto::QueryTimings {
+                planning_micros: 1_500,
+                execution_micros: 20,
+                streaming_micros: 0,
+            }),
+            ..Default::default()
+        };
+        let stats = Summary::from(&summary);
+        assert_eq!((stats.nodes_created, stats.properties_set), (1, 3));
+        assert_eq!(stats.planning, Some(Duration::from_micros(1_500)));
+        assert_eq!(stats.execution, Some(Duration::from_micros(20)));
+        assert_eq!(stats.streaming, Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,127 @@
+//! Tabular view of a binding table result.
+
+use std::fmt;
+
+use crate::error::GqlError;
+use crate::types::Value;
+
+use super::{ResultCursor, Row};
+
+/// A fully collected binding table: column names and rows.
+///
+/// Its `Display` implementation renders an aligned text table, suitable
+/// for terminals and logs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BindingTable {
+    /// Column names, in order.
+    pub columns: Vec<String>,
+    /// Rows, each with one value per column.
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl BindingTable {
+    /// Create a table from column names and rows.
+    #[must_use]
+    pub fn new(columns: Vec<String>, rows: Vec<Vec<Value>>) -> Self {
+        Self { columns, rows }
+    }
+
+    /// Consume the remaining rows of a cursor into a table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result stream fails.
+    pub async fn from_cursor(cursor: &mut ResultCursor) -> Result<Self, GqlError> {
+        let columns = cursor.column_names().await?;
+        let rows = cursor
+            .collect_rows()
+            .await?
+            .into_iter()
+            .map(Row::into_values)
+            .collect();
+        Ok(Self { columns, rows })
+    }
+
+    /// Number of rows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the table has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl fmt::Display for BindingTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(ToString::to_string).collect())
+            .collect();
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.chars().count()).collect();
+        for row in &cells {
+            for (i, cell) in row.iter().enumerate() {
+                let width = cell.chars().count();
+                match widths.get_mut(i) {
+                    Some(w) => *w = (*w).max(width),
+                    None => widths.push(width),
+                }
+            }
+        }
+
+        let write_row = |f: &mut fmt::Formatter<'_>, values: &[String]| {
+            let line: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(i, w)| format!("{:<w$}", values.get(i).map_or("", String::as_str)))
+                .collect();
+            writeln!(f, " {} ", line.join(" | ").trim_end())
+        };
+
+        write_row(f, &self.columns)?;
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        writeln!(f, "-{}-", rule.join("-+-"))?;
+        for row in &cells {
+            write_row(f, row)?;
+        }
+        let n = self.rows.len();
+        write!(f, "({n} {})", if n == 1 { "row" } else { "rows" })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_aligned_table() {
+        let table = BindingTable::new(
+            vec!["name".to_owned(), "age".to_owned()],
+            vec![
+                vec![Value::from("Alice"), Value::from(30i64)],
+                vec![Value::from("Bob"), Value::Null],
+            ],
+        );
+        let rendered = table.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with(" name"));
+        assert!(lines[1].contains("-+-"));
+        assert_eq!(lines[4], "(2 rows)");
+        // Every row has its separator at the same column.
+        let bar = lines[0].find('|').unwrap();
+        assert_eq!(lines[2].find('|'), Some(bar));
+        assert_eq!(lines[3].find('|'), Some(bar));
+    }
+
+    #[test]
+    fn renders_empty_table() {
+        let table = BindingTable::new(vec!["x".to_owned()], Vec::new());
+        assert!(table.is_empty());
+        assert!(table.to_string().ends_with("(0 rows)"));
+    }
+}
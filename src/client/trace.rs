@@ -0,0 +1,114 @@
+//! Helpers for client tracing spans and trace context propagation.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use tonic::metadata::MetadataValue;
+use tonic::service::Interceptor;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+use crate::error::GqlError;
+use crate::proto::{QUERY_ID_METADATA, TRACEPARENT_METADATA};
+use crate::trace_context::TraceContext;
+
+/// Longest statement prefix recorded in a span.
+const MAX_STATEMENT_LEN: usize = 100;
+
+static NEXT_QUERY: AtomicU64 = AtomicU64::new(1);
+
+/// A query id unique within this process, prefixed by the session id.
+pub(crate) fn next_query_id(session_id: &str) -> String {
+    format!(
+        "{session_id}/{}",
+        NEXT_QUERY.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// The statement, truncated for recording in a span.
+pub(crate) fn statement_field(statement: &str) -> &str {
+    match statement.char_indices().nth(MAX_STATEMENT_LEN) {
+        Some((end, _)) => &statement[..end],
+        None => statement,
+    }
+}
+
+/// Wrap `message` in a request carrying `query_id` as metadata.
+pub(crate) fn with_query_id<T>(message: T, query_id: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    if let Ok(value) = MetadataValue::try_from(query_id) {
+        request.metadata_mut().insert(QUERY_ID_METADATA, value);
+    }
+    request
+}
+
+/// A channel whose requests carry the session's trace context.
+pub(crate) type TracedChannel = InterceptedService<Channel, TraceInterceptor>;
+
+/// Inserts a `traceparent` on every request: a fresh child span of the
+/// session's trace context, which all clones of the interceptor share.
+#[derive(Debug, Clone)]
+pub(crate) struct TraceInterceptor(Arc<RwLock<TraceContext>>);
+
+impl TraceInterceptor {
+    /// An interceptor for a new root trace.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn new() -> Result<Self, GqlError> {
+        Ok(Self(Arc::new(RwLock::new(TraceContext::new_root()?))))
+    }
+
+    /// The trace context requests are sent as children of.
+    pub(crate) fn context(&self) -> TraceContext {
+        *self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Send later requests as children of `context`.
+    pub(crate) fn set_context(&self, context: TraceContext) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = context;
+    }
+}
+
+impl Interceptor for TraceInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let parent = self.context();
+        // Fall back to the parent span rather than fail the call if no
+        // span ID can be drawn.
+        let span = parent.child().unwrap_or(parent);
+        if let Ok(value) = MetadataValue::try_from(span.to_string()) {
+            request.metadata_mut().insert(TRACEPARENT_METADATA, value);
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_ids_are_unique_per_session() {
+        let a = next_query_id("s1");
+        let b = next_query_id("s1");
+        assert!(a.starts_with("s1/"));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn long_statements_are_truncated_on_char_boundaries() {
+        let statement = "é".repeat(150);
+        assert_eq!(statement_field(&statement).chars().count(), 100);
+        assert_eq!(statement_field("MATCH (n)"), "MATCH (n)");
+    }
+
+    #[test]
+    fn requests_carry_child_spans_of_the_session_trace() {
+        let mut interceptor = TraceInterceptor::new().unwrap();
+        let root = interceptor.context();
+        let request = interceptor.call(Request::new(())).unwrap();
+        let sent = request.metadata().get(TRACEPARENT_METADATA).unwrap();
+        let sent = TraceContext::parse(sent.to_str().unwrap()).unwrap();
+        assert_eq!(sent.trace_id(), root.trace_id());
+        assert_ne!(sent.span_id(), root.span_id());
+    }
+}
@@ -1,8 +1,9 @@
 //! Client-side transaction wrapper.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
-use tonic::transport::Channel;
+use tracing::Instrument;
 
 use crate::error::GqlError;
 use crate::proto;
@@ -10,7 +11,64 @@ use crate::proto::gql_service_client::GqlServiceClient;
 use crate::status;
 use crate::types::Value;
 
+use super::encryption::{self, FieldEncryption};
+use super::prepared::PreparedStatement;
+use super::query_log::{self, QueryLog};
 use super::result::ResultCursor;
+use super::session::ExecuteOptions;
+use super::trace::{self, TracedChannel};
+
+/// Characteristics of a transaction begun with
+/// [`GqlSession::begin_transaction_with`](super::GqlSession::begin_transaction_with).
+///
+/// The default is a read-write transaction at the server's default
+/// isolation level, with no timeout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxOptions {
+    mode: proto::TransactionMode,
+    isolation_level: proto::IsolationLevel,
+    timeout: Option<Duration>,
+}
+
+impl TxOptions {
+    /// Read-write at the server's default isolation level.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the transaction read-only.
+    #[must_use]
+    pub fn read_only(mut self) -> Self {
+        self.mode = proto::TransactionMode::ReadOnly;
+        self
+    }
+
+    /// Request an isolation level. Servers whose backend does not offer
+    /// it fail the begin with
+    /// [`UNSUPPORTED_FEATURE`](crate::status::UNSUPPORTED_FEATURE).
+    #[must_use]
+    pub fn isolation_level(mut self, level: proto::IsolationLevel) -> Self {
+        self.isolation_level = level;
+        self
+    }
+
+    /// Have the server roll the transaction back if it is still open
+    /// after `timeout`, so a stalled client does not hold it open.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn timeout_ms(self) -> Option<u32> {
+        self.timeout.map(|timeout| {
+            u32::try_from(timeout.as_millis())
+                .unwrap_or(u32::MAX)
+                .max(1)
+        })
+    }
+}
 
 /// An active transaction within a session.
 ///
@@ -19,22 +77,38 @@ use super::result::ResultCursor;
 pub struct Transaction {
     session_id: String,
     id: String,
-    client: GqlServiceClient<Channel>,
+    client: GqlServiceClient<TracedChannel>,
     committed: bool,
     rolled_back: bool,
+    query_log: Option<QueryLog>,
+    encryption: Option<FieldEncryption>,
+    row_batch_checksums: bool,
+    execute_options: ExecuteOptions,
 }
 
 impl Transaction {
     /// Begin a transaction (called by `GqlSession`).
+    #[tracing::instrument(
+        name = "gwp.begin",
+        level = "debug",
+        skip_all,
+        fields(session_id = %session_id, transaction_id)
+    )]
     pub(crate) async fn begin(
         session_id: String,
-        mut client: GqlServiceClient<Channel>,
-        mode: proto::TransactionMode,
+        mut client: GqlServiceClient<TracedChannel>,
+        options: TxOptions,
+        query_log: Option<QueryLog>,
+        encryption: Option<FieldEncryption>,
+        row_batch_checksums: bool,
+        execute_options: ExecuteOptions,
     ) -> Result<Self, GqlError> {
         let resp = client
             .begin_transaction(proto::BeginRequest {
                 session_id: session_id.clone(),
-                mode: mode.into(),
+                mode: options.mode.into(),
+                isolation_level: options.isolation_level.into(),
+                timeout_ms: options.timeout_ms(),
             })
             .await?
             .into_inner();
@@ -52,15 +126,45 @@ impl Transaction {
             ));
         }
 
+        tracing::Span::current().record("transaction_id", &resp.transaction_id);
+
         Ok(Self {
             session_id,
             id: resp.transaction_id,
             client,
             committed: false,
             rolled_back: false,
+            query_log,
+            encryption,
+            row_batch_checksums,
+            execute_options,
         })
     }
 
+    /// Take over a transaction that is already open, such as one kept
+    /// by a resumed session (called by `GqlSession`).
+    pub(crate) fn attach(
+        session_id: String,
+        id: String,
+        client: GqlServiceClient<TracedChannel>,
+        query_log: Option<QueryLog>,
+        encryption: Option<FieldEncryption>,
+        row_batch_checksums: bool,
+        execute_options: ExecuteOptions,
+    ) -> Self {
+        Self {
+            session_id,
+            id,
+            client,
+            committed: false,
+            rolled_back: false,
+            query_log,
+            encryption,
+            row_batch_checksums,
+            execute_options,
+        }
+    }
+
     /// Get the transaction ID.
     #[must_use]
     pub fn transaction_id(&self) -> &str {
@@ -72,28 +176,142 @@ impl Transaction {
     /// # Errors
     ///
     /// Returns an error if the server rejects the request.
+    #[tracing::instrument(
+        name = "gwp.execute",
+        level = "debug",
+        skip_all,
+        fields(
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+            query_id,
+            statement = trace::statement_field(statement),
+        )
+    )]
     pub async fn execute(
         &mut self,
         statement: &str,
         parameters: HashMap<String, Value>,
     ) -> Result<ResultCursor, GqlError> {
+        let parameters = match &self.encryption {
+            Some(encryption) => encryption
+                .encrypt_parameters(parameters)
+                .map_err(encryption::failed)?,
+            None => parameters,
+        };
+        let query = self
+            .query_log
+            .as_ref()
+            .map(|log| log.start(&self.session_id, Some(&self.id), statement, &parameters));
         let proto_params: HashMap<String, proto::Value> = parameters
             .into_iter()
             .map(|(k, v)| (k, proto::Value::from(v)))
             .collect();
 
-        let stream = self
-            .client
-            .execute(proto::ExecuteRequest {
-                session_id: self.session_id.clone(),
-                statement: statement.to_owned(),
-                parameters: proto_params,
-                transaction_id: Some(self.id.clone()),
-            })
-            .await?
-            .into_inner();
+        let query_id = trace::next_query_id(&self.session_id);
+        tracing::Span::current().record("query_id", &query_id);
+        let mut request = proto::ExecuteRequest {
+            session_id: self.session_id.clone(),
+            statement: statement.to_owned(),
+            parameters: proto_params,
+            transaction_id: Some(self.id.clone()),
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        self.execute_options.apply(&mut request);
+        let response = self
+            .execute_options
+            .accepting(self.client.clone())
+            .execute(trace::with_query_id(request, &query_id))
+            .await;
+
+        match response {
+            Ok(response) => Ok(ResultCursor::new(response.into_inner())
+                .with_required_checksums(self.row_batch_checksums)
+                .with_encryption(self.encryption.clone())
+                .with_query(query)
+                .with_span(tracing::Span::current())),
+            Err(status) => Err(query_log::failed(query, status.into())),
+        }
+    }
+
+    /// Execute a statement prepared in this transaction's session within
+    /// this transaction. Only the statement ID is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement was prepared in another session
+    /// or the server rejects the request.
+    #[tracing::instrument(
+        name = "gwp.execute",
+        level = "debug",
+        skip_all,
+        fields(
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+            query_id,
+            statement = trace::statement_field(prepared.statement()),
+        )
+    )]
+    pub async fn execute_prepared(
+        &mut self,
+        prepared: &PreparedStatement,
+        parameters: HashMap<String, Value>,
+    ) -> Result<ResultCursor, GqlError> {
+        if prepared.session_id() != self.session_id {
+            return Err(GqlError::Session(
+                "statement was prepared in another session".to_owned(),
+            ));
+        }
+        let parameters = match &self.encryption {
+            Some(encryption) => encryption
+                .encrypt_parameters(parameters)
+                .map_err(encryption::failed)?,
+            None => parameters,
+        };
+        let query = self.query_log.as_ref().map(|log| {
+            log.start(
+                &self.session_id,
+                Some(&self.id),
+                prepared.statement(),
+                &parameters,
+            )
+        });
+        let proto_params: HashMap<String, proto::Value> = parameters
+            .into_iter()
+            .map(|(k, v)| (k, proto::Value::from(v)))
+            .collect();
 
-        Ok(ResultCursor::new(stream))
+        let query_id = trace::next_query_id(&self.session_id);
+        tracing::Span::current().record("query_id", &query_id);
+        let mut request = proto::ExecutePreparedRequest {
+            session_id: self.session_id.clone(),
+            statement_id: prepared.statement_id().to_owned(),
+            parameters: proto_params,
+            transaction_id: Some(self.id.clone()),
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        self.execute_options.apply_prepared(&mut request);
+        let response = self
+            .execute_options
+            .accepting(self.client.clone())
+            .execute_prepared(trace::with_query_id(request, &query_id))
+            .await;
+
+        match response {
+            Ok(response) => Ok(ResultCursor::new(response.into_inner())
+                .with_required_checksums(self.row_batch_checksums)
+                .with_encryption(self.encryption.clone())
+                .with_query(query)
+                .with_span(tracing::Span::current())),
+            Err(status) => Err(query_log::failed(query, status.into())),
+        }
     }
 
     /// Execute a statement within this transaction with no parameters.
@@ -111,18 +329,64 @@ impl Transaction {
     ///
     /// # Errors
     ///
-    /// Returns an error if the commit fails.
+    /// Returns an error if the commit fails; the transaction is then
+    /// rolled back.
     pub async fn commit(mut self) -> Result<(), GqlError> {
+        let span = tracing::debug_span!(
+            "gwp.commit",
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+        );
         let resp = self
             .client
             .commit(proto::CommitRequest {
                 session_id: self.session_id.clone(),
                 transaction_id: self.id.clone(),
             })
+            .instrument(span)
             .await?
             .into_inner();
 
+        if let Some(ref s) = resp.status {
+            if status::is_exception(&s.code) {
+                // The server keeps a transaction whose commit failed open;
+                // end it so the session can begin another.
+                let _ = self.do_rollback().await;
+                return Err(GqlError::Status { status: s.clone() });
+            }
+        }
+
         self.committed = true;
+        Ok(())
+    }
+
+    /// Prepare the transaction for a two-phase commit, returning its ID.
+    ///
+    /// A prepared transaction is detached from the session and is not
+    /// rolled back when this handle or the session goes away. Finish it
+    /// from any session with
+    /// [`GqlSession::commit_prepared`](super::GqlSession::commit_prepared)
+    /// or [`GqlSession::rollback_prepared`](super::GqlSession::rollback_prepared).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server or backend refuses to prepare; the
+    /// transaction is then rolled back.
+    pub async fn prepare_commit(mut self) -> Result<String, GqlError> {
+        let span = tracing::debug_span!(
+            "gwp.prepare_commit",
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+        );
+        let resp = self
+            .client
+            .prepare_commit(proto::PrepareCommitRequest {
+                session_id: self.session_id.clone(),
+                transaction_id: self.id.clone(),
+            })
+            .instrument(span)
+            .await?
+            .into_inner();
 
         if let Some(ref s) = resp.status {
             if status::is_exception(&s.code) {
@@ -130,7 +394,90 @@ impl Transaction {
             }
         }
 
-        Ok(())
+        self.committed = true;
+        Ok(self.id.clone())
+    }
+
+    /// Mark a savepoint called `name`, to undo later changes with
+    /// [`rollback_to_savepoint`](Self::rollback_to_savepoint) without
+    /// giving up the whole transaction. Reusing a name hides the earlier
+    /// savepoint until the new one is released.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server or backend does not support
+    /// savepoints.
+    pub async fn savepoint(&mut self, name: &str) -> Result<(), GqlError> {
+        let span = tracing::debug_span!(
+            "gwp.savepoint",
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+            savepoint = name,
+        );
+        let resp = self
+            .client
+            .savepoint(proto::SavepointRequest {
+                session_id: self.session_id.clone(),
+                transaction_id: self.id.clone(),
+                name: name.to_owned(),
+            })
+            .instrument(span)
+            .await?
+            .into_inner();
+        savepoint_result(resp)
+    }
+
+    /// Forget the savepoint `name` and those marked after it, keeping
+    /// their changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no savepoint is called `name`.
+    pub async fn release_savepoint(&mut self, name: &str) -> Result<(), GqlError> {
+        let span = tracing::debug_span!(
+            "gwp.release_savepoint",
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+            savepoint = name,
+        );
+        let resp = self
+            .client
+            .release_savepoint(proto::ReleaseSavepointRequest {
+                session_id: self.session_id.clone(),
+                transaction_id: self.id.clone(),
+                name: name.to_owned(),
+            })
+            .instrument(span)
+            .await?
+            .into_inner();
+        savepoint_result(resp)
+    }
+
+    /// Undo the changes made since the savepoint `name`. The savepoint
+    /// stays, so it can be rolled back to again, and the transaction
+    /// stays active.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no savepoint is called `name`.
+    pub async fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), GqlError> {
+        let span = tracing::debug_span!(
+            "gwp.rollback_to_savepoint",
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+            savepoint = name,
+        );
+        let resp = self
+            .client
+            .rollback_to_savepoint(proto::RollbackToSavepointRequest {
+                session_id: self.session_id.clone(),
+                transaction_id: self.id.clone(),
+                name: name.to_owned(),
+            })
+            .instrument(span)
+            .await?
+            .into_inner();
+        savepoint_result(resp)
     }
 
     /// Roll back the transaction.
@@ -148,12 +495,18 @@ impl Transaction {
             return Ok(());
         }
 
+        let span = tracing::debug_span!(
+            "gwp.rollback",
+            session_id = %self.session_id,
+            transaction_id = %self.id,
+        );
         let resp = self
             .client
             .rollback(proto::RollbackRequest {
                 session_id: self.session_id.clone(),
                 transaction_id: self.id.clone(),
             })
+            .instrument(span)
             .await?
             .into_inner();
 
@@ -188,3 +541,13 @@ impl Drop for Transaction {
         }
     }
 }
+
+/// The outcome of a savepoint request, which leaves the transaction
+/// active whether or not it succeeds.
+#[allow(clippy::result_large_err)]
+fn savepoint_result(resp: proto::SavepointResponse) -> Result<(), GqlError> {
+    match resp.status {
+        Some(s) if status::is_exception(&s.code) => Err(GqlError::Status { status: s }),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,305 @@
+//! Typed parameters and rows.
+
+// Conversions report errors as the `GqlError` the cursor returns them in.
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crate::error::GqlError;
+use crate::types::{Edge, Node, Path, Record, Value};
+
+/// Conversion from a result value into a Rust type.
+///
+/// Implemented for `Value` itself, the Rust types with a matching GQL
+/// type, narrower integer types (checked for range), `Option<T>` (NULL
+/// is `None`), and `Vec<T>` (from lists; byte strings convert into
+/// `Vec<u8>`).
+pub trait FromValue: Sized {
+    /// Convert `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the expected and actual type if `value`
+    /// does not convert.
+    fn from_value(value: Value) -> Result<Self, GqlError>;
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self, GqlError> {
+        Ok(value)
+    }
+}
+
+/// Implement `FromValue` through the `TryFrom<Value>` impls in `types`.
+macro_rules! from_value_via_try_from {
+    ($($ty:ty),*) => {
+        $(
+            impl FromValue for $ty {
+                fn from_value(value: Value) -> Result<Self, GqlError> {
+                    Ok(Self::try_from(value)?)
+                }
+            }
+        )*
+    };
+}
+
+from_value_via_try_from!(
+    bool,
+    i64,
+    i32,
+    i16,
+    u64,
+    u32,
+    u16,
+    usize,
+    f64,
+    String,
+    Vec<u8>,
+    Node,
+    Edge,
+    Path,
+    Record
+);
+
+impl FromValue for f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_value(value: Value) -> Result<Self, GqlError> {
+        f64::from_value(value).map(|f| f as f32)
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Result<Self, GqlError> {
+        match value {
+            Value::Null => Ok(None),
+            other => T::from_value(other).map(Some),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Result<Self, GqlError> {
+        match value {
+            Value::List(elements) => elements
+                .into_iter()
+                .enumerate()
+                .map(|(i, element)| {
+                    T::from_value(element).map_err(|e| context(&format!("list element {i}"), e))
+                })
+                .collect(),
+            other => Err(GqlError::Protocol(format!(
+                "expected List, got {}",
+                other.type_name()
+            ))),
+        }
+    }
+}
+
+/// Conversion from a result row into a Rust type, for
+/// [`GqlSession::execute_typed`](super::GqlSession::execute_typed) and
+/// [`ResultCursor::collect_as`](super::ResultCursor::collect_as).
+///
+/// Implemented for `Vec<Value>` and for tuples of up to eight
+/// [`FromValue`] types, which take the columns in order. Structs
+/// implement it by name with [`column`]:
+///
+/// ```
+/// use gwp::client::{FromRow, column};
+/// use gwp::error::GqlError;
+/// use gwp::types::Value;
+///
+/// struct Person {
+///     name: String,
+///     age: Option<i64>,
+/// }
+///
+/// impl FromRow for Person {
+///     fn from_row(columns: &[String], mut values: Vec<Value>) -> Result<Self, GqlError> {
+///         Ok(Self {
+///             name: column(columns, &mut values, "name")?,
+///             age: column(columns, &mut values, "age")?,
+///         })
+///     }
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Convert a row with the given column names.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column is missing or does not convert.
+    fn from_row(columns: &[String], values: Vec<Value>) -> Result<Self, GqlError>;
+}
+
+impl FromRow for Vec<Value> {
+    fn from_row(_columns: &[String], values: Vec<Value>) -> Result<Self, GqlError> {
+        Ok(values)
+    }
+}
+
+/// Take the value of the column called `name` out of `values`, leaving
+/// NULL in its place, and convert it.
+///
+/// # Errors
+///
+/// Returns an error if there is no such column or its value does not
+/// convert, naming the column.
+pub fn column<T: FromValue>(
+    columns: &[String],
+    values: &mut [Value],
+    name: &str,
+) -> Result<T, GqlError> {
+    let value = columns
+        .iter()
+        .position(|c| c == name)
+        .and_then(|i| values.get_mut(i))
+        .ok_or_else(|| GqlError::Protocol(format!("no column `{name}` in result")))?;
+    T::from_value(std::mem::replace(value, Value::Null))
+        .map_err(|e| context(&format!("column `{name}`"), e))
+}
+
+/// Convert the value of the `index`th column.
+fn positional<T: FromValue>(columns: &[String], index: usize, value: Value) -> Result<T, GqlError> {
+    T::from_value(value).map_err(|e| match columns.get(index) {
+        Some(name) => context(&format!("column `{name}`"), e),
+        None => context(&format!("column {index}"), e),
+    })
+}
+
+/// Prefix a conversion error with where the value came from.
+pub(super) fn context(location: &str, err: GqlError) -> GqlError {
+    match err {
+        GqlError::Protocol(message) => GqlError::Protocol(format!("{location}: {message}")),
+        other => other,
+    }
+}
+
+/// Implement `FromRow` for a tuple, taking the columns in order.
+macro_rules! from_row_tuple {
+    ($len:literal => $($ty:ident),+) => {
+        impl<$($ty: FromValue),+> FromRow for ($($ty,)+) {
+            fn from_row(columns: &[String], values: Vec<Value>) -> Result<Self, GqlError> {
+                if values.len() != $len {
+                    return Err(GqlError::Protocol(format!(
+                        "expected {} columns, got {}",
+                        $len,
+                        values.len()
+                    )));
+                }
+                let mut values = values.into_iter().enumerate();
+                Ok(($({
+                    let (index, value) = values.next().unwrap_or((0, Value::Null));
+                    positional::<$ty>(columns, index, value)?
+                },)+))
+            }
+        }
+    };
+}
+
+from_row_tuple!(1 => A);
+from_row_tuple!(2 => A, B);
+from_row_tuple!(3 => A, B, C);
+from_row_tuple!(4 => A, B, C, D);
+from_row_tuple!(5 => A, B, C, D, E);
+from_row_tuple!(6 => A, B, C, D, E, F);
+from_row_tuple!(7 => A, B, C, D, E, F, G);
+from_row_tuple!(8 => A, B, C, D, E, F, G, H);
+
+/// Statement parameters, built with [`set`](Self::set) or the
+/// [`params!`](crate::params) macro.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params(HashMap<String, Value>);
+
+impl Params {
+    /// No parameters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the parameter `name`.
+    #[must_use]
+    pub fn set(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.0.insert(name.into(), value.into());
+        self
+    }
+
+    /// Get the parameter `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    /// Number of parameters.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no parameters.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<S: BuildHasher + Default> From<Params> for HashMap<String, Value, S> {
+    fn from(params: Params) -> Self {
+        params.0.into_iter().collect()
+    }
+}
+
+/// Build [`Params`](crate::client::Params) from `name => value` pairs.
+///
+/// ```
+/// let params = gwp::params! { "name" => "Alice", "min_age" => 30 };
+/// assert_eq!(params.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! params {
+    ($($name:expr => $value:expr),* $(,)?) => {
+        $crate::client::Params::new()$(.set($name, $value))*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&n| n.to_owned()).collect()
+    }
+
+    #[test]
+    fn converts_rows_by_position_and_name() {
+        let names = columns(&["name", "age", "tags"]);
+        let row = || {
+            vec![
+                Value::from("Alice"),
+                Value::Null,
+                Value::List(vec![Value::from("a"), Value::from("b")]),
+            ]
+        };
+
+        let (name, age, tags): (String, Option<i32>, Vec<String>) =
+            FromRow::from_row(&names, row()).unwrap();
+        assert_eq!((name.as_str(), age), ("Alice", None));
+        assert_eq!(tags, ["a", "b"]);
+
+        let mut values = row();
+        let err = column::<i64>(&names, &mut values, "name").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "protocol error: column `name`: expected Integer, got String"
+        );
+        assert!(column::<i64>(&names, &mut values, "missing").is_err());
+        assert!(<(String,)>::from_row(&names, row()).is_err());
+    }
+
+    #[test]
+    fn narrowing_rejects_out_of_range_integers() {
+        assert_eq!(i32::from_value(Value::Integer(7)).unwrap(), 7);
+        assert!(i32::from_value(Value::Integer(i64::MAX)).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+//! Typed warnings and notifications from the server.
+
+use crate::proto;
+use crate::status;
+
+/// How serious a [`ServerWarning`] is, from its GQLSTATUS class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// A completion with warning (class 01), such as a slow query.
+    Warning,
+    /// Any other condition worth reporting, such as an informational
+    /// status (class 03).
+    Information,
+}
+
+/// A warning the server raised about a statement, from
+/// [`ResultCursor::warnings`](super::ResultCursor::warnings).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerWarning {
+    /// The GQLSTATUS code.
+    pub code: String,
+    /// Severity, from the code's class.
+    pub severity: Severity,
+    /// Human-readable description.
+    pub message: String,
+    /// Where in the statement the warning applies, if the server said.
+    pub position: Option<proto::SourcePosition>,
+}
+
+impl From<proto::GqlStatus> for ServerWarning {
+    fn from(status: proto::GqlStatus) -> Self {
+        let severity = if status::is_warning(&status.code) {
+            Severity::Warning
+        } else {
+            Severity::Information
+        };
+        Self {
+            position: status.diagnostic.and_then(|d| d.position),
+            severity,
+            code: status.code,
+            message: status.message,
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Columnar encoding of row batches.
+//!
+//! Analytical results are often wide and numeric, and sending them row by
+//! row wraps every cell in its own `Value` message. Sessions that
+//! negotiate columnar batches at handshake receive each `RowBatch` as a
+//! `ColumnarBatch` instead: one vector per column, with nulls marked in a
+//! bitmap and the remaining values packed into a typed vector when they
+//! all share one scalar kind. The client cursor turns batches back into
+//! rows before handing them out.
+//!
+//! Encoding runs after dictionary encoding, so string columns whose
+//! values were interned travel as mixed columns of dictionary references.
+
+use crate::proto;
+use crate::proto::batch_column::Values;
+use crate::proto::value::Kind;
+
+/// The `ServerInfo.features` entry a server lists when it sends the
+/// session columnar batches.
+pub const FEATURE: &str = "columnar_batches";
+
+/// Transpose the rows of `batch` into columns. Batches that are empty,
+/// already columnar, or whose rows differ in width are left unchanged.
+pub fn encode(batch: &mut proto::RowBatch) {
+    let Some(first) = batch.rows.first() else {
+        return;
+    };
+    let width = first.values.len();
+    if batch.columns.is_some() || batch.rows.iter().any(|row| row.values.len() != width) {
+        return;
+    }
+    let Ok(row_count) = u32::try_from(batch.rows.len()) else {
+        return;
+    };
+    let mut columns: Vec<Vec<proto::Value>> = (0..width)
+        .map(|_| Vec::with_capacity(batch.rows.len()))
+        .collect();
+    for row in std::mem::take(&mut batch.rows) {
+        for (column, value) in columns.iter_mut().zip(row.values) {
+            column.push(value);
+        }
+    }
+    batch.columns = Some(proto::ColumnarBatch {
+        row_count,
+        columns: columns.into_iter().map(encode_column).collect(),
+    });
+}
+
+/// Transpose the columns of `batch` back into rows.
+///
+/// Returns `false` if the batch is malformed: it has both rows and
+/// columns, or a column's null bitmap or value count does not match the
+/// row count.
+#[must_use]
+pub fn decode(batch: &mut proto::RowBatch) -> bool {
+    let Some(columnar) = batch.columns.take() else {
+        return true;
+    };
+    if !batch.rows.is_empty() {
+        return false;
+    }
+    let Ok(row_count) = usize::try_from(columnar.row_count) else {
+        return false;
+    };
+    let mut rows: Vec<proto::Row> = (0..row_count)
+        .map(|_| proto::Row {
+            values: Vec::with_capacity(columnar.columns.len()),
+        })
+        .collect();
+    for column in columnar.columns {
+        let Some(values) = decode_column(column, row_count) else {
+            return false;
+        };
+        for (row, value) in rows.iter_mut().zip(values) {
+            row.values.push(value);
+        }
+    }
+    batch.rows = rows;
+    true
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+fn encode_column(values: Vec<proto::Value>) -> proto::BatchColumn {
+    let mut nulls = vec![0u8; values.len().div_ceil(8)];
+    let mut any_null = false;
+    let mut present = Vec::with_capacity(values.len());
+    for (i, value) in values.into_iter().enumerate() {
+        if matches!(value.kind, Some(Kind::NullValue(_))) {
+            nulls[i / 8] |= 1 << (i % 8);
+            any_null = true;
+        } else {
+            present.push(value);
+        }
+    }
+    if !any_null {
+        nulls.clear();
+    }
+    proto::BatchColumn {
+        nulls,
+        values: Some(pack(present)),
+    }
+}
+
+/// Pack `values` into a typed vector if they all share one scalar kind.
+fn pack(values: Vec<proto::Value>) -> Values {
+    macro_rules! packed {
+        ($kind:ident, $column:ident, $variant:ident) => {
+            if values
+                .iter()
+                .all(|v| matches!(v.kind, Some(Kind::$kind(_))))
+            {
+                return Values::$variant(proto::$column {
+                    values: values
+                        .into_iter()
+                        .filter_map(|v| match v.kind {
+                            Some(Kind::$kind(value)) => Some(value),
+                            _ => None,
+                        })
+                        .collect(),
+                });
+            }
+        };
+    }
+    if !values.is_empty() {
+        packed!(IntegerValue, IntegerColumn, Integers);
+        packed!(
+            UnsignedIntegerValue,
+            UnsignedIntegerColumn,
+            UnsignedIntegers
+        );
+        packed!(FloatValue, FloatColumn, Floats);
+        packed!(BooleanValue, BooleanColumn, Booleans);
+        packed!(StringValue, StringColumn, Strings);
+    }
+    Values::Mixed(proto::ValueColumn { values })
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+fn decode_column(column: proto::BatchColumn, row_count: usize) -> Option<Vec<proto::Value>> {
+    let nulls = column.nulls;
+    if !nulls.is_empty() && nulls.len() != row_count.div_ceil(8) {
+        return None;
+    }
+    let is_null = |i: usize| {
+        nulls
+            .get(i / 8)
+            .is_some_and(|byte| byte & (1 << (i % 8)) != 0)
+    };
+    let null_count = (0..row_count).filter(|&i| is_null(i)).count();
+
+    let present: Vec<proto::Value> = match column.values? {
+        Values::Integers(c) => wrap(c.values, Kind::IntegerValue),
+        Values::UnsignedIntegers(c) => wrap(c.values, Kind::UnsignedIntegerValue),
+        Values::Floats(c) => wrap(c.values, Kind::FloatValue),
+        Values::Booleans(c) => wrap(c.values, Kind::BooleanValue),
+        Values::Strings(c) => wrap(c.values, Kind::StringValue),
+        Values::Mixed(c) => c.values,
+    };
+    if present.len() != row_count - null_count {
+        return None;
+    }
+    let mut present = present.into_iter();
+    Some(
+        (0..row_count)
+            .map(|i| {
+                if is_null(i) {
+                    proto::Value {
+                        kind: Some(Kind::NullValue(proto::NullValue {})),
+                    }
+                } else {
+                    present.next().unwrap_or_default()
+                }
+            })
+            .collect(),
+    )
+}
+
+fn wrap<T>(values: Vec<T>, kind: fn(T) -> Kind) -> Vec<proto::Value> {
+    values
+        .into_iter()
+        .map(|v| proto::Value {
+            kind: Some(kind(v)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+    use crate::types::Value;
+
+    fn batch(rows: Vec<Vec<Value>>) -> proto::RowBatch {
+        proto::RowBatch {
+            rows: rows
+                .into_iter()
+                .map(|values| proto::Row {
+                    values: values.into_iter().map(proto::Value::from).collect(),
+                })
+                .collect(),
+            checksum: None,
+            dictionary: Vec::new(),
+            columns: None,
+        }
+    }
+
+    fn rows(batch: proto::RowBatch) -> Vec<Vec<Value>> {
+        batch
+            .rows
+            .into_iter()
+            .map(|row| row.values.into_iter().map(Value::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_typed_and_mixed_columns() {
+        let rows_in = vec![
+            vec![
+                Value::Integer(1),
+                Value::from("a"),
+                Value::Null,
+                Value::Float(0.5),
+            ],
+            vec![
+                Value::Null,
+                Value::from("b"),
+                Value::Boolean(true),
+                Value::Float(1.5),
+            ],
+            vec![
+                Value::Integer(-3),
+                Value::from("c"),
+                Value::Integer(7),
+                Value::Null,
+            ],
+        ];
+        let mut encoded = batch(rows_in.clone());
+        encode(&mut encoded);
+
+        assert!(encoded.rows.is_empty());
+        let columns = &encoded.columns.as_ref().unwrap().columns;
+        assert_eq!(columns[0].nulls, [0b010]);
+        assert!(matches!(columns[0].values, Some(Values::Integers(_))));
+        assert!(columns[1].nulls.is_empty());
+        assert!(matches!(columns[1].values, Some(Values::Strings(_))));
+        assert!(matches!(columns[2].values, Some(Values::Mixed(_))));
+        assert!(matches!(columns[3].values, Some(Values::Floats(_))));
+
+        assert!(decode(&mut encoded));
+        assert_eq!(rows(encoded), rows_in);
+    }
+
+    #[test]
+    fn shrinks_numeric_batches() {
+        let rows_in: Vec<Vec<Value>> = (0..100)
+            .map(|i| (0..8).map(|j| Value::Integer(i * j)).collect())
+            .collect();
+        let original = batch(rows_in);
+        let mut encoded = original.clone();
+        encode(&mut encoded);
+        assert!(encoded.encoded_len() * 2 < original.encoded_len());
+    }
+
+    #[test]
+    fn rejects_malformed_batches() {
+        let mut encoded = batch(vec![vec![Value::Integer(1)], vec![Value::Null]]);
+        encode(&mut encoded);
+
+        let mut short = encoded.clone();
+        short.columns.as_mut().unwrap().row_count = 3;
+        assert!(!decode(&mut short));
+
+        let mut bad_nulls = encoded.clone();
+        bad_nulls.columns.as_mut().unwrap().columns[0].nulls = vec![0, 0];
+        assert!(!decode(&mut bad_nulls));
+
+        let mut both = encoded;
+        both.rows.push(proto::Row::default());
+        assert!(!decode(&mut both));
+    }
+}
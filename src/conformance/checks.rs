@@ -0,0 +1,520 @@
+//! The individual conformance checks.
+//!
+//! Checks talk to the server with the raw generated clients so they can
+//! observe exactly what goes over the wire: every frame, every status.
+
+use std::collections::HashMap;
+
+use tonic::Code;
+use tonic::transport::Channel;
+
+use crate::proto;
+use crate::proto::admin_service_client::AdminServiceClient;
+use crate::proto::catalog_service_client::CatalogServiceClient;
+use crate::proto::execute_response::Frame;
+use crate::proto::gql_service_client::GqlServiceClient;
+use crate::proto::search_service_client::SearchServiceClient;
+use crate::proto::session_service_client::SessionServiceClient;
+use crate::server::GqlBackend;
+use crate::status;
+use crate::testing::TestServer;
+
+use super::{CheckOutcome, ConformanceConfig, ConformanceReport};
+
+/// Session id that no backend will have issued.
+const UNKNOWN_SESSION: &str = "gwp-conformance-unknown-session";
+
+/// Raw clients for one server, plus the suite configuration.
+struct Harness<'a> {
+    config: &'a ConformanceConfig,
+    session: SessionServiceClient<Channel>,
+    gql: GqlServiceClient<Channel>,
+    channel: Channel,
+}
+
+impl Harness<'_> {
+    /// Handshake and return the response for the new session.
+    async fn handshake(&mut self) -> Result<proto::HandshakeResponse, String> {
+        let resp = self
+            .session
+            .handshake(proto::HandshakeRequest {
+                protocol_version: 1,
+                credentials: None,
+                client_info: HashMap::new(),
+                resumption_token: None,
+                row_batch_checksums: false,
+                dictionary_encoding: false,
+                columnar_batches: false,
+                database: None,
+            })
+            .await
+            .map_err(|s| format!("handshake failed: {s}"))?
+            .into_inner();
+        if resp.session_id.is_empty() {
+            return Err("handshake returned an empty session id".to_owned());
+        }
+        Ok(resp)
+    }
+
+    /// Handshake and return the new session id.
+    async fn open(&mut self) -> Result<String, String> {
+        self.handshake().await.map(|resp| resp.session_id)
+    }
+
+    async fn close(&mut self, session_id: &str) -> Result<(), String> {
+        self.session
+            .close(proto::CloseRequest {
+                session_id: session_id.to_owned(),
+            })
+            .await
+            .map(|_| ())
+            .map_err(|s| format!("close failed: {s}"))
+    }
+
+    /// Execute a statement and collect every frame of the result stream.
+    async fn execute(
+        &mut self,
+        session_id: &str,
+        statement: &str,
+        transaction_id: Option<String>,
+    ) -> Result<Vec<Frame>, tonic::Status> {
+        let mut stream = self
+            .gql
+            .execute(proto::ExecuteRequest {
+                session_id: session_id.to_owned(),
+                statement: statement.to_owned(),
+                parameters: HashMap::new(),
+                transaction_id,
+                fetch_size: None,
+                timeout_ms: None,
+                compression: None,
+                max_rows: None,
+                max_bytes: None,
+            })
+            .await?
+            .into_inner();
+        let mut frames = Vec::new();
+        while let Some(resp) = stream.message().await? {
+            frames.extend(resp.frame);
+        }
+        Ok(frames)
+    }
+
+    async fn begin(&mut self, session_id: &str) -> Result<proto::BeginResponse, String> {
+        self.gql
+            .begin_transaction(proto::BeginRequest {
+                session_id: session_id.to_owned(),
+                mode: proto::TransactionMode::ReadWrite.into(),
+                isolation_level: proto::IsolationLevel::Default.into(),
+                timeout_ms: None,
+            })
+            .await
+            .map(tonic::Response::into_inner)
+            .map_err(|s| format!("begin failed: {s}"))
+    }
+}
+
+/// Run every check against the server, recording into `report`.
+pub(super) async fn run_all<B: GqlBackend>(
+    server: &TestServer<B>,
+    config: &ConformanceConfig,
+    report: &mut ConformanceReport,
+) {
+    let channel = server.connection().channel().clone();
+    let mut h = Harness {
+        config,
+        session: SessionServiceClient::new(channel.clone()),
+        gql: GqlServiceClient::new(channel.clone()),
+        channel,
+    };
+
+    report.record("session/handshake", session_handshake(&mut h).await);
+    report.record("session/ping", session_ping(&mut h).await);
+    report.record(
+        "session/configure_reset",
+        session_configure_reset(&mut h).await,
+    );
+    report.record("session/close", session_close(&mut h).await);
+
+    report.record("streaming/query_frames", streaming_query(&mut h).await);
+    report.record("streaming/write_frames", streaming_write(&mut h).await);
+
+    report.record("transaction/commit", transaction_commit(&mut h).await);
+    report.record("transaction/rollback", transaction_rollback(&mut h).await);
+    report.record(
+        "transaction/double_begin",
+        transaction_double_begin(&mut h).await,
+    );
+    report.record(
+        "transaction/unknown_commit",
+        transaction_unknown_commit(&mut h).await,
+    );
+
+    report.record(
+        "errors/invalid_statement",
+        errors_invalid_statement(&mut h).await,
+    );
+    report.record(
+        "errors/unknown_session",
+        errors_unknown_session(&mut h).await,
+    );
+
+    let graph = match &config.graph {
+        Some(graph) => Some(graph.clone()),
+        None => match h.handshake().await {
+            Ok(resp) => {
+                let _ = h.close(&resp.session_id).await;
+                resp.default_graph
+            }
+            Err(_) => None,
+        },
+    };
+    report.push("optional/catalog", optional_catalog(&h).await);
+    report.push("optional/admin", optional_admin(&h, graph.as_deref()).await);
+    report.push(
+        "optional/search",
+        optional_search(&h, graph.as_deref()).await,
+    );
+}
+
+// =========================================================================
+// Session lifecycle
+// =========================================================================
+
+async fn session_handshake(h: &mut Harness<'_>) -> Result<(), String> {
+    let resp = h.handshake().await?;
+    if resp.protocol_version != 1 {
+        return Err(format!(
+            "expected protocol version 1, got {}",
+            resp.protocol_version
+        ));
+    }
+    let other = h.open().await?;
+    if other == resp.session_id {
+        return Err("two handshakes returned the same session id".to_owned());
+    }
+    h.close(&resp.session_id).await?;
+    h.close(&other).await
+}
+
+async fn session_ping(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    h.session
+        .ping(proto::PingRequest {
+            session_id: session_id.clone(),
+        })
+        .await
+        .map_err(|s| format!("ping failed: {s}"))?;
+    h.close(&session_id).await
+}
+
+async fn session_configure_reset(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    h.session
+        .configure(proto::ConfigureRequest {
+            session_id: session_id.clone(),
+            property: Some(proto::configure_request::Property::TimeZoneOffsetMinutes(0)),
+        })
+        .await
+        .map_err(|s| format!("configure failed: {s}"))?;
+    h.session
+        .reset(proto::ResetRequest {
+            session_id: session_id.clone(),
+            target: proto::ResetTarget::ResetAll.into(),
+        })
+        .await
+        .map_err(|s| format!("reset failed: {s}"))?;
+    h.close(&session_id).await
+}
+
+async fn session_close(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    h.close(&session_id).await?;
+    match h.execute(&session_id, &h.config.query.clone(), None).await {
+        Err(s) if s.code() == Code::NotFound => Ok(()),
+        Err(s) => Err(format!(
+            "execute on a closed session: expected NOT_FOUND, got {s}"
+        )),
+        Ok(_) => Err("execute on a closed session succeeded".to_owned()),
+    }
+}
+
+// =========================================================================
+// Streaming
+// =========================================================================
+
+/// Check frame ordering: an optional header first, then row batches, then
+/// exactly one summary last, with warnings anywhere before the summary.
+/// Returns the header and summary.
+fn check_frames(
+    frames: &[Frame],
+) -> Result<(Option<&proto::ResultHeader>, &proto::ResultSummary), String> {
+    let Some((Frame::Summary(summary), body)) = frames.split_last() else {
+        return Err("result stream does not end with a summary".to_owned());
+    };
+    let body: Vec<&Frame> = body
+        .iter()
+        .filter(|frame| !matches!(frame, Frame::Warning(_)))
+        .collect();
+    let (header, batches) = match body.split_first() {
+        Some((Frame::Header(header), rest)) => (Some(header), rest),
+        _ => (None, body.as_slice()),
+    };
+    for frame in batches {
+        match frame {
+            Frame::RowBatch(batch) => {
+                let Some(header) = header else {
+                    return Err("row batch without a header".to_owned());
+                };
+                if let Some(row) = batch
+                    .rows
+                    .iter()
+                    .find(|row| row.values.len() != header.columns.len())
+                {
+                    return Err(format!(
+                        "row has {} values but header declares {} columns",
+                        row.values.len(),
+                        header.columns.len()
+                    ));
+                }
+            }
+            Frame::Header(_) => return Err("header is not the first frame".to_owned()),
+            Frame::Summary(_) => return Err("summary before the end of the stream".to_owned()),
+            Frame::Warning(_) => {}
+        }
+    }
+    Ok((header, summary))
+}
+
+fn summary_code(summary: &proto::ResultSummary) -> &str {
+    summary.status.as_ref().map_or("", |s| s.code.as_str())
+}
+
+async fn streaming_query(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    let frames = h
+        .execute(&session_id, &h.config.query.clone(), None)
+        .await
+        .map_err(|s| format!("query failed: {s}"))?;
+    let (header, summary) = check_frames(&frames)?;
+    if header.map(proto::ResultHeader::result_type) != Some(proto::ResultType::BindingTable) {
+        return Err("query did not produce a binding table header".to_owned());
+    }
+    if status::is_exception(summary_code(summary)) {
+        return Err(format!("query failed with {}", summary_code(summary)));
+    }
+    h.close(&session_id).await
+}
+
+async fn streaming_write(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    let frames = h
+        .execute(&session_id, &h.config.write_statement.clone(), None)
+        .await
+        .map_err(|s| format!("write failed: {s}"))?;
+    let (header, summary) = check_frames(&frames)?;
+    if header.is_some_and(|h| h.result_type() == proto::ResultType::BindingTable)
+        && frames.iter().any(|f| matches!(f, Frame::RowBatch(_)))
+    {
+        return Err("write statement returned rows".to_owned());
+    }
+    if status::is_exception(summary_code(summary)) {
+        return Err(format!("write failed with {}", summary_code(summary)));
+    }
+    h.close(&session_id).await
+}
+
+// =========================================================================
+// Transactions
+// =========================================================================
+
+async fn transaction_commit(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    let begin = h.begin(&session_id).await?;
+    if begin.transaction_id.is_empty() {
+        return Err("begin returned an empty transaction id".to_owned());
+    }
+    let frames = h
+        .execute(
+            &session_id,
+            &h.config.write_statement.clone(),
+            Some(begin.transaction_id.clone()),
+        )
+        .await
+        .map_err(|s| format!("write in transaction failed: {s}"))?;
+    check_frames(&frames)?;
+    let commit = h
+        .gql
+        .commit(proto::CommitRequest {
+            session_id: session_id.clone(),
+            transaction_id: begin.transaction_id,
+        })
+        .await
+        .map_err(|s| format!("commit failed: {s}"))?
+        .into_inner();
+    if let Some(code) = commit
+        .status
+        .as_ref()
+        .map(|s| s.code.as_str())
+        .filter(|c| status::is_exception(c))
+    {
+        return Err(format!("commit failed with {code}"));
+    }
+    // The session can start another transaction once the first committed.
+    let again = h.begin(&session_id).await?;
+    if again.transaction_id.is_empty() {
+        return Err("begin after commit was rejected".to_owned());
+    }
+    h.close(&session_id).await
+}
+
+async fn transaction_rollback(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    let begin = h.begin(&session_id).await?;
+    let rollback = h
+        .gql
+        .rollback(proto::RollbackRequest {
+            session_id: session_id.clone(),
+            transaction_id: begin.transaction_id,
+        })
+        .await
+        .map_err(|s| format!("rollback failed: {s}"))?
+        .into_inner();
+    if let Some(code) = rollback
+        .status
+        .as_ref()
+        .map(|s| s.code.as_str())
+        .filter(|c| status::is_exception(c))
+    {
+        return Err(format!("rollback failed with {code}"));
+    }
+    h.close(&session_id).await
+}
+
+async fn transaction_double_begin(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    h.begin(&session_id).await?;
+    let second = h.begin(&session_id).await?;
+    let code = second.status.as_ref().map_or("", |s| s.code.as_str());
+    if !second.transaction_id.is_empty() || !status::is_exception(code) {
+        return Err("second begin on the same session was accepted".to_owned());
+    }
+    h.close(&session_id).await
+}
+
+async fn transaction_unknown_commit(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    let result = h
+        .gql
+        .commit(proto::CommitRequest {
+            session_id: session_id.clone(),
+            transaction_id: "gwp-conformance-unknown-transaction".to_owned(),
+        })
+        .await;
+    if let Ok(resp) = result {
+        let code = resp.into_inner().status.map(|s| s.code).unwrap_or_default();
+        if !status::is_exception(&code) {
+            return Err("commit of an unknown transaction succeeded".to_owned());
+        }
+    }
+    h.close(&session_id).await
+}
+
+// =========================================================================
+// Error mapping
+// =========================================================================
+
+async fn errors_invalid_statement(h: &mut Harness<'_>) -> Result<(), String> {
+    let session_id = h.open().await?;
+    let frames = h
+        .execute(&session_id, &h.config.invalid_statement.clone(), None)
+        .await
+        .map_err(|s| format!("invalid statement must fail in the summary, not with {s}"))?;
+    let (_, summary) = check_frames(&frames)?;
+    if !status::is_exception(summary_code(summary)) {
+        return Err(format!(
+            "invalid statement completed with {:?}",
+            summary_code(summary)
+        ));
+    }
+    // The session stays usable after a failed statement.
+    h.session
+        .ping(proto::PingRequest {
+            session_id: session_id.clone(),
+        })
+        .await
+        .map_err(|s| format!("session unusable after a failed statement: {s}"))?;
+    h.close(&session_id).await
+}
+
+async fn errors_unknown_session(h: &mut Harness<'_>) -> Result<(), String> {
+    match h
+        .execute(UNKNOWN_SESSION, &h.config.query.clone(), None)
+        .await
+    {
+        Err(s) if s.code() == Code::NotFound => Ok(()),
+        Err(s) => Err(format!("expected NOT_FOUND, got {s}")),
+        Ok(_) => Err("execute on an unknown session succeeded".to_owned()),
+    }
+}
+
+// =========================================================================
+// Optional features
+// =========================================================================
+
+/// Classify an optional RPC: unsupported features must answer
+/// `UNIMPLEMENTED`, and no answer may be an internal error.
+fn optional<T>(feature: &str, result: Result<T, tonic::Status>) -> CheckOutcome {
+    match result {
+        Err(s) if s.code() == Code::Unimplemented => {
+            CheckOutcome::Skipped(format!("{feature} not supported"))
+        }
+        Err(s) if matches!(s.code(), Code::Internal | Code::Unknown) => {
+            CheckOutcome::Failed(format!("{feature} answered with {s}"))
+        }
+        // Any other answer, including errors such as a missing index,
+        // shows the feature is implemented.
+        _ => CheckOutcome::Passed,
+    }
+}
+
+async fn optional_catalog(h: &Harness<'_>) -> CheckOutcome {
+    let mut client = CatalogServiceClient::new(h.channel.clone());
+    optional(
+        "catalog",
+        client.list_schemas(proto::ListSchemasRequest {}).await,
+    )
+}
+
+async fn optional_admin(h: &Harness<'_>, graph: Option<&str>) -> CheckOutcome {
+    let Some(graph) = graph else {
+        return CheckOutcome::Skipped("no graph configured".to_owned());
+    };
+    let mut client = AdminServiceClient::new(h.channel.clone());
+    optional(
+        "admin",
+        client
+            .get_graph_stats(proto::GetGraphStatsRequest {
+                graph: graph.to_owned(),
+            })
+            .await,
+    )
+}
+
+async fn optional_search(h: &Harness<'_>, graph: Option<&str>) -> CheckOutcome {
+    let Some(graph) = graph else {
+        return CheckOutcome::Skipped("no graph configured".to_owned());
+    };
+    let mut client = SearchServiceClient::new(h.channel.clone());
+    optional(
+        "search",
+        client
+            .text_search(proto::TextSearchRequest {
+                graph: graph.to_owned(),
+                query: "conformance".to_owned(),
+                k: 1,
+                ..Default::default()
+            })
+            .await,
+    )
+}
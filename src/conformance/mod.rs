@@ -0,0 +1,78 @@
+//! Reusable conformance suite for [`GqlBackend`] implementations.
+//!
+//! The suite starts a real server around the backend and drives it over
+//! the wire, checking the behaviour clients rely on: session lifecycle,
+//! streaming frame ordering, transaction semantics, error mapping, and
+//! how optional features (catalog, admin, search) are reported.
+//!
+//! ```no_run
+//! # async fn example() {
+//! use gwp::conformance::{self, ConformanceConfig};
+//! use gwp::server::mock_backend::MockBackend;
+//!
+//! let config = ConformanceConfig {
+//!     invalid_statement: "ERROR".to_owned(),
+//!     ..ConformanceConfig::default()
+//! };
+//! let report = conformance::run_with_config(MockBackend::new(), &config).await;
+//! report.assert_passed();
+//! # }
+//! ```
+
+mod checks;
+mod report;
+
+pub use report::{CheckOutcome, CheckResult, ConformanceReport};
+
+use crate::server::GqlBackend;
+use crate::testing::TestServer;
+
+/// Statements and names the suite uses against the backend.
+///
+/// The defaults are plain GQL; backends with a different dialect or
+/// without a default graph can override them.
+#[derive(Debug, Clone)]
+pub struct ConformanceConfig {
+    /// A query that returns a binding table.
+    pub query: String,
+    /// A data-modifying statement that returns no table.
+    pub write_statement: String,
+    /// A statement the backend must reject.
+    pub invalid_statement: String,
+    /// Graph for graph-scoped optional features. When `None`, the graph a
+    /// new session starts on is used; if there is none, those checks are
+    /// skipped.
+    pub graph: Option<String>,
+}
+
+impl Default for ConformanceConfig {
+    fn default() -> Self {
+        Self {
+            query: "MATCH (n) RETURN n".to_owned(),
+            write_statement: "INSERT (:Conformance {id: 1})".to_owned(),
+            invalid_statement: "THIS IS NOT GQL".to_owned(),
+            graph: None,
+        }
+    }
+}
+
+/// Run the conformance suite with the default configuration.
+pub async fn run<B: GqlBackend>(backend: B) -> ConformanceReport {
+    run_with_config(backend, &ConformanceConfig::default()).await
+}
+
+/// Run the conformance suite with the given configuration.
+///
+/// Every check runs even if earlier ones fail; inspect the returned
+/// report or call [`ConformanceReport::assert_passed`].
+pub async fn run_with_config<B: GqlBackend>(
+    backend: B,
+    config: &ConformanceConfig,
+) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    match TestServer::start(backend).await {
+        Ok(server) => checks::run_all(&server, config, &mut report).await,
+        Err(e) => report.record("server/start", Err(e.to_string())),
+    }
+    report
+}
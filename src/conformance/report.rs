@@ -0,0 +1,94 @@
+//! Conformance results.
+
+use std::fmt;
+
+/// The outcome of a single conformance check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    /// The backend behaved as required.
+    Passed,
+    /// The check did not apply, e.g. an optional feature is not supported.
+    Skipped(String),
+    /// The backend deviated from the protocol.
+    Failed(String),
+}
+
+/// A named check and its outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    /// Check name, as `category/check`.
+    pub name: &'static str,
+    /// What happened.
+    pub outcome: CheckOutcome,
+}
+
+/// Results of a conformance run.
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    /// Every check that ran, in order.
+    pub results: Vec<CheckResult>,
+}
+
+impl ConformanceReport {
+    /// Record a required check: `Err` fails it.
+    pub(super) fn record(&mut self, name: &'static str, result: Result<(), String>) {
+        let outcome = match result {
+            Ok(()) => CheckOutcome::Passed,
+            Err(reason) => CheckOutcome::Failed(reason),
+        };
+        self.push(name, outcome);
+    }
+
+    pub(super) fn push(&mut self, name: &'static str, outcome: CheckOutcome) {
+        self.results.push(CheckResult { name, outcome });
+    }
+
+    /// Whether no check failed.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// The checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.outcome, CheckOutcome::Failed(_)))
+    }
+
+    /// The outcome of the named check, if it ran.
+    #[must_use]
+    pub fn outcome(&self, name: &str) -> Option<&CheckOutcome> {
+        self.results
+            .iter()
+            .find(|r| r.name == name)
+            .map(|r| &r.outcome)
+    }
+
+    /// Assert that no check failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the full report if any check failed.
+    #[track_caller]
+    pub fn assert_passed(&self) {
+        assert!(self.is_success(), "backend failed conformance:\n{self}");
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            match &result.outcome {
+                CheckOutcome::Passed => writeln!(f, "  ok    {}", result.name)?,
+                CheckOutcome::Skipped(reason) => {
+                    writeln!(f, "  skip  {}: {reason}", result.name)?;
+                }
+                CheckOutcome::Failed(reason) => {
+                    writeln!(f, "  FAIL  {}: {reason}", result.name)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+//! Dictionary encoding of row batches.
+//!
+//! Graph results repeat the same labels, property keys, and
+//! low-cardinality string values many times per batch. Sessions that
+//! negotiate dictionary encoding at handshake receive each such string
+//! once per `RowBatch`, in its `dictionary`, with occurrences replaced by
+//! indexes into it: `Value.dictionary_ref` for string values, and
+//! `label_refs` and `property_refs` on nodes and edges. The client cursor
+//! decodes batches before handing out rows.
+//!
+//! Only strings that occur more than once in a batch are interned, so
+//! encoding never grows a batch by more than its index overhead. An
+//! element's labels are interned only if all of them are.
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::proto::value::Kind;
+
+/// Replace strings repeated within `batch` by references to its
+/// dictionary. Batches that are already encoded are left unchanged.
+pub fn encode(batch: &mut proto::RowBatch) {
+    if !batch.dictionary.is_empty() {
+        return;
+    }
+    let mut counts = HashMap::new();
+    for row in &batch.rows {
+        for value in &row.values {
+            count_value(value, &mut counts);
+        }
+    }
+    let mut dictionary: Vec<&str> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(string, _)| string)
+        .collect();
+    if dictionary.is_empty() {
+        return;
+    }
+    dictionary.sort_unstable();
+    let dictionary: Vec<String> = dictionary.into_iter().map(str::to_owned).collect();
+    let index: HashMap<String, u32> = dictionary
+        .iter()
+        .zip(0..)
+        .map(|(string, i)| (string.clone(), i))
+        .collect();
+    for row in &mut batch.rows {
+        for value in &mut row.values {
+            encode_value(value, &index);
+        }
+    }
+    batch.dictionary = dictionary;
+}
+
+/// Resolve the dictionary references in `batch` and drop its dictionary.
+///
+/// Returns `false` if a reference is out of range, leaving the batch
+/// partially decoded.
+#[must_use]
+pub fn decode(batch: &mut proto::RowBatch) -> bool {
+    if batch.dictionary.is_empty() {
+        return true;
+    }
+    let dictionary = std::mem::take(&mut batch.dictionary);
+    batch
+        .rows
+        .iter_mut()
+        .flat_map(|row| row.values.iter_mut())
+        .all(|value| decode_value(value, &dictionary))
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+fn count_value<'a>(value: &'a proto::Value, counts: &mut HashMap<&'a str, usize>) {
+    match &value.kind {
+        Some(Kind::StringValue(s)) => *counts.entry(s).or_default() += 1,
+        Some(Kind::ListValue(list)) => {
+            for element in &list.elements {
+                count_value(element, counts);
+            }
+        }
+        Some(Kind::RecordValue(record)) => {
+            for value in record.fields.iter().filter_map(|f| f.value.as_ref()) {
+                count_value(value, counts);
+            }
+        }
+        Some(Kind::NodeValue(node)) => count_element(&node.labels, &node.properties, counts),
+        Some(Kind::EdgeValue(edge)) => count_element(&edge.labels, &edge.properties, counts),
+        Some(Kind::PathValue(path)) => {
+            for node in &path.nodes {
+                count_element(&node.labels, &node.properties, counts);
+            }
+            for edge in &path.edges {
+                count_element(&edge.labels, &edge.properties, counts);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn count_element<'a>(
+    labels: &'a [String],
+    properties: &'a HashMap<String, proto::Value>,
+    counts: &mut HashMap<&'a str, usize>,
+) {
+    for label in labels {
+        *counts.entry(label).or_default() += 1;
+    }
+    for (key, value) in properties {
+        *counts.entry(key).or_default() += 1;
+        count_value(value, counts);
+    }
+}
+
+fn encode_value(value: &mut proto::Value, index: &HashMap<String, u32>) {
+    match &mut value.kind {
+        Some(Kind::StringValue(s)) => {
+            if let Some(&i) = index.get(s.as_str()) {
+                value.kind = Some(Kind::DictionaryRef(i));
+            }
+        }
+        Some(Kind::ListValue(list)) => {
+            for element in &mut list.elements {
+                encode_value(element, index);
+            }
+        }
+        Some(Kind::RecordValue(record)) => {
+            for value in record.fields.iter_mut().filter_map(|f| f.value.as_mut()) {
+                encode_value(value, index);
+            }
+        }
+        Some(Kind::NodeValue(node)) => encode_node(node, index),
+        Some(Kind::EdgeValue(edge)) => encode_edge(edge, index),
+        Some(Kind::PathValue(path)) => {
+            for node in &mut path.nodes {
+                encode_node(node, index);
+            }
+            for edge in &mut path.edges {
+                encode_edge(edge, index);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn encode_node(node: &mut proto::Node, index: &HashMap<String, u32>) {
+    encode_element(
+        &mut node.labels,
+        &mut node.label_refs,
+        &mut node.properties,
+        &mut node.property_refs,
+        index,
+    );
+}
+
+fn encode_edge(edge: &mut proto::Edge, index: &HashMap<String, u32>) {
+    encode_element(
+        &mut edge.labels,
+        &mut edge.label_refs,
+        &mut edge.properties,
+        &mut edge.property_refs,
+        index,
+    );
+}
+
+fn encode_element(
+    labels: &mut Vec<String>,
+    label_refs: &mut Vec<u32>,
+    properties: &mut HashMap<String, proto::Value>,
+    property_refs: &mut HashMap<u32, proto::Value>,
+    index: &HashMap<String, u32>,
+) {
+    // Labels are interned all or none, so decoding keeps their order.
+    let refs: Option<Vec<u32>> = labels
+        .iter()
+        .map(|label| index.get(label).copied())
+        .collect();
+    if let Some(refs) = refs {
+        labels.clear();
+        *label_refs = refs;
+    }
+    for (key, mut value) in std::mem::take(properties) {
+        encode_value(&mut value, index);
+        match index.get(&key) {
+            Some(&i) => {
+                property_refs.insert(i, value);
+            }
+            None => {
+                properties.insert(key, value);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+fn decode_value(value: &mut proto::Value, dictionary: &[String]) -> bool {
+    match &mut value.kind {
+        Some(Kind::DictionaryRef(i)) => match lookup(dictionary, *i) {
+            Some(s) => {
+                value.kind = Some(Kind::StringValue(s));
+                true
+            }
+            None => false,
+        },
+        Some(Kind::ListValue(list)) => list
+            .elements
+            .iter_mut()
+            .all(|element| decode_value(element, dictionary)),
+        Some(Kind::RecordValue(record)) => record
+            .fields
+            .iter_mut()
+            .filter_map(|f| f.value.as_mut())
+            .all(|value| decode_value(value, dictionary)),
+        Some(Kind::NodeValue(node)) => decode_node(node, dictionary),
+        Some(Kind::EdgeValue(edge)) => decode_edge(edge, dictionary),
+        Some(Kind::PathValue(path)) => {
+            path.nodes.iter_mut().all(|n| decode_node(n, dictionary))
+                && path.edges.iter_mut().all(|e| decode_edge(e, dictionary))
+        }
+        _ => true,
+    }
+}
+
+fn decode_node(node: &mut proto::Node, dictionary: &[String]) -> bool {
+    decode_element(
+        &mut node.labels,
+        &mut node.label_refs,
+        &mut node.properties,
+        &mut node.property_refs,
+        dictionary,
+    )
+}
+
+fn decode_edge(edge: &mut proto::Edge, dictionary: &[String]) -> bool {
+    decode_element(
+        &mut edge.labels,
+        &mut edge.label_refs,
+        &mut edge.properties,
+        &mut edge.property_refs,
+        dictionary,
+    )
+}
+
+fn decode_element(
+    labels: &mut Vec<String>,
+    label_refs: &mut Vec<u32>,
+    properties: &mut HashMap<String, proto::Value>,
+    property_refs: &mut HashMap<u32, proto::Value>,
+    dictionary: &[String],
+) -> bool {
+    for i in std::mem::take(label_refs) {
+        match lookup(dictionary, i) {
+            Some(label) => labels.push(label),
+            None => return false,
+        }
+    }
+    for (i, value) in std::mem::take(property_refs) {
+        match lookup(dictionary, i) {
+            Some(key) => {
+                properties.insert(key, value);
+            }
+            None => return false,
+        }
+    }
+    properties
+        .values_mut()
+        .all(|value| decode_value(value, dictionary))
+}
+
+fn lookup(dictionary: &[String], i: u32) -> Option<String> {
+    dictionary.get(usize::try_from(i).ok()?).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Node, Value};
+
+    fn batch(rows: Vec<Vec<Value>>) -> proto::RowBatch {
+        proto::RowBatch {
+            rows: rows
+                .into_iter()
+                .map(|values| proto::Row {
+                    values: values.into_iter().map(proto::Value::from).collect(),
+                })
+                .collect(),
+            checksum: None,
+            dictionary: Vec::new(),
+            columns: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_and_interns_repeated_strings() {
+        let person = |id: u8, name: &str| {
+            Value::Node(
+                Node::new(vec![id])
+                    .with_label("Person")
+                    .with_property("name", Value::from(name))
+                    .with_property("city", Value::from("Utrecht")),
+            )
+        };
+        let rows = vec![
+            vec![person(1, "Alice"), Value::from("Utrecht")],
+            vec![person(2, "Bob"), Value::from("unique")],
+        ];
+        let original = batch(rows.clone());
+        let mut encoded = original.clone();
+        encode(&mut encoded);
+
+        assert_eq!(encoded.dictionary, ["Person", "Utrecht", "city", "name"]);
+        assert_eq!(encoded.rows[0].values[1].kind, Some(Kind::DictionaryRef(1)));
+        assert_eq!(
+            encoded.rows[1].values[1].kind,
+            Some(Kind::StringValue("unique".to_owned()))
+        );
+
+        assert!(decode(&mut encoded));
+        let decoded: Vec<Vec<Value>> = encoded
+            .rows
+            .into_iter()
+            .map(|row| row.values.into_iter().map(Value::from).collect())
+            .collect();
+        assert_eq!(decoded, rows);
+    }
+
+    #[test]
+    fn rejects_out_of_range_references() {
+        let mut encoded = batch(vec![vec![Value::from("a")], vec![Value::from("a")]]);
+        encode(&mut encoded);
+        encoded.rows[1].values[0].kind = Some(Kind::DictionaryRef(7));
+        assert!(!decode(&mut encoded));
+    }
+}
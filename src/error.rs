@@ -46,6 +46,10 @@ pub enum GqlError {
     /// gRPC status error from tonic.
     #[error("gRPC error: {0}")]
     Grpc(#[from] tonic::Status),
+
+    /// Local I/O error, such as when spilling results to disk.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 impl GqlError {
@@ -79,6 +83,7 @@ impl GqlError {
             }
             Self::Transport(err) => tonic::Status::unavailable(err.to_string()),
             Self::Grpc(status) => status.clone(),
+            Self::Io(err) => tonic::Status::internal(err.to_string()),
         }
     }
 
@@ -0,0 +1,215 @@
+//! JSON encoding of GQL values and result frames.
+//!
+//! Scalars map onto their JSON counterparts. Values JSON has no type for
+//! (bytes, temporals, decimals, big numbers, non-finite floats) are encoded
+//! as strings in their display form. Graph elements become objects with
+//! hex-encoded ids.
+
+use std::fmt::Write;
+
+use serde_json::{Map, Number, json};
+
+use crate::proto;
+use crate::proto::execute_response::Frame;
+use crate::types::{Edge, Field, Node, Record, Value};
+
+/// Encode a GQL value as JSON.
+#[must_use]
+pub fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Boolean(b) => serde_json::Value::Bool(*b),
+        Value::Integer(i) => json!(i),
+        Value::UnsignedInteger(u) => json!(u),
+        Value::Float(f) => {
+            Number::from_f64(*f).map_or_else(|| json!(f.to_string()), serde_json::Value::Number)
+        }
+        Value::String(s) => json!(s),
+        Value::List(items) => items.iter().map(value_to_json).collect(),
+        Value::Record(record) => serde_json::Value::Object(
+            record
+                .fields
+                .iter()
+                .map(|f| (f.name.clone(), value_to_json(&f.value)))
+                .collect(),
+        ),
+        Value::Node(node) => node_to_json(node),
+        Value::Edge(edge) => edge_to_json(edge),
+        Value::Path(path) => json!({
+            "nodes": path.nodes.iter().map(node_to_json).collect::<Vec<_>>(),
+            "edges": path.edges.iter().map(edge_to_json).collect::<Vec<_>>(),
+        }),
+        other => json!(other.to_string()),
+    }
+}
+
+/// Decode a JSON parameter value.
+///
+/// Integers become `Integer` (or `UnsignedInteger` above `i64::MAX`),
+/// other numbers become `Float`, arrays become lists, and objects become
+/// records.
+#[must_use]
+pub fn value_from_json(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Boolean(b),
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| n.as_u64().map(Value::UnsignedInteger))
+            .unwrap_or_else(|| Value::Float(n.as_f64().unwrap_or(f64::NAN))),
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(items) => {
+            Value::List(items.into_iter().map(value_from_json).collect())
+        }
+        serde_json::Value::Object(fields) => Value::Record(Record {
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| Field {
+                    name,
+                    value: value_from_json(value),
+                })
+                .collect(),
+        }),
+    }
+}
+
+/// Encode one execute response frame as a JSON object with a single
+/// `header`, `rows`, `warning`, or `summary` key.
+#[must_use]
+pub fn frame_to_json(frame: &proto::ExecuteResponse) -> serde_json::Value {
+    match &frame.frame {
+        Some(Frame::Header(header)) => json!({
+            "header": {
+                "columns": header.columns.iter().map(|c| &c.name).collect::<Vec<_>>(),
+                "ordered": header.ordered,
+            }
+        }),
+        Some(Frame::RowBatch(batch)) => json!({
+            "rows": batch
+                .rows
+                .iter()
+                .map(|row| {
+                    row.values
+                        .iter()
+                        .map(|v| value_to_json(&Value::from(v.clone())))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+        }),
+        Some(Frame::Summary(summary)) => {
+            let status = summary.status.clone().unwrap_or_default();
+            json!({
+                "summary": {
+                    "code": status.code,
+                    "message": status.message,
+                    "rows_affected": summary.rows_affected,
+                    "counters": summary.counters,
+                    "update_counters": summary.update_counters.map(|c| json!({
+                        "nodes_created": c.nodes_created,
+                        "nodes_deleted": c.nodes_deleted,
+                        "edges_created": c.edges_created,
+                        "edges_deleted": c.edges_deleted,
+                        "properties_set": c.properties_set,
+                        "labels_added": c.labels_added,
+                    })),
+                    "timings": summary.timings.map(|t| json!({
+                        "planning_micros": t.planning_micros,
+                        "execution_micros": t.execution_micros,
+                        "streaming_micros": t.streaming_micros,
+                    })),
+                    "warnings": summary
+                        .warnings
+                        .iter()
+                        .map(|w| json!({ "code": w.code, "message": w.message }))
+                        .collect::<Vec<_>>(),
+                }
+            })
+        }
+        Some(Frame::Warning(warning)) => json!({
+            "warning": { "code": warning.code, "message": warning.message }
+        }),
+        None => serde_json::Value::Object(Map::new()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+fn properties_to_json<'a>(
+    properties: impl IntoIterator<Item = (&'a String, &'a Value)>,
+) -> serde_json::Value {
+    serde_json::Value::Object(
+        properties
+            .into_iter()
+            .map(|(k, v)| (k.clone(), value_to_json(v)))
+            .collect(),
+    )
+}
+
+fn node_to_json(node: &Node) -> serde_json::Value {
+    json!({
+        "id": hex(&node.id),
+        "labels": node.labels,
+        "properties": properties_to_json(&node.properties),
+    })
+}
+
+fn edge_to_json(edge: &Edge) -> serde_json::Value {
+    json!({
+        "id": hex(&edge.id),
+        "labels": edge.labels,
+        "source": hex(&edge.source_node_id),
+        "target": hex(&edge.target_node_id),
+        "undirected": edge.undirected,
+        "properties": properties_to_json(&edge.properties),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn scalars_round_trip() {
+        for value in [
+            Value::Null,
+            Value::Boolean(true),
+            Value::Integer(-7),
+            Value::UnsignedInteger(u64::MAX),
+            Value::Float(1.5),
+            Value::from("hello"),
+            Value::List(vec![Value::Integer(1), Value::from("two")]),
+        ] {
+            assert_eq!(value_from_json(value_to_json(&value)), value);
+        }
+    }
+
+    #[test]
+    fn non_json_values_encode_as_strings() {
+        assert_eq!(
+            value_to_json(&Value::Bytes(vec![0xab, 0x01])),
+            json!("0xab01")
+        );
+        assert_eq!(value_to_json(&Value::Float(f64::INFINITY)), json!("inf"));
+    }
+
+    #[test]
+    fn nodes_encode_as_objects() {
+        let node = Node {
+            id: vec![1, 2],
+            labels: vec!["Person".to_owned()],
+            properties: HashMap::from([("name".to_owned(), Value::from("Alice"))]),
+        };
+        assert_eq!(
+            value_to_json(&Value::Node(node)),
+            json!({"id": "0102", "labels": ["Person"], "properties": {"name": "Alice"}})
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! HTTP/JSON gateway onto the gRPC services.
+//!
+//! For environments where gRPC is impractical (curl, serverless runtimes,
+//! legacy stacks), [`Gateway`] exposes sessions, statement execution, and
+//! catalog operations as a small REST API. Every request is forwarded to
+//! a GWP server over gRPC.
+//!
+//! | Method   | Path                                        | gRPC call          |
+//! |----------|---------------------------------------------|--------------------|
+//! | `POST`   | `/sessions`                                 | `Handshake`        |
+//! | `DELETE` | `/sessions/{session_id}`                    | `Close`            |
+//! | `POST`   | `/execute`                                  | `Execute` (NDJSON) |
+//! | `POST`   | `/transactions`                             | `BeginTransaction` |
+//! | `POST`   | `/transactions/{transaction_id}/commit`     | `Commit`           |
+//! | `POST`   | `/transactions/{transaction_id}/rollback`   | `Rollback`         |
+//! | `GET`    | `/schemas`                                  | `ListSchemas`      |
+//! | `POST`   | `/schemas`                                  | `CreateSchema`     |
+//! | `DELETE` | `/schemas/{schema}`                         | `DropSchema`       |
+//! | `GET`    | `/schemas/{schema}/graphs`                  | `ListGraphs`       |
+//! | `GET`    | `/schemas/{schema}/graphs/{graph}`          | `GetGraphInfo`     |
+//! | `DELETE` | `/schemas/{schema}/graphs/{graph}`          | `DropGraph`        |
+//!
+//! `POST /execute` responds with newline-delimited JSON: one object per
+//! result frame, keyed `header`, `rows`, or `summary`. Errors use the HTTP
+//! status closest to the gRPC code and a body of the form
+//! `{"error": {"code": "NotFound", "message": "..."}}`.
+//!
+//! Requires the `gateway` feature.
+//!
+//! ```no_run
+//! use gwp::client::GqlConnection;
+//! use gwp::gateway::Gateway;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let conn = GqlConnection::connect("http://localhost:50051").await?;
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+//! Gateway::new(&conn).serve(listener).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod json;
+mod routes;
+
+use axum::Router;
+use axum::routing::{delete, get, post};
+use tonic::transport::Channel;
+
+use crate::client::GqlConnection;
+
+pub use json::{frame_to_json, value_from_json, value_to_json};
+
+/// REST gateway forwarding HTTP/JSON requests to a GWP server.
+#[derive(Debug, Clone)]
+pub struct Gateway {
+    channel: Channel,
+}
+
+impl Gateway {
+    /// Create a gateway that forwards over an existing connection.
+    #[must_use]
+    pub fn new(connection: &GqlConnection) -> Self {
+        Self::from_channel(connection.channel().clone())
+    }
+
+    /// Create a gateway from a tonic channel.
+    #[must_use]
+    pub fn from_channel(channel: Channel) -> Self {
+        Self { channel }
+    }
+
+    /// Build the axum router, for mounting inside a larger application.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/sessions", post(routes::create_session))
+            .route("/sessions/{session_id}", delete(routes::close_session))
+            .route("/execute", post(routes::execute))
+            .route("/transactions", post(routes::begin))
+            .route(
+                "/transactions/{transaction_id}/commit",
+                post(routes::commit),
+            )
+            .route(
+                "/transactions/{transaction_id}/rollback",
+                post(routes::rollback),
+            )
+            .route(
+                "/schemas",
+                get(routes::list_schemas).post(routes::create_schema),
+            )
+            .route("/schemas/{schema}", delete(routes::drop_schema))
+            .route("/schemas/{schema}/graphs", get(routes::list_graphs))
+            .route(
+                "/schemas/{schema}/graphs/{graph}",
+                get(routes::get_graph).delete(routes::drop_graph),
+            )
+            .with_state(self.channel)
+    }
+
+    /// Serve the gateway on `listener` until the process exits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if accepting connections fails.
+    pub async fn serve(self, listener: tokio::net::TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.router()).await
+    }
+}
@@ -0,0 +1,385 @@
+//! HTTP handlers that translate JSON requests into gRPC calls.
+
+use std::collections::HashMap;
+
+use axum::Json;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::json;
+use tokio_stream::StreamExt;
+use tonic::transport::Channel;
+
+use super::json::{frame_to_json, value_from_json};
+use crate::proto;
+use crate::proto::catalog_service_client::CatalogServiceClient;
+use crate::proto::gql_service_client::GqlServiceClient;
+use crate::proto::session_service_client::SessionServiceClient;
+
+/// A gRPC status rendered as an HTTP error with a JSON body.
+pub(super) struct ApiError(tonic::Status);
+
+impl From<tonic::Status> for ApiError {
+    fn from(status: tonic::Status) -> Self {
+        Self(status)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (http_status(self.0.code()), Json(status_json(&self.0))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+fn http_status(code: tonic::Code) -> StatusCode {
+    match code {
+        tonic::Code::Ok => StatusCode::OK,
+        tonic::Code::InvalidArgument | tonic::Code::OutOfRange => StatusCode::BAD_REQUEST,
+        tonic::Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        tonic::Code::PermissionDenied => StatusCode::FORBIDDEN,
+        tonic::Code::NotFound => StatusCode::NOT_FOUND,
+        tonic::Code::AlreadyExists | tonic::Code::Aborted | tonic::Code::FailedPrecondition => {
+            StatusCode::CONFLICT
+        }
+        tonic::Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        tonic::Code::Cancelled => StatusCode::REQUEST_TIMEOUT,
+        tonic::Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        tonic::Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        tonic::Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        tonic::Code::Unknown | tonic::Code::Internal | tonic::Code::DataLoss => {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+fn status_json(status: &tonic::Status) -> serde_json::Value {
+    json!({
+        "error": {
+            "code": format!("{:?}", status.code()),
+            "message": status.message(),
+        }
+    })
+}
+
+// ============================================================================
+// Sessions
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct CreateSession {
+    #[serde(default)]
+    client_info: HashMap<String, String>,
+    username: Option<String>,
+    password: Option<String>,
+    database: Option<String>,
+}
+
+/// `POST /sessions`: handshake. Credentials come from an
+/// `Authorization: Bearer` header or `username`/`password` in the body,
+/// and an optional `database` routes the session's statements.
+pub(super) async fn create_session(
+    State(channel): State<Channel>,
+    headers: HeaderMap,
+    body: Option<Json<CreateSession>>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let Json(body) = body.unwrap_or_default();
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let method = match (bearer, body.username) {
+        (Some(token), _) => Some(proto::auth_credentials::Method::BearerToken(
+            token.to_owned(),
+        )),
+        (None, Some(username)) => Some(proto::auth_credentials::Method::Basic(proto::BasicAuth {
+            username,
+            password: body.password.unwrap_or_default(),
+        })),
+        (None, None) => None,
+    };
+
+    let response = SessionServiceClient::new(channel)
+        .handshake(proto::HandshakeRequest {
+            protocol_version: 1,
+            credentials: method.map(|method| proto::AuthCredentials {
+                method: Some(method),
+            }),
+            client_info: body.client_info,
+            resumption_token: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
+            database: body.database,
+        })
+        .await?
+        .into_inner();
+
+    Ok(Json(json!({
+        "session_id": response.session_id,
+        "default_schema": response.default_schema,
+        "default_graph": response.default_graph,
+    })))
+}
+
+/// `DELETE /sessions/{session_id}`: close a session.
+pub(super) async fn close_session(
+    State(channel): State<Channel>,
+    Path(session_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    SessionServiceClient::new(channel)
+        .close(proto::CloseRequest { session_id })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Execute {
+    session_id: String,
+    statement: String,
+    #[serde(default)]
+    parameters: HashMap<String, serde_json::Value>,
+    transaction_id: Option<String>,
+    fetch_size: Option<u32>,
+    timeout_ms: Option<u32>,
+}
+
+/// `POST /execute`: run a statement and stream its frames as NDJSON.
+///
+/// Each line is a JSON object with a `header`, `rows`, or `summary` key.
+/// A stream that fails midway ends with an `error` line.
+pub(super) async fn execute(
+    State(channel): State<Channel>,
+    Json(body): Json<Execute>,
+) -> ApiResult<Response> {
+    let frames = GqlServiceClient::new(channel)
+        .execute(proto::ExecuteRequest {
+            session_id: body.session_id,
+            statement: body.statement,
+            parameters: body
+                .parameters
+                .into_iter()
+                .map(|(name, value)| (name, value_from_json(value).into()))
+                .collect(),
+            transaction_id: body.transaction_id,
+            fetch_size: body.fetch_size,
+            timeout_ms: body.timeout_ms,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        })
+        .await?
+        .into_inner();
+
+    let lines = frames.map(|frame| {
+        let json = match frame {
+            Ok(frame) => frame_to_json(&frame),
+            Err(status) => status_json(&status),
+        };
+        let mut line = json.to_string();
+        line.push('\n');
+        Ok::<_, std::convert::Infallible>(Bytes::from(line))
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Begin {
+    session_id: String,
+    #[serde(default)]
+    read_only: bool,
+}
+
+/// `POST /transactions`: begin a transaction.
+pub(super) async fn begin(
+    State(channel): State<Channel>,
+    Json(body): Json<Begin>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let mode = if body.read_only {
+        proto::TransactionMode::ReadOnly
+    } else {
+        proto::TransactionMode::ReadWrite
+    };
+    let response = GqlServiceClient::new(channel)
+        .begin_transaction(proto::BeginRequest {
+            session_id: body.session_id,
+            mode: mode.into(),
+            isolation_level: proto::IsolationLevel::Default.into(),
+            timeout_ms: None,
+        })
+        .await?
+        .into_inner();
+    Ok(Json(json!({ "transaction_id": response.transaction_id })))
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Finish {
+    session_id: String,
+}
+
+/// `POST /transactions/{transaction_id}/commit`.
+pub(super) async fn commit(
+    State(channel): State<Channel>,
+    Path(transaction_id): Path<String>,
+    Json(body): Json<Finish>,
+) -> ApiResult<StatusCode> {
+    GqlServiceClient::new(channel)
+        .commit(proto::CommitRequest {
+            session_id: body.session_id,
+            transaction_id,
+        })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /transactions/{transaction_id}/rollback`.
+pub(super) async fn rollback(
+    State(channel): State<Channel>,
+    Path(transaction_id): Path<String>,
+    Json(body): Json<Finish>,
+) -> ApiResult<StatusCode> {
+    GqlServiceClient::new(channel)
+        .rollback(proto::RollbackRequest {
+            session_id: body.session_id,
+            transaction_id,
+        })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Catalog
+// ============================================================================
+
+/// `GET /schemas`.
+pub(super) async fn list_schemas(
+    State(channel): State<Channel>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let schemas = CatalogServiceClient::new(channel)
+        .list_schemas(proto::ListSchemasRequest {})
+        .await?
+        .into_inner()
+        .schemas;
+    Ok(Json(json!({
+        "schemas": schemas
+            .iter()
+            .map(|s| json!({
+                "name": s.name,
+                "graph_count": s.graph_count,
+                "graph_type_count": s.graph_type_count,
+            }))
+            .collect::<Vec<_>>(),
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct CreateSchema {
+    name: String,
+    #[serde(default)]
+    if_not_exists: bool,
+}
+
+/// `POST /schemas`.
+pub(super) async fn create_schema(
+    State(channel): State<Channel>,
+    Json(body): Json<CreateSchema>,
+) -> ApiResult<StatusCode> {
+    CatalogServiceClient::new(channel)
+        .create_schema(proto::CreateSchemaRequest {
+            name: body.name,
+            if_not_exists: body.if_not_exists,
+        })
+        .await?;
+    Ok(StatusCode::CREATED)
+}
+
+/// `DELETE /schemas/{schema}`.
+pub(super) async fn drop_schema(
+    State(channel): State<Channel>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let existed = CatalogServiceClient::new(channel)
+        .drop_schema(proto::DropSchemaRequest {
+            name,
+            if_exists: true,
+        })
+        .await?
+        .into_inner()
+        .existed;
+    Ok(Json(json!({ "existed": existed })))
+}
+
+/// `GET /schemas/{schema}/graphs`.
+pub(super) async fn list_graphs(
+    State(channel): State<Channel>,
+    Path(schema): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let graphs = CatalogServiceClient::new(channel)
+        .list_graphs(proto::ListGraphsRequest { schema })
+        .await?
+        .into_inner()
+        .graphs;
+    Ok(Json(json!({
+        "graphs": graphs
+            .iter()
+            .map(|g| json!({
+                "schema": g.schema,
+                "name": g.name,
+                "node_count": g.node_count,
+                "edge_count": g.edge_count,
+                "graph_type": g.graph_type,
+            }))
+            .collect::<Vec<_>>(),
+    })))
+}
+
+/// `GET /schemas/{schema}/graphs/{graph}`.
+pub(super) async fn get_graph(
+    State(channel): State<Channel>,
+    Path((schema, name)): Path<(String, String)>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let info = CatalogServiceClient::new(channel)
+        .get_graph_info(proto::GetGraphInfoRequest { schema, name })
+        .await?
+        .into_inner();
+    Ok(Json(json!({
+        "schema": info.schema,
+        "name": info.name,
+        "node_count": info.node_count,
+        "edge_count": info.edge_count,
+        "graph_type": info.graph_type,
+        "storage_mode": info.storage_mode,
+        "memory_limit_bytes": info.memory_limit_bytes,
+        "backward_edges": info.backward_edges,
+        "threads": info.threads,
+    })))
+}
+
+/// `DELETE /schemas/{schema}/graphs/{graph}`.
+pub(super) async fn drop_graph(
+    State(channel): State<Channel>,
+    Path((schema, name)): Path<(String, String)>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let existed = CatalogServiceClient::new(channel)
+        .drop_graph(proto::DropGraphRequest {
+            schema,
+            name,
+            if_exists: true,
+        })
+        .await?
+        .into_inner()
+        .existed;
+    Ok(Json(json!({ "existed": existed })))
+}
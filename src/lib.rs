@@ -7,9 +7,17 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, clippy::all, clippy::pedantic)]
 
+pub mod checksum;
 pub mod client;
+pub mod columnar;
+pub mod conformance;
+pub mod dictionary;
 pub mod error;
+#[cfg(feature = "gateway")]
+pub mod gateway;
 pub mod proto;
 pub mod server;
 pub mod status;
+pub mod testing;
+pub mod trace_context;
 pub mod types;
@@ -12,3 +12,32 @@
 )]
 
 tonic::include_proto!("gql");
+
+/// Request metadata key carrying a client-assigned query id, so client
+/// and server traces of the same statement can be correlated.
+pub const QUERY_ID_METADATA: &str = "gwp-query-id";
+
+/// Request metadata key naming the session a `CatalogService`,
+/// `AdminService`, or `SearchService` call is made for, so the server can
+/// authorize it as that session's principal.
+pub const SESSION_ID_METADATA: &str = "gwp-session-id";
+
+/// Request metadata key carrying the caller's W3C trace context, so
+/// distributed traces connect the client, server, and backend.
+pub const TRACEPARENT_METADATA: &str = "traceparent";
+
+impl Compression {
+    /// The transport encoding for this compression, or `None` for
+    /// [`Compression::None`] and for encodings this build lacks: gzip
+    /// needs the `gzip` feature and zstd the `zstd` feature.
+    #[must_use]
+    pub fn encoding(self) -> Option<tonic::codec::CompressionEncoding> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Some(tonic::codec::CompressionEncoding::Gzip),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Some(tonic::codec::CompressionEncoding::Zstd),
+            _ => None,
+        }
+    }
+}
@@ -5,22 +5,106 @@
 
 use std::sync::Arc;
 
-use tonic::{Request, Response, Status};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
 
 use crate::proto;
 use crate::proto::admin_service_server::AdminService;
 
-use super::backend::{GqlBackend, IndexDefinition};
+use super::auth::{Authorization, Authorizer, Operation, authorize};
+use super::backend::{GqlBackend, GraphConfig, IndexDefinition, IndexState, LabelCount};
+use super::backup::{BackupChunks, RestoreChunks};
+use super::import::ImportElements;
+use super::interchange::{ExportChunks, GraphFormat};
+use super::jobs::{IndexBuildStatus, Job, JobManager};
+use super::{QueryStats, SessionHandle, SessionManager, TransactionHandle, TransactionManager};
+
+/// The `ServerInfo.features` entry a server lists when it serves the
+/// `AdminService`.
+pub const ADMIN_FEATURE: &str = "admin";
+
+/// Progress updates queued ahead of a slow client.
+const PROGRESS_BUFFER: usize = 4;
 
 /// Implementation of the `AdminService` gRPC service.
 pub struct AdminServiceImpl<B: GqlBackend> {
     backend: Arc<B>,
+    query_stats: Option<QueryStats>,
+    sessions: Option<(SessionManager, TransactionManager)>,
+    jobs: JobManager,
+    authorization: Option<Authorization>,
 }
 
 impl<B: GqlBackend> AdminServiceImpl<B> {
     /// Create a new admin service.
     pub fn new(backend: Arc<B>) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            query_stats: None,
+            sessions: None,
+            jobs: JobManager::new(),
+            authorization: None,
+        }
+    }
+
+    /// Serve statement statistics from `stats`. Without this, the query
+    /// statistics RPCs return `UNIMPLEMENTED`.
+    #[must_use]
+    pub fn with_query_stats(mut self, stats: QueryStats) -> Self {
+        self.query_stats = Some(stats);
+        self
+    }
+
+    /// List and kill the sessions in `sessions`, rolling back their
+    /// transactions in `transactions`. Without this, the session RPCs
+    /// return `UNIMPLEMENTED`.
+    #[must_use]
+    pub fn with_sessions(
+        mut self,
+        sessions: SessionManager,
+        transactions: TransactionManager,
+    ) -> Self {
+        self.sessions = Some((sessions, transactions));
+        self
+    }
+
+    /// Run background jobs on `jobs`, so they can be followed from outside
+    /// the service or shared with another one.
+    #[must_use]
+    pub fn with_jobs(mut self, jobs: JobManager) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Require `authorizer` to allow every call as an admin operation.
+    /// Calls are attributed to the session their request metadata names.
+    #[must_use]
+    pub fn with_authorizer(
+        mut self,
+        authorizer: Arc<dyn Authorizer>,
+        sessions: SessionManager,
+    ) -> Self {
+        self.authorization = Some(Authorization::new(authorizer, sessions));
+        self
+    }
+
+    fn query_stats(&self) -> Result<&QueryStats, Status> {
+        self.query_stats
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("query statistics not enabled"))
+    }
+
+    fn sessions(&self) -> Result<&(SessionManager, TransactionManager), Status> {
+        self.sessions
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("session administration not enabled"))
+    }
+
+    fn submit(&self, job: Job) -> Result<String, Status> {
+        self.jobs
+            .submit(Arc::clone(&self.backend), job)
+            .map_err(|e| Status::internal(e.to_string()))
     }
 }
 
@@ -31,6 +115,8 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
         &self,
         request: Request<proto::GetGraphStatsRequest>,
     ) -> Result<Response<proto::GetGraphStatsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("graph", &req.graph);
 
@@ -53,14 +139,197 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
             index_count: stats.index_count,
             memory_bytes: stats.memory_bytes,
             disk_bytes: stats.disk_bytes,
+            memory_breakdown: stats.memory_breakdown.map(proto::MemoryBreakdown::from),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn get_label_stats(
+        &self,
+        request: Request<proto::GetLabelStatsRequest>,
+    ) -> Result<Response<proto::GetLabelStatsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("graph", &req.graph);
+
+        if req.graph.is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+
+        let stats = self
+            .backend
+            .get_label_stats(&req.graph)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+
+        let to_proto_count = |c: LabelCount| proto::LabelCount {
+            label: c.label,
+            count: c.count,
+        };
+
+        Ok(Response::new(proto::GetLabelStatsResponse {
+            node_labels: stats.node_labels.into_iter().map(to_proto_count).collect(),
+            edge_types: stats.edge_types.into_iter().map(to_proto_count).collect(),
+            properties: stats
+                .properties
+                .into_iter()
+                .map(|p| proto::PropertyCardinality {
+                    label: p.label,
+                    property: p.property,
+                    non_null_count: p.non_null_count,
+                    distinct_estimate: p.distinct_estimate,
+                })
+                .collect(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn get_graph_config(
+        &self,
+        request: Request<proto::GetGraphConfigRequest>,
+    ) -> Result<Response<proto::GetGraphConfigResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("graph", &req.graph);
+
+        if req.graph.is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+
+        let config = self
+            .backend
+            .get_graph_config(&req.graph)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+
+        Ok(Response::new(proto::GetGraphConfigResponse {
+            config: Some(config.into()),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn set_graph_config(
+        &self,
+        request: Request<proto::SetGraphConfigRequest>,
+    ) -> Result<Response<proto::SetGraphConfigResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("graph", &req.graph);
+
+        if req.graph.is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+
+        let changes = req.config.map(GraphConfig::from).unwrap_or_default();
+
+        let config = self
+            .backend
+            .set_graph_config(&req.graph, changes)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+
+        tracing::info!(graph = %req.graph, "graph configuration updated");
+
+        Ok(Response::new(proto::SetGraphConfigResponse {
+            config: Some(config.into()),
         }))
     }
 
+    #[tracing::instrument(skip(self, request))]
+    async fn get_query_stats(
+        &self,
+        request: Request<proto::GetQueryStatsRequest>,
+    ) -> Result<Response<proto::GetQueryStatsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        let mut statements = self.query_stats()?.snapshot();
+        if req.limit > 0 {
+            statements.truncate(usize::try_from(req.limit).unwrap_or(usize::MAX));
+        }
+
+        Ok(Response::new(proto::GetQueryStatsResponse {
+            statements: statements
+                .into_iter()
+                .map(proto::QueryStatistics::from)
+                .collect(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    async fn reset_query_stats(
+        &self,
+        request: Request<proto::ResetQueryStatsRequest>,
+    ) -> Result<Response<proto::ResetQueryStatsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        self.query_stats()?.reset();
+        tracing::info!("query statistics reset");
+        Ok(Response::new(proto::ResetQueryStatsResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    async fn list_sessions(
+        &self,
+        request: Request<proto::ListSessionsRequest>,
+    ) -> Result<Response<proto::ListSessionsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let (sessions, _) = self.sessions()?;
+        Ok(Response::new(proto::ListSessionsResponse {
+            sessions: sessions
+                .list()
+                .await
+                .into_iter()
+                .map(proto::SessionInfo::from)
+                .collect(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id))]
+    async fn kill_session(
+        &self,
+        request: Request<proto::KillSessionRequest>,
+    ) -> Result<Response<proto::KillSessionResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        let session_id = &req.session_id;
+        tracing::Span::current().record("session_id", session_id);
+
+        let (sessions, transactions) = self.sessions()?;
+        if !sessions.exists(session_id).await {
+            return Err(Status::not_found(format!("session {session_id} not found")));
+        }
+
+        let handle = SessionHandle(session_id.clone());
+        for tx_id in transactions.remove_for_session(session_id).await {
+            tracing::info!(session_id, transaction_id = %tx_id, "rolling back transaction on kill");
+            let _ = self
+                .backend
+                .rollback(&handle, &TransactionHandle(tx_id))
+                .await;
+        }
+        self.backend
+            .close_session(&handle)
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        sessions.remove(session_id).await;
+
+        tracing::info!(session_id, "session killed");
+        Ok(Response::new(proto::KillSessionResponse {}))
+    }
+
     #[tracing::instrument(skip(self, request), fields(graph))]
     async fn wal_status(
         &self,
         request: Request<proto::WalStatusRequest>,
     ) -> Result<Response<proto::WalStatusResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("graph", &req.graph);
 
@@ -89,6 +358,8 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
         &self,
         request: Request<proto::WalCheckpointRequest>,
     ) -> Result<Response<proto::WalCheckpointResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("graph", &req.graph);
 
@@ -111,6 +382,8 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
         &self,
         request: Request<proto::ValidateRequest>,
     ) -> Result<Response<proto::ValidateResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("graph", &req.graph);
 
@@ -124,27 +397,7 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
             .await
             .map_err(|e| e.to_optional_service_status())?;
 
-        Ok(Response::new(proto::ValidateResponse {
-            valid: result.valid,
-            errors: result
-                .errors
-                .into_iter()
-                .map(|e| proto::ValidationError {
-                    code: e.code,
-                    message: e.message,
-                    context: e.context,
-                })
-                .collect(),
-            warnings: result
-                .warnings
-                .into_iter()
-                .map(|w| proto::ValidationWarning {
-                    code: w.code,
-                    message: w.message,
-                    context: w.context,
-                })
-                .collect(),
-        }))
+        Ok(Response::new(result.into()))
     }
 
     #[tracing::instrument(skip(self, request), fields(graph))]
@@ -152,6 +405,8 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
         &self,
         request: Request<proto::CreateIndexRequest>,
     ) -> Result<Response<proto::CreateIndexResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("graph", &req.graph);
 
@@ -159,28 +414,21 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
             return Err(Status::invalid_argument("graph name is required"));
         }
 
-        let index_def = match req.index {
-            Some(proto::create_index_request::Index::PropertyIndex(def)) => {
-                IndexDefinition::Property {
-                    property: def.property,
-                }
-            }
-            Some(proto::create_index_request::Index::VectorIndex(def)) => IndexDefinition::Vector {
-                label: def.label,
-                property: def.property,
-                dimensions: def.dimensions,
-                metric: def.metric,
-                m: def.m,
-                ef_construction: def.ef_construction,
-            },
-            Some(proto::create_index_request::Index::TextIndex(def)) => IndexDefinition::Text {
-                label: def.label,
-                property: def.property,
-            },
-            None => {
-                return Err(Status::invalid_argument("index definition is required"));
-            }
-        };
+        let index_def = IndexDefinition::from(
+            req.index
+                .ok_or_else(|| Status::invalid_argument("index definition is required"))?,
+        );
+
+        if req.background {
+            let job_id = self.submit(Job::CreateIndex {
+                graph: req.graph.clone(),
+                index: index_def,
+            })?;
+            tracing::info!(graph = %req.graph, job_id, "index build started");
+            return Ok(Response::new(proto::CreateIndexResponse {
+                job_id: Some(job_id),
+            }));
+        }
 
         self.backend
             .create_index(&req.graph, index_def)
@@ -189,7 +437,40 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
 
         tracing::info!(graph = %req.graph, "index created");
 
-        Ok(Response::new(proto::CreateIndexResponse {}))
+        Ok(Response::new(proto::CreateIndexResponse { job_id: None }))
+    }
+
+    type IndexBuildProgressStream = ReceiverStream<Result<proto::IndexBuildProgressUpdate, Status>>;
+
+    #[tracing::instrument(skip(self, request), fields(job_id))]
+    async fn index_build_progress(
+        &self,
+        request: Request<proto::IndexBuildProgressRequest>,
+    ) -> Result<Response<Self::IndexBuildProgressStream>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("job_id", &req.job_id);
+
+        let mut status = self
+            .jobs
+            .watch(&req.job_id)
+            .filter(|status| matches!(status.borrow().job, Job::CreateIndex { .. }))
+            .ok_or_else(|| Status::not_found(format!("index build {} not found", req.job_id)))?;
+        let (tx, rx) = mpsc::channel(PROGRESS_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let update = IndexBuildStatus::from(&*status.borrow_and_update());
+                let finished = update.state != IndexState::Building;
+                if tx.send(Ok(update.into())).await.is_err() || finished {
+                    break;
+                }
+                if status.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
     }
 
     #[tracing::instrument(skip(self, request), fields(graph))]
@@ -197,6 +478,8 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
         &self,
         request: Request<proto::DropIndexRequest>,
     ) -> Result<Response<proto::DropIndexResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("graph", &req.graph);
 
@@ -235,4 +518,220 @@ impl<B: GqlBackend> AdminService for AdminServiceImpl<B> {
 
         Ok(Response::new(proto::DropIndexResponse { existed }))
     }
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn list_indexes(
+        &self,
+        request: Request<proto::ListIndexesRequest>,
+    ) -> Result<Response<proto::ListIndexesResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("graph", &req.graph);
+
+        if req.graph.is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+
+        let indexes = self
+            .backend
+            .list_indexes(&req.graph)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+
+        Ok(Response::new(proto::ListIndexesResponse {
+            indexes: indexes.into_iter().map(proto::IndexInfo::from).collect(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn submit_job(
+        &self,
+        request: Request<proto::SubmitJobRequest>,
+    ) -> Result<Response<proto::SubmitJobResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let job = request
+            .into_inner()
+            .job
+            .ok_or_else(|| Status::invalid_argument("job is required"))?;
+        let job = Job::try_from(job).map_err(|e| e.to_grpc_status())?;
+        tracing::Span::current().record("graph", job.graph());
+
+        if job.graph().is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+
+        let job_id = self.submit(job)?;
+        tracing::info!(job_id, "job submitted");
+
+        Ok(Response::new(proto::SubmitJobResponse { job_id }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(job_id))]
+    async fn get_job_status(
+        &self,
+        request: Request<proto::GetJobStatusRequest>,
+    ) -> Result<Response<proto::GetJobStatusResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("job_id", &req.job_id);
+
+        let status = self
+            .jobs
+            .status(&req.job_id)
+            .ok_or_else(|| Status::not_found(format!("job {} not found", req.job_id)))?;
+
+        Ok(Response::new(proto::GetJobStatusResponse {
+            status: Some(status.into()),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(job_id))]
+    async fn cancel_job(
+        &self,
+        request: Request<proto::CancelJobRequest>,
+    ) -> Result<Response<proto::CancelJobResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("job_id", &req.job_id);
+
+        let cancelled = self
+            .jobs
+            .cancel(&req.job_id)
+            .ok_or_else(|| Status::not_found(format!("job {} not found", req.job_id)))?;
+
+        Ok(Response::new(proto::CancelJobResponse { cancelled }))
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    async fn list_jobs(
+        &self,
+        request: Request<proto::ListJobsRequest>,
+    ) -> Result<Response<proto::ListJobsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        Ok(Response::new(proto::ListJobsResponse {
+            jobs: self
+                .jobs
+                .list()
+                .into_iter()
+                .map(proto::JobStatus::from)
+                .collect(),
+        }))
+    }
+    type BackupDatabaseStream = BackupChunks;
+
+    #[tracing::instrument(skip(self, request))]
+    async fn backup_database(
+        &self,
+        request: Request<proto::BackupDatabaseRequest>,
+    ) -> Result<Response<Self::BackupDatabaseStream>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let backup = self
+            .backend
+            .backup()
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+        tracing::info!("backup started");
+
+        Ok(Response::new(BackupChunks::new(backup)))
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    async fn restore_database(
+        &self,
+        request: Request<Streaming<proto::RestoreDatabaseRequest>>,
+    ) -> Result<Response<proto::RestoreDatabaseResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let mut chunks = RestoreChunks::new(request.into_inner());
+        self.backend
+            .restore(&mut chunks)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+
+        let bytes_restored = chunks.bytes_read();
+        tracing::info!(bytes_restored, "database restored");
+
+        Ok(Response::new(proto::RestoreDatabaseResponse {
+            bytes_restored,
+        }))
+    }
+    type ExportGraphStream = ExportChunks;
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn export_graph(
+        &self,
+        request: Request<proto::ExportGraphRequest>,
+    ) -> Result<Response<Self::ExportGraphStream>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("graph", &req.graph);
+
+        if req.graph.is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+        let format = proto::GraphFormat::try_from(req.format)
+            .map_err(|_| Status::invalid_argument(format!("unknown format {}", req.format)))?;
+
+        let elements = self
+            .backend
+            .export_graph(&req.graph)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+        tracing::info!(graph = %req.graph, ?format, "export started");
+
+        Ok(Response::new(ExportChunks::new(
+            elements,
+            GraphFormat::from(format),
+        )))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(graph))]
+    async fn import_graph(
+        &self,
+        request: Request<Streaming<proto::ImportGraphRequest>>,
+    ) -> Result<Response<proto::ImportGraphResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let mut requests = request.into_inner();
+        let first = requests
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("import stream is empty"))?;
+        tracing::Span::current().record("graph", &first.graph);
+
+        if first.graph.is_empty() {
+            return Err(Status::invalid_argument("graph name is required"));
+        }
+        let format = proto::GraphFormat::try_from(first.format)
+            .map_err(|_| Status::invalid_argument(format!("unknown format {}", first.format)))?;
+        let mut elements = ImportElements::new(GraphFormat::from(format), first.data, requests)
+            .ok_or_else(|| {
+                Status::invalid_argument(format!("{format:?} import is not supported"))
+            })?;
+
+        let mut report = self
+            .backend
+            .import_graph(&first.graph, &mut elements)
+            .await
+            .map_err(|e| e.to_optional_service_status())?;
+        let mut errors = elements.take_errors();
+        errors.append(&mut report.errors);
+        report.errors = errors;
+        tracing::info!(
+            graph = %first.graph,
+            nodes_created = report.nodes_created,
+            edges_created = report.edges_created,
+            errors = report.errors.len(),
+            "graph imported"
+        );
+
+        Ok(Response::new(report.into()))
+    }
 }
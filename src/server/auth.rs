@@ -1,17 +1,269 @@
-//! Authentication for the GQL wire protocol server.
+//! Authentication and authorization for the GQL wire protocol server.
+
+use std::sync::Arc;
+
+use tonic::{Request, Status};
 
 use crate::error::GqlError;
 use crate::proto;
 
+use super::SessionManager;
+use super::client_cert::ClientCertificate;
+use super::result_cache::is_read_only_statement;
+
+/// The identity a session authenticated as.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Principal {
+    /// User name, or empty for credentials that carry none.
+    pub name: String,
+    /// Roles granted to the principal.
+    pub roles: Vec<String>,
+}
+
+impl Principal {
+    /// A principal with the given name and no roles.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            roles: Vec::new(),
+        }
+    }
+
+    /// Grant the principal a role.
+    #[must_use]
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+
+    /// Whether the principal has been granted `role`.
+    #[must_use]
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    /// The principal named by `credentials`: the user name of basic
+    /// credentials, or an unnamed principal for a bearer token.
+    #[must_use]
+    pub fn from_credentials(credentials: &proto::AuthCredentials) -> Self {
+        match &credentials.method {
+            Some(proto::auth_credentials::Method::Basic(basic)) => Self::new(&basic.username),
+            _ => Self::default(),
+        }
+    }
+
+    /// The principal named by a client certificate: its subject's common
+    /// name, or the whole subject if it has none.
+    #[must_use]
+    pub fn from_certificate(certificate: &ClientCertificate) -> Self {
+        Self::new(
+            certificate
+                .common_name
+                .as_deref()
+                .unwrap_or(&certificate.subject),
+        )
+    }
+}
+
+/// The kind of operation an [`Authorizer`] is asked to allow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Reading data: read-only statements, catalog listings, and searches.
+    Read,
+    /// Modifying data.
+    Write,
+    /// Creating, dropping, or altering schemas, graphs, and graph types.
+    Ddl,
+    /// Administration: statistics, WAL, indexes, and server settings.
+    Admin,
+}
+
+impl Operation {
+    /// Classify a GQL statement by its keywords.
+    ///
+    /// Statements starting with `CREATE`, `DROP`, or `ALTER` followed by
+    /// a catalog object are DDL; other statements are reads if
+    /// [`is_read_only_statement`](super::is_read_only_statement) says so,
+    /// and writes otherwise.
+    #[must_use]
+    pub fn of_statement(statement: &str) -> Self {
+        let mut words = statement.split_whitespace().map(str::to_ascii_uppercase);
+        let verb = words.next().unwrap_or_default();
+        let object = words
+            .find(|w| !matches!(w.as_str(), "OR" | "REPLACE" | "PROPERTY"))
+            .unwrap_or_default();
+        if matches!(verb.as_str(), "CREATE" | "DROP" | "ALTER")
+            && matches!(object.as_str(), "SCHEMA" | "GRAPH")
+        {
+            Self::Ddl
+        } else if is_read_only_statement(statement) {
+            Self::Read
+        } else {
+            Self::Write
+        }
+    }
+}
+
 /// Validates client credentials during handshake.
 ///
 /// Implement this trait to add authentication to the server.
 /// If no validator is configured on the server builder, all
-/// connections are accepted.
+/// connections are accepted, and sessions created over mutual TLS run
+/// as [`Principal::from_certificate`].
 #[tonic::async_trait]
 pub trait AuthValidator: Send + Sync + 'static {
     /// Validate the given credentials.
     ///
     /// Return `Ok(())` to accept, or `Err(GqlError)` to reject.
     async fn validate(&self, credentials: &proto::AuthCredentials) -> Result<(), GqlError>;
+
+    /// Validate the given credentials and return the principal the
+    /// session runs as, which is passed to the server's [`Authorizer`].
+    ///
+    /// The default calls [`validate`](Self::validate) and derives the
+    /// principal with [`Principal::from_credentials`]. Override it to
+    /// attach roles.
+    async fn authenticate(
+        &self,
+        credentials: &proto::AuthCredentials,
+    ) -> Result<Principal, GqlError> {
+        self.validate(credentials).await?;
+        Ok(Principal::from_credentials(credentials))
+    }
+
+    /// Accept a handshake that carries no credentials but arrived over
+    /// mutual TLS with `certificate`, returning the principal the session
+    /// runs as. Explicit credentials take precedence over a certificate.
+    ///
+    /// The TLS layer has already verified the certificate against the
+    /// server's client CA roots. The default accepts it as
+    /// [`Principal::from_certificate`]; override it to map subjects to
+    /// users and roles, or to reject some.
+    async fn validate_tls(&self, certificate: &ClientCertificate) -> Result<Principal, GqlError> {
+        Ok(Principal::from_certificate(certificate))
+    }
+}
+
+/// Decides whether a principal may perform an operation.
+///
+/// Consulted on every statement execution and on every `CatalogService`,
+/// `AdminService`, and `SearchService` call. Catalog, admin, and search
+/// requests are attributed to the session named in their
+/// [`SESSION_ID_METADATA`](proto::SESSION_ID_METADATA); without it, or
+/// for sessions created without authentication, the principal is `None`.
+#[tonic::async_trait]
+pub trait Authorizer: Send + Sync + 'static {
+    /// Allow `operation` for `principal`.
+    ///
+    /// Return `Ok(())` to allow it, or an error to deny it. A denied
+    /// statement ends with the error's GQLSTATUS, or
+    /// [`PERMISSION_DENIED`](crate::status::PERMISSION_DENIED) if it has
+    /// none; other denied calls fail with `PERMISSION_DENIED`.
+    async fn authorize(
+        &self,
+        principal: Option<&Principal>,
+        operation: Operation,
+    ) -> Result<(), GqlError>;
+}
+
+/// An authorizer and the sessions whose principals it judges, shared by
+/// the services that consult it.
+#[derive(Clone)]
+pub(crate) struct Authorization {
+    authorizer: Arc<dyn Authorizer>,
+    sessions: SessionManager,
+}
+
+impl Authorization {
+    pub(crate) fn new(authorizer: Arc<dyn Authorizer>, sessions: SessionManager) -> Self {
+        Self {
+            authorizer,
+            sessions,
+        }
+    }
+
+    /// Ask whether the principal of `session_id` may perform `operation`.
+    pub(crate) async fn check(
+        &self,
+        session_id: &str,
+        operation: Operation,
+    ) -> Result<(), GqlError> {
+        let principal = self
+            .sessions
+            .state(session_id)
+            .await
+            .and_then(|state| state.principal);
+        let result = self
+            .authorizer
+            .authorize(principal.as_ref(), operation)
+            .await;
+        if let Err(err) = &result {
+            tracing::warn!(session_id, ?operation, error = %err, "operation denied");
+        }
+        result
+    }
+
+    /// Ask whether the session named in `request`'s metadata may perform
+    /// `operation`, as a gRPC status.
+    pub(crate) async fn check_request<T>(
+        &self,
+        request: &Request<T>,
+        operation: Operation,
+    ) -> Result<(), Status> {
+        let session_id = request
+            .metadata()
+            .get(proto::SESSION_ID_METADATA)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        self.check(session_id, operation)
+            .await
+            .map_err(|err| match err {
+                GqlError::Grpc(status) => status,
+                GqlError::Status { status } => Status::permission_denied(status.message),
+                err => Status::permission_denied(err.to_string()),
+            })
+    }
+}
+
+/// Check `request` against `authorization`, if the service has one.
+pub(crate) async fn authorize<T>(
+    authorization: Option<&Authorization>,
+    request: &Request<T>,
+    operation: Operation,
+) -> Result<(), Status> {
+    match authorization {
+        Some(authorization) => authorization.check_request(request, operation).await,
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements_are_classified_by_keywords() {
+        assert_eq!(
+            Operation::of_statement("MATCH (n) RETURN n"),
+            Operation::Read
+        );
+        assert_eq!(
+            Operation::of_statement("INSERT (:Person)"),
+            Operation::Write
+        );
+        assert_eq!(
+            Operation::of_statement("create or replace graph g ANY"),
+            Operation::Ddl
+        );
+        assert_eq!(
+            Operation::of_statement("DROP PROPERTY GRAPH g"),
+            Operation::Ddl
+        );
+        assert_eq!(Operation::of_statement("CREATE SCHEMA /s"), Operation::Ddl);
+        assert_eq!(
+            Operation::of_statement("CREATE (n:Person)"),
+            Operation::Write
+        );
+    }
 }
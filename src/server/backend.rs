@@ -6,10 +6,17 @@
 
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::time::Duration;
 
 use crate::error::GqlError;
 use crate::proto;
-use crate::types::Value;
+use crate::trace_context::TraceContext;
+use crate::types::{Edge, Node, Value};
+
+use super::backup::RestoreChunks;
+use super::bulk_load::{BulkChunks, BulkLoadStats};
+use super::import::ImportElements;
+use super::jobs::{Job, JobOutput, JobProgress};
 
 /// Opaque session identifier issued at handshake.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -19,6 +26,11 @@ pub struct SessionHandle(pub String);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TransactionHandle(pub String);
 
+/// Opaque prepared statement identifier issued by the server at prepare,
+/// unique within its session.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreparedHandle(pub String);
+
 /// Configuration for a new session, derived from the handshake request.
 #[derive(Debug, Clone)]
 pub struct SessionConfig {
@@ -26,6 +38,42 @@ pub struct SessionConfig {
     pub protocol_version: u32,
     /// Client metadata (driver name, version, platform).
     pub client_info: HashMap<String, String>,
+    /// Database the client asked to route the session's statements to,
+    /// or `None` for the server's default.
+    pub database: Option<String>,
+}
+
+/// Where a statement execution sits in the caller's distributed trace.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionContext {
+    /// The server's span for the statement, a child of the client's
+    /// `traceparent`. Spans the backend starts should be children of it.
+    /// `None` if the client sent no valid trace context.
+    pub trace: Option<TraceContext>,
+    /// The client-assigned query ID, if the client sent one.
+    pub query_id: Option<String>,
+}
+
+/// Characteristics of a new transaction, from the begin request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionConfig {
+    /// Access mode.
+    pub mode: proto::TransactionMode,
+    /// Requested isolation level; `Default` leaves it to the backend.
+    pub isolation_level: proto::IsolationLevel,
+    /// How long the transaction may stay open before the server rolls it
+    /// back; `None` leaves it open until it ends.
+    pub timeout: Option<Duration>,
+}
+
+impl From<proto::TransactionMode> for TransactionConfig {
+    fn from(mode: proto::TransactionMode) -> Self {
+        Self {
+            mode,
+            isolation_level: proto::IsolationLevel::Default,
+            timeout: None,
+        }
+    }
 }
 
 /// A session property to configure.
@@ -37,13 +85,16 @@ pub enum SessionProperty {
     Graph(String),
     /// Set the session timezone (UTC offset in minutes).
     TimeZone(i32),
-    /// Set a named session parameter.
+    /// Set a named session parameter, passed with every later statement
+    /// the statement does not set itself.
     Parameter {
         /// Parameter name.
         name: String,
         /// Parameter value.
         value: Value,
     },
+    /// Route the session's statements to another database (`USE`).
+    Database(String),
 }
 
 /// What to reset on a session.
@@ -61,13 +112,28 @@ pub enum ResetTarget {
     Parameters,
 }
 
+/// Whether a backend can serve requests, as reported by
+/// [`GqlBackend::health`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// Requests can be served.
+    Serving,
+    /// Requests cannot be served now, such as during recovery or under
+    /// overload.
+    NotServing,
+}
+
 /// A single frame in the result stream from executing a GQL statement.
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum ResultFrame {
     /// Column metadata and result type. Always the first frame.
     Header(proto::ResultHeader),
     /// A batch of rows.
     Batch(proto::RowBatch),
+    /// A warning, sent to the client as soon as it arises instead of
+    /// waiting for the summary. May come anywhere before the summary.
+    Warning(proto::GqlStatus),
     /// Completion status and statistics. Always the last frame.
     Summary(proto::ResultSummary),
 }
@@ -86,6 +152,101 @@ pub trait ResultStream: Send + 'static {
     ) -> std::task::Poll<Option<Result<ResultFrame, GqlError>>>;
 }
 
+/// Change events a backend pushes for one subscribed topic.
+///
+/// Backends return a `NotificationSource` from `subscribe()`. The server
+/// forwards each event to the subscriber until the source ends or the
+/// subscriber unsubscribes.
+pub trait NotificationSource: Send + 'static {
+    /// Get the next event.
+    ///
+    /// Returns `None` once the topic will produce no more events.
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<ChangeEvent>>;
+}
+
+/// Chunks of a database backup produced by a backend.
+///
+/// Backends return a `BackupStream` from `backup()`. The server sends
+/// each chunk to the client as it is produced, so a backup need not fit
+/// in memory.
+pub trait BackupStream: Send + 'static {
+    /// Get the next chunk of the backup.
+    ///
+    /// Returns `None` once the backup is complete.
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<Vec<u8>, GqlError>>>;
+}
+
+/// A node or edge of a graph.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphElement {
+    /// A node.
+    Node(Node),
+    /// An edge.
+    Edge(Edge),
+}
+
+/// Nodes and edges of a graph produced by a backend for export.
+///
+/// Backends return an `ElementStream` from `export_graph()`. The server
+/// encodes the elements in the format the client asked for as they are
+/// produced.
+pub trait ElementStream: Send + 'static {
+    /// Get the next element.
+    ///
+    /// Returns `None` once every element has been produced.
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<GraphElement, GqlError>>>;
+}
+
+/// A change a backend notifies subscribers of, such as a graph mutation
+/// or a schema change.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeEvent {
+    /// Topic the event was published on.
+    pub topic: String,
+    /// Backend-defined kind of change (e.g. `graph_mutation`).
+    pub kind: String,
+    /// Backend-defined details of the change.
+    pub attributes: HashMap<String, Value>,
+}
+
+impl From<proto::Notification> for ChangeEvent {
+    fn from(n: proto::Notification) -> Self {
+        Self {
+            topic: n.topic,
+            kind: n.kind,
+            attributes: n
+                .attributes
+                .into_iter()
+                .map(|(k, v)| (k, Value::from(v)))
+                .collect(),
+        }
+    }
+}
+
+impl From<ChangeEvent> for proto::Notification {
+    fn from(event: ChangeEvent) -> Self {
+        Self {
+            topic: event.topic,
+            kind: event.kind,
+            attributes: event
+                .attributes
+                .into_iter()
+                .map(|(k, v)| (k, proto::Value::from(v)))
+                .collect(),
+            status: None,
+        }
+    }
+}
+
 // ============================================================================
 // Catalog types
 // ============================================================================
@@ -101,6 +262,114 @@ pub struct SchemaInfo {
     pub graph_type_count: u32,
 }
 
+/// Default schema and graph for sessions on a database.
+///
+/// Sessions start with these as their current schema and graph, and
+/// resetting a session returns to them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionDefaults {
+    /// Default schema, if any.
+    pub schema: Option<String>,
+    /// Default graph, if any.
+    pub graph: Option<String>,
+}
+
+impl From<proto::SessionDefaults> for SessionDefaults {
+    fn from(d: proto::SessionDefaults) -> Self {
+        Self {
+            schema: d.schema,
+            graph: d.graph,
+        }
+    }
+}
+
+impl From<SessionDefaults> for proto::SessionDefaults {
+    fn from(d: SessionDefaults) -> Self {
+        Self {
+            schema: d.schema,
+            graph: d.graph,
+        }
+    }
+}
+
+/// Information about a database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseInfo {
+    /// Database name.
+    pub name: String,
+    /// Schema and graph that sessions on the database start with.
+    pub defaults: SessionDefaults,
+}
+
+impl From<DatabaseInfo> for proto::DatabaseInfo {
+    fn from(info: DatabaseInfo) -> Self {
+        Self {
+            name: info.name,
+            defaults: Some(info.defaults.into()),
+        }
+    }
+}
+
+impl From<proto::DatabaseInfo> for DatabaseInfo {
+    fn from(info: proto::DatabaseInfo) -> Self {
+        Self {
+            name: info.name,
+            defaults: info.defaults.map(SessionDefaults::from).unwrap_or_default(),
+        }
+    }
+}
+
+/// Configuration for creating a new database.
+#[derive(Debug, Clone, Default)]
+pub struct CreateDatabaseConfig {
+    /// Database name.
+    pub name: String,
+    /// Whether to skip if the database already exists.
+    pub if_not_exists: bool,
+    /// Schema and graph that sessions on the database start with.
+    pub defaults: SessionDefaults,
+}
+
+/// A query plan: the operator at its root and the plans feeding it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryPlan {
+    /// Operator name (e.g. `NodeScan`, `Expand`, `Filter`).
+    pub operator: String,
+    /// Backend-specific description of what the operator does, such as
+    /// the label it scans or the predicate it filters on.
+    pub details: String,
+    /// Estimated number of rows the operator produces.
+    pub estimated_rows: f64,
+    /// Estimated cost, in backend-defined units.
+    pub cost: f64,
+    /// Plans producing the operator's input.
+    pub children: Vec<QueryPlan>,
+}
+
+impl From<proto::PlanNode> for QueryPlan {
+    fn from(node: proto::PlanNode) -> Self {
+        Self {
+            operator: node.operator,
+            details: node.details,
+            estimated_rows: node.estimated_rows,
+            cost: node.cost,
+            children: node.children.into_iter().map(Self::from).collect(),
+        }
+    }
+}
+
+impl From<QueryPlan> for proto::PlanNode {
+    fn from(plan: QueryPlan) -> Self {
+        Self {
+            operator: plan.operator,
+            details: plan.details,
+            estimated_rows: plan.estimated_rows,
+            cost: plan.cost,
+            children: plan.children.into_iter().map(Self::from).collect(),
+        }
+    }
+}
+
 /// Summary information about a graph.
 #[derive(Debug, Clone)]
 pub struct GraphInfo {
@@ -162,6 +431,25 @@ pub struct CreateGraphConfig {
     pub wal_durability: Option<String>,
 }
 
+/// Configuration for cloning a graph.
+#[derive(Debug, Clone)]
+pub struct CloneGraphConfig {
+    /// Schema of the graph to copy.
+    pub source_schema: String,
+    /// Name of the graph to copy.
+    pub source_name: String,
+    /// Schema to create the clone in.
+    pub target_schema: String,
+    /// Name of the clone.
+    pub target_name: String,
+    /// Whether to skip if the target graph already exists.
+    pub if_not_exists: bool,
+    /// Whether to rebuild the source's indexes on the clone.
+    pub include_indexes: bool,
+    /// Storage mode override (defaults to the source's storage mode).
+    pub storage_mode: Option<String>,
+}
+
 /// Summary information about a graph type.
 #[derive(Debug, Clone)]
 pub struct GraphTypeInfo {
@@ -191,7 +479,12 @@ pub trait GqlBackend: Send + Sync + 'static {
     /// the connection drops. Any active transaction should be rolled back.
     async fn close_session(&self, session: &SessionHandle) -> Result<(), GqlError>;
 
-    /// Set a session property (schema, graph, timezone, or parameter).
+    /// Set a session property (schema, graph, timezone, parameter, or
+    /// database).
+    ///
+    /// Backends serving several databases route the session's later
+    /// statements to the one a [`SessionProperty::Database`] names, and
+    /// to [`SessionConfig::database`] before that.
     async fn configure_session(
         &self,
         session: &SessionHandle,
@@ -209,13 +502,18 @@ pub trait GqlBackend: Send + Sync + 'static {
     ///
     /// The stream should emit frames in order: Header, then zero or more
     /// Batch frames, then Summary. The server converts these into
-    /// streaming gRPC `ExecuteResponse` messages.
+    /// streaming gRPC `ExecuteResponse` messages. `context` carries the
+    /// client's trace context, for connecting the engine's own spans.
+    ///
+    /// `parameters` holds the session's parameters as well as the
+    /// statement's, which take precedence.
     async fn execute(
         &self,
         session: &SessionHandle,
         statement: &str,
         parameters: &HashMap<String, Value>,
         transaction: Option<&TransactionHandle>,
+        context: &ExecutionContext,
     ) -> Result<Pin<Box<dyn ResultStream>>, GqlError>;
 
     /// Begin an explicit transaction.
@@ -228,6 +526,30 @@ pub trait GqlBackend: Send + Sync + 'static {
         mode: proto::TransactionMode,
     ) -> Result<TransactionHandle, GqlError>;
 
+    /// Begin an explicit transaction with the requested characteristics.
+    ///
+    /// The server calls this for every begin request. The default
+    /// implementation calls [`begin_transaction`](Self::begin_transaction)
+    /// for the backend's default isolation level and rejects any other
+    /// with [`UNSUPPORTED_FEATURE`](crate::status::UNSUPPORTED_FEATURE);
+    /// backends offering several isolation levels override it.
+    async fn begin_transaction_with(
+        &self,
+        session: &SessionHandle,
+        config: TransactionConfig,
+    ) -> Result<TransactionHandle, GqlError> {
+        if config.isolation_level != proto::IsolationLevel::Default {
+            return Err(GqlError::status(
+                crate::status::UNSUPPORTED_FEATURE,
+                format!(
+                    "isolation level {} not supported",
+                    config.isolation_level.as_str_name()
+                ),
+            ));
+        }
+        self.begin_transaction(session, config.mode).await
+    }
+
     /// Commit the transaction.
     async fn commit(
         &self,
@@ -242,10 +564,235 @@ pub trait GqlBackend: Send + Sync + 'static {
         transaction: &TransactionHandle,
     ) -> Result<(), GqlError>;
 
+    /// Whether the backend can serve requests.
+    ///
+    /// The server polls this and reports the result through the gRPC
+    /// health service, so load balancers and clients can route away from
+    /// a backend that is recovering or overloaded. Defaults to serving.
+    async fn health(&self) -> HealthStatus {
+        HealthStatus::Serving
+    }
+
+    // =========================================================================
+    // Two-phase commit (optional)
+    // =========================================================================
+
+    /// Prepare the transaction for a two-phase commit.
+    ///
+    /// Once this succeeds the backend must be able to commit the
+    /// transaction until [`commit_prepared`](Self::commit_prepared) or
+    /// [`rollback_prepared`](Self::rollback_prepared) is called, even if
+    /// its session closes. An error votes to abort; the transaction stays
+    /// active and is usually rolled back by the coordinator.
+    async fn prepare_commit(
+        &self,
+        _session: &SessionHandle,
+        _transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("two-phase commit not supported".into()))
+    }
+
+    /// Commit a prepared transaction.
+    ///
+    /// `session` is the session finishing the transaction, which need not
+    /// be the one that began it. On error the transaction stays prepared
+    /// so the coordinator can retry.
+    async fn commit_prepared(
+        &self,
+        _session: &SessionHandle,
+        _transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("two-phase commit not supported".into()))
+    }
+
+    /// Roll back a prepared transaction.
+    ///
+    /// `session` is the session finishing the transaction, which need not
+    /// be the one that began it. On error the transaction stays prepared
+    /// so the coordinator can retry.
+    async fn rollback_prepared(
+        &self,
+        _session: &SessionHandle,
+        _transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("two-phase commit not supported".into()))
+    }
+
+    // =========================================================================
+    // Savepoints (optional)
+    // =========================================================================
+
+    /// Mark the savepoint `name` in the transaction. Reusing a name hides
+    /// the earlier savepoint until the new one is released.
+    async fn savepoint(
+        &self,
+        _session: &SessionHandle,
+        _transaction: &TransactionHandle,
+        _name: &str,
+    ) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("savepoints not supported".into()))
+    }
+
+    /// Forget the savepoint `name` and every savepoint marked after it,
+    /// keeping their changes.
+    async fn release_savepoint(
+        &self,
+        _session: &SessionHandle,
+        _transaction: &TransactionHandle,
+        _name: &str,
+    ) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("savepoints not supported".into()))
+    }
+
+    /// Undo the changes made since the savepoint `name`, forgetting the
+    /// savepoints marked after it but keeping `name` itself. The
+    /// transaction stays active.
+    async fn rollback_to_savepoint(
+        &self,
+        _session: &SessionHandle,
+        _transaction: &TransactionHandle,
+        _name: &str,
+    ) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("savepoints not supported".into()))
+    }
+
+    // =========================================================================
+    // Prepared statements (optional)
+    // =========================================================================
+
+    /// Prepare `statement` for repeated execution as `prepared`.
+    ///
+    /// Backends can parse and plan the statement here, failing early on
+    /// errors, and cache the plan for
+    /// [`execute_prepared`](Self::execute_prepared). The default
+    /// implementation does nothing, so statements are parsed on every
+    /// execution.
+    async fn prepare(
+        &self,
+        _session: &SessionHandle,
+        _prepared: &PreparedHandle,
+        _statement: &str,
+    ) -> Result<(), GqlError> {
+        Ok(())
+    }
+
+    /// Execute a prepared statement. `statement` is the text it was
+    /// prepared from.
+    ///
+    /// The default implementation calls [`execute`](Self::execute) with
+    /// `statement`.
+    async fn execute_prepared(
+        &self,
+        session: &SessionHandle,
+        _prepared: &PreparedHandle,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+        transaction: Option<&TransactionHandle>,
+        context: &ExecutionContext,
+    ) -> Result<Pin<Box<dyn ResultStream>>, GqlError> {
+        self.execute(session, statement, parameters, transaction, context)
+            .await
+    }
+
+    /// Release a prepared statement.
+    ///
+    /// Statements still prepared when their session closes are not
+    /// released one by one; backends drop them in
+    /// [`close_session`](Self::close_session).
+    async fn close_prepared(
+        &self,
+        _session: &SessionHandle,
+        _prepared: &PreparedHandle,
+    ) -> Result<(), GqlError> {
+        Ok(())
+    }
+
+    /// Plan `statement` without running it, and return the plan.
+    ///
+    /// The default implementation returns an error, so `Explain` requests
+    /// fail.
+    async fn explain(
+        &self,
+        _session: &SessionHandle,
+        _statement: &str,
+        _parameters: &HashMap<String, Value>,
+    ) -> Result<QueryPlan, GqlError> {
+        Err(GqlError::Protocol("explain not supported".into()))
+    }
+
+    // =========================================================================
+    // Bulk loading (optional)
+    // =========================================================================
+
+    /// Load the nodes and edges of `chunks` into the session's current
+    /// graph, and return the totals.
+    ///
+    /// Backends should [`report`](BulkChunks::report) progress as they
+    /// go; the returned totals are sent to the client last. Elements
+    /// rejected one by one count as errors; an `Err` aborts the load. The
+    /// default implementation returns an error.
+    async fn bulk_load(
+        &self,
+        _session: &SessionHandle,
+        _chunks: &mut BulkChunks,
+    ) -> Result<BulkLoadStats, GqlError> {
+        Err(GqlError::Protocol("bulk load not supported".into()))
+    }
+
+    // =========================================================================
+    // Notifications (optional)
+    // =========================================================================
+
+    /// Start sending the session the change events published on `topic`.
+    ///
+    /// Topics are backend-defined, such as a graph name. The subscription
+    /// ends when the returned source is dropped. The default
+    /// implementation returns an error.
+    async fn subscribe(
+        &self,
+        _session: &SessionHandle,
+        _topic: &str,
+    ) -> Result<Pin<Box<dyn NotificationSource>>, GqlError> {
+        Err(GqlError::Protocol("notifications not supported".into()))
+    }
+
     // =========================================================================
     // Catalog operations (optional - sec 12)
     // =========================================================================
 
+    /// Get the default schema and graph for sessions on a database, or
+    /// on the server's default database if `database` is `None`.
+    ///
+    /// Called on every handshake with the database the client selected,
+    /// and again when a session switches database. The default
+    /// implementation returns no defaults, so sessions start without a
+    /// schema or graph.
+    async fn session_defaults(&self, _database: Option<&str>) -> Result<SessionDefaults, GqlError> {
+        Ok(SessionDefaults::default())
+    }
+
+    /// Create a database.
+    async fn create_database(
+        &self,
+        _config: CreateDatabaseConfig,
+    ) -> Result<DatabaseInfo, GqlError> {
+        Err(GqlError::Protocol("catalog not supported".into()))
+    }
+
+    /// Replace a database's default schema and graph.
+    async fn alter_database(
+        &self,
+        _name: &str,
+        _defaults: SessionDefaults,
+    ) -> Result<DatabaseInfo, GqlError> {
+        Err(GqlError::Protocol("catalog not supported".into()))
+    }
+
+    /// Get information about a database.
+    async fn get_database_info(&self, _name: &str) -> Result<DatabaseInfo, GqlError> {
+        Err(GqlError::Protocol("catalog not supported".into()))
+    }
+
     /// List all schemas.
     async fn list_schemas(&self) -> Result<Vec<SchemaInfo>, GqlError> {
         Err(GqlError::Protocol("catalog not supported".into()))
@@ -286,6 +833,11 @@ pub trait GqlBackend: Send + Sync + 'static {
         Err(GqlError::Protocol("catalog not supported".into()))
     }
 
+    /// Clone a graph, returning information about the new graph.
+    async fn clone_graph(&self, _config: CloneGraphConfig) -> Result<GraphInfo, GqlError> {
+        Err(GqlError::Protocol("catalog not supported".into()))
+    }
+
     /// List graph types in a schema.
     async fn list_graph_types(&self, _schema: &str) -> Result<Vec<GraphTypeInfo>, GqlError> {
         Err(GqlError::Protocol("catalog not supported".into()))
@@ -321,6 +873,28 @@ pub trait GqlBackend: Send + Sync + 'static {
         Err(GqlError::Protocol("admin not supported".into()))
     }
 
+    /// Get per-label counts and property cardinality estimates.
+    async fn get_label_stats(&self, _graph: &str) -> Result<LabelStats, GqlError> {
+        Err(GqlError::Protocol("admin not supported".into()))
+    }
+
+    /// Get the runtime-changeable configuration of a graph.
+    async fn get_graph_config(&self, _graph: &str) -> Result<GraphConfig, GqlError> {
+        Err(GqlError::Protocol("admin not supported".into()))
+    }
+
+    /// Apply runtime configuration changes to a graph.
+    ///
+    /// Only the fields set in `changes` are applied. Returns the effective
+    /// configuration after the change.
+    async fn set_graph_config(
+        &self,
+        _graph: &str,
+        _changes: GraphConfig,
+    ) -> Result<GraphConfig, GqlError> {
+        Err(GqlError::Protocol("admin not supported".into()))
+    }
+
     /// Get WAL status for a graph.
     async fn wal_status(&self, _graph: &str) -> Result<AdminWalStatus, GqlError> {
         Err(GqlError::Protocol("admin not supported".into()))
@@ -341,27 +915,103 @@ pub trait GqlBackend: Send + Sync + 'static {
         Err(GqlError::Protocol("admin not supported".into()))
     }
 
+    /// Create an index on a graph in the background, reporting how far
+    /// the build has come through `progress`.
+    ///
+    /// The default calls [`create_index`](Self::create_index) and reports
+    /// nothing until it returns. Backends with long HNSW or text index
+    /// builds should override this to report progress as they go.
+    async fn build_index(
+        &self,
+        graph: &str,
+        index: IndexDefinition,
+        _progress: &JobProgress,
+    ) -> Result<(), GqlError> {
+        self.create_index(graph, index).await
+    }
+
     /// Drop an index from a graph.
     async fn drop_index(&self, _graph: &str, _index: IndexDefinition) -> Result<bool, GqlError> {
         Err(GqlError::Protocol("admin not supported".into()))
     }
 
+    /// List the indexes on a graph.
+    async fn list_indexes(&self, _graph: &str) -> Result<Vec<IndexInfo>, GqlError> {
+        Err(GqlError::Protocol("admin not supported".into()))
+    }
+
+    /// Run a background job submitted through the `AdminService`,
+    /// reporting how far it has come through `progress`.
+    ///
+    /// The default runs index builds with
+    /// [`build_index`](Self::build_index), and validation and checkpoints
+    /// with their foreground methods. A cancelled job's future is dropped
+    /// at its next `.await`.
+    async fn run_job(&self, job: Job, progress: &JobProgress) -> Result<JobOutput, GqlError> {
+        match job {
+            Job::CreateIndex { graph, index } => self
+                .build_index(&graph, index, progress)
+                .await
+                .map(|()| JobOutput::Done),
+            Job::Validate { graph } => self.validate(&graph).await.map(JobOutput::Validation),
+            Job::WalCheckpoint { graph } => {
+                self.wal_checkpoint(&graph).await.map(|()| JobOutput::Done)
+            }
+        }
+    }
+
+    /// Start a backup of the whole database, as opaque chunks that
+    /// [`restore`](Self::restore) accepts in the same order.
+    async fn backup(&self) -> Result<Pin<Box<dyn BackupStream>>, GqlError> {
+        Err(GqlError::Protocol("backup not supported".into()))
+    }
+
+    /// Replace the whole database with the backup read from `chunks`.
+    ///
+    /// If reading a chunk fails, the client gave up part way and the
+    /// database should be left as it was.
+    async fn restore(&self, _chunks: &mut RestoreChunks) -> Result<(), GqlError> {
+        Err(GqlError::Protocol("restore not supported".into()))
+    }
+
+    /// Stream every node and edge of a graph for export.
+    ///
+    /// Elements may come in any order; the server encodes them in the
+    /// format the client asked for.
+    async fn export_graph(&self, _graph: &str) -> Result<Pin<Box<dyn ElementStream>>, GqlError> {
+        Err(GqlError::Protocol("export not supported".into()))
+    }
+
+    /// Import the nodes and edges read from `elements` into a graph.
+    ///
+    /// Elements whose ID is already taken should be skipped and counted as
+    /// duplicates, and elements that cannot be created, such as edges to
+    /// missing nodes, reported as errors. The server adds the elements it
+    /// could not decode to the report's errors.
+    async fn import_graph(
+        &self,
+        _graph: &str,
+        _elements: &mut ImportElements,
+    ) -> Result<ImportReport, GqlError> {
+        Err(GqlError::Protocol("import not supported".into()))
+    }
+
     // =========================================================================
     // Search operations (optional)
     // =========================================================================
 
     /// Vector similarity search (KNN).
-    async fn vector_search(&self, _req: VectorSearchParams) -> Result<Vec<SearchHit>, GqlError> {
+    async fn vector_search(&self, _req: VectorSearchParams) -> Result<SearchResults, GqlError> {
         Err(GqlError::Protocol("search not supported".into()))
     }
 
     /// Full-text search (BM25).
-    async fn text_search(&self, _req: TextSearchParams) -> Result<Vec<SearchHit>, GqlError> {
+    async fn text_search(&self, _req: TextSearchParams) -> Result<SearchResults, GqlError> {
         Err(GqlError::Protocol("search not supported".into()))
     }
 
     /// Hybrid search (vector + text with rank fusion).
-    async fn hybrid_search(&self, _req: HybridSearchParams) -> Result<Vec<SearchHit>, GqlError> {
+    async fn hybrid_search(&self, _req: HybridSearchParams) -> Result<SearchResults, GqlError> {
         Err(GqlError::Protocol("search not supported".into()))
     }
 }
@@ -389,6 +1039,128 @@ pub struct AdminStats {
     pub memory_bytes: u64,
     /// Disk usage in bytes (if persistent).
     pub disk_bytes: Option<u64>,
+    /// Per-component memory usage (if tracked by the backend).
+    pub memory_breakdown: Option<MemoryBreakdown>,
+}
+
+/// Per-component memory usage in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    /// Node storage.
+    pub node_store_bytes: u64,
+    /// Edge storage.
+    pub edge_store_bytes: u64,
+    /// Property storage.
+    pub property_store_bytes: u64,
+    /// Indexes (property, vector, and text).
+    pub index_bytes: u64,
+    /// WAL buffers not yet flushed to disk.
+    pub wal_buffer_bytes: u64,
+}
+
+impl From<proto::MemoryBreakdown> for MemoryBreakdown {
+    fn from(m: proto::MemoryBreakdown) -> Self {
+        Self {
+            node_store_bytes: m.node_store_bytes,
+            edge_store_bytes: m.edge_store_bytes,
+            property_store_bytes: m.property_store_bytes,
+            index_bytes: m.index_bytes,
+            wal_buffer_bytes: m.wal_buffer_bytes,
+        }
+    }
+}
+
+impl From<MemoryBreakdown> for proto::MemoryBreakdown {
+    fn from(m: MemoryBreakdown) -> Self {
+        Self {
+            node_store_bytes: m.node_store_bytes,
+            edge_store_bytes: m.edge_store_bytes,
+            property_store_bytes: m.property_store_bytes,
+            index_bytes: m.index_bytes,
+            wal_buffer_bytes: m.wal_buffer_bytes,
+        }
+    }
+}
+
+/// Per-label statistics for a graph.
+#[derive(Debug, Clone, Default)]
+pub struct LabelStats {
+    /// Node counts per label.
+    pub node_labels: Vec<LabelCount>,
+    /// Edge counts per edge type.
+    pub edge_types: Vec<LabelCount>,
+    /// Property cardinality estimates per label.
+    pub properties: Vec<PropertyCardinality>,
+}
+
+/// Number of elements carrying a node label or edge type.
+#[derive(Debug, Clone)]
+pub struct LabelCount {
+    /// Node label or edge type.
+    pub label: String,
+    /// Number of elements.
+    pub count: u64,
+}
+
+/// Cardinality estimate for a property on elements with a given label.
+#[derive(Debug, Clone)]
+pub struct PropertyCardinality {
+    /// Node label or edge type.
+    pub label: String,
+    /// Property name.
+    pub property: String,
+    /// Number of elements with a value for this property.
+    pub non_null_count: u64,
+    /// Estimated number of distinct values.
+    pub distinct_estimate: u64,
+}
+
+/// Runtime-changeable graph options.
+///
+/// When used as a change set, `None` fields are left unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphConfig {
+    /// Memory limit in bytes.
+    pub memory_limit_bytes: Option<u64>,
+    /// WAL durability mode.
+    pub wal_durability: Option<String>,
+    /// Number of worker threads.
+    pub threads: Option<u32>,
+}
+
+impl GraphConfig {
+    /// Overlay the fields set in `changes` onto this configuration.
+    pub fn apply(&mut self, changes: GraphConfig) {
+        if changes.memory_limit_bytes.is_some() {
+            self.memory_limit_bytes = changes.memory_limit_bytes;
+        }
+        if changes.wal_durability.is_some() {
+            self.wal_durability = changes.wal_durability;
+        }
+        if changes.threads.is_some() {
+            self.threads = changes.threads;
+        }
+    }
+}
+
+impl From<proto::GraphConfig> for GraphConfig {
+    fn from(c: proto::GraphConfig) -> Self {
+        Self {
+            memory_limit_bytes: c.memory_limit_bytes,
+            wal_durability: c.wal_durability,
+            threads: c.threads,
+        }
+    }
+}
+
+impl From<GraphConfig> for proto::GraphConfig {
+    fn from(c: GraphConfig) -> Self {
+        Self {
+            memory_limit_bytes: c.memory_limit_bytes,
+            wal_durability: c.wal_durability,
+            threads: c.threads,
+        }
+    }
 }
 
 /// WAL status information.
@@ -419,6 +1191,97 @@ pub struct AdminValidationResult {
     pub warnings: Vec<ValidationDiagnostic>,
 }
 
+impl From<AdminValidationResult> for proto::ValidateResponse {
+    fn from(result: AdminValidationResult) -> Self {
+        Self {
+            valid: result.valid,
+            errors: result
+                .errors
+                .into_iter()
+                .map(proto::ValidationError::from)
+                .collect(),
+            warnings: result
+                .warnings
+                .into_iter()
+                .map(proto::ValidationWarning::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<proto::ValidateResponse> for AdminValidationResult {
+    fn from(p: proto::ValidateResponse) -> Self {
+        Self {
+            valid: p.valid,
+            errors: p
+                .errors
+                .into_iter()
+                .map(ValidationDiagnostic::from)
+                .collect(),
+            warnings: p
+                .warnings
+                .into_iter()
+                .map(ValidationDiagnostic::from)
+                .collect(),
+        }
+    }
+}
+
+/// What a graph import created and what it left out.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// Number of nodes created.
+    pub nodes_created: u64,
+    /// Number of edges created.
+    pub edges_created: u64,
+    /// Number of elements skipped because their ID was already taken.
+    pub duplicates_skipped: u64,
+    /// Elements that were not imported, and why.
+    pub errors: Vec<ValidationDiagnostic>,
+    /// Elements that were imported with a caveat.
+    pub warnings: Vec<ValidationDiagnostic>,
+}
+
+impl From<ImportReport> for proto::ImportGraphResponse {
+    fn from(report: ImportReport) -> Self {
+        Self {
+            nodes_created: report.nodes_created,
+            edges_created: report.edges_created,
+            duplicates_skipped: report.duplicates_skipped,
+            errors: report
+                .errors
+                .into_iter()
+                .map(proto::ValidationError::from)
+                .collect(),
+            warnings: report
+                .warnings
+                .into_iter()
+                .map(proto::ValidationWarning::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<proto::ImportGraphResponse> for ImportReport {
+    fn from(p: proto::ImportGraphResponse) -> Self {
+        Self {
+            nodes_created: p.nodes_created,
+            edges_created: p.edges_created,
+            duplicates_skipped: p.duplicates_skipped,
+            errors: p
+                .errors
+                .into_iter()
+                .map(ValidationDiagnostic::from)
+                .collect(),
+            warnings: p
+                .warnings
+                .into_iter()
+                .map(ValidationDiagnostic::from)
+                .collect(),
+        }
+    }
+}
+
 /// A single validation diagnostic (error or warning).
 #[derive(Debug, Clone)]
 pub struct ValidationDiagnostic {
@@ -430,6 +1293,46 @@ pub struct ValidationDiagnostic {
     pub context: Option<String>,
 }
 
+impl From<ValidationDiagnostic> for proto::ValidationError {
+    fn from(d: ValidationDiagnostic) -> Self {
+        Self {
+            code: d.code,
+            message: d.message,
+            context: d.context,
+        }
+    }
+}
+
+impl From<ValidationDiagnostic> for proto::ValidationWarning {
+    fn from(d: ValidationDiagnostic) -> Self {
+        Self {
+            code: d.code,
+            message: d.message,
+            context: d.context,
+        }
+    }
+}
+
+impl From<proto::ValidationError> for ValidationDiagnostic {
+    fn from(e: proto::ValidationError) -> Self {
+        Self {
+            code: e.code,
+            message: e.message,
+            context: e.context,
+        }
+    }
+}
+
+impl From<proto::ValidationWarning> for ValidationDiagnostic {
+    fn from(w: proto::ValidationWarning) -> Self {
+        Self {
+            code: w.code,
+            message: w.message,
+            context: w.context,
+        }
+    }
+}
+
 /// Index definition for create/drop operations.
 #[derive(Debug, Clone)]
 pub enum IndexDefinition {
@@ -462,6 +1365,165 @@ pub enum IndexDefinition {
     },
 }
 
+impl From<IndexDefinition> for proto::create_index_request::Index {
+    fn from(index: IndexDefinition) -> Self {
+        match index {
+            IndexDefinition::Property { property } => {
+                Self::PropertyIndex(proto::PropertyIndexDef { property })
+            }
+            IndexDefinition::Vector {
+                label,
+                property,
+                dimensions,
+                metric,
+                m,
+                ef_construction,
+            } => Self::VectorIndex(proto::VectorIndexDef {
+                label,
+                property,
+                dimensions,
+                metric,
+                m,
+                ef_construction,
+            }),
+            IndexDefinition::Text { label, property } => {
+                Self::TextIndex(proto::TextIndexDef { label, property })
+            }
+        }
+    }
+}
+
+impl From<proto::create_index_request::Index> for IndexDefinition {
+    fn from(index: proto::create_index_request::Index) -> Self {
+        use proto::create_index_request::Index;
+        match index {
+            Index::PropertyIndex(def) => Self::Property {
+                property: def.property,
+            },
+            Index::VectorIndex(def) => Self::Vector {
+                label: def.label,
+                property: def.property,
+                dimensions: def.dimensions,
+                metric: def.metric,
+                m: def.m,
+                ef_construction: def.ef_construction,
+            },
+            Index::TextIndex(def) => Self::Text {
+                label: def.label,
+                property: def.property,
+            },
+        }
+    }
+}
+
+/// An index on a graph, as listed by [`GqlBackend::list_indexes`].
+#[derive(Debug, Clone)]
+pub struct IndexInfo {
+    /// What the index covers, with the options it was built with.
+    pub definition: IndexDefinition,
+    /// Size of the index in bytes.
+    pub size_bytes: u64,
+    /// Whether the index is ready for queries.
+    pub state: IndexState,
+}
+
+/// Build state of an index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndexState {
+    /// Built and used by queries.
+    #[default]
+    Ready,
+    /// Still being built, and not yet used by queries.
+    Building,
+    /// The build failed; the index should be dropped and recreated.
+    Failed,
+}
+
+impl From<proto::IndexState> for IndexState {
+    fn from(state: proto::IndexState) -> Self {
+        match state {
+            proto::IndexState::Ready => Self::Ready,
+            proto::IndexState::Building => Self::Building,
+            proto::IndexState::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<IndexState> for proto::IndexState {
+    fn from(state: IndexState) -> Self {
+        match state {
+            IndexState::Ready => Self::Ready,
+            IndexState::Building => Self::Building,
+            IndexState::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<IndexInfo> for proto::IndexInfo {
+    fn from(info: IndexInfo) -> Self {
+        use proto::index_info::Index;
+        let index = match info.definition {
+            IndexDefinition::Property { property } => {
+                Index::PropertyIndex(proto::PropertyIndexDef { property })
+            }
+            IndexDefinition::Vector {
+                label,
+                property,
+                dimensions,
+                metric,
+                m,
+                ef_construction,
+            } => Index::VectorIndex(proto::VectorIndexDef {
+                label,
+                property,
+                dimensions,
+                metric,
+                m,
+                ef_construction,
+            }),
+            IndexDefinition::Text { label, property } => {
+                Index::TextIndex(proto::TextIndexDef { label, property })
+            }
+        };
+        Self {
+            index: Some(index),
+            size_bytes: info.size_bytes,
+            state: proto::IndexState::from(info.state).into(),
+        }
+    }
+}
+
+impl TryFrom<proto::IndexInfo> for IndexInfo {
+    type Error = GqlError;
+
+    fn try_from(p: proto::IndexInfo) -> Result<Self, GqlError> {
+        use proto::index_info::Index;
+        let definition = match p.index {
+            Some(Index::PropertyIndex(def)) => IndexDefinition::Property {
+                property: def.property,
+            },
+            Some(Index::VectorIndex(def)) => IndexDefinition::Vector {
+                label: def.label,
+                property: def.property,
+                dimensions: def.dimensions,
+                metric: def.metric,
+                m: def.m,
+                ef_construction: def.ef_construction,
+            },
+            Some(Index::TextIndex(def)) => IndexDefinition::Text {
+                label: def.label,
+                property: def.property,
+            },
+            None => return Err(GqlError::Protocol("index without a definition".into())),
+        };
+        Ok(Self {
+            definition,
+            size_bytes: p.size_bytes,
+            state: proto::IndexState::try_from(p.state).map_or(IndexState::Ready, IndexState::from),
+        })
+    }
+}
+
 // ============================================================================
 // Search types
 // ============================================================================
@@ -471,7 +1533,7 @@ pub enum IndexDefinition {
 pub struct VectorSearchParams {
     /// Graph name.
     pub graph: String,
-    /// Node label.
+    /// Node label (or edge label when targeting edges).
     pub label: String,
     /// Property name.
     pub property: String,
@@ -483,6 +1545,14 @@ pub struct VectorSearchParams {
     pub ef: Option<u32>,
     /// Property filters.
     pub filters: std::collections::HashMap<String, Value>,
+    /// Number of leading hits to skip.
+    pub offset: u32,
+    /// Continuation token from a previous page (takes precedence over `offset`).
+    pub page_token: Option<String>,
+    /// Whether to return the full graph element with each hit.
+    pub include_elements: bool,
+    /// Whether to search nodes or edges.
+    pub target: SearchTarget,
 }
 
 /// Text search parameters.
@@ -490,7 +1560,7 @@ pub struct VectorSearchParams {
 pub struct TextSearchParams {
     /// Graph name.
     pub graph: String,
-    /// Node label.
+    /// Node label (or edge label when targeting edges).
     pub label: String,
     /// Property name.
     pub property: String,
@@ -498,6 +1568,14 @@ pub struct TextSearchParams {
     pub query: String,
     /// Number of results.
     pub k: u32,
+    /// Number of leading hits to skip.
+    pub offset: u32,
+    /// Continuation token from a previous page (takes precedence over `offset`).
+    pub page_token: Option<String>,
+    /// Whether to return the full graph element with each hit.
+    pub include_elements: bool,
+    /// Whether to search nodes or edges.
+    pub target: SearchTarget,
 }
 
 /// Hybrid search parameters.
@@ -505,7 +1583,7 @@ pub struct TextSearchParams {
 pub struct HybridSearchParams {
     /// Graph name.
     pub graph: String,
-    /// Node label.
+    /// Node label (or edge label when targeting edges).
     pub label: String,
     /// Text property name.
     pub text_property: String,
@@ -517,6 +1595,184 @@ pub struct HybridSearchParams {
     pub query_vector: Vec<f32>,
     /// Number of results.
     pub k: u32,
+    /// Number of leading hits to skip.
+    pub offset: u32,
+    /// Continuation token from a previous page (takes precedence over `offset`).
+    pub page_token: Option<String>,
+    /// Whether to return the full graph element with each hit.
+    pub include_elements: bool,
+    /// Whether to search nodes or edges.
+    pub target: SearchTarget,
+}
+
+/// Builder-style setters for the paging and target fields shared by
+/// every search's parameters.
+macro_rules! search_options {
+    ($params:ty) => {
+        impl $params {
+            /// Skip the first `offset` hits.
+            #[must_use]
+            pub fn offset(mut self, offset: u32) -> Self {
+                self.offset = offset;
+                self
+            }
+
+            /// Continue from the page a previous search ended at.
+            #[must_use]
+            pub fn page_token(mut self, token: impl Into<String>) -> Self {
+                self.page_token = Some(token.into());
+                self
+            }
+
+            /// Return the full graph element with each hit.
+            #[must_use]
+            pub fn include_elements(mut self) -> Self {
+                self.include_elements = true;
+                self
+            }
+
+            /// Search edges instead of nodes.
+            #[must_use]
+            pub fn edges(mut self) -> Self {
+                self.target = SearchTarget::Edges;
+                self
+            }
+        }
+    };
+}
+
+impl VectorSearchParams {
+    /// Find the `k` nodes of `label` whose `property` vector is nearest to
+    /// `query_vector`.
+    #[must_use]
+    pub fn new(
+        graph: impl Into<String>,
+        label: impl Into<String>,
+        property: impl Into<String>,
+        query_vector: Vec<f32>,
+        k: u32,
+    ) -> Self {
+        Self {
+            graph: graph.into(),
+            label: label.into(),
+            property: property.into(),
+            query_vector,
+            k,
+            ef: None,
+            filters: std::collections::HashMap::new(),
+            offset: 0,
+            page_token: None,
+            include_elements: false,
+            target: SearchTarget::Nodes,
+        }
+    }
+
+    /// Set the search beam width.
+    #[must_use]
+    pub fn ef(mut self, ef: u32) -> Self {
+        self.ef = Some(ef);
+        self
+    }
+
+    /// Only return hits whose `property` equals `value`.
+    #[must_use]
+    pub fn filter(mut self, property: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.filters.insert(property.into(), value.into());
+        self
+    }
+}
+
+impl TextSearchParams {
+    /// Find the `k` nodes of `label` whose `property` best matches `query`.
+    #[must_use]
+    pub fn new(
+        graph: impl Into<String>,
+        label: impl Into<String>,
+        property: impl Into<String>,
+        query: impl Into<String>,
+        k: u32,
+    ) -> Self {
+        Self {
+            graph: graph.into(),
+            label: label.into(),
+            property: property.into(),
+            query: query.into(),
+            k,
+            offset: 0,
+            page_token: None,
+            include_elements: false,
+            target: SearchTarget::Nodes,
+        }
+    }
+}
+
+impl HybridSearchParams {
+    /// Find the `k` nodes of `label` ranking best across a text search of
+    /// `text_property` for `query_text` and, once a query vector is set
+    /// with [`query_vector`](Self::query_vector), a vector search of
+    /// `vector_property`.
+    #[must_use]
+    pub fn new(
+        graph: impl Into<String>,
+        label: impl Into<String>,
+        text_property: impl Into<String>,
+        vector_property: impl Into<String>,
+        query_text: impl Into<String>,
+        k: u32,
+    ) -> Self {
+        Self {
+            graph: graph.into(),
+            label: label.into(),
+            text_property: text_property.into(),
+            vector_property: vector_property.into(),
+            query_text: query_text.into(),
+            query_vector: Vec::new(),
+            k,
+            offset: 0,
+            page_token: None,
+            include_elements: false,
+            target: SearchTarget::Nodes,
+        }
+    }
+
+    /// Rank by vector similarity to `query_vector` as well as by text.
+    #[must_use]
+    pub fn query_vector(mut self, query_vector: Vec<f32>) -> Self {
+        self.query_vector = query_vector;
+        self
+    }
+}
+
+search_options!(VectorSearchParams);
+search_options!(TextSearchParams);
+search_options!(HybridSearchParams);
+
+/// Which kind of graph element a search targets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchTarget {
+    /// Search node labels and node properties.
+    #[default]
+    Nodes,
+    /// Search edge labels and edge properties.
+    Edges,
+}
+
+impl From<proto::SearchTarget> for SearchTarget {
+    fn from(target: proto::SearchTarget) -> Self {
+        match target {
+            proto::SearchTarget::SearchNodes => Self::Nodes,
+            proto::SearchTarget::SearchEdges => Self::Edges,
+        }
+    }
+}
+
+impl From<SearchTarget> for proto::SearchTarget {
+    fn from(target: SearchTarget) -> Self {
+        match target {
+            SearchTarget::Nodes => Self::SearchNodes,
+            SearchTarget::Edges => Self::SearchEdges,
+        }
+    }
 }
 
 /// A single search result hit.
@@ -524,12 +1780,38 @@ pub struct HybridSearchParams {
 /// Search results use a numeric `node_id` (uint64) rather than the opaque
 /// `bytes` element ID from the GQL type system. This is an internal
 /// identifier suitable for fast lookups; it is not the same as `Node.id`.
+/// For edge searches, `node_id` identifies the matched edge and the
+/// endpoint fields are populated.
 #[derive(Debug, Clone)]
 pub struct SearchHit {
-    /// Internal numeric node identifier (not the opaque GQL element ID).
+    /// Internal numeric identifier of the matched node or edge
+    /// (not the opaque GQL element ID).
     pub node_id: u64,
     /// Relevance score (distance for vector, BM25 for text).
     pub score: f64,
-    /// Node properties.
+    /// Element properties.
     pub properties: std::collections::HashMap<String, Value>,
+    /// The full node, when the request asked for `include_elements`.
+    pub node: Option<Node>,
+    /// The full edge, for edge hits when the request asked for `include_elements`.
+    pub edge: Option<Edge>,
+    /// Internal numeric identifier of the source node (edge hits only).
+    pub source_node_id: Option<u64>,
+    /// Internal numeric identifier of the target node (edge hits only).
+    pub target_node_id: Option<u64>,
+}
+
+/// A page of search results.
+///
+/// `next_page_token` is set when more hits are available; pass it back
+/// as `page_token` on the next request to continue. Backends that cannot
+/// produce tokens may leave it unset and rely on `offset` paging instead.
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    /// Hits on this page, best first.
+    pub hits: Vec<SearchHit>,
+    /// Token for fetching the next page, if any.
+    pub next_page_token: Option<String>,
+    /// Estimated total number of matching hits, if known.
+    pub total_hits_estimate: Option<u64>,
 }
@@ -0,0 +1,88 @@
+//! Database backups streamed between the client and the backend.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tonic::Status;
+
+use crate::error::GqlError;
+use crate::proto;
+
+use super::backend::BackupStream;
+
+/// The chunks of a backup as they arrive from the client, passed to
+/// [`GqlBackend::restore`](super::GqlBackend::restore).
+pub struct RestoreChunks {
+    requests: tonic::Streaming<proto::RestoreDatabaseRequest>,
+    bytes_read: u64,
+}
+
+impl RestoreChunks {
+    pub(crate) fn new(requests: tonic::Streaming<proto::RestoreDatabaseRequest>) -> Self {
+        Self {
+            requests,
+            bytes_read: 0,
+        }
+    }
+
+    /// Wait for the next chunk, or `None` once the client has sent them
+    /// all.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client's stream fails, in which case the
+    /// backup is incomplete and must not be applied.
+    pub async fn next(&mut self) -> Result<Option<Vec<u8>>, GqlError> {
+        let Some(request) = self.requests.message().await? else {
+            return Ok(None);
+        };
+        self.bytes_read += request.data.len() as u64;
+        Ok(Some(request.data))
+    }
+
+    /// Number of backup bytes read so far.
+    #[must_use]
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+}
+
+impl std::fmt::Debug for RestoreChunks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RestoreChunks")
+            .field("bytes_read", &self.bytes_read)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Adapts a backend's [`BackupStream`] to the `BackupDatabase` response
+/// stream.
+pub struct BackupChunks {
+    inner: Pin<Box<dyn BackupStream>>,
+}
+
+impl BackupChunks {
+    pub(crate) fn new(inner: Pin<Box<dyn BackupStream>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl tokio_stream::Stream for BackupChunks {
+    type Item = Result<proto::BackupChunk, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx).map(|chunk| {
+            chunk.map(|chunk| {
+                chunk
+                    .map(|data| proto::BackupChunk { data })
+                    .map_err(|e| e.to_grpc_status())
+            })
+        })
+    }
+}
+
+impl std::fmt::Debug for BackupChunks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackupChunks").finish_non_exhaustive()
+    }
+}
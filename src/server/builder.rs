@@ -8,29 +8,165 @@ use std::time::Duration;
 
 use tonic::transport::Server;
 
+use crate::error::GqlError;
+use crate::proto;
 use crate::proto::admin_service_server::AdminServiceServer;
 use crate::proto::catalog_service_server::CatalogServiceServer;
 use crate::proto::gql_service_server::GqlServiceServer;
+use crate::proto::notification_service_server::NotificationServiceServer;
 use crate::proto::search_service_server::SearchServiceServer;
 use crate::proto::session_service_server::SessionServiceServer;
 
-use super::admin_service::AdminServiceImpl;
-use super::auth::AuthValidator;
-use super::backend::{GqlBackend, SessionHandle};
+use super::admin_service::{ADMIN_FEATURE, AdminServiceImpl};
+use super::auth::{AuthValidator, Authorizer};
+use super::backend::{GqlBackend, HealthStatus, SessionHandle};
 use super::catalog_service::CatalogServiceImpl;
+use super::deadlines::DefaultDeadlines;
+use super::drain::{self, DrainHandle};
 use super::gql_service::GqlServiceImpl;
-use super::search_service::SearchServiceImpl;
+use super::notification_service::NotificationServiceImpl;
+use super::query_log::{QueryLogOptions, QueryLogger};
+use super::rate_limit::RateLimits;
+use super::result_cache::ResultCache;
+use super::search_service::{SEARCH_FEATURE, SearchServiceImpl};
 use super::session_service::SessionServiceImpl;
-use super::{SessionManager, TransactionManager};
+use super::statement_limits::{ResultLimits, StatementLimits};
+use super::{QueryStats, SessionManager, TransactionManager};
+
+/// Enable the builder's compression encodings for sending and accepting
+/// messages on a generated service server, and apply its message size
+/// limit.
+macro_rules! configured {
+    ($server:expr, $builder:expr) => {{
+        let server = $builder
+            .compression
+            .iter()
+            .fold($server, |server, &encoding| {
+                server.send_compressed(encoding).accept_compressed(encoding)
+            });
+        match $builder.max_message_size {
+            Some(max) => server
+                .max_decoding_message_size(max)
+                .max_encoding_message_size(max),
+            None => server,
+        }
+    }};
+}
+
+/// Where the server accepts connections.
+enum Listen {
+    /// Bind to an address.
+    Addr(SocketAddr),
+    /// Accept on an already-bound listener.
+    Listener(tokio::net::TcpListener),
+    /// Accept on a Unix domain socket bound at the given path.
+    #[cfg(unix)]
+    Uds(tokio::net::UnixListener, std::path::PathBuf),
+    /// Accept in-memory streams from a
+    /// [`LocalConnection`](crate::client::LocalConnection).
+    Local(tokio::sync::mpsc::Receiver<tokio::io::DuplexStream>),
+}
+
+/// The layers wrapped around every service.
+#[cfg(not(feature = "web"))]
+type ServerLayer = tower::layer::util::Identity;
+
+/// The layers wrapped around every service: CORS, then gRPC-Web
+/// translation, both skipped unless
+/// [`grpc_web`](GqlServer::grpc_web) is set.
+#[cfg(feature = "web")]
+type ServerLayer = tower::layer::util::Stack<
+    tower::util::Either<tonic_web::GrpcWebLayer, tower::layer::util::Identity>,
+    tower::layer::util::Stack<
+        tower::util::Either<tower_http::cors::CorsLayer, tower::layer::util::Identity>,
+        tower::layer::util::Identity,
+    >,
+>;
+
+/// A transport server that also accepts gRPC-Web requests, answering
+/// CORS with `cors`, if it is set.
+#[cfg(feature = "web")]
+fn web_transport(cors: Option<tower_http::cors::CorsLayer>) -> Server<ServerLayer> {
+    let web = cors.is_some();
+    Server::builder()
+        .accept_http1(web)
+        .layer(tower::util::option_layer(cors))
+        .layer(tower::util::option_layer(
+            web.then(tonic_web::GrpcWebLayer::new),
+        ))
+}
+
+impl Listen {
+    /// Serve `router` on these connections until `signal` completes.
+    async fn serve(
+        self,
+        router: tonic::transport::server::Router<ServerLayer>,
+        signal: impl Future<Output = ()>,
+    ) -> Result<(), tonic::transport::Error> {
+        match self {
+            Self::Addr(addr) => {
+                tracing::info!(%addr, "GWP server listening");
+                router.serve_with_shutdown(addr, signal).await
+            }
+            Self::Listener(listener) => {
+                if let Ok(addr) = listener.local_addr() {
+                    tracing::info!(%addr, "GWP server listening");
+                }
+                let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+                router.serve_with_incoming_shutdown(incoming, signal).await
+            }
+            #[cfg(unix)]
+            Self::Uds(listener, path) => {
+                tracing::info!(path = %path.display(), "GWP server listening");
+                let incoming = tokio_stream::wrappers::UnixListenerStream::new(listener);
+                let result = router.serve_with_incoming_shutdown(incoming, signal).await;
+                let _ = std::fs::remove_file(&path);
+                result
+            }
+            Self::Local(incoming) => {
+                tracing::info!("GWP server listening in process");
+                let incoming = tokio_stream::StreamExt::map(
+                    tokio_stream::wrappers::ReceiverStream::new(incoming),
+                    Ok::<_, std::io::Error>,
+                );
+                router.serve_with_incoming_shutdown(incoming, signal).await
+            }
+        }
+    }
+}
 
 /// Builder for the GQL wire protocol server.
 pub struct GqlServer<B: GqlBackend> {
-    backend: B,
+    backend: Arc<B>,
     #[cfg(feature = "tls")]
     tls_config: Option<tonic::transport::ServerTlsConfig>,
+    #[cfg(feature = "web")]
+    grpc_web: Option<tower_http::cors::CorsLayer>,
+    compression: Vec<tonic::codec::CompressionEncoding>,
     auth_validator: Option<Arc<dyn AuthValidator>>,
+    authorizer: Option<Arc<dyn Authorizer>>,
     idle_timeout: Option<Duration>,
     max_sessions: Option<usize>,
+    max_sessions_per_database: Option<usize>,
+    database_session_limits: Vec<(String, usize)>,
+    deadlines: DefaultDeadlines,
+    result_cache: Option<ResultCache>,
+    query_logger: Option<Arc<dyn QueryLogger>>,
+    query_log_options: QueryLogOptions,
+    statement_limits: StatementLimits,
+    result_limits: ResultLimits,
+    rate_limits: RateLimits,
+    max_in_flight_frames: Option<usize>,
+    max_message_size: Option<usize>,
+    statement_timeout: Option<Duration>,
+    slow_query_threshold: Option<Duration>,
+    resumption_window: Option<Duration>,
+    routing_table: Option<(String, Vec<String>)>,
+    health_check_interval: Duration,
+    drain: DrainHandle,
+    drain_grace: Option<Duration>,
+    admin: bool,
+    search: bool,
     shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
 }
 
@@ -38,19 +174,77 @@ impl<B: GqlBackend> GqlServer<B> {
     /// Start building a server with the given backend.
     #[must_use]
     pub fn builder(backend: B) -> Self {
+        Self::builder_shared(Arc::new(backend))
+    }
+
+    /// Start building a server around a backend the caller keeps a
+    /// handle to, for administering or inspecting it directly while the
+    /// server runs, or for serving one backend from several servers.
+    #[must_use]
+    pub fn builder_shared(backend: Arc<B>) -> Self {
         Self {
             backend,
             #[cfg(feature = "tls")]
             tls_config: None,
+            #[cfg(feature = "web")]
+            grpc_web: None,
+            compression: Vec::new(),
             auth_validator: None,
+            authorizer: None,
             idle_timeout: None,
             max_sessions: None,
+            max_sessions_per_database: None,
+            database_session_limits: Vec::new(),
+            deadlines: DefaultDeadlines::default(),
+            result_cache: None,
+            query_logger: None,
+            query_log_options: QueryLogOptions::default(),
+            statement_limits: StatementLimits::default(),
+            result_limits: ResultLimits::default(),
+            rate_limits: RateLimits::default(),
+            max_in_flight_frames: None,
+            max_message_size: None,
+            statement_timeout: None,
+            slow_query_threshold: None,
+            resumption_window: None,
+            routing_table: None,
+            health_check_interval: Duration::from_secs(5),
+            drain: DrainHandle::default(),
+            drain_grace: None,
+            admin: true,
+            search: true,
             shutdown: None,
         }
     }
 
+    /// Leave out the `AdminService`, for servers that should not expose
+    /// statistics, session administration, or index management. Clients
+    /// see it missing from the handshake's `ServerInfo.features`.
+    #[must_use]
+    pub fn disable_admin(mut self) -> Self {
+        self.admin = false;
+        self
+    }
+
+    /// Leave out the `SearchService`, for backends without search.
+    /// Clients see it missing from the handshake's `ServerInfo.features`.
+    #[must_use]
+    pub fn disable_search(mut self) -> Self {
+        self.search = false;
+        self
+    }
+
     /// Set TLS configuration for the server.
     ///
+    /// A configuration with a
+    /// [`client_ca_root`](tonic::transport::ServerTlsConfig::client_ca_root)
+    /// requires clients to present a certificate signed by that CA. The
+    /// session service passes it to
+    /// [`AuthValidator::validate_tls`](super::AuthValidator::validate_tls)
+    /// when a handshake carries no credentials, and records its subject in
+    /// the session's client info under
+    /// [`CLIENT_CERT_SUBJECT`](super::CLIENT_CERT_SUBJECT).
+    ///
     /// Requires the `tls` feature to be enabled.
     #[cfg(feature = "tls")]
     #[must_use]
@@ -59,6 +253,66 @@ impl<B: GqlBackend> GqlServer<B> {
         self
     }
 
+    /// Accept gRPC-Web requests over HTTP/1.1 alongside plain gRPC, so
+    /// browser-based consoles can call the server directly, answering
+    /// cross-origin requests according to `cors`.
+    ///
+    /// Browsers only pass gRPC-Web responses through to the page when
+    /// `cors` exposes the `grpc-status` and `grpc-message` headers and
+    /// allows the request headers the client sends, such as
+    /// `content-type`, `x-grpc-web`, and `x-user-agent`.
+    ///
+    /// Requires the `web` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use http::HeaderName;
+    /// use tower_http::cors::{AllowOrigin, CorsLayer};
+    /// use gwp::server::GqlServer;
+    /// use gwp::server::mock_backend::MockBackend;
+    ///
+    /// let cors = CorsLayer::new()
+    ///     .allow_origin(AllowOrigin::exact("https://console.example.com".parse().unwrap()))
+    ///     .allow_headers([
+    ///         HeaderName::from_static("content-type"),
+    ///         HeaderName::from_static("x-grpc-web"),
+    ///         HeaderName::from_static("x-user-agent"),
+    ///     ])
+    ///     .expose_headers([
+    ///         HeaderName::from_static("grpc-status"),
+    ///         HeaderName::from_static("grpc-message"),
+    ///     ]);
+    /// let server = GqlServer::builder(MockBackend::new()).grpc_web(cors);
+    /// ```
+    #[cfg(feature = "web")]
+    #[must_use]
+    pub fn grpc_web(mut self, cors: tower_http::cors::CorsLayer) -> Self {
+        self.grpc_web = Some(cors);
+        self
+    }
+
+    /// Compress messages with `compression` for clients that accept it,
+    /// and accept messages clients compressed with it. Call once per
+    /// encoding to offer several, most preferred first.
+    ///
+    /// Clients pick an encoding per call, so a statement's result stream
+    /// is compressed only when its client asks; see
+    /// [`GqlSession::set_compression`](crate::client::GqlSession::set_compression).
+    /// Gzip needs the `gzip` feature and zstd the `zstd` feature; without
+    /// it the encoding is not offered.
+    #[must_use]
+    pub fn compression(mut self, compression: proto::Compression) -> Self {
+        match compression.encoding() {
+            Some(encoding) => self.compression.push(encoding),
+            None if compression != proto::Compression::None => {
+                tracing::warn!(?compression, "compression not supported by this build");
+            }
+            None => {}
+        }
+        self
+    }
+
     /// Set an authentication validator.
     ///
     /// When set, the server requires valid credentials on every handshake.
@@ -69,6 +323,18 @@ impl<B: GqlBackend> GqlServer<B> {
         self
     }
 
+    /// Set an authorizer consulted on every statement and every catalog,
+    /// admin, and search call.
+    ///
+    /// The authorizer sees the principal returned by the
+    /// [`AuthValidator`] at handshake. When not set, every operation is
+    /// allowed.
+    #[must_use]
+    pub fn authorizer(mut self, authorizer: impl Authorizer) -> Self {
+        self.authorizer = Some(Arc::new(authorizer));
+        self
+    }
+
     /// Set the idle timeout for sessions.
     ///
     /// Sessions with no activity for longer than this duration will be
@@ -90,11 +356,263 @@ impl<B: GqlBackend> GqlServer<B> {
         self
     }
 
+    /// Set the maximum number of concurrent sessions on any one database.
+    ///
+    /// Sessions count against the database (`schema/graph`, prefixed
+    /// with `database/` after `USE database`) they have selected.
+    /// Handshakes, `Configure` and `Reset` requests that would take a
+    /// database over its limit are rejected with `RESOURCE_EXHAUSTED`,
+    /// so one busy database cannot starve the others.
+    #[must_use]
+    pub fn max_sessions_per_database(mut self, limit: usize) -> Self {
+        self.max_sessions_per_database = Some(limit);
+        self
+    }
+
+    /// Set the maximum number of concurrent sessions on `database`,
+    /// named `schema/graph` or `database/schema/graph`, overriding
+    /// [`max_sessions_per_database`](Self::max_sessions_per_database).
+    #[must_use]
+    pub fn database_session_limit(mut self, database: impl Into<String>, limit: usize) -> Self {
+        self.database_session_limits.push((database.into(), limit));
+        self
+    }
+
+    /// Set default deadlines per RPC class.
+    ///
+    /// They apply to requests whose client set no deadline; requests that
+    /// exceed them fail with `DEADLINE_EXCEEDED`. By default no deadline
+    /// is applied.
+    #[must_use]
+    pub fn default_deadlines(mut self, deadlines: DefaultDeadlines) -> Self {
+        self.deadlines = deadlines;
+        self
+    }
+
+    /// Cache results of repeated read-only statements.
+    ///
+    /// Holds up to `capacity` complete results, each served for at most
+    /// `ttl`. Entries are keyed by the session's schema and graph, the
+    /// statement, and its parameters; statements run inside transactions
+    /// bypass the cache. A write statement or commit drops the entries of
+    /// the database it ran against. Read-only statements are recognized
+    /// by [`is_read_only_statement`](super::is_read_only_statement).
+    #[must_use]
+    pub fn result_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.result_cache = Some(ResultCache::new(capacity, ttl));
+        self
+    }
+
+    /// Cache results in `cache`, as [`result_cache`](Self::result_cache)
+    /// does, sharing it with the caller.
+    ///
+    /// A backend holding a clone of `cache` can call
+    /// [`ResultCache::invalidate`] when data changes other than through
+    /// GQL statements, such as bulk loads or replication.
+    #[must_use]
+    pub fn shared_result_cache(mut self, cache: ResultCache) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// Send an event for every executed statement to `logger`, for audit
+    /// trails and slow-query analysis.
+    ///
+    /// Events carry the session, statement, duration, GQLSTATUS, and row
+    /// count, and are sent once each result ends. Use
+    /// [`JsonLinesQueryLogger`](super::JsonLinesQueryLogger) to write them
+    /// to a file, [`TracingQueryLogger`](super::TracingQueryLogger) to emit
+    /// them as `tracing` events, or a closure.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gwp::server::{GqlServer, JsonLinesQueryLogger};
+    /// use gwp::server::mock_backend::MockBackend;
+    ///
+    /// let logger = JsonLinesQueryLogger::open("queries.jsonl").unwrap();
+    /// let server = GqlServer::builder(MockBackend::new()).query_logger(logger);
+    /// ```
+    #[must_use]
+    pub fn query_logger(mut self, logger: impl QueryLogger) -> Self {
+        self.query_logger = Some(Arc::new(logger));
+        self
+    }
+
+    /// Choose what the [`query_logger`](Self::query_logger) records. By
+    /// default statements are logged in full and parameter values are
+    /// left out.
+    #[must_use]
+    pub fn query_log_options(mut self, options: QueryLogOptions) -> Self {
+        self.query_log_options = options;
+        self
+    }
+
+    /// Set limits on statement text length and parameter count.
+    ///
+    /// Oversized statements are rejected before they reach the backend,
+    /// with a [`STATEMENT_TOO_LARGE`](crate::status::STATEMENT_TOO_LARGE)
+    /// summary. By default statements are unbounded.
+    #[must_use]
+    pub fn statement_limits(mut self, limits: StatementLimits) -> Self {
+        self.statement_limits = limits;
+        self
+    }
+
+    /// Set limits on the rows and bytes each statement may return.
+    ///
+    /// A result that would go over a limit ends with a
+    /// [`RESULT_TOO_LARGE`](crate::status::RESULT_TOO_LARGE) summary
+    /// instead, and the backend's result stream is dropped. Clients may
+    /// ask for lower limits per statement. By default results are
+    /// unbounded.
+    #[must_use]
+    pub fn result_limits(mut self, limits: ResultLimits) -> Self {
+        self.result_limits = limits;
+        self
+    }
+
+    /// Set rate limits on statements, counted per session or, with
+    /// [`RateLimitKey::Principal`](super::RateLimitKey::Principal), per
+    /// authenticated principal.
+    ///
+    /// Statements over the statement rate or the concurrent stream limit
+    /// fail with `RESOURCE_EXHAUSTED` before they reach the backend.
+    /// Results over the row rate are paced instead, and end with a
+    /// [`WARNING_THROTTLED`](crate::status::WARNING_THROTTLED) warning.
+    /// By default nothing is limited.
+    #[must_use]
+    pub fn rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limits = limits;
+        self
+    }
+
+    /// Abort statements whose result has not ended within `timeout`.
+    ///
+    /// The result ends early with a
+    /// [`STATEMENT_TIMEOUT`](crate::status::STATEMENT_TIMEOUT) summary, and
+    /// the backend's result stream is dropped. Clients may ask for a
+    /// shorter timeout per statement. By default statements run until
+    /// they finish or the client gives up.
+    #[must_use]
+    pub fn statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
+    /// Flag statements whose result takes longer than `threshold`, from
+    /// request until the summary is sent.
+    ///
+    /// The summary of a slow statement carries a
+    /// [`WARNING_SLOW_QUERY`](crate::status::WARNING_SLOW_QUERY) warning
+    /// with its timing, and the server emits a `tracing` warning under the
+    /// `gwp::slow_query` target splitting the time between the backend
+    /// executing the statement and the result streaming to the client. By
+    /// default no threshold is set.
+    #[must_use]
+    pub fn slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_threshold = Some(threshold);
+        self
+    }
+
+    /// Let each result stream run up to `max` frames ahead of the client.
+    ///
+    /// By default the server pulls result frames from the backend only as
+    /// gRPC flow control lets it send them, so a slow client slows the
+    /// backend down. With this set, a background task keeps up to `max`
+    /// frames ready for each stream, hiding backend latency at the cost
+    /// of holding that many frames per stream in memory.
+    #[must_use]
+    pub fn max_in_flight_frames(mut self, max: usize) -> Self {
+        self.max_in_flight_frames = Some(max);
+        self
+    }
+
+    /// Limit the messages every service sends and accepts to `max` bytes
+    /// (default: tonic's 4 MiB for received messages, no limit for sent
+    /// ones). Row batches larger than the limit, or 4 MiB by default, are
+    /// split into several result frames rather than failing the stream;
+    /// clients need at least the same limit, set with
+    /// [`GqlConnection::with_max_message_size`](crate::client::GqlConnection::with_max_message_size).
+    #[must_use]
+    pub fn max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = Some(max);
+        self
+    }
+
+    /// Let clients resume sessions after a dropped connection.
+    ///
+    /// Each handshake returns a single-use resumption token. Presenting it
+    /// in a later handshake reattaches to the same session, with its
+    /// schema, graph, and parameters, if the session was active within
+    /// `window`; open transactions are rolled back. Otherwise a new
+    /// session is created. The `Resume` RPC reattaches the same way but
+    /// keeps the active transaction, and fails instead of creating a
+    /// session. Sessions that can still be resumed are kept for `window`
+    /// even if the idle timeout is shorter.
+    #[must_use]
+    pub fn session_resumption(mut self, window: Duration) -> Self {
+        self.resumption_window = Some(window);
+        self
+    }
+
+    /// Advertise a routing table in every handshake: `writer` takes writes
+    /// and read-write transactions, and `readers` serve read-only
+    /// statements and transactions. Clients that route, such as
+    /// [`ClusterConnection::route`](crate::client::ClusterConnection::route),
+    /// connect to the advertised endpoints.
+    #[must_use]
+    pub fn routing_table(
+        mut self,
+        writer: impl Into<String>,
+        readers: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.routing_table = Some((writer.into(), readers.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// How often to poll [`GqlBackend::health`] for the gRPC health
+    /// service (default: 5 seconds). While the backend reports
+    /// [`NotServing`](super::HealthStatus::NotServing), so does every
+    /// service the server runs.
+    #[must_use]
+    pub fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// Drain sessions before shutting down, giving open transactions
+    /// `grace` to finish.
+    ///
+    /// When the shutdown signal fires, the server stops accepting new
+    /// sessions and sends each session a
+    /// [`DrainNotice`](crate::proto::DrainNotice) on its `ServerEvents`
+    /// stream. Once no transaction is open, or `grace` has passed, it
+    /// rolls back the transactions still open, closes every session, and
+    /// then shuts down. `grace` also applies to drains started with a
+    /// [`drain_handle`](Self::drain_handle).
+    #[must_use]
+    pub fn drain_grace_period(mut self, grace: Duration) -> Self {
+        self.drain_grace = Some(grace);
+        self
+    }
+
+    /// A handle for draining the server while it runs, such as from a
+    /// Kubernetes `preStop` hook, without shutting it down.
+    #[must_use]
+    pub fn drain_handle(&self) -> DrainHandle {
+        self.drain.clone()
+    }
+
     /// Set a shutdown signal.
     ///
     /// When the future completes, the server will stop accepting new
     /// connections and drain in-flight requests before returning.
-    /// The idle session reaper is also stopped on shutdown.
+    /// Result streams still running end with a
+    /// [`SERVER_SHUTDOWN`](crate::status::SERVER_SHUTDOWN) summary.
+    /// The idle session reaper is also stopped on shutdown. With a
+    /// [`drain_grace_period`](Self::drain_grace_period), sessions are
+    /// drained first.
     #[must_use]
     pub fn shutdown(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
         self.shutdown = Some(Box::pin(signal));
@@ -107,79 +625,203 @@ impl<B: GqlBackend> GqlServer<B> {
     ///
     /// Returns an error if the server fails to bind or start.
     pub async fn serve(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
-        let backend = Arc::new(self.backend);
-        let sessions = match self.max_sessions {
+        self.run(Listen::Addr(addr)).await
+    }
+
+    /// Build and start serving on an already-bound listener.
+    ///
+    /// Useful for binding port 0 and learning the assigned address before
+    /// the server starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server fails to start.
+    pub async fn serve_with_listener(
+        self,
+        listener: tokio::net::TcpListener,
+    ) -> Result<(), tonic::transport::Error> {
+        self.run(Listen::Listener(listener)).await
+    }
+
+    /// Build and start serving on a Unix domain socket bound at `path`,
+    /// for clients on the same host that connect with
+    /// [`GqlConnection::connect_uds`](crate::client::GqlConnection::connect_uds).
+    ///
+    /// Nothing may exist at `path` yet. The socket file is removed when
+    /// the server stops.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket cannot be bound or the server fails
+    /// to start.
+    #[cfg(unix)]
+    pub async fn serve_uds(self, path: impl AsRef<std::path::Path>) -> Result<(), GqlError> {
+        let path = path.as_ref().to_path_buf();
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        self.run(Listen::Uds(listener, path)).await?;
+        Ok(())
+    }
+
+    /// Serve the in-memory streams `incoming` delivers, until every
+    /// sender is dropped.
+    pub(crate) async fn serve_local(
+        self,
+        incoming: tokio::sync::mpsc::Receiver<tokio::io::DuplexStream>,
+    ) -> Result<(), tonic::transport::Error> {
+        self.run(Listen::Local(incoming)).await
+    }
+
+    fn session_manager(&self) -> SessionManager {
+        let mut sessions = match self.max_sessions {
             Some(limit) => SessionManager::with_capacity(limit),
             None => SessionManager::new(),
         };
-        let transactions = TransactionManager::new();
+        if let Some(limit) = self.max_sessions_per_database {
+            sessions = sessions.with_max_sessions_per_database(limit);
+        }
+        for (database, limit) in &self.database_session_limits {
+            sessions = sessions.with_database_session_limit(database.clone(), *limit);
+        }
+        if let Some(window) = self.resumption_window {
+            sessions = sessions.with_orphan_grace(window);
+        }
+        sessions
+    }
 
-        let session_service = SessionServiceImpl::new(
-            Arc::clone(&backend),
+    fn gql_service(
+        &mut self,
+        sessions: &SessionManager,
+        transactions: &TransactionManager,
+        query_stats: &QueryStats,
+        stopping: &tokio_util::sync::CancellationToken,
+    ) -> GqlServiceImpl<B> {
+        let mut gql_service = GqlServiceImpl::new(
+            Arc::clone(&self.backend),
             sessions.clone(),
             transactions.clone(),
-            self.auth_validator,
-        );
+        )
+        .with_query_stats(query_stats.clone())
+        .with_deadlines(self.deadlines)
+        .with_statement_limits(self.statement_limits)
+        .with_rate_limits(self.rate_limits)
+        .with_result_limits(self.result_limits)
+        .with_shutdown_notice(stopping.clone());
+        if let Some(cache) = self.result_cache.take() {
+            gql_service = gql_service.with_result_cache(cache);
+        }
+        if let Some(logger) = self.query_logger.take() {
+            gql_service = gql_service.with_query_logger(logger, self.query_log_options);
+        }
+        if let Some(max) = self.max_in_flight_frames {
+            gql_service = gql_service.with_max_in_flight_frames(max);
+        }
+        if let Some(max) = self.max_message_size {
+            gql_service = gql_service.with_max_message_size(max);
+        }
+        if let Some(timeout) = self.statement_timeout {
+            gql_service = gql_service.with_statement_timeout(timeout);
+        }
+        if let Some(threshold) = self.slow_query_threshold {
+            gql_service = gql_service.with_slow_query_threshold(threshold);
+        }
+        if let Some(authorizer) = &self.authorizer {
+            gql_service = gql_service.with_authorizer(Arc::clone(authorizer));
+        }
+        gql_service
+    }
 
-        let gql_service =
-            GqlServiceImpl::new(Arc::clone(&backend), sessions.clone(), transactions.clone());
+    fn session_service(
+        &mut self,
+        sessions: &SessionManager,
+        transactions: &TransactionManager,
+        stopping: &tokio_util::sync::CancellationToken,
+    ) -> SessionServiceImpl<B> {
+        let mut session_service = SessionServiceImpl::new(
+            Arc::clone(&self.backend),
+            sessions.clone(),
+            transactions.clone(),
+            self.auth_validator.take(),
+        )
+        .with_deadlines(self.deadlines)
+        .with_drain(self.drain.clone(), self.drain_grace.unwrap_or_default())
+        .with_shutdown_notice(stopping.clone())
+        .with_features(self.features());
+        if let Some(window) = self.resumption_window {
+            session_service = session_service.with_resumption(window);
+        }
+        if let Some((writer, readers)) = self.routing_table.take() {
+            session_service = session_service.with_routing_table(writer, readers);
+        }
+        session_service
+    }
 
-        let catalog_service = CatalogServiceImpl::new(Arc::clone(&backend));
-        let admin_service = AdminServiceImpl::new(Arc::clone(&backend));
-        let search_service = SearchServiceImpl::new(Arc::clone(&backend));
+    /// The optional services this server runs, as listed in
+    /// `ServerInfo.features`.
+    fn features(&self) -> Vec<String> {
+        let mut features = Vec::new();
+        if self.admin {
+            features.push(ADMIN_FEATURE.to_owned());
+        }
+        if self.search {
+            features.push(SEARCH_FEATURE.to_owned());
+        }
+        features
+    }
 
-        // Health check service
-        let (health_reporter, health_service) = tonic_health::server::health_reporter();
-        health_reporter
-            .set_serving::<SessionServiceServer<SessionServiceImpl<B>>>()
-            .await;
-        health_reporter
-            .set_serving::<GqlServiceServer<GqlServiceImpl<B>>>()
-            .await;
-        health_reporter
-            .set_serving::<CatalogServiceServer<CatalogServiceImpl<B>>>()
-            .await;
-        health_reporter
-            .set_serving::<AdminServiceServer<AdminServiceImpl<B>>>()
-            .await;
-        health_reporter
-            .set_serving::<SearchServiceServer<SearchServiceImpl<B>>>()
-            .await;
+    async fn run(mut self, listen: Listen) -> Result<(), tonic::transport::Error> {
+        let sessions = self.session_manager();
+        let transactions = TransactionManager::new();
+        let query_stats = QueryStats::new();
+        let stopping = tokio_util::sync::CancellationToken::new();
+        let gql_service = self.gql_service(&sessions, &transactions, &query_stats, &stopping);
+        let session_service = self.session_service(&sessions, &transactions, &stopping);
+        let backend = self.backend;
+        let interval = self.health_check_interval;
+        let health = health_service(Arc::clone(&backend), self.admin, self.search, interval);
+        let (health_service, health_task) = health.await;
+
+        let mut catalog_service = CatalogServiceImpl::new(Arc::clone(&backend));
+        let mut admin_service = AdminServiceImpl::new(Arc::clone(&backend))
+            .with_query_stats(query_stats)
+            .with_sessions(sessions.clone(), transactions.clone());
+        let mut search_service = SearchServiceImpl::new(Arc::clone(&backend));
+        let mut notification_service =
+            NotificationServiceImpl::new(Arc::clone(&backend), sessions.clone())
+                .with_shutdown_notice(stopping.clone());
+        if let Some(authorizer) = self.authorizer {
+            catalog_service =
+                catalog_service.with_authorizer(Arc::clone(&authorizer), sessions.clone());
+            admin_service =
+                admin_service.with_authorizer(Arc::clone(&authorizer), sessions.clone());
+            search_service =
+                search_service.with_authorizer(Arc::clone(&authorizer), sessions.clone());
+            notification_service = notification_service.with_authorizer(authorizer);
+        }
+
+        let drain_task = spawn_drain(
+            self.drain.clone(),
+            self.drain_grace.unwrap_or_default(),
+            Arc::clone(&backend),
+            sessions.clone(),
+            transactions.clone(),
+        );
 
         // Idle session reaper
-        let reaper_handle = if let Some(timeout) = self.idle_timeout {
-            let reaper_sessions = sessions.clone();
-            let reaper_transactions = transactions.clone();
-            let reaper_backend = Arc::clone(&backend);
-            let token = tokio_util::sync::CancellationToken::new();
-            let reaper_token = token.clone();
-            let handle = tokio::spawn(async move {
-                let mut interval = tokio::time::interval(timeout / 2);
-                loop {
-                    tokio::select! {
-                        _ = interval.tick() => {
-                            let expired = reaper_sessions.reap_idle(timeout).await;
-                            for session_id in &expired {
-                                reaper_transactions.remove_for_session(session_id).await;
-                                let _ = reaper_backend
-                                    .close_session(&SessionHandle(session_id.clone()))
-                                    .await;
-                            }
-                        }
-                        () = reaper_token.cancelled() => {
-                            tracing::info!("session reaper stopped");
-                            break;
-                        }
-                    }
-                }
-            });
-            Some((handle, token))
-        } else {
-            None
-        };
+        let reaper_handle = self.idle_timeout.map(|timeout| {
+            spawn_reaper(
+                timeout,
+                Arc::clone(&backend),
+                sessions.clone(),
+                transactions.clone(),
+            )
+        });
 
+        #[cfg(not(feature = "web"))]
         let mut server = Server::builder();
 
+        #[cfg(feature = "web")]
+        let mut server = web_transport(self.grpc_web.take());
+
         #[cfg(feature = "tls")]
         if let Some(tls) = self.tls_config {
             server = server.tls_config(tls)?;
@@ -187,21 +829,46 @@ impl<B: GqlBackend> GqlServer<B> {
 
         let router = server
             .add_service(health_service)
-            .add_service(SessionServiceServer::new(session_service))
-            .add_service(GqlServiceServer::new(gql_service))
-            .add_service(CatalogServiceServer::new(catalog_service))
-            .add_service(AdminServiceServer::new(admin_service))
-            .add_service(SearchServiceServer::new(search_service));
-
-        tracing::info!(%addr, "GWP server listening");
-
-        let result = if let Some(signal) = self.shutdown {
-            router.serve_with_shutdown(addr, signal).await
-        } else {
-            router.serve(addr).await
-        };
+            .add_service(configured!(
+                SessionServiceServer::new(session_service),
+                self
+            ))
+            .add_service(configured!(GqlServiceServer::new(gql_service), self))
+            .add_service(configured!(
+                CatalogServiceServer::new(catalog_service),
+                self
+            ))
+            .add_optional_service(
+                self.admin
+                    .then(|| configured!(AdminServiceServer::new(admin_service), self)),
+            )
+            .add_optional_service(
+                self.search
+                    .then(|| configured!(SearchServiceServer::new(search_service), self)),
+            )
+            .add_service(configured!(
+                NotificationServiceServer::new(notification_service),
+                self
+            ));
+
+        let drain_on_shutdown = self.drain_grace.is_some();
+        let shutdown = self
+            .shutdown
+            .map(|signal| stop_after(signal, self.drain, drain_on_shutdown, stopping));
 
-        // Stop the reaper on shutdown
+        let result = listen
+            .serve(router, async {
+                match shutdown {
+                    Some(signal) => signal.await,
+                    None => std::future::pending().await,
+                }
+            })
+            .await;
+
+        // Stop the reaper, the health poll, and a drain that never started,
+        // on shutdown
+        drain_task.abort();
+        health_task.abort();
         if let Some((handle, token)) = reaper_handle {
             token.cancel();
             let _ = handle.await;
@@ -235,3 +902,146 @@ impl<B: GqlBackend> GqlServer<B> {
             .await
     }
 }
+
+/// Wait for `signal`, drain sessions if `drain_on_shutdown` is set or a
+/// drain is under way, then notify in-flight result streams through
+/// `stopping` before tonic drains them.
+async fn stop_after(
+    signal: Pin<Box<dyn Future<Output = ()> + Send>>,
+    drain: DrainHandle,
+    drain_on_shutdown: bool,
+    stopping: tokio_util::sync::CancellationToken,
+) {
+    signal.await;
+    if drain_on_shutdown {
+        drain.drain();
+    }
+    if drain.is_draining() {
+        drain.drained().await;
+    }
+    tracing::info!("shutdown started, notifying in-flight streams");
+    stopping.cancel();
+}
+
+/// A health service reporting every GWP service the server runs, and the
+/// task that keeps it in line with the backend's health.
+async fn health_service<B: GqlBackend>(
+    backend: Arc<B>,
+    admin: bool,
+    search: bool,
+    interval: Duration,
+) -> (
+    tonic_health::pb::health_server::HealthServer<impl tonic_health::pb::health_server::Health>,
+    tokio::task::JoinHandle<()>,
+) {
+    let (reporter, health_service) = tonic_health::server::health_reporter();
+    let services = service_names(admin, search);
+    report_health(&reporter, &services, HealthStatus::Serving).await;
+    let task = spawn_health_poll(backend, reporter, services, interval);
+    (health_service, task)
+}
+
+/// Names of the GWP services the server runs, as reported by the health
+/// service.
+fn service_names(admin: bool, search: bool) -> Vec<&'static str> {
+    let mut names = vec![
+        proto::session_service_server::SERVICE_NAME,
+        proto::gql_service_server::SERVICE_NAME,
+        proto::catalog_service_server::SERVICE_NAME,
+        proto::notification_service_server::SERVICE_NAME,
+    ];
+    if admin {
+        names.push(proto::admin_service_server::SERVICE_NAME);
+    }
+    if search {
+        names.push(proto::search_service_server::SERVICE_NAME);
+    }
+    names
+}
+
+/// Report `status` for the server as a whole and for each of `services`.
+async fn report_health(
+    reporter: &tonic_health::server::HealthReporter,
+    services: &[&'static str],
+    status: HealthStatus,
+) {
+    let status = match status {
+        HealthStatus::Serving => tonic_health::ServingStatus::Serving,
+        HealthStatus::NotServing => tonic_health::ServingStatus::NotServing,
+    };
+    for service in std::iter::once(&"").chain(services) {
+        reporter.set_service_status(service, status).await;
+    }
+}
+
+/// Spawn the task that polls the backend's health every `interval` and
+/// reports changes to the health service.
+fn spawn_health_poll<B: GqlBackend>(
+    backend: Arc<B>,
+    reporter: tonic_health::server::HealthReporter,
+    services: Vec<&'static str>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut current = HealthStatus::Serving;
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let status = backend.health().await;
+            if status != current {
+                tracing::info!(?status, "backend health changed");
+                report_health(&reporter, &services, status).await;
+                current = status;
+            }
+        }
+    })
+}
+
+/// Spawn the task that drains sessions once `drain` starts.
+fn spawn_drain<B: GqlBackend>(
+    drain: DrainHandle,
+    grace: Duration,
+    backend: Arc<B>,
+    sessions: SessionManager,
+    transactions: TransactionManager,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        drain::run(drain, grace, backend.as_ref(), &sessions, &transactions).await;
+    })
+}
+
+/// Spawn the idle session reaper, returning its task and a token to stop it.
+fn spawn_reaper<B: GqlBackend>(
+    timeout: Duration,
+    backend: Arc<B>,
+    sessions: SessionManager,
+    transactions: TransactionManager,
+) -> (
+    tokio::task::JoinHandle<()>,
+    tokio_util::sync::CancellationToken,
+) {
+    let token = tokio_util::sync::CancellationToken::new();
+    let reaper_token = token.clone();
+    let handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(timeout / 2);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let expired = sessions.reap_idle(timeout).await;
+                    for session_id in &expired {
+                        transactions.remove_for_session(session_id).await;
+                        let _ = backend
+                            .close_session(&SessionHandle(session_id.clone()))
+                            .await;
+                    }
+                }
+                () = reaper_token.cancelled() => {
+                    tracing::info!("session reaper stopped");
+                    break;
+                }
+            }
+        }
+    });
+    (handle, token)
+}
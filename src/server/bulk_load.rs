@@ -0,0 +1,114 @@
+//! Bulk loading of nodes and edges streamed by a client.
+
+use tokio::sync::watch;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::types::{Edge, Node};
+
+/// Nodes and edges sent together in one bulk load message.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkChunk {
+    /// Nodes to create.
+    pub nodes: Vec<Node>,
+    /// Edges to create.
+    pub edges: Vec<Edge>,
+}
+
+impl From<proto::BulkLoadRequest> for BulkChunk {
+    fn from(req: proto::BulkLoadRequest) -> Self {
+        Self {
+            nodes: req.nodes.into_iter().map(Node::from).collect(),
+            edges: req.edges.into_iter().map(Edge::from).collect(),
+        }
+    }
+}
+
+/// How far a bulk load has got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BulkLoadStats {
+    /// Nodes loaded so far.
+    pub nodes_loaded: u64,
+    /// Edges loaded so far.
+    pub edges_loaded: u64,
+    /// Elements the backend rejected.
+    pub errors: u64,
+}
+
+impl From<proto::BulkLoadProgress> for BulkLoadStats {
+    fn from(p: proto::BulkLoadProgress) -> Self {
+        Self {
+            nodes_loaded: p.nodes_loaded,
+            edges_loaded: p.edges_loaded,
+            errors: p.errors,
+        }
+    }
+}
+
+impl From<BulkLoadStats> for proto::BulkLoadProgress {
+    fn from(stats: BulkLoadStats) -> Self {
+        Self {
+            nodes_loaded: stats.nodes_loaded,
+            edges_loaded: stats.edges_loaded,
+            errors: stats.errors,
+            status: None,
+        }
+    }
+}
+
+/// The chunks of a bulk load as they arrive from the client, passed to
+/// [`GqlBackend::bulk_load`](super::GqlBackend::bulk_load).
+///
+/// Backends read chunks with [`next`](Self::next) and tell the client
+/// how far they have got with [`report`](Self::report).
+pub struct BulkChunks {
+    first: Option<proto::BulkLoadRequest>,
+    requests: tonic::Streaming<proto::BulkLoadRequest>,
+    progress: watch::Sender<BulkLoadStats>,
+}
+
+impl BulkChunks {
+    pub(crate) fn new(
+        first: proto::BulkLoadRequest,
+        requests: tonic::Streaming<proto::BulkLoadRequest>,
+    ) -> (Self, watch::Receiver<BulkLoadStats>) {
+        let (progress, receiver) = watch::channel(BulkLoadStats::default());
+        let chunks = Self {
+            first: Some(first),
+            requests,
+            progress,
+        };
+        (chunks, receiver)
+    }
+
+    /// Wait for the next chunk, or `None` once the client has sent them
+    /// all.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client's stream fails.
+    pub async fn next(&mut self) -> Result<Option<BulkChunk>, GqlError> {
+        if let Some(first) = self.first.take() {
+            return Ok(Some(first.into()));
+        }
+        Ok(self.requests.message().await?.map(BulkChunk::from))
+    }
+
+    /// Report progress to the client. Reports made faster than the
+    /// client reads them are coalesced.
+    pub fn report(&self, stats: BulkLoadStats) {
+        self.progress.send_replace(stats);
+    }
+
+    /// The progress last reported.
+    #[must_use]
+    pub fn reported(&self) -> BulkLoadStats {
+        *self.progress.borrow()
+    }
+}
+
+impl std::fmt::Debug for BulkChunks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkChunks").finish_non_exhaustive()
+    }
+}
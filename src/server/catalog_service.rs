@@ -1,6 +1,7 @@
 //! `CatalogService` gRPC implementation.
 //!
-//! Manages the catalog hierarchy: schemas, graphs, and graph types.
+//! Manages the catalog hierarchy: databases, schemas, graphs, and graph
+//! types.
 //! Replaces the flat `DatabaseService` with a spec-aligned catalog model (sec 12).
 //! All errors are returned as gRPC status codes.
 
@@ -12,17 +13,40 @@ use crate::error::GqlError;
 use crate::proto;
 use crate::proto::catalog_service_server::CatalogService;
 
-use super::backend::{CreateGraphConfig, GqlBackend, GraphTypeSpec};
+use super::SessionManager;
+use super::auth::{Authorization, Authorizer, Operation, authorize};
+use super::backend::{
+    CloneGraphConfig, CreateDatabaseConfig, CreateGraphConfig, GqlBackend, GraphTypeSpec,
+    SessionDefaults,
+};
 
 /// Implementation of the `CatalogService` gRPC service.
 pub struct CatalogServiceImpl<B: GqlBackend> {
     backend: Arc<B>,
+    authorization: Option<Authorization>,
 }
 
 impl<B: GqlBackend> CatalogServiceImpl<B> {
     /// Create a new catalog service.
     pub fn new(backend: Arc<B>) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            authorization: None,
+        }
+    }
+
+    /// Ask `authorizer` before each call: listings are reads, creating,
+    /// dropping, or cloning is DDL, and changing the session defaults is
+    /// administration. The principal is that of the session named in the
+    /// request's [`SESSION_ID_METADATA`](proto::SESSION_ID_METADATA).
+    #[must_use]
+    pub fn with_authorizer(
+        mut self,
+        authorizer: Arc<dyn Authorizer>,
+        sessions: SessionManager,
+    ) -> Self {
+        self.authorization = Some(Authorization::new(authorizer, sessions));
+        self
     }
 }
 
@@ -46,11 +70,13 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
     // Schema operations
     // =========================================================================
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn list_schemas(
         &self,
-        _request: Request<proto::ListSchemasRequest>,
+        request: Request<proto::ListSchemasRequest>,
     ) -> Result<Response<proto::ListSchemasResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let schemas = self.backend.list_schemas().await.map_err(map_error)?;
 
         Ok(Response::new(proto::ListSchemasResponse {
@@ -70,6 +96,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::CreateSchemaRequest>,
     ) -> Result<Response<proto::CreateSchemaResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema_name", &req.name);
 
@@ -92,6 +120,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::DropSchemaRequest>,
     ) -> Result<Response<proto::DropSchemaResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema_name", &req.name);
 
@@ -108,6 +138,96 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         Ok(Response::new(proto::DropSchemaResponse { existed }))
     }
 
+    // =========================================================================
+    // Database operations
+    // =========================================================================
+
+    #[tracing::instrument(skip(self, request), fields(database))]
+    async fn create_database(
+        &self,
+        request: Request<proto::CreateDatabaseRequest>,
+    ) -> Result<Response<proto::CreateDatabaseResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("database", &req.name);
+
+        if req.name.is_empty() {
+            return Err(Status::invalid_argument("database name is required"));
+        }
+
+        let config = CreateDatabaseConfig {
+            name: req.name,
+            if_not_exists: req.if_not_exists,
+            defaults: req.defaults.map(SessionDefaults::from).unwrap_or_default(),
+        };
+        let info = self
+            .backend
+            .create_database(config)
+            .await
+            .map_err(map_error)?;
+
+        tracing::info!(database = %info.name, "database created");
+
+        Ok(Response::new(proto::CreateDatabaseResponse {
+            database: Some(info.into()),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(database))]
+    async fn alter_database(
+        &self,
+        request: Request<proto::AlterDatabaseRequest>,
+    ) -> Result<Response<proto::AlterDatabaseResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Admin).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("database", &req.name);
+
+        if req.name.is_empty() {
+            return Err(Status::invalid_argument("database name is required"));
+        }
+
+        let defaults = req.defaults.map(SessionDefaults::from).unwrap_or_default();
+        let info = self
+            .backend
+            .alter_database(&req.name, defaults)
+            .await
+            .map_err(map_error)?;
+
+        tracing::info!(
+            database = %info.name,
+            schema = ?info.defaults.schema,
+            graph = ?info.defaults.graph,
+            "database defaults updated"
+        );
+
+        Ok(Response::new(proto::AlterDatabaseResponse {
+            database: Some(info.into()),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(database))]
+    async fn get_database_info(
+        &self,
+        request: Request<proto::GetDatabaseInfoRequest>,
+    ) -> Result<Response<proto::GetDatabaseInfoResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("database", &req.name);
+
+        let info = self
+            .backend
+            .get_database_info(&req.name)
+            .await
+            .map_err(map_error)?;
+
+        Ok(Response::new(proto::GetDatabaseInfoResponse {
+            database: Some(info.into()),
+        }))
+    }
+
     // =========================================================================
     // Graph operations
     // =========================================================================
@@ -117,6 +237,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::ListGraphsRequest>,
     ) -> Result<Response<proto::ListGraphsResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
 
@@ -145,6 +267,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::CreateGraphRequest>,
     ) -> Result<Response<proto::CreateGraphResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
         tracing::Span::current().record("graph_name", &req.name);
@@ -199,6 +323,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::DropGraphRequest>,
     ) -> Result<Response<proto::DropGraphResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
         tracing::Span::current().record("graph_name", &req.name);
@@ -221,6 +347,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::GetGraphInfoRequest>,
     ) -> Result<Response<proto::GetGraphInfoResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
         tracing::Span::current().record("graph_name", &req.name);
@@ -248,6 +376,50 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         }))
     }
 
+    #[tracing::instrument(skip(self, request), fields(source, target))]
+    async fn clone_graph(
+        &self,
+        request: Request<proto::CloneGraphRequest>,
+    ) -> Result<Response<proto::CloneGraphResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
+        let req = request.into_inner();
+        tracing::Span::current().record("source", &req.source_name);
+        tracing::Span::current().record("target", &req.target_name);
+
+        if req.source_name.is_empty() {
+            return Err(Status::invalid_argument("source graph name is required"));
+        }
+        if req.target_name.is_empty() {
+            return Err(Status::invalid_argument("target graph name is required"));
+        }
+
+        let options = req.options.unwrap_or_default();
+        let config = CloneGraphConfig {
+            source_schema: req.source_schema,
+            source_name: req.source_name,
+            target_schema: req.target_schema,
+            target_name: req.target_name,
+            if_not_exists: options.if_not_exists,
+            include_indexes: options.include_indexes,
+            storage_mode: options.storage_mode,
+        };
+
+        let info = self.backend.clone_graph(config).await.map_err(map_error)?;
+
+        tracing::info!(schema = %info.schema, graph = %info.name, "graph cloned");
+
+        Ok(Response::new(proto::CloneGraphResponse {
+            graph: Some(proto::GraphSummary {
+                schema: info.schema,
+                name: info.name,
+                node_count: info.node_count,
+                edge_count: info.edge_count,
+                graph_type: info.graph_type,
+            }),
+        }))
+    }
+
     // =========================================================================
     // Graph type operations
     // =========================================================================
@@ -257,6 +429,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::ListGraphTypesRequest>,
     ) -> Result<Response<proto::ListGraphTypesResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
 
@@ -282,6 +456,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::CreateGraphTypeRequest>,
     ) -> Result<Response<proto::CreateGraphTypeResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
         tracing::Span::current().record("type_name", &req.name);
@@ -305,6 +481,8 @@ impl<B: GqlBackend> CatalogService for CatalogServiceImpl<B> {
         &self,
         request: Request<proto::DropGraphTypeRequest>,
     ) -> Result<Response<proto::DropGraphTypeResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Ddl).await?;
+
         let req = request.into_inner();
         tracing::Span::current().record("schema", &req.schema);
         tracing::Span::current().record("type_name", &req.name);
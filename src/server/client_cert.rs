@@ -0,0 +1,76 @@
+//! Client certificates presented during a mutual TLS handshake.
+//!
+//! A server whose TLS configuration names a client CA root verifies the
+//! certificate each client presents. The session service reads the
+//! verified leaf certificate of the connection a handshake arrives on,
+//! hands it to the [`AuthValidator`](super::AuthValidator), and records
+//! its subject in the session's client info under
+//! [`CLIENT_CERT_SUBJECT`].
+
+use tonic::Request;
+
+/// The key of the client info entry holding the subject of the client
+/// certificate a session was created over. Clients cannot set it
+/// themselves: a value they send is replaced or removed.
+pub const CLIENT_CERT_SUBJECT: &str = "tls.client_subject";
+
+/// The verified certificate a client presented over mutual TLS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientCertificate {
+    /// The subject's distinguished name, such as `CN=alice, O=Example`.
+    pub subject: String,
+    /// The subject's common name, if it has one.
+    pub common_name: Option<String>,
+    /// DNS names, email addresses, and URIs from the subject alternative
+    /// name extension.
+    pub alt_names: Vec<String>,
+}
+
+/// The client certificate of the connection `request` arrived on, if the
+/// client presented one the TLS layer verified.
+#[cfg(feature = "tls")]
+pub(crate) fn from_request<T>(request: &Request<T>) -> Option<ClientCertificate> {
+    use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+
+    let certs = request.peer_certs()?;
+    let leaf = certs.first()?;
+    let cert = match X509Certificate::from_der(leaf) {
+        Ok((_, cert)) => cert,
+        Err(err) => {
+            tracing::warn!(error = %err, "unreadable client certificate");
+            return None;
+        }
+    };
+    let common_name = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_owned);
+    let alt_names = match cert.subject_alternative_name() {
+        Ok(Some(san)) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(name)
+                | GeneralName::RFC822Name(name)
+                | GeneralName::URI(name) => Some((*name).to_owned()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some(ClientCertificate {
+        subject: cert.subject().to_string(),
+        common_name,
+        alt_names,
+    })
+}
+
+/// Without the `tls` feature there is no TLS layer to present a
+/// certificate.
+#[cfg(not(feature = "tls"))]
+pub(crate) fn from_request<T>(_request: &Request<T>) -> Option<ClientCertificate> {
+    None
+}
@@ -0,0 +1,60 @@
+//! Server-side default deadlines for RPCs.
+//!
+//! Clients that set a deadline send it in the `grpc-timeout` header, which
+//! tonic enforces. For requests without one, the server applies the
+//! configured default so work abandoned by a client that never times out
+//! does not run forever.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tonic::Status;
+use tonic::metadata::MetadataMap;
+
+/// Header carrying a client-set deadline.
+const GRPC_TIMEOUT: &str = "grpc-timeout";
+
+/// Default deadlines per RPC class, applied when the client sets none.
+///
+/// A `None` field leaves that class unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultDeadlines {
+    /// Session creation during the handshake.
+    pub handshake: Option<Duration>,
+    /// Applying a session property.
+    pub configure: Option<Duration>,
+    /// Time from an execute request until the backend returns its result
+    /// stream.
+    pub execute_first_frame: Option<Duration>,
+    /// Transaction commit.
+    pub commit: Option<Duration>,
+}
+
+impl DefaultDeadlines {
+    /// The deadline to apply to a request: `default`, unless the client
+    /// set its own.
+    pub(crate) fn resolve(metadata: &MetadataMap, default: Option<Duration>) -> Option<Duration> {
+        if metadata.contains_key(GRPC_TIMEOUT) {
+            None
+        } else {
+            default
+        }
+    }
+}
+
+/// Run `work`, failing with `DEADLINE_EXCEEDED` if it outlasts `deadline`.
+pub(crate) async fn within<T>(
+    deadline: Option<Duration>,
+    rpc: &str,
+    work: impl Future<Output = T>,
+) -> Result<T, Status> {
+    let Some(deadline) = deadline else {
+        return Ok(work.await);
+    };
+    tokio::time::timeout(deadline, work).await.map_err(|_| {
+        tracing::warn!(rpc, ?deadline, "default deadline exceeded");
+        Status::deadline_exceeded(format!(
+            "{rpc} exceeded the server deadline of {deadline:?}"
+        ))
+    })
+}
@@ -0,0 +1,83 @@
+//! Graceful draining of sessions ahead of a shutdown or rollout.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::backend::{GqlBackend, SessionHandle, TransactionHandle};
+use super::{SessionManager, TransactionManager};
+
+/// How often a drain checks whether open transactions have finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Starts draining a running server.
+///
+/// Obtained from [`GqlServer::drain_handle`](super::GqlServer::drain_handle)
+/// before the server starts. A draining server rejects new handshakes and
+/// resumptions with `UNAVAILABLE`, sends every session a
+/// [`DrainNotice`](crate::proto::DrainNotice) on its `ServerEvents`
+/// stream, and after the grace period rolls back the transactions still
+/// open and closes every session. It keeps serving requests otherwise,
+/// until it is shut down.
+#[derive(Debug, Clone, Default)]
+pub struct DrainHandle {
+    started: CancellationToken,
+    finished: CancellationToken,
+}
+
+impl DrainHandle {
+    /// Start draining. Does nothing if the server is already draining.
+    pub fn drain(&self) {
+        if !self.started.is_cancelled() {
+            tracing::info!("drain requested");
+        }
+        self.started.cancel();
+    }
+
+    /// Whether draining has started.
+    #[must_use]
+    pub fn is_draining(&self) -> bool {
+        self.started.is_cancelled()
+    }
+
+    /// Wait until the drain has closed every session.
+    pub async fn drained(&self) {
+        self.finished.cancelled().await;
+    }
+
+    /// Wait until draining starts.
+    pub(crate) async fn started(&self) {
+        self.started.cancelled().await;
+    }
+}
+
+/// Wait for draining to start, then give open transactions up to `grace`
+/// to finish before rolling back the rest and closing every session.
+pub(crate) async fn run<B: GqlBackend>(
+    handle: DrainHandle,
+    grace: Duration,
+    backend: &B,
+    sessions: &SessionManager,
+    transactions: &TransactionManager,
+) {
+    handle.started().await;
+    tracing::info!(?grace, "draining sessions");
+
+    let deadline = tokio::time::Instant::now() + grace;
+    while transactions.active_count().await > 0 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep_until(deadline.min(tokio::time::Instant::now() + POLL_INTERVAL)).await;
+    }
+
+    for session_id in sessions.session_ids().await {
+        let session = SessionHandle(session_id.clone());
+        for tx_id in transactions.remove_for_session(&session_id).await {
+            tracing::info!(session_id, transaction_id = %tx_id, "rolling back transaction on drain");
+            let _ = backend.rollback(&session, &TransactionHandle(tx_id)).await;
+        }
+        let _ = backend.close_session(&session).await;
+        sessions.remove(&session_id).await;
+    }
+
+    tracing::info!("drain finished");
+    handle.finished.cancel();
+}
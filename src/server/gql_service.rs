@@ -5,25 +5,102 @@
 //! transport-level failure.
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
-use tokio_stream::Stream;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 use tonic::{Request, Response, Status};
+use tracing::Instrument;
 
+use crate::checksum;
+use crate::columnar;
+use crate::dictionary;
+use crate::error::GqlError;
 use crate::proto;
 use crate::proto::gql_service_server::GqlService;
 use crate::status as gql_status;
+use crate::trace_context::TraceContext;
 use crate::types::Value;
 
-use super::backend::{GqlBackend, ResultFrame, ResultStream};
-use super::{SessionHandle, SessionManager, TransactionHandle, TransactionManager};
+use super::auth::{Authorization, Authorizer, Operation};
+use super::backend::{ExecutionContext, GqlBackend, ResultFrame, ResultStream};
+use super::bulk_load::{BulkChunks, BulkLoadStats};
+use super::deadlines::{self, DefaultDeadlines};
+use super::query_log::{QueryLog, QueryLogOptions, QueryLogger};
+use super::query_stats::{QueryRecorder, micros, normalize_statement};
+use super::rate_limit::{RateLimitPermit, RateLimiter, RateLimits};
+use super::result_cache::{CacheKey, ResultCache, is_read_only_statement};
+use super::session_manager::RunningStatement;
+use super::statement_limits::{ResultLimits, ResultQuota, StatementLimits};
+use super::{
+    PreparedHandle, QueryStats, SessionHandle, SessionManager, TransactionConfig,
+    TransactionHandle, TransactionManager,
+};
+
+type ExecuteResponseStream =
+    Pin<Box<dyn Stream<Item = Result<proto::ExecuteResponse, Status>> + Send>>;
+
+type BatchSender = tokio::sync::mpsc::Sender<Result<proto::ExecuteBatchResponse, Status>>;
+
+/// Messages a batch runs ahead of what its client has received.
+const BATCH_BUFFER: usize = 16;
+
+/// Message size limit row batches are kept under by default: tonic's
+/// default limit on decoded messages, which clients apply unless
+/// configured otherwise.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Room left in a message for the response framing and a checksum added
+/// after a batch is sized.
+const FRAME_OVERHEAD: usize = 64;
+
+type BulkLoadSender = tokio::sync::mpsc::Sender<Result<proto::BulkLoadProgress, Status>>;
 
 /// Implementation of the `GqlService` gRPC service.
 pub struct GqlServiceImpl<B: GqlBackend> {
     backend: Arc<B>,
     sessions: SessionManager,
     transactions: TransactionManager,
+    query_stats: Option<QueryStats>,
+    query_log: Option<QueryLog>,
+    deadlines: DefaultDeadlines,
+    statement_limits: StatementLimits,
+    result_limits: ResultLimits,
+    max_in_flight_frames: Option<usize>,
+    max_message_size: usize,
+    statement_timeout: Option<Duration>,
+    slow_query_threshold: Option<Duration>,
+    rate_limiter: Option<RateLimiter>,
+    shutdown: Option<CancellationToken>,
+    result_cache: Option<ResultCache>,
+    authorization: Option<Authorization>,
+}
+
+impl<B: GqlBackend> Clone for GqlServiceImpl<B> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: Arc::clone(&self.backend),
+            sessions: self.sessions.clone(),
+            transactions: self.transactions.clone(),
+            query_stats: self.query_stats.clone(),
+            query_log: self.query_log.clone(),
+            deadlines: self.deadlines,
+            statement_limits: self.statement_limits,
+            result_limits: self.result_limits,
+            max_in_flight_frames: self.max_in_flight_frames,
+            max_message_size: self.max_message_size,
+            statement_timeout: self.statement_timeout,
+            slow_query_threshold: self.slow_query_threshold,
+            rate_limiter: self.rate_limiter.clone(),
+            shutdown: self.shutdown.clone(),
+            result_cache: self.result_cache.clone(),
+            authorization: self.authorization.clone(),
+        }
+    }
 }
 
 impl<B: GqlBackend> GqlServiceImpl<B> {
@@ -37,7 +114,747 @@ impl<B: GqlBackend> GqlServiceImpl<B> {
             backend,
             sessions,
             transactions,
+            query_stats: None,
+            query_log: None,
+            deadlines: DefaultDeadlines::default(),
+            statement_limits: StatementLimits::default(),
+            result_limits: ResultLimits::default(),
+            max_in_flight_frames: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            statement_timeout: None,
+            slow_query_threshold: None,
+            rate_limiter: None,
+            shutdown: None,
+            result_cache: None,
+            authorization: None,
+        }
+    }
+
+    /// Record execution statistics for every statement into `stats`.
+    #[must_use]
+    pub fn with_query_stats(mut self, stats: QueryStats) -> Self {
+        self.query_stats = Some(stats);
+        self
+    }
+
+    /// Send an event for every statement to `logger`, recording what
+    /// `options` asks for.
+    #[must_use]
+    pub fn with_query_logger(
+        mut self,
+        logger: Arc<dyn QueryLogger>,
+        options: QueryLogOptions,
+    ) -> Self {
+        self.query_log = Some(QueryLog::new(logger, options));
+        self
+    }
+
+    /// Apply `deadlines` to executions and commits that carry no deadline
+    /// of their own.
+    #[must_use]
+    pub fn with_deadlines(mut self, deadlines: DefaultDeadlines) -> Self {
+        self.deadlines = deadlines;
+        self
+    }
+
+    /// Reject statements that exceed `limits` before they reach the
+    /// backend.
+    #[must_use]
+    pub fn with_statement_limits(mut self, limits: StatementLimits) -> Self {
+        self.statement_limits = limits;
+        self
+    }
+
+    /// End results that grow past `limits` with a [`RESULT_TOO_LARGE`]
+    /// summary. Requests may ask for lower limits, but not higher ones.
+    ///
+    /// [`RESULT_TOO_LARGE`]: crate::status::RESULT_TOO_LARGE
+    #[must_use]
+    pub fn with_result_limits(mut self, limits: ResultLimits) -> Self {
+        self.result_limits = limits;
+        self
+    }
+
+    /// Pull each result stream from the backend in a background task, up
+    /// to `max` frames ahead of what gRPC flow control has let the server
+    /// send. By default frames are pulled only as they can be sent.
+    #[must_use]
+    pub fn with_max_in_flight_frames(mut self, max: usize) -> Self {
+        self.max_in_flight_frames = Some(max);
+        self
+    }
+
+    /// Split row batches so each result frame stays within `max` bytes,
+    /// the largest message clients accept (default: 4 MiB). A single row
+    /// larger than that is still sent whole.
+    #[must_use]
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
+    /// Abort statements whose result has not ended within `timeout`,
+    /// ending them with a [`STATEMENT_TIMEOUT`] summary. Requests may ask
+    /// for a shorter timeout, but not a longer one.
+    ///
+    /// [`STATEMENT_TIMEOUT`]: crate::status::STATEMENT_TIMEOUT
+    #[must_use]
+    pub fn with_statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
+    /// Warn about statements whose result took longer than `threshold`,
+    /// with a [`WARNING_SLOW_QUERY`] in the summary and a `tracing` event
+    /// under the `gwp::slow_query` target.
+    ///
+    /// [`WARNING_SLOW_QUERY`]: crate::status::WARNING_SLOW_QUERY
+    #[must_use]
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_threshold = Some(threshold);
+        self
+    }
+
+    /// Hold statements to `limits`, per session or per principal.
+    #[must_use]
+    pub fn with_rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limiter = RateLimiter::new(limits);
+        self
+    }
+
+    /// End in-flight result streams with a [`SERVER_SHUTDOWN`] summary
+    /// once `token` is cancelled, so clients can tell a drain from a
+    /// network failure and retry elsewhere.
+    ///
+    /// [`SERVER_SHUTDOWN`]: crate::status::SERVER_SHUTDOWN
+    #[must_use]
+    pub fn with_shutdown_notice(mut self, token: CancellationToken) -> Self {
+        self.shutdown = Some(token);
+        self
+    }
+
+    /// Serve read-only statements run outside transactions from `cache`,
+    /// and invalidate a database's entries when a statement or commit
+    /// writes to it.
+    #[must_use]
+    pub fn with_result_cache(mut self, cache: ResultCache) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// Ask `authorizer` before running each statement, as a read, write,
+    /// or DDL operation by the session's principal.
+    #[must_use]
+    pub fn with_authorizer(mut self, authorizer: Arc<dyn Authorizer>) -> Self {
+        self.authorization = Some(Authorization::new(authorizer, self.sessions.clone()));
+        self
+    }
+
+    /// Run a statement on the backend, serving it from and storing it in
+    /// the result cache when possible.
+    async fn run_statement(
+        &self,
+        session: &SessionHandle,
+        (statement, prepared): (&str, Option<&PreparedHandle>),
+        parameters: &HashMap<String, Value>,
+        transaction: Option<&TransactionHandle>,
+        deadline: Option<Duration>,
+        context: &ExecutionContext,
+    ) -> Result<Result<Pin<Box<dyn ResultStream>>, GqlError>, Status> {
+        let cached = match &self.result_cache {
+            Some(cache) => {
+                let key = self
+                    .cache_key(cache, &session.0, statement, parameters, transaction)
+                    .await;
+                key.map(|key| (cache, key))
+            }
+            None => None,
+        };
+        if let Some((cache, key)) = &cached {
+            if let Some(stream) = cache.get(key) {
+                tracing::debug!("result served from cache");
+                return Ok(Ok(stream));
+            }
+        }
+
+        let execution = async {
+            match prepared {
+                Some(prepared) => {
+                    self.backend
+                        .execute_prepared(
+                            session,
+                            prepared,
+                            statement,
+                            parameters,
+                            transaction,
+                            context,
+                        )
+                        .await
+                }
+                None => {
+                    self.backend
+                        .execute(session, statement, parameters, transaction, context)
+                        .await
+                }
+            }
+        };
+        let result = deadlines::within(deadline, "execute", execution).await?;
+        Ok(match cached {
+            Some((cache, key)) => result.map(|stream| cache.fill(key, stream)),
+            None => result,
+        })
+    }
+
+    /// The cache key for a cacheable statement. Writes invalidate the
+    /// session's database instead.
+    async fn cache_key(
+        &self,
+        cache: &ResultCache,
+        session_id: &str,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+        transaction: Option<&TransactionHandle>,
+    ) -> Option<CacheKey> {
+        let state = self.sessions.state(session_id).await?;
+        let database = state.database();
+        if !is_read_only_statement(statement) {
+            cache.invalidate(&database);
+            return None;
+        }
+        if transaction.is_some() {
+            return None;
+        }
+        Some(CacheKey::new(database, statement, parameters))
+    }
+
+    /// The parameters a statement runs with: the session's parameters,
+    /// overridden by those sent with the statement.
+    async fn statement_parameters(
+        &self,
+        session_id: &str,
+        sent: HashMap<String, proto::Value>,
+    ) -> HashMap<String, Value> {
+        let mut parameters = self.sessions.parameters(session_id).await;
+        parameters.extend(sent.into_iter().map(|(k, v)| (k, Value::from(v))));
+        parameters
+    }
+
+    /// Start recording an execution into the statistics and the query log,
+    /// if either is enabled.
+    fn recorder(
+        &self,
+        session_id: &str,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+    ) -> Option<QueryRecorder> {
+        if self.query_stats.is_none() && self.query_log.is_none() {
+            return None;
+        }
+        let log = self
+            .query_log
+            .as_ref()
+            .map(|log| log.start(session_id, statement, parameters));
+        Some(QueryRecorder::new(
+            self.query_stats.as_ref(),
+            statement,
+            log,
+        ))
+    }
+
+    /// The transaction a statement runs in, validated to belong to its
+    /// session.
+    async fn statement_transaction(
+        &self,
+        session_id: &str,
+        transaction_id: Option<&str>,
+    ) -> Result<Option<TransactionHandle>, Status> {
+        let Some(tx_id) = transaction_id else {
+            return Ok(None);
+        };
+        self.transactions
+            .validate(tx_id, session_id)
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Some(TransactionHandle(tx_id.to_owned())))
+    }
+
+    /// Admit a statement of `session_id` under the rate limits, if any
+    /// are set, failing with `RESOURCE_EXHAUSTED` when over them.
+    async fn admit(&self, session_id: &str) -> Result<Option<RateLimitPermit>, Status> {
+        let Some(limiter) = &self.rate_limiter else {
+            return Ok(None);
+        };
+        let principal = if limiter.by_principal() {
+            self.sessions
+                .state(session_id)
+                .await
+                .and_then(|state| state.principal)
+        } else {
+            None
+        };
+        let key = match principal {
+            Some(principal) => format!("principal:{}", principal.name),
+            None => format!("session:{session_id}"),
+        };
+        match limiter.admit(key) {
+            Ok(permit) => Ok(Some(permit)),
+            Err(status) => {
+                tracing::warn!(message = %status.message(), "statement rate limited");
+                Err(status)
+            }
+        }
+    }
+
+    /// The timeout for a statement whose request asked for `timeout_ms`:
+    /// the shorter of that and the server's.
+    fn statement_timeout(&self, timeout_ms: Option<u32>) -> Option<Duration> {
+        let requested = timeout_ms
+            .filter(|&ms| ms > 0)
+            .map(|ms| Duration::from_millis(u64::from(ms)));
+        match (requested, self.statement_timeout) {
+            (Some(requested), Some(server)) => Some(requested.min(server)),
+            (requested, server) => requested.or(server),
+        }
+    }
+
+    /// Execute a statement, prepared as `prepared` if set, and stream its
+    /// result.
+    async fn execute_statement(
+        &self,
+        req: proto::ExecuteRequest,
+        prepared: Option<PreparedHandle>,
+        deadline: Option<Duration>,
+        context: ExecutionContext,
+    ) -> Result<Response<ExecuteResponseStream>, Status> {
+        let span = tracing::Span::current();
+        let started = tokio::time::Instant::now();
+        let timeout = self
+            .statement_timeout(req.timeout_ms)
+            .map(|timeout| (timeout, tokio::time::Instant::now() + timeout));
+        span.record("session_id", &req.session_id);
+        span.record(
+            "statement",
+            tracing::field::display(statement_field(&req.statement)),
+        );
+
+        self.validate_session(&req.session_id).await?;
+        let rate_limit = self.admit(&req.session_id).await?;
+
+        if let Some(status) = self
+            .reject(&req.session_id, &req.statement, req.parameters.len())
+            .await
+        {
+            return Ok(failure_response(status));
+        }
+
+        let session = SessionHandle(req.session_id.clone());
+        let transaction = self
+            .statement_transaction(&req.session_id, req.transaction_id.as_deref())
+            .await?;
+
+        let parameters = self
+            .statement_parameters(&req.session_id, req.parameters)
+            .await;
+
+        let mut recorder = self.recorder(&req.session_id, &req.statement, &parameters);
+        let running = self
+            .sessions
+            .start_statement(&req.session_id, &req.statement);
+
+        let execution = self.run_statement(
+            &session,
+            (&req.statement, prepared.as_ref()),
+            &parameters,
+            transaction.as_ref(),
+            deadline,
+            &context,
+        );
+        let execution = match timeout {
+            Some((timeout, at)) => tokio::time::timeout_at(at, execution)
+                .await
+                .unwrap_or_else(|_| Ok(Err(timed_out(timeout)))),
+            None => execution.await,
+        };
+        let result_stream = match execution {
+            Ok(result) => result,
+            Err(status) => {
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.fail(None);
+                }
+                return Err(status);
+            }
+        };
+
+        match result_stream {
+            Ok(stream) => {
+                let output = ResultStreamAdapter {
+                    inner: stream,
+                    recorder,
+                    _running: running,
+                    rate_limit,
+                    throttle: None,
+                    throttled: false,
+                    slow_query: self.slow_query_threshold.map(|threshold| {
+                        SlowQuery::new(threshold, &req.session_id, &req.statement, started)
+                    }),
+                    timings: PhaseTimings::new(started),
+                    shutdown: self
+                        .shutdown
+                        .clone()
+                        .map(|token| Box::pin(token.cancelled_owned())),
+                    finished: false,
+                    checksums: self.sessions.row_batch_checksums(&req.session_id).await,
+                    dictionary_encoding: self.sessions.dictionary_encoding(&req.session_id).await,
+                    columnar_batches: self.sessions.columnar_batches(&req.session_id).await,
+                    fetch_size: fetch_size(req.fetch_size),
+                    max_batch_bytes: self.max_message_size.saturating_sub(FRAME_OVERHEAD),
+                    pending: None,
+                    quota: self.result_limits.quota(req.max_rows, req.max_bytes),
+                    timeout: timeout
+                        .map(|(timeout, at)| (timeout, Box::pin(tokio::time::sleep_until(at)))),
+                };
+                Ok(match self.max_in_flight_frames {
+                    Some(max) => Response::new(Box::pin(prefetch(output, max))),
+                    None => Response::new(Box::pin(output)),
+                })
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "execute failed");
+                // GQL errors go in the response payload, not gRPC status
+                let status = match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::DATA_EXCEPTION, err.to_string()),
+                };
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.fail(Some(&status));
+                }
+
+                Ok(failure_response(status))
+            }
+        }
+    }
+
+    /// Run the statements of a batch in order, sending their frames to
+    /// `tx`, and finish with the batch status. An atomic batch outside a
+    /// transaction runs in one it begins and ends itself.
+    async fn run_batch(
+        &self,
+        req: proto::ExecuteBatchRequest,
+        deadline: Option<Duration>,
+        context: ExecutionContext,
+        tx: BatchSender,
+    ) {
+        let count = u32::try_from(req.statements.len()).unwrap_or(u32::MAX);
+        let mut transaction_id = req.transaction_id;
+        let mut implicit = None;
+        if req.atomic && transaction_id.is_none() {
+            match self.begin_batch_transaction(&req.session_id).await {
+                Ok(id) => {
+                    transaction_id = Some(id.clone());
+                    implicit = Some(id);
+                }
+                Err(status) => {
+                    let _ = tx.send(Ok(batch_status(0, status))).await;
+                    return;
+                }
+            }
+        }
+
+        let mut outcome = (count, gql_status::success());
+        for (index, statement) in (0..).zip(req.statements) {
+            let request = proto::ExecuteRequest {
+                session_id: req.session_id.clone(),
+                statement: statement.statement,
+                parameters: statement.parameters,
+                transaction_id: transaction_id.clone(),
+                fetch_size: None,
+                timeout_ms: None,
+                compression: None,
+                max_rows: None,
+                max_bytes: None,
+            };
+            match self
+                .forward_batch_statement(index, request, deadline, &context, &tx)
+                .await
+            {
+                Ok(None) => {}
+                Ok(Some(status)) => {
+                    outcome = (index, status);
+                    break;
+                }
+                Err(()) => {
+                    if let Some(id) = implicit {
+                        self.end_batch_transaction(&req.session_id, id, false).await;
+                    }
+                    return;
+                }
+            }
+        }
+
+        if let Some(id) = implicit {
+            let committed = gql_status::is_success(&outcome.1.code);
+            if let Some(status) = self
+                .end_batch_transaction(&req.session_id, id, committed)
+                .await
+            {
+                outcome = (count, status);
+            }
+        }
+        let _ = tx.send(Ok(batch_status(outcome.0, outcome.1))).await;
+    }
+
+    /// Execute one statement of a batch and send its frames tagged with
+    /// `index`. Returns the status it failed with, if it did, or `Err`
+    /// once the batch cannot go on.
+    async fn forward_batch_statement(
+        &self,
+        index: u32,
+        request: proto::ExecuteRequest,
+        deadline: Option<Duration>,
+        context: &ExecutionContext,
+        tx: &BatchSender,
+    ) -> Result<Option<proto::GqlStatus>, ()> {
+        let mut frames = match self
+            .execute_statement(request, None, deadline, context.clone())
+            .await
+        {
+            Ok(frames) => frames.into_inner(),
+            Err(status) => {
+                let _ = tx.send(Err(status)).await;
+                return Err(());
+            }
+        };
+        let mut failure = None;
+        while let Some(frame) = frames.next().await {
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(status) => {
+                    let _ = tx.send(Err(status)).await;
+                    return Err(());
+                }
+            };
+            if let Some(proto::execute_response::Frame::Summary(summary)) = &frame.frame {
+                failure = summary
+                    .status
+                    .clone()
+                    .filter(|status| gql_status::is_exception(&status.code));
+            }
+            let message = proto::ExecuteBatchResponse {
+                statement_index: index,
+                frame: Some(proto::execute_batch_response::Frame::Result(frame)),
+            };
+            if tx.send(Ok(message)).await.is_err() {
+                return Err(());
+            }
+        }
+        Ok(failure)
+    }
+
+    /// Begin the implicit transaction of an atomic batch.
+    async fn begin_batch_transaction(&self, session_id: &str) -> Result<String, proto::GqlStatus> {
+        let begin = proto::BeginRequest {
+            session_id: session_id.to_owned(),
+            mode: proto::TransactionMode::ReadWrite.into(),
+            isolation_level: proto::IsolationLevel::Default.into(),
+            timeout_ms: None,
+        };
+        match self.begin_transaction(Request::new(begin)).await {
+            Ok(response) => {
+                let response = response.into_inner();
+                match response.status {
+                    Some(status) if gql_status::is_exception(&status.code) => Err(status),
+                    _ => Ok(response.transaction_id),
+                }
+            }
+            Err(status) => Err(gql_status::error(
+                gql_status::ACTIVE_TRANSACTION,
+                status.message(),
+            )),
+        }
+    }
+
+    /// Commit or roll back the implicit transaction of an atomic batch,
+    /// returning the status a failed commit ended with.
+    async fn end_batch_transaction(
+        &self,
+        session_id: &str,
+        transaction_id: String,
+        commit: bool,
+    ) -> Option<proto::GqlStatus> {
+        if commit {
+            let request = proto::CommitRequest {
+                session_id: session_id.to_owned(),
+                transaction_id,
+            };
+            let status = match self.commit(Request::new(request)).await {
+                Ok(response) => response.into_inner().status,
+                Err(status) => Some(gql_status::error(
+                    gql_status::INVALID_TRANSACTION_STATE,
+                    status.message(),
+                )),
+            };
+            status.filter(|status| gql_status::is_exception(&status.code))
+        } else {
+            let request = proto::RollbackRequest {
+                session_id: session_id.to_owned(),
+                transaction_id,
+            };
+            if let Err(status) = self.rollback(Request::new(request)).await {
+                tracing::warn!(error = %status.message(), "batch rollback failed");
+            }
+            None
+        }
+    }
+
+    /// Run a bulk load, sending progress to `tx` as the backend reports
+    /// it and finishing with the totals and status.
+    async fn run_bulk_load(
+        &self,
+        session: SessionHandle,
+        mut chunks: BulkChunks,
+        mut progress: tokio::sync::watch::Receiver<BulkLoadStats>,
+        tx: BulkLoadSender,
+    ) {
+        let load = self.backend.bulk_load(&session, &mut chunks);
+        tokio::pin!(load);
+        let mut reporting = true;
+        let result = loop {
+            tokio::select! {
+                result = &mut load => break result,
+                changed = progress.changed(), if reporting => {
+                    if changed.is_err() {
+                        reporting = false;
+                        continue;
+                    }
+                    let stats = *progress.borrow_and_update();
+                    if tx.send(Ok(stats.into())).await.is_err() {
+                        tracing::debug!("bulk load abandoned by client");
+                        return;
+                    }
+                }
+            }
+        };
+
+        if let Some(cache) = &self.result_cache {
+            if let Some(state) = self.sessions.state(&session.0).await {
+                cache.invalidate(&state.database());
+            }
+        }
+        let (totals, status) = match result {
+            Ok(totals) => {
+                tracing::info!(
+                    nodes = totals.nodes_loaded,
+                    edges = totals.edges_loaded,
+                    errors = totals.errors,
+                    "bulk load finished"
+                );
+                (totals, gql_status::success())
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "bulk load failed");
+                let status = match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::DATA_EXCEPTION, err.to_string()),
+                };
+                (*progress.borrow(), status)
+            }
+        };
+        let _ = tx
+            .send(Ok(proto::BulkLoadProgress {
+                status: Some(status),
+                ..totals.into()
+            }))
+            .await;
+    }
+
+    /// The status to end a statement with before it reaches the backend:
+    /// it is oversized, or its session's principal may not run it.
+    async fn reject(
+        &self,
+        session_id: &str,
+        statement: &str,
+        parameter_count: usize,
+    ) -> Option<proto::GqlStatus> {
+        if let Some(status) = self.statement_limits.check(statement, parameter_count) {
+            tracing::warn!(message = %status.message, "statement rejected");
+            return Some(status);
+        }
+        let authorization = self.authorization.as_ref()?;
+        let operation = Operation::of_statement(statement);
+        let err = authorization.check(session_id, operation).await.err()?;
+        Some(match err.gql_status() {
+            Some(s) => s.clone(),
+            None => gql_status::error(gql_status::PERMISSION_DENIED, err.to_string()),
+        })
+    }
+
+    /// Roll back `transaction` once `timeout` passes unless it has ended
+    /// by then.
+    fn expire_transaction_after(
+        &self,
+        timeout: Duration,
+        session: SessionHandle,
+        transaction: TransactionHandle,
+    ) {
+        let service = self.clone();
+        let expiry = async move {
+            tokio::time::sleep(timeout).await;
+            if service.transactions.expire(&transaction.0).await.is_none() {
+                return;
+            }
+            tracing::warn!(?timeout, "transaction timed out");
+            if let Err(err) = service.backend.rollback(&session, &transaction).await {
+                tracing::warn!(error = %err, "rollback of timed-out transaction failed");
+            }
+            service
+                .sessions
+                .set_active_transaction(&session.0, None)
+                .await
+                .ok();
+        };
+        let shutdown = self.shutdown.clone().unwrap_or_default();
+        tokio::spawn(
+            async move {
+                tokio::select! {
+                    () = expiry => {}
+                    () = shutdown.cancelled() => {}
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Check the session and transaction a savepoint request names,
+    /// returning the handles to pass the backend, or the status to
+    /// answer with if the transaction is not open in the session.
+    async fn savepoint_target(
+        &self,
+        session_id: &str,
+        transaction_id: &str,
+        name: &str,
+    ) -> Result<Result<(SessionHandle, TransactionHandle), proto::GqlStatus>, Status> {
+        let span = tracing::Span::current();
+        span.record("session_id", session_id);
+        span.record("transaction_id", transaction_id);
+        span.record("savepoint", name);
+        self.validate_session(session_id).await?;
+
+        if let Err(e) = self.transactions.validate(transaction_id, session_id).await {
+            return Ok(Err(gql_status::error(
+                gql_status::INVALID_TRANSACTION_STATE,
+                e.to_string(),
+            )));
+        }
+        if name.is_empty() {
+            return Ok(Err(gql_status::error(
+                gql_status::INVALID_REFERENCE,
+                "savepoint name is empty",
+            )));
         }
+        Ok(Ok((
+            SessionHandle(session_id.to_owned()),
+            TransactionHandle(transaction_id.to_owned()),
+        )))
     }
 
     /// Validate a session exists and update its activity timestamp.
@@ -53,75 +870,292 @@ impl<B: GqlBackend> GqlServiceImpl<B> {
 
 #[tonic::async_trait]
 impl<B: GqlBackend> GqlService for GqlServiceImpl<B> {
-    type ExecuteStream = Pin<Box<dyn Stream<Item = Result<proto::ExecuteResponse, Status>> + Send>>;
+    type ExecuteStream = ExecuteResponseStream;
 
-    #[tracing::instrument(skip(self, request), fields(session_id, statement))]
+    #[tracing::instrument(
+        skip(self, request),
+        fields(session_id, query_id, trace_id, span_id, statement)
+    )]
     async fn execute(
         &self,
         request: Request<proto::ExecuteRequest>,
     ) -> Result<Response<Self::ExecuteStream>, Status> {
+        let deadline =
+            DefaultDeadlines::resolve(request.metadata(), self.deadlines.execute_first_frame);
+        let context = execution_context(request.metadata());
+        self.execute_statement(request.into_inner(), None, deadline, context)
+            .await
+    }
+
+    type ExecuteBatchStream = ReceiverStream<Result<proto::ExecuteBatchResponse, Status>>;
+
+    #[tracing::instrument(
+        skip(self, request),
+        fields(session_id, query_id, trace_id, span_id, statement, statements)
+    )]
+    async fn execute_batch(
+        &self,
+        request: Request<proto::ExecuteBatchRequest>,
+    ) -> Result<Response<Self::ExecuteBatchStream>, Status> {
+        let deadline =
+            DefaultDeadlines::resolve(request.metadata(), self.deadlines.execute_first_frame);
+        let context = execution_context(request.metadata());
         let req = request.into_inner();
         let span = tracing::Span::current();
         span.record("session_id", &req.session_id);
-        span.record(
-            "statement",
-            tracing::field::display(if req.statement.len() > 100 {
-                &req.statement[..100]
-            } else {
-                &req.statement
-            }),
+        span.record("statements", req.statements.len());
+        self.validate_session(&req.session_id).await?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(BATCH_BUFFER);
+        let service = self.clone();
+        tokio::spawn(
+            async move { service.run_batch(req, deadline, context, tx).await }.instrument(span),
         );
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
 
-        self.validate_session(&req.session_id).await?;
+    type BulkLoadStream = ReceiverStream<Result<proto::BulkLoadProgress, Status>>;
 
-        let session = SessionHandle(req.session_id.clone());
-        let transaction = if let Some(ref tx_id) = req.transaction_id {
-            // Validate the transaction belongs to this session
-            self.transactions
-                .validate(tx_id, &req.session_id)
+    #[tracing::instrument(skip(self, request), fields(session_id))]
+    async fn bulk_load(
+        &self,
+        request: Request<tonic::Streaming<proto::BulkLoadRequest>>,
+    ) -> Result<Response<Self::BulkLoadStream>, Status> {
+        let mut requests = request.into_inner();
+        let Some(first) = requests.message().await? else {
+            return Err(Status::invalid_argument("bulk load sent no messages"));
+        };
+        let span = tracing::Span::current();
+        span.record("session_id", &first.session_id);
+        self.validate_session(&first.session_id).await?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(BATCH_BUFFER);
+        if let Some(authorization) = &self.authorization {
+            if let Err(err) = authorization
+                .check(&first.session_id, Operation::Write)
                 .await
-                .map_err(|e| e.to_grpc_status())?;
-            Some(TransactionHandle(tx_id.clone()))
-        } else {
-            None
+            {
+                let status = match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::PERMISSION_DENIED, err.to_string()),
+                };
+                let _ = tx
+                    .send(Ok(proto::BulkLoadProgress {
+                        status: Some(status),
+                        ..BulkLoadStats::default().into()
+                    }))
+                    .await;
+                return Ok(Response::new(ReceiverStream::new(rx)));
+            }
+        }
+
+        let session = SessionHandle(first.session_id.clone());
+        let (chunks, progress) = BulkChunks::new(first, requests);
+        let service = self.clone();
+        tokio::spawn(
+            async move { service.run_bulk_load(session, chunks, progress, tx).await }
+                .instrument(span),
+        );
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    type ExecutePreparedStream = ExecuteResponseStream;
+
+    #[tracing::instrument(
+        skip(self, request),
+        fields(session_id, query_id, trace_id, span_id, statement, statement_id)
+    )]
+    async fn execute_prepared(
+        &self,
+        request: Request<proto::ExecutePreparedRequest>,
+    ) -> Result<Response<Self::ExecutePreparedStream>, Status> {
+        let deadline =
+            DefaultDeadlines::resolve(request.metadata(), self.deadlines.execute_first_frame);
+        let context = execution_context(request.metadata());
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record("statement_id", &req.statement_id);
+        self.validate_session(&req.session_id).await?;
+
+        let Some(statement) = self
+            .sessions
+            .prepared_statement(&req.session_id, &req.statement_id)
+            .await
+        else {
+            return Ok(failure_response(gql_status::error(
+                gql_status::PREPARED_STATEMENT_NOT_FOUND,
+                format!("no prepared statement {}", req.statement_id),
+            )));
+        };
+        let request = proto::ExecuteRequest {
+            session_id: req.session_id,
+            statement,
+            parameters: req.parameters,
+            transaction_id: req.transaction_id,
+            fetch_size: req.fetch_size,
+            timeout_ms: req.timeout_ms,
+            compression: req.compression,
+            max_rows: req.max_rows,
+            max_bytes: req.max_bytes,
         };
+        let prepared = PreparedHandle(req.statement_id);
+        self.execute_statement(request, Some(prepared), deadline, context)
+            .await
+    }
 
-        let parameters: HashMap<String, Value> = req
-            .parameters
-            .into_iter()
-            .map(|(k, v)| (k, Value::from(v)))
-            .collect();
+    #[tracing::instrument(skip(self, request), fields(session_id, statement))]
+    async fn prepare(
+        &self,
+        request: Request<proto::PrepareRequest>,
+    ) -> Result<Response<proto::PrepareResponse>, Status> {
+        let deadline =
+            DefaultDeadlines::resolve(request.metadata(), self.deadlines.execute_first_frame);
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record(
+            "statement",
+            tracing::field::display(statement_field(&req.statement)),
+        );
+        self.validate_session(&req.session_id).await?;
 
-        let result_stream = self
-            .backend
-            .execute(&session, &req.statement, &parameters, transaction.as_ref())
-            .await;
+        let failure = |status| {
+            Ok(Response::new(proto::PrepareResponse {
+                statement_id: String::new(),
+                status: Some(status),
+            }))
+        };
+        if let Some(status) = self.statement_limits.check(&req.statement, 0) {
+            tracing::warn!(message = %status.message, "statement rejected");
+            return failure(status);
+        }
+        let statement_id = match self
+            .sessions
+            .add_prepared_statement(&req.session_id, &req.statement)
+            .await
+        {
+            Ok(id) => id,
+            Err(err) => return Err(err.to_grpc_status()),
+        };
 
-        match result_stream {
-            Ok(stream) => {
-                let output = ResultStreamAdapter { inner: stream };
-                Ok(Response::new(Box::pin(output)))
+        let session = SessionHandle(req.session_id.clone());
+        let prepared = PreparedHandle(statement_id.clone());
+        let prepare = self.backend.prepare(&session, &prepared, &req.statement);
+        match deadlines::within(deadline, "prepare", prepare).await {
+            Ok(Ok(())) => {
+                tracing::debug!(statement_id, "statement prepared");
+                Ok(Response::new(proto::PrepareResponse {
+                    statement_id,
+                    status: Some(gql_status::success()),
+                }))
             }
+            Ok(Err(err)) => {
+                self.sessions
+                    .remove_prepared_statement(&req.session_id, &statement_id)
+                    .await;
+                tracing::warn!(error = %err, "prepare failed");
+                failure(match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::SYNTAX_OR_ACCESS_ERROR, err.to_string()),
+                })
+            }
+            Err(status) => {
+                self.sessions
+                    .remove_prepared_statement(&req.session_id, &statement_id)
+                    .await;
+                Err(status)
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, statement_id))]
+    async fn close_prepared(
+        &self,
+        request: Request<proto::ClosePreparedRequest>,
+    ) -> Result<Response<proto::ClosePreparedResponse>, Status> {
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record("statement_id", &req.statement_id);
+        self.validate_session(&req.session_id).await?;
+
+        if !self
+            .sessions
+            .remove_prepared_statement(&req.session_id, &req.statement_id)
+            .await
+        {
+            return Ok(Response::new(proto::ClosePreparedResponse {
+                status: Some(gql_status::error(
+                    gql_status::PREPARED_STATEMENT_NOT_FOUND,
+                    format!("no prepared statement {}", req.statement_id),
+                )),
+            }));
+        }
+        let session = SessionHandle(req.session_id);
+        let prepared = PreparedHandle(req.statement_id);
+        let status = match self.backend.close_prepared(&session, &prepared).await {
+            Ok(()) => gql_status::success(),
             Err(err) => {
-                tracing::warn!(error = %err, "execute failed");
-                // GQL errors go in the response payload, not gRPC status
-                let status = match err.gql_status() {
+                // The statement is gone from the session either way.
+                tracing::warn!(error = %err, "close prepared failed");
+                match err.gql_status() {
                     Some(s) => s.clone(),
                     None => gql_status::error(gql_status::DATA_EXCEPTION, err.to_string()),
-                };
+                }
+            }
+        };
+        Ok(Response::new(proto::ClosePreparedResponse {
+            status: Some(status),
+        }))
+    }
 
-                let summary_stream = futures_single_response(proto::ExecuteResponse {
-                    frame: Some(proto::execute_response::Frame::Summary(
-                        proto::ResultSummary {
-                            status: Some(status),
-                            warnings: Vec::new(),
-                            rows_affected: 0,
-                            counters: HashMap::new(),
-                        },
-                    )),
-                });
+    #[tracing::instrument(skip(self, request), fields(session_id, statement))]
+    async fn explain(
+        &self,
+        request: Request<proto::ExplainRequest>,
+    ) -> Result<Response<proto::ExplainResponse>, Status> {
+        let deadline =
+            DefaultDeadlines::resolve(request.metadata(), self.deadlines.execute_first_frame);
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record(
+            "statement",
+            tracing::field::display(statement_field(&req.statement)),
+        );
+        self.validate_session(&req.session_id).await?;
+
+        let failure = |status| {
+            Ok(Response::new(proto::ExplainResponse {
+                plan: None,
+                status: Some(status),
+            }))
+        };
+        // Explaining a statement reveals as much as running it would.
+        if let Some(status) = self
+            .reject(&req.session_id, &req.statement, req.parameters.len())
+            .await
+        {
+            return failure(status);
+        }
 
-                Ok(Response::new(Box::pin(summary_stream)))
+        let parameters = self
+            .statement_parameters(&req.session_id, req.parameters)
+            .await;
+        let session = SessionHandle(req.session_id);
+        let explain = self.backend.explain(&session, &req.statement, &parameters);
+        match deadlines::within(deadline, "explain", explain).await? {
+            Ok(plan) => Ok(Response::new(proto::ExplainResponse {
+                plan: Some(plan.into()),
+                status: Some(gql_status::success()),
+            })),
+            Err(err) => {
+                tracing::warn!(error = %err, "explain failed");
+                failure(match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::SYNTAX_OR_ACCESS_ERROR, err.to_string()),
+                })
             }
         }
     }
@@ -136,16 +1170,26 @@ impl<B: GqlBackend> GqlService for GqlServiceImpl<B> {
         self.validate_session(&req.session_id).await?;
 
         let session = SessionHandle(req.session_id.clone());
-        let mode =
-            proto::TransactionMode::try_from(req.mode).unwrap_or(proto::TransactionMode::ReadWrite);
+        let Ok(isolation_level) = proto::IsolationLevel::try_from(req.isolation_level) else {
+            return Err(Status::invalid_argument("invalid isolation level"));
+        };
+        let config = TransactionConfig {
+            mode: proto::TransactionMode::try_from(req.mode)
+                .unwrap_or(proto::TransactionMode::ReadWrite),
+            isolation_level,
+            timeout: req
+                .timeout_ms
+                .filter(|&ms| ms > 0)
+                .map(|ms| Duration::from_millis(u64::from(ms))),
+        };
 
-        match self.backend.begin_transaction(&session, mode).await {
+        match self.backend.begin_transaction_with(&session, config).await {
             Ok(handle) => {
                 let tx_id = handle.0.clone();
 
                 if let Err(e) = self
                     .transactions
-                    .register(&tx_id, &req.session_id, mode)
+                    .register_with(&tx_id, &req.session_id, config)
                     .await
                 {
                     // Roll back the backend transaction if we can't register it
@@ -166,6 +1210,9 @@ impl<B: GqlBackend> GqlService for GqlServiceImpl<B> {
                     .ok();
 
                 tracing::info!(session_id = %req.session_id, transaction_id = %tx_id, "transaction started");
+                if let Some(timeout) = config.timeout {
+                    self.expire_transaction_after(timeout, session, handle);
+                }
 
                 Ok(Response::new(proto::BeginResponse {
                     transaction_id: tx_id,
@@ -190,6 +1237,7 @@ impl<B: GqlBackend> GqlService for GqlServiceImpl<B> {
         &self,
         request: Request<proto::CommitRequest>,
     ) -> Result<Response<proto::CommitResponse>, Status> {
+        let deadline = DefaultDeadlines::resolve(request.metadata(), self.deadlines.commit);
         let req = request.into_inner();
         let span = tracing::Span::current();
         span.record("session_id", &req.session_id);
@@ -212,8 +1260,14 @@ impl<B: GqlBackend> GqlService for GqlServiceImpl<B> {
         let session = SessionHandle(req.session_id.clone());
         let transaction = TransactionHandle(req.transaction_id.clone());
 
-        match self.backend.commit(&session, &transaction).await {
+        let commit = self.backend.commit(&session, &transaction);
+        match deadlines::within(deadline, "commit", commit).await? {
             Ok(()) => {
+                if let Some(cache) = &self.result_cache {
+                    if let Some(state) = self.sessions.state(&req.session_id).await {
+                        cache.invalidate(&state.database());
+                    }
+                }
                 self.transactions.remove(&req.transaction_id).await.ok();
                 self.sessions
                     .set_active_transaction(&req.session_id, None)
@@ -292,15 +1346,470 @@ impl<B: GqlBackend> GqlService for GqlServiceImpl<B> {
             }
         }
     }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, transaction_id))]
+    async fn prepare_commit(
+        &self,
+        request: Request<proto::PrepareCommitRequest>,
+    ) -> Result<Response<proto::PrepareCommitResponse>, Status> {
+        let deadline = DefaultDeadlines::resolve(request.metadata(), self.deadlines.commit);
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record("transaction_id", &req.transaction_id);
+        self.validate_session(&req.session_id).await?;
+
+        if let Err(e) = self
+            .transactions
+            .validate(&req.transaction_id, &req.session_id)
+            .await
+        {
+            return Ok(Response::new(proto::PrepareCommitResponse {
+                status: Some(gql_status::error(
+                    gql_status::INVALID_TRANSACTION_STATE,
+                    e.to_string(),
+                )),
+            }));
+        }
+
+        let session = SessionHandle(req.session_id.clone());
+        let transaction = TransactionHandle(req.transaction_id.clone());
+
+        let prepare = self.backend.prepare_commit(&session, &transaction);
+        let status = match deadlines::within(deadline, "prepare", prepare).await? {
+            Ok(()) => match self
+                .transactions
+                .prepare(&req.transaction_id, &req.session_id)
+                .await
+            {
+                Ok(()) => {
+                    self.sessions
+                        .set_active_transaction(&req.session_id, None)
+                        .await
+                        .ok();
+                    tracing::info!("transaction prepared");
+                    gql_status::success()
+                }
+                Err(e) => gql_status::error(gql_status::INVALID_TRANSACTION_STATE, e.to_string()),
+            },
+            Err(err) => {
+                tracing::warn!(error = %err, "prepare failed");
+                match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(
+                        gql_status::INVALID_TRANSACTION_TERMINATION,
+                        err.to_string(),
+                    ),
+                }
+            }
+        };
+        Ok(Response::new(proto::PrepareCommitResponse {
+            status: Some(status),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, transaction_id))]
+    async fn commit_prepared(
+        &self,
+        request: Request<proto::CommitPreparedRequest>,
+    ) -> Result<Response<proto::CommitResponse>, Status> {
+        let deadline = DefaultDeadlines::resolve(request.metadata(), self.deadlines.commit);
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record("transaction_id", &req.transaction_id);
+        self.validate_session(&req.session_id).await?;
+
+        // Taken before calling the backend so that concurrent commits and
+        // rollbacks of the transaction cannot both reach it.
+        let state = match self.transactions.take_prepared(&req.transaction_id).await {
+            Ok(state) => state,
+            Err(e) => {
+                return Ok(Response::new(proto::CommitResponse {
+                    status: Some(gql_status::error(
+                        gql_status::INVALID_TRANSACTION_STATE,
+                        e.to_string(),
+                    )),
+                }));
+            }
+        };
+
+        let session = SessionHandle(req.session_id.clone());
+        let transaction = TransactionHandle(req.transaction_id.clone());
+
+        let commit = self.backend.commit_prepared(&session, &transaction);
+        let result = match deadlines::within(deadline, "commit", commit).await {
+            Ok(result) => result,
+            Err(status) => {
+                self.transactions.restore(&req.transaction_id, state).await;
+                return Err(status);
+            }
+        };
+        let status = match result {
+            Ok(()) => {
+                if let Some(cache) = &self.result_cache {
+                    cache.invalidate_all();
+                }
+                tracing::info!("prepared transaction committed");
+                gql_status::success()
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "prepared commit failed");
+                self.transactions.restore(&req.transaction_id, state).await;
+                match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::COMPLETION_UNKNOWN, err.to_string()),
+                }
+            }
+        };
+        Ok(Response::new(proto::CommitResponse {
+            status: Some(status),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, transaction_id))]
+    async fn rollback_prepared(
+        &self,
+        request: Request<proto::RollbackPreparedRequest>,
+    ) -> Result<Response<proto::RollbackResponse>, Status> {
+        let req = request.into_inner();
+        let span = tracing::Span::current();
+        span.record("session_id", &req.session_id);
+        span.record("transaction_id", &req.transaction_id);
+        self.validate_session(&req.session_id).await?;
+
+        let state = match self.transactions.take_prepared(&req.transaction_id).await {
+            Ok(state) => state,
+            Err(e) => {
+                return Ok(Response::new(proto::RollbackResponse {
+                    status: Some(gql_status::error(
+                        gql_status::INVALID_TRANSACTION_STATE,
+                        e.to_string(),
+                    )),
+                }));
+            }
+        };
+
+        let session = SessionHandle(req.session_id.clone());
+        let transaction = TransactionHandle(req.transaction_id.clone());
+
+        let status = match self.backend.rollback_prepared(&session, &transaction).await {
+            Ok(()) => {
+                tracing::info!("prepared transaction rolled back");
+                gql_status::success()
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "prepared rollback failed");
+                self.transactions.restore(&req.transaction_id, state).await;
+                match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::TRANSACTION_ROLLBACK, err.to_string()),
+                }
+            }
+        };
+        Ok(Response::new(proto::RollbackResponse {
+            status: Some(status),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, transaction_id, savepoint))]
+    async fn savepoint(
+        &self,
+        request: Request<proto::SavepointRequest>,
+    ) -> Result<Response<proto::SavepointResponse>, Status> {
+        let req = request.into_inner();
+        let status = match self
+            .savepoint_target(&req.session_id, &req.transaction_id, &req.name)
+            .await?
+        {
+            Ok((session, transaction)) => savepoint_status(
+                "marked",
+                self.backend
+                    .savepoint(&session, &transaction, &req.name)
+                    .await,
+            ),
+            Err(status) => status,
+        };
+        Ok(Response::new(proto::SavepointResponse {
+            status: Some(status),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, transaction_id, savepoint))]
+    async fn release_savepoint(
+        &self,
+        request: Request<proto::ReleaseSavepointRequest>,
+    ) -> Result<Response<proto::SavepointResponse>, Status> {
+        let req = request.into_inner();
+        let status = match self
+            .savepoint_target(&req.session_id, &req.transaction_id, &req.name)
+            .await?
+        {
+            Ok((session, transaction)) => savepoint_status(
+                "released",
+                self.backend
+                    .release_savepoint(&session, &transaction, &req.name)
+                    .await,
+            ),
+            Err(status) => status,
+        };
+        Ok(Response::new(proto::SavepointResponse {
+            status: Some(status),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id, transaction_id, savepoint))]
+    async fn rollback_to_savepoint(
+        &self,
+        request: Request<proto::RollbackToSavepointRequest>,
+    ) -> Result<Response<proto::SavepointResponse>, Status> {
+        let req = request.into_inner();
+        let status = match self
+            .savepoint_target(&req.session_id, &req.transaction_id, &req.name)
+            .await?
+        {
+            Ok((session, transaction)) => savepoint_status(
+                "rolled back to",
+                self.backend
+                    .rollback_to_savepoint(&session, &transaction, &req.name)
+                    .await,
+            ),
+            Err(status) => status,
+        };
+        Ok(Response::new(proto::SavepointResponse {
+            status: Some(status),
+        }))
+    }
 }
 
 // ============================================================================
 // Stream adapters
 // ============================================================================
 
+/// Timing of one execution, checked against the slow-query threshold
+/// when its summary is sent.
+struct SlowQuery {
+    threshold: Duration,
+    session_id: String,
+    statement: String,
+    started: tokio::time::Instant,
+    /// Time the backend took to return the result stream.
+    execution: Duration,
+}
+
+impl SlowQuery {
+    /// Start checking an execution that began at `started` and whose
+    /// result stream the backend has just returned.
+    fn new(
+        threshold: Duration,
+        session_id: &str,
+        statement: &str,
+        started: tokio::time::Instant,
+    ) -> Self {
+        Self {
+            threshold,
+            session_id: session_id.to_owned(),
+            statement: normalize_statement(statement),
+            started,
+            execution: started.elapsed(),
+        }
+    }
+
+    /// The warning for the summary, if the execution outlasted the
+    /// threshold. Logs the execution and streaming times when it did.
+    fn check(&self) -> Option<proto::GqlStatus> {
+        let total = self.started.elapsed();
+        if total <= self.threshold {
+            return None;
+        }
+        let streaming = total.saturating_sub(self.execution);
+        tracing::warn!(
+            target: "gwp::slow_query",
+            session_id = %self.session_id,
+            statement = %self.statement,
+            total_ms = total.as_secs_f64() * 1000.0,
+            execution_ms = self.execution.as_secs_f64() * 1000.0,
+            streaming_ms = streaming.as_secs_f64() * 1000.0,
+            threshold_ms = self.threshold.as_secs_f64() * 1000.0,
+            "slow query"
+        );
+        Some(gql_status::warning(
+            gql_status::WARNING_SLOW_QUERY,
+            format!(
+                "statement took {total:?}, over the slow-query threshold of {:?} \
+                 ({:?} executing, {streaming:?} streaming)",
+                self.threshold, self.execution
+            ),
+        ))
+    }
+}
+
+/// Phases of one execution, for the summary of a backend that reports
+/// no timings of its own.
+struct PhaseTimings {
+    /// Time until the backend returned the result stream.
+    planning: Duration,
+    opened: tokio::time::Instant,
+    first_frame: Option<tokio::time::Instant>,
+}
+
+impl PhaseTimings {
+    /// Start timing an execution that began at `started` and whose result
+    /// stream the backend has just returned.
+    fn new(started: tokio::time::Instant) -> Self {
+        let opened = tokio::time::Instant::now();
+        Self {
+            planning: opened - started,
+            opened,
+            first_frame: None,
+        }
+    }
+
+    fn observe_frame(&mut self, frame: &ResultFrame) {
+        if !matches!(frame, ResultFrame::Warning(_)) {
+            self.first_frame
+                .get_or_insert_with(tokio::time::Instant::now);
+        }
+    }
+
+    /// The timings as of the summary being sent.
+    fn report(&self) -> proto::QueryTimings {
+        let now = tokio::time::Instant::now();
+        let first_frame = self.first_frame.unwrap_or(now);
+        proto::QueryTimings {
+            planning_micros: micros(self.planning),
+            execution_micros: micros(first_frame - self.opened),
+            streaming_micros: micros(now - first_frame),
+        }
+    }
+}
+
 /// Adapts a `ResultStream` into a tonic-compatible `Stream`.
+#[allow(clippy::struct_excessive_bools)]
 struct ResultStreamAdapter {
     inner: Pin<Box<dyn ResultStream>>,
+    /// Statistics for this execution, recorded when the stream is dropped.
+    recorder: Option<QueryRecorder>,
+    /// Lists the statement as running in its session until the stream is
+    /// dropped.
+    _running: RunningStatement,
+    /// Timing for the slow-query warning, if a threshold is set.
+    slow_query: Option<SlowQuery>,
+    /// Phase timings for the summary.
+    timings: PhaseTimings,
+    /// The rate limit allowance this stream holds.
+    rate_limit: Option<RateLimitPermit>,
+    /// Delay before the next batch, to stay within the row rate.
+    throttle: Option<Pin<Box<tokio::time::Sleep>>>,
+    /// Whether any batch was delayed by the row rate.
+    throttled: bool,
+    /// Resolves when the server starts shutting down.
+    shutdown: Option<Pin<Box<WaitForCancellationFutureOwned>>>,
+    /// Whether the summary has been sent.
+    finished: bool,
+    /// Whether to stamp row batches with a checksum.
+    checksums: bool,
+    /// Whether to dictionary-encode row batches.
+    dictionary_encoding: bool,
+    /// Whether to transpose row batches into columns.
+    columnar_batches: bool,
+    /// Maximum rows per batch the client asked for.
+    fetch_size: Option<usize>,
+    /// Maximum encoded size of a batch, to stay within the message size
+    /// limit.
+    max_batch_bytes: usize,
+    /// Rows of a split batch not yet sent.
+    pending: Option<proto::RowBatch>,
+    /// Rows and bytes this result may still send.
+    quota: ResultQuota,
+    /// The statement timeout, and when it expires.
+    timeout: Option<(Duration, Pin<Box<tokio::time::Sleep>>)>,
+}
+
+impl ResultStreamAdapter {
+    /// Prepare a backend batch for sending, keeping the rows beyond the
+    /// fetch size or the message size limit for the next poll, or end the
+    /// result if the batch would take it over its quota.
+    fn batch_response(&mut self, mut batch: proto::RowBatch) -> proto::ExecuteResponse {
+        if let Some(max) = self.fetch_size {
+            if batch.rows.len() > max {
+                let rest = batch.rows.split_off(max);
+                self.defer(&mut batch, rest);
+            }
+        }
+        if let Some(rest) = split_to_fit(&mut batch, self.max_batch_bytes) {
+            self.defer(&mut batch, rest);
+        }
+        let rows = u64::try_from(batch.rows.len()).unwrap_or(u64::MAX);
+        let bytes = u64::try_from(prost::Message::encoded_len(&batch)).unwrap_or(u64::MAX);
+        if let Some(status) = self.quota.charge(rows, bytes) {
+            tracing::warn!(message = %status.message, "result cut off");
+            self.finished = true;
+            self.pending = None;
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.fail(Some(&status));
+            }
+            return summary_response(Some(status));
+        }
+        if let Some(delay) = self
+            .rate_limit
+            .as_ref()
+            .and_then(|permit| permit.consume_rows(batch.rows.len()))
+        {
+            self.throttled = true;
+            self.throttle = Some(Box::pin(tokio::time::sleep(delay)));
+        }
+        if self.checksums && batch.checksum.is_none() {
+            batch.checksum = Some(checksum::row_batch_checksum(&batch.rows));
+        }
+        // The checksum covers the decoded rows.
+        if self.dictionary_encoding {
+            dictionary::encode(&mut batch);
+        }
+        if self.columnar_batches {
+            columnar::encode(&mut batch);
+        }
+        proto::ExecuteResponse {
+            frame: Some(proto::execute_response::Frame::RowBatch(batch)),
+        }
+    }
+}
+
+impl ResultStreamAdapter {
+    /// Keep `rows`, split off the end of `batch`, to send before any rows
+    /// already pending.
+    fn defer(&mut self, batch: &mut proto::RowBatch, mut rows: Vec<proto::Row>) {
+        // A backend checksum covers the whole batch.
+        batch.checksum = None;
+        if let Some(pending) = self.pending.take() {
+            rows.extend(pending.rows);
+        }
+        self.pending = Some(proto::RowBatch {
+            rows,
+            checksum: None,
+            dictionary: batch.dictionary.clone(),
+            columns: None,
+        });
+    }
+
+    /// Warnings the server adds to the summary: a slow-query warning, if
+    /// the result took too long, and a throttle warning, if the row rate
+    /// slowed it.
+    fn summary_warnings(&self) -> Vec<proto::GqlStatus> {
+        let slow = self.slow_query.as_ref().and_then(SlowQuery::check);
+        let throttled = self
+            .rate_limit
+            .as_ref()
+            .and_then(RateLimitPermit::rows_per_second)
+            .filter(|_| self.throttled)
+            .map(|rate| {
+                gql_status::warning(
+                    gql_status::WARNING_THROTTLED,
+                    format!("result throttled to the rate limit of {rate} rows per second"),
+                )
+            });
+        slow.into_iter().chain(throttled).collect()
+    }
 }
 
 impl Stream for ResultStreamAdapter {
@@ -310,45 +1819,234 @@ impl Stream for ResultStreamAdapter {
         mut self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
+        if self.finished {
+            return std::task::Poll::Ready(None);
+        }
+        let shutting_down = self
+            .shutdown
+            .as_mut()
+            .is_some_and(|signal| signal.as_mut().poll(cx).is_ready());
+        if shutting_down {
+            self.finished = true;
+            let status = gql_status::informational(
+                gql_status::SERVER_SHUTDOWN,
+                "server shutting down; retry on another server",
+            );
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.fail(Some(&status));
+            }
+            return std::task::Poll::Ready(Some(Ok(summary_response(Some(status)))));
+        }
+        let expired = self
+            .timeout
+            .as_mut()
+            .and_then(|(timeout, sleep)| sleep.as_mut().poll(cx).is_ready().then_some(*timeout));
+        if let Some(timeout) = expired {
+            self.finished = true;
+            let status = timed_out(timeout).gql_status().cloned();
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.fail(status.as_ref());
+            }
+            return std::task::Poll::Ready(Some(Ok(summary_response(status))));
+        }
+        if let Some(throttle) = self.throttle.as_mut() {
+            if throttle.as_mut().poll(cx).is_pending() {
+                return std::task::Poll::Pending;
+            }
+            self.throttle = None;
+        }
+        if let Some(batch) = self.pending.take() {
+            let response = self.batch_response(batch);
+            return std::task::Poll::Ready(Some(Ok(response)));
+        }
+
         match self.inner.as_mut().poll_next(cx) {
             std::task::Poll::Ready(Some(Ok(frame))) => {
+                if let Some(recorder) = self.recorder.as_mut() {
+                    recorder.observe(&frame);
+                }
+                self.finished = matches!(frame, ResultFrame::Summary(_));
+                self.timings.observe_frame(&frame);
                 let response = match frame {
                     ResultFrame::Header(h) => proto::ExecuteResponse {
                         frame: Some(proto::execute_response::Frame::Header(h)),
                     },
-                    ResultFrame::Batch(b) => proto::ExecuteResponse {
-                        frame: Some(proto::execute_response::Frame::RowBatch(b)),
-                    },
-                    ResultFrame::Summary(s) => proto::ExecuteResponse {
-                        frame: Some(proto::execute_response::Frame::Summary(s)),
+                    ResultFrame::Batch(b) => self.batch_response(b),
+                    ResultFrame::Warning(w) => proto::ExecuteResponse {
+                        frame: Some(proto::execute_response::Frame::Warning(w)),
                     },
+                    ResultFrame::Summary(mut s) => {
+                        s.warnings.extend(self.summary_warnings());
+                        if s.timings.is_none() {
+                            s.timings = Some(self.timings.report());
+                        }
+                        proto::ExecuteResponse {
+                            frame: Some(proto::execute_response::Frame::Summary(s)),
+                        }
+                    }
                 };
                 std::task::Poll::Ready(Some(Ok(response)))
             }
             std::task::Poll::Ready(Some(Err(err))) => {
+                self.finished = true;
                 // Convert backend error to a summary frame with GQLSTATUS
                 let status = match err.gql_status() {
                     Some(s) => s.clone(),
                     None => gql_status::error(gql_status::DATA_EXCEPTION, err.to_string()),
                 };
-                let response = proto::ExecuteResponse {
-                    frame: Some(proto::execute_response::Frame::Summary(
-                        proto::ResultSummary {
-                            status: Some(status),
-                            warnings: Vec::new(),
-                            rows_affected: 0,
-                            counters: HashMap::new(),
-                        },
-                    )),
-                };
-                std::task::Poll::Ready(Some(Ok(response)))
+                if let Some(recorder) = self.recorder.as_mut() {
+                    recorder.fail(Some(&status));
+                }
+                std::task::Poll::Ready(Some(Ok(summary_response(Some(status)))))
+            }
+            std::task::Poll::Ready(None) => {
+                self.finished = true;
+                std::task::Poll::Ready(None)
             }
-            std::task::Poll::Ready(None) => std::task::Poll::Ready(None),
             std::task::Poll::Pending => std::task::Poll::Pending,
         }
     }
 }
 
+/// Split rows off the end of `batch` until it encodes to at most
+/// `max_bytes`, returning them, or `None` if it already fits. A batch is
+/// never split below one row.
+fn split_to_fit(batch: &mut proto::RowBatch, max_bytes: usize) -> Option<Vec<proto::Row>> {
+    let mut len = prost::Message::encoded_len(batch);
+    if len <= max_bytes || batch.rows.len() < 2 {
+        return None;
+    }
+    let mut rest = Vec::new();
+    while len > max_bytes && batch.rows.len() > 1 {
+        // Estimate the rows that fit from the average row size, then
+        // check again in case the rows vary in size.
+        let keep =
+            (batch.rows.len().saturating_mul(max_bytes) / len).clamp(1, batch.rows.len() - 1);
+        let mut tail = batch.rows.split_off(keep);
+        tail.append(&mut rest);
+        rest = tail;
+        len = prost::Message::encoded_len(batch);
+    }
+    Some(rest)
+}
+
+/// The context of a statement whose request carried `metadata`: the
+/// client's query ID, and a span continuing the client's trace. Both are
+/// recorded on the current span.
+fn execution_context(metadata: &tonic::metadata::MetadataMap) -> ExecutionContext {
+    let value = |key| metadata.get(key).and_then(|v| v.to_str().ok());
+    let span = tracing::Span::current();
+    let query_id = value(proto::QUERY_ID_METADATA).map(str::to_owned);
+    if let Some(query_id) = &query_id {
+        span.record("query_id", query_id.as_str());
+    }
+    let trace = value(proto::TRACEPARENT_METADATA)
+        .and_then(TraceContext::parse)
+        .map(|parent| parent.child().unwrap_or(parent));
+    if let Some(trace) = &trace {
+        span.record("trace_id", trace.trace_id());
+        span.record("span_id", trace.span_id());
+    }
+    ExecutionContext { trace, query_id }
+}
+
+/// The last message of a batch, tagged with statement `index`.
+fn batch_status(index: u32, status: proto::GqlStatus) -> proto::ExecuteBatchResponse {
+    proto::ExecuteBatchResponse {
+        statement_index: index,
+        frame: Some(proto::execute_batch_response::Frame::BatchStatus(status)),
+    }
+}
+
+/// The status to answer a savepoint request with once the backend has
+/// handled it. The transaction stays active either way.
+fn savepoint_status(action: &str, result: Result<(), GqlError>) -> proto::GqlStatus {
+    match result {
+        Ok(()) => {
+            tracing::info!("savepoint {action}");
+            gql_status::success()
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "savepoint not {action}");
+            match err.gql_status() {
+                Some(s) => s.clone(),
+                None => gql_status::error(gql_status::INVALID_TRANSACTION_STATE, err.to_string()),
+            }
+        }
+    }
+}
+
+/// The start of a statement, short enough for a span field.
+fn statement_field(statement: &str) -> &str {
+    let mut end = statement.len().min(100);
+    while !statement.is_char_boundary(end) {
+        end -= 1;
+    }
+    &statement[..end]
+}
+
+/// Drive `frames` in a background task that stays at most `max` frames
+/// ahead of the returned stream. The task ends with the result or when
+/// the returned stream is dropped.
+fn prefetch(
+    mut frames: ResultStreamAdapter,
+    max: usize,
+) -> ReceiverStream<Result<proto::ExecuteResponse, Status>> {
+    let (tx, rx) = tokio::sync::mpsc::channel(max.max(1));
+    tokio::spawn(async move {
+        loop {
+            let frame = tokio::select! {
+                frame = frames.next() => frame,
+                () = tx.closed() => return,
+            };
+            let Some(frame) = frame else {
+                return;
+            };
+            if tx.send(frame).await.is_err() {
+                return;
+            }
+        }
+    });
+    ReceiverStream::new(rx)
+}
+
+/// The batch size a client asked for, with 0 meaning no limit.
+fn fetch_size(rows: Option<u32>) -> Option<usize> {
+    rows.filter(|&rows| rows > 0)
+        .and_then(|rows| usize::try_from(rows).ok())
+}
+
+/// The error a statement ends with once it outlasts `timeout`.
+fn timed_out(timeout: Duration) -> GqlError {
+    tracing::warn!(?timeout, "statement timed out");
+    GqlError::status(
+        gql_status::STATEMENT_TIMEOUT,
+        format!("statement exceeded the timeout of {timeout:?}"),
+    )
+}
+
+/// A summary frame with `status` and nothing else.
+fn summary_response(status: Option<proto::GqlStatus>) -> proto::ExecuteResponse {
+    proto::ExecuteResponse {
+        frame: Some(proto::execute_response::Frame::Summary(
+            proto::ResultSummary {
+                status,
+                warnings: Vec::new(),
+                rows_affected: 0,
+                counters: HashMap::new(),
+                update_counters: None,
+                timings: None,
+            },
+        )),
+    }
+}
+
+/// An execute response consisting of a single summary with `status`.
+fn failure_response(status: proto::GqlStatus) -> Response<ExecuteResponseStream> {
+    let summary_stream = futures_single_response(summary_response(Some(status)));
+    Response::new(Box::pin(summary_stream))
+}
+
 /// Create a stream that yields a single response then completes.
 fn futures_single_response(
     response: proto::ExecuteResponse,
@@ -0,0 +1,828 @@
+//! Decoding graph imports as their chunks arrive.
+//!
+//! Imports read the files [`ExportChunks`](super::ExportChunks) writes:
+//! JSON Lines with one element per line, or `GraphML` with `labels` and
+//! `properties` data. `GraphML` from other tools is read too: data under
+//! any other key becomes a property typed by the key's `attr.type`.
+//! Element IDs written as hex are decoded to their bytes, and any other ID
+//! is taken as its UTF-8 bytes.
+//!
+//! Elements that cannot be decoded are skipped and reported as
+//! `PARSE_ERROR` diagnostics naming the line (JSON Lines) or element
+//! (`GraphML`).
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::types::{Edge, Node, Value};
+
+use super::backend::{GraphElement, ValidationDiagnostic};
+use super::interchange::GraphFormat;
+
+/// Diagnostic code for elements that could not be decoded.
+const PARSE_ERROR: &str = "PARSE_ERROR";
+
+/// The elements of a graph import as they are decoded from the client's
+/// chunks, passed to
+/// [`GqlBackend::import_graph`](super::GqlBackend::import_graph).
+pub struct ImportElements {
+    requests: tonic::Streaming<proto::ImportGraphRequest>,
+    decoder: Decoder,
+    decoded: Decoded,
+    first: Option<Vec<u8>>,
+    finished: bool,
+}
+
+impl ImportElements {
+    /// Decode the chunks of an import in `format`, starting with the data
+    /// of the first message, or `None` if imports in `format` are not
+    /// supported.
+    pub(crate) fn new(
+        format: GraphFormat,
+        first: Vec<u8>,
+        requests: tonic::Streaming<proto::ImportGraphRequest>,
+    ) -> Option<Self> {
+        Some(Self {
+            requests,
+            decoder: Decoder::new(format)?,
+            decoded: Decoded::default(),
+            first: Some(first),
+            finished: false,
+        })
+    }
+
+    /// Wait for the next element, or `None` once the client has sent them
+    /// all.
+    ///
+    /// Elements that cannot be decoded are skipped; the server reports
+    /// them once the import is done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client's stream fails, in which case the
+    /// import is incomplete.
+    pub async fn next(&mut self) -> Result<Option<GraphElement>, GqlError> {
+        loop {
+            if let Some(element) = self.decoded.elements.pop_front() {
+                return Ok(Some(element));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+            let data = if let Some(data) = self.first.take() {
+                data
+            } else if let Some(request) = self.requests.message().await? {
+                request.data
+            } else {
+                self.decoder.finish(&mut self.decoded);
+                self.finished = true;
+                continue;
+            };
+            self.decoder.feed(&data, &mut self.decoded);
+        }
+    }
+
+    /// Take the diagnostics for the elements that could not be decoded so
+    /// far.
+    pub(crate) fn take_errors(&mut self) -> Vec<ValidationDiagnostic> {
+        std::mem::take(&mut self.decoded.errors)
+    }
+}
+
+impl std::fmt::Debug for ImportElements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportElements")
+            .field("decoder", &self.decoder)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Elements decoded and not yet taken, and the elements that failed.
+#[derive(Debug, Default)]
+struct Decoded {
+    elements: VecDeque<GraphElement>,
+    errors: Vec<ValidationDiagnostic>,
+}
+
+impl Decoded {
+    fn push(&mut self, element: Result<GraphElement, String>, context: String) {
+        match element {
+            Ok(element) => self.elements.push_back(element),
+            Err(message) => self.errors.push(ValidationDiagnostic {
+                code: PARSE_ERROR.to_owned(),
+                message,
+                context: Some(context),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Decoder {
+    JsonLines(JsonLines),
+    GraphMl(GraphMl),
+}
+
+impl Decoder {
+    fn new(format: GraphFormat) -> Option<Self> {
+        match format {
+            GraphFormat::JsonLines => Some(Self::JsonLines(JsonLines::default())),
+            GraphFormat::GraphMl => Some(Self::GraphMl(GraphMl::default())),
+            GraphFormat::Csv => None,
+        }
+    }
+
+    fn feed(&mut self, data: &[u8], out: &mut Decoded) {
+        match self {
+            Self::JsonLines(decoder) => decoder.feed(data, out),
+            Self::GraphMl(decoder) => decoder.feed(data, out),
+        }
+    }
+
+    fn finish(&mut self, out: &mut Decoded) {
+        match self {
+            Self::JsonLines(decoder) => decoder.finish(out),
+            Self::GraphMl(decoder) => decoder.finish(out),
+        }
+    }
+}
+
+/// Splits JSON Lines input into lines, decoding each as it completes.
+#[derive(Debug, Default)]
+struct JsonLines {
+    buffer: Vec<u8>,
+    line: usize,
+}
+
+impl JsonLines {
+    fn feed(&mut self, data: &[u8], out: &mut Decoded) {
+        self.buffer.extend_from_slice(data);
+        let mut start = 0;
+        while let Some(end) = self.buffer[start..].iter().position(|&b| b == b'\n') {
+            let line = self.buffer[start..start + end].to_vec();
+            start += end + 1;
+            self.decode_line(&line, out);
+        }
+        self.buffer.drain(..start);
+    }
+
+    fn finish(&mut self, out: &mut Decoded) {
+        let line = std::mem::take(&mut self.buffer);
+        if !line.is_empty() {
+            self.decode_line(&line, out);
+        }
+    }
+
+    fn decode_line(&mut self, line: &[u8], out: &mut Decoded) {
+        self.line += 1;
+        let Ok(line) = std::str::from_utf8(line) else {
+            out.push(
+                Err("invalid UTF-8".to_owned()),
+                format!("line {}", self.line),
+            );
+            return;
+        };
+        let line = line.trim();
+        if !line.is_empty() {
+            out.push(json_element(line), format!("line {}", self.line));
+        }
+    }
+}
+
+/// Decode one JSON Lines element.
+fn json_element(line: &str) -> Result<GraphElement, String> {
+    let Json::Object(fields) = parse_json(line)? else {
+        return Err("expected a JSON object".to_owned());
+    };
+    let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|(_, v)| v);
+    let string = |name: &str| match field(name) {
+        Some(Json::String(s)) => Ok(s.as_str()),
+        Some(_) => Err(format!("{name} must be a string")),
+        None => Err(format!("missing {name}")),
+    };
+
+    let id = element_id(string("id")?);
+    let labels = match field("labels") {
+        None => Vec::new(),
+        Some(Json::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                Json::String(label) => Ok(label.clone()),
+                _ => Err("labels must be strings".to_owned()),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("labels must be an array".to_owned()),
+    };
+    let properties = match field("properties") {
+        None => HashMap::new(),
+        Some(Json::Object(properties)) => properties
+            .iter()
+            .map(|(name, value)| match value {
+                Json::String(literal) => Value::parse_literal(literal)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|e| format!("property {name}: {e}")),
+                _ => Err(format!("property {name} must be a GQL literal string")),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("properties must be an object".to_owned()),
+    };
+
+    match string("kind")? {
+        "node" => Ok(GraphElement::Node(Node {
+            id,
+            labels,
+            properties,
+        })),
+        "edge" => Ok(GraphElement::Edge(Edge {
+            id,
+            labels,
+            source_node_id: element_id(string("source")?),
+            target_node_id: element_id(string("target")?),
+            undirected: match field("undirected") {
+                None | Some(Json::Bool(false)) => false,
+                Some(Json::Bool(true)) => true,
+                Some(_) => return Err("undirected must be a boolean".to_owned()),
+            },
+            properties,
+        })),
+        kind => Err(format!("unknown element kind {kind}")),
+    }
+}
+
+/// Collects `GraphML` elements as their tags complete.
+#[derive(Debug, Default)]
+struct GraphMl {
+    buffer: Vec<u8>,
+    /// Declared keys by ID: the attribute name and type.
+    keys: HashMap<String, (String, String)>,
+    undirected_default: bool,
+    /// Number of `<node>` and `<edge>` elements begun.
+    count: usize,
+    element: Option<PendingElement>,
+    /// The key of the `<data>` being read, and its text so far.
+    data: Option<(String, String)>,
+}
+
+#[derive(Debug)]
+struct PendingElement {
+    kind: String,
+    attributes: HashMap<String, String>,
+    data: Vec<(String, String)>,
+}
+
+impl GraphMl {
+    fn feed(&mut self, data: &[u8], out: &mut Decoded) {
+        self.buffer.extend_from_slice(data);
+        let mut pos = 0;
+        loop {
+            let rest = &self.buffer[pos..];
+            let Some(open) = rest.iter().position(|&b| b == b'<') else {
+                // Hold text back until its tag arrives; it may end in the
+                // middle of a character.
+                break;
+            };
+            if open > 0 {
+                let text = String::from_utf8_lossy(&rest[..open]).into_owned();
+                self.text(&text);
+                pos += open;
+                continue;
+            }
+            let Some(len) = markup_len(rest) else {
+                break;
+            };
+            let markup = String::from_utf8_lossy(&rest[..len]).into_owned();
+            pos += len;
+            self.markup(&markup, out);
+        }
+        self.buffer.drain(..pos);
+    }
+
+    fn finish(&mut self, out: &mut Decoded) {
+        let rest = std::mem::take(&mut self.buffer);
+        if self.element.is_some() || !rest.iter().all(u8::is_ascii_whitespace) {
+            out.push(
+                Err("unexpected end of input".to_owned()),
+                format!("element {}", self.count.max(1)),
+            );
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some((_, value)) = &mut self.data {
+            value.push_str(&unescape_xml(text));
+        }
+    }
+
+    /// Handle one tag, comment, or declaration, `<` to `>` inclusive.
+    fn markup(&mut self, markup: &str, out: &mut Decoded) {
+        if let Some(text) = markup
+            .strip_prefix("<![CDATA[")
+            .and_then(|m| m.strip_suffix("]]>"))
+        {
+            if let Some((_, value)) = &mut self.data {
+                value.push_str(text);
+            }
+            return;
+        }
+        if markup.starts_with("<!") || markup.starts_with("<?") {
+            return;
+        }
+        if let Some(name) = markup.strip_prefix("</") {
+            let name = name.trim_end_matches('>').trim();
+            self.end_tag(name, out);
+            return;
+        }
+        let inner = markup[1..markup.len() - 1].trim();
+        let (inner, empty) = match inner.strip_suffix('/') {
+            Some(inner) => (inner, true),
+            None => (inner, false),
+        };
+        let (name, attributes) = parse_tag(inner);
+        match name {
+            "graph" => {
+                self.undirected_default =
+                    attributes.get("edgedefault").map(String::as_str) == Some("undirected");
+            }
+            "key" => {
+                if let Some(id) = attributes.get("id") {
+                    let name = attributes.get("attr.name").unwrap_or(id).clone();
+                    let ty = attributes
+                        .get("attr.type")
+                        .cloned()
+                        .unwrap_or_else(|| "string".to_owned());
+                    self.keys.insert(id.clone(), (name, ty));
+                }
+            }
+            "node" | "edge" => {
+                self.count += 1;
+                self.element = Some(PendingElement {
+                    kind: name.to_owned(),
+                    attributes,
+                    data: Vec::new(),
+                });
+                if empty {
+                    self.end_tag(name, out);
+                }
+            }
+            "data" if self.element.is_some() => {
+                let key = attributes.get("key").cloned().unwrap_or_default();
+                if empty {
+                    if let Some(element) = &mut self.element {
+                        element.data.push((key, String::new()));
+                    }
+                } else {
+                    self.data = Some((key, String::new()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_tag(&mut self, name: &str, out: &mut Decoded) {
+        match name {
+            "data" => {
+                if let (Some(data), Some(element)) = (self.data.take(), &mut self.element) {
+                    element.data.push(data);
+                }
+            }
+            "node" | "edge" => {
+                if let Some(element) = self.element.take() {
+                    let element = self.build(&element);
+                    out.push(element, format!("element {}", self.count));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn build(&self, element: &PendingElement) -> Result<GraphElement, String> {
+        let attribute = |name: &str| {
+            element
+                .attributes
+                .get(name)
+                .map(|value| element_id(value))
+                .ok_or_else(|| format!("{} has no {name}", element.kind))
+        };
+        let id = attribute("id")?;
+        let mut labels = Vec::new();
+        let mut properties = HashMap::new();
+        for (key, text) in &element.data {
+            let (name, ty) = self
+                .keys
+                .get(key)
+                .map_or((key.as_str(), "string"), |(name, ty)| {
+                    (name.as_str(), ty.as_str())
+                });
+            match name {
+                "labels" => labels.extend(
+                    text.split(';')
+                        .filter(|label| !label.is_empty())
+                        .map(str::to_owned),
+                ),
+                "properties" => match Value::parse_literal(text) {
+                    Ok(Value::Record(record)) => properties.extend(
+                        record
+                            .fields
+                            .into_iter()
+                            .map(|field| (field.name, field.value)),
+                    ),
+                    Ok(_) => return Err("properties must be a record literal".to_owned()),
+                    Err(e) => return Err(format!("properties: {e}")),
+                },
+                name => {
+                    properties.insert(name.to_owned(), typed_value(text, ty, name)?);
+                }
+            }
+        }
+
+        if element.kind == "node" {
+            return Ok(GraphElement::Node(Node {
+                id,
+                labels,
+                properties,
+            }));
+        }
+        let undirected = match element.attributes.get("directed").map(String::as_str) {
+            Some("false") => true,
+            Some("true") => false,
+            Some(other) => return Err(format!("directed must be true or false, not {other}")),
+            None => self.undirected_default,
+        };
+        Ok(GraphElement::Edge(Edge {
+            id,
+            labels,
+            source_node_id: attribute("source")?,
+            target_node_id: attribute("target")?,
+            undirected,
+            properties,
+        }))
+    }
+}
+
+/// The value of `GraphML` data under a key of type `ty`.
+fn typed_value(text: &str, ty: &str, name: &str) -> Result<Value, String> {
+    let invalid = || format!("property {name}: {text:?} is not a {ty}");
+    match ty {
+        "boolean" => match text.trim() {
+            "true" => Ok(Value::Boolean(true)),
+            "false" => Ok(Value::Boolean(false)),
+            _ => Err(invalid()),
+        },
+        "int" | "long" => text
+            .trim()
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| invalid()),
+        "float" | "double" => text.trim().parse().map(Value::Float).map_err(|_| invalid()),
+        _ => Ok(Value::String(text.to_owned())),
+    }
+}
+
+/// The length of the markup at the start of `bytes`, or `None` if it has
+/// not fully arrived.
+fn markup_len(bytes: &[u8]) -> Option<usize> {
+    let find = |pattern: &[u8]| {
+        bytes
+            .windows(pattern.len())
+            .position(|w| w == pattern)
+            .map(|i| i + pattern.len())
+    };
+    if bytes.starts_with(b"<!--") {
+        return find(b"-->");
+    }
+    if bytes.starts_with(b"<![CDATA[") {
+        return find(b"]]>");
+    }
+    let mut quote = None;
+    for (i, &b) in bytes.iter().enumerate() {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), b) if b == q => quote = None,
+            (None, b'>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a start tag's contents into its name and attributes.
+fn parse_tag(inner: &str) -> (&str, HashMap<String, String>) {
+    let name_end = inner
+        .find(|c: char| c.is_ascii_whitespace())
+        .unwrap_or(inner.len());
+    let (name, mut rest) = inner.split_at(name_end);
+    let mut attributes = HashMap::new();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        attributes.insert(key.to_owned(), unescape_xml(&value[1..=end]));
+        rest = &value[end + 2..];
+    }
+    (name, attributes)
+}
+
+fn unescape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let c = entity.and_then(|(name, _)| match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let code = match name.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#').and_then(|n| n.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        if let (Some(c), Some((_, end))) = (c, entity) {
+            out.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// An element ID: the bytes of an even-length hex string, or else the
+/// UTF-8 bytes of the text.
+fn element_id(text: &str) -> Vec<u8> {
+    let hex =
+        !text.is_empty() && text.len() % 2 == 0 && text.bytes().all(|b| b.is_ascii_hexdigit());
+    if hex {
+        (0..text.len())
+            .step_by(2)
+            .filter_map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+            .collect()
+    } else {
+        text.as_bytes().to_vec()
+    }
+}
+
+/// A parsed JSON value, keeping only what imports read.
+#[derive(Debug)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number,
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+fn parse_json(text: &str) -> Result<Json, String> {
+    let mut parser = JsonParser { text, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(format!("unexpected input at column {}", parser.pos + 1));
+    }
+    Ok(value)
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.text.len() - self.rest().trim_start().len();
+    }
+
+    fn error(&self, expected: &str) -> String {
+        format!("expected {expected} at column {}", self.pos + 1)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some('-' | '0'..='9') => {
+                let len = self
+                    .rest()
+                    .find(|c: char| !matches!(c, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+                    .unwrap_or(self.rest().len());
+                self.pos += len;
+                Ok(Json::Number)
+            }
+            _ if self.eat("true") => Ok(Json::Bool(true)),
+            _ if self.eat("false") => Ok(Json::Bool(false)),
+            _ if self.eat("null") => Ok(Json::Null),
+            _ => Err(self.error("a value")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut fields = Vec::new();
+        if self.eat("}") {
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            if !self.eat(":") {
+                return Err(self.error("':'"));
+            }
+            fields.push((key, self.value()?));
+            if self.eat("}") {
+                return Ok(Json::Object(fields));
+            }
+            if !self.eat(",") {
+                return Err(self.error("',' or '}'"));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if self.eat("]") {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.eat("]") {
+                return Ok(Json::Array(items));
+            }
+            if !self.eat(",") {
+                return Err(self.error("',' or ']'"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.rest().starts_with('"') {
+            return Err(self.error("a string"));
+        }
+        self.pos += 1;
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        let unit = |chars: &mut std::str::CharIndices<'_>| {
+            let hex: String = chars.take(4).map(|(_, c)| c).collect();
+            u32::from_str_radix(&hex, 16).ok()
+        };
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let high = unit(&mut chars).ok_or("invalid \\u escape")?;
+                            let code = if (0xD800..0xDC00).contains(&high) {
+                                if chars.next().map(|(_, c)| c) != Some('\\')
+                                    || chars.next().map(|(_, c)| c) != Some('u')
+                                {
+                                    return Err("unpaired surrogate in \\u escape".to_owned());
+                                }
+                                let low = unit(&mut chars).ok_or("invalid \\u escape")?;
+                                0x10000
+                                    + ((high - 0xD800) << 10)
+                                    + (low.wrapping_sub(0xDC00) & 0x3FF)
+                            } else {
+                                high
+                            };
+                            char::from_u32(code).ok_or("invalid \\u escape")?
+                        }
+                        _ => return Err("invalid escape in string".to_owned()),
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+        Err("unterminated string".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::interchange::tests::{elements, encode};
+
+    fn decode(format: GraphFormat, input: &str, chunk: usize) -> Decoded {
+        let mut decoder = Decoder::new(format).unwrap();
+        let mut out = Decoded::default();
+        for chunk in input.as_bytes().chunks(chunk) {
+            decoder.feed(chunk, &mut out);
+        }
+        decoder.finish(&mut out);
+        out
+    }
+
+    #[test]
+    fn exports_decode_to_the_same_elements() {
+        let expected = elements();
+        for format in [GraphFormat::JsonLines, GraphFormat::GraphMl] {
+            // Chunks that split lines, tags, and characters.
+            for chunk in [1, 7, 4096] {
+                let decoded = decode(format, &encode(format), chunk);
+                assert!(decoded.errors.is_empty(), "{:?}", decoded.errors);
+                assert_eq!(Vec::from(decoded.elements), expected, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn graphml_keys_type_other_data() {
+        let input = r#"<graphml>
+  <key id="d0" for="node" attr.name="age" attr.type="int"/>
+  <key id="d1" for="node" attr.name="member" attr.type="boolean"/>
+  <graph edgedefault="undirected">
+    <node id="n0"><data key="d0">42</data><data key="d1">true</data><data key="name">Bo &amp; Co</data></node>
+    <edge source="n0" target="n0"/>
+  </graph>
+</graphml>"#;
+        let decoded = decode(GraphFormat::GraphMl, input, 4096);
+        let GraphElement::Node(node) = &decoded.elements[0] else {
+            panic!("expected a node");
+        };
+        assert_eq!(node.id, b"n0");
+        assert_eq!(node.properties["age"], Value::Integer(42));
+        assert_eq!(node.properties["member"], Value::Boolean(true));
+        assert_eq!(node.properties["name"], Value::String("Bo & Co".into()));
+        assert_eq!(decoded.errors.len(), 1);
+        assert_eq!(decoded.errors[0].message, "edge has no id");
+        assert_eq!(decoded.errors[0].context.as_deref(), Some("element 2"));
+    }
+
+    #[test]
+    fn bad_lines_are_reported_and_skipped() {
+        let input = concat!(
+            r#"{"kind":"node","id":"01"}"#,
+            "\n",
+            "not json\n",
+            "\n",
+            r#"{"kind":"node","id":"02","properties":{"age":"thirty"}}"#,
+            "\n",
+            r#"{"kind":"node","id":"03","labels":["été"]}"#,
+        );
+        let decoded = decode(GraphFormat::JsonLines, input, 5);
+        let ids: Vec<_> = decoded
+            .elements
+            .iter()
+            .map(|element| match element {
+                GraphElement::Node(node) => node.id.clone(),
+                GraphElement::Edge(edge) => edge.id.clone(),
+            })
+            .collect();
+        assert_eq!(ids, [vec![1], vec![3]]);
+        let GraphElement::Node(node) = &decoded.elements[1] else {
+            panic!("expected a node");
+        };
+        assert_eq!(node.labels, ["été"]);
+        let contexts: Vec<_> = decoded
+            .errors
+            .iter()
+            .map(|e| e.context.as_deref().unwrap())
+            .collect();
+        assert_eq!(contexts, ["line 2", "line 4"]);
+        assert!(decoded.errors[1].message.starts_with("property age"));
+    }
+}
@@ -0,0 +1,400 @@
+//! Interchange formats for exporting graphs.
+//!
+//! Every format writes property values as GQL literals
+//! ([`Value::to_gql_literal`]), so `30`, `3E1`, and `DATE '2026-01-01'`
+//! read back as the values they were, and element IDs in lowercase hex:
+//!
+//! | Format | Element |
+//! |--------|---------|
+//! | JSON Lines | `{"kind":"node","id":"01","labels":["Person"],"properties":{"name":"'Alice'"}}`, edges adding `source`, `target`, and `undirected` |
+//! | `GraphML` | `<node>` and `<edge>` with `labels` and `properties` data |
+//! | CSV | a `kind,id,labels,source,target,undirected,properties` row |
+//!
+//! `GraphML` and CSV separate labels with `;` and write the properties as
+//! one record literal, e.g. `{age: 30, name: 'Alice'}`. Properties are
+//! written in name order.
+
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tonic::Status;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::types::{Field, Record, Value};
+
+use super::backend::{ElementStream, GraphElement};
+
+/// Encoded bytes gathered before an export chunk is sent.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+const GRAPHML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="labels" for="all" attr.name="labels" attr.type="string"/>
+  <key id="properties" for="all" attr.name="properties" attr.type="string"/>
+  <graph edgedefault="directed">
+"#;
+
+const GRAPHML_FOOTER: &str = "  </graph>\n</graphml>\n";
+
+const CSV_HEADER: &str = "kind,id,labels,source,target,undirected,properties\n";
+
+/// A file format for graph interchange.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// One JSON object per element per line.
+    #[default]
+    JsonLines,
+    /// `GraphML` XML.
+    GraphMl,
+    /// Comma-separated values, one row per element.
+    Csv,
+}
+
+impl From<proto::GraphFormat> for GraphFormat {
+    fn from(format: proto::GraphFormat) -> Self {
+        match format {
+            proto::GraphFormat::Jsonl => Self::JsonLines,
+            proto::GraphFormat::Graphml => Self::GraphMl,
+            proto::GraphFormat::Csv => Self::Csv,
+        }
+    }
+}
+
+impl From<GraphFormat> for proto::GraphFormat {
+    fn from(format: GraphFormat) -> Self {
+        match format {
+            GraphFormat::JsonLines => Self::Jsonl,
+            GraphFormat::GraphMl => Self::Graphml,
+            GraphFormat::Csv => Self::Csv,
+        }
+    }
+}
+
+impl GraphFormat {
+    fn header(self) -> &'static str {
+        match self {
+            Self::JsonLines => "",
+            Self::GraphMl => GRAPHML_HEADER,
+            Self::Csv => CSV_HEADER,
+        }
+    }
+
+    fn footer(self) -> &'static str {
+        match self {
+            Self::GraphMl => GRAPHML_FOOTER,
+            Self::JsonLines | Self::Csv => "",
+        }
+    }
+
+    /// Append `element` to `out`.
+    fn write_element(self, out: &mut String, element: &GraphElement) -> Result<(), GqlError> {
+        let (kind, id, labels, properties) = match element {
+            GraphElement::Node(node) => ("node", &node.id, &node.labels, &node.properties),
+            GraphElement::Edge(edge) => ("edge", &edge.id, &edge.labels, &edge.properties),
+        };
+        let id = hex(id);
+        let edge = match element {
+            GraphElement::Node(_) => None,
+            GraphElement::Edge(edge) => Some((
+                hex(&edge.source_node_id),
+                hex(&edge.target_node_id),
+                edge.undirected,
+            )),
+        };
+        let no_literal =
+            || GqlError::Protocol(format!("a property of {kind} {id} has no literal form"));
+
+        match self {
+            Self::JsonLines => {
+                let _ = write!(out, r#"{{"kind":"{kind}","id":"{id}","labels":["#);
+                for (i, label) in labels.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(out, label);
+                }
+                out.push(']');
+                if let Some((source, target, undirected)) = &edge {
+                    let _ = write!(
+                        out,
+                        r#","source":"{source}","target":"{target}","undirected":{undirected}"#
+                    );
+                }
+                out.push_str(r#","properties":{"#);
+                for (i, (name, value)) in sorted(properties).into_iter().enumerate() {
+                    let literal = value.to_gql_literal().ok_or_else(no_literal)?;
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(out, name);
+                    out.push(':');
+                    write_json_string(out, &literal);
+                }
+                out.push_str("}}\n");
+            }
+            Self::GraphMl => {
+                let properties = record_literal(properties).ok_or_else(no_literal)?;
+                match &edge {
+                    None => {
+                        let _ = write!(out, r#"    <node id="{id}">"#);
+                    }
+                    Some((source, target, undirected)) => {
+                        let _ = write!(
+                            out,
+                            r#"    <edge id="{id}" source="{source}" target="{target}""#
+                        );
+                        if *undirected {
+                            out.push_str(r#" directed="false""#);
+                        }
+                        out.push('>');
+                    }
+                }
+                out.push_str(r#"<data key="labels">"#);
+                write_xml_text(out, &labels.join(";"));
+                out.push_str(r#"</data><data key="properties">"#);
+                write_xml_text(out, &properties);
+                let _ = writeln!(out, "</data></{kind}>");
+            }
+            Self::Csv => {
+                let properties = record_literal(properties).ok_or_else(no_literal)?;
+                let _ = write!(out, "{kind},{id},");
+                write_csv_field(out, &labels.join(";"));
+                match &edge {
+                    None => out.push_str(",,,,"),
+                    Some((source, target, undirected)) => {
+                        let _ = write!(out, ",{source},{target},{undirected},");
+                    }
+                }
+                write_csv_field(out, &properties);
+                out.push('\n');
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Adapts a backend's [`ElementStream`] to the `ExportGraph` response
+/// stream, encoding elements in a [`GraphFormat`].
+pub struct ExportChunks {
+    elements: Pin<Box<dyn ElementStream>>,
+    format: GraphFormat,
+    buffer: String,
+    done: bool,
+}
+
+impl ExportChunks {
+    pub(crate) fn new(elements: Pin<Box<dyn ElementStream>>, format: GraphFormat) -> Self {
+        Self {
+            elements,
+            format,
+            buffer: format.header().to_owned(),
+            done: false,
+        }
+    }
+
+    fn take_chunk(&mut self) -> proto::ExportChunk {
+        proto::ExportChunk {
+            data: std::mem::take(&mut self.buffer).into_bytes(),
+        }
+    }
+}
+
+impl tokio_stream::Stream for ExportChunks {
+    type Item = Result<proto::ExportChunk, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done {
+            match this.elements.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(element))) => {
+                    if let Err(e) = this.format.write_element(&mut this.buffer, &element) {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(e.to_grpc_status())));
+                    }
+                    if this.buffer.len() >= CHUNK_SIZE {
+                        return Poll::Ready(Some(Ok(this.take_chunk())));
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e.to_grpc_status())));
+                }
+                Poll::Ready(None) => {
+                    this.done = true;
+                    this.buffer.push_str(this.format.footer());
+                    if !this.buffer.is_empty() {
+                        return Poll::Ready(Some(Ok(this.take_chunk())));
+                    }
+                }
+                // Send what is encoded rather than hold it while the
+                // backend is busy.
+                Poll::Pending if !this.buffer.is_empty() => {
+                    return Poll::Ready(Some(Ok(this.take_chunk())));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+impl std::fmt::Debug for ExportChunks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportChunks")
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+fn sorted(properties: &HashMap<String, Value>) -> Vec<(&String, &Value)> {
+    let mut properties: Vec<_> = properties.iter().collect();
+    properties.sort_by_key(|(name, _)| *name);
+    properties
+}
+
+/// The properties as one record literal, or `None` if a value has no
+/// literal form.
+fn record_literal(properties: &HashMap<String, Value>) -> Option<String> {
+    let fields = sorted(properties)
+        .into_iter()
+        .map(|(name, value)| Field {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    Value::Record(Record { fields }).to_gql_literal()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_xml_text(out: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Write a CSV field, quoted if it holds a comma, quote, or line break.
+fn write_csv_field(out: &mut String, s: &str) {
+    if s.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&s.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(s);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::types::{Edge, Node};
+
+    pub(crate) fn elements() -> Vec<GraphElement> {
+        let alice = Node::new([1])
+            .with_label("Person")
+            .with_property("name", "Alice, \"Al\"")
+            .with_property("age", 30_i64);
+        let knows = Edge::undirected([9], [1], [2])
+            .with_label("KNOWS")
+            .with_property("since", 2.5);
+        vec![GraphElement::Node(alice), GraphElement::Edge(knows)]
+    }
+
+    pub(crate) fn encode(format: GraphFormat) -> String {
+        let mut out = format.header().to_owned();
+        for element in elements() {
+            format.write_element(&mut out, &element).unwrap();
+        }
+        out.push_str(format.footer());
+        out
+    }
+
+    #[test]
+    fn json_lines_write_properties_as_literals() {
+        assert_eq!(
+            encode(GraphFormat::JsonLines),
+            concat!(
+                r#"{"kind":"node","id":"01","labels":["Person"],"properties":{"age":"30","name":"'Alice, \"Al\"'"}}"#,
+                "\n",
+                r#"{"kind":"edge","id":"09","labels":["KNOWS"],"source":"01","target":"02","undirected":true,"properties":{"since":"2.5E0"}}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn graphml_writes_labels_and_a_record_literal() {
+        let graphml = encode(GraphFormat::GraphMl);
+        assert!(graphml.starts_with(GRAPHML_HEADER));
+        assert!(graphml.ends_with(GRAPHML_FOOTER));
+        assert!(graphml.contains(
+            r#"<node id="01"><data key="labels">Person</data><data key="properties">{age: 30, name: 'Alice, &quot;Al&quot;'}</data></node>"#
+        ));
+        assert!(graphml.contains(
+            r#"<edge id="09" source="01" target="02" directed="false"><data key="labels">KNOWS</data>"#
+        ));
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        assert_eq!(
+            encode(GraphFormat::Csv),
+            concat!(
+                "kind,id,labels,source,target,undirected,properties\n",
+                "node,01,Person,,,,\"{age: 30, name: 'Alice, \"\"Al\"\"'}\"\n",
+                "edge,09,KNOWS,01,02,true,{since: 2.5E0}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn values_without_literals_are_rejected() {
+        let node = Node::new([1]).with_property("score", f64::NAN);
+        for format in [
+            GraphFormat::JsonLines,
+            GraphFormat::GraphMl,
+            GraphFormat::Csv,
+        ] {
+            let err = format
+                .write_element(&mut String::new(), &GraphElement::Node(node.clone()))
+                .unwrap_err();
+            assert!(err.to_string().contains("no literal form"), "{err}");
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! Long-running admin operations run as background jobs.
+//!
+//! `SubmitJob`, and a `CreateIndex` request with `background` set, hand a
+//! [`Job`] to [`GqlBackend::run_job`] on its own task and return a job ID
+//! at once. The [`JobManager`] keeps each job's [`JobStatus`] for
+//! `GetJobStatus`, `ListJobs`, and `IndexBuildProgress`, and aborts the
+//! job's task on `CancelJob`.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+
+use crate::error::GqlError;
+use crate::proto;
+
+use super::backend::{AdminValidationResult, GqlBackend, IndexDefinition, IndexState};
+
+/// Number of finished jobs remembered for late status requests.
+const FINISHED_JOBS: usize = 64;
+
+/// A long-running admin operation.
+#[derive(Debug, Clone)]
+pub enum Job {
+    /// Build an index.
+    CreateIndex {
+        /// Graph to index.
+        graph: String,
+        /// Index to build.
+        index: IndexDefinition,
+    },
+    /// Validate graph integrity.
+    Validate {
+        /// Graph to validate.
+        graph: String,
+    },
+    /// Force a WAL checkpoint.
+    WalCheckpoint {
+        /// Graph to checkpoint.
+        graph: String,
+    },
+}
+
+impl Job {
+    /// The graph the job works on.
+    #[must_use]
+    pub fn graph(&self) -> &str {
+        match self {
+            Self::CreateIndex { graph, .. }
+            | Self::Validate { graph }
+            | Self::WalCheckpoint { graph } => graph,
+        }
+    }
+}
+
+impl From<Job> for proto::Job {
+    fn from(job: Job) -> Self {
+        use proto::job::Kind;
+        let kind = match job {
+            Job::CreateIndex { graph, index } => Kind::CreateIndex(proto::CreateIndexRequest {
+                graph,
+                index: Some(index.into()),
+                background: true,
+            }),
+            Job::Validate { graph } => Kind::Validate(proto::ValidateRequest { graph }),
+            Job::WalCheckpoint { graph } => {
+                Kind::WalCheckpoint(proto::WalCheckpointRequest { graph })
+            }
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<proto::Job> for Job {
+    type Error = GqlError;
+
+    fn try_from(p: proto::Job) -> Result<Self, GqlError> {
+        use proto::job::Kind;
+        match p.kind {
+            Some(Kind::CreateIndex(req)) => {
+                let index = req
+                    .index
+                    .ok_or_else(|| GqlError::Protocol("index definition is required".into()))?;
+                Ok(Self::CreateIndex {
+                    graph: req.graph,
+                    index: index.into(),
+                })
+            }
+            Some(Kind::Validate(req)) => Ok(Self::Validate { graph: req.graph }),
+            Some(Kind::WalCheckpoint(req)) => Ok(Self::WalCheckpoint { graph: req.graph }),
+            None => Err(GqlError::Protocol("job kind is required".into())),
+        }
+    }
+}
+
+/// What a successful job produced.
+#[derive(Debug, Clone)]
+pub enum JobOutput {
+    /// The job has no output.
+    Done,
+    /// The result of a [`Job::Validate`].
+    Validation(AdminValidationResult),
+}
+
+/// Where a job is in its life.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JobState {
+    /// Still running.
+    #[default]
+    Running,
+    /// Finished successfully.
+    Succeeded,
+    /// Finished with an error.
+    Failed,
+    /// Cancelled before it finished.
+    Cancelled,
+}
+
+impl From<proto::JobState> for JobState {
+    fn from(state: proto::JobState) -> Self {
+        match state {
+            proto::JobState::Running => Self::Running,
+            proto::JobState::Succeeded => Self::Succeeded,
+            proto::JobState::Failed => Self::Failed,
+            proto::JobState::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl From<JobState> for proto::JobState {
+    fn from(state: JobState) -> Self {
+        match state {
+            JobState::Running => Self::Running,
+            JobState::Succeeded => Self::Succeeded,
+            JobState::Failed => Self::Failed,
+            JobState::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+/// The state and progress of a background job.
+#[derive(Debug, Clone)]
+pub struct JobStatus {
+    /// ID the job was submitted under.
+    pub job_id: String,
+    /// What the job does.
+    pub job: Job,
+    /// Whether the job is still running, and how it ended.
+    pub state: JobState,
+    /// Percent complete, from 0 to 100.
+    pub percent: f64,
+    /// Why the job failed.
+    pub error: Option<String>,
+    /// What the job produced, once it has succeeded.
+    pub output: Option<JobOutput>,
+}
+
+impl From<JobStatus> for proto::JobStatus {
+    fn from(s: JobStatus) -> Self {
+        let output = match s.output {
+            Some(JobOutput::Validation(result)) => {
+                Some(proto::job_status::Output::Validation(result.into()))
+            }
+            Some(JobOutput::Done) | None => None,
+        };
+        Self {
+            job_id: s.job_id,
+            job: Some(s.job.into()),
+            state: proto::JobState::from(s.state).into(),
+            percent: s.percent,
+            error: s.error,
+            output,
+        }
+    }
+}
+
+impl TryFrom<proto::JobStatus> for JobStatus {
+    type Error = GqlError;
+
+    fn try_from(p: proto::JobStatus) -> Result<Self, GqlError> {
+        let job = p
+            .job
+            .ok_or_else(|| GqlError::Protocol("job status without a job".into()))?;
+        let state = proto::JobState::try_from(p.state).map_or(JobState::Running, JobState::from);
+        let output = match p.output {
+            Some(proto::job_status::Output::Validation(result)) => {
+                Some(JobOutput::Validation(result.into()))
+            }
+            None if state == JobState::Succeeded => Some(JobOutput::Done),
+            None => None,
+        };
+        Ok(Self {
+            job_id: p.job_id,
+            job: Job::try_from(job)?,
+            state,
+            percent: p.percent,
+            error: p.error,
+            output,
+        })
+    }
+}
+
+/// How far an index build has come.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexBuildStatus {
+    /// Percent complete, from 0 to 100.
+    pub percent: f64,
+    /// [`IndexState::Building`] until the build ends.
+    pub state: IndexState,
+    /// Why the build failed.
+    pub error: Option<String>,
+}
+
+impl From<&JobStatus> for IndexBuildStatus {
+    fn from(s: &JobStatus) -> Self {
+        let (state, error) = match s.state {
+            JobState::Running => (IndexState::Building, None),
+            JobState::Succeeded => (IndexState::Ready, None),
+            JobState::Failed => (IndexState::Failed, s.error.clone()),
+            JobState::Cancelled => (IndexState::Failed, Some("index build cancelled".to_owned())),
+        };
+        Self {
+            percent: s.percent,
+            state,
+            error,
+        }
+    }
+}
+
+impl From<IndexBuildStatus> for proto::IndexBuildProgressUpdate {
+    fn from(s: IndexBuildStatus) -> Self {
+        Self {
+            percent: s.percent,
+            state: proto::IndexState::from(s.state).into(),
+            error: s.error,
+        }
+    }
+}
+
+impl From<proto::IndexBuildProgressUpdate> for IndexBuildStatus {
+    fn from(p: proto::IndexBuildProgressUpdate) -> Self {
+        Self {
+            percent: p.percent,
+            state: proto::IndexState::try_from(p.state).map_or(IndexState::Ready, IndexState::from),
+            error: p.error,
+        }
+    }
+}
+
+/// Where [`GqlBackend::run_job`] reports the progress of a job.
+#[derive(Debug)]
+pub struct JobProgress {
+    status: Arc<watch::Sender<JobStatus>>,
+}
+
+impl JobProgress {
+    /// Record that the job is `percent` complete, clamped to 0 to 100.
+    pub fn report(&self, percent: f64) {
+        self.status
+            .send_modify(|status| status.percent = percent.clamp(0.0, 100.0));
+    }
+
+    /// Record how the job ended, unless it was cancelled first.
+    fn finish(&self, result: Result<JobOutput, GqlError>) {
+        self.status.send_if_modified(|status| {
+            if status.state != JobState::Running {
+                return false;
+            }
+            match result {
+                Ok(output) => {
+                    status.percent = 100.0;
+                    status.state = JobState::Succeeded;
+                    status.output = Some(output);
+                }
+                Err(err) => {
+                    status.state = JobState::Failed;
+                    status.error = Some(err.to_string());
+                }
+            }
+            true
+        });
+    }
+}
+
+#[derive(Debug)]
+struct JobEntry {
+    status: Arc<watch::Sender<JobStatus>>,
+    task: AbortHandle,
+}
+
+impl JobEntry {
+    fn is_running(&self) -> bool {
+        self.status.borrow().state == JobState::Running
+    }
+}
+
+/// The background jobs a server has started, in submission order.
+///
+/// Running jobs are kept until they finish; once 64 jobs have finished,
+/// the oldest finished ones are forgotten.
+#[derive(Debug, Clone, Default)]
+pub struct JobManager {
+    jobs: Arc<Mutex<Vec<JobEntry>>>,
+}
+
+impl JobManager {
+    /// Create an empty job manager.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `job` on `backend` on its own task, returning the job's ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    #[allow(clippy::result_large_err)]
+    pub fn submit<B: GqlBackend>(&self, backend: Arc<B>, job: Job) -> Result<String, GqlError> {
+        let mut bytes = [0u8; 8];
+        getrandom::fill(&mut bytes)
+            .map_err(|e| GqlError::Session(format!("cannot generate job ID: {e}")))?;
+        let id = format!("{:016x}", u64::from_le_bytes(bytes));
+        let (status, _) = watch::channel(JobStatus {
+            job_id: id.clone(),
+            job: job.clone(),
+            state: JobState::Running,
+            percent: 0.0,
+            error: None,
+            output: None,
+        });
+        let status = Arc::new(status);
+
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        let finished = jobs.iter().filter(|entry| !entry.is_running()).count();
+        let mut excess = finished.saturating_sub(FINISHED_JOBS - 1);
+        jobs.retain(|entry| {
+            if excess > 0 && !entry.is_running() {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+
+        let progress = JobProgress {
+            status: Arc::clone(&status),
+        };
+        let job_id = id.clone();
+        let task = tokio::spawn(async move {
+            let graph = job.graph().to_owned();
+            let result = backend.run_job(job, &progress).await;
+            match &result {
+                Ok(_) => tracing::info!(job_id, graph, "job finished"),
+                Err(err) => tracing::warn!(job_id, graph, error = %err, "job failed"),
+            }
+            progress.finish(result);
+        });
+        jobs.push(JobEntry {
+            status,
+            task: task.abort_handle(),
+        });
+        Ok(id)
+    }
+
+    /// The status of the job with ID `id`.
+    #[must_use]
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        self.find(id, |entry| entry.status.borrow().clone())
+    }
+
+    /// Follow the status of the job with ID `id` as it changes.
+    #[must_use]
+    pub fn watch(&self, id: &str) -> Option<watch::Receiver<JobStatus>> {
+        self.find(id, |entry| entry.status.subscribe())
+    }
+
+    /// Cancel the job with ID `id`, aborting its task. Returns `false` if
+    /// the job had already finished, and `None` if there is no such job.
+    #[must_use]
+    pub fn cancel(&self, id: &str) -> Option<bool> {
+        self.find(id, |entry| {
+            let cancelled = entry.status.send_if_modified(|status| {
+                if status.state != JobState::Running {
+                    return false;
+                }
+                status.state = JobState::Cancelled;
+                true
+            });
+            if cancelled {
+                entry.task.abort();
+                tracing::info!(job_id = id, "job cancelled");
+            }
+            cancelled
+        })
+    }
+
+    /// The status of every running and remembered job, oldest first.
+    #[must_use]
+    pub fn list(&self) -> Vec<JobStatus> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|entry| entry.status.borrow().clone())
+            .collect()
+    }
+
+    fn find<T>(&self, id: &str, f: impl FnOnce(&JobEntry) -> T) -> Option<T> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|entry| entry.status.borrow().job_id == id)
+            .map(f)
+    }
+}
@@ -0,0 +1,83 @@
+//! The nodes and edges `MockBackend` exports.
+//!
+//! The default graph holds the search corpus: a `Person` node per corpus
+//! name, and a `KNOWS` edge from each person to the next. Imports add to
+//! it, so nodes must come before the edges that use them.
+
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use crate::error::GqlError;
+use crate::server::backend::{ElementStream, GraphElement};
+
+#[derive(Debug)]
+pub(super) struct Elements {
+    elements: Mutex<Vec<GraphElement>>,
+}
+
+impl Elements {
+    pub(super) fn new(elements: Vec<GraphElement>) -> Self {
+        Self {
+            elements: Mutex::new(elements),
+        }
+    }
+
+    /// Stream a snapshot of the elements.
+    pub(super) fn export(&self) -> ElementSnapshot {
+        let elements = self
+            .elements
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        ElementSnapshot(elements.into_iter())
+    }
+}
+
+/// What became of an imported element.
+pub(super) enum Insert {
+    Created,
+    /// An element of the same kind already has the ID.
+    Duplicate,
+    /// An edge endpoint is not a node of the graph.
+    MissingEndpoint,
+}
+
+impl Elements {
+    pub(super) fn insert(&self, element: GraphElement) -> Insert {
+        let mut elements = self.elements.lock().unwrap_or_else(PoisonError::into_inner);
+        let node = |id: &[u8]| {
+            elements
+                .iter()
+                .any(|e| matches!(e, GraphElement::Node(node) if node.id == id))
+        };
+        let taken = match &element {
+            GraphElement::Node(new) => node(&new.id),
+            GraphElement::Edge(new) => {
+                if !node(&new.source_node_id) || !node(&new.target_node_id) {
+                    return Insert::MissingEndpoint;
+                }
+                elements
+                    .iter()
+                    .any(|e| matches!(e, GraphElement::Edge(edge) if edge.id == new.id))
+            }
+        };
+        if taken {
+            return Insert::Duplicate;
+        }
+        elements.push(element);
+        Insert::Created
+    }
+}
+
+/// A copy of the elements, handed out one at a time.
+pub(super) struct ElementSnapshot(std::vec::IntoIter<GraphElement>);
+
+impl ElementStream for ElementSnapshot {
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<GraphElement, GqlError>>> {
+        Poll::Ready(self.0.next().map(Ok))
+    }
+}
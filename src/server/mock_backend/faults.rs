@@ -0,0 +1,171 @@
+//! Deterministic fault injection for `MockBackend`.
+//!
+//! Faults are armed with the `fail_*` / `drop_*` methods on
+//! [`MockBackend`] and stay in effect until [`MockBackend::clear_faults`].
+//! Random session drops use a seeded generator, so a given seed produces
+//! the same sequence of drops on every run.
+
+use std::collections::HashSet;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::error::GqlError;
+use crate::server::backend::SessionHandle;
+
+use super::MockBackend;
+use super::rng::SplitMix64;
+
+/// A GQLSTATUS code and message to fail with.
+#[derive(Debug, Clone)]
+struct InjectedError {
+    code: String,
+    message: String,
+}
+
+impl InjectedError {
+    fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    fn to_error(&self) -> GqlError {
+        GqlError::status(&self.code, self.message.clone())
+    }
+}
+
+/// Random session drop configuration and generator state.
+#[derive(Debug)]
+struct SessionDrops {
+    probability: f64,
+    rng: SplitMix64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    executes: u64,
+    fail_execute_at: Option<(u64, InjectedError)>,
+    fail_streams_after: Option<(usize, InjectedError)>,
+    failing_commits: usize,
+    session_drops: Option<SessionDrops>,
+    dropped: HashSet<String>,
+}
+
+/// Armed faults, consulted by the mock's backend methods.
+#[derive(Debug, Default)]
+pub(super) struct Faults {
+    state: Mutex<State>,
+}
+
+impl Faults {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Count an execute and return the injected error if it is the one
+    /// armed to fail.
+    pub(super) fn on_execute(&self) -> Option<GqlError> {
+        let mut state = self.lock();
+        state.executes += 1;
+        let executes = state.executes;
+        match state.fail_execute_at.take() {
+            Some((at, err)) if at == executes => Some(err.to_error()),
+            other => {
+                state.fail_execute_at = other;
+                None
+            }
+        }
+    }
+
+    /// The batch count after which result streams fail, with the error.
+    pub(super) fn stream_failure(&self) -> Option<(usize, GqlError)> {
+        self.lock()
+            .fail_streams_after
+            .as_ref()
+            .map(|(batches, err)| (*batches, err.to_error()))
+    }
+
+    /// Return a serialization failure if a commit failure is armed.
+    pub(super) fn on_commit(&self) -> Option<GqlError> {
+        let mut state = self.lock();
+        if state.failing_commits == 0 {
+            return None;
+        }
+        state.failing_commits -= 1;
+        Some(GqlError::status(
+            crate::status::SERIALIZATION_FAILURE,
+            "mock serialization failure",
+        ))
+    }
+
+    /// Roll for a random session drop, returning the error if the session
+    /// is dropped. Once dropped, a session stays dropped and every later
+    /// call on it fails.
+    pub(super) fn check_session(&self, session: &SessionHandle) -> Option<GqlError> {
+        let mut state = self.lock();
+        let dropped = state.dropped.contains(&session.0)
+            || state
+                .session_drops
+                .as_mut()
+                .is_some_and(|drops| drops.rng.next_unit() < drops.probability);
+        if !dropped {
+            return None;
+        }
+        state.dropped.insert(session.0.clone());
+        Some(GqlError::Session(format!(
+            "session {} not found",
+            session.0
+        )))
+    }
+}
+
+impl MockBackend {
+    /// Fail the `n`th execute from now (1-based) with the given GQLSTATUS.
+    pub fn fail_nth_execute(&self, n: u64, code: &str, message: impl Into<String>) {
+        let mut state = self.faults.lock();
+        let at = state.executes + n.max(1);
+        state.fail_execute_at = Some((at, InjectedError::new(code, message)));
+    }
+
+    /// Make every result stream fail after delivering its header and at
+    /// most `batches` row batches, instead of completing normally.
+    pub fn fail_streams_after_batches(
+        &self,
+        batches: usize,
+        code: &str,
+        message: impl Into<String>,
+    ) {
+        self.faults.lock().fail_streams_after = Some((batches, InjectedError::new(code, message)));
+    }
+
+    /// Fail the next `count` commits or two-phase commit prepares with a
+    /// serialization failure.
+    pub fn fail_next_commits(&self, count: usize) {
+        self.faults.lock().failing_commits = count;
+    }
+
+    /// Drop sessions at random: each execute, begin, or commit drops its
+    /// session with the given probability. The sequence of drops is
+    /// determined by `seed`.
+    pub fn drop_sessions_randomly(&self, probability: f64, seed: u64) {
+        self.faults.lock().session_drops = Some(SessionDrops {
+            probability,
+            rng: SplitMix64::new(seed),
+        });
+    }
+
+    /// Whether a session has been dropped by fault injection.
+    #[must_use]
+    pub fn is_session_dropped(&self, session_id: &str) -> bool {
+        self.faults.lock().dropped.contains(session_id)
+    }
+
+    /// Disarm all faults. Sessions that were already dropped stay dropped.
+    pub fn clear_faults(&self) {
+        let mut state = self.faults.lock();
+        state.fail_execute_at = None;
+        state.fail_streams_after = None;
+        state.failing_commits = 0;
+        state.session_drops = None;
+    }
+}
@@ -0,0 +1,60 @@
+//! The bytes `MockBackend` backs up and restores.
+//!
+//! The mock has no real database to copy, so it stands in an opaque
+//! image: a restore replaces it and a backup streams it back in chunks.
+//! Before the first restore the image is empty.
+
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use crate::error::GqlError;
+use crate::server::backend::BackupStream;
+
+/// Bytes per backup chunk, small enough that tests see several.
+const CHUNK_SIZE: usize = 1024;
+
+#[derive(Debug, Default)]
+pub(super) struct Image {
+    bytes: Mutex<Vec<u8>>,
+}
+
+impl Image {
+    /// Stream the current image in chunks.
+    pub(super) fn backup(&self) -> ImageChunks {
+        ImageChunks {
+            bytes: self
+                .bytes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+            offset: 0,
+        }
+    }
+
+    /// Replace the image with `bytes`.
+    pub(super) fn restore(&self, bytes: Vec<u8>) {
+        *self.bytes.lock().unwrap_or_else(PoisonError::into_inner) = bytes;
+    }
+}
+
+/// A copy of the image, handed out one chunk at a time.
+pub(super) struct ImageChunks {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl BackupStream for ImageChunks {
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Vec<u8>, GqlError>>> {
+        let start = self.offset;
+        if start >= self.bytes.len() {
+            return Poll::Ready(None);
+        }
+        let end = (start + CHUNK_SIZE).min(self.bytes.len());
+        self.offset = end;
+        Poll::Ready(Some(Ok(self.bytes[start..end].to_vec())))
+    }
+}
@@ -0,0 +1,181 @@
+//! Simulated latency for `MockBackend`.
+//!
+//! Each [`MockOperation`] can be given a [`Latency`]: a base delay plus an
+//! optional [`Jitter`] distribution. Jitter is drawn from a seeded
+//! generator, so a given seed produces the same sequence of delays.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use super::MockBackend;
+use super::rng::SplitMix64;
+
+/// A backend operation whose latency can be simulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockOperation {
+    /// Session creation during the handshake.
+    Handshake,
+    /// Time from an execute request until its first result frame.
+    ExecuteFirstFrame,
+    /// Delay before each row batch of a result stream.
+    Batch,
+    /// Transaction commit.
+    Commit,
+    /// Closing a session.
+    CloseSession,
+    /// The second half of a background index build, after it reports 50%.
+    IndexBuild,
+}
+
+/// Random variation added on top of a base latency.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Jitter {
+    /// No variation.
+    #[default]
+    None,
+    /// Uniformly distributed in `[0, max)`.
+    Uniform(Duration),
+    /// Exponentially distributed with the given mean, for long-tail delays.
+    Exponential(Duration),
+}
+
+/// Simulated latency for one operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Latency {
+    /// Delay applied on every call.
+    pub base: Duration,
+    /// Random delay added to `base`.
+    pub jitter: Jitter,
+}
+
+impl Latency {
+    /// A fixed delay without jitter.
+    #[must_use]
+    pub fn fixed(base: Duration) -> Self {
+        Self {
+            base,
+            jitter: Jitter::None,
+        }
+    }
+
+    /// Add jitter on top of the base delay.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    fn sample(&self, rng: &mut SplitMix64) -> Duration {
+        let extra = match self.jitter {
+            Jitter::None => Duration::ZERO,
+            Jitter::Uniform(max) => max.mul_f64(rng.next_unit()),
+            Jitter::Exponential(mean) => mean.mul_f64(-(1.0 - rng.next_unit()).ln()),
+        };
+        self.base.saturating_add(extra)
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    latencies: HashMap<MockOperation, Latency>,
+    rng: SplitMix64,
+}
+
+/// Configured latencies, consulted by the mock's backend methods.
+#[derive(Debug)]
+pub(super) struct Delays {
+    state: Mutex<State>,
+}
+
+impl Default for Delays {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                latencies: HashMap::new(),
+                rng: SplitMix64::new(0),
+            }),
+        }
+    }
+}
+
+impl Delays {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Draw the delay for one occurrence of `op`, if it has a latency.
+    pub(super) fn sample(&self, op: MockOperation) -> Option<Duration> {
+        let mut state = self.lock();
+        let latency = *state.latencies.get(&op)?;
+        Some(latency.sample(&mut state.rng))
+    }
+
+    /// Sleep for one occurrence of `op`.
+    pub(super) async fn wait(&self, op: MockOperation) {
+        if let Some(delay) = self.sample(op) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+impl MockBackend {
+    /// Simulate latency on an operation, replacing any previous setting.
+    pub fn set_latency(&self, op: MockOperation, latency: Latency) {
+        self.delays.lock().latencies.insert(op, latency);
+    }
+
+    /// Reseed the jitter generator.
+    pub fn seed_latency(&self, seed: u64) {
+        self.delays.lock().rng = SplitMix64::new(seed);
+    }
+
+    /// Remove all simulated latency.
+    pub fn clear_latency(&self) {
+        self.delays.lock().latencies.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_latency_has_no_jitter() {
+        let latency = Latency::fixed(Duration::from_millis(5));
+        let mut rng = SplitMix64::new(1);
+        assert_eq!(latency.sample(&mut rng), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn uniform_jitter_within_bounds() {
+        let latency = Latency::fixed(Duration::from_millis(10))
+            .with_jitter(Jitter::Uniform(Duration::from_millis(5)));
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..1000 {
+            let d = latency.sample(&mut rng);
+            assert!(d >= Duration::from_millis(10));
+            assert!(d < Duration::from_millis(15));
+        }
+    }
+
+    #[test]
+    fn jitter_is_reproducible_from_seed() {
+        let latency = Latency::fixed(Duration::ZERO)
+            .with_jitter(Jitter::Exponential(Duration::from_millis(3)));
+        let draw = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..16)
+                .map(|_| latency.sample(&mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(99), draw(99));
+        assert_ne!(draw(99), draw(100));
+    }
+
+    #[test]
+    fn unconfigured_operation_has_no_delay() {
+        let delays = Delays::default();
+        assert!(delays.sample(MockOperation::Commit).is_none());
+    }
+}
@@ -0,0 +1,1436 @@
+//! Mock backend for testing the wire protocol server.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::types::{Edge, Node, Value};
+
+mod elements;
+mod faults;
+mod image;
+mod latency;
+mod notifications;
+mod rng;
+mod savepoints;
+mod script;
+
+pub use latency::{Jitter, Latency, MockOperation};
+pub use script::{MockCall, StatementStub};
+
+use elements::{Elements, Insert};
+use faults::Faults;
+use image::Image;
+use latency::Delays;
+use notifications::Subscribers;
+use savepoints::Savepoints;
+use script::Script;
+
+use super::backend::{
+    AdminStats, AdminValidationResult, AdminWalStatus, BackupStream, CloneGraphConfig,
+    CreateDatabaseConfig, CreateGraphConfig, DatabaseInfo, ElementStream, ExecutionContext,
+    GqlBackend, GraphConfig, GraphElement, GraphInfo, GraphTypeInfo, HealthStatus,
+    HybridSearchParams, ImportReport, IndexDefinition, IndexInfo, IndexState, LabelCount,
+    LabelStats, MemoryBreakdown, NotificationSource, PreparedHandle, PropertyCardinality,
+    QueryPlan, ResetTarget, ResultFrame, ResultStream, SchemaInfo, SearchHit, SearchResults,
+    SearchTarget, SessionConfig, SessionDefaults, SessionHandle, SessionProperty, TextSearchParams,
+    TransactionConfig, TransactionHandle, ValidationDiagnostic, VectorSearchParams,
+};
+use super::backup::RestoreChunks;
+use super::bulk_load::{BulkChunks, BulkLoadStats};
+use super::import::ImportElements;
+use super::jobs::JobProgress;
+
+/// A simple in-memory backend for testing.
+///
+/// Tracks sessions and transactions. For `execute()`, returns the
+/// received statement and parameters in [echo mode](MockBackend::set_echo),
+/// else the response scripted with [`MockBackend::on_statement`] if one
+/// matches, otherwise canned results based on the statement text. Every session,
+/// execute, and transaction call is recorded for later inspection, and
+/// faults and latency can be injected to exercise error, retry, and
+/// timeout paths.
+pub struct MockBackend {
+    script: Script,
+    faults: Faults,
+    delays: Delays,
+    subscribers: Subscribers,
+    savepoints: Savepoints,
+    echo: AtomicBool,
+    serving: AtomicBool,
+    session_counter: AtomicU64,
+    transaction_counter: AtomicU64,
+    graph_configs: RwLock<HashMap<String, GraphConfig>>,
+    indexes: RwLock<Vec<IndexDefinition>>,
+    wal_checkpoints: AtomicU64,
+    image: Image,
+    elements: Elements,
+    databases: RwLock<HashMap<String, SessionDefaults>>,
+}
+
+impl MockBackend {
+    /// Create a new mock backend.
+    #[must_use]
+    pub fn new() -> Self {
+        let default_config = GraphConfig {
+            memory_limit_bytes: None,
+            wal_durability: Some("sync".to_owned()),
+            threads: Some(4),
+        };
+        Self {
+            script: Script::default(),
+            faults: Faults::default(),
+            delays: Delays::default(),
+            subscribers: Subscribers::default(),
+            savepoints: Savepoints::default(),
+            echo: AtomicBool::new(false),
+            serving: AtomicBool::new(true),
+            session_counter: AtomicU64::new(1),
+            transaction_counter: AtomicU64::new(1),
+            graph_configs: RwLock::new(HashMap::from([("default".to_owned(), default_config)])),
+            indexes: RwLock::new(Vec::new()),
+            wal_checkpoints: AtomicU64::new(0),
+            image: Image::default(),
+            elements: Elements::new(corpus_graph()),
+            databases: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl MockBackend {
+    /// Answer every statement with the statement text and parameters it
+    /// was executed with, for checking a driver's value encoding against
+    /// this server.
+    ///
+    /// The result has the columns `statement`, `parameter`, and `value`,
+    /// with one row per parameter in name order, or a single row with
+    /// null `parameter` and `value` if there are none. Parameter values
+    /// have been decoded from and are re-encoded to protobuf, so a driver
+    /// should read back exactly the values it sent. Faults and latency
+    /// still apply; scripted responses do not.
+    pub fn set_echo(&self, enabled: bool) {
+        self.echo.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Report the backend as serving or not serving from
+    /// [`GqlBackend::health`], for exercising health checks.
+    pub fn set_serving(&self, serving: bool) {
+        self.serving.store(serving, Ordering::Relaxed);
+    }
+
+    /// Reject database defaults naming a graph that does not exist.
+    async fn check_defaults(&self, defaults: &SessionDefaults) -> Result<(), GqlError> {
+        if let Some(graph) = &defaults.graph {
+            let schema = defaults.schema.as_deref().unwrap_or("default");
+            self.get_graph_info(schema, graph).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tonic::async_trait]
+impl GqlBackend for MockBackend {
+    async fn create_session(&self, config: &SessionConfig) -> Result<SessionHandle, GqlError> {
+        self.delays.wait(MockOperation::Handshake).await;
+        let id = self.session_counter.fetch_add(1, Ordering::Relaxed);
+        let session = SessionHandle(format!("mock-session-{id}"));
+        self.script.record(MockCall::CreateSession {
+            session: session.clone(),
+            client_info: config.client_info.clone(),
+            database: config.database.clone(),
+        });
+        Ok(session)
+    }
+
+    async fn close_session(&self, session: &SessionHandle) -> Result<(), GqlError> {
+        self.delays.wait(MockOperation::CloseSession).await;
+        self.script.record(MockCall::CloseSession {
+            session: session.clone(),
+        });
+        Ok(())
+    }
+
+    async fn configure_session(
+        &self,
+        session: &SessionHandle,
+        property: SessionProperty,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::Configure {
+            session: session.clone(),
+            property,
+        });
+        Ok(())
+    }
+
+    async fn reset_session(
+        &self,
+        _session: &SessionHandle,
+        _target: ResetTarget,
+    ) -> Result<(), GqlError> {
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        session: &SessionHandle,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+        transaction: Option<&TransactionHandle>,
+        context: &ExecutionContext,
+    ) -> Result<Pin<Box<dyn ResultStream>>, GqlError> {
+        self.script.record(MockCall::Execute {
+            session: session.clone(),
+            statement: statement.to_owned(),
+            parameters: parameters.clone(),
+            transaction: transaction.cloned(),
+            context: context.clone(),
+        });
+
+        self.delays.wait(MockOperation::ExecuteFirstFrame).await;
+        if let Some(err) = self
+            .faults
+            .check_session(session)
+            .or_else(|| self.faults.on_execute())
+        {
+            return Err(err);
+        }
+
+        let stream = if self.echo.load(Ordering::Relaxed) {
+            MockResultStream::echo(statement, parameters)
+        } else if let Some(scripted) = self.script.respond(statement) {
+            scripted?
+        } else {
+            // Parse statement to determine response
+            let trimmed = statement.trim().to_uppercase();
+
+            if trimmed.starts_with("MATCH") || trimmed.starts_with("RETURN") {
+                // Simulate a binding table result with some rows
+                MockResultStream::binding_table()
+            } else if trimmed.starts_with("INSERT")
+                || trimmed.starts_with("DELETE")
+                || trimmed.starts_with("SET")
+            {
+                // Simulate a DML operation
+                MockResultStream::dml(3)
+            } else if trimmed.starts_with("CREATE") || trimmed.starts_with("DROP") {
+                // Simulate a DDL operation
+                MockResultStream::ddl()
+            } else if trimmed.starts_with("ERROR") {
+                // Simulate an error for testing
+                return Err(GqlError::status(
+                    crate::status::INVALID_SYNTAX,
+                    "mock syntax error",
+                ));
+            } else {
+                MockResultStream::ddl()
+            }
+        };
+
+        let mut stream = match self.faults.stream_failure() {
+            Some((batches, err)) => stream.fail_after_batches(batches, err),
+            None => stream,
+        };
+        stream.batch_delays = stream
+            .frames
+            .iter()
+            .filter(|frame| matches!(frame, ResultFrame::Batch(_)))
+            .filter_map(|_| self.delays.sample(MockOperation::Batch))
+            .collect();
+        Ok(Box::pin(stream))
+    }
+
+    async fn begin_transaction(
+        &self,
+        session: &SessionHandle,
+        mode: proto::TransactionMode,
+    ) -> Result<TransactionHandle, GqlError> {
+        self.begin_transaction_with(session, mode.into()).await
+    }
+
+    async fn begin_transaction_with(
+        &self,
+        session: &SessionHandle,
+        config: TransactionConfig,
+    ) -> Result<TransactionHandle, GqlError> {
+        if let Some(err) = self.faults.check_session(session) {
+            return Err(err);
+        }
+        let id = self.transaction_counter.fetch_add(1, Ordering::Relaxed);
+        let transaction = TransactionHandle(format!("mock-tx-{id}"));
+        self.script.record(MockCall::BeginTransaction {
+            session: session.clone(),
+            transaction: transaction.clone(),
+            isolation_level: config.isolation_level,
+            timeout: config.timeout,
+        });
+        Ok(transaction)
+    }
+
+    async fn commit(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::Commit {
+            session: session.clone(),
+            transaction: transaction.clone(),
+        });
+        self.savepoints.end(transaction);
+        self.delays.wait(MockOperation::Commit).await;
+        match self
+            .faults
+            .check_session(session)
+            .or_else(|| self.faults.on_commit())
+        {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    async fn rollback(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::Rollback {
+            session: session.clone(),
+            transaction: transaction.clone(),
+        });
+        self.savepoints.end(transaction);
+        Ok(())
+    }
+
+    async fn health(&self) -> HealthStatus {
+        if self.serving.load(Ordering::Relaxed) {
+            HealthStatus::Serving
+        } else {
+            HealthStatus::NotServing
+        }
+    }
+
+    async fn prepare_commit(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::PrepareCommit {
+            session: session.clone(),
+            transaction: transaction.clone(),
+        });
+        match self
+            .faults
+            .check_session(session)
+            .or_else(|| self.faults.on_commit())
+        {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    async fn commit_prepared(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::CommitPrepared {
+            session: session.clone(),
+            transaction: transaction.clone(),
+        });
+        self.savepoints.end(transaction);
+        self.delays.wait(MockOperation::Commit).await;
+        Ok(())
+    }
+
+    async fn rollback_prepared(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::RollbackPrepared {
+            session: session.clone(),
+            transaction: transaction.clone(),
+        });
+        self.savepoints.end(transaction);
+        Ok(())
+    }
+
+    // =========================================================================
+    // Savepoints
+    // =========================================================================
+
+    async fn savepoint(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+        name: &str,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::Savepoint {
+            session: session.clone(),
+            transaction: transaction.clone(),
+            name: name.to_owned(),
+        });
+        self.savepoints.mark(transaction, name);
+        Ok(())
+    }
+
+    async fn release_savepoint(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+        name: &str,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::ReleaseSavepoint {
+            session: session.clone(),
+            transaction: transaction.clone(),
+            name: name.to_owned(),
+        });
+        self.savepoints.release(transaction, name)
+    }
+
+    async fn rollback_to_savepoint(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+        name: &str,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::RollbackToSavepoint {
+            session: session.clone(),
+            transaction: transaction.clone(),
+            name: name.to_owned(),
+        });
+        self.savepoints.rollback_to(transaction, name)
+    }
+
+    // =========================================================================
+    // Prepared statements
+    // =========================================================================
+
+    async fn prepare(
+        &self,
+        session: &SessionHandle,
+        prepared: &PreparedHandle,
+        statement: &str,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::Prepare {
+            session: session.clone(),
+            prepared: prepared.clone(),
+            statement: statement.to_owned(),
+        });
+        if statement.trim().to_uppercase().starts_with("ERROR") {
+            return Err(GqlError::status(
+                crate::status::INVALID_SYNTAX,
+                "mock syntax error",
+            ));
+        }
+        Ok(())
+    }
+
+    async fn close_prepared(
+        &self,
+        session: &SessionHandle,
+        prepared: &PreparedHandle,
+    ) -> Result<(), GqlError> {
+        self.script.record(MockCall::ClosePrepared {
+            session: session.clone(),
+            prepared: prepared.clone(),
+        });
+        Ok(())
+    }
+
+    async fn bulk_load(
+        &self,
+        session: &SessionHandle,
+        chunks: &mut BulkChunks,
+    ) -> Result<BulkLoadStats, GqlError> {
+        let mut stats = BulkLoadStats::default();
+        let mut received = Vec::new();
+        while let Some(chunk) = chunks.next().await? {
+            for node in &chunk.nodes {
+                if node.id.is_empty() {
+                    stats.errors += 1;
+                } else {
+                    stats.nodes_loaded += 1;
+                }
+            }
+            for edge in &chunk.edges {
+                if edge.source_node_id.is_empty() || edge.target_node_id.is_empty() {
+                    stats.errors += 1;
+                } else {
+                    stats.edges_loaded += 1;
+                }
+            }
+            chunks.report(stats);
+            received.push(chunk);
+        }
+        self.script.record(MockCall::BulkLoad {
+            session: session.clone(),
+            chunks: received,
+        });
+        Ok(stats)
+    }
+
+    async fn subscribe(
+        &self,
+        session: &SessionHandle,
+        topic: &str,
+    ) -> Result<Pin<Box<dyn NotificationSource>>, GqlError> {
+        self.script.record(MockCall::Subscribe {
+            session: session.clone(),
+            topic: topic.to_owned(),
+        });
+        Ok(Box::pin(self.subscribers.subscribe(topic)))
+    }
+
+    async fn explain(
+        &self,
+        session: &SessionHandle,
+        statement: &str,
+        _parameters: &HashMap<String, Value>,
+    ) -> Result<QueryPlan, GqlError> {
+        self.script.record(MockCall::Explain {
+            session: session.clone(),
+            statement: statement.to_owned(),
+        });
+        if statement.trim().to_uppercase().starts_with("ERROR") {
+            return Err(GqlError::status(
+                crate::status::INVALID_SYNTAX,
+                "mock syntax error",
+            ));
+        }
+        Ok(QueryPlan {
+            operator: "Project".to_owned(),
+            details: statement.trim().to_owned(),
+            estimated_rows: 3.0,
+            cost: 4.5,
+            children: vec![QueryPlan {
+                operator: "NodeScan".to_owned(),
+                details: "(n)".to_owned(),
+                estimated_rows: 3.0,
+                cost: 3.0,
+                children: Vec::new(),
+            }],
+        })
+    }
+
+    // =========================================================================
+    // Catalog operations
+    // =========================================================================
+
+    async fn list_schemas(&self) -> Result<Vec<SchemaInfo>, GqlError> {
+        Ok(vec![SchemaInfo {
+            name: "default".to_owned(),
+            graph_count: 2,
+            graph_type_count: 0,
+        }])
+    }
+
+    async fn create_schema(&self, name: &str, if_not_exists: bool) -> Result<(), GqlError> {
+        if name == "default" && !if_not_exists {
+            return Err(GqlError::Session(
+                "schema 'default' already exists".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn drop_schema(&self, name: &str, if_exists: bool) -> Result<bool, GqlError> {
+        if name == "default" {
+            return Err(GqlError::Session(
+                "cannot drop the default schema".to_owned(),
+            ));
+        }
+        if name == "nonexistent" && !if_exists {
+            return Err(GqlError::Session(format!("schema '{name}' not found")));
+        }
+        Ok(name != "nonexistent")
+    }
+
+    async fn session_defaults(&self, database: Option<&str>) -> Result<SessionDefaults, GqlError> {
+        let Some(name) = database else {
+            return Ok(SessionDefaults {
+                schema: Some("default".to_owned()),
+                graph: Some("default".to_owned()),
+            });
+        };
+        // Databases that were never created only route statements.
+        Ok(self
+            .databases
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn create_database(
+        &self,
+        config: CreateDatabaseConfig,
+    ) -> Result<DatabaseInfo, GqlError> {
+        self.check_defaults(&config.defaults).await?;
+        let mut databases = self.databases.write().await;
+        if let Some(defaults) = databases.get(&config.name) {
+            if !config.if_not_exists {
+                return Err(GqlError::Session(format!(
+                    "database '{}' already exists",
+                    config.name
+                )));
+            }
+            return Ok(DatabaseInfo {
+                name: config.name,
+                defaults: defaults.clone(),
+            });
+        }
+        databases.insert(config.name.clone(), config.defaults.clone());
+        Ok(DatabaseInfo {
+            name: config.name,
+            defaults: config.defaults,
+        })
+    }
+
+    async fn alter_database(
+        &self,
+        name: &str,
+        defaults: SessionDefaults,
+    ) -> Result<DatabaseInfo, GqlError> {
+        self.check_defaults(&defaults).await?;
+        let mut databases = self.databases.write().await;
+        let Some(current) = databases.get_mut(name) else {
+            return Err(GqlError::Session(format!("database '{name}' not found")));
+        };
+        current.clone_from(&defaults);
+        Ok(DatabaseInfo {
+            name: name.to_owned(),
+            defaults,
+        })
+    }
+
+    async fn get_database_info(&self, name: &str) -> Result<DatabaseInfo, GqlError> {
+        let databases = self.databases.read().await;
+        let defaults = databases
+            .get(name)
+            .ok_or_else(|| GqlError::Session(format!("database '{name}' not found")))?;
+        Ok(DatabaseInfo {
+            name: name.to_owned(),
+            defaults: defaults.clone(),
+        })
+    }
+
+    async fn list_graphs(&self, _schema: &str) -> Result<Vec<GraphInfo>, GqlError> {
+        Ok(vec![
+            GraphInfo {
+                schema: "default".to_owned(),
+                name: "default".to_owned(),
+                node_count: 100,
+                edge_count: 50,
+                graph_type: String::new(),
+                storage_mode: "InMemory".to_owned(),
+                memory_limit_bytes: None,
+                backward_edges: Some(false),
+                threads: None,
+            },
+            GraphInfo {
+                schema: "default".to_owned(),
+                name: "test".to_owned(),
+                node_count: 10,
+                edge_count: 5,
+                graph_type: String::new(),
+                storage_mode: "InMemory".to_owned(),
+                memory_limit_bytes: None,
+                backward_edges: None,
+                threads: None,
+            },
+        ])
+    }
+
+    async fn create_graph(&self, config: CreateGraphConfig) -> Result<GraphInfo, GqlError> {
+        if config.name == "default" && !config.if_not_exists {
+            return Err(GqlError::Session(
+                "graph 'default' already exists".to_owned(),
+            ));
+        }
+        Ok(GraphInfo {
+            schema: config.schema,
+            name: config.name,
+            node_count: 0,
+            edge_count: 0,
+            graph_type: String::new(),
+            storage_mode: config.storage_mode,
+            memory_limit_bytes: config.memory_limit_bytes,
+            backward_edges: config.backward_edges,
+            threads: config.threads,
+        })
+    }
+
+    async fn drop_graph(
+        &self,
+        _schema: &str,
+        name: &str,
+        if_exists: bool,
+    ) -> Result<bool, GqlError> {
+        if name == "default" {
+            return Err(GqlError::Session(
+                "cannot drop the default graph".to_owned(),
+            ));
+        }
+        if name == "nonexistent" && !if_exists {
+            return Err(GqlError::Session(format!("graph '{name}' not found")));
+        }
+        Ok(name != "nonexistent")
+    }
+
+    async fn get_graph_info(&self, schema: &str, name: &str) -> Result<GraphInfo, GqlError> {
+        match name {
+            "default" => Ok(GraphInfo {
+                schema: schema.to_owned(),
+                name: "default".to_owned(),
+                node_count: 100,
+                edge_count: 50,
+                graph_type: String::new(),
+                storage_mode: "InMemory".to_owned(),
+                memory_limit_bytes: None,
+                backward_edges: Some(false),
+                threads: None,
+            }),
+            "test" => Ok(GraphInfo {
+                schema: schema.to_owned(),
+                name: "test".to_owned(),
+                node_count: 10,
+                edge_count: 5,
+                graph_type: String::new(),
+                storage_mode: "InMemory".to_owned(),
+                memory_limit_bytes: None,
+                backward_edges: None,
+                threads: None,
+            }),
+            _ => Err(GqlError::Session(format!("graph '{name}' not found"))),
+        }
+    }
+
+    async fn clone_graph(&self, config: CloneGraphConfig) -> Result<GraphInfo, GqlError> {
+        let source = self
+            .get_graph_info(&config.source_schema, &config.source_name)
+            .await?;
+        if matches!(config.target_name.as_str(), "default" | "test") && !config.if_not_exists {
+            return Err(GqlError::Session(format!(
+                "graph '{}' already exists",
+                config.target_name
+            )));
+        }
+        Ok(GraphInfo {
+            schema: config.target_schema,
+            name: config.target_name,
+            storage_mode: config.storage_mode.unwrap_or(source.storage_mode),
+            ..source
+        })
+    }
+
+    async fn list_graph_types(&self, schema: &str) -> Result<Vec<GraphTypeInfo>, GqlError> {
+        Ok(vec![GraphTypeInfo {
+            schema: schema.to_owned(),
+            name: "PersonGraph".to_owned(),
+        }])
+    }
+
+    async fn create_graph_type(
+        &self,
+        _schema: &str,
+        name: &str,
+        if_not_exists: bool,
+        _or_replace: bool,
+    ) -> Result<(), GqlError> {
+        if name == "PersonGraph" && !if_not_exists {
+            return Err(GqlError::Session(
+                "graph type 'PersonGraph' already exists".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn drop_graph_type(
+        &self,
+        _schema: &str,
+        name: &str,
+        if_exists: bool,
+    ) -> Result<bool, GqlError> {
+        if name == "nonexistent" && !if_exists {
+            return Err(GqlError::Session(format!("graph type '{name}' not found")));
+        }
+        Ok(name != "nonexistent")
+    }
+
+    // =========================================================================
+    // Admin operations
+    // =========================================================================
+
+    async fn get_graph_stats(&self, graph: &str) -> Result<AdminStats, GqlError> {
+        if graph != "default" {
+            return Err(GqlError::Session(format!("graph '{graph}' not found")));
+        }
+        let memory_breakdown = MemoryBreakdown {
+            node_store_bytes: 4096,
+            edge_store_bytes: 2048,
+            property_store_bytes: 8192,
+            index_bytes: 1024,
+            wal_buffer_bytes: 512,
+        };
+        Ok(AdminStats {
+            node_count: 100,
+            edge_count: 50,
+            label_count: 2,
+            edge_type_count: 2,
+            property_key_count: 3,
+            index_count: 1,
+            memory_bytes: 15872,
+            disk_bytes: None,
+            memory_breakdown: Some(memory_breakdown),
+        })
+    }
+
+    async fn get_graph_config(&self, graph: &str) -> Result<GraphConfig, GqlError> {
+        self.graph_configs
+            .read()
+            .await
+            .get(graph)
+            .cloned()
+            .ok_or_else(|| GqlError::Session(format!("graph '{graph}' not found")))
+    }
+
+    async fn set_graph_config(
+        &self,
+        graph: &str,
+        changes: GraphConfig,
+    ) -> Result<GraphConfig, GqlError> {
+        if let Some(durability) = changes
+            .wal_durability
+            .as_deref()
+            .filter(|d| !matches!(*d, "sync" | "batch" | "async" | "none"))
+        {
+            return Err(GqlError::Grpc(tonic::Status::invalid_argument(format!(
+                "unknown WAL durability mode '{durability}'"
+            ))));
+        }
+        if changes.threads == Some(0) {
+            return Err(GqlError::Grpc(tonic::Status::invalid_argument(
+                "threads must be at least 1",
+            )));
+        }
+
+        let mut configs = self.graph_configs.write().await;
+        let config = configs
+            .get_mut(graph)
+            .ok_or_else(|| GqlError::Session(format!("graph '{graph}' not found")))?;
+        config.apply(changes);
+        Ok(config.clone())
+    }
+
+    async fn get_label_stats(&self, graph: &str) -> Result<LabelStats, GqlError> {
+        if graph != "default" {
+            return Err(GqlError::Session(format!("graph '{graph}' not found")));
+        }
+        Ok(LabelStats {
+            node_labels: vec![
+                LabelCount {
+                    label: "Person".to_owned(),
+                    count: 80,
+                },
+                LabelCount {
+                    label: "City".to_owned(),
+                    count: 20,
+                },
+            ],
+            edge_types: vec![
+                LabelCount {
+                    label: "KNOWS".to_owned(),
+                    count: 40,
+                },
+                LabelCount {
+                    label: "LIVES_IN".to_owned(),
+                    count: 10,
+                },
+            ],
+            properties: vec![
+                PropertyCardinality {
+                    label: "Person".to_owned(),
+                    property: "name".to_owned(),
+                    non_null_count: 80,
+                    distinct_estimate: 75,
+                },
+                PropertyCardinality {
+                    label: "Person".to_owned(),
+                    property: "age".to_owned(),
+                    non_null_count: 60,
+                    distinct_estimate: 40,
+                },
+            ],
+        })
+    }
+
+    async fn wal_status(&self, graph: &str) -> Result<AdminWalStatus, GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        let checkpoints = self.wal_checkpoints.load(Ordering::Relaxed);
+        let record_count = if checkpoints == 0 { 12 } else { 0 };
+        Ok(AdminWalStatus {
+            enabled: true,
+            path: Some("mock.wal".to_owned()),
+            size_bytes: record_count * 64,
+            record_count,
+            last_checkpoint: (checkpoints > 0).then_some(checkpoints),
+            current_epoch: checkpoints + 1,
+        })
+    }
+
+    async fn wal_checkpoint(&self, graph: &str) -> Result<(), GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        self.wal_checkpoints.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn validate(&self, graph: &str) -> Result<AdminValidationResult, GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        Ok(AdminValidationResult {
+            valid: true,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn create_index(&self, graph: &str, index: IndexDefinition) -> Result<(), GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        let mut indexes = self.indexes.write().await;
+        if indexes.iter().any(|i| same_index(i, &index)) {
+            return Err(GqlError::Grpc(tonic::Status::already_exists(
+                "index already exists",
+            )));
+        }
+        indexes.push(index);
+        Ok(())
+    }
+
+    async fn build_index(
+        &self,
+        graph: &str,
+        index: IndexDefinition,
+        progress: &JobProgress,
+    ) -> Result<(), GqlError> {
+        progress.report(50.0);
+        self.delays.wait(MockOperation::IndexBuild).await;
+        self.create_index(graph, index).await
+    }
+
+    async fn backup(&self) -> Result<Pin<Box<dyn BackupStream>>, GqlError> {
+        Ok(Box::pin(self.image.backup()))
+    }
+
+    async fn restore(&self, chunks: &mut RestoreChunks) -> Result<(), GqlError> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = chunks.next().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        self.image.restore(bytes);
+        Ok(())
+    }
+
+    async fn export_graph(&self, graph: &str) -> Result<Pin<Box<dyn ElementStream>>, GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        Ok(Box::pin(self.elements.export()))
+    }
+
+    async fn import_graph(
+        &self,
+        graph: &str,
+        elements: &mut ImportElements,
+    ) -> Result<ImportReport, GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        let mut report = ImportReport::default();
+        while let Some(element) = elements.next().await? {
+            let edge = match &element {
+                GraphElement::Node(_) => None,
+                GraphElement::Edge(edge) => Some(edge.id.clone()),
+            };
+            match (self.elements.insert(element), edge) {
+                (Insert::Created, None) => report.nodes_created += 1,
+                (Insert::Created, Some(_)) => report.edges_created += 1,
+                (Insert::Duplicate, _) => report.duplicates_skipped += 1,
+                (Insert::MissingEndpoint, id) => report.errors.push(ValidationDiagnostic {
+                    code: "DANGLING_EDGE".into(),
+                    message: "edge endpoint is not a node of the graph".into(),
+                    context: id.map(|id| {
+                        id.iter().fold("edge ".to_owned(), |mut s, b| {
+                            let _ = write!(s, "{b:02x}");
+                            s
+                        })
+                    }),
+                }),
+            }
+        }
+        Ok(report)
+    }
+
+    async fn list_indexes(&self, graph: &str) -> Result<Vec<IndexInfo>, GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        Ok(self
+            .indexes
+            .read()
+            .await
+            .iter()
+            .map(|index| IndexInfo {
+                definition: index.clone(),
+                size_bytes: 1024,
+                state: IndexState::Ready,
+            })
+            .collect())
+    }
+
+    async fn drop_index(&self, graph: &str, index: IndexDefinition) -> Result<bool, GqlError> {
+        if graph != "default" {
+            return Err(graph_not_found(graph));
+        }
+        let mut indexes = self.indexes.write().await;
+        let before = indexes.len();
+        indexes.retain(|i| !same_index(i, &index));
+        Ok(indexes.len() < before)
+    }
+
+    // =========================================================================
+    // Search operations
+    // =========================================================================
+
+    async fn vector_search(&self, req: VectorSearchParams) -> Result<SearchResults, GqlError> {
+        let mut page = mock_search_page(
+            req.k,
+            req.offset,
+            req.page_token.as_deref(),
+            req.include_elements,
+            req.target,
+        )
+        .ok_or_else(invalid_page_token)?;
+        // Filters narrow the page rather than the corpus.
+        page.hits.retain(|hit| {
+            req.filters
+                .iter()
+                .all(|(name, value)| hit.properties.get(name) == Some(value))
+        });
+        Ok(page)
+    }
+
+    async fn text_search(&self, req: TextSearchParams) -> Result<SearchResults, GqlError> {
+        mock_search_page(
+            req.k,
+            req.offset,
+            req.page_token.as_deref(),
+            req.include_elements,
+            req.target,
+        )
+        .ok_or_else(invalid_page_token)
+    }
+
+    async fn hybrid_search(&self, req: HybridSearchParams) -> Result<SearchResults, GqlError> {
+        mock_search_page(
+            req.k,
+            req.offset,
+            req.page_token.as_deref(),
+            req.include_elements,
+            req.target,
+        )
+        .ok_or_else(invalid_page_token)
+    }
+}
+
+/// Names of the nodes in the mock search corpus, in descending score order.
+const MOCK_SEARCH_CORPUS: [&str; 5] = ["Alice", "Bob", "Carol", "Dave", "Eve"];
+
+/// The mock search corpus as a graph: a `Person` node per entry, and a
+/// `KNOWS` edge from each person to the next, as edge searches return.
+fn corpus_graph() -> Vec<GraphElement> {
+    let len = MOCK_SEARCH_CORPUS.len() as u64;
+    let nodes = (1..).zip(MOCK_SEARCH_CORPUS).map(|(id, name): (u64, _)| {
+        GraphElement::Node(
+            Node::new(id.to_be_bytes())
+                .with_label("Person")
+                .with_property("name", name),
+        )
+    });
+    let edges = (1..=len).map(|id| {
+        let since = 2020 + i64::try_from(id).unwrap_or(i64::MAX);
+        GraphElement::Edge(
+            Edge::directed(
+                id.to_be_bytes(),
+                id.to_be_bytes(),
+                (id % len + 1).to_be_bytes(),
+            )
+            .with_label("KNOWS")
+            .with_property("since", since),
+        )
+    });
+    nodes.chain(edges).collect()
+}
+
+/// Return one page of hits from the fixed mock search corpus.
+///
+/// Node searches return one `Person` hit per corpus entry. Edge searches
+/// return one `KNOWS` edge per entry, linking each person to the next
+/// (the last wraps around to the first).
+///
+/// Page tokens are the decimal offset of the next hit. Returns `None`
+/// if the page token cannot be parsed.
+fn mock_search_page(
+    k: u32,
+    offset: u32,
+    page_token: Option<&str>,
+    include_elements: bool,
+    target: SearchTarget,
+) -> Option<SearchResults> {
+    let start = match page_token {
+        Some(token) => token.parse::<usize>().ok()?,
+        None => offset as usize,
+    };
+    let start = start.min(MOCK_SEARCH_CORPUS.len());
+    let end = start
+        .saturating_add(k as usize)
+        .min(MOCK_SEARCH_CORPUS.len());
+
+    let hits = MOCK_SEARCH_CORPUS[start..end]
+        .iter()
+        .zip(start..)
+        .map(|(name, i)| {
+            let id = i as u64 + 1;
+            let score = 1.0 - f64::from(u32::try_from(i).unwrap_or(u32::MAX)) / 10.0;
+            match target {
+                SearchTarget::Nodes => SearchHit {
+                    node_id: id,
+                    score,
+                    properties: HashMap::from([("name".to_owned(), Value::from(*name))]),
+                    node: include_elements.then(|| {
+                        Node::new(id.to_be_bytes())
+                            .with_label("Person")
+                            .with_property("name", *name)
+                    }),
+                    edge: None,
+                    source_node_id: None,
+                    target_node_id: None,
+                },
+                SearchTarget::Edges => {
+                    let source = id;
+                    let target = id % MOCK_SEARCH_CORPUS.len() as u64 + 1;
+                    let since = 2020 + i64::try_from(id).unwrap_or(i64::MAX);
+                    SearchHit {
+                        node_id: id,
+                        score,
+                        properties: HashMap::from([("since".to_owned(), Value::from(since))]),
+                        node: None,
+                        edge: include_elements.then(|| {
+                            Edge::directed(
+                                id.to_be_bytes(),
+                                source.to_be_bytes(),
+                                target.to_be_bytes(),
+                            )
+                            .with_label("KNOWS")
+                            .with_property("since", since)
+                        }),
+                        source_node_id: Some(source),
+                        target_node_id: Some(target),
+                    }
+                }
+            }
+        })
+        .collect();
+
+    Some(SearchResults {
+        hits,
+        next_page_token: (end < MOCK_SEARCH_CORPUS.len()).then(|| end.to_string()),
+        total_hits_estimate: Some(MOCK_SEARCH_CORPUS.len() as u64),
+    })
+}
+
+fn invalid_page_token() -> GqlError {
+    GqlError::Grpc(tonic::Status::invalid_argument("invalid page token"))
+}
+
+fn graph_not_found(graph: &str) -> GqlError {
+    GqlError::Session(format!("graph '{graph}' not found"))
+}
+
+/// Whether two definitions name the same index, ignoring vector options.
+fn same_index(a: &IndexDefinition, b: &IndexDefinition) -> bool {
+    match (a, b) {
+        (IndexDefinition::Property { property: a }, IndexDefinition::Property { property: b }) => {
+            a == b
+        }
+        (
+            IndexDefinition::Vector {
+                label: la,
+                property: pa,
+                ..
+            },
+            IndexDefinition::Vector {
+                label: lb,
+                property: pb,
+                ..
+            },
+        )
+        | (
+            IndexDefinition::Text {
+                label: la,
+                property: pa,
+            },
+            IndexDefinition::Text {
+                label: lb,
+                property: pb,
+            },
+        ) => la == lb && pa == pb,
+        _ => false,
+    }
+}
+
+/// Build a non-nullable column descriptor of the given type.
+fn column(name: &str, gql_type: proto::GqlType) -> proto::ColumnDescriptor {
+    proto::ColumnDescriptor {
+        name: name.to_owned(),
+        r#type: Some(proto::TypeDescriptor {
+            r#type: gql_type.into(),
+            nullable: false,
+            element_type: None,
+            fields: Vec::new(),
+            precision: None,
+            scale: None,
+            min_length: None,
+            max_length: None,
+            max_cardinality: None,
+            is_group: false,
+            is_open: false,
+            duration_qualifier: proto::DurationQualifier::DurationUnspecified.into(),
+            component_types: Vec::new(),
+        }),
+    }
+}
+
+/// Mock result stream that yields pre-configured frames.
+struct MockResultStream {
+    frames: Vec<ResultFrame>,
+    index: usize,
+    /// Error yielded once all frames have been delivered.
+    error: Option<GqlError>,
+    /// Delays applied before successive row batches.
+    batch_delays: VecDeque<Duration>,
+    /// Pending delay before the next frame.
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl MockResultStream {
+    /// Keep the header and at most `batches` row batches, then yield `err`
+    /// in place of the remaining frames.
+    fn fail_after_batches(mut self, batches: usize, err: GqlError) -> Self {
+        let mut kept = 0;
+        self.frames.retain(|frame| match frame {
+            ResultFrame::Header(_) | ResultFrame::Warning(_) => true,
+            ResultFrame::Batch(_) => {
+                kept += 1;
+                kept <= batches
+            }
+            ResultFrame::Summary(_) => false,
+        });
+        self.error = Some(err);
+        self
+    }
+
+    /// Send `warnings` right after the header.
+    fn with_warnings(mut self, warnings: Vec<proto::GqlStatus>) -> Self {
+        let at = usize::from(matches!(self.frames.first(), Some(ResultFrame::Header(_))));
+        self.frames
+            .splice(at..at, warnings.into_iter().map(ResultFrame::Warning));
+        self
+    }
+
+    /// Report `counters` in the summary.
+    fn with_update_counters(mut self, counters: proto::UpdateCounters) -> Self {
+        for frame in &mut self.frames {
+            if let ResultFrame::Summary(summary) = frame {
+                summary.update_counters = Some(counters);
+            }
+        }
+        self
+    }
+
+    fn binding_table() -> Self {
+        Self::rows(
+            vec![
+                column("name", proto::GqlType::TypeString),
+                column("age", proto::GqlType::TypeInt64),
+            ],
+            vec![
+                vec![Value::from("Alice"), Value::from(30_i64)],
+                vec![Value::from("Bob"), Value::from(25_i64)],
+            ],
+        )
+    }
+
+    fn echo(statement: &str, parameters: &HashMap<String, Value>) -> Self {
+        let mut names: Vec<&String> = parameters.keys().collect();
+        names.sort_unstable();
+        let rows = if names.is_empty() {
+            vec![vec![Value::from(statement), Value::Null, Value::Null]]
+        } else {
+            names
+                .into_iter()
+                .map(|name| {
+                    vec![
+                        Value::from(statement),
+                        Value::from(name.as_str()),
+                        parameters[name].clone(),
+                    ]
+                })
+                .collect()
+        };
+        Self::rows(
+            vec![
+                column("statement", proto::GqlType::TypeString),
+                column("parameter", proto::GqlType::TypeString),
+                column("value", proto::GqlType::TypeUnknown),
+            ],
+            rows,
+        )
+    }
+
+    fn rows(columns: Vec<proto::ColumnDescriptor>, rows: Vec<Vec<Value>>) -> Self {
+        let header = ResultFrame::Header(proto::ResultHeader {
+            result_type: proto::ResultType::BindingTable.into(),
+            columns,
+            ordered: false,
+        });
+
+        let row_count = i64::try_from(rows.len()).unwrap_or(i64::MAX);
+        let batch = ResultFrame::Batch(proto::RowBatch {
+            rows: rows
+                .into_iter()
+                .map(|row| proto::Row {
+                    values: row.into_iter().map(proto::Value::from).collect(),
+                })
+                .collect(),
+            checksum: None,
+            dictionary: Vec::new(),
+            columns: None,
+        });
+
+        let summary = ResultFrame::Summary(proto::ResultSummary {
+            status: Some(crate::status::success()),
+            warnings: Vec::new(),
+            rows_affected: row_count,
+            counters: HashMap::new(),
+            update_counters: None,
+            timings: None,
+        });
+
+        Self {
+            frames: vec![header, batch, summary],
+            index: 0,
+            error: None,
+            batch_delays: VecDeque::new(),
+            sleep: None,
+        }
+    }
+
+    fn dml(rows_affected: i64) -> Self {
+        let header = ResultFrame::Header(proto::ResultHeader {
+            result_type: proto::ResultType::Omitted.into(),
+            columns: Vec::new(),
+            ordered: false,
+        });
+
+        let summary = ResultFrame::Summary(proto::ResultSummary {
+            status: Some(crate::status::success()),
+            warnings: Vec::new(),
+            rows_affected,
+            counters: HashMap::new(),
+            update_counters: None,
+            timings: None,
+        });
+
+        Self {
+            frames: vec![header, summary],
+            index: 0,
+            error: None,
+            batch_delays: VecDeque::new(),
+            sleep: None,
+        }
+    }
+
+    fn ddl() -> Self {
+        let header = ResultFrame::Header(proto::ResultHeader {
+            result_type: proto::ResultType::Omitted.into(),
+            columns: Vec::new(),
+            ordered: false,
+        });
+
+        let summary = ResultFrame::Summary(proto::ResultSummary {
+            status: Some(crate::status::omitted()),
+            warnings: Vec::new(),
+            rows_affected: 0,
+            counters: HashMap::new(),
+            update_counters: None,
+            timings: None,
+        });
+
+        Self {
+            frames: vec![header, summary],
+            index: 0,
+            error: None,
+            batch_delays: VecDeque::new(),
+            sleep: None,
+        }
+    }
+}
+
+impl ResultStream for MockResultStream {
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ResultFrame, GqlError>>> {
+        if self.index < self.frames.len() {
+            if self.sleep.is_none() && matches!(self.frames[self.index], ResultFrame::Batch(_)) {
+                self.sleep = self
+                    .batch_delays
+                    .pop_front()
+                    .map(|delay| Box::pin(tokio::time::sleep(delay)));
+            }
+            if let Some(sleep) = self.sleep.as_mut() {
+                if sleep.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                self.sleep = None;
+            }
+            let frame = self.frames[self.index].clone();
+            self.index += 1;
+            Poll::Ready(Some(Ok(frame)))
+        } else {
+            Poll::Ready(self.error.take().map(Err))
+        }
+    }
+}
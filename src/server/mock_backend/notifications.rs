@@ -0,0 +1,57 @@
+//! Change events published through `MockBackend`.
+//!
+//! Sessions subscribe through the backend's `subscribe`; tests publish
+//! events with [`MockBackend::publish`] and they reach every session
+//! subscribed to the event's topic.
+
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+
+use tokio::sync::mpsc;
+
+use crate::server::backend::{ChangeEvent, NotificationSource};
+
+use super::MockBackend;
+
+/// The open subscriptions, by topic.
+#[derive(Debug, Default)]
+pub(super) struct Subscribers {
+    inner: Mutex<Vec<(String, mpsc::UnboundedSender<ChangeEvent>)>>,
+}
+
+impl Subscribers {
+    fn lock(&self) -> MutexGuard<'_, Vec<(String, mpsc::UnboundedSender<ChangeEvent>)>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Open a subscription to `topic`.
+    pub(super) fn subscribe(&self, topic: &str) -> MockNotifications {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.lock().push((topic.to_owned(), tx));
+        MockNotifications(rx)
+    }
+}
+
+/// The events of one subscription.
+pub(super) struct MockNotifications(mpsc::UnboundedReceiver<ChangeEvent>);
+
+impl NotificationSource for MockNotifications {
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ChangeEvent>> {
+        self.get_mut().0.poll_recv(cx)
+    }
+}
+
+impl MockBackend {
+    /// Send `event` to every open subscription to its topic, and return
+    /// how many it reached. Subscriptions that have ended are dropped.
+    pub fn publish(&self, event: &ChangeEvent) -> usize {
+        let mut subscribers = self.subscribers.lock();
+        subscribers.retain(|(_, tx)| !tx.is_closed());
+        subscribers
+            .iter()
+            .filter(|(topic, _)| *topic == event.topic)
+            .filter(|(_, tx)| tx.send(event.clone()).is_ok())
+            .count()
+    }
+}
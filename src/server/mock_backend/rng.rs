@@ -0,0 +1,32 @@
+//! Small seeded generator for reproducible mock behavior.
+
+/// `SplitMix64` pseudo-random generator.
+///
+/// Not cryptographically secure; used so that randomized mock behavior
+/// (session drops, latency jitter) is reproducible from a seed.
+#[derive(Debug, Clone)]
+pub(super) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(super) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Next value in `[0, 1)`.
+    pub(super) fn next_unit(&mut self) -> f64 {
+        // Top 53 bits give a uniformly distributed f64 mantissa.
+        #[allow(clippy::cast_precision_loss)]
+        let unit = (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        unit
+    }
+}
@@ -0,0 +1,78 @@
+//! Savepoints marked in `MockBackend` transactions.
+//!
+//! The mock keeps no data to undo, so it only tracks the savepoints of
+//! each transaction, failing releases and rollbacks to names that are
+//! not marked the way a real backend would.
+
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::error::GqlError;
+use crate::server::backend::TransactionHandle;
+use crate::status;
+
+/// The savepoints of each open transaction, oldest first.
+#[derive(Debug, Default)]
+pub(super) struct Savepoints {
+    inner: Mutex<HashMap<TransactionHandle, Vec<String>>>,
+}
+
+impl Savepoints {
+    fn lock(&self) -> MutexGuard<'_, HashMap<TransactionHandle, Vec<String>>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Mark `name` in `transaction`.
+    pub(super) fn mark(&self, transaction: &TransactionHandle, name: &str) {
+        self.lock()
+            .entry(transaction.clone())
+            .or_default()
+            .push(name.to_owned());
+    }
+
+    /// Forget `name` and the savepoints marked after it.
+    pub(super) fn release(
+        &self,
+        transaction: &TransactionHandle,
+        name: &str,
+    ) -> Result<(), GqlError> {
+        let mut savepoints = self.lock();
+        let marked = savepoints.entry(transaction.clone()).or_default();
+        let position = find(marked, name)?;
+        marked.truncate(position);
+        Ok(())
+    }
+
+    /// Forget the savepoints marked after `name`, keeping `name`.
+    pub(super) fn rollback_to(
+        &self,
+        transaction: &TransactionHandle,
+        name: &str,
+    ) -> Result<(), GqlError> {
+        let mut savepoints = self.lock();
+        let marked = savepoints.entry(transaction.clone()).or_default();
+        let position = find(marked, name)?;
+        marked.truncate(position + 1);
+        Ok(())
+    }
+
+    /// Forget every savepoint of a transaction that has ended.
+    pub(super) fn end(&self, transaction: &TransactionHandle) {
+        self.lock().remove(transaction);
+    }
+}
+
+/// The position of the latest savepoint called `name`.
+fn find(marked: &[String], name: &str) -> Result<usize, GqlError> {
+    marked
+        .iter()
+        .rposition(|marked| marked == name)
+        .ok_or_else(|| {
+            GqlError::status(
+                status::INVALID_REFERENCE,
+                format!("savepoint {name} does not exist"),
+            )
+        })
+}
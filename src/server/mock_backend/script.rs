@@ -0,0 +1,433 @@
+//! Scripted responses and call recording for `MockBackend`.
+//!
+//! Tests register responses for specific statements with
+//! [`MockBackend::on_statement`] and inspect what the server forwarded
+//! to the backend with [`MockBackend::calls`] and the `assert_*` helpers.
+//! Statements without a registered response fall back to the built-in
+//! prefix heuristics.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::server::backend::{
+    ExecutionContext, PreparedHandle, SessionHandle, SessionProperty, TransactionHandle,
+};
+use crate::server::bulk_load::BulkChunk;
+use crate::types::Value;
+
+use super::{MockBackend, MockResultStream, column};
+
+/// A backend call recorded by `MockBackend`.
+#[derive(Debug, Clone)]
+pub enum MockCall {
+    /// A session was created.
+    CreateSession {
+        /// The handle issued for the new session.
+        session: SessionHandle,
+        /// Client metadata from the handshake.
+        client_info: HashMap<String, String>,
+        /// Database the handshake asked for.
+        database: Option<String>,
+    },
+    /// A session was closed.
+    CloseSession {
+        /// The closed session.
+        session: SessionHandle,
+    },
+    /// A session property was configured.
+    Configure {
+        /// The configured session.
+        session: SessionHandle,
+        /// The property that was set.
+        property: SessionProperty,
+    },
+    /// A statement was executed.
+    Execute {
+        /// The executing session.
+        session: SessionHandle,
+        /// Statement text as received.
+        statement: String,
+        /// Statement parameters.
+        parameters: HashMap<String, Value>,
+        /// The enclosing transaction, if any.
+        transaction: Option<TransactionHandle>,
+        /// The trace context and query ID the server passed along.
+        context: ExecutionContext,
+    },
+    /// A transaction was started.
+    BeginTransaction {
+        /// The session starting the transaction.
+        session: SessionHandle,
+        /// The handle issued for the new transaction.
+        transaction: TransactionHandle,
+        /// The requested isolation level.
+        isolation_level: proto::IsolationLevel,
+        /// The requested timeout, if any.
+        timeout: Option<Duration>,
+    },
+    /// A transaction was committed.
+    Commit {
+        /// The committing session.
+        session: SessionHandle,
+        /// The committed transaction.
+        transaction: TransactionHandle,
+    },
+    /// A transaction was rolled back.
+    Rollback {
+        /// The session rolling back.
+        session: SessionHandle,
+        /// The rolled-back transaction.
+        transaction: TransactionHandle,
+    },
+    /// A transaction was prepared for a two-phase commit.
+    PrepareCommit {
+        /// The session preparing the transaction.
+        session: SessionHandle,
+        /// The prepared transaction.
+        transaction: TransactionHandle,
+    },
+    /// A prepared transaction was committed.
+    CommitPrepared {
+        /// The session finishing the transaction.
+        session: SessionHandle,
+        /// The committed transaction.
+        transaction: TransactionHandle,
+    },
+    /// A prepared transaction was rolled back.
+    RollbackPrepared {
+        /// The session finishing the transaction.
+        session: SessionHandle,
+        /// The rolled-back transaction.
+        transaction: TransactionHandle,
+    },
+    /// A savepoint was marked.
+    Savepoint {
+        /// The session marking the savepoint.
+        session: SessionHandle,
+        /// The enclosing transaction.
+        transaction: TransactionHandle,
+        /// The savepoint's name.
+        name: String,
+    },
+    /// A savepoint was released.
+    ReleaseSavepoint {
+        /// The session releasing the savepoint.
+        session: SessionHandle,
+        /// The enclosing transaction.
+        transaction: TransactionHandle,
+        /// The savepoint's name.
+        name: String,
+    },
+    /// A transaction was rolled back to a savepoint.
+    RollbackToSavepoint {
+        /// The session rolling back.
+        session: SessionHandle,
+        /// The enclosing transaction.
+        transaction: TransactionHandle,
+        /// The savepoint's name.
+        name: String,
+    },
+    /// A statement was prepared. Its executions are recorded as
+    /// [`Execute`](Self::Execute) calls.
+    Prepare {
+        /// The preparing session.
+        session: SessionHandle,
+        /// The handle issued for the statement.
+        prepared: PreparedHandle,
+        /// Statement text as received.
+        statement: String,
+    },
+    /// A prepared statement was closed.
+    ClosePrepared {
+        /// The session closing the statement.
+        session: SessionHandle,
+        /// The closed statement.
+        prepared: PreparedHandle,
+    },
+    /// A bulk load finished reading its chunks.
+    BulkLoad {
+        /// The loading session.
+        session: SessionHandle,
+        /// The chunks received, in order.
+        chunks: Vec<BulkChunk>,
+    },
+    /// A session subscribed to a notification topic.
+    Subscribe {
+        /// The subscribing session.
+        session: SessionHandle,
+        /// The topic subscribed to.
+        topic: String,
+    },
+    /// A statement was explained.
+    Explain {
+        /// The explaining session.
+        session: SessionHandle,
+        /// Statement text as received.
+        statement: String,
+    },
+}
+
+/// How a registered statement is matched.
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// Trimmed statement text equals the pattern.
+    Exact(String),
+    /// Trimmed statement text starts with the pattern (case-insensitive).
+    Prefix(String),
+}
+
+impl Matcher {
+    fn matches(&self, statement: &str) -> bool {
+        let statement = statement.trim();
+        match self {
+            Self::Exact(pattern) => statement == pattern,
+            Self::Prefix(prefix) => statement
+                .get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix)),
+        }
+    }
+}
+
+/// A scripted response to a statement.
+#[derive(Debug, Clone)]
+enum Response {
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<Vec<Value>>,
+    },
+    RowsAffected(i64),
+    Changes(proto::UpdateCounters),
+    Empty,
+    Error {
+        code: String,
+        message: String,
+    },
+}
+
+/// Registered responses and recorded calls.
+#[derive(Debug, Default)]
+pub(super) struct Script {
+    stubs: Mutex<Vec<(Matcher, Response, Vec<proto::GqlStatus>)>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl Script {
+    pub(super) fn record(&self, call: MockCall) {
+        lock(&self.calls).push(call);
+    }
+
+    /// Produce the scripted result for a statement, if one is registered.
+    ///
+    /// Later registrations take precedence over earlier ones.
+    pub(super) fn respond(&self, statement: &str) -> Option<Result<MockResultStream, GqlError>> {
+        let stubs = lock(&self.stubs);
+        let (_, response, warnings) = stubs.iter().rev().find(|(m, ..)| m.matches(statement))?;
+        let stream = match response {
+            Response::Rows { columns, rows } => Ok(MockResultStream::rows(
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| {
+                        let first = rows.first().and_then(|row| row.get(i));
+                        column(name, first.map_or(proto::GqlType::TypeUnknown, gql_type_of))
+                    })
+                    .collect(),
+                rows.clone(),
+            )),
+            Response::RowsAffected(n) => Ok(MockResultStream::dml(*n)),
+            Response::Changes(counters) => {
+                Ok(MockResultStream::dml(0).with_update_counters(*counters))
+            }
+            Response::Empty => Ok(MockResultStream::ddl()),
+            Response::Error { code, message } => Err(GqlError::status(code, message.clone())),
+        };
+        Some(stream.map(|stream| stream.with_warnings(warnings.clone())))
+    }
+
+    fn register(&self, stub: StatementStub<'_>, response: Response) {
+        lock(&self.stubs).push((stub.matcher, response, stub.warnings));
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Best-effort column type for a scripted value.
+fn gql_type_of(value: &Value) -> proto::GqlType {
+    match value {
+        Value::Boolean(_) => proto::GqlType::TypeBoolean,
+        Value::Integer(_) => proto::GqlType::TypeInt64,
+        Value::UnsignedInteger(_) => proto::GqlType::TypeUint64,
+        Value::Float(_) => proto::GqlType::TypeFloat64,
+        Value::String(_) => proto::GqlType::TypeString,
+        Value::Bytes(_) => proto::GqlType::TypeBytes,
+        Value::List(_) => proto::GqlType::TypeList,
+        Value::Record(_) => proto::GqlType::TypeRecord,
+        Value::Path(_) => proto::GqlType::TypePath,
+        _ => proto::GqlType::TypeUnknown,
+    }
+}
+
+/// Builder returned by [`MockBackend::on_statement`] to choose a response.
+#[must_use = "a statement stub does nothing until a response is chosen"]
+pub struct StatementStub<'a> {
+    script: &'a Script,
+    matcher: Matcher,
+    warnings: Vec<proto::GqlStatus>,
+}
+
+impl StatementStub<'_> {
+    /// Send `warning` mid-stream, right after the header. Has no effect
+    /// on an error response.
+    pub fn with_warning(mut self, warning: proto::GqlStatus) -> Self {
+        self.warnings.push(warning);
+        self
+    }
+
+    /// Respond with a binding table of the given columns and rows.
+    pub fn respond_rows<I, S>(self, columns: I, rows: Vec<Vec<Value>>)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let columns = columns.into_iter().map(Into::into).collect();
+        self.script.register(self, Response::Rows { columns, rows });
+    }
+
+    /// Respond with an omitted result reporting `rows_affected` changes.
+    pub fn respond_rows_affected(self, rows_affected: i64) {
+        self.script
+            .register(self, Response::RowsAffected(rows_affected));
+    }
+
+    /// Respond with an omitted result reporting `counters` changes to the
+    /// graph.
+    pub fn respond_update_counters(self, counters: proto::UpdateCounters) {
+        self.script.register(self, Response::Changes(counters));
+    }
+
+    /// Respond with an omitted result and no changes, like DDL.
+    pub fn respond_empty(self) {
+        self.script.register(self, Response::Empty);
+    }
+
+    /// Fail execution with the given GQLSTATUS code and message.
+    pub fn respond_error(self, code: &str, message: impl Into<String>) {
+        self.script.register(
+            self,
+            Response::Error {
+                code: code.to_owned(),
+                message: message.into(),
+            },
+        );
+    }
+}
+
+impl MockBackend {
+    /// Script the response to a statement whose trimmed text equals
+    /// `statement`.
+    pub fn on_statement(&self, statement: &str) -> StatementStub<'_> {
+        StatementStub {
+            script: &self.script,
+            matcher: Matcher::Exact(statement.trim().to_owned()),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Script the response to any statement starting with `prefix`
+    /// (case-insensitive, ignoring leading whitespace).
+    pub fn on_statement_prefix(&self, prefix: &str) -> StatementStub<'_> {
+        StatementStub {
+            script: &self.script,
+            matcher: Matcher::Prefix(prefix.trim().to_owned()),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// All backend calls recorded so far, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<MockCall> {
+        lock(&self.script.calls).clone()
+    }
+
+    /// Forget all recorded calls.
+    pub fn clear_calls(&self) {
+        lock(&self.script.calls).clear();
+    }
+
+    /// The text of every executed statement, in order.
+    #[must_use]
+    pub fn executed_statements(&self) -> Vec<String> {
+        lock(&self.script.calls)
+            .iter()
+            .filter_map(|call| match call {
+                MockCall::Execute { statement, .. } => Some(statement.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Number of times a statement with the given trimmed text was executed.
+    #[must_use]
+    pub fn execute_count(&self, statement: &str) -> usize {
+        let statement = statement.trim();
+        self.executed_statements()
+            .iter()
+            .filter(|s| s.trim() == statement)
+            .count()
+    }
+
+    /// Assert that a statement with the given trimmed text was executed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the statement was never executed.
+    #[track_caller]
+    pub fn assert_executed(&self, statement: &str) {
+        assert!(
+            self.execute_count(statement) > 0,
+            "expected statement to be executed: {statement:?}\nexecuted: {:#?}",
+            self.executed_statements()
+        );
+    }
+
+    /// Assert that a statement with the given trimmed text was never executed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the statement was executed.
+    #[track_caller]
+    pub fn assert_not_executed(&self, statement: &str) {
+        assert!(
+            self.execute_count(statement) == 0,
+            "expected statement not to be executed: {statement:?}"
+        );
+    }
+
+    /// Assert the number of commits and rollbacks recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either count differs.
+    #[track_caller]
+    pub fn assert_transactions(&self, commits: usize, rollbacks: usize) {
+        let calls = self.calls();
+        let actual_commits = calls
+            .iter()
+            .filter(|c| matches!(c, MockCall::Commit { .. }))
+            .count();
+        let actual_rollbacks = calls
+            .iter()
+            .filter(|c| matches!(c, MockCall::Rollback { .. }))
+            .count();
+        assert_eq!(
+            (actual_commits, actual_rollbacks),
+            (commits, rollbacks),
+            "expected (commits, rollbacks)"
+        );
+    }
+}
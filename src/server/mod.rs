@@ -1,32 +1,66 @@
 //! gRPC server implementation.
 //!
-//! Provides the `SessionService`, `GqlService`, and `CatalogService` implementations,
+//! Provides the `SessionService`, `GqlService`, `CatalogService`, and other service implementations,
 //! session/transaction state management, and the pluggable `GqlBackend` trait.
 
 mod admin_service;
 mod auth;
 mod backend;
+mod backup;
 pub mod builder;
+mod bulk_load;
 mod catalog_service;
+mod client_cert;
+mod deadlines;
+mod drain;
 mod gql_service;
+mod import;
+mod interchange;
+mod jobs;
 pub mod mock_backend;
+mod notification_service;
+mod query_log;
+mod query_stats;
+mod rate_limit;
+mod result_cache;
 mod search_service;
 mod session_manager;
 mod session_service;
+mod statement_limits;
 mod transaction_manager;
 
-pub use admin_service::AdminServiceImpl;
-pub use auth::AuthValidator;
+pub use admin_service::{ADMIN_FEATURE, AdminServiceImpl};
+pub use auth::{AuthValidator, Authorizer, Operation, Principal};
 pub use backend::{
-    AdminStats, AdminValidationResult, AdminWalStatus, CreateGraphConfig, GqlBackend, GraphInfo,
-    GraphTypeInfo, GraphTypeSpec, HybridSearchParams, IndexDefinition, ResetTarget, ResultFrame,
-    ResultStream, SchemaInfo, SearchHit, SessionConfig, SessionHandle, SessionProperty,
-    TextSearchParams, TransactionHandle, ValidationDiagnostic, VectorSearchParams,
+    AdminStats, AdminValidationResult, AdminWalStatus, BackupStream, ChangeEvent, CloneGraphConfig,
+    CreateDatabaseConfig, CreateGraphConfig, DatabaseInfo, ElementStream, ExecutionContext,
+    GqlBackend, GraphConfig, GraphElement, GraphInfo, GraphTypeInfo, GraphTypeSpec, HealthStatus,
+    HybridSearchParams, ImportReport, IndexDefinition, IndexInfo, IndexState, LabelCount,
+    LabelStats, MemoryBreakdown, NotificationSource, PreparedHandle, PropertyCardinality,
+    QueryPlan, ResetTarget, ResultFrame, ResultStream, SchemaInfo, SearchHit, SearchResults,
+    SearchTarget, SessionConfig, SessionDefaults, SessionHandle, SessionProperty, TextSearchParams,
+    TransactionConfig, TransactionHandle, ValidationDiagnostic, VectorSearchParams,
 };
+pub use backup::{BackupChunks, RestoreChunks};
 pub use builder::GqlServer;
+pub use bulk_load::{BulkChunk, BulkChunks, BulkLoadStats};
 pub use catalog_service::CatalogServiceImpl;
+pub use client_cert::{CLIENT_CERT_SUBJECT, ClientCertificate};
+pub use deadlines::DefaultDeadlines;
+pub use drain::DrainHandle;
 pub use gql_service::GqlServiceImpl;
-pub use search_service::SearchServiceImpl;
-pub use session_manager::SessionManager;
+pub use import::ImportElements;
+pub use interchange::{ExportChunks, GraphFormat};
+pub use jobs::{IndexBuildStatus, Job, JobManager, JobOutput, JobProgress, JobState, JobStatus};
+pub use notification_service::NotificationServiceImpl;
+pub use query_log::{
+    JsonLinesQueryLogger, QueryLogEvent, QueryLogOptions, QueryLogger, TracingQueryLogger,
+};
+pub use query_stats::{QueryStatistics, QueryStats, normalize_statement};
+pub use rate_limit::{RateLimitKey, RateLimits};
+pub use result_cache::{ResultCache, is_read_only_statement};
+pub use search_service::{SEARCH_FEATURE, SearchServiceImpl};
+pub use session_manager::{SessionInfo, SessionManager, SessionSnapshot};
 pub use session_service::SessionServiceImpl;
+pub use statement_limits::{ResultLimits, StatementLimits};
 pub use transaction_manager::TransactionManager;
@@ -0,0 +1,212 @@
+//! `NotificationService` gRPC implementation.
+//!
+//! Forwards the change events a backend publishes to the sessions
+//! subscribed to their topics. Errors ending a stream are returned as
+//! gRPC status codes; a topic that cannot be subscribed to is reported
+//! in the stream.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::task::AbortHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::Instrument;
+
+use crate::error::GqlError;
+use crate::proto;
+use crate::proto::notification_service_server::NotificationService;
+use crate::status as gql_status;
+
+use super::SessionManager;
+use super::auth::{Authorization, Authorizer, Operation};
+use super::backend::{GqlBackend, SessionHandle};
+
+/// Notifications queued for a subscriber before the server waits.
+const NOTIFICATION_BUFFER: usize = 64;
+
+type NotificationSender = tokio::sync::mpsc::Sender<Result<proto::Notification, Status>>;
+
+/// Implementation of the `NotificationService` gRPC service.
+pub struct NotificationServiceImpl<B: GqlBackend> {
+    backend: Arc<B>,
+    sessions: SessionManager,
+    authorization: Option<Authorization>,
+    shutdown: CancellationToken,
+}
+
+impl<B: GqlBackend> NotificationServiceImpl<B> {
+    /// Create a new notification service.
+    pub fn new(backend: Arc<B>, sessions: SessionManager) -> Self {
+        Self {
+            backend,
+            sessions,
+            authorization: None,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Check each subscription with `authorizer` as a read by the
+    /// principal of the subscribing session.
+    #[must_use]
+    pub fn with_authorizer(mut self, authorizer: Arc<dyn Authorizer>) -> Self {
+        self.authorization = Some(Authorization::new(authorizer, self.sessions.clone()));
+        self
+    }
+
+    /// End subscription streams once `token` is cancelled, so they do not
+    /// hold up a graceful shutdown.
+    #[must_use]
+    pub fn with_shutdown_notice(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+}
+
+/// The topics one `Subscribe` stream is subscribed to.
+struct Subscriptions<B: GqlBackend> {
+    backend: Arc<B>,
+    session: SessionHandle,
+    authorization: Option<Authorization>,
+    tx: NotificationSender,
+    topics: HashMap<String, AbortHandle>,
+}
+
+impl<B: GqlBackend> Subscriptions<B> {
+    /// Apply a subscribe or unsubscribe request.
+    async fn apply(&mut self, action: proto::subscribe_request::Action) {
+        match action {
+            proto::subscribe_request::Action::Subscribe(topic) => {
+                if !self.topics.contains_key(&topic) {
+                    self.subscribe(topic).await;
+                }
+            }
+            proto::subscribe_request::Action::Unsubscribe(topic) => {
+                if let Some(forwarding) = self.topics.remove(&topic) {
+                    tracing::debug!(topic, "unsubscribed");
+                    forwarding.abort();
+                }
+            }
+        }
+    }
+
+    /// Subscribe to `topic` and forward its events, or report why not.
+    async fn subscribe(&mut self, topic: String) {
+        let source = match self.authorize().await {
+            Ok(()) => self.backend.subscribe(&self.session, &topic).await,
+            Err(err) => Err(err),
+        };
+        let mut source = match source {
+            Ok(source) => source,
+            Err(err) => {
+                tracing::warn!(topic, error = %err, "subscribe failed");
+                let status = match err.gql_status() {
+                    Some(s) => s.clone(),
+                    None => gql_status::error(gql_status::DATA_EXCEPTION, err.to_string()),
+                };
+                let _ = self
+                    .tx
+                    .send(Ok(proto::Notification {
+                        topic,
+                        status: Some(status),
+                        ..proto::Notification::default()
+                    }))
+                    .await;
+                return;
+            }
+        };
+        tracing::debug!(topic, "subscribed");
+        let tx = self.tx.clone();
+        let forwarding = tokio::spawn(
+            async move {
+                while let Some(event) =
+                    std::future::poll_fn(|cx| source.as_mut().poll_next(cx)).await
+                {
+                    if tx.send(Ok(event.into())).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+        self.topics.insert(topic, forwarding.abort_handle());
+    }
+
+    async fn authorize(&self) -> Result<(), GqlError> {
+        match &self.authorization {
+            Some(authorization) => authorization.check(&self.session.0, Operation::Read).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl<B: GqlBackend> Drop for Subscriptions<B> {
+    fn drop(&mut self) {
+        for forwarding in self.topics.values() {
+            forwarding.abort();
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<B: GqlBackend> NotificationService for NotificationServiceImpl<B> {
+    type SubscribeStream = ReceiverStream<Result<proto::Notification, Status>>;
+
+    #[tracing::instrument(skip(self, request), fields(session_id))]
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<proto::SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let mut requests = request.into_inner();
+        let Some(first) = requests.message().await? else {
+            return Err(Status::invalid_argument("subscribe sent no messages"));
+        };
+        let span = tracing::Span::current();
+        span.record("session_id", &first.session_id);
+        if !self.sessions.exists(&first.session_id).await {
+            return Err(Status::not_found(format!(
+                "session {} not found",
+                first.session_id
+            )));
+        }
+        self.sessions.touch(&first.session_id).await;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(NOTIFICATION_BUFFER);
+        let mut subscriptions = Subscriptions {
+            backend: Arc::clone(&self.backend),
+            session: SessionHandle(first.session_id),
+            authorization: self.authorization.clone(),
+            tx: tx.clone(),
+            topics: HashMap::new(),
+        };
+        let shutdown = self.shutdown.clone();
+        tokio::spawn(
+            async move {
+                if let Some(action) = first.action {
+                    subscriptions.apply(action).await;
+                }
+                // Keep delivering after the client stops sending requests,
+                // until it hangs up.
+                let mut reading = true;
+                loop {
+                    tokio::select! {
+                        request = requests.message(), if reading => match request {
+                            Ok(Some(request)) => {
+                                if let Some(action) = request.action {
+                                    subscriptions.apply(action).await;
+                                }
+                            }
+                            Ok(None) => reading = false,
+                            Err(_) => return,
+                        },
+                        () = tx.closed() => return,
+                        () = shutdown.cancelled() => return,
+                    }
+                }
+            }
+            .instrument(span),
+        );
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
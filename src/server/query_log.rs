@@ -0,0 +1,340 @@
+//! Server-side structured query log.
+//!
+//! Every statement the server executes produces one [`QueryLogEvent`]
+//! once its result ends, delivered to the [`QueryLogger`] configured with
+//! [`GqlServer::query_logger`](super::GqlServer::query_logger). Built-in
+//! loggers write JSON lines to a file or emit `tracing` events.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::fs::OpenOptions;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::types::Value;
+
+use super::query_stats::{normalize_statement, statement_hash};
+
+/// One executed statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryLogEvent {
+    /// When the statement was received.
+    pub timestamp: SystemTime,
+    /// Session the statement ran in.
+    pub session_id: String,
+    /// The statement text, or its normalized form when
+    /// [`QueryLogOptions::fingerprint_statements`] is set.
+    pub statement: String,
+    /// Hash of the normalized statement, matching
+    /// [`QueryStatistics::statement_hash`](super::QueryStatistics::statement_hash).
+    pub statement_hash: u64,
+    /// Parameter values, empty unless
+    /// [`QueryLogOptions::include_parameters`] is set.
+    pub parameters: HashMap<String, Value>,
+    /// Whether the statement had parameters whose values were left out.
+    pub parameters_redacted: bool,
+    /// Time from the request until the result ended.
+    pub duration: Duration,
+    /// GQLSTATUS code the result ended with, if it reached one.
+    pub status: Option<String>,
+    /// Rows returned.
+    pub rows: u64,
+}
+
+/// Receives a [`QueryLogEvent`] for every statement the server executes.
+///
+/// Called on the task streaming the result, so implementations should
+/// not block for long. Implemented for closures taking `&QueryLogEvent`.
+pub trait QueryLogger: Send + Sync + 'static {
+    /// Record an executed statement.
+    fn log(&self, event: &QueryLogEvent);
+}
+
+impl<F> QueryLogger for F
+where
+    F: Fn(&QueryLogEvent) + Send + Sync + 'static,
+{
+    fn log(&self, event: &QueryLogEvent) {
+        self(event);
+    }
+}
+
+/// What a query log records about each statement.
+///
+/// By default the full statement text is logged and parameter values are
+/// left out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryLogOptions {
+    /// Log the normalized statement, with literals replaced by `?`,
+    /// instead of its text.
+    pub fingerprint_statements: bool,
+    /// Log parameter values.
+    pub include_parameters: bool,
+}
+
+/// Logs each statement as a `tracing` event at info level under the
+/// `gwp::query_log` target.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingQueryLogger;
+
+impl QueryLogger for TracingQueryLogger {
+    fn log(&self, event: &QueryLogEvent) {
+        tracing::info!(
+            target: "gwp::query_log",
+            session_id = %event.session_id,
+            statement = %event.statement,
+            statement_hash = event.statement_hash,
+            parameters = ?event.parameters,
+            parameters_redacted = event.parameters_redacted,
+            duration_ms = event.duration.as_secs_f64() * 1000.0,
+            status = event.status.as_deref(),
+            rows = event.rows,
+            "query"
+        );
+    }
+}
+
+/// Writes each statement as one JSON object per line.
+///
+/// Parameter values are written as strings in their display form. Write
+/// errors are reported through `tracing` and otherwise ignored, so a full
+/// disk never fails a statement.
+pub struct JsonLinesQueryLogger {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLinesQueryLogger {
+    /// Append to the file at `path`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(LineWriter::new(file)))
+    }
+
+    /// Write to `out`, such as a buffer in tests or a pipe.
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            out: Mutex::new(Box::new(out)),
+        }
+    }
+}
+
+impl QueryLogger for JsonLinesQueryLogger {
+    fn log(&self, event: &QueryLogEvent) {
+        let mut line = event_json(event);
+        line.push('\n');
+        let mut out = self.out.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(err) = out.write_all(line.as_bytes()) {
+            tracing::warn!(error = %err, "failed to write query log");
+        }
+    }
+}
+
+impl fmt::Debug for JsonLinesQueryLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLinesQueryLogger")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Render `event` as a single-line JSON object.
+fn event_json(event: &QueryLogEvent) -> String {
+    let timestamp = event
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut json = format!(
+        "{{\"timestamp_ms\":{},\"session_id\":",
+        timestamp.as_millis()
+    );
+    push_json_string(&mut json, &event.session_id);
+    json.push_str(",\"statement\":");
+    push_json_string(&mut json, &event.statement);
+    let _ = write!(json, ",\"statement_hash\":{}", event.statement_hash);
+    json.push_str(",\"parameters\":{");
+    let mut names: Vec<&String> = event.parameters.keys().collect();
+    names.sort();
+    for (i, name) in names.into_iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        push_json_string(&mut json, name);
+        json.push(':');
+        push_json_string(&mut json, &event.parameters[name].to_string());
+    }
+    let _ = write!(
+        json,
+        "}},\"parameters_redacted\":{},\"duration_us\":{},\"status\":",
+        event.parameters_redacted,
+        event.duration.as_micros()
+    );
+    match &event.status {
+        Some(status) => push_json_string(&mut json, status),
+        None => json.push_str("null"),
+    }
+    let _ = write!(json, ",\"rows\":{}}}", event.rows);
+    json
+}
+
+/// Append `s` to `json` as a quoted, escaped JSON string.
+fn push_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// A logger with the options it was configured with.
+#[derive(Clone)]
+pub(crate) struct QueryLog {
+    logger: Arc<dyn QueryLogger>,
+    options: QueryLogOptions,
+}
+
+impl QueryLog {
+    pub(crate) fn new(logger: Arc<dyn QueryLogger>, options: QueryLogOptions) -> Self {
+        Self { logger, options }
+    }
+
+    /// Start an event for `statement`, completed by
+    /// [`PendingEvent::emit`].
+    pub(crate) fn start(
+        &self,
+        session_id: &str,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+    ) -> PendingEvent {
+        let normalized = normalize_statement(statement);
+        let statement_hash = statement_hash(&normalized);
+        let include = self.options.include_parameters;
+        PendingEvent {
+            logger: Arc::clone(&self.logger),
+            event: QueryLogEvent {
+                timestamp: SystemTime::now(),
+                session_id: session_id.to_owned(),
+                statement: if self.options.fingerprint_statements {
+                    normalized
+                } else {
+                    statement.to_owned()
+                },
+                statement_hash,
+                parameters: if include {
+                    parameters.clone()
+                } else {
+                    HashMap::new()
+                },
+                parameters_redacted: !include && !parameters.is_empty(),
+                duration: Duration::ZERO,
+                status: None,
+                rows: 0,
+            },
+        }
+    }
+}
+
+impl fmt::Debug for QueryLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryLog")
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An event waiting for its statement's result to end.
+pub(crate) struct PendingEvent {
+    logger: Arc<dyn QueryLogger>,
+    event: QueryLogEvent,
+}
+
+impl PendingEvent {
+    /// Complete the event and hand it to the logger.
+    pub(crate) fn emit(mut self, duration: Duration, status: Option<String>, rows: u64) {
+        self.event.duration = duration;
+        self.event.status = status;
+        self.event.rows = rows;
+        self.logger.log(&self.event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(options: QueryLogOptions) -> (QueryLog, Arc<Mutex<Vec<QueryLogEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let logger = move |event: &QueryLogEvent| sink.lock().unwrap().push(event.clone());
+        (QueryLog::new(Arc::new(logger), options), events)
+    }
+
+    #[test]
+    fn parameters_are_redacted_by_default() {
+        let (log, events) = capture(QueryLogOptions::default());
+        let parameters = HashMap::from([("id".to_owned(), Value::Integer(7))]);
+        log.start("s1", "MATCH (n) WHERE n.id = $id RETURN n", &parameters)
+            .emit(Duration::from_millis(3), Some("00000".to_owned()), 2);
+        log.start("s1", "RETURN 1", &HashMap::new())
+            .emit(Duration::ZERO, None, 0);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0].statement, "MATCH (n) WHERE n.id = $id RETURN n");
+        assert!(events[0].parameters.is_empty());
+        assert!(events[0].parameters_redacted);
+        assert_eq!(events[0].rows, 2);
+        assert!(!events[1].parameters_redacted);
+    }
+
+    #[test]
+    fn fingerprints_replace_literals() {
+        let (log, events) = capture(QueryLogOptions {
+            fingerprint_statements: true,
+            include_parameters: true,
+        });
+        let parameters = HashMap::from([("name".to_owned(), Value::from("Alix"))]);
+        log.start("s1", "MATCH (n {age: 30}) RETURN n", &parameters)
+            .emit(Duration::ZERO, None, 0);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0].statement, "MATCH (n {age: ?}) RETURN n");
+        assert_eq!(events[0].parameters["name"], Value::from("Alix"));
+        assert!(!events[0].parameters_redacted);
+    }
+
+    #[test]
+    fn json_lines_escape_strings() {
+        let event = QueryLogEvent {
+            timestamp: UNIX_EPOCH + Duration::from_millis(1500),
+            session_id: "s1".to_owned(),
+            statement: "RETURN \"a\"\n".to_owned(),
+            statement_hash: 42,
+            parameters: HashMap::from([("p".to_owned(), Value::Integer(1))]),
+            parameters_redacted: false,
+            duration: Duration::from_micros(250),
+            status: Some("00000".to_owned()),
+            rows: 1,
+        };
+        assert_eq!(
+            event_json(&event),
+            "{\"timestamp_ms\":1500,\"session_id\":\"s1\",\
+             \"statement\":\"RETURN \\\"a\\\"\\n\",\"statement_hash\":42,\
+             \"parameters\":{\"p\":\"1\"},\"parameters_redacted\":false,\
+             \"duration_us\":250,\"status\":\"00000\",\"rows\":1}"
+        );
+    }
+}
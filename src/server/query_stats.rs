@@ -0,0 +1,468 @@
+//! Server-side statement statistics.
+//!
+//! Executions are grouped by normalized statement text: literals are
+//! replaced with `?` and whitespace is collapsed, so statements that
+//! differ only in constants share an entry.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::proto;
+use crate::status;
+
+use super::backend::ResultFrame;
+use super::query_log::PendingEvent;
+
+/// Default maximum number of distinct statements tracked.
+const DEFAULT_MAX_STATEMENTS: usize = 5000;
+
+/// Number of recent latencies kept per statement for percentiles.
+const LATENCY_SAMPLES: usize = 1024;
+
+/// Aggregated statistics for one normalized statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryStatistics {
+    /// Stable hash of the normalized statement.
+    pub statement_hash: u64,
+    /// Normalized statement text.
+    pub statement: String,
+    /// Number of executions.
+    pub calls: u64,
+    /// Executions that ended with an exception status.
+    pub errors: u64,
+    /// Total rows returned.
+    pub rows: u64,
+    /// Total execution time, from request until the summary is produced.
+    pub total_time: Duration,
+    /// Mean execution time.
+    pub mean_time: Duration,
+    /// Median execution time over recent executions.
+    pub p50_time: Duration,
+    /// 95th percentile execution time over recent executions.
+    pub p95_time: Duration,
+    /// 99th percentile execution time over recent executions.
+    pub p99_time: Duration,
+}
+
+pub(super) fn micros(d: Duration) -> u64 {
+    u64::try_from(d.as_micros()).unwrap_or(u64::MAX)
+}
+
+impl From<QueryStatistics> for proto::QueryStatistics {
+    fn from(s: QueryStatistics) -> Self {
+        Self {
+            statement_hash: s.statement_hash,
+            statement: s.statement,
+            calls: s.calls,
+            errors: s.errors,
+            rows: s.rows,
+            total_time_micros: micros(s.total_time),
+            mean_time_micros: micros(s.mean_time),
+            p50_time_micros: micros(s.p50_time),
+            p95_time_micros: micros(s.p95_time),
+            p99_time_micros: micros(s.p99_time),
+        }
+    }
+}
+
+impl From<proto::QueryStatistics> for QueryStatistics {
+    fn from(p: proto::QueryStatistics) -> Self {
+        Self {
+            statement_hash: p.statement_hash,
+            statement: p.statement,
+            calls: p.calls,
+            errors: p.errors,
+            rows: p.rows,
+            total_time: Duration::from_micros(p.total_time_micros),
+            mean_time: Duration::from_micros(p.mean_time_micros),
+            p50_time: Duration::from_micros(p.p50_time_micros),
+            p95_time: Duration::from_micros(p.p95_time_micros),
+            p99_time: Duration::from_micros(p.p99_time_micros),
+        }
+    }
+}
+
+/// Running totals for one statement.
+#[derive(Debug)]
+struct Entry {
+    statement: String,
+    calls: u64,
+    errors: u64,
+    rows: u64,
+    total_time: Duration,
+    recent: VecDeque<Duration>,
+}
+
+impl Entry {
+    fn new(statement: String) -> Self {
+        Self {
+            statement,
+            calls: 0,
+            errors: 0,
+            rows: 0,
+            total_time: Duration::ZERO,
+            recent: VecDeque::new(),
+        }
+    }
+
+    fn to_statistics(&self, statement_hash: u64) -> QueryStatistics {
+        let mut sorted: Vec<Duration> = self.recent.iter().copied().collect();
+        sorted.sort_unstable();
+        let percentile = |p: usize| {
+            sorted
+                .get((sorted.len() * p).div_ceil(100).saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+        QueryStatistics {
+            statement_hash,
+            statement: self.statement.clone(),
+            calls: self.calls,
+            errors: self.errors,
+            rows: self.rows,
+            total_time: self.total_time,
+            mean_time: self
+                .total_time
+                .checked_div(u32::try_from(self.calls).unwrap_or(u32::MAX))
+                .unwrap_or_default(),
+            p50_time: percentile(50),
+            p95_time: percentile(95),
+            p99_time: percentile(99),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    entries: HashMap<u64, Entry>,
+    max_statements: usize,
+}
+
+/// Aggregates execution statistics for every statement the server runs.
+///
+/// Cloning is cheap; clones share the same statistics.
+#[derive(Debug, Clone)]
+pub struct QueryStats {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl QueryStats {
+    /// Create an empty aggregator tracking up to 5000 distinct statements.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_MAX_STATEMENTS)
+    }
+
+    /// Create an empty aggregator tracking up to `max_statements` distinct
+    /// statements. When full, the least-called statement is evicted to
+    /// make room for a new one.
+    #[must_use]
+    pub fn with_capacity(max_statements: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                entries: HashMap::new(),
+                max_statements: max_statements.max(1),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start timing an execution of `statement`. The execution is recorded
+    /// when its summary is observed, or when the recorder is dropped if the
+    /// stream ends early.
+    #[cfg(test)]
+    pub(crate) fn start(&self, statement: &str) -> QueryRecorder {
+        QueryRecorder::new(Some(self), statement, None)
+    }
+
+    fn record(&self, statement: String, elapsed: Duration, rows: u64, failed: bool) {
+        let hash = statement_hash(&statement);
+        let mut inner = self.lock();
+        if !inner.entries.contains_key(&hash) && inner.entries.len() >= inner.max_statements {
+            let evict = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.calls)
+                .map(|(hash, _)| *hash);
+            if let Some(evict) = evict {
+                inner.entries.remove(&evict);
+            }
+        }
+        let entry = inner
+            .entries
+            .entry(hash)
+            .or_insert_with(|| Entry::new(statement));
+        entry.calls += 1;
+        entry.errors += u64::from(failed);
+        entry.rows += rows;
+        entry.total_time += elapsed;
+        if entry.recent.len() == LATENCY_SAMPLES {
+            entry.recent.pop_front();
+        }
+        entry.recent.push_back(elapsed);
+    }
+
+    /// Statistics for every tracked statement, ordered by total time,
+    /// descending.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<QueryStatistics> {
+        let mut stats: Vec<QueryStatistics> = self
+            .lock()
+            .entries
+            .iter()
+            .map(|(hash, entry)| entry.to_statistics(*hash))
+            .collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.total_time));
+        stats
+    }
+
+    /// Discard all statistics.
+    pub fn reset(&self) {
+        self.lock().entries.clear();
+    }
+}
+
+impl Default for QueryStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Times one execution and tallies its rows and outcome, for the
+/// statistics and the query log.
+pub(crate) struct QueryRecorder {
+    /// Statistics and the normalized statement to record them under.
+    stats: Option<(QueryStats, String)>,
+    /// Query log event to emit.
+    log: Option<PendingEvent>,
+    started: Instant,
+    rows: u64,
+    failed: bool,
+    /// GQLSTATUS code the result ended with.
+    status: Option<String>,
+}
+
+impl QueryRecorder {
+    /// Start timing an execution of `statement`, recorded into `stats`
+    /// and `log`, whichever are set.
+    pub(crate) fn new(
+        stats: Option<&QueryStats>,
+        statement: &str,
+        log: Option<PendingEvent>,
+    ) -> Self {
+        Self {
+            stats: stats.map(|stats| (stats.clone(), normalize_statement(statement))),
+            log,
+            started: Instant::now(),
+            rows: 0,
+            failed: false,
+            status: None,
+        }
+    }
+
+    /// Account for a frame sent to the client. The summary completes the
+    /// execution.
+    pub(crate) fn observe(&mut self, frame: &ResultFrame) {
+        match frame {
+            ResultFrame::Batch(batch) => {
+                self.rows += u64::try_from(batch.rows.len()).unwrap_or(u64::MAX);
+            }
+            ResultFrame::Summary(summary) => {
+                if let Some(status) = &summary.status {
+                    self.failed |= status::is_exception(&status.code);
+                    self.status = Some(status.code.clone());
+                }
+                self.finish();
+            }
+            ResultFrame::Header(_) | ResultFrame::Warning(_) => {}
+        }
+    }
+
+    /// Record the execution as failed, ending with `status` if it reached
+    /// one.
+    pub(crate) fn fail(&mut self, status: Option<&proto::GqlStatus>) {
+        self.failed = true;
+        if let Some(status) = status {
+            self.status = Some(status.code.clone());
+        }
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        let elapsed = self.started.elapsed();
+        if let Some((stats, statement)) = self.stats.take() {
+            stats.record(statement, elapsed, self.rows, self.failed);
+        }
+        if let Some(log) = self.log.take() {
+            log.emit(elapsed, self.status.take(), self.rows);
+        }
+    }
+}
+
+impl Drop for QueryRecorder {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Normalize a statement for grouping: string and numeric literals become
+/// `?` and runs of whitespace collapse to a single space. Parameters
+/// (`$name`) and identifiers, including delimited ones, are kept.
+#[must_use]
+pub fn normalize_statement(statement: &str) -> String {
+    let mut out = String::with_capacity(statement.len());
+    let mut chars = statement.trim().chars().peekable();
+    // Whether the previous character continues an identifier or parameter,
+    // so digits in `n1` or `$p2` are not mistaken for literals.
+    let mut in_word = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                skip_quoted(&mut chars, c);
+                out.push('?');
+                in_word = false;
+            }
+            '`' => {
+                out.push(c);
+                for d in chars.by_ref() {
+                    out.push(d);
+                    if d == '`' {
+                        break;
+                    }
+                }
+                in_word = false;
+            }
+            c if c.is_ascii_digit() && !in_word => {
+                while chars
+                    .peek()
+                    .is_some_and(|d| d.is_ascii_alphanumeric() || *d == '.' || *d == '_')
+                {
+                    chars.next();
+                }
+                out.push('?');
+            }
+            c if c.is_whitespace() => {
+                while chars.peek().is_some_and(|d| d.is_whitespace()) {
+                    chars.next();
+                }
+                out.push(' ');
+                in_word = false;
+            }
+            c => {
+                out.push(c);
+                in_word = c.is_alphanumeric() || c == '_' || c == '$';
+            }
+        }
+    }
+    out
+}
+
+/// Consume a quoted literal up to its closing quote. A doubled quote or a
+/// backslash escapes the next character.
+fn skip_quoted(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, quote: char) {
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            if chars.peek() == Some(&quote) {
+                chars.next();
+            } else {
+                return;
+            }
+        }
+    }
+}
+
+/// FNV-1a hash of the normalized statement, stable across releases and
+/// platforms.
+pub(crate) fn statement_hash(normalized: &str) -> u64 {
+    normalized
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_replaces_literals() {
+        assert_eq!(
+            normalize_statement("MATCH (n:Person {name: 'Alice', age: 30}) RETURN n"),
+            "MATCH (n:Person {name: ?, age: ?}) RETURN n"
+        );
+        assert_eq!(
+            normalize_statement("RETURN 1.5, \"it''s\", 'a\\'b'"),
+            "RETURN ?, ?, ?"
+        );
+    }
+
+    #[test]
+    fn normalize_keeps_identifiers_and_parameters() {
+        assert_eq!(
+            normalize_statement("MATCH (n1)  WHERE n1.x = $p2\n RETURN `col 1`"),
+            "MATCH (n1) WHERE n1.x = $p2 RETURN `col 1`"
+        );
+    }
+
+    #[test]
+    fn statements_differing_in_literals_share_an_entry() {
+        let stats = QueryStats::new();
+        drop(stats.start("MATCH (n) WHERE n.id = 1 RETURN n"));
+        drop(stats.start("MATCH (n) WHERE n.id = 2 RETURN n"));
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].calls, 2);
+        assert_eq!(snapshot[0].statement, "MATCH (n) WHERE n.id = ? RETURN n");
+    }
+
+    #[test]
+    fn recorder_counts_rows_and_errors() {
+        let stats = QueryStats::new();
+        let mut recorder = stats.start("MATCH (n) RETURN n");
+        recorder.observe(&ResultFrame::Batch(proto::RowBatch {
+            rows: vec![proto::Row::default(); 3],
+            checksum: None,
+            dictionary: Vec::new(),
+            columns: None,
+        }));
+        recorder.observe(&ResultFrame::Summary(proto::ResultSummary {
+            status: Some(status::error(status::DATA_EXCEPTION, "boom")),
+            ..Default::default()
+        }));
+        drop(recorder);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot[0].rows, 3);
+        assert_eq!(snapshot[0].errors, 1);
+    }
+
+    #[test]
+    fn full_aggregator_evicts_least_called() {
+        let stats = QueryStats::with_capacity(2);
+        for _ in 0..3 {
+            drop(stats.start("RETURN a"));
+        }
+        drop(stats.start("RETURN b"));
+        drop(stats.start("RETURN c"));
+
+        let statements: Vec<String> = stats.snapshot().into_iter().map(|s| s.statement).collect();
+        assert_eq!(statements.len(), 2);
+        assert!(statements.contains(&"RETURN a".to_owned()));
+        assert!(statements.contains(&"RETURN c".to_owned()));
+    }
+
+    #[test]
+    fn reset_clears_statistics() {
+        let stats = QueryStats::new();
+        drop(stats.start("RETURN 1"));
+        stats.reset();
+        assert!(stats.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+//! Per-session and per-principal rate limits on statements.
+//!
+//! Statement rates and row rates are token buckets that refill
+//! continuously and hold up to one second's worth of tokens, so short
+//! bursts pass while sustained load is held to the configured rate.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use tonic::Status;
+
+/// Number of tracked keys past which idle keys are pruned.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// What rate limits are counted against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitKey {
+    /// Each session has its own allowance.
+    #[default]
+    Session,
+    /// Sessions of the same authenticated principal share an allowance.
+    /// Sessions without a principal fall back to their own.
+    Principal,
+}
+
+/// Rate limits on the statements a server runs.
+///
+/// A `None` field leaves that dimension unbounded. Statements over the
+/// statement rate or the concurrent stream limit fail with
+/// `RESOURCE_EXHAUSTED`. Results over the row rate are slowed down
+/// instead, and their summary carries a
+/// [`WARNING_THROTTLED`](crate::status::WARNING_THROTTLED) warning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Whether limits apply per session or per principal.
+    pub key: RateLimitKey,
+    /// Statements per second.
+    pub queries_per_second: Option<u32>,
+    /// Result streams open at the same time.
+    pub max_concurrent_streams: Option<usize>,
+    /// Rows streamed per second.
+    pub rows_per_second: Option<u32>,
+}
+
+impl RateLimits {
+    fn is_unbounded(&self) -> bool {
+        self.queries_per_second.is_none()
+            && self.max_concurrent_streams.is_none()
+            && self.rows_per_second.is_none()
+    }
+}
+
+/// A token bucket holding up to `rate` tokens, refilled at `rate` per
+/// second. The balance may go negative, which delays later takers.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(rate: u32, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(rate),
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, rate: u32, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * f64::from(rate)).min(f64::from(rate));
+        self.updated = now;
+    }
+
+    fn is_full(&self, rate: u32, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * f64::from(rate) >= f64::from(rate)
+    }
+}
+
+/// Allowance state of one key.
+#[derive(Debug)]
+struct KeyState {
+    queries: Bucket,
+    rows: Bucket,
+    streams: usize,
+}
+
+#[derive(Debug)]
+struct Inner {
+    keys: HashMap<String, KeyState>,
+    next_prune: usize,
+}
+
+/// Enforces [`RateLimits`] across the statements of every session.
+///
+/// Cloning is cheap; clones share the same allowances.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    limits: RateLimits,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl RateLimiter {
+    /// A limiter enforcing `limits`, or `None` if they limit nothing.
+    pub(crate) fn new(limits: RateLimits) -> Option<Self> {
+        (!limits.is_unbounded()).then(|| Self {
+            limits,
+            inner: Arc::new(Mutex::new(Inner {
+                keys: HashMap::new(),
+                next_prune: PRUNE_THRESHOLD,
+            })),
+        })
+    }
+
+    /// Whether allowances are shared by principal.
+    pub(crate) fn by_principal(&self) -> bool {
+        self.limits.key == RateLimitKey::Principal
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Admit a statement counted against `key`, holding one of its
+    /// concurrent streams until the permit is dropped.
+    pub(crate) fn admit(&self, key: String) -> Result<RateLimitPermit, Status> {
+        let now = Instant::now();
+        let limits = self.limits;
+        let qps = limits.queries_per_second.unwrap_or_default();
+        let rows = limits.rows_per_second.unwrap_or_default();
+        let mut inner = self.lock();
+        if inner.keys.len() >= inner.next_prune {
+            inner
+                .keys
+                .retain(|_, state| state.streams > 0 || !state.is_idle(qps, rows, now));
+            inner.next_prune = (inner.keys.len() * 2).max(PRUNE_THRESHOLD);
+        }
+        let state = inner.keys.entry(key.clone()).or_insert_with(|| KeyState {
+            queries: Bucket::new(qps, now),
+            rows: Bucket::new(rows, now),
+            streams: 0,
+        });
+        if let Some(max) = limits.max_concurrent_streams {
+            if state.streams >= max {
+                return Err(Status::resource_exhausted(format!(
+                    "limit of {max} concurrent result streams reached"
+                )));
+            }
+        }
+        if let Some(rate) = limits.queries_per_second {
+            state.queries.refill(rate, now);
+            if state.queries.tokens < 1.0 {
+                return Err(Status::resource_exhausted(format!(
+                    "rate limit of {rate} statements per second exceeded"
+                )));
+            }
+            state.queries.tokens -= 1.0;
+        }
+        state.streams += 1;
+        Ok(RateLimitPermit {
+            limiter: self.clone(),
+            key,
+        })
+    }
+}
+
+impl KeyState {
+    /// Whether the key has nothing left to remember: both buckets have
+    /// refilled.
+    fn is_idle(&self, qps: u32, rows: u32, now: Instant) -> bool {
+        self.queries.is_full(qps, now) && self.rows.is_full(rows, now)
+    }
+}
+
+/// One admitted statement, released when its result stream is dropped.
+#[derive(Debug)]
+pub(crate) struct RateLimitPermit {
+    limiter: RateLimiter,
+    key: String,
+}
+
+impl RateLimitPermit {
+    /// Account for `count` rows sent, returning how long to wait before
+    /// sending more to stay within the row rate.
+    pub(crate) fn consume_rows(&self, count: usize) -> Option<Duration> {
+        let rate = self.limiter.limits.rows_per_second?;
+        if rate == 0 {
+            return None;
+        }
+        let now = Instant::now();
+        let mut inner = self.limiter.lock();
+        let state = inner.keys.get_mut(&self.key)?;
+        state.rows.refill(rate, now);
+        #[allow(clippy::cast_precision_loss)]
+        let count = count as f64;
+        state.rows.tokens -= count;
+        (state.rows.tokens < 0.0)
+            .then(|| Duration::from_secs_f64(-state.rows.tokens / f64::from(rate)))
+    }
+
+    /// The row rate, for the throttle warning.
+    pub(crate) fn rows_per_second(&self) -> Option<u32> {
+        self.limiter.limits.rows_per_second
+    }
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        if let Some(state) = self.limiter.lock().keys.get_mut(&self.key) {
+            state.streams = state.streams.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statement_rate_allows_a_burst_then_rejects() {
+        let limiter = RateLimiter::new(RateLimits {
+            queries_per_second: Some(2),
+            ..RateLimits::default()
+        })
+        .unwrap();
+        let permits: Vec<_> = (0..2)
+            .map(|_| limiter.admit("s1".to_owned()).unwrap())
+            .collect();
+        let err = limiter.admit("s1".to_owned()).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+        assert!(limiter.admit("s2".to_owned()).is_ok());
+        drop(permits);
+    }
+
+    #[test]
+    fn concurrent_streams_are_released_on_drop() {
+        let limiter = RateLimiter::new(RateLimits {
+            max_concurrent_streams: Some(1),
+            ..RateLimits::default()
+        })
+        .unwrap();
+        let permit = limiter.admit("s1".to_owned()).unwrap();
+        assert!(limiter.admit("s1".to_owned()).is_err());
+        drop(permit);
+        assert!(limiter.admit("s1".to_owned()).is_ok());
+    }
+
+    #[test]
+    fn rows_over_the_rate_are_delayed() {
+        let limiter = RateLimiter::new(RateLimits {
+            rows_per_second: Some(100),
+            ..RateLimits::default()
+        })
+        .unwrap();
+        let permit = limiter.admit("s1".to_owned()).unwrap();
+        assert_eq!(permit.consume_rows(100), None);
+        let delay = permit.consume_rows(50).unwrap();
+        assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn unbounded_limits_need_no_limiter() {
+        assert!(RateLimiter::new(RateLimits::default()).is_none());
+    }
+}
@@ -0,0 +1,319 @@
+//! Server-side cache of results for repeated read-only statements.
+//!
+//! Entries are keyed by the session's database (selected database,
+//! current schema, and graph), the statement text, and its parameters,
+//! and hold the complete frame sequence of a successful execution.
+//! Writes to a database drop that database's entries.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::error::GqlError;
+use crate::status;
+use crate::types::Value;
+
+use super::backend::{ResultFrame, ResultStream};
+use super::normalize_statement;
+
+/// Largest result, in rows, that is stored.
+const DEFAULT_MAX_ROWS: usize = 10_000;
+
+/// Keywords that make a statement a write or catalog change.
+const WRITE_KEYWORDS: &[&str] = &[
+    "INSERT", "SET", "REMOVE", "DELETE", "DETACH", "CREATE", "DROP", "CALL", "MERGE",
+];
+
+/// Whether `statement` only reads data, judged by its keywords.
+///
+/// Literals are ignored, so `MATCH (n {name: 'DELETE'})` is read-only.
+/// Statements calling procedures are treated as writes.
+#[must_use]
+pub fn is_read_only_statement(statement: &str) -> bool {
+    let normalized = normalize_statement(statement).to_ascii_uppercase();
+    !normalized
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .any(|word| WRITE_KEYWORDS.contains(&word))
+}
+
+#[derive(Debug)]
+struct Entry {
+    database: String,
+    frames: Arc<Vec<ResultFrame>>,
+    stored: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    /// Bumped on every write, so results read before a write are not
+    /// stored after it.
+    generations: HashMap<String, u64>,
+}
+
+/// A bounded, time-limited cache of read-only results.
+///
+/// Cloning is cheap; clones share the same entries.
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    inner: Arc<Mutex<Inner>>,
+    capacity: usize,
+    ttl: Duration,
+    max_rows: usize,
+}
+
+impl ResultCache {
+    /// Create a cache holding up to `capacity` results, each served for
+    /// at most `ttl`.
+    #[must_use]
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            inner: Arc::default(),
+            capacity: capacity.max(1),
+            ttl,
+            max_rows: DEFAULT_MAX_ROWS,
+        }
+    }
+
+    /// Only store results with at most `max_rows` rows (default 10 000).
+    #[must_use]
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Drop every result cached for `database`, named `schema/graph`
+    /// with empty components for an unset schema or graph, prefixed with
+    /// `database/` if the session selected a database.
+    pub fn invalidate(&self, database: &str) {
+        let mut inner = self.lock();
+        *inner.generations.entry(database.to_owned()).or_default() += 1;
+        inner.entries.retain(|_, entry| entry.database != database);
+    }
+
+    /// Drop every cached result.
+    pub fn invalidate_all(&self) {
+        let mut inner = self.lock();
+        for generation in inner.generations.values_mut() {
+            *generation += 1;
+        }
+        inner.entries.clear();
+    }
+
+    /// Number of cached results, including expired ones not yet evicted.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache holds no results.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replay the cached result for `key`, if fresh.
+    pub(crate) fn get(&self, key: &CacheKey) -> Option<Pin<Box<dyn ResultStream>>> {
+        let mut inner = self.lock();
+        let entry = inner.entries.get(&key.key)?;
+        if entry.stored.elapsed() > self.ttl {
+            inner.entries.remove(&key.key);
+            return None;
+        }
+        Some(Box::pin(Replay {
+            frames: entry.frames.iter().cloned().collect(),
+        }))
+    }
+
+    /// Wrap `stream` so that its result is stored once it completes
+    /// successfully.
+    pub(crate) fn fill(
+        &self,
+        key: CacheKey,
+        stream: Pin<Box<dyn ResultStream>>,
+    ) -> Pin<Box<dyn ResultStream>> {
+        let generation = self.generation(&key.database);
+        Box::pin(Fill {
+            cache: self.clone(),
+            key,
+            generation,
+            inner: stream,
+            frames: Some(Vec::new()),
+            rows: 0,
+        })
+    }
+
+    fn generation(&self, database: &str) -> u64 {
+        self.lock().generations.get(database).copied().unwrap_or(0)
+    }
+
+    fn insert(&self, key: CacheKey, generation: u64, frames: Vec<ResultFrame>) {
+        let mut inner = self.lock();
+        if inner.generations.get(&key.database).copied().unwrap_or(0) != generation {
+            return;
+        }
+        let ttl = self.ttl;
+        inner
+            .entries
+            .retain(|_, entry| entry.stored.elapsed() <= ttl);
+        if !inner.entries.contains_key(&key.key) && inner.entries.len() >= self.capacity {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                inner.entries.remove(&oldest);
+            }
+        }
+        inner.entries.insert(
+            key.key,
+            Entry {
+                database: key.database,
+                frames: Arc::new(frames),
+                stored: Instant::now(),
+            },
+        );
+    }
+}
+
+/// Identifies a cached result.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheKey {
+    database: String,
+    key: String,
+}
+
+impl CacheKey {
+    pub(crate) fn new(
+        database: String,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+    ) -> Self {
+        let mut names: Vec<&String> = parameters.keys().collect();
+        names.sort_unstable();
+        let mut key = format!("{database}\u{0}{statement}");
+        for name in names {
+            let _ = write!(key, "\u{0}{name}={:?}", parameters[name]);
+        }
+        Self { database, key }
+    }
+}
+
+/// Replays a cached frame sequence.
+struct Replay {
+    frames: VecDeque<ResultFrame>,
+}
+
+impl ResultStream for Replay {
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<ResultFrame, GqlError>>> {
+        std::task::Poll::Ready(self.frames.pop_front().map(Ok))
+    }
+}
+
+/// Passes frames through, storing them once the summary reports success.
+struct Fill {
+    cache: ResultCache,
+    key: CacheKey,
+    generation: u64,
+    inner: Pin<Box<dyn ResultStream>>,
+    /// Frames seen so far; `None` once the result is known not to be
+    /// cacheable.
+    frames: Option<Vec<ResultFrame>>,
+    rows: usize,
+}
+
+impl ResultStream for Fill {
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<ResultFrame, GqlError>>> {
+        let poll = self.inner.as_mut().poll_next(cx);
+        match &poll {
+            std::task::Poll::Ready(Some(Ok(frame))) => {
+                if let ResultFrame::Batch(batch) = frame {
+                    self.rows += batch.rows.len();
+                }
+                let too_large = self.rows > self.cache.max_rows;
+                let this = &mut *self;
+                match this.frames.as_mut() {
+                    Some(_) if too_large => this.frames = None,
+                    Some(frames) => frames.push(frame.clone()),
+                    None => {}
+                }
+                if let ResultFrame::Summary(summary) = frame {
+                    let succeeded = summary
+                        .status
+                        .as_ref()
+                        .is_some_and(|s| status::is_success(&s.code));
+                    if let Some(frames) = this.frames.take().filter(|_| succeeded) {
+                        this.cache.insert(this.key.clone(), this.generation, frames);
+                    }
+                }
+            }
+            std::task::Poll::Ready(Some(Err(_)) | None) => self.frames = None,
+            std::task::Poll::Pending => {}
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_read_only_statements() {
+        assert!(is_read_only_statement("MATCH (n) RETURN n"));
+        assert!(is_read_only_statement(
+            "MATCH (n {name: 'DELETE'}) RETURN n"
+        ));
+        assert!(!is_read_only_statement("MATCH (n) SET n.x = 1"));
+        assert!(!is_read_only_statement("insert (:Person)"));
+        assert!(!is_read_only_statement("CALL db.labels()"));
+    }
+
+    #[test]
+    fn keys_ignore_parameter_order() {
+        let a = HashMap::from([
+            ("x".to_owned(), Value::Integer(1)),
+            ("y".to_owned(), Value::Integer(2)),
+        ]);
+        let b = HashMap::from([
+            ("y".to_owned(), Value::Integer(2)),
+            ("x".to_owned(), Value::Integer(1)),
+        ]);
+        assert_eq!(
+            CacheKey::new("db".into(), "MATCH", &a).key,
+            CacheKey::new("db".into(), "MATCH", &b).key
+        );
+        assert_ne!(
+            CacheKey::new("db".into(), "MATCH", &a).key,
+            CacheKey::new("other".into(), "MATCH", &a).key
+        );
+    }
+
+    #[test]
+    fn invalidation_discards_in_flight_results() {
+        let cache = ResultCache::new(4, Duration::from_secs(60));
+        let key = CacheKey::new("db".into(), "MATCH", &HashMap::new());
+        let generation = cache.generation("db");
+        cache.invalidate("db");
+        cache.insert(key.clone(), generation, Vec::new());
+        assert!(cache.get(&key).is_none());
+
+        cache.insert(key.clone(), cache.generation("db"), Vec::new());
+        assert!(cache.get(&key).is_some());
+        cache.invalidate("db");
+        assert!(cache.is_empty());
+    }
+}
@@ -12,17 +12,41 @@ use crate::proto;
 use crate::proto::search_service_server::SearchService;
 use crate::types::Value;
 
-use super::backend::{GqlBackend, HybridSearchParams, TextSearchParams, VectorSearchParams};
+use super::SessionManager;
+use super::auth::{Authorization, Authorizer, Operation, authorize};
+use super::backend::{
+    GqlBackend, HybridSearchParams, SearchTarget, TextSearchParams, VectorSearchParams,
+};
+
+/// The `ServerInfo.features` entry a server lists when it serves the
+/// `SearchService`.
+pub const SEARCH_FEATURE: &str = "search";
 
 /// Implementation of the `SearchService` gRPC service.
 pub struct SearchServiceImpl<B: GqlBackend> {
     backend: Arc<B>,
+    authorization: Option<Authorization>,
 }
 
 impl<B: GqlBackend> SearchServiceImpl<B> {
     /// Create a new search service.
     pub fn new(backend: Arc<B>) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            authorization: None,
+        }
+    }
+
+    /// Check each search with `authorizer` as a read by the principal of
+    /// the session named in the request metadata.
+    #[must_use]
+    pub fn with_authorizer(
+        mut self,
+        authorizer: Arc<dyn Authorizer>,
+        sessions: SessionManager,
+    ) -> Self {
+        self.authorization = Some(Authorization::new(authorizer, sessions));
+        self
     }
 }
 
@@ -36,9 +60,18 @@ fn to_proto_hit(hit: &super::backend::SearchHit) -> proto::SearchHit {
             .iter()
             .map(|(k, v)| (k.clone(), proto::Value::from(v.clone())))
             .collect(),
+        node: hit.node.clone().map(proto::Node::from),
+        edge: hit.edge.clone().map(proto::Edge::from),
+        source_node_id: hit.source_node_id,
+        target_node_id: hit.target_node_id,
     }
 }
 
+/// Decode the wire search target, defaulting unknown values to nodes.
+fn search_target(target: i32) -> SearchTarget {
+    proto::SearchTarget::try_from(target).map_or(SearchTarget::Nodes, SearchTarget::from)
+}
+
 #[tonic::async_trait]
 impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
     #[tracing::instrument(skip(self, request), fields(graph, label, property))]
@@ -46,6 +79,8 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
         &self,
         request: Request<proto::VectorSearchRequest>,
     ) -> Result<Response<proto::VectorSearchResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let req = request.into_inner();
         let span = tracing::Span::current();
         span.record("graph", &req.graph);
@@ -65,7 +100,7 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
             .map(|(k, v)| (k, Value::from(v)))
             .collect();
 
-        let hits = self
+        let results = self
             .backend
             .vector_search(VectorSearchParams {
                 graph: req.graph,
@@ -75,12 +110,18 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
                 k: req.k,
                 ef: req.ef,
                 filters,
+                offset: req.offset,
+                page_token: req.page_token,
+                include_elements: req.include_elements,
+                target: search_target(req.target),
             })
             .await
             .map_err(|e| e.to_optional_service_status())?;
 
         Ok(Response::new(proto::VectorSearchResponse {
-            hits: hits.iter().map(to_proto_hit).collect(),
+            hits: results.hits.iter().map(to_proto_hit).collect(),
+            next_page_token: results.next_page_token,
+            total_hits_estimate: results.total_hits_estimate,
         }))
     }
 
@@ -89,6 +130,8 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
         &self,
         request: Request<proto::TextSearchRequest>,
     ) -> Result<Response<proto::TextSearchResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let req = request.into_inner();
         let span = tracing::Span::current();
         span.record("graph", &req.graph);
@@ -102,7 +145,7 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
             return Err(Status::invalid_argument("query text is required"));
         }
 
-        let hits = self
+        let results = self
             .backend
             .text_search(TextSearchParams {
                 graph: req.graph,
@@ -110,12 +153,18 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
                 property: req.property,
                 query: req.query,
                 k: req.k,
+                offset: req.offset,
+                page_token: req.page_token,
+                include_elements: req.include_elements,
+                target: search_target(req.target),
             })
             .await
             .map_err(|e| e.to_optional_service_status())?;
 
         Ok(Response::new(proto::TextSearchResponse {
-            hits: hits.iter().map(to_proto_hit).collect(),
+            hits: results.hits.iter().map(to_proto_hit).collect(),
+            next_page_token: results.next_page_token,
+            total_hits_estimate: results.total_hits_estimate,
         }))
     }
 
@@ -124,6 +173,8 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
         &self,
         request: Request<proto::HybridSearchRequest>,
     ) -> Result<Response<proto::HybridSearchResponse>, Status> {
+        authorize(self.authorization.as_ref(), &request, Operation::Read).await?;
+
         let req = request.into_inner();
         let span = tracing::Span::current();
         span.record("graph", &req.graph);
@@ -136,7 +187,7 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
             return Err(Status::invalid_argument("query_text is required"));
         }
 
-        let hits = self
+        let results = self
             .backend
             .hybrid_search(HybridSearchParams {
                 graph: req.graph,
@@ -146,12 +197,18 @@ impl<B: GqlBackend> SearchService for SearchServiceImpl<B> {
                 query_text: req.query_text,
                 query_vector: req.query_vector,
                 k: req.k,
+                offset: req.offset,
+                page_token: req.page_token,
+                include_elements: req.include_elements,
+                target: search_target(req.target),
             })
             .await
             .map_err(|e| e.to_optional_service_status())?;
 
         Ok(Response::new(proto::HybridSearchResponse {
-            hits: hits.iter().map(to_proto_hit).collect(),
+            hits: results.hits.iter().map(to_proto_hit).collect(),
+            next_page_token: results.next_page_token,
+            total_hits_estimate: results.total_hits_estimate,
         }))
     }
 }
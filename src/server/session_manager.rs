@@ -1,12 +1,19 @@
 //! Server-side session state tracking.
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 
 use tokio::sync::RwLock;
 use tokio::time::Instant;
 
-use super::SessionProperty;
+use crate::error::GqlError;
+use crate::proto;
+
+use super::auth::Principal;
+use super::backend::ResetTarget;
+use super::{SessionDefaults, SessionProperty};
 
 /// Tracks the mutable state for a single session.
 #[derive(Debug, Clone)]
@@ -21,8 +28,94 @@ pub struct SessionState {
     pub parameters: HashMap<String, crate::types::Value>,
     /// Active transaction ID, if any.
     pub active_transaction: Option<String>,
+    /// Database the session's statements are routed to, or `None` for
+    /// the server's default. Kept when the session is reset.
+    pub current_database: Option<String>,
     /// Timestamp of last activity for idle detection.
     pub last_activity: Instant,
+    /// Schema and graph the session started with, restored on reset.
+    pub defaults: SessionDefaults,
+    /// Whether row batches sent to this session carry checksums, as
+    /// negotiated at handshake.
+    pub row_batch_checksums: bool,
+    /// Whether row batches sent to this session may be dictionary-encoded,
+    /// as negotiated at handshake.
+    pub dictionary_encoding: bool,
+    /// Whether row batches sent to this session are transposed into
+    /// columns, as negotiated at handshake.
+    pub columnar_batches: bool,
+    /// Prepared statement texts by statement ID.
+    pub prepared_statements: HashMap<String, String>,
+    /// The principal the session authenticated as, if the server
+    /// requires authentication.
+    pub principal: Option<Principal>,
+    /// Client information sent at handshake.
+    pub client_info: HashMap<String, String>,
+}
+
+impl SessionState {
+    /// Create the initial state for a session starting on the given defaults.
+    #[must_use]
+    pub fn with_defaults(defaults: SessionDefaults) -> Self {
+        Self {
+            schema: defaults.schema.clone(),
+            graph: defaults.graph.clone(),
+            defaults,
+            ..Self::default()
+        }
+    }
+
+    /// The database the session's statements run against, named
+    /// `schema/graph` with empty components for an unset schema or graph,
+    /// prefixed with `database/` if the session selected a database.
+    #[must_use]
+    pub fn database(&self) -> String {
+        let schema = self.schema.as_deref().unwrap_or_default();
+        let graph = self.graph.as_deref().unwrap_or_default();
+        match &self.current_database {
+            Some(database) => format!("{database}/{schema}/{graph}"),
+            None => format!("{schema}/{graph}"),
+        }
+    }
+
+    fn apply(&mut self, property: &SessionProperty) {
+        match property {
+            SessionProperty::Schema(s) => self.schema = Some(s.clone()),
+            SessionProperty::Graph(g) => self.graph = Some(g.clone()),
+            SessionProperty::TimeZone(offset) => self.time_zone_offset_minutes = *offset,
+            SessionProperty::Parameter { name, value } => {
+                self.parameters.insert(name.clone(), value.clone());
+            }
+            SessionProperty::Database(d) => self.current_database = Some(d.clone()),
+        }
+    }
+
+    fn start_from(&mut self, defaults: SessionDefaults) {
+        self.schema.clone_from(&defaults.schema);
+        self.graph.clone_from(&defaults.graph);
+        self.defaults = defaults;
+    }
+
+    fn apply_reset(&mut self, target: ResetTarget) {
+        match target {
+            ResetTarget::All => {
+                *self = Self {
+                    row_batch_checksums: self.row_batch_checksums,
+                    dictionary_encoding: self.dictionary_encoding,
+                    columnar_batches: self.columnar_batches,
+                    prepared_statements: std::mem::take(&mut self.prepared_statements),
+                    principal: self.principal.take(),
+                    client_info: std::mem::take(&mut self.client_info),
+                    current_database: self.current_database.take(),
+                    ..Self::with_defaults(self.defaults.clone())
+                };
+            }
+            ResetTarget::Schema => self.schema.clone_from(&self.defaults.schema),
+            ResetTarget::Graph => self.graph.clone_from(&self.defaults.graph),
+            ResetTarget::TimeZone => self.time_zone_offset_minutes = 0,
+            ResetTarget::Parameters => self.parameters.clear(),
+        }
+    }
 }
 
 impl Default for SessionState {
@@ -33,7 +126,15 @@ impl Default for SessionState {
             time_zone_offset_minutes: 0,
             parameters: HashMap::new(),
             active_transaction: None,
+            current_database: None,
             last_activity: Instant::now(),
+            defaults: SessionDefaults::default(),
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
+            prepared_statements: HashMap::new(),
+            principal: None,
+            client_info: HashMap::new(),
         }
     }
 }
@@ -42,7 +143,159 @@ impl Default for SessionState {
 #[derive(Debug, Clone)]
 pub struct SessionManager {
     sessions: Arc<RwLock<HashMap<String, SessionState>>>,
+    /// Resumption token to session ID.
+    resumption_tokens: Arc<RwLock<HashMap<String, String>>>,
     max_sessions: Option<usize>,
+    /// Session limit for databases without their own.
+    max_sessions_per_database: Option<usize>,
+    /// Session limits for individual databases.
+    database_session_limits: HashMap<String, usize>,
+    /// How long resumable sessions outlive the idle timeout.
+    orphan_grace: Option<Duration>,
+    /// Statements whose results are still streaming.
+    running: Arc<Mutex<RunningStatements>>,
+}
+
+/// Statements in flight, keyed by the order they started in.
+#[derive(Debug, Default)]
+struct RunningStatements {
+    next_id: u64,
+    statements: BTreeMap<u64, (String, String)>,
+}
+
+/// A point-in-time view of one session, as listed by
+/// [`AdminClient::list_sessions`](crate::client::AdminClient::list_sessions).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    /// Session ID.
+    pub session_id: String,
+    /// Client information sent at handshake.
+    pub client_info: HashMap<String, String>,
+    /// Current schema.
+    pub schema: Option<String>,
+    /// Current graph.
+    pub graph: Option<String>,
+    /// Active transaction ID, if any.
+    pub transaction_id: Option<String>,
+    /// Time since the session's last request.
+    pub idle: Duration,
+    /// Statements whose results are still streaming, oldest first.
+    pub running_statements: Vec<String>,
+    /// Name of the principal the session authenticated as.
+    pub principal: Option<String>,
+}
+
+impl From<SessionInfo> for proto::SessionInfo {
+    fn from(s: SessionInfo) -> Self {
+        Self {
+            session_id: s.session_id,
+            client_info: s.client_info,
+            schema: s.schema,
+            graph: s.graph,
+            transaction_id: s.transaction_id,
+            idle_millis: u64::try_from(s.idle.as_millis()).unwrap_or(u64::MAX),
+            running_statements: s.running_statements,
+            principal: s.principal,
+        }
+    }
+}
+
+impl From<proto::SessionInfo> for SessionInfo {
+    fn from(p: proto::SessionInfo) -> Self {
+        Self {
+            session_id: p.session_id,
+            client_info: p.client_info,
+            schema: p.schema,
+            graph: p.graph,
+            transaction_id: p.transaction_id,
+            idle: Duration::from_millis(p.idle_millis),
+            running_statements: p.running_statements,
+            principal: p.principal,
+        }
+    }
+}
+
+/// The configurable state of one session, as returned by
+/// [`GqlSession::current_state`](crate::client::GqlSession::current_state).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSnapshot {
+    /// Current schema.
+    pub schema: Option<String>,
+    /// Current graph.
+    pub graph: Option<String>,
+    /// Timezone offset in minutes.
+    pub time_zone_offset_minutes: i32,
+    /// Session parameters.
+    pub parameters: HashMap<String, crate::types::Value>,
+    /// Database the session's statements are routed to, or `None` for
+    /// the server's default.
+    pub database: Option<String>,
+    /// Active transaction ID, if any.
+    pub transaction_id: Option<String>,
+}
+
+impl From<&SessionState> for SessionSnapshot {
+    fn from(state: &SessionState) -> Self {
+        Self {
+            schema: state.schema.clone(),
+            graph: state.graph.clone(),
+            time_zone_offset_minutes: state.time_zone_offset_minutes,
+            parameters: state.parameters.clone(),
+            database: state.current_database.clone(),
+            transaction_id: state.active_transaction.clone(),
+        }
+    }
+}
+
+impl From<SessionSnapshot> for proto::GetSessionStateResponse {
+    fn from(s: SessionSnapshot) -> Self {
+        Self {
+            schema: s.schema,
+            graph: s.graph,
+            time_zone_offset_minutes: s.time_zone_offset_minutes,
+            parameters: s
+                .parameters
+                .into_iter()
+                .map(|(name, value)| (name, value.into()))
+                .collect(),
+            database: s.database,
+            transaction_id: s.transaction_id,
+        }
+    }
+}
+
+impl From<proto::GetSessionStateResponse> for SessionSnapshot {
+    fn from(p: proto::GetSessionStateResponse) -> Self {
+        Self {
+            schema: p.schema,
+            graph: p.graph,
+            time_zone_offset_minutes: p.time_zone_offset_minutes,
+            parameters: p
+                .parameters
+                .into_iter()
+                .map(|(name, value)| (name, value.into()))
+                .collect(),
+            database: p.database,
+            transaction_id: p.transaction_id,
+        }
+    }
+}
+
+/// Marks a statement as running until dropped.
+#[derive(Debug)]
+pub(crate) struct RunningStatement {
+    running: Arc<Mutex<RunningStatements>>,
+    id: u64,
+}
+
+impl Drop for RunningStatement {
+    fn drop(&mut self) {
+        self.running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .statements
+            .remove(&self.id);
+    }
 }
 
 impl SessionManager {
@@ -51,7 +304,12 @@ impl SessionManager {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            resumption_tokens: Arc::default(),
             max_sessions: None,
+            max_sessions_per_database: None,
+            database_session_limits: HashMap::new(),
+            orphan_grace: None,
+            running: Arc::default(),
         }
     }
 
@@ -60,33 +318,114 @@ impl SessionManager {
     pub fn with_capacity(max_sessions: usize) -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            resumption_tokens: Arc::default(),
             max_sessions: Some(max_sessions),
+            max_sessions_per_database: None,
+            database_session_limits: HashMap::new(),
+            orphan_grace: None,
+            running: Arc::default(),
         }
     }
 
+    /// Limit the number of sessions on any one database, as named by
+    /// [`SessionState::database`].
+    ///
+    /// Sessions are counted against the database they currently have
+    /// selected, so one busy database cannot take every session slot.
+    #[must_use]
+    pub fn with_max_sessions_per_database(mut self, limit: usize) -> Self {
+        self.max_sessions_per_database = Some(limit);
+        self
+    }
+
+    /// Limit the number of sessions on `database`, overriding
+    /// [`with_max_sessions_per_database`](Self::with_max_sessions_per_database)
+    /// for it.
+    #[must_use]
+    pub fn with_database_session_limit(
+        mut self,
+        database: impl Into<String>,
+        limit: usize,
+    ) -> Self {
+        self.database_session_limits.insert(database.into(), limit);
+        self
+    }
+
+    /// Keep sessions that still hold an unredeemed resumption token for
+    /// `grace` after they go idle, even if the idle timeout is shorter.
+    ///
+    /// An idle session with a resumption token may belong to a client whose
+    /// connection dropped; the grace period gives it time to reconnect and
+    /// resume before the reaper removes the session.
+    #[must_use]
+    pub fn with_orphan_grace(mut self, grace: Duration) -> Self {
+        self.orphan_grace = Some(grace);
+        self
+    }
+
     /// Register a new session.
     ///
     /// # Errors
     ///
-    /// Returns an error if the session limit has been reached.
-    pub async fn register(&self, session_id: &str) -> Result<(), crate::error::GqlError> {
+    /// Returns an error if the session limit, or that of the default
+    /// database, has been reached.
+    pub async fn register(&self, session_id: &str) -> Result<(), GqlError> {
+        self.register_with_defaults(session_id, SessionDefaults::default())
+            .await
+    }
+
+    /// Register a new session whose schema and graph start on `defaults`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session limit, or that of the database
+    /// `defaults` selects, has been reached.
+    pub async fn register_with_defaults(
+        &self,
+        session_id: &str,
+        defaults: SessionDefaults,
+    ) -> Result<(), GqlError> {
+        self.register_on_database(session_id, defaults, None).await
+    }
+
+    /// Register a new session whose schema and graph start on `defaults`,
+    /// routed to `database`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session limit, or that of the database the
+    /// session starts on, has been reached.
+    pub async fn register_on_database(
+        &self,
+        session_id: &str,
+        defaults: SessionDefaults,
+        database: Option<String>,
+    ) -> Result<(), GqlError> {
         let mut sessions = self.sessions.write().await;
         if let Some(max) = self.max_sessions {
             if sessions.len() >= max {
-                return Err(crate::error::GqlError::Session(
-                    "session limit reached".to_owned(),
-                ));
+                return Err(GqlError::Session("session limit reached".to_owned()));
             }
         }
-        sessions.insert(session_id.to_owned(), SessionState::default());
+        let state = SessionState {
+            current_database: database,
+            ..SessionState::with_defaults(defaults)
+        };
+        if let Some(err) = self.database_limit_error(&sessions, session_id, &state) {
+            return Err(err);
+        }
+        sessions.insert(session_id.to_owned(), state);
         tracing::info!(session_id, "session registered");
         Ok(())
     }
 
     /// Remove a session.
     pub async fn remove(&self, session_id: &str) -> bool {
-        let mut sessions = self.sessions.write().await;
-        let removed = sessions.remove(session_id).is_some();
+        let removed = self.sessions.write().await.remove(session_id).is_some();
+        self.resumption_tokens
+            .write()
+            .await
+            .retain(|_, id| id != session_id);
         if removed {
             tracing::info!(session_id, "session removed");
         }
@@ -99,6 +438,60 @@ impl SessionManager {
         sessions.contains_key(session_id)
     }
 
+    /// IDs of all registered sessions.
+    pub async fn session_ids(&self) -> Vec<String> {
+        self.sessions.read().await.keys().cloned().collect()
+    }
+
+    /// Snapshots of all registered sessions, ordered by session ID.
+    pub async fn list(&self) -> Vec<SessionInfo> {
+        let now = Instant::now();
+        let mut running: HashMap<String, Vec<String>> = HashMap::new();
+        for (session_id, statement) in self
+            .running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .statements
+            .values()
+        {
+            running
+                .entry(session_id.clone())
+                .or_default()
+                .push(statement.clone());
+        }
+        let sessions = self.sessions.read().await;
+        let mut list: Vec<SessionInfo> = sessions
+            .iter()
+            .map(|(id, state)| SessionInfo {
+                session_id: id.clone(),
+                client_info: state.client_info.clone(),
+                schema: state.schema.clone(),
+                graph: state.graph.clone(),
+                transaction_id: state.active_transaction.clone(),
+                idle: now.saturating_duration_since(state.last_activity),
+                running_statements: running.remove(id).unwrap_or_default(),
+                principal: state.principal.as_ref().map(|p| p.name.clone()),
+            })
+            .collect();
+        list.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        list
+    }
+
+    /// Mark `statement` as running in a session until the returned guard
+    /// is dropped.
+    pub(crate) fn start_statement(&self, session_id: &str, statement: &str) -> RunningStatement {
+        let mut running = self.running.lock().unwrap_or_else(PoisonError::into_inner);
+        let id = running.next_id;
+        running.next_id += 1;
+        running
+            .statements
+            .insert(id, (session_id.to_owned(), statement.to_owned()));
+        RunningStatement {
+            running: Arc::clone(&self.running),
+            id,
+        }
+    }
+
     /// Update the last-activity timestamp for a session.
     pub async fn touch(&self, session_id: &str) {
         if let Some(state) = self.sessions.write().await.get_mut(session_id) {
@@ -106,77 +499,299 @@ impl SessionManager {
         }
     }
 
-    /// Remove sessions that have been idle longer than `max_idle`.
+    /// Remove sessions that have been idle longer than `max_idle`, or
+    /// than the orphan grace period if they can still be resumed and it is
+    /// longer.
     ///
     /// Returns the IDs of reaped sessions.
     pub async fn reap_idle(&self, max_idle: std::time::Duration) -> Vec<String> {
+        let resumable: HashSet<String> = match self.orphan_grace {
+            Some(_) => self
+                .resumption_tokens
+                .read()
+                .await
+                .values()
+                .cloned()
+                .collect(),
+            None => HashSet::new(),
+        };
+        let orphan_idle = self
+            .orphan_grace
+            .map_or(max_idle, |grace| grace.max(max_idle));
         let mut sessions = self.sessions.write().await;
         let now = Instant::now();
         let expired: Vec<String> = sessions
             .iter()
-            .filter(|(_, s)| now.duration_since(s.last_activity) > max_idle)
+            .filter(|(id, s)| {
+                let limit = if resumable.contains(*id) {
+                    orphan_idle
+                } else {
+                    max_idle
+                };
+                now.duration_since(s.last_activity) > limit
+            })
             .map(|(id, _)| id.clone())
             .collect();
         for id in &expired {
             sessions.remove(id);
         }
+        drop(sessions);
+        if !expired.is_empty() {
+            self.resumption_tokens
+                .write()
+                .await
+                .retain(|_, id| !expired.contains(id));
+        }
         if !expired.is_empty() {
             tracing::info!(count = expired.len(), "idle sessions reaped");
         }
         expired
     }
 
+    /// Issue a new resumption token for a session, replacing any earlier
+    /// one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub async fn issue_resumption_token(&self, session_id: &str) -> Result<String, GqlError> {
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes)
+            .map_err(|e| GqlError::Session(format!("cannot generate resumption token: {e}")))?;
+        let token = bytes.iter().fold(String::with_capacity(64), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        });
+        let mut tokens = self.resumption_tokens.write().await;
+        tokens.retain(|_, id| id != session_id);
+        tokens.insert(token.clone(), session_id.to_owned());
+        Ok(token)
+    }
+
+    /// Redeem a resumption token, returning its session if the session is
+    /// still live and was active within `window`.
+    ///
+    /// Tokens are single-use: the token is consumed whether or not the
+    /// session can be resumed.
+    pub async fn resume(&self, token: &str, window: Duration) -> Option<String> {
+        let session_id = self.resumption_tokens.write().await.remove(token)?;
+        let mut sessions = self.sessions.write().await;
+        let state = sessions.get_mut(&session_id)?;
+        if state.last_activity.elapsed() > window {
+            return None;
+        }
+        state.last_activity = Instant::now();
+        Some(session_id)
+    }
+
     /// Apply a session property.
     ///
     /// # Errors
     ///
-    /// Returns an error if the session does not exist.
+    /// Returns an error if the session does not exist, or if the property
+    /// selects a database whose session limit has been reached.
     pub async fn configure(
         &self,
         session_id: &str,
         property: &SessionProperty,
-    ) -> Result<(), crate::error::GqlError> {
+    ) -> Result<(), GqlError> {
         let mut sessions = self.sessions.write().await;
-        let state = sessions.get_mut(session_id).ok_or_else(|| {
-            crate::error::GqlError::Session(format!("session {session_id} not found"))
-        })?;
-
-        match property {
-            SessionProperty::Schema(s) => state.schema = Some(s.clone()),
-            SessionProperty::Graph(g) => state.graph = Some(g.clone()),
-            SessionProperty::TimeZone(offset) => state.time_zone_offset_minutes = *offset,
-            SessionProperty::Parameter { name, value } => {
-                state.parameters.insert(name.clone(), value.clone());
-            }
+        let mut state = sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| not_found(session_id))?;
+        state.apply(property);
+        if let Some(err) = self.database_limit_error(&sessions, session_id, &state) {
+            return Err(err);
         }
+        sessions.insert(session_id.to_owned(), state);
         Ok(())
     }
 
-    /// Reset session state.
+    /// Move a session onto new defaults, as when it switches database:
+    /// its current schema and graph become the defaults' and a reset
+    /// returns to them.
     ///
     /// # Errors
     ///
     /// Returns an error if the session does not exist.
-    pub async fn reset(
+    pub async fn start_from_defaults(
         &self,
         session_id: &str,
-        target: super::backend::ResetTarget,
-    ) -> Result<(), crate::error::GqlError> {
+        defaults: SessionDefaults,
+    ) -> Result<(), GqlError> {
         let mut sessions = self.sessions.write().await;
-        let state = sessions.get_mut(session_id).ok_or_else(|| {
-            crate::error::GqlError::Session(format!("session {session_id} not found"))
-        })?;
+        let state = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| not_found(session_id))?;
+        state.start_from(defaults);
+        Ok(())
+    }
 
-        match target {
-            super::backend::ResetTarget::All => *state = SessionState::default(),
-            super::backend::ResetTarget::Schema => state.schema = None,
-            super::backend::ResetTarget::Graph => state.graph = None,
-            super::backend::ResetTarget::TimeZone => state.time_zone_offset_minutes = 0,
-            super::backend::ResetTarget::Parameters => state.parameters.clear(),
+    /// Check that [`configure`](Self::configure) would not exceed a
+    /// database session limit, without applying the property.
+    ///
+    /// Call this before applying the property to the backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist, or if the property
+    /// selects a database whose session limit has been reached.
+    pub async fn check_configure(
+        &self,
+        session_id: &str,
+        property: &SessionProperty,
+    ) -> Result<(), GqlError> {
+        let sessions = self.sessions.read().await;
+        let mut state = sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| not_found(session_id))?;
+        state.apply(property);
+        self.database_limit_error(&sessions, session_id, &state)
+            .map_or(Ok(()), Err)
+    }
+
+    /// Reset session state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist, or if the reset
+    /// returns it to a database whose session limit has been reached.
+    pub async fn reset(&self, session_id: &str, target: ResetTarget) -> Result<(), GqlError> {
+        let mut sessions = self.sessions.write().await;
+        let mut state = sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| not_found(session_id))?;
+        state.apply_reset(target);
+        if let Some(err) = self.database_limit_error(&sessions, session_id, &state) {
+            return Err(err);
         }
+        sessions.insert(session_id.to_owned(), state);
         Ok(())
     }
 
+    /// Check that [`reset`](Self::reset) would not exceed a database
+    /// session limit, without resetting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist, or if the reset
+    /// returns it to a database whose session limit has been reached.
+    pub async fn check_reset(&self, session_id: &str, target: ResetTarget) -> Result<(), GqlError> {
+        let sessions = self.sessions.read().await;
+        let mut state = sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| not_found(session_id))?;
+        state.apply_reset(target);
+        self.database_limit_error(&sessions, session_id, &state)
+            .map_or(Ok(()), Err)
+    }
+
+    /// A `RESOURCE_EXHAUSTED` error if moving `session_id` to `state`
+    /// would put its database over its session limit.
+    fn database_limit_error(
+        &self,
+        sessions: &HashMap<String, SessionState>,
+        session_id: &str,
+        state: &SessionState,
+    ) -> Option<GqlError> {
+        let database = state.database();
+        let limit = self
+            .database_session_limits
+            .get(&database)
+            .copied()
+            .or(self.max_sessions_per_database)?;
+        let current = sessions.get(session_id);
+        if current.is_some_and(|s| s.database() == database) {
+            return None;
+        }
+        let count = sessions
+            .values()
+            .filter(|s| s.database() == database)
+            .count();
+        (count >= limit).then(|| {
+            GqlError::Grpc(tonic::Status::resource_exhausted(format!(
+                "session limit reached for database {database}"
+            )))
+        })
+    }
+
+    /// Get a session's parameters, or an empty map if the session does
+    /// not exist.
+    pub async fn parameters(&self, session_id: &str) -> HashMap<String, crate::types::Value> {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .map(|state| state.parameters.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get a snapshot of a session's state.
+    pub async fn state(&self, session_id: &str) -> Option<SessionState> {
+        self.sessions.read().await.get(session_id).cloned()
+    }
+
+    /// Record the principal a session authenticated as.
+    pub async fn set_principal(&self, session_id: &str, principal: Principal) {
+        if let Some(state) = self.sessions.write().await.get_mut(session_id) {
+            state.principal = Some(principal);
+        }
+    }
+
+    /// Record the client information a session sent at handshake.
+    pub async fn set_client_info(&self, session_id: &str, client_info: HashMap<String, String>) {
+        if let Some(state) = self.sessions.write().await.get_mut(session_id) {
+            state.client_info = client_info;
+        }
+    }
+
+    /// Checksum the row batches sent to a session.
+    pub async fn enable_row_batch_checksums(&self, session_id: &str) {
+        if let Some(state) = self.sessions.write().await.get_mut(session_id) {
+            state.row_batch_checksums = true;
+        }
+    }
+
+    /// Whether row batches sent to a session carry checksums.
+    pub async fn row_batch_checksums(&self, session_id: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions
+            .get(session_id)
+            .is_some_and(|s| s.row_batch_checksums)
+    }
+
+    /// Dictionary-encode the row batches sent to a session.
+    pub async fn enable_dictionary_encoding(&self, session_id: &str) {
+        if let Some(state) = self.sessions.write().await.get_mut(session_id) {
+            state.dictionary_encoding = true;
+        }
+    }
+
+    /// Whether row batches sent to a session are dictionary-encoded.
+    pub async fn dictionary_encoding(&self, session_id: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions
+            .get(session_id)
+            .is_some_and(|s| s.dictionary_encoding)
+    }
+
+    /// Send a session its row batches by column.
+    pub async fn enable_columnar_batches(&self, session_id: &str) {
+        if let Some(state) = self.sessions.write().await.get_mut(session_id) {
+            state.columnar_batches = true;
+        }
+    }
+
+    /// Whether row batches sent to a session are transposed into columns.
+    pub async fn columnar_batches(&self, session_id: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).is_some_and(|s| s.columnar_batches)
+    }
+
     /// Get the active transaction for a session.
     pub async fn active_transaction(&self, session_id: &str) -> Option<String> {
         let sessions = self.sessions.read().await;
@@ -185,6 +800,50 @@ impl SessionManager {
             .and_then(|s| s.active_transaction.clone())
     }
 
+    /// Remember `statement` as prepared in a session, returning its new
+    /// statement ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist or the system random
+    /// number generator fails.
+    pub async fn add_prepared_statement(
+        &self,
+        session_id: &str,
+        statement: &str,
+    ) -> Result<String, GqlError> {
+        let mut bytes = [0u8; 8];
+        getrandom::fill(&mut bytes)
+            .map_err(|e| GqlError::Session(format!("cannot generate statement ID: {e}")))?;
+        let id = format!("{:016x}", u64::from_le_bytes(bytes));
+        let mut sessions = self.sessions.write().await;
+        let state = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| not_found(session_id))?;
+        state
+            .prepared_statements
+            .insert(id.clone(), statement.to_owned());
+        Ok(id)
+    }
+
+    /// The text of a statement prepared in a session.
+    pub async fn prepared_statement(&self, session_id: &str, statement_id: &str) -> Option<String> {
+        let sessions = self.sessions.read().await;
+        sessions
+            .get(session_id)?
+            .prepared_statements
+            .get(statement_id)
+            .cloned()
+    }
+
+    /// Forget a prepared statement. Returns whether the session had it.
+    pub async fn remove_prepared_statement(&self, session_id: &str, statement_id: &str) -> bool {
+        let mut sessions = self.sessions.write().await;
+        sessions
+            .get_mut(session_id)
+            .is_some_and(|s| s.prepared_statements.remove(statement_id).is_some())
+    }
+
     /// Set the active transaction for a session.
     ///
     /// # Errors
@@ -194,18 +853,159 @@ impl SessionManager {
         &self,
         session_id: &str,
         transaction_id: Option<String>,
-    ) -> Result<(), crate::error::GqlError> {
+    ) -> Result<(), GqlError> {
         let mut sessions = self.sessions.write().await;
-        let state = sessions.get_mut(session_id).ok_or_else(|| {
-            crate::error::GqlError::Session(format!("session {session_id} not found"))
-        })?;
+        let state = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| GqlError::Session(format!("session {session_id} not found")))?;
         state.active_transaction = transaction_id;
         Ok(())
     }
 }
 
+fn not_found(session_id: &str) -> GqlError {
+    GqlError::Session(format!("session {session_id} not found"))
+}
+
 impl Default for SessionManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn resumption_tokens_are_single_use_and_expire() {
+        let sessions = SessionManager::new();
+        sessions.register("s1").await.unwrap();
+
+        let token = sessions.issue_resumption_token("s1").await.unwrap();
+        assert_eq!(token.len(), 64);
+        let window = Duration::from_secs(30);
+        assert_eq!(sessions.resume(&token, window).await.as_deref(), Some("s1"));
+        assert_eq!(sessions.resume(&token, window).await, None);
+
+        let token = sessions.issue_resumption_token("s1").await.unwrap();
+        tokio::time::advance(window * 2).await;
+        assert_eq!(sessions.resume(&token, window).await, None);
+
+        let token = sessions.issue_resumption_token("s1").await.unwrap();
+        sessions.remove("s1").await;
+        assert_eq!(sessions.resume(&token, window).await, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resumable_sessions_get_a_grace_period() {
+        let sessions = SessionManager::new().with_orphan_grace(Duration::from_secs(60));
+        sessions.register("orphan").await.unwrap();
+        sessions.register("idle").await.unwrap();
+        sessions.issue_resumption_token("orphan").await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(20)).await;
+        assert_eq!(sessions.reap_idle(Duration::from_secs(10)).await, ["idle"]);
+        assert!(sessions.exists("orphan").await);
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(
+            sessions.reap_idle(Duration::from_secs(10)).await,
+            ["orphan"]
+        );
+    }
+
+    #[tokio::test]
+    async fn database_limits_count_sessions_where_they_are() {
+        let sessions = SessionManager::with_capacity(10)
+            .with_max_sessions_per_database(2)
+            .with_database_session_limit("/hot", 1);
+        let graph = |name: &str| SessionProperty::Graph(name.to_owned());
+
+        sessions.register("a").await.unwrap();
+        sessions.configure("a", &graph("hot")).await.unwrap();
+        sessions.register("b").await.unwrap();
+        let err = sessions.check_configure("b", &graph("hot")).await;
+        assert!(matches!(err, Err(GqlError::Grpc(s)) if s.message().contains("/hot")));
+        assert!(sessions.configure("b", &graph("hot")).await.is_err());
+        assert_eq!(sessions.state("b").await.unwrap().database(), "/");
+
+        // Re-selecting the current database does not count twice.
+        sessions.configure("a", &graph("hot")).await.unwrap();
+
+        sessions.register("c").await.unwrap();
+        assert!(sessions.register("d").await.is_err());
+        assert!(sessions.check_reset("a", ResetTarget::Graph).await.is_err());
+        sessions.remove("c").await;
+        sessions.reset("a", ResetTarget::All).await.unwrap();
+        assert_eq!(sessions.state("a").await.unwrap().database(), "/");
+    }
+
+    #[tokio::test]
+    async fn selected_databases_are_kept_across_resets() {
+        let sessions = SessionManager::new().with_database_session_limit("sales//", 1);
+        let defaults = SessionDefaults::default();
+        sessions
+            .register_on_database("a", defaults.clone(), Some("sales".into()))
+            .await
+            .unwrap();
+        assert!(
+            sessions
+                .register_on_database("b", defaults, Some("sales".into()))
+                .await
+                .is_err()
+        );
+
+        let graph = SessionProperty::Graph("g".to_owned());
+        sessions.configure("a", &graph).await.unwrap();
+        assert_eq!(sessions.state("a").await.unwrap().database(), "sales//g");
+        sessions.reset("a", ResetTarget::All).await.unwrap();
+        let state = sessions.state("a").await.unwrap();
+        assert_eq!(state.current_database.as_deref(), Some("sales"));
+        assert_eq!(state.database(), "sales//");
+
+        let use_hr = SessionProperty::Database("hr".to_owned());
+        sessions.configure("a", &use_hr).await.unwrap();
+        assert_eq!(sessions.state("a").await.unwrap().database(), "hr//");
+    }
+
+    #[tokio::test]
+    async fn new_defaults_are_restored_on_reset() {
+        let sessions = SessionManager::new();
+        sessions.register("a").await.unwrap();
+        let defaults = SessionDefaults {
+            schema: Some("s".to_owned()),
+            graph: Some("g".to_owned()),
+        };
+        sessions
+            .start_from_defaults("a", defaults.clone())
+            .await
+            .unwrap();
+        assert_eq!(sessions.state("a").await.unwrap().database(), "s/g");
+
+        let graph = SessionProperty::Graph("other".to_owned());
+        sessions.configure("a", &graph).await.unwrap();
+        sessions.reset("a", ResetTarget::Graph).await.unwrap();
+        assert_eq!(sessions.state("a").await.unwrap().defaults, defaults);
+        assert_eq!(sessions.state("a").await.unwrap().database(), "s/g");
+    }
+
+    #[tokio::test]
+    async fn listed_sessions_show_running_statements() {
+        let sessions = SessionManager::new();
+        sessions.register("s1").await.unwrap();
+        sessions.register("s2").await.unwrap();
+        let first = sessions.start_statement("s1", "RETURN 1");
+        let second = sessions.start_statement("s1", "RETURN 2");
+
+        let list = sessions.list().await;
+        assert_eq!(list[0].session_id, "s1");
+        assert_eq!(list[0].running_statements, ["RETURN 1", "RETURN 2"]);
+        assert!(list[1].running_statements.is_empty());
+
+        drop(first);
+        assert_eq!(sessions.list().await[0].running_statements, ["RETURN 2"]);
+        drop(second);
+        assert!(sessions.list().await[0].running_statements.is_empty());
+    }
+}
@@ -3,16 +3,22 @@
 //! All errors are returned as gRPC status codes - no GQLSTATUS here.
 
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
 use tonic::{Request, Response, Status};
 
+use crate::columnar;
 use crate::proto;
 use crate::proto::session_service_server::SessionService;
 
-use super::auth::AuthValidator;
+use super::auth::{AuthValidator, Principal};
 use super::backend::{GqlBackend, ResetTarget, SessionConfig, SessionProperty};
-use super::{SessionManager, TransactionManager};
+use super::client_cert::{self, CLIENT_CERT_SUBJECT, ClientCertificate};
+use super::deadlines::{self, DefaultDeadlines};
+use super::drain::DrainHandle;
+use super::{SessionManager, SessionSnapshot, TransactionManager};
 
 /// Implementation of the `SessionService` gRPC service.
 pub struct SessionServiceImpl<B: GqlBackend> {
@@ -20,6 +26,13 @@ pub struct SessionServiceImpl<B: GqlBackend> {
     sessions: SessionManager,
     transactions: TransactionManager,
     auth: Option<Arc<dyn AuthValidator>>,
+    deadlines: DefaultDeadlines,
+    resumption_window: Option<Duration>,
+    drain: DrainHandle,
+    drain_grace: Duration,
+    shutdown: CancellationToken,
+    features: Vec<String>,
+    routing_table: Option<proto::RoutingTable>,
 }
 
 impl<B: GqlBackend> SessionServiceImpl<B> {
@@ -35,6 +48,199 @@ impl<B: GqlBackend> SessionServiceImpl<B> {
             sessions,
             transactions,
             auth,
+            deadlines: DefaultDeadlines::default(),
+            resumption_window: None,
+            drain: DrainHandle::default(),
+            drain_grace: Duration::ZERO,
+            shutdown: CancellationToken::new(),
+            features: Vec::new(),
+            routing_table: None,
+        }
+    }
+
+    /// Apply `deadlines` to handshakes and configuration requests that
+    /// carry no deadline of their own.
+    #[must_use]
+    pub fn with_deadlines(mut self, deadlines: DefaultDeadlines) -> Self {
+        self.deadlines = deadlines;
+        self
+    }
+
+    /// Issue resumption tokens at handshake, honouring them for sessions
+    /// that were active within `window`.
+    #[must_use]
+    pub fn with_resumption(mut self, window: Duration) -> Self {
+        self.resumption_window = Some(window);
+        self
+    }
+
+    /// Reject new sessions once `drain` starts, and announce it to
+    /// sessions listening for server events along with its `grace`
+    /// period.
+    #[must_use]
+    pub fn with_drain(mut self, drain: DrainHandle, grace: Duration) -> Self {
+        self.drain = drain;
+        self.drain_grace = grace;
+        self
+    }
+
+    /// End `ServerEvents` streams once `token` is cancelled, so they do
+    /// not hold up a graceful shutdown.
+    #[must_use]
+    pub fn with_shutdown_notice(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    /// List `features`, such as the optional services the server runs, in
+    /// the `ServerInfo` of every handshake.
+    #[must_use]
+    pub fn with_features(mut self, features: Vec<String>) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Advertise `writer` and `readers` in every handshake, so clients
+    /// can route reads and writes.
+    #[must_use]
+    pub fn with_routing_table(mut self, writer: String, readers: Vec<String>) -> Self {
+        self.routing_table = Some(proto::RoutingTable { writer, readers });
+        self
+    }
+
+    /// Fail with `UNAVAILABLE` if the server is draining.
+    fn check_not_draining(&self) -> Result<(), Status> {
+        if self.drain.is_draining() {
+            tracing::warn!("rejected new session while draining");
+            return Err(Status::unavailable("server is draining"));
+        }
+        Ok(())
+    }
+
+    /// Validate `credentials`, or failing those the client `certificate`
+    /// of a mutual TLS connection, if the server requires authentication,
+    /// returning the principal they authenticate.
+    async fn authenticate(
+        &self,
+        credentials: Option<&proto::AuthCredentials>,
+        certificate: Option<&ClientCertificate>,
+    ) -> Result<Option<Principal>, Status> {
+        let Some(ref auth) = self.auth else {
+            return Ok(certificate.map(Principal::from_certificate));
+        };
+        if let Some(creds) = credentials {
+            let principal = auth.authenticate(creds).await.map_err(|_| {
+                tracing::warn!("authentication failed");
+                Status::unauthenticated("invalid credentials")
+            })?;
+            Ok(Some(principal))
+        } else if let Some(certificate) = certificate {
+            let principal = auth.validate_tls(certificate).await.map_err(|_| {
+                tracing::warn!(subject = %certificate.subject, "client certificate rejected");
+                Status::unauthenticated("client certificate rejected")
+            })?;
+            Ok(Some(principal))
+        } else {
+            tracing::warn!("handshake missing credentials");
+            Err(Status::unauthenticated("credentials required"))
+        }
+    }
+
+    /// Roll back every transaction open in a session.
+    async fn rollback_transactions(&self, session_id: &str, reason: &str) {
+        let active_txns = self.transactions.remove_for_session(session_id).await;
+        for tx_id in &active_txns {
+            tracing::info!(session_id, transaction_id = %tx_id, "rolling back transaction on {reason}");
+            let _ = self
+                .backend
+                .rollback(
+                    &super::SessionHandle(session_id.to_owned()),
+                    &super::TransactionHandle(tx_id.clone()),
+                )
+                .await;
+        }
+    }
+
+    /// Reattach to the session `token` was issued for, if it is still
+    /// live. Open transactions are kept if `keep_transaction` is set, and
+    /// rolled back otherwise.
+    #[allow(clippy::fn_params_excessive_bools)]
+    async fn reattach(
+        &self,
+        token: &str,
+        window: Duration,
+        row_batch_checksums: bool,
+        dictionary_encoding: bool,
+        columnar_batches: bool,
+        keep_transaction: bool,
+    ) -> Option<proto::HandshakeResponse> {
+        let session_id = self.sessions.resume(token, window).await?;
+        if row_batch_checksums {
+            self.sessions.enable_row_batch_checksums(&session_id).await;
+        }
+        if dictionary_encoding {
+            self.sessions.enable_dictionary_encoding(&session_id).await;
+        }
+        if columnar_batches {
+            self.sessions.enable_columnar_batches(&session_id).await;
+        }
+        if !keep_transaction {
+            self.rollback_transactions(&session_id, "resume").await;
+            let _ = self
+                .sessions
+                .set_active_transaction(&session_id, None)
+                .await;
+        }
+        let state = self.sessions.state(&session_id).await?;
+        let token = self
+            .sessions
+            .issue_resumption_token(&session_id)
+            .await
+            .inspect_err(|e| tracing::warn!("{e}"))
+            .ok();
+
+        tracing::info!(session_id, "session resumed");
+        Some(proto::HandshakeResponse {
+            resumption_token: token,
+            resumed: true,
+            row_batch_checksums: state.row_batch_checksums,
+            dictionary_encoding: state.dictionary_encoding,
+            default_schema: state.schema,
+            default_graph: state.graph,
+            transaction_id: state.active_transaction,
+            ..self.handshake_response(session_id, state.columnar_batches)
+        })
+    }
+
+    /// A handshake response for `session_id` with the server's details
+    /// and routing table, listing the columnar batches feature if the
+    /// session negotiated it.
+    fn handshake_response(
+        &self,
+        session_id: String,
+        columnar_batches: bool,
+    ) -> proto::HandshakeResponse {
+        let mut features = self.features.clone();
+        if columnar_batches {
+            features.push(columnar::FEATURE.to_owned());
+        }
+        proto::HandshakeResponse {
+            protocol_version: 1,
+            session_id,
+            server_info: Some(proto::ServerInfo {
+                name: "gql-wire-protocol".to_owned(),
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+                features,
+            }),
+            limits: std::collections::HashMap::new(),
+            default_schema: None,
+            default_graph: None,
+            resumption_token: None,
+            resumed: false,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            transaction_id: None,
+            routing_table: self.routing_table.clone(),
         }
     }
 }
@@ -46,56 +252,194 @@ impl<B: GqlBackend> SessionService for SessionServiceImpl<B> {
         &self,
         request: Request<proto::HandshakeRequest>,
     ) -> Result<Response<proto::HandshakeResponse>, Status> {
+        self.check_not_draining()?;
+        let deadline = DefaultDeadlines::resolve(request.metadata(), self.deadlines.handshake);
+        let certificate = client_cert::from_request(&request);
         let req = request.into_inner();
 
-        if let Some(ref auth) = self.auth {
-            if let Some(ref creds) = req.credentials {
-                auth.validate(creds).await.map_err(|_| {
-                    tracing::warn!("authentication failed");
-                    Status::unauthenticated("invalid credentials")
-                })?;
-            } else {
-                tracing::warn!("handshake missing credentials");
-                return Err(Status::unauthenticated("credentials required"));
+        let principal = self
+            .authenticate(req.credentials.as_ref(), certificate.as_ref())
+            .await?;
+
+        if let (Some(window), Some(token)) = (self.resumption_window, &req.resumption_token) {
+            let resumed = self
+                .reattach(
+                    token,
+                    window,
+                    req.row_batch_checksums,
+                    req.dictionary_encoding,
+                    req.columnar_batches,
+                    false,
+                )
+                .await;
+            if let Some(response) = resumed {
+                if let Some(principal) = principal {
+                    self.sessions
+                        .set_principal(&response.session_id, principal)
+                        .await;
+                }
+                return Ok(Response::new(response));
             }
+            tracing::info!("resumption token rejected, creating a new session");
         }
 
+        let mut client_info = req.client_info;
+        match certificate {
+            Some(certificate) => {
+                client_info.insert(CLIENT_CERT_SUBJECT.to_owned(), certificate.subject);
+            }
+            None => {
+                client_info.remove(CLIENT_CERT_SUBJECT);
+            }
+        }
         let config = SessionConfig {
             protocol_version: req.protocol_version,
-            client_info: req.client_info,
+            client_info,
+            database: req.database,
         };
 
-        let handle = self
-            .backend
-            .create_session(&config)
+        let (defaults, handle) = deadlines::within(deadline, "handshake", async {
+            let defaults = self
+                .backend
+                .session_defaults(config.database.as_deref())
+                .await?;
+            let handle = self.backend.create_session(&config).await?;
+            Ok::<_, crate::error::GqlError>((defaults, handle))
+        })
+        .await?
+        .map_err(|e| e.to_grpc_status())?;
+
+        if let Err(e) = self
+            .sessions
+            .register_on_database(&handle.0, defaults.clone(), config.database.clone())
             .await
-            .map_err(|e| e.to_grpc_status())?;
-
-        if let Err(e) = self.sessions.register(&handle.0).await {
+        {
             let _ = self.backend.close_session(&handle).await;
             tracing::warn!("session limit reached");
-            return Err(Status::resource_exhausted(e.to_string()));
+            return Err(match e {
+                crate::error::GqlError::Grpc(status) => status,
+                e => Status::resource_exhausted(e.to_string()),
+            });
         }
 
+        self.sessions
+            .set_client_info(&handle.0, config.client_info)
+            .await;
+        if let Some(principal) = principal {
+            self.sessions.set_principal(&handle.0, principal).await;
+        }
+        if req.row_batch_checksums {
+            self.sessions.enable_row_batch_checksums(&handle.0).await;
+        }
+        if req.dictionary_encoding {
+            self.sessions.enable_dictionary_encoding(&handle.0).await;
+        }
+        if req.columnar_batches {
+            self.sessions.enable_columnar_batches(&handle.0).await;
+        }
         tracing::info!(session_id = %handle.0, "session created");
 
+        let resumption_token = match self.resumption_window {
+            Some(_) => self
+                .sessions
+                .issue_resumption_token(&handle.0)
+                .await
+                .inspect_err(|e| tracing::warn!("{e}"))
+                .ok(),
+            None => None,
+        };
+
         Ok(Response::new(proto::HandshakeResponse {
-            protocol_version: 1,
-            session_id: handle.0,
-            server_info: Some(proto::ServerInfo {
-                name: "gql-wire-protocol".to_owned(),
-                version: env!("CARGO_PKG_VERSION").to_owned(),
-                features: Vec::new(),
-            }),
-            limits: std::collections::HashMap::new(),
+            resumption_token,
+            row_batch_checksums: req.row_batch_checksums,
+            dictionary_encoding: req.dictionary_encoding,
+            default_schema: defaults.schema,
+            default_graph: defaults.graph,
+            ..self.handshake_response(handle.0, req.columnar_batches)
         }))
     }
 
+    #[tracing::instrument(skip(self, request))]
+    async fn resume(
+        &self,
+        request: Request<proto::ResumeRequest>,
+    ) -> Result<Response<proto::HandshakeResponse>, Status> {
+        self.check_not_draining()?;
+        let certificate = client_cert::from_request(&request);
+        let req = request.into_inner();
+        let principal = self
+            .authenticate(req.credentials.as_ref(), certificate.as_ref())
+            .await?;
+
+        let Some(window) = self.resumption_window else {
+            return Err(Status::failed_precondition(
+                "session resumption is not enabled",
+            ));
+        };
+        let response = self
+            .reattach(
+                &req.resumption_token,
+                window,
+                req.row_batch_checksums,
+                req.dictionary_encoding,
+                req.columnar_batches,
+                true,
+            )
+            .await
+            .ok_or_else(|| Status::not_found("resumption token is unknown or expired"))?;
+        if let Some(principal) = principal {
+            self.sessions
+                .set_principal(&response.session_id, principal)
+                .await;
+        }
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self, request), fields(session_id))]
+    async fn refresh_credentials(
+        &self,
+        request: Request<proto::RefreshCredentialsRequest>,
+    ) -> Result<Response<proto::RefreshCredentialsResponse>, Status> {
+        let certificate = client_cert::from_request(&request);
+        let req = request.into_inner();
+        let session_id = &req.session_id;
+        tracing::Span::current().record("session_id", session_id);
+
+        let Some(state) = self.sessions.state(session_id).await else {
+            return Err(Status::not_found(format!("session {session_id} not found")));
+        };
+        self.sessions.touch(session_id).await;
+
+        let Some(principal) = self
+            .authenticate(req.credentials.as_ref(), certificate.as_ref())
+            .await?
+        else {
+            return Ok(Response::new(proto::RefreshCredentialsResponse {}));
+        };
+        // Refreshing renews the session's credentials; it must not switch
+        // the session to another identity.
+        if state
+            .principal
+            .as_ref()
+            .is_some_and(|p| p.name != principal.name)
+        {
+            tracing::warn!(session_id, "refreshed credentials name another principal");
+            return Err(Status::permission_denied(
+                "credentials belong to a different principal",
+            ));
+        }
+        self.sessions.set_principal(session_id, principal).await;
+        tracing::info!(session_id, "session credentials refreshed");
+
+        Ok(Response::new(proto::RefreshCredentialsResponse {}))
+    }
+
     #[tracing::instrument(skip(self, request), fields(session_id))]
     async fn configure(
         &self,
         request: Request<proto::ConfigureRequest>,
     ) -> Result<Response<proto::ConfigureResponse>, Status> {
+        let deadline = DefaultDeadlines::resolve(request.metadata(), self.deadlines.configure);
         let req = request.into_inner();
         let session_id = &req.session_id;
         tracing::Span::current().record("session_id", session_id);
@@ -117,20 +461,46 @@ impl<B: GqlBackend> SessionService for SessionServiceImpl<B> {
                     .value
                     .map_or(crate::types::Value::Null, crate::types::Value::from),
             },
+            Some(proto::configure_request::Property::Database(d)) => SessionProperty::Database(d),
             None => return Err(Status::invalid_argument("no property specified")),
         };
 
-        self.backend
-            .configure_session(&super::SessionHandle(session_id.clone()), property.clone())
+        self.sessions
+            .check_configure(session_id, &property)
             .await
             .map_err(|e| e.to_grpc_status())?;
 
+        let handle = super::SessionHandle(session_id.clone());
+        let defaults = deadlines::within(deadline, "configure", async {
+            let defaults = match &property {
+                SessionProperty::Database(d) => Some(self.backend.session_defaults(Some(d)).await?),
+                _ => None,
+            };
+            self.backend
+                .configure_session(&handle, property.clone())
+                .await?;
+            Ok::<_, crate::error::GqlError>(defaults)
+        })
+        .await?
+        .map_err(|e| e.to_grpc_status())?;
+
         self.sessions
             .configure(session_id, &property)
             .await
             .map_err(|e| e.to_grpc_status())?;
 
-        Ok(Response::new(proto::ConfigureResponse {}))
+        let Some(defaults) = defaults else {
+            return Ok(Response::new(proto::ConfigureResponse::default()));
+        };
+        self.sessions
+            .start_from_defaults(session_id, defaults.clone())
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+
+        Ok(Response::new(proto::ConfigureResponse {
+            default_schema: defaults.schema,
+            default_graph: defaults.graph,
+        }))
     }
 
     #[tracing::instrument(skip(self, request), fields(session_id))]
@@ -156,6 +526,11 @@ impl<B: GqlBackend> SessionService for SessionServiceImpl<B> {
             Err(_) => return Err(Status::invalid_argument("invalid reset target")),
         };
 
+        self.sessions
+            .check_reset(session_id, target)
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+
         self.backend
             .reset_session(&super::SessionHandle(session_id.clone()), target)
             .await
@@ -182,18 +557,7 @@ impl<B: GqlBackend> SessionService for SessionServiceImpl<B> {
             return Err(Status::not_found(format!("session {session_id} not found")));
         }
 
-        // Roll back any active transactions
-        let active_txns = self.transactions.remove_for_session(session_id).await;
-        for tx_id in &active_txns {
-            tracing::info!(session_id, transaction_id = %tx_id, "rolling back transaction on close");
-            let _ = self
-                .backend
-                .rollback(
-                    &super::SessionHandle(session_id.clone()),
-                    &super::TransactionHandle(tx_id.clone()),
-                )
-                .await;
-        }
+        self.rollback_transactions(session_id, "close").await;
 
         self.backend
             .close_session(&super::SessionHandle(session_id.clone()))
@@ -207,6 +571,23 @@ impl<B: GqlBackend> SessionService for SessionServiceImpl<B> {
         Ok(Response::new(proto::CloseResponse {}))
     }
 
+    #[tracing::instrument(skip(self, request), fields(session_id))]
+    async fn get_session_state(
+        &self,
+        request: Request<proto::GetSessionStateRequest>,
+    ) -> Result<Response<proto::GetSessionStateResponse>, Status> {
+        let req = request.into_inner();
+        tracing::Span::current().record("session_id", &req.session_id);
+
+        let state =
+            self.sessions.state(&req.session_id).await.ok_or_else(|| {
+                Status::not_found(format!("session {} not found", req.session_id))
+            })?;
+        self.sessions.touch(&req.session_id).await;
+
+        Ok(Response::new(SessionSnapshot::from(&state).into()))
+    }
+
     #[tracing::instrument(skip(self, request), fields(session_id))]
     async fn ping(
         &self,
@@ -229,4 +610,49 @@ impl<B: GqlBackend> SessionService for SessionServiceImpl<B> {
 
         Ok(Response::new(proto::PongResponse { timestamp }))
     }
+
+    type ServerEventsStream = ReceiverStream<Result<proto::ServerEvent, Status>>;
+
+    #[tracing::instrument(skip(self, request), fields(session_id))]
+    async fn server_events(
+        &self,
+        request: Request<proto::ServerEventsRequest>,
+    ) -> Result<Response<Self::ServerEventsStream>, Status> {
+        let req = request.into_inner();
+        tracing::Span::current().record("session_id", &req.session_id);
+
+        if !self.sessions.exists(&req.session_id).await {
+            return Err(Status::not_found(format!(
+                "session {} not found",
+                req.session_id
+            )));
+        }
+        self.sessions.touch(&req.session_id).await;
+
+        let drain = self.drain.clone();
+        let shutdown = self.shutdown.clone();
+        let notice = proto::ServerEvent {
+            event: Some(proto::server_event::Event::Draining(proto::DrainNotice {
+                grace_period_ms: u32::try_from(self.drain_grace.as_millis()).unwrap_or(u32::MAX),
+            })),
+        };
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            tokio::select! {
+                () = drain.started() => {}
+                () = shutdown.cancelled() => return,
+                () = tx.closed() => return,
+            }
+            if tx.send(Ok(notice)).await.is_err() {
+                return;
+            }
+            // Hold the stream open until the drain closes the session.
+            tokio::select! {
+                () = drain.drained() => {}
+                () = shutdown.cancelled() => {}
+                () = tx.closed() => {}
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
 }
@@ -0,0 +1,167 @@
+//! Size limits on execute requests and their results.
+//!
+//! Oversized statements and parameter maps are rejected before they reach
+//! the backend, so a single request cannot make the parser allocate
+//! without bound. Results that grow past their row or byte quota are cut
+//! off, so a runaway query cannot stream without bound either.
+
+use crate::proto;
+use crate::status;
+
+/// Limits on the statements a server accepts.
+///
+/// A `None` field leaves that dimension unbounded. Requests over a limit
+/// end with a [`STATEMENT_TOO_LARGE`](status::STATEMENT_TOO_LARGE)
+/// summary naming the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementLimits {
+    /// Maximum statement text length, in bytes.
+    pub max_statement_length: Option<usize>,
+    /// Maximum number of parameters.
+    pub max_parameters: Option<usize>,
+}
+
+impl StatementLimits {
+    /// The status to reject a statement with, if it exceeds a limit.
+    pub(crate) fn check(&self, statement: &str, parameters: usize) -> Option<proto::GqlStatus> {
+        if let Some(max) = self.max_statement_length {
+            if statement.len() > max {
+                return Some(status::error(
+                    status::STATEMENT_TOO_LARGE,
+                    format!(
+                        "statement is {} bytes, over the limit of {max}",
+                        statement.len()
+                    ),
+                ));
+            }
+        }
+        if let Some(max) = self.max_parameters {
+            if parameters > max {
+                return Some(status::error(
+                    status::STATEMENT_TOO_LARGE,
+                    format!("statement has {parameters} parameters, over the limit of {max}"),
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Limits on the result of each statement.
+///
+/// A `None` field leaves that dimension unbounded. Requests may ask for
+/// lower limits, but not higher ones. A result that would go over a limit
+/// ends instead with a [`RESULT_TOO_LARGE`](status::RESULT_TOO_LARGE)
+/// summary naming the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// Maximum rows per result.
+    pub max_rows: Option<u64>,
+    /// Maximum encoded size of a result's row batches, in bytes.
+    pub max_bytes: Option<u64>,
+}
+
+impl ResultLimits {
+    /// A quota for one result, lowered to the limits its request asked
+    /// for. Requested limits of 0 are ignored.
+    pub(crate) fn quota(self, max_rows: Option<u64>, max_bytes: Option<u64>) -> ResultQuota {
+        let lower = |server: Option<u64>, requested: Option<u64>| match (
+            server,
+            requested.filter(|&n| n > 0),
+        ) {
+            (Some(server), Some(requested)) => Some(server.min(requested)),
+            (server, requested) => server.or(requested),
+        };
+        ResultQuota {
+            limits: Self {
+                max_rows: lower(self.max_rows, max_rows),
+                max_bytes: lower(self.max_bytes, max_bytes),
+            },
+            rows: 0,
+            bytes: 0,
+        }
+    }
+}
+
+/// What one result has used of its limits.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResultQuota {
+    limits: ResultLimits,
+    rows: u64,
+    bytes: u64,
+}
+
+impl ResultQuota {
+    /// Charge a batch of `rows` rows and `bytes` bytes, or return the
+    /// status to end the result with if the batch would go over a limit.
+    pub(crate) fn charge(&mut self, rows: u64, bytes: u64) -> Option<proto::GqlStatus> {
+        let rows = self.rows.saturating_add(rows);
+        let bytes = self.bytes.saturating_add(bytes);
+        if let Some(max) = self.limits.max_rows.filter(|&max| rows > max) {
+            return Some(status::error(
+                status::RESULT_TOO_LARGE,
+                format!("result exceeds the limit of {max} rows"),
+            ));
+        }
+        if let Some(max) = self.limits.max_bytes.filter(|&max| bytes > max) {
+            return Some(status::error(
+                status::RESULT_TOO_LARGE,
+                format!("result exceeds the limit of {max} bytes"),
+            ));
+        }
+        self.rows = rows;
+        self.bytes = bytes;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_only_what_exceeds_a_limit() {
+        let limits = StatementLimits {
+            max_statement_length: Some(10),
+            max_parameters: Some(1),
+        };
+        assert!(limits.check("RETURN 1", 1).is_none());
+
+        let status = limits.check("RETURN 12345", 0).unwrap();
+        assert_eq!(status.code, status::STATEMENT_TOO_LARGE);
+        assert!(status.message.contains("12 bytes"));
+
+        let status = limits.check("RETURN 1", 2).unwrap();
+        assert!(status.message.contains("2 parameters"));
+
+        assert!(
+            StatementLimits::default()
+                .check(&"x".repeat(1 << 20), 1000)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn results_stop_at_the_lower_of_server_and_request_limits() {
+        let limits = ResultLimits {
+            max_rows: Some(10),
+            max_bytes: None,
+        };
+        let mut quota = limits.quota(Some(5), Some(0));
+        assert!(quota.charge(5, 1 << 20).is_none());
+        let status = quota.charge(1, 0).unwrap();
+        assert_eq!(status.code, status::RESULT_TOO_LARGE);
+        assert!(status.message.contains("5 rows"));
+
+        let mut quota = limits.quota(None, Some(100));
+        assert!(quota.charge(1, 100).is_none());
+        assert!(quota.charge(0, 1).unwrap().message.contains("100 bytes"));
+
+        assert!(
+            ResultLimits::default()
+                .quota(None, None)
+                .charge(u64::MAX, 1)
+                .is_none()
+        );
+    }
+}
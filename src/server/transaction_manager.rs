@@ -2,12 +2,15 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::RwLock;
 
 use crate::error::GqlError;
 use crate::proto;
 
+use super::TransactionConfig;
+
 /// State of an active transaction.
 #[derive(Debug, Clone)]
 pub struct TransactionState {
@@ -15,12 +18,22 @@ pub struct TransactionState {
     pub session_id: String,
     /// Transaction access mode.
     pub mode: proto::TransactionMode,
+    /// Isolation level the transaction was begun with.
+    pub isolation_level: proto::IsolationLevel,
+    /// How long the transaction may stay open before it is rolled back.
+    pub timeout: Option<Duration>,
+    /// Whether the transaction has been prepared for a two-phase commit.
+    /// Prepared transactions no longer count as their session's active
+    /// transaction and outlive the session.
+    pub prepared: bool,
 }
 
 /// Manages transaction state across all sessions.
 ///
 /// Enforces the GQL constraint that at most one transaction
-/// can be active per session.
+/// can be active per session. Prepared transactions are tracked
+/// until they are committed or rolled back with the `*_prepared`
+/// calls.
 #[derive(Debug, Clone)]
 pub struct TransactionManager {
     transactions: Arc<RwLock<HashMap<String, TransactionState>>>,
@@ -45,11 +58,29 @@ impl TransactionManager {
         transaction_id: &str,
         session_id: &str,
         mode: proto::TransactionMode,
+    ) -> Result<(), GqlError> {
+        self.register_with(transaction_id, session_id, mode.into())
+            .await
+    }
+
+    /// Register a new transaction for a session with the characteristics
+    /// it was begun with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session already has an active transaction.
+    pub async fn register_with(
+        &self,
+        transaction_id: &str,
+        session_id: &str,
+        config: TransactionConfig,
     ) -> Result<(), GqlError> {
         let mut txns = self.transactions.write().await;
 
         // Check no active transaction for this session
-        let has_active = txns.values().any(|t| t.session_id == session_id);
+        let has_active = txns
+            .values()
+            .any(|t| t.session_id == session_id && !t.prepared);
         if has_active {
             return Err(GqlError::Transaction(
                 "session already has an active transaction".to_owned(),
@@ -60,7 +91,10 @@ impl TransactionManager {
             transaction_id.to_owned(),
             TransactionState {
                 session_id: session_id.to_owned(),
-                mode,
+                mode: config.mode,
+                isolation_level: config.isolation_level,
+                timeout: config.timeout,
+                prepared: false,
             },
         );
         Ok(())
@@ -77,14 +111,28 @@ impl TransactionManager {
             .ok_or_else(|| GqlError::Transaction(format!("transaction {transaction_id} not found")))
     }
 
+    /// Remove a transaction whose timeout has passed, unless it has
+    /// ended or been prepared in the meantime.
+    pub async fn expire(&self, transaction_id: &str) -> Option<TransactionState> {
+        let mut txns = self.transactions.write().await;
+        if txns.get(transaction_id)?.prepared {
+            return None;
+        }
+        txns.remove(transaction_id)
+    }
+
     /// Validate that a transaction exists and belongs to the given session.
     ///
     /// # Errors
     ///
-    /// Returns an error if the transaction does not exist or belongs to another session.
+    /// Returns an error if the transaction does not exist, belongs to another
+    /// session, or has been prepared.
     pub async fn validate(&self, transaction_id: &str, session_id: &str) -> Result<(), GqlError> {
         let txns = self.transactions.read().await;
         match txns.get(transaction_id) {
+            Some(state) if state.prepared => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} is prepared"
+            ))),
             Some(state) if state.session_id == session_id => Ok(()),
             Some(_) => Err(GqlError::Transaction(
                 "transaction does not belong to this session".to_owned(),
@@ -95,12 +143,101 @@ impl TransactionManager {
         }
     }
 
-    /// Remove all transactions for a session (on session close).
+    /// Mark a session's transaction as prepared for a two-phase commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction does not exist, belongs to another
+    /// session, or is already prepared.
+    pub async fn prepare(&self, transaction_id: &str, session_id: &str) -> Result<(), GqlError> {
+        let mut txns = self.transactions.write().await;
+        match txns.get_mut(transaction_id) {
+            Some(state) if state.prepared => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} is already prepared"
+            ))),
+            Some(state) if state.session_id == session_id => {
+                state.prepared = true;
+                Ok(())
+            }
+            Some(_) => Err(GqlError::Transaction(
+                "transaction does not belong to this session".to_owned(),
+            )),
+            None => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} not found"
+            ))),
+        }
+    }
+
+    /// Check that a transaction exists and is prepared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction does not exist or is not prepared.
+    pub async fn validate_prepared(&self, transaction_id: &str) -> Result<(), GqlError> {
+        match self.transactions.read().await.get(transaction_id) {
+            Some(state) if state.prepared => Ok(()),
+            Some(_) => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} is not prepared"
+            ))),
+            None => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} not found"
+            ))),
+        }
+    }
+
+    /// Remove a prepared transaction so that only one caller commits or
+    /// rolls it back. Put it back with [`restore`](Self::restore) if that
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction does not exist, is not
+    /// prepared, or is already being committed or rolled back.
+    pub async fn take_prepared(&self, transaction_id: &str) -> Result<TransactionState, GqlError> {
+        let mut txns = self.transactions.write().await;
+        match txns.get(transaction_id) {
+            Some(state) if state.prepared => txns.remove(transaction_id).ok_or_else(|| {
+                GqlError::Transaction(format!("transaction {transaction_id} not found"))
+            }),
+            Some(_) => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} is not prepared"
+            ))),
+            None => Err(GqlError::Transaction(format!(
+                "transaction {transaction_id} not found"
+            ))),
+        }
+    }
+
+    /// Put back a transaction taken with
+    /// [`take_prepared`](Self::take_prepared).
+    pub async fn restore(&self, transaction_id: &str, state: TransactionState) {
+        self.transactions
+            .write()
+            .await
+            .insert(transaction_id.to_owned(), state);
+    }
+
+    /// IDs of all prepared transactions, for coordinator recovery.
+    pub async fn prepared(&self) -> Vec<String> {
+        let txns = self.transactions.read().await;
+        txns.iter()
+            .filter(|(_, state)| state.prepared)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Number of transactions that are open and not prepared.
+    pub async fn active_count(&self) -> usize {
+        let txns = self.transactions.read().await;
+        txns.values().filter(|state| !state.prepared).count()
+    }
+
+    /// Remove all unprepared transactions for a session (on session close).
     pub async fn remove_for_session(&self, session_id: &str) -> Vec<String> {
         let mut txns = self.transactions.write().await;
         let to_remove: Vec<String> = txns
             .iter()
-            .filter(|(_, state)| state.session_id == session_id)
+            .filter(|(_, state)| state.session_id == session_id && !state.prepared)
             .map(|(id, _)| id.clone())
             .collect();
         for id in &to_remove {
@@ -168,4 +305,59 @@ mod tests {
         let result = tm.validate("tx1", "sess1").await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn prepared_transactions_outlive_their_session() {
+        let tm = TransactionManager::new();
+        tm.register("tx1", "sess1", proto::TransactionMode::ReadWrite)
+            .await
+            .unwrap();
+        assert!(tm.validate_prepared("tx1").await.is_err());
+
+        tm.prepare("tx1", "sess1").await.unwrap();
+        assert!(tm.prepare("tx1", "sess1").await.is_err());
+        assert!(tm.validate("tx1", "sess1").await.is_err());
+        assert_eq!(tm.prepared().await, vec!["tx1"]);
+
+        // The session may begin another transaction, and closing it leaves
+        // the prepared one in place.
+        tm.register("tx2", "sess1", proto::TransactionMode::ReadWrite)
+            .await
+            .unwrap();
+        assert_eq!(tm.remove_for_session("sess1").await, vec!["tx2"]);
+        tm.validate_prepared("tx1").await.unwrap();
+        assert!(tm.remove("tx1").await.unwrap().prepared);
+    }
+
+    #[tokio::test]
+    async fn prepared_transactions_are_taken_once() {
+        let tm = TransactionManager::new();
+        tm.register("tx1", "sess1", proto::TransactionMode::ReadWrite)
+            .await
+            .unwrap();
+        assert!(tm.take_prepared("tx1").await.is_err());
+        tm.prepare("tx1", "sess1").await.unwrap();
+
+        let state = tm.take_prepared("tx1").await.unwrap();
+        assert!(tm.take_prepared("tx1").await.is_err());
+        tm.restore("tx1", state).await;
+        assert!(tm.take_prepared("tx1").await.unwrap().prepared);
+    }
+
+    #[tokio::test]
+    async fn expiring_skips_prepared_transactions() {
+        let tm = TransactionManager::new();
+        tm.register("tx1", "sess1", proto::TransactionMode::ReadWrite)
+            .await
+            .unwrap();
+        tm.register("tx2", "sess2", proto::TransactionMode::ReadWrite)
+            .await
+            .unwrap();
+        tm.prepare("tx2", "sess2").await.unwrap();
+
+        assert_eq!(tm.expire("tx1").await.unwrap().session_id, "sess1");
+        assert!(tm.expire("tx1").await.is_none());
+        assert!(tm.expire("tx2").await.is_none());
+        tm.validate_prepared("tx2").await.unwrap();
+    }
 }
@@ -35,6 +35,14 @@ pub const WARNING_GRAPH_TYPE_NOT_FOUND: &str = "01G04";
 /// Null value eliminated in set function.
 pub const WARNING_NULL_ELIMINATED: &str = "01G11";
 
+/// The statement ran longer than the server's slow-query threshold.
+/// Implementation-defined subclass.
+pub const WARNING_SLOW_QUERY: &str = "01S01";
+
+/// The result was streamed more slowly to stay within the server's row
+/// rate limit. Implementation-defined subclass.
+pub const WARNING_THROTTLED: &str = "01S02";
+
 // ============================================================================
 // No data (class 02)
 // ============================================================================
@@ -49,6 +57,10 @@ pub const NO_DATA: &str = "02000";
 /// Informational (no subclass).
 pub const INFORMATIONAL: &str = "03000";
 
+/// The server is shutting down and ended the result early; the statement
+/// can be retried on another server. Implementation-defined subclass.
+pub const SERVER_SHUTDOWN: &str = "03S01";
+
 // ============================================================================
 // Connection exceptions (class 08)
 // ============================================================================
@@ -138,6 +150,10 @@ pub const RECORD_MISMATCH: &str = "22G0U";
 /// Malformed path.
 pub const MALFORMED_PATH: &str = "22G0Z";
 
+/// The result exceeded the rows or bytes a statement may return.
+/// Implementation-defined subclass.
+pub const RESULT_TOO_LARGE: &str = "22S01";
+
 // ============================================================================
 // Transaction state (class 25)
 // ============================================================================
@@ -171,9 +187,16 @@ pub const INVALID_TRANSACTION_TERMINATION: &str = "2D000";
 /// Transaction rollback.
 pub const TRANSACTION_ROLLBACK: &str = "40000";
 
+/// Serialization failure (conflicting concurrent transaction).
+pub const SERIALIZATION_FAILURE: &str = "40001";
+
 /// Statement completion unknown.
 pub const COMPLETION_UNKNOWN: &str = "40003";
 
+/// The statement outlasted its timeout and was aborted.
+/// Implementation-defined subclass.
+pub const STATEMENT_TIMEOUT: &str = "40S01";
+
 // ============================================================================
 // Syntax / access (class 42)
 // ============================================================================
@@ -223,6 +246,18 @@ pub const INVALID_GRAPH_MODIFICATION: &str = "42014";
 /// Procedure not found.
 pub const PROCEDURE_NOT_FOUND: &str = "42015";
 
+/// The statement text or its parameters exceed a server limit.
+/// Implementation-defined subclass.
+pub const STATEMENT_TOO_LARGE: &str = "42S01";
+
+/// No prepared statement with the given ID exists in the session.
+/// Implementation-defined subclass.
+pub const PREPARED_STATEMENT_NOT_FOUND: &str = "42S02";
+
+/// The session's principal may not perform the statement.
+/// Implementation-defined subclass.
+pub const PERMISSION_DENIED: &str = "42S03";
+
 // ============================================================================
 // Dependent object errors (class G1)
 // ============================================================================
@@ -305,6 +340,25 @@ pub fn warning(code: &str, message: impl Into<String>) -> proto::GqlStatus {
     }
 }
 
+/// Create a warning `GqlStatus` about the part of the statement at
+/// `position`.
+#[must_use]
+pub fn warning_at(
+    code: &str,
+    message: impl Into<String>,
+    position: proto::SourcePosition,
+) -> proto::GqlStatus {
+    proto::GqlStatus {
+        code: code.to_owned(),
+        message: message.into(),
+        diagnostic: Some(proto::DiagnosticRecord {
+            position: Some(position),
+            ..Default::default()
+        }),
+        cause: None,
+    }
+}
+
 /// Create an informational `GqlStatus` with the given code and message.
 #[must_use]
 pub fn informational(code: &str, message: impl Into<String>) -> proto::GqlStatus {
@@ -332,6 +386,7 @@ pub fn error_with_diagnostic(
             operation_code,
             current_schema: None,
             invalid_reference: None,
+            position: None,
         }),
         cause: None,
     }
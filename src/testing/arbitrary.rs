@@ -0,0 +1,354 @@
+//! Proptest strategies and `Arbitrary` implementations for wire types.
+//!
+//! Every generated value is representable on the wire. Floats never
+//! generate NaN, so values compare equal to themselves after a round trip.
+
+use std::collections::HashMap;
+
+use proptest::collection::{hash_map, vec};
+use proptest::prelude::*;
+
+use crate::proto;
+use crate::server::ResultFrame;
+use crate::status;
+use crate::types::{
+    Date, Duration, Edge, EncryptedValue, Field, LocalDateTime, LocalTime, Node, Path, Record,
+    Value, ZonedDateTime, ZonedTime,
+};
+
+/// Maximum nesting depth of generated lists and records.
+const MAX_DEPTH: u32 = 3;
+
+/// Maximum length of generated collections.
+const MAX_LEN: usize = 4;
+
+/// Expected number of items per generated list or record.
+const BRANCH_SIZE: u32 = 4;
+
+/// Largest UTC offset in minutes (18 hours).
+const MAX_OFFSET_MINUTES: i32 = 18 * 60;
+
+macro_rules! arbitrary_via {
+    ($ty:ty, $strategy:expr) => {
+        impl Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+                $strategy.boxed()
+            }
+        }
+    };
+}
+
+arbitrary_via!(Date, date());
+arbitrary_via!(LocalTime, local_time());
+arbitrary_via!(ZonedTime, zoned_time());
+arbitrary_via!(LocalDateTime, local_date_time());
+arbitrary_via!(ZonedDateTime, zoned_date_time());
+arbitrary_via!(Duration, duration());
+arbitrary_via!(Node, node());
+arbitrary_via!(Edge, edge());
+arbitrary_via!(Path, path());
+arbitrary_via!(Record, record(value()));
+arbitrary_via!(Value, value());
+arbitrary_via!(ResultFrame, result_frame());
+
+// ============================================================================
+// Temporals
+// ============================================================================
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let leap = year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0);
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Calendar-valid dates in years -9999 through 9999.
+pub fn date() -> impl Strategy<Value = Date> {
+    (-9999i32..=9999, 1u32..=12)
+        .prop_flat_map(|(year, month)| (Just(year), Just(month), 1..=days_in_month(year, month)))
+        .prop_map(|(year, month, day)| Date { year, month, day })
+}
+
+/// Valid times of day with nanosecond precision.
+pub fn local_time() -> impl Strategy<Value = LocalTime> {
+    (0u32..24, 0u32..60, 0u32..60, 0u32..1_000_000_000).prop_map(
+        |(hour, minute, second, nanosecond)| LocalTime {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        },
+    )
+}
+
+fn offset_minutes() -> impl Strategy<Value = i32> {
+    -MAX_OFFSET_MINUTES..=MAX_OFFSET_MINUTES
+}
+
+/// Times of day with a UTC offset of at most 18 hours.
+pub fn zoned_time() -> impl Strategy<Value = ZonedTime> {
+    (local_time(), offset_minutes()).prop_map(|(time, offset_minutes)| ZonedTime {
+        time,
+        offset_minutes,
+    })
+}
+
+/// Valid dates with a time of day.
+pub fn local_date_time() -> impl Strategy<Value = LocalDateTime> {
+    (date(), local_time()).prop_map(|(date, time)| LocalDateTime { date, time })
+}
+
+/// Valid dates with a time of day and a UTC offset.
+pub fn zoned_date_time() -> impl Strategy<Value = ZonedDateTime> {
+    (date(), local_time(), offset_minutes()).prop_map(|(date, time, offset_minutes)| {
+        ZonedDateTime {
+            date,
+            time,
+            offset_minutes,
+        }
+    })
+}
+
+/// Durations with any month and nanosecond components.
+pub fn duration() -> impl Strategy<Value = Duration> {
+    (any::<i64>(), any::<i64>()).prop_map(|(months, nanoseconds)| Duration {
+        months,
+        nanoseconds,
+    })
+}
+
+// ============================================================================
+// Graph elements
+// ============================================================================
+
+fn name() -> impl Strategy<Value = String> {
+    "[A-Za-z_][A-Za-z0-9_]{0,11}"
+}
+
+fn label() -> impl Strategy<Value = String> {
+    "[A-Z][A-Za-z]{0,7}"
+}
+
+fn element_id() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 1..=8)
+}
+
+fn properties() -> impl Strategy<Value = HashMap<String, Value>> {
+    hash_map(name(), scalar_value(), 0..=MAX_LEN)
+}
+
+/// Nodes with up to three labels and scalar properties.
+pub fn node() -> impl Strategy<Value = Node> {
+    (element_id(), vec(label(), 0..=3), properties()).prop_map(|(id, labels, properties)| Node {
+        id,
+        labels,
+        properties,
+    })
+}
+
+/// Edges between arbitrary endpoint ids, with scalar properties.
+pub fn edge() -> impl Strategy<Value = Edge> {
+    (
+        element_id(),
+        vec(label(), 0..=1),
+        element_id(),
+        element_id(),
+        any::<bool>(),
+        properties(),
+    )
+        .prop_map(
+            |(id, labels, source_node_id, target_node_id, undirected, properties)| Edge {
+                id,
+                labels,
+                source_node_id,
+                target_node_id,
+                undirected,
+                properties,
+            },
+        )
+}
+
+/// Paths whose edges connect consecutive nodes.
+pub fn path() -> impl Strategy<Value = Path> {
+    vec(node(), 1..=MAX_LEN)
+        .prop_flat_map(|nodes| {
+            let hops = nodes.len() - 1;
+            (Just(nodes), vec(edge(), hops))
+        })
+        .prop_map(|(nodes, mut edges)| {
+            for (edge, pair) in edges.iter_mut().zip(nodes.windows(2)) {
+                edge.source_node_id.clone_from(&pair[0].id);
+                edge.target_node_id.clone_from(&pair[1].id);
+            }
+            Path { nodes, edges }
+        })
+}
+
+// ============================================================================
+// Values
+// ============================================================================
+
+fn float() -> impl Strategy<Value = f64> {
+    any::<f64>().prop_filter("NaN never equals itself", |f| !f.is_nan())
+}
+
+/// Non-nested values: everything except lists, records, and graph elements.
+pub fn scalar_value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::Boolean),
+            any::<i64>().prop_map(Value::Integer),
+            any::<u64>().prop_map(Value::UnsignedInteger),
+            float().prop_map(Value::Float),
+            any::<String>().prop_map(Value::String),
+            vec(any::<u8>(), 0..16).prop_map(Value::Bytes),
+        ],
+        prop_oneof![
+            date().prop_map(Value::Date),
+            local_time().prop_map(Value::LocalTime),
+            zoned_time().prop_map(Value::ZonedTime),
+            local_date_time().prop_map(Value::LocalDateTime),
+            zoned_date_time().prop_map(Value::ZonedDateTime),
+            duration().prop_map(Value::Duration),
+        ],
+        prop_oneof![
+            (vec(any::<u8>(), 1..=16), any::<i32>())
+                .prop_map(|(unscaled, scale)| Value::Decimal { unscaled, scale }),
+            (vec(any::<u8>(), 1..=32), any::<bool>())
+                .prop_map(|(value, is_signed)| Value::BigInteger { value, is_signed }),
+            prop_oneof![Just((128u32, 16usize)), Just((256, 32))]
+                .prop_flat_map(|(width, len)| (vec(any::<u8>(), len), Just(width)))
+                .prop_map(|(value, width)| Value::BigFloat { value, width }),
+            (name(), name(), vec(any::<u8>(), 0..32)).prop_map(
+                |(key_id, algorithm, ciphertext)| Value::Encrypted(EncryptedValue {
+                    key_id,
+                    algorithm,
+                    ciphertext,
+                })
+            ),
+        ],
+    ]
+}
+
+/// Records whose field values come from `values`.
+pub fn record(values: impl Strategy<Value = Value>) -> impl Strategy<Value = Record> {
+    vec((name(), values), 0..=MAX_LEN).prop_map(|fields| Record {
+        fields: fields
+            .into_iter()
+            .map(|(name, value)| Field { name, value })
+            .collect(),
+    })
+}
+
+/// Any value, with lists and records nested a few levels deep.
+pub fn value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        4 => scalar_value(),
+        1 => node().prop_map(Value::Node),
+        1 => edge().prop_map(Value::Edge),
+        1 => path().prop_map(Value::Path),
+    ];
+    leaf.prop_recursive(MAX_DEPTH, 32, BRANCH_SIZE, |inner| {
+        prop_oneof![
+            vec(inner.clone(), 0..=MAX_LEN).prop_map(Value::List),
+            record(inner).prop_map(Value::Record),
+        ]
+    })
+}
+
+// ============================================================================
+// Result frames
+// ============================================================================
+
+/// A binding table header with `width` columns.
+pub fn result_header(width: usize) -> impl Strategy<Value = proto::ResultHeader> {
+    (vec(name(), width), any::<bool>()).prop_map(|(names, ordered)| proto::ResultHeader {
+        result_type: proto::ResultType::BindingTable.into(),
+        columns: names
+            .into_iter()
+            .map(|name| proto::ColumnDescriptor {
+                name,
+                r#type: Some(proto::TypeDescriptor {
+                    r#type: proto::GqlType::TypeUnknown.into(),
+                    nullable: true,
+                    ..Default::default()
+                }),
+            })
+            .collect(),
+        ordered,
+    })
+}
+
+/// A batch of rows that each have `width` values.
+pub fn row_batch(width: usize) -> impl Strategy<Value = proto::RowBatch> {
+    vec(vec(value(), width), 0..=MAX_LEN).prop_map(|rows| proto::RowBatch {
+        rows: rows
+            .into_iter()
+            .map(|values| proto::Row {
+                values: values.into_iter().map(proto::Value::from).collect(),
+            })
+            .collect(),
+        checksum: None,
+        dictionary: Vec::new(),
+        columns: None,
+    })
+}
+
+/// A successful or failed result summary.
+pub fn result_summary() -> impl Strategy<Value = proto::ResultSummary> {
+    (
+        prop_oneof![
+            Just(status::success()),
+            Just(status::error(status::DATA_EXCEPTION, "generated failure")),
+        ],
+        0i64..1000,
+    )
+        .prop_map(|(status, rows_affected)| proto::ResultSummary {
+            status: Some(status),
+            warnings: Vec::new(),
+            rows_affected,
+            counters: HashMap::new(),
+            update_counters: None,
+            timings: None,
+        })
+}
+
+/// A single frame of any kind.
+pub fn result_frame() -> impl Strategy<Value = ResultFrame> {
+    prop_oneof![
+        (0..=MAX_LEN)
+            .prop_flat_map(result_header)
+            .prop_map(ResultFrame::Header),
+        (0..=MAX_LEN)
+            .prop_flat_map(row_batch)
+            .prop_map(ResultFrame::Batch),
+        Just(status::warning(status::WARNING, "generated warning")).prop_map(ResultFrame::Warning),
+        result_summary().prop_map(ResultFrame::Summary),
+    ]
+}
+
+/// A well-formed result stream: a header, row batches matching its
+/// width, then a summary.
+pub fn result_stream() -> impl Strategy<Value = Vec<ResultFrame>> {
+    (0..=MAX_LEN)
+        .prop_flat_map(|width| {
+            (
+                result_header(width),
+                vec(row_batch(width), 0..=MAX_LEN),
+                result_summary(),
+            )
+        })
+        .prop_map(|(header, batches, summary)| {
+            let mut frames = vec![ResultFrame::Header(header)];
+            frames.extend(batches.into_iter().map(ResultFrame::Batch));
+            frames.push(ResultFrame::Summary(summary));
+            frames
+        })
+}
@@ -0,0 +1,26 @@
+//! Test fixtures for exercising a backend over the real wire protocol.
+//!
+//! [`TestServer`] runs a full GWP server on an ephemeral localhost port
+//! and hands out clients connected to it:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), gwp::error::GqlError> {
+//! use gwp::server::mock_backend::MockBackend;
+//! use gwp::testing::TestServer;
+//!
+//! let server = TestServer::start(MockBackend::new()).await?;
+//! let mut session = server.session().await?;
+//! session.ping().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `testing` feature, [`arbitrary`] provides proptest strategies
+//! and `Arbitrary` implementations for values, graph elements, temporals,
+//! and result frames.
+
+#[cfg(feature = "testing")]
+pub mod arbitrary;
+mod server;
+
+pub use server::TestServer;
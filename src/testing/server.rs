@@ -0,0 +1,226 @@
+//! In-process server fixture for integration tests.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tonic::server::NamedService;
+use tonic::transport::Channel;
+use tonic_health::pb::HealthCheckRequest;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+
+use crate::client::{AdminClient, CatalogClient, GqlConnection, GqlSession, SearchClient};
+use crate::error::GqlError;
+use crate::proto::gql_service_server::GqlServiceServer;
+use crate::server::{GqlBackend, GqlServer, GqlServiceImpl};
+
+/// How long to wait for a freshly spawned server to report serving.
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay between readiness probes.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A GWP server running on an ephemeral localhost port.
+///
+/// The server is started with [`TestServer::start`] and is ready to accept
+/// requests once that returns. It shuts down when the fixture is dropped;
+/// use [`TestServer::shutdown`] to also wait for it to stop.
+pub struct TestServer<B: GqlBackend> {
+    addr: SocketAddr,
+    backend: Arc<B>,
+    connection: GqlConnection,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<Result<(), tonic::transport::Error>>>,
+}
+
+impl<B: GqlBackend> TestServer<B> {
+    /// Start a server with default settings around the given backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the port cannot be bound or the server does
+    /// not become ready.
+    pub async fn start(backend: B) -> Result<Self, GqlError> {
+        Self::start_shared(Arc::new(backend)).await
+    }
+
+    /// Start a server around a backend the test keeps a handle to, for
+    /// inspecting or reconfiguring it while the server runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the port cannot be bound or the server does
+    /// not become ready.
+    pub async fn start_shared(backend: Arc<B>) -> Result<Self, GqlError> {
+        Self::spawn(GqlServer::builder_shared(Arc::clone(&backend)), backend).await
+    }
+
+    /// Start a server whose builder is adjusted by `configure`, for
+    /// exercising options such as limits, deadlines, or caching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the port cannot be bound or the server does
+    /// not become ready.
+    pub async fn start_with(
+        backend: B,
+        configure: impl FnOnce(GqlServer<B>) -> GqlServer<B>,
+    ) -> Result<Self, GqlError> {
+        let backend = Arc::new(backend);
+        let server = configure(GqlServer::builder_shared(Arc::clone(&backend)));
+        Self::spawn(server, backend).await
+    }
+
+    /// Bind an ephemeral port, run `server` on it, and wait until it is
+    /// serving.
+    async fn spawn(server: GqlServer<B>, backend: Arc<B>) -> Result<Self, GqlError> {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .map_err(|e| GqlError::Protocol(format!("failed to bind test server: {e}")))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| GqlError::Protocol(format!("failed to bind test server: {e}")))?;
+
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let handle = tokio::spawn(
+            server
+                .shutdown(async {
+                    let _ = signal.await;
+                })
+                .serve_with_listener(listener),
+        );
+
+        let mut server = Self {
+            addr,
+            backend,
+            connection: GqlConnection::from_channel(
+                Channel::from_shared(format!("http://{addr}"))
+                    .map_err(|e| GqlError::Protocol(e.to_string()))?
+                    .connect_lazy(),
+            ),
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        };
+        server.wait_ready().await?;
+        Ok(server)
+    }
+
+    /// Poll the health service until the GQL service reports serving.
+    async fn wait_ready(&mut self) -> Result<(), GqlError> {
+        let mut health = HealthClient::new(self.connection.channel().clone());
+        let request = HealthCheckRequest {
+            service: <GqlServiceServer<GqlServiceImpl<B>> as NamedService>::NAME.to_owned(),
+        };
+        let deadline = tokio::time::Instant::now() + READY_TIMEOUT;
+        loop {
+            if let Ok(response) = health.check(request.clone()).await {
+                if response.into_inner().status() == ServingStatus::Serving {
+                    return Ok(());
+                }
+            }
+            if let Some(handle) = self.handle.take_if(|handle| handle.is_finished()) {
+                return Err(match handle.await {
+                    Ok(Err(e)) => GqlError::Transport(e),
+                    _ => GqlError::Protocol("test server exited before ready".into()),
+                });
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(GqlError::Protocol(format!(
+                    "test server on {} not ready after {READY_TIMEOUT:?}",
+                    self.addr
+                )));
+            }
+            tokio::time::sleep(READY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// The address the server is listening on.
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The server's endpoint URL, e.g. `http://127.0.0.1:40123`.
+    #[must_use]
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// The backend the server is running.
+    #[must_use]
+    pub fn backend(&self) -> &Arc<B> {
+        &self.backend
+    }
+
+    /// A connection to the server.
+    ///
+    /// Connections share one underlying channel, so cloning is cheap.
+    #[must_use]
+    pub fn connection(&self) -> GqlConnection {
+        self.connection.clone()
+    }
+
+    /// Perform a handshake and return a new session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handshake fails.
+    pub async fn session(&self) -> Result<GqlSession, GqlError> {
+        self.connection.create_session().await
+    }
+
+    /// A catalog client connected to the server.
+    #[must_use]
+    pub fn catalog_client(&self) -> CatalogClient {
+        self.connection.create_catalog_client()
+    }
+
+    /// An admin client connected to the server.
+    #[must_use]
+    pub fn admin_client(&self) -> AdminClient {
+        self.connection.create_admin_client()
+    }
+
+    /// A search client connected to the server.
+    #[must_use]
+    pub fn search_client(&self) -> SearchClient {
+        self.connection.create_search_client()
+    }
+
+    /// Stop the server and wait for it to finish draining.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server stopped with a transport error.
+    pub async fn shutdown(mut self) -> Result<(), GqlError> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        match self.handle.take() {
+            Some(handle) => match handle.await {
+                Ok(result) => result.map_err(GqlError::from),
+                Err(e) => Err(GqlError::Protocol(format!("test server task failed: {e}"))),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+impl<B: GqlBackend> Drop for TestServer<B> {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+impl<B: GqlBackend> std::fmt::Debug for TestServer<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestServer")
+            .field("addr", &self.addr)
+            .finish_non_exhaustive()
+    }
+}
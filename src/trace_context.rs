@@ -0,0 +1,198 @@
+//! W3C Trace Context propagation.
+//!
+//! Clients send a `traceparent` header on every RPC naming the trace and
+//! the calling span, following the W3C Trace Context format
+//! (`00-<trace id>-<parent id>-<flags>`). The server continues the trace
+//! with a span of its own and hands it to the backend, so a distributed
+//! trace connects the application, the wire server, and the database
+//! engine without either side depending on a tracing SDK.
+
+// Random ID failures are reported as the `GqlError` sessions return.
+#![allow(clippy::result_large_err)]
+
+use std::fmt::{self, Write};
+
+use crate::error::GqlError;
+
+/// The only `traceparent` version this crate produces.
+const VERSION: &str = "00";
+
+/// Flag marking a trace as sampled by its originator.
+const SAMPLED: u8 = 0x01;
+
+/// A position in a distributed trace: the trace and the current span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    flags: u8,
+}
+
+impl TraceContext {
+    /// Start a new sampled trace with a random trace and span ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn new_root() -> Result<Self, GqlError> {
+        let mut trace_id = [0u8; 16];
+        random(&mut trace_id)?;
+        Ok(Self {
+            trace_id,
+            span_id: random_span_id()?,
+            flags: SAMPLED,
+        })
+    }
+
+    /// A new span in the same trace, whose parent is this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn child(&self) -> Result<Self, GqlError> {
+        Ok(Self {
+            span_id: random_span_id()?,
+            ..*self
+        })
+    }
+
+    /// Parse a `traceparent` header value.
+    ///
+    /// Returns `None` if it is malformed or names the all-zero trace or
+    /// span, which the specification declares invalid. Versions other
+    /// than `00` are read by their first four fields, as the
+    /// specification asks.
+    #[must_use]
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut fields = traceparent.trim().split('-');
+        let version = fields.next()?;
+        if version.len() != 2 || version.eq_ignore_ascii_case("ff") {
+            return None;
+        }
+        let trace_id = hex_array::<16>(fields.next()?)?;
+        let span_id = hex_array::<8>(fields.next()?)?;
+        let [flags] = hex_array::<1>(fields.next()?)?;
+        if version == VERSION && fields.next().is_some() {
+            return None;
+        }
+        if trace_id == [0; 16] || span_id == [0; 8] {
+            return None;
+        }
+        Some(Self {
+            trace_id,
+            span_id,
+            flags,
+        })
+    }
+
+    /// The trace ID as 32 lowercase hex digits.
+    #[must_use]
+    pub fn trace_id(&self) -> String {
+        hex(&self.trace_id)
+    }
+
+    /// The span ID as 16 lowercase hex digits.
+    #[must_use]
+    pub fn span_id(&self) -> String {
+        hex(&self.span_id)
+    }
+
+    /// Whether the trace's originator sampled it.
+    #[must_use]
+    pub fn is_sampled(&self) -> bool {
+        self.flags & SAMPLED != 0
+    }
+}
+
+/// Formats as a `traceparent` header value.
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{VERSION}-{}-{}-{:02x}",
+            self.trace_id(),
+            self.span_id(),
+            self.flags
+        )
+    }
+}
+
+fn random(bytes: &mut [u8]) -> Result<(), GqlError> {
+    getrandom::fill(bytes).map_err(|e| GqlError::Protocol(format!("cannot generate trace ID: {e}")))
+}
+
+fn random_span_id() -> Result<[u8; 8], GqlError> {
+    let mut span_id = [0u8; 8];
+    // An all-zero span ID is invalid; draw again in the unlikely case.
+    while span_id == [0; 8] {
+        random(&mut span_id)?;
+    }
+    Ok(span_id)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
+
+/// Decode exactly `N` bytes of lowercase hex.
+fn hex_array<const N: usize>(digits: &str) -> Option<[u8; N]> {
+    if digits.len() != N * 2
+        || !digits
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn traceparent_round_trips() {
+        let context = TraceContext::parse(EXAMPLE).unwrap();
+        assert_eq!(context.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.span_id(), "00f067aa0ba902b7");
+        assert!(context.is_sampled());
+        assert_eq!(context.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn children_share_the_trace() {
+        let root = TraceContext::new_root().unwrap();
+        let child = root.child().unwrap();
+        assert_eq!(child.trace_id(), root.trace_id());
+        assert_ne!(child.span_id(), root.span_id());
+        assert_eq!(TraceContext::parse(&child.to_string()), Some(child));
+    }
+
+    #[test]
+    fn malformed_headers_are_ignored() {
+        for header in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert_eq!(TraceContext::parse(header), None, "{header}");
+        }
+        // Later versions may append fields.
+        assert!(TraceContext::parse(&format!("01{}-extra", &EXAMPLE[2..])).is_some());
+    }
+}
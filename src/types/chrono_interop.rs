@@ -0,0 +1,244 @@
+//! Conversions between the temporal types and `chrono`, via the `chrono`
+//! feature.
+//!
+//! | GQL type | `chrono` type |
+//! |----------|---------------|
+//! | [`Date`] | `NaiveDate` |
+//! | [`LocalTime`] | `NaiveTime` |
+//! | [`LocalDateTime`] | `NaiveDateTime` |
+//! | [`ZonedDateTime`] | `DateTime<FixedOffset>`, and from `DateTime<Utc>` |
+//! | [`Duration`] | `TimeDelta` |
+//!
+//! Conversions that can fail return a [`TemporalRangeError`]: dates and
+//! times out of range or not on the calendar, leap seconds, offsets that
+//! are not whole minutes, and durations with a month component or beyond
+//! the range of the target type.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone,
+    Timelike, Utc,
+};
+
+use super::{Date, Duration, LocalDateTime, LocalTime, ZonedDateTime};
+
+/// Error returned when a temporal value has no counterpart in the target
+/// type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("{0} is out of range")]
+pub struct TemporalRangeError(&'static str);
+
+// ============================================================================
+// Dates and times
+// ============================================================================
+
+impl From<NaiveDate> for Date {
+    fn from(d: NaiveDate) -> Self {
+        Self {
+            year: d.year(),
+            month: d.month(),
+            day: d.day(),
+        }
+    }
+}
+
+impl TryFrom<Date> for NaiveDate {
+    type Error = TemporalRangeError;
+
+    fn try_from(d: Date) -> Result<Self, Self::Error> {
+        Self::from_ymd_opt(d.year, d.month, d.day).ok_or(TemporalRangeError("date"))
+    }
+}
+
+impl TryFrom<NaiveTime> for LocalTime {
+    type Error = TemporalRangeError;
+
+    /// Fails for leap seconds, which `LocalTime` cannot represent.
+    fn try_from(t: NaiveTime) -> Result<Self, Self::Error> {
+        if t.nanosecond() >= 1_000_000_000 {
+            return Err(TemporalRangeError("leap second"));
+        }
+        Ok(Self {
+            hour: t.hour(),
+            minute: t.minute(),
+            second: t.second(),
+            nanosecond: t.nanosecond(),
+        })
+    }
+}
+
+impl TryFrom<LocalTime> for NaiveTime {
+    type Error = TemporalRangeError;
+
+    fn try_from(t: LocalTime) -> Result<Self, Self::Error> {
+        if t.nanosecond >= 1_000_000_000 {
+            return Err(TemporalRangeError("time"));
+        }
+        Self::from_hms_nano_opt(t.hour, t.minute, t.second, t.nanosecond)
+            .ok_or(TemporalRangeError("time"))
+    }
+}
+
+impl TryFrom<NaiveDateTime> for LocalDateTime {
+    type Error = TemporalRangeError;
+
+    fn try_from(dt: NaiveDateTime) -> Result<Self, Self::Error> {
+        Ok(Self {
+            date: dt.date().into(),
+            time: dt.time().try_into()?,
+        })
+    }
+}
+
+impl TryFrom<LocalDateTime> for NaiveDateTime {
+    type Error = TemporalRangeError;
+
+    fn try_from(dt: LocalDateTime) -> Result<Self, Self::Error> {
+        Ok(Self::new(dt.date.try_into()?, dt.time.try_into()?))
+    }
+}
+
+impl TryFrom<DateTime<FixedOffset>> for ZonedDateTime {
+    type Error = TemporalRangeError;
+
+    /// Fails for leap seconds and offsets that are not whole minutes.
+    fn try_from(dt: DateTime<FixedOffset>) -> Result<Self, Self::Error> {
+        let offset_seconds = dt.offset().local_minus_utc();
+        if offset_seconds % 60 != 0 {
+            return Err(TemporalRangeError("sub-minute offset"));
+        }
+        let local = LocalDateTime::try_from(dt.naive_local())?;
+        Ok(Self {
+            date: local.date,
+            time: local.time,
+            offset_minutes: offset_seconds / 60,
+        })
+    }
+}
+
+impl TryFrom<DateTime<Utc>> for ZonedDateTime {
+    type Error = TemporalRangeError;
+
+    fn try_from(dt: DateTime<Utc>) -> Result<Self, Self::Error> {
+        dt.fixed_offset().try_into()
+    }
+}
+
+impl TryFrom<ZonedDateTime> for DateTime<FixedOffset> {
+    type Error = TemporalRangeError;
+
+    fn try_from(dt: ZonedDateTime) -> Result<Self, Self::Error> {
+        let offset = dt
+            .offset_minutes
+            .checked_mul(60)
+            .and_then(FixedOffset::east_opt)
+            .ok_or(TemporalRangeError("offset"))?;
+        let local = NaiveDateTime::try_from(LocalDateTime {
+            date: dt.date,
+            time: dt.time,
+        })?;
+        offset
+            .from_local_datetime(&local)
+            .single()
+            .ok_or(TemporalRangeError("datetime"))
+    }
+}
+
+// ============================================================================
+// Durations
+// ============================================================================
+
+impl TryFrom<TimeDelta> for Duration {
+    type Error = TemporalRangeError;
+
+    /// Fails for deltas beyond about 292 years, the range of `i64`
+    /// nanoseconds.
+    fn try_from(delta: TimeDelta) -> Result<Self, Self::Error> {
+        Ok(Self {
+            months: 0,
+            nanoseconds: delta
+                .num_nanoseconds()
+                .ok_or(TemporalRangeError("duration"))?,
+        })
+    }
+}
+
+impl TryFrom<Duration> for TimeDelta {
+    type Error = TemporalRangeError;
+
+    /// Fails for durations with a month component, whose length in days
+    /// depends on the date it is applied to.
+    fn try_from(d: Duration) -> Result<Self, Self::Error> {
+        if d.months != 0 {
+            return Err(TemporalRangeError("month component"));
+        }
+        Ok(Self::nanoseconds(d.nanoseconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_and_times_round_trip() {
+        let naive = NaiveDate::from_ymd_opt(2026, 2, 13)
+            .unwrap()
+            .and_hms_nano_opt(14, 30, 5, 123_456_789)
+            .unwrap();
+        let local = LocalDateTime::try_from(naive).unwrap();
+        assert_eq!(local.date.day, 13);
+        assert_eq!(local.time.nanosecond, 123_456_789);
+        assert_eq!(NaiveDateTime::try_from(local).unwrap(), naive);
+
+        let zoned = FixedOffset::west_opt(5 * 3600)
+            .unwrap()
+            .from_local_datetime(&naive)
+            .unwrap();
+        let gql = ZonedDateTime::try_from(zoned).unwrap();
+        assert_eq!(gql.offset_minutes, -300);
+        assert_eq!(DateTime::<FixedOffset>::try_from(gql).unwrap(), zoned);
+
+        let utc = ZonedDateTime::try_from(zoned.with_timezone(&Utc)).unwrap();
+        assert_eq!(utc.offset_minutes, 0);
+        assert_eq!(utc.time.hour, 19);
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let feb_30 = Date {
+            year: 2026,
+            month: 2,
+            day: 30,
+        };
+        assert_eq!(NaiveDate::try_from(feb_30), Err(TemporalRangeError("date")));
+        let late = LocalTime {
+            hour: 24,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+        };
+        assert!(NaiveTime::try_from(late).is_err());
+        let leap = NaiveTime::from_hms_nano_opt(23, 59, 59, 1_500_000_000).unwrap();
+        assert!(LocalTime::try_from(leap).is_err());
+        let odd_offset = FixedOffset::east_opt(30)
+            .unwrap()
+            .with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+            .unwrap();
+        assert!(ZonedDateTime::try_from(odd_offset).is_err());
+    }
+
+    #[test]
+    fn durations_convert_without_months() {
+        let delta = TimeDelta::seconds(90) + TimeDelta::nanoseconds(5);
+        let d = Duration::try_from(delta).unwrap();
+        assert_eq!(d.nanoseconds, 90_000_000_005);
+        assert_eq!(TimeDelta::try_from(d).unwrap(), delta);
+
+        let monthly = Duration {
+            months: 1,
+            nanoseconds: 0,
+        };
+        assert!(TimeDelta::try_from(monthly).is_err());
+        assert!(Duration::try_from(TimeDelta::MAX).is_err());
+    }
+}
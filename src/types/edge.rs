@@ -7,14 +7,18 @@ use crate::proto;
 
 /// A property graph edge with an opaque ID, labels, endpoints, and properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge {
     /// Opaque element identifier.
+    #[cfg_attr(feature = "serde", serde(with = "super::serialization::base64"))]
     pub id: Vec<u8>,
     /// Label set.
     pub labels: Vec<String>,
     /// Source node ID (directed) or endpoint A (undirected).
+    #[cfg_attr(feature = "serde", serde(with = "super::serialization::base64"))]
     pub source_node_id: Vec<u8>,
     /// Target node ID (directed) or endpoint B (undirected).
+    #[cfg_attr(feature = "serde", serde(with = "super::serialization::base64"))]
     pub target_node_id: Vec<u8>,
     /// Whether this is an undirected edge.
     pub undirected: bool,
@@ -71,6 +75,21 @@ impl Edge {
         self
     }
 
+    /// Add several properties to the edge, such as those built with
+    /// [`props!`](crate::props).
+    #[must_use]
+    pub fn with_properties<K: Into<String>, V: Into<Value>>(
+        mut self,
+        properties: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.properties.extend(
+            properties
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into())),
+        );
+        self
+    }
+
     /// Get a property value by name.
     #[must_use]
     pub fn property(&self, name: &str) -> Option<&Value> {
@@ -112,6 +131,8 @@ impl From<Edge> for proto::Edge {
                 .into_iter()
                 .map(|(k, v)| (k, proto::Value::from(v)))
                 .collect(),
+            label_refs: Vec::new(),
+            property_refs: HashMap::new(),
         }
     }
 }
@@ -0,0 +1,192 @@
+//! Encrypted value envelope and the cipher hook that produces it.
+
+use crate::proto;
+
+use super::{Edge, Field, Node, Path, Record, Value};
+
+/// A value encrypted by the client, opaque to the server.
+///
+/// The server and wire-protocol frontend pass it through unchanged; only
+/// holders of the key can recover the plaintext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncryptedValue {
+    /// Identifies the key the value was encrypted with.
+    pub key_id: String,
+    /// Encryption scheme, e.g. `"AES-256-GCM"`.
+    pub algorithm: String,
+    /// Scheme-specific ciphertext, including any nonce and tag.
+    #[cfg_attr(feature = "serde", serde(with = "super::serialization::base64"))]
+    pub ciphertext: Vec<u8>,
+}
+
+/// Error returned by a [`ValueCipher`].
+pub type CipherError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Encrypts and decrypts values for field-level encryption.
+///
+/// Implement this on top of the crypto library and key management of your
+/// choice. The same implementation can be used by clients and by backends
+/// that need to read encrypted properties.
+pub trait ValueCipher: Send + Sync + 'static {
+    /// Encrypt `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be encrypted.
+    fn encrypt(&self, value: &Value) -> Result<EncryptedValue, CipherError>;
+
+    /// Decrypt `value`, or return `None` if this cipher does not hold its
+    /// key, leaving the value encrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is held but decryption fails.
+    fn decrypt(&self, value: &EncryptedValue) -> Result<Option<Value>, CipherError>;
+}
+
+impl Value {
+    /// Decrypt every encrypted value within this one, including inside
+    /// lists, records, and graph elements, that `cipher` holds the key
+    /// for.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first decryption error.
+    pub fn decrypt_with(self, cipher: &dyn ValueCipher) -> Result<Self, CipherError> {
+        Ok(match self {
+            Self::Encrypted(encrypted) => match cipher.decrypt(&encrypted)? {
+                Some(value) => value,
+                None => Self::Encrypted(encrypted),
+            },
+            Self::List(elements) => Self::List(
+                elements
+                    .into_iter()
+                    .map(|v| v.decrypt_with(cipher))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Record(record) => Self::Record(Record {
+                fields: record
+                    .fields
+                    .into_iter()
+                    .map(|f| {
+                        Ok(Field {
+                            name: f.name,
+                            value: f.value.decrypt_with(cipher)?,
+                        })
+                    })
+                    .collect::<Result<_, CipherError>>()?,
+            }),
+            Self::Node(node) => Self::Node(decrypt_node(node, cipher)?),
+            Self::Edge(edge) => Self::Edge(decrypt_edge(edge, cipher)?),
+            Self::Path(path) => Self::Path(Path {
+                nodes: path
+                    .nodes
+                    .into_iter()
+                    .map(|n| decrypt_node(n, cipher))
+                    .collect::<Result<_, _>>()?,
+                edges: path
+                    .edges
+                    .into_iter()
+                    .map(|e| decrypt_edge(e, cipher))
+                    .collect::<Result<_, _>>()?,
+            }),
+            other => other,
+        })
+    }
+}
+
+fn decrypt_node(mut node: Node, cipher: &dyn ValueCipher) -> Result<Node, CipherError> {
+    node.properties = node
+        .properties
+        .into_iter()
+        .map(|(k, v)| Ok((k, v.decrypt_with(cipher)?)))
+        .collect::<Result<_, CipherError>>()?;
+    Ok(node)
+}
+
+fn decrypt_edge(mut edge: Edge, cipher: &dyn ValueCipher) -> Result<Edge, CipherError> {
+    edge.properties = edge
+        .properties
+        .into_iter()
+        .map(|(k, v)| Ok((k, v.decrypt_with(cipher)?)))
+        .collect::<Result<_, CipherError>>()?;
+    Ok(edge)
+}
+
+// ============================================================================
+// Proto conversions
+// ============================================================================
+
+impl From<proto::EncryptedValue> for EncryptedValue {
+    fn from(p: proto::EncryptedValue) -> Self {
+        Self {
+            key_id: p.key_id,
+            algorithm: p.algorithm,
+            ciphertext: p.ciphertext,
+        }
+    }
+}
+
+impl From<EncryptedValue> for proto::EncryptedValue {
+    fn from(e: EncryptedValue) -> Self {
+        Self {
+            key_id: e.key_id,
+            algorithm: e.algorithm,
+            ciphertext: e.ciphertext,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reverses string bytes under key "k1".
+    struct Reverse;
+
+    impl ValueCipher for Reverse {
+        fn encrypt(&self, value: &Value) -> Result<EncryptedValue, CipherError> {
+            let mut ciphertext = value.as_str().unwrap_or_default().as_bytes().to_vec();
+            ciphertext.reverse();
+            Ok(EncryptedValue {
+                key_id: "k1".to_owned(),
+                algorithm: "reverse".to_owned(),
+                ciphertext,
+            })
+        }
+
+        fn decrypt(&self, value: &EncryptedValue) -> Result<Option<Value>, CipherError> {
+            if value.key_id != "k1" {
+                return Ok(None);
+            }
+            let mut plaintext = value.ciphertext.clone();
+            plaintext.reverse();
+            String::from_utf8(plaintext)
+                .map(|s| Some(Value::String(s)))
+                .map_err(Into::into)
+        }
+    }
+
+    #[test]
+    fn decrypts_nested_values_with_known_keys() {
+        let secret = Value::Encrypted(Reverse.encrypt(&Value::from("alice")).unwrap());
+        let foreign = Value::Encrypted(EncryptedValue {
+            key_id: "k2".to_owned(),
+            algorithm: "reverse".to_owned(),
+            ciphertext: b"xyz".to_vec(),
+        });
+        let node = Node::new(vec![1])
+            .with_property("name", secret.clone())
+            .with_property("ssn", foreign.clone());
+        let value = Value::List(vec![secret, Value::Node(node)]);
+
+        let Value::List(decrypted) = value.decrypt_with(&Reverse).unwrap() else {
+            panic!("expected a list");
+        };
+        assert_eq!(decrypted[0], Value::from("alice"));
+        let node = decrypted[1].as_node().unwrap();
+        assert_eq!(node.property("name"), Some(&Value::from("alice")));
+        assert_eq!(node.property("ssn"), Some(&foreign));
+    }
+}
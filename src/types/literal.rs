@@ -0,0 +1,935 @@
+//! GQL literal syntax for values.
+//!
+//! [`Value::to_gql_literal`] writes, and [`Value::parse_literal`] reads,
+//! the literal forms of ISO/IEC 39075:
+//!
+//! | Value | Literal |
+//! |-------|---------|
+//! | `Null`, `Boolean` | `NULL`, `TRUE`, `FALSE` |
+//! | `Integer`, `UnsignedInteger`, `BigInteger` | `42`, `-7`, `18446744073709551616` |
+//! | `Float` | `1.5E0`, or with an `F` or `D` suffix, `1.5D` |
+//! | `Decimal` | `12.50`, or with an `M` suffix, `12M`, `1.2E3M` |
+//! | `String` | `'it''s'`, `"line\n"`, with backslash escapes |
+//! | `Bytes` | `X'0AFF'` |
+//! | `Date` | `DATE '2026-01-01'` |
+//! | `LocalTime`, `ZonedTime` | `TIME '14:30:00'`, `TIME '14:30:00+01:00'` |
+//! | `LocalDateTime`, `ZonedDateTime` | `DATETIME '2026-01-01T14:30:00'`, with an offset if zoned |
+//! | `Duration` | `DURATION 'P1Y2M3DT4H5M6.5S'` |
+//! | `List` | `[1, 2, 3]`, optionally prefixed with `LIST` or `ARRAY` |
+//! | `Record` | `{name: 'Alice', age: 30}`, optionally prefixed with `RECORD` |
+//!
+//! Keywords are case-insensitive, and `TIMESTAMP` is read as `DATETIME`.
+//! `LOCAL_TIME`, `ZONED_TIME`, `LOCAL_DATETIME`, and `ZONED_DATETIME` name
+//! the temporal type explicitly. Integers parse to the narrowest of
+//! `Integer`, `UnsignedInteger`, and `BigInteger` that holds them. Graph
+//! elements, big floats, encrypted values, and non-finite floats have no
+//! literal form.
+
+use std::fmt::Write;
+
+use super::temporal::{
+    parse_date, parse_local_datetime, parse_local_time, parse_zoned_datetime, parse_zoned_time,
+};
+use super::{Duration, Field, Record, Value};
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_MINUTE: i128 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i128 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i128 = 24 * NANOS_PER_HOUR;
+
+/// Error returned by [`Value::parse_literal`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at offset {offset}")]
+pub struct ParseLiteralError {
+    offset: usize,
+    message: String,
+}
+
+impl ParseLiteralError {
+    /// Byte offset in the input where parsing failed.
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Value {
+    /// Parse a GQL literal, such as `DATE '2026-01-01'` or
+    /// `{name: 'Alice', tags: ['a', 'b']}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the offset of the first character that is
+    /// not part of a valid literal.
+    pub fn parse_literal(text: &str) -> Result<Self, ParseLiteralError> {
+        let mut parser = Parser { text, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return Err(parser.error("unexpected input after literal"));
+        }
+        Ok(value)
+    }
+
+    /// Format this value as a GQL literal, or `None` if it has no literal
+    /// form: graph elements, big floats, encrypted values, non-finite
+    /// floats, and lists or records containing them.
+    #[must_use]
+    pub fn to_gql_literal(&self) -> Option<String> {
+        let mut out = String::new();
+        write_literal(&mut out, self)?;
+        Some(out)
+    }
+}
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+fn write_literal(out: &mut String, value: &Value) -> Option<()> {
+    match value {
+        Value::Null => out.push_str("NULL"),
+        Value::Boolean(b) => out.push_str(if *b { "TRUE" } else { "FALSE" }),
+        Value::Integer(i) => write!(out, "{i}").ok()?,
+        Value::UnsignedInteger(u) => write!(out, "{u}").ok()?,
+        Value::Float(f) if f.is_finite() => write!(out, "{f:E}").ok()?,
+        Value::String(s) => write_string(out, s),
+        Value::Bytes(bytes) => {
+            out.push_str("X'");
+            for b in bytes {
+                write!(out, "{b:02X}").ok()?;
+            }
+            out.push('\'');
+        }
+        Value::Date(_) => write!(out, "DATE '{value}'").ok()?,
+        Value::LocalTime(_) | Value::ZonedTime(_) => write!(out, "TIME '{value}'").ok()?,
+        Value::LocalDateTime(_) | Value::ZonedDateTime(_) => {
+            write!(out, "DATETIME '{value}'").ok()?;
+        }
+        Value::Duration(d) => {
+            out.push_str("DURATION '");
+            write_duration(out, *d);
+            out.push('\'');
+        }
+        Value::List(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_literal(out, item)?;
+            }
+            out.push(']');
+        }
+        Value::Record(record) => {
+            out.push('{');
+            for (i, field) in record.fields.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_name(out, &field.name);
+                out.push_str(": ");
+                write_literal(out, &field.value)?;
+            }
+            out.push('}');
+        }
+        Value::BigInteger { value, is_signed } => {
+            let (negative, digits) = to_decimal(value, *is_signed);
+            if negative {
+                out.push('-');
+            }
+            out.push_str(&digits);
+        }
+        Value::Decimal { unscaled, scale } => write_decimal(out, unscaled, *scale),
+        Value::Float(_)
+        | Value::Node(_)
+        | Value::Edge(_)
+        | Value::Path(_)
+        | Value::BigFloat { .. }
+        | Value::Encrypted(_) => return None,
+    }
+    Some(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('\'');
+    for c in s.chars() {
+        match c {
+            '\'' => out.push_str("\\'"),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04X}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+}
+
+/// Write a record field name, delimited with backticks unless it is a
+/// plain identifier.
+fn write_name(out: &mut String, name: &str) {
+    let mut chars = name.chars();
+    let plain = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        out.push_str(name);
+    } else {
+        out.push('`');
+        out.push_str(&name.replace('`', "``"));
+        out.push('`');
+    }
+}
+
+fn write_decimal(out: &mut String, unscaled: &[u8], scale: i32) {
+    let (negative, digits) = to_decimal(unscaled, true);
+    if negative {
+        out.push('-');
+    }
+    match usize::try_from(scale) {
+        Ok(0) | Err(_) => {
+            out.push_str(&digits);
+            if scale != 0 {
+                let _ = write!(out, "E{}", -i64::from(scale));
+            }
+            out.push('M');
+        }
+        Ok(scale) => {
+            let padded = format!("{digits:0>width$}", width = scale + 1);
+            let (whole, fraction) = padded.split_at(padded.len() - scale);
+            let _ = write!(out, "{whole}.{fraction}");
+        }
+    }
+}
+
+/// Write `d` as an ISO 8601 duration, `[-]PnYnMnDTnHnMn.nS`. Components
+/// of opposite signs each carry their own sign.
+fn write_duration(out: &mut String, d: Duration) {
+    let mut months = i128::from(d.months);
+    let mut nanos = i128::from(d.nanoseconds);
+    if months <= 0 && nanos <= 0 && (months < 0 || nanos < 0) {
+        out.push('-');
+        months = -months;
+        nanos = -nanos;
+    }
+    out.push('P');
+    let start = out.len();
+    for (amount, unit) in [
+        (months / 12, 'Y'),
+        (months % 12, 'M'),
+        (nanos / NANOS_PER_DAY, 'D'),
+    ] {
+        if amount != 0 {
+            let _ = write!(out, "{amount}{unit}");
+        }
+    }
+    let time = nanos % NANOS_PER_DAY;
+    if time != 0 {
+        out.push('T');
+        for (amount, unit) in [
+            (time / NANOS_PER_HOUR, 'H'),
+            (time % NANOS_PER_HOUR / NANOS_PER_MINUTE, 'M'),
+        ] {
+            if amount != 0 {
+                let _ = write!(out, "{amount}{unit}");
+            }
+        }
+        let seconds = time % NANOS_PER_MINUTE;
+        if seconds != 0 {
+            if seconds < 0 {
+                out.push('-');
+            }
+            let seconds = seconds.unsigned_abs();
+            let whole = seconds / NANOS_PER_SECOND.unsigned_abs();
+            let fraction = seconds % NANOS_PER_SECOND.unsigned_abs();
+            let _ = write!(out, "{whole}");
+            if fraction != 0 {
+                let fraction = format!("{fraction:09}");
+                let _ = write!(out, ".{}", fraction.trim_end_matches('0'));
+            }
+            out.push('S');
+        }
+    }
+    if out.len() == start {
+        out.push_str("T0S");
+    }
+}
+
+/// The sign and decimal digits of a big-endian integer, two's complement
+/// if `signed`.
+fn to_decimal(bytes: &[u8], signed: bool) -> (bool, String) {
+    let negative = signed && bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut magnitude = bytes.to_vec();
+    if negative {
+        negate(&mut magnitude);
+    }
+    let mut digits = Vec::new();
+    while magnitude.iter().any(|&b| b != 0) {
+        let mut remainder = 0u16;
+        for byte in &mut magnitude {
+            let current = remainder << 8 | u16::from(*byte);
+            *byte = u8::try_from(current / 10).unwrap_or(u8::MAX);
+            remainder = current % 10;
+        }
+        digits.push(char::from(b'0' + u8::try_from(remainder).unwrap_or(0)));
+    }
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    (negative, digits.into_iter().rev().collect())
+}
+
+/// The minimal big-endian two's complement encoding of the integer with
+/// decimal `digits`.
+fn from_decimal(negative: bool, digits: &str) -> Vec<u8> {
+    let mut bytes = vec![0u8];
+    for digit in digits.bytes() {
+        let mut carry = u16::from(digit - b'0');
+        for byte in bytes.iter_mut().rev() {
+            let [high, low] = (u16::from(*byte) * 10 + carry).to_be_bytes();
+            *byte = low;
+            carry = u16::from(high);
+        }
+        if carry > 0 {
+            bytes.insert(0, u8::try_from(carry).unwrap_or(0));
+        }
+    }
+    // A leading zero byte keeps the magnitude positive, so that negating
+    // it cannot overflow.
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    if negative {
+        negate(&mut bytes);
+    }
+    // Drop redundant sign bytes.
+    while bytes.len() > 1
+        && ((bytes[0] == 0 && bytes[1] & 0x80 == 0) || (bytes[0] == 0xff && bytes[1] & 0x80 != 0))
+    {
+        bytes.remove(0);
+    }
+    bytes
+}
+
+/// Negate a big-endian two's complement integer in place.
+fn negate(bytes: &mut [u8]) {
+    let mut carry = true;
+    for byte in bytes.iter_mut().rev() {
+        let (sum, overflow) = (!*byte).overflowing_add(u8::from(carry));
+        *byte = sum;
+        carry = overflow;
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: impl Into<String>) -> ParseLiteralError {
+        ParseLiteralError {
+            offset: self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseLiteralError> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{expected}`")))
+        }
+    }
+
+    /// Consume `expected` if it comes next, after any whitespace.
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(expected);
+        if found {
+            self.pos += expected.len_utf8();
+        }
+        found
+    }
+
+    fn word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn value(&mut self) -> Result<Value, ParseLiteralError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('\'' | '"') => self.string().map(Value::String),
+            Some('[') => self.list(),
+            Some('{') => self.record(),
+            Some(c) if c == '-' || c == '.' || c.is_ascii_digit() => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => self.keyword(),
+            Some(_) => Err(self.error("expected a literal")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn keyword(&mut self) -> Result<Value, ParseLiteralError> {
+        let start = self.pos;
+        let word = self.word().to_ascii_uppercase();
+        if word == "X" && self.peek() == Some('\'') {
+            return self.bytes();
+        }
+        match word.as_str() {
+            "NULL" => Ok(Value::Null),
+            "TRUE" => Ok(Value::Boolean(true)),
+            "FALSE" => Ok(Value::Boolean(false)),
+            "LIST" | "ARRAY" => self.list(),
+            "RECORD" => self.record(),
+            "DATE" => self.temporal(&word, |s| parse_date(s).map(Value::Date)),
+            "TIME" => self.temporal(&word, |s| {
+                parse_zoned_time(s)
+                    .map(Value::ZonedTime)
+                    .or_else(|| parse_local_time(s).map(Value::LocalTime))
+            }),
+            "DATETIME" | "TIMESTAMP" => self.temporal(&word, |s| {
+                parse_zoned_datetime(s)
+                    .map(Value::ZonedDateTime)
+                    .or_else(|| parse_local_datetime(s).map(Value::LocalDateTime))
+            }),
+            "LOCAL_TIME" => self.temporal(&word, |s| parse_local_time(s).map(Value::LocalTime)),
+            "ZONED_TIME" => self.temporal(&word, |s| parse_zoned_time(s).map(Value::ZonedTime)),
+            "LOCAL_DATETIME" => {
+                self.temporal(&word, |s| parse_local_datetime(s).map(Value::LocalDateTime))
+            }
+            "ZONED_DATETIME" => {
+                self.temporal(&word, |s| parse_zoned_datetime(s).map(Value::ZonedDateTime))
+            }
+            "DURATION" => self.temporal(&word, |s| parse_duration(s).map(Value::Duration)),
+            _ => {
+                self.pos = start;
+                Err(self.error(format!("unknown keyword `{word}`")))
+            }
+        }
+    }
+
+    /// Parse the quoted string after a temporal keyword with `parse`.
+    fn temporal(
+        &mut self,
+        keyword: &str,
+        parse: fn(&str) -> Option<Value>,
+    ) -> Result<Value, ParseLiteralError> {
+        self.skip_whitespace();
+        let at = self.pos;
+        let text = self.string()?;
+        parse(&text).ok_or(ParseLiteralError {
+            offset: at,
+            message: format!("invalid {keyword} string"),
+        })
+    }
+
+    /// Parse a quoted string, with backslash escapes and doubled quotes.
+    fn string(&mut self) -> Result<String, ParseLiteralError> {
+        let Some(quote) = self.peek().filter(|c| matches!(c, '\'' | '"' | '`')) else {
+            return Err(self.error("expected a quoted string"));
+        };
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                self.pos = start;
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                c if c == quote => {
+                    if self.peek() == Some(quote) {
+                        self.pos += 1;
+                        out.push(quote);
+                    } else {
+                        return Ok(out);
+                    }
+                }
+                '\\' if quote != '`' => out.push(self.escape()?),
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ParseLiteralError> {
+        let Some(c) = self.peek() else {
+            return Err(self.error("unterminated escape"));
+        };
+        self.pos += c.len_utf8();
+        Ok(match c {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'u' | 'U' => {
+                let len = if c == 'u' { 4 } else { 6 };
+                let hex = self.rest().get(..len).unwrap_or_default();
+                let code = u32::from_str_radix(hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == len)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?;
+                self.pos += len;
+                code
+            }
+            c => c,
+        })
+    }
+
+    fn bytes(&mut self) -> Result<Value, ParseLiteralError> {
+        let at = self.pos;
+        let hex: String = self.string()?.split_whitespace().collect();
+        let digits: Option<Vec<u8>> = hex
+            .chars()
+            .map(|c| c.to_digit(16).and_then(|d| u8::try_from(d).ok()))
+            .collect();
+        match digits {
+            Some(digits) if digits.len() % 2 == 0 => Ok(Value::Bytes(
+                digits
+                    .chunks(2)
+                    .map(|pair| pair[0] << 4 | pair[1])
+                    .collect(),
+            )),
+            _ => Err(ParseLiteralError {
+                offset: at,
+                message: "invalid byte string".into(),
+            }),
+        }
+    }
+
+    fn list(&mut self) -> Result<Value, ParseLiteralError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        if !self.eat(']') {
+            loop {
+                items.push(self.value()?);
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        Ok(Value::List(items))
+    }
+
+    fn record(&mut self) -> Result<Value, ParseLiteralError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        if !self.eat('}') {
+            loop {
+                self.skip_whitespace();
+                let name = match self.peek() {
+                    Some('\'' | '"' | '`') => self.string()?,
+                    Some(c) if c.is_ascii_alphabetic() || c == '_' => self.word().to_owned(),
+                    _ => return Err(self.error("expected a field name")),
+                };
+                self.expect(':')?;
+                fields.push(Field {
+                    name,
+                    value: self.value()?,
+                });
+                if self.eat('}') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        Ok(Value::Record(Record { fields }))
+    }
+
+    fn number(&mut self) -> Result<Value, ParseLiteralError> {
+        let start = self.pos;
+        let rest = self.rest();
+        let bytes = rest.as_bytes();
+        let digits_from = |i: usize| {
+            bytes[i..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .map_or(bytes.len(), |n| i + n)
+        };
+
+        let negative = bytes[0] == b'-';
+        let whole_start = usize::from(negative);
+        let whole_end = digits_from(whole_start);
+        let (fraction_start, fraction_end) = if bytes.get(whole_end) == Some(&b'.') {
+            (whole_end + 1, digits_from(whole_end + 1))
+        } else {
+            (whole_end, whole_end)
+        };
+        if whole_end == whole_start && fraction_end == fraction_start {
+            return Err(self.error("expected a number"));
+        }
+        let mut end = fraction_end;
+        let mut exponent = None;
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+            let exponent_end = digits_from(end + 1 + sign);
+            if exponent_end > end + 1 + sign {
+                exponent = Some(&rest[end + 1..exponent_end]);
+                end = exponent_end;
+            }
+        }
+        let suffix = bytes
+            .get(end)
+            .map(u8::to_ascii_uppercase)
+            .filter(|b| matches!(b, b'M' | b'F' | b'D'))
+            .filter(|_| {
+                bytes
+                    .get(end + 1)
+                    .is_none_or(|b| !(b.is_ascii_alphanumeric() || *b == b'_'))
+            });
+        let text = &rest[..end];
+        self.pos += end + usize::from(suffix.is_some());
+        if self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err(self.error("invalid number"));
+        }
+
+        let exact = match suffix {
+            Some(b'M') => true,
+            Some(_) => false,
+            None => exponent.is_none(),
+        };
+        if !exact {
+            return text
+                .parse()
+                .map(Value::Float)
+                .map_err(|_| ParseLiteralError {
+                    offset: start,
+                    message: "invalid number".into(),
+                });
+        }
+        let whole = &rest[whole_start..whole_end];
+        let fraction = &rest[fraction_start..fraction_end];
+        if fraction_start == whole_end && exponent.is_none() && suffix.is_none() {
+            return Ok(integer(negative, whole));
+        }
+
+        let exponent: i64 = exponent
+            .map_or(Ok(0), str::parse)
+            .map_err(|_| ParseLiteralError {
+                offset: start,
+                message: "exponent out of range".into(),
+            })?;
+        let scale = i64::try_from(fraction.len())
+            .ok()
+            .and_then(|len| len.checked_sub(exponent))
+            .and_then(|scale| i32::try_from(scale).ok())
+            .ok_or(ParseLiteralError {
+                offset: start,
+                message: "exponent out of range".into(),
+            })?;
+        Ok(Value::Decimal {
+            unscaled: from_decimal(negative, &format!("{whole}{fraction}")),
+            scale,
+        })
+    }
+}
+
+/// The narrowest integer value holding the integer with decimal `digits`.
+fn integer(negative: bool, digits: &str) -> Value {
+    let text = if negative {
+        format!("-{digits}")
+    } else {
+        digits.to_owned()
+    };
+    text.parse()
+        .map(Value::Integer)
+        .or_else(|_| text.parse().map(Value::UnsignedInteger))
+        .unwrap_or_else(|_| Value::BigInteger {
+            value: from_decimal(negative, digits),
+            is_signed: true,
+        })
+}
+
+/// Parse an ISO 8601 duration, `[-]PnYnMnWnDTnHnMn.nS`. Each component
+/// may carry its own sign, and only seconds may have a fraction.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let text = text.strip_prefix(['P', 'p'])?;
+    let (date, time) = match text.split_once(['T', 't']) {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (text, None),
+    };
+    if date.is_empty() && time.is_none() {
+        return None;
+    }
+
+    let mut months: i128 = 0;
+    let mut nanos: i128 = 0;
+    for (amount, unit) in components(date)? {
+        let amount = whole(amount)?;
+        match unit.to_ascii_uppercase() {
+            'Y' => months = months.checked_add(amount.checked_mul(12)?)?,
+            'M' => months = months.checked_add(amount)?,
+            'W' => nanos = nanos.checked_add(amount.checked_mul(7 * NANOS_PER_DAY)?)?,
+            'D' => nanos = nanos.checked_add(amount.checked_mul(NANOS_PER_DAY)?)?,
+            _ => return None,
+        }
+    }
+    for (amount, unit) in time.map_or(Some(Vec::new()), components)? {
+        nanos = nanos.checked_add(match unit.to_ascii_uppercase() {
+            'H' => whole(amount)?.checked_mul(NANOS_PER_HOUR)?,
+            'M' => whole(amount)?.checked_mul(NANOS_PER_MINUTE)?,
+            'S' => seconds(amount)?,
+            _ => return None,
+        })?;
+    }
+    if negative {
+        months = months.checked_neg()?;
+        nanos = nanos.checked_neg()?;
+    }
+    Some(Duration {
+        months: i64::try_from(months).ok()?,
+        nanoseconds: i64::try_from(nanos).ok()?,
+    })
+}
+
+/// Split `1Y-2M` into `[("1", 'Y'), ("-2", 'M')]`.
+fn components(text: &str) -> Option<Vec<(&str, char)>> {
+    let mut out = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let at = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let unit = rest[at..].chars().next()?;
+        if at == 0 {
+            return None;
+        }
+        out.push((&rest[..at], unit));
+        rest = &rest[at + unit.len_utf8()..];
+    }
+    Some(out)
+}
+
+fn whole(amount: &str) -> Option<i128> {
+    if amount.bytes().any(|b| b.is_ascii_digit()) {
+        amount.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Parse `[-]S[.fraction]` seconds into nanoseconds.
+fn seconds(amount: &str) -> Option<i128> {
+    let (negative, unsigned) = match amount.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, amount.strip_prefix('+').unwrap_or(amount)),
+    };
+    let (whole_part, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if whole_part.is_empty()
+        || !whole_part.bytes().all(|b| b.is_ascii_digit())
+        || fraction.len() > 9
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let nanos = whole_part
+        .parse::<i128>()
+        .ok()?
+        .checked_mul(NANOS_PER_SECOND)?
+        + if fraction.is_empty() {
+            0
+        } else {
+            format!("{fraction:0<9}").parse::<i128>().ok()?
+        };
+    Some(if negative { -nanos } else { nanos })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Date, LocalDateTime, LocalTime, Node, ZonedTime};
+
+    fn round_trip(value: &Value) -> String {
+        let literal = value.to_gql_literal().unwrap();
+        assert_eq!(&Value::parse_literal(&literal).unwrap(), value, "{literal}");
+        literal
+    }
+
+    #[test]
+    fn scalars_round_trip() {
+        assert_eq!(round_trip(&Value::Null), "NULL");
+        assert_eq!(round_trip(&Value::Boolean(false)), "FALSE");
+        assert_eq!(round_trip(&Value::Integer(-42)), "-42");
+        assert_eq!(round_trip(&Value::Float(1.5)), "1.5E0");
+        assert_eq!(round_trip(&Value::Float(-2e-300)), "-2E-300");
+        assert_eq!(round_trip(&Value::from("it's\n")), r"'it\'s\n'");
+        assert_eq!(round_trip(&Value::Bytes(vec![0x0a, 0xff])), "X'0AFF'");
+        round_trip(&Value::UnsignedInteger(u64::MAX));
+        round_trip(&Value::from(i128::MIN));
+    }
+
+    #[test]
+    fn numbers_follow_gql_exactness() {
+        assert_eq!(
+            Value::parse_literal("12.50").unwrap(),
+            Value::Decimal {
+                unscaled: vec![0x04, 0xe2],
+                scale: 2,
+            }
+        );
+        assert_eq!(Value::parse_literal("1.5d").unwrap(), Value::Float(1.5));
+        assert_eq!(Value::parse_literal("3F").unwrap(), Value::Float(3.0));
+        assert_eq!(
+            Value::parse_literal("1.2E3M").unwrap(),
+            Value::Decimal {
+                unscaled: vec![12],
+                scale: -2,
+            }
+        );
+        assert_eq!(
+            Value::parse_literal("-128M").unwrap(),
+            Value::Decimal {
+                unscaled: vec![0x80],
+                scale: 0,
+            }
+        );
+        for literal in ["12.50", "-0.005", "128M", "12E2M"] {
+            let value = Value::parse_literal(literal).unwrap();
+            assert_eq!(value.to_gql_literal().unwrap(), literal);
+        }
+    }
+
+    #[test]
+    fn temporals_round_trip() {
+        let date = Date {
+            year: 2026,
+            month: 1,
+            day: 1,
+        };
+        let time = LocalTime {
+            hour: 14,
+            minute: 30,
+            second: 0,
+            nanosecond: 0,
+        };
+        assert_eq!(round_trip(&Value::Date(date)), "DATE '2026-01-01'");
+        assert_eq!(
+            round_trip(&Value::ZonedTime(ZonedTime {
+                time,
+                offset_minutes: 60,
+            })),
+            "TIME '14:30:00+01:00'"
+        );
+        assert_eq!(
+            round_trip(&Value::LocalDateTime(LocalDateTime { date, time })),
+            "DATETIME '2026-01-01T14:30:00'"
+        );
+        assert!(matches!(
+            Value::parse_literal("timestamp '2026-01-01T00:00:00Z'").unwrap(),
+            Value::ZonedDateTime(_)
+        ));
+        assert!(Value::parse_literal("LOCAL_TIME '14:30:00+01:00'").is_err());
+    }
+
+    #[test]
+    fn durations_round_trip() {
+        let parsed = Value::parse_literal("DURATION 'P1Y2M3DT4H5M6.5S'").unwrap();
+        assert_eq!(
+            parsed,
+            Value::Duration(Duration {
+                months: 14,
+                nanoseconds: 3 * 86_400_000_000_000 + 14_706_500_000_000,
+            })
+        );
+        assert_eq!(round_trip(&parsed), "DURATION 'P1Y2M3DT4H5M6.5S'");
+        for (months, nanoseconds, literal) in [
+            (0, 0, "DURATION 'PT0S'"),
+            (-1, -1_000_000_000, "DURATION '-P1MT1S'"),
+            (1, -500_000_000, "DURATION 'P1MT-0.5S'"),
+        ] {
+            let value = Value::Duration(Duration {
+                months,
+                nanoseconds,
+            });
+            assert_eq!(round_trip(&value), literal);
+        }
+        assert_eq!(
+            Value::parse_literal("DURATION 'P2W'").unwrap(),
+            Value::Duration(Duration {
+                months: 0,
+                nanoseconds: 14 * 86_400_000_000_000,
+            })
+        );
+        assert!(Value::parse_literal("DURATION 'P'").is_err());
+    }
+
+    #[test]
+    fn duration_overflow_is_an_error() {
+        assert!(
+            Value::parse_literal("DURATION 'P170141183460469231731687303715884105727M1M'").is_err()
+        );
+        assert!(
+            Value::parse_literal("DURATION '-P-170141183460469231731687303715884105728M'").is_err()
+        );
+    }
+
+    #[test]
+    fn collections_round_trip() {
+        let record = Value::Record(
+            Record::new()
+                .with_field("name", "Alice")
+                .with_field("two words", Value::List(vec![Value::Integer(1)])),
+        );
+        assert_eq!(round_trip(&record), "{name: 'Alice', `two words`: [1]}");
+        assert_eq!(
+            Value::parse_literal(" RECORD { a : LIST [ ] , \"b\": {} } ").unwrap(),
+            Value::Record(
+                Record::new()
+                    .with_field("a", Value::List(vec![]))
+                    .with_field("b", Value::Record(Record::new()))
+            )
+        );
+    }
+
+    #[test]
+    fn values_without_literals_and_bad_input() {
+        assert_eq!(Value::Node(Node::new(vec![1])).to_gql_literal(), None);
+        assert_eq!(Value::Float(f64::NAN).to_gql_literal(), None);
+
+        let err = Value::parse_literal("[1, 2").unwrap_err();
+        assert_eq!(err.offset(), 5);
+        assert!(Value::parse_literal("'open").is_err());
+        assert!(Value::parse_literal("1 2").is_err());
+        assert!(Value::parse_literal("12abc").is_err());
+        assert!(Value::parse_literal("X'ABC'").is_err());
+        assert!(Value::parse_literal("MAYBE").is_err());
+    }
+}
@@ -4,16 +4,53 @@
 //! Backend implementors and client users interact with these types rather
 //! than the raw protobuf representations.
 
+#[cfg(feature = "chrono")]
+mod chrono_interop;
 mod edge;
+mod encrypted;
+mod literal;
 mod node;
+#[cfg(feature = "num-bigint")]
+mod num_bigint_interop;
 mod path;
 mod record;
+#[cfg(feature = "serde")]
+mod serialization;
 mod temporal;
 mod value;
 
+#[cfg(feature = "chrono")]
+pub use chrono_interop::TemporalRangeError;
 pub use edge::Edge;
+pub use encrypted::{CipherError, EncryptedValue, ValueCipher};
+pub use literal::ParseLiteralError;
 pub use node::Node;
 pub use path::Path;
 pub use record::{Field, Record};
 pub use temporal::{Date, Duration, LocalDateTime, LocalTime, ZonedDateTime, ZonedTime};
-pub use value::Value;
+pub use value::{Value, ValueConversionError};
+
+/// Build a property map, `HashMap<String, Value>`, from `name => value`
+/// pairs. Names convert with `Into<String>` and values with
+/// `Value::from`.
+///
+/// ```
+/// use gwp::props;
+/// use gwp::types::{Node, Value};
+///
+/// let node = Node::new(vec![1])
+///     .with_label("Person")
+///     .with_properties(props! { "name" => "Alice", "age" => 30 });
+/// assert_eq!(node.property("age"), Some(&Value::Integer(30)));
+/// ```
+#[macro_export]
+macro_rules! props {
+    ($($name:expr => $value:expr),* $(,)?) => {
+        ::std::collections::HashMap::<::std::string::String, $crate::types::Value>::from([
+            $((
+                ::std::convert::Into::<::std::string::String>::into($name),
+                $crate::types::Value::from($value),
+            )),*
+        ])
+    };
+}
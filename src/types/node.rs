@@ -7,8 +7,10 @@ use crate::proto;
 
 /// A property graph node with an opaque ID, labels, and properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     /// Opaque element identifier.
+    #[cfg_attr(feature = "serde", serde(with = "super::serialization::base64"))]
     pub id: Vec<u8>,
     /// Label set (unordered).
     pub labels: Vec<String>,
@@ -41,6 +43,21 @@ impl Node {
         self
     }
 
+    /// Add several properties to the node, such as those built with
+    /// [`props!`](crate::props).
+    #[must_use]
+    pub fn with_properties<K: Into<String>, V: Into<Value>>(
+        mut self,
+        properties: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.properties.extend(
+            properties
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into())),
+        );
+        self
+    }
+
     /// Get a property value by name.
     #[must_use]
     pub fn property(&self, name: &str) -> Option<&Value> {
@@ -82,6 +99,8 @@ impl From<Node> for proto::Node {
                 .into_iter()
                 .map(|(k, v)| (k, proto::Value::from(v)))
                 .collect(),
+            label_refs: Vec::new(),
+            property_refs: HashMap::new(),
         }
     }
 }
@@ -109,6 +128,19 @@ mod tests {
         assert_eq!(node.property("missing"), None);
     }
 
+    #[test]
+    fn props_macro_builds_property_maps() {
+        let node = Node::new(vec![1])
+            .with_property("name", "Alice")
+            .with_properties(
+                crate::props! { "name" => "Bob", "age" => 30, "tags" => vec![Value::from("a")] },
+            );
+        assert_eq!(node.property("name"), Some(&Value::from("Bob")));
+        assert_eq!(node.property("age"), Some(&Value::Integer(30)));
+        assert_eq!(node.properties.len(), 3);
+        assert!(crate::props! {}.is_empty());
+    }
+
     #[test]
     fn round_trip() {
         let node = Node::new(vec![0x01, 0x02])
@@ -0,0 +1,95 @@
+//! Conversions between `Value` and `num-bigint` integers, via the
+//! `num-bigint` feature.
+//!
+//! `BigInt` converts to a signed `Value::BigInteger` and `BigUint` to an
+//! unsigned one, in the shortest big-endian encoding. Both convert back
+//! from `Integer`, `UnsignedInteger`, and `BigInteger` values of any width.
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use super::{Value, ValueConversionError};
+
+impl From<BigInt> for Value {
+    fn from(v: BigInt) -> Self {
+        Self::BigInteger {
+            value: v.to_signed_bytes_be(),
+            is_signed: true,
+        }
+    }
+}
+
+impl From<BigUint> for Value {
+    fn from(v: BigUint) -> Self {
+        Self::BigInteger {
+            value: v.to_bytes_be(),
+            is_signed: false,
+        }
+    }
+}
+
+impl TryFrom<Value> for BigInt {
+    type Error = ValueConversionError;
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Integer(i) => Ok(Self::from(i)),
+            Value::UnsignedInteger(u) => Ok(Self::from(u)),
+            Value::BigInteger {
+                value,
+                is_signed: true,
+            } => Ok(Self::from_signed_bytes_be(&value)),
+            Value::BigInteger {
+                value,
+                is_signed: false,
+            } => Ok(Self::from_bytes_be(Sign::Plus, &value)),
+            other => Err(ValueConversionError::UnexpectedType {
+                expected: "Integer",
+                actual: other.type_name(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<Value> for BigUint {
+    type Error = ValueConversionError;
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        let signed = BigInt::try_from(v)?;
+        signed
+            .to_biguint()
+            .ok_or_else(|| ValueConversionError::OutOfRange {
+                value: signed.to_string(),
+                target: "BigUint",
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_integers_round_trip() {
+        let big: BigInt = BigInt::from(i128::MIN) * 1000;
+        let value = Value::from(big.clone());
+        assert_eq!(BigInt::try_from(value).unwrap(), big);
+
+        let unsigned = BigUint::from(u128::MAX) + 1u32;
+        let value = Value::from(unsigned.clone());
+        assert_eq!(BigUint::try_from(value.clone()).unwrap(), unsigned);
+        assert_eq!(BigInt::try_from(value).unwrap(), BigInt::from(unsigned));
+    }
+
+    #[test]
+    fn agrees_with_128_bit_encodings() {
+        assert_eq!(
+            BigInt::try_from(Value::from(-3i128)).unwrap(),
+            BigInt::from(-3)
+        );
+        assert_eq!(i128::try_from(Value::from(BigInt::from(-3))).unwrap(), -3);
+        assert_eq!(
+            BigUint::try_from(Value::UnsignedInteger(7)).unwrap(),
+            BigUint::from(7u32)
+        );
+        assert!(BigUint::try_from(Value::from(-1i128)).is_err());
+        assert!(BigInt::try_from(Value::from("1")).is_err());
+    }
+}
@@ -8,6 +8,7 @@ use crate::proto;
 /// Consists of an alternating sequence of nodes and edges where
 /// `edges[i]` connects `nodes[i]` and `nodes[i+1]`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Path {
     /// Nodes in the path (length = `edges.len()` + 1 for non-empty paths).
     pub nodes: Vec<Node>,
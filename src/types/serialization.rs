@@ -0,0 +1,543 @@
+//! Serde support for values and graph types, via the `serde` feature.
+//!
+//! Values map onto the serde data model so that JSON output reads
+//! naturally, and values JSON has no type for are wrapped in a
+//! single-key object whose key names the type:
+//!
+//! | GQL | JSON |
+//! |-----|------|
+//! | `NULL`, `BOOLEAN`, `STRING` | `null`, `true`, `"text"` |
+//! | `INTEGER` | `42` |
+//! | `UNSIGNED INTEGER` | `{"$uint": 42}` |
+//! | `FLOAT` | `1.5`, or `{"$float": "NaN"}`, `"inf"`, `"-inf"` |
+//! | `BYTES` | `{"$bytes": "AQI="}`, standard base64 |
+//! | `DATE` | `{"$date": "2026-02-13"}` |
+//! | `LOCAL TIME`, `ZONED TIME` | `{"$local_time": "14:30:00"}`, `{"$zoned_time": "14:30:00+01:00"}` |
+//! | `LOCAL DATETIME` | `{"$local_datetime": "2026-02-13T14:30:00"}` |
+//! | `ZONED DATETIME` | `{"$zoned_datetime": "2026-02-13T14:30:00-05:00"}` |
+//! | `DURATION` | `{"$duration": {"months": 1, "nanoseconds": 0}}` |
+//! | `LIST` | array |
+//! | `RECORD` | object, wrapped as `{"$record": {...}}` if its first field name starts with `$` |
+//! | node, edge, path | `{"$node": {...}}`, `{"$edge": {...}}`, `{"$path": {...}}` |
+//! | `DECIMAL` | `{"$decimal": {"unscaled": "AQI=", "scale": 2}}` |
+//! | big integers and floats | `{"$big_integer": {"value", "signed"}}`, `{"$big_float": {"value", "width"}}` |
+//! | encrypted values | `{"$encrypted": {"key_id", "algorithm", "ciphertext"}}` |
+//!
+//! Temporals serialize on their own as the ISO 8601 strings above, and
+//! nodes, edges, and paths as objects of their fields, with ids in
+//! base64. Deserializing a value needs a self-describing format such as
+//! JSON; integers above `i64::MAX` without a tag read as unsigned.
+
+use std::fmt;
+
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+use super::temporal::{
+    parse_date, parse_local_datetime, parse_local_time, parse_zoned_datetime, parse_zoned_time,
+};
+use super::{Date, Field, LocalDateTime, LocalTime, Record, Value, ZonedDateTime, ZonedTime};
+
+// ============================================================================
+// Value
+// ============================================================================
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Null => serializer.serialize_unit(),
+            Self::Boolean(b) => serializer.serialize_bool(*b),
+            Self::Integer(i) => serializer.serialize_i64(*i),
+            Self::UnsignedInteger(u) => tagged(serializer, "$uint", u),
+            Self::Float(f) if f.is_finite() => serializer.serialize_f64(*f),
+            Self::Float(f) => tagged(serializer, "$float", &f.to_string()),
+            Self::String(s) => serializer.serialize_str(s),
+            Self::Bytes(b) => tagged(serializer, "$bytes", &Base64(b)),
+            Self::Date(d) => tagged(serializer, "$date", d),
+            Self::LocalTime(t) => tagged(serializer, "$local_time", t),
+            Self::ZonedTime(t) => tagged(serializer, "$zoned_time", t),
+            Self::LocalDateTime(dt) => tagged(serializer, "$local_datetime", dt),
+            Self::ZonedDateTime(dt) => tagged(serializer, "$zoned_datetime", dt),
+            Self::Duration(d) => tagged(serializer, "$duration", d),
+            Self::List(items) => serializer.collect_seq(items),
+            Self::Record(r) if r.fields.first().is_some_and(|f| f.name.starts_with('$')) => {
+                tagged(serializer, "$record", r)
+            }
+            Self::Record(r) => r.serialize(serializer),
+            Self::Node(n) => tagged(serializer, "$node", n),
+            Self::Edge(e) => tagged(serializer, "$edge", e),
+            Self::Path(p) => tagged(serializer, "$path", p),
+            Self::Decimal { unscaled, scale } => tagged(
+                serializer,
+                "$decimal",
+                &Decimal {
+                    unscaled: Base64Buf(unscaled.clone()),
+                    scale: *scale,
+                },
+            ),
+            Self::BigInteger { value, is_signed } => tagged(
+                serializer,
+                "$big_integer",
+                &BigInteger {
+                    value: Base64Buf(value.clone()),
+                    signed: *is_signed,
+                },
+            ),
+            Self::BigFloat { value, width } => tagged(
+                serializer,
+                "$big_float",
+                &BigFloat {
+                    value: Base64Buf(value.clone()),
+                    width: *width,
+                },
+            ),
+            Self::Encrypted(e) => tagged(serializer, "$encrypted", e),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a GQL value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Integer(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(i64::try_from(v).map_or(Value::UnsignedInteger(v), Value::Integer))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::List(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let Some(key) = map.next_key::<String>()? else {
+            return Ok(Value::Record(Record::new()));
+        };
+        if !key.starts_with('$') {
+            let mut fields = vec![Field {
+                name: key,
+                value: map.next_value()?,
+            }];
+            while let Some((name, value)) = map.next_entry()? {
+                fields.push(Field { name, value });
+            }
+            return Ok(Value::Record(Record { fields }));
+        }
+
+        let value = match key.as_str() {
+            "$uint" => Value::UnsignedInteger(map.next_value()?),
+            "$float" => {
+                let text: String = map.next_value()?;
+                Value::Float(text.parse().map_err(|_| {
+                    de::Error::invalid_value(de::Unexpected::Str(&text), &"NaN, inf, or -inf")
+                })?)
+            }
+            "$bytes" => Value::Bytes(map.next_value::<Base64Buf>()?.0),
+            "$date" => Value::Date(map.next_value()?),
+            "$local_time" => Value::LocalTime(map.next_value()?),
+            "$zoned_time" => Value::ZonedTime(map.next_value()?),
+            "$local_datetime" => Value::LocalDateTime(map.next_value()?),
+            "$zoned_datetime" => Value::ZonedDateTime(map.next_value()?),
+            "$duration" => Value::Duration(map.next_value()?),
+            "$record" => Value::Record(map.next_value()?),
+            "$node" => Value::Node(map.next_value()?),
+            "$edge" => Value::Edge(map.next_value()?),
+            "$path" => Value::Path(map.next_value()?),
+            "$decimal" => {
+                let d: Decimal = map.next_value()?;
+                Value::Decimal {
+                    unscaled: d.unscaled.0,
+                    scale: d.scale,
+                }
+            }
+            "$big_integer" => {
+                let b: BigInteger = map.next_value()?;
+                Value::BigInteger {
+                    value: b.value.0,
+                    is_signed: b.signed,
+                }
+            }
+            "$big_float" => {
+                let b: BigFloat = map.next_value()?;
+                Value::BigFloat {
+                    value: b.value.0,
+                    width: b.width,
+                }
+            }
+            "$encrypted" => Value::Encrypted(map.next_value()?),
+            _ => return Err(de::Error::unknown_field(&key, TAGS)),
+        };
+        if map.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::custom(format!(
+                "`{key}` must be the only key of a tagged value"
+            )));
+        }
+        Ok(value)
+    }
+}
+
+const TAGS: &[&str] = &[
+    "$uint",
+    "$float",
+    "$bytes",
+    "$date",
+    "$local_time",
+    "$zoned_time",
+    "$local_datetime",
+    "$zoned_datetime",
+    "$duration",
+    "$record",
+    "$node",
+    "$edge",
+    "$path",
+    "$decimal",
+    "$big_integer",
+    "$big_float",
+    "$encrypted",
+];
+
+fn tagged<S: Serializer, T: Serialize + ?Sized>(
+    serializer: S,
+    tag: &str,
+    value: &T,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(tag, value)?;
+    map.end()
+}
+
+#[derive(Serialize, Deserialize)]
+struct Decimal {
+    unscaled: Base64Buf,
+    scale: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BigInteger {
+    value: Base64Buf,
+    signed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BigFloat {
+    value: Base64Buf,
+    width: u32,
+}
+
+// ============================================================================
+// Record
+// ============================================================================
+
+impl Serialize for Record {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in &self.fields {
+            map.serialize_entry(&field.name, &field.value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Record {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RecordVisitor;
+
+        impl<'de> Visitor<'de> for RecordVisitor {
+            type Value = Record;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map of field names to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Record, A::Error> {
+                let mut fields = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some((name, value)) = map.next_entry()? {
+                    fields.push(Field { name, value });
+                }
+                Ok(Record { fields })
+            }
+        }
+
+        deserializer.deserialize_map(RecordVisitor)
+    }
+}
+
+// ============================================================================
+// Temporals
+// ============================================================================
+
+macro_rules! iso_8601 {
+    ($type:ident, $variant:ident, $parse:ident, $expecting:literal) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(&Value::$variant(*self))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let text = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                $parse(&text).ok_or_else(|| {
+                    de::Error::invalid_value(de::Unexpected::Str(&text), &$expecting)
+                })
+            }
+        }
+    };
+}
+
+iso_8601!(Date, Date, parse_date, "an ISO 8601 date");
+iso_8601!(LocalTime, LocalTime, parse_local_time, "an ISO 8601 time");
+iso_8601!(
+    ZonedTime,
+    ZonedTime,
+    parse_zoned_time,
+    "an ISO 8601 time with offset"
+);
+iso_8601!(
+    LocalDateTime,
+    LocalDateTime,
+    parse_local_datetime,
+    "an ISO 8601 datetime"
+);
+iso_8601!(
+    ZonedDateTime,
+    ZonedDateTime,
+    parse_zoned_datetime,
+    "an ISO 8601 datetime with offset"
+);
+
+// ============================================================================
+// Bytes
+// ============================================================================
+
+/// Serde `with` module encoding byte fields as standard base64 strings.
+pub(super) mod base64 {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub(in crate::types) fn serialize<S: Serializer>(
+        bytes: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub(in crate::types) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let text = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        STANDARD.decode(text.as_bytes()).map_err(de::Error::custom)
+    }
+}
+
+struct Base64<'a>(&'a [u8]);
+
+impl Serialize for Base64<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        base64::serialize(self.0, serializer)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct Base64Buf(#[serde(with = "base64")] Vec<u8>);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{Duration, Edge, EncryptedValue, Node, Path};
+
+    fn round_trip(value: &Value) -> serde_json::Value {
+        let json = serde_json::to_value(value).unwrap();
+        let back: Value = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(&back, value, "{json}");
+        json
+    }
+
+    #[test]
+    fn scalars_map_to_plain_json() {
+        assert_eq!(round_trip(&Value::Null), json!(null));
+        assert_eq!(round_trip(&Value::Boolean(true)), json!(true));
+        assert_eq!(round_trip(&Value::Integer(-7)), json!(-7));
+        assert_eq!(round_trip(&Value::Float(1.5)), json!(1.5));
+        assert_eq!(round_trip(&Value::from("hi")), json!("hi"));
+        assert_eq!(
+            round_trip(&Value::List(vec![Value::Integer(1), Value::Null])),
+            json!([1, null])
+        );
+        assert_eq!(
+            round_trip(&Value::Record(
+                Record::new().with_field("a", 1).with_field("b", "x")
+            )),
+            json!({"a": 1, "b": "x"})
+        );
+    }
+
+    #[test]
+    fn other_values_are_tagged() {
+        assert_eq!(
+            round_trip(&Value::UnsignedInteger(u64::MAX)),
+            json!({"$uint": u64::MAX})
+        );
+        assert_eq!(
+            round_trip(&Value::Bytes(vec![1, 2])),
+            json!({"$bytes": "AQI="})
+        );
+        assert_eq!(
+            round_trip(&Value::Float(f64::NEG_INFINITY)),
+            json!({"$float": "-inf"})
+        );
+        let nan = serde_json::to_value(Value::Float(f64::NAN)).unwrap();
+        assert!(matches!(
+            serde_json::from_value(nan).unwrap(),
+            Value::Float(f) if f.is_nan()
+        ));
+        assert_eq!(
+            round_trip(&Value::Duration(Duration {
+                months: 14,
+                nanoseconds: -5,
+            })),
+            json!({"$duration": {"months": 14, "nanoseconds": -5}})
+        );
+        round_trip(&Value::Decimal {
+            unscaled: vec![0x30, 0x39],
+            scale: 2,
+        });
+        round_trip(&Value::BigInteger {
+            value: vec![0xff; 16],
+            is_signed: true,
+        });
+        round_trip(&Value::BigFloat {
+            value: vec![0; 16],
+            width: 128,
+        });
+        round_trip(&Value::Encrypted(EncryptedValue {
+            key_id: "k1".into(),
+            algorithm: "AES-256-GCM".into(),
+            ciphertext: vec![9, 9],
+        }));
+    }
+
+    #[test]
+    fn temporals_are_iso_8601() {
+        let time = LocalTime {
+            hour: 14,
+            minute: 30,
+            second: 0,
+            nanosecond: 500_000_000,
+        };
+        let date = Date {
+            year: 2026,
+            month: 2,
+            day: 13,
+        };
+        assert_eq!(
+            round_trip(&Value::Date(date)),
+            json!({"$date": "2026-02-13"})
+        );
+        assert_eq!(
+            round_trip(&Value::LocalDateTime(LocalDateTime { date, time })),
+            json!({"$local_datetime": "2026-02-13T14:30:00.500000000"})
+        );
+        assert_eq!(
+            round_trip(&Value::ZonedTime(ZonedTime {
+                time,
+                offset_minutes: 60,
+            })),
+            json!({"$zoned_time": "14:30:00.500000000+01:00"})
+        );
+        round_trip(&Value::ZonedDateTime(ZonedDateTime {
+            date: Date {
+                year: -500,
+                month: 1,
+                day: 1,
+            },
+            time,
+            offset_minutes: -300,
+        }));
+
+        let utc: ZonedDateTime = serde_json::from_str("\"2026-02-13T14:30:00.5Z\"").unwrap();
+        assert_eq!(utc.time, time);
+        assert_eq!(utc.offset_minutes, 0);
+        assert!(serde_json::from_str::<Date>("\"2026-13-01\"").is_err());
+        assert!(serde_json::from_str::<LocalTime>("\"25:00:00\"").is_err());
+    }
+
+    #[test]
+    fn graph_elements_round_trip() {
+        let alice = Node::new(vec![1])
+            .with_label("Person")
+            .with_property("age", 30);
+        let bob = Node::new(vec![2]).with_label("Person");
+        let knows = Edge::directed(vec![3], vec![1], vec![2]).with_label("KNOWS");
+        let json = round_trip(&Value::Node(alice.clone()));
+        assert_eq!(json["$node"]["id"], json!("AQ=="));
+        round_trip(&Value::Edge(knows.clone()));
+        round_trip(&Value::Path(Path::from_node(alice).with_step(knows, bob)));
+    }
+
+    #[test]
+    fn records_with_tag_like_names_are_wrapped() {
+        let record = Record::new().with_field("$date", "not a date");
+        assert_eq!(
+            round_trip(&Value::Record(record)),
+            json!({"$record": {"$date": "not a date"}})
+        );
+        assert!(serde_json::from_value::<Value>(json!({"$nope": 1})).is_err());
+        assert!(serde_json::from_value::<Value>(json!({"$uint": 1, "b": 2})).is_err());
+    }
+}
@@ -57,6 +57,7 @@ pub struct ZonedDateTime {
 
 /// Temporal duration with two components per ISO/IEC 39075.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Duration {
     /// Year-to-month component.
     pub months: i64,
@@ -222,6 +223,93 @@ impl From<Duration> for proto::Duration {
     }
 }
 
+// ============================================================================
+// ISO 8601 parsing
+// ============================================================================
+
+/// Parse `[-]YYYY-MM-DD`.
+pub(super) fn parse_date(text: &str) -> Option<Date> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text),
+    };
+    let mut parts = unsigned.splitn(3, '-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some(Date {
+        year: sign * year,
+        month,
+        day,
+    })
+}
+
+/// Parse `HH:MM:SS[.fraction]`.
+pub(super) fn parse_local_time(text: &str) -> Option<LocalTime> {
+    let (hms, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let mut parts = hms.splitn(3, ':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second = parts.next()?.parse().ok()?;
+    let nanosecond = if fraction.is_empty() {
+        0
+    } else if fraction.len() <= 9 && fraction.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{fraction:0<9}").parse().ok()?
+    } else {
+        return None;
+    };
+    (hour < 24 && minute < 60 && second < 60).then_some(LocalTime {
+        hour,
+        minute,
+        second,
+        nanosecond,
+    })
+}
+
+/// Split a trailing `Z` or `±HH:MM` offset from `text`.
+fn split_offset(text: &str) -> Option<(&str, i32)> {
+    if let Some(rest) = text.strip_suffix('Z') {
+        return Some((rest, 0));
+    }
+    let at = text.len().checked_sub(6)?;
+    let (rest, offset) = text.split_at_checked(at)?;
+    let sign = match offset.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let (hours, minutes) = offset[1..].split_once(':')?;
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    (minutes < 60).then_some((rest, sign * (hours * 60 + minutes)))
+}
+
+pub(super) fn parse_zoned_time(text: &str) -> Option<ZonedTime> {
+    let (time, offset_minutes) = split_offset(text)?;
+    Some(ZonedTime {
+        time: parse_local_time(time)?,
+        offset_minutes,
+    })
+}
+
+pub(super) fn parse_local_datetime(text: &str) -> Option<LocalDateTime> {
+    let (date, time) = text.split_once('T')?;
+    Some(LocalDateTime {
+        date: parse_date(date)?,
+        time: parse_local_time(time)?,
+    })
+}
+
+pub(super) fn parse_zoned_datetime(text: &str) -> Option<ZonedDateTime> {
+    let (datetime, offset_minutes) = split_offset(text)?;
+    let LocalDateTime { date, time } = parse_local_datetime(datetime)?;
+    Some(ZonedDateTime {
+        date,
+        time,
+        offset_minutes,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,7 +5,8 @@ use std::fmt;
 use crate::proto;
 
 use super::{
-    Date, Duration, Edge, LocalDateTime, LocalTime, Node, Path, Record, ZonedDateTime, ZonedTime,
+    Date, Duration, Edge, EncryptedValue, LocalDateTime, LocalTime, Node, Path, Record,
+    ZonedDateTime, ZonedTime,
 };
 
 /// A GQL value - the discriminated union of all types that can appear
@@ -69,6 +70,8 @@ pub enum Value {
         /// Bit width (128 or 256).
         width: u32,
     },
+    /// Client-side encrypted value, opaque to the server.
+    Encrypted(EncryptedValue),
 }
 
 // ============================================================================
@@ -165,151 +168,173 @@ impl From<Record> for Value {
     }
 }
 
-// ============================================================================
-// TryFrom implementations for extracting typed values
-// ============================================================================
-
-impl TryFrom<Value> for bool {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Boolean(b) => Ok(b),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Boolean, got {}",
-                other.type_name()
-            ))),
+impl From<i128> for Value {
+    fn from(v: i128) -> Self {
+        Self::BigInteger {
+            value: v.to_be_bytes().to_vec(),
+            is_signed: true,
         }
     }
 }
 
-impl TryFrom<Value> for i64 {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Integer(i) => Ok(i),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Integer, got {}",
-                other.type_name()
-            ))),
+impl From<u128> for Value {
+    fn from(v: u128) -> Self {
+        Self::BigInteger {
+            value: v.to_be_bytes().to_vec(),
+            is_signed: false,
         }
     }
 }
 
-impl TryFrom<Value> for u64 {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::UnsignedInteger(u) => Ok(u),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected UnsignedInteger, got {}",
-                other.type_name()
-            ))),
-        }
-    }
+// ============================================================================
+// TryFrom implementations for extracting typed values
+// ============================================================================
+
+/// Error returned when a [`Value`] does not convert to a Rust type.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValueConversionError {
+    /// The value is not of a variant the target type converts from.
+    #[error("expected {expected}, got {actual}")]
+    UnexpectedType {
+        /// The variant the target type converts from.
+        expected: &'static str,
+        /// The variant of the value, as named by [`Value::type_name`].
+        actual: &'static str,
+    },
+    /// The value is of the right variant but outside the target's range.
+    #[error("{value} out of range for {target}")]
+    OutOfRange {
+        /// The value in its display form.
+        value: std::string::String,
+        /// The target type.
+        target: &'static str,
+    },
 }
 
-impl TryFrom<Value> for f64 {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Float(f) => Ok(f),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Float, got {}",
-                other.type_name()
-            ))),
+impl ValueConversionError {
+    fn unexpected(expected: &'static str, actual: &Value) -> Self {
+        Self::UnexpectedType {
+            expected,
+            actual: actual.type_name(),
         }
     }
-}
 
-impl TryFrom<Value> for std::string::String {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::String(s) => Ok(s),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected String, got {}",
-                other.type_name()
-            ))),
+    fn out_of_range(value: &impl fmt::Display, target: &'static str) -> Self {
+        Self::OutOfRange {
+            value: value.to_string(),
+            target,
         }
     }
 }
 
-impl TryFrom<Value> for Vec<u8> {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Bytes(b) => Ok(b),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Bytes, got {}",
-                other.type_name()
-            ))),
-        }
+impl From<ValueConversionError> for crate::error::GqlError {
+    fn from(e: ValueConversionError) -> Self {
+        Self::Protocol(e.to_string())
     }
 }
 
-impl TryFrom<Value> for Vec<Value> {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::List(l) => Ok(l),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected List, got {}",
-                other.type_name()
-            ))),
-        }
-    }
+/// Implement `TryFrom<Value>` for the types a single variant wraps.
+macro_rules! try_from_variant {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl TryFrom<Value> for $ty {
+                type Error = ValueConversionError;
+                fn try_from(v: Value) -> Result<Self, Self::Error> {
+                    match v {
+                        Value::$variant(inner) => Ok(inner),
+                        other => Err(ValueConversionError::unexpected(
+                            stringify!($variant),
+                            &other,
+                        )),
+                    }
+                }
+            }
+        )*
+    };
 }
 
-impl TryFrom<Value> for Node {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Node(n) => Ok(n),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Node, got {}",
-                other.type_name()
-            ))),
-        }
-    }
+try_from_variant!(
+    bool => Boolean,
+    f64 => Float,
+    std::string::String => String,
+    Vec<u8> => Bytes,
+    Vec<Value> => List,
+    Record => Record,
+    Node => Node,
+    Edge => Edge,
+    Path => Path,
+    Date => Date,
+    LocalTime => LocalTime,
+    ZonedTime => ZonedTime,
+    LocalDateTime => LocalDateTime,
+    ZonedDateTime => ZonedDateTime,
+    Duration => Duration,
+    EncryptedValue => Encrypted,
+);
+
+/// Implement `TryFrom<Value>` for the integer types, from signed,
+/// unsigned, or big integers in range.
+macro_rules! try_from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<Value> for $ty {
+                type Error = ValueConversionError;
+                fn try_from(v: Value) -> Result<Self, Self::Error> {
+                    let out_of_range = |v: &Value| {
+                        ValueConversionError::out_of_range(v, stringify!($ty))
+                    };
+                    match &v {
+                        Value::Integer(i) => Self::try_from(*i).map_err(|_| out_of_range(&v)),
+                        Value::UnsignedInteger(u) => {
+                            Self::try_from(*u).map_err(|_| out_of_range(&v))
+                        }
+                        Value::BigInteger { value, is_signed } => {
+                            big_integer_to_i128(value, *is_signed)
+                                .and_then(|i| Self::try_from(i).ok())
+                                .or_else(|| {
+                                    big_integer_to_u128(value, *is_signed)
+                                        .and_then(|u| Self::try_from(u).ok())
+                                })
+                                .ok_or_else(|| out_of_range(&v))
+                        }
+                        other => Err(ValueConversionError::unexpected("Integer", other)),
+                    }
+                }
+            }
+        )*
+    };
 }
 
-impl TryFrom<Value> for Edge {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Edge(e) => Ok(e),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Edge, got {}",
-                other.type_name()
-            ))),
-        }
-    }
+try_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize);
+
+/// Decode a big-endian two's complement integer that fits in an `i128`.
+fn big_integer_to_i128(bytes: &[u8], is_signed: bool) -> Option<i128> {
+    let negative = is_signed && bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let buffer = sign_extend::<16>(bytes, if negative { 0xff } else { 0 })?;
+    let value = i128::from_be_bytes(buffer);
+    // Unsigned values with the top bit set are beyond `i128::MAX`.
+    ((value < 0) == negative).then_some(value)
 }
 
-impl TryFrom<Value> for Path {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Path(p) => Ok(p),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Path, got {}",
-                other.type_name()
-            ))),
-        }
+/// Decode a big-endian integer that is non-negative and fits in a `u128`.
+fn big_integer_to_u128(bytes: &[u8], is_signed: bool) -> Option<u128> {
+    if is_signed && bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        return None;
     }
+    sign_extend::<16>(bytes, 0).map(u128::from_be_bytes)
 }
 
-impl TryFrom<Value> for Record {
-    type Error = crate::error::GqlError;
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match v {
-            Value::Record(r) => Ok(r),
-            other => Err(crate::error::GqlError::Protocol(format!(
-                "expected Record, got {}",
-                other.type_name()
-            ))),
-        }
-    }
+/// Widen `bytes` to `N` bytes with `fill`, or `None` if dropping its
+/// leading bytes would lose anything but `fill`.
+fn sign_extend<const N: usize>(bytes: &[u8], fill: u8) -> Option<[u8; N]> {
+    let start = bytes.len().saturating_sub(N);
+    let (extra, tail) = bytes.split_at(start);
+    if extra.iter().any(|&b| b != fill) {
+        return None;
+    }
+    let mut buffer = [fill; N];
+    buffer[N - tail.len()..].copy_from_slice(tail);
+    Some(buffer)
 }
 
 // ============================================================================
@@ -342,6 +367,7 @@ impl Value {
             Self::Decimal { .. } => "Decimal",
             Self::BigInteger { .. } => "BigInteger",
             Self::BigFloat { .. } => "BigFloat",
+            Self::Encrypted(_) => "Encrypted",
         }
     }
 
@@ -449,6 +475,166 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Returns a reference to the encrypted envelope, if this is `Encrypted`.
+    #[must_use]
+    pub fn as_encrypted(&self) -> Option<&EncryptedValue> {
+        match self {
+            Self::Encrypted(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i64`, if this is an `Integer`, or an
+    /// `UnsignedInteger` or `BigInteger` in range.
+    #[must_use]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            Self::UnsignedInteger(u) => i64::try_from(*u).ok(),
+            Self::BigInteger { value, is_signed } => {
+                big_integer_to_i128(value, *is_signed).and_then(|i| i64::try_from(i).ok())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `u64`, if this is an `UnsignedInteger`, or a
+    /// non-negative `Integer` or `BigInteger` in range.
+    #[must_use]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Integer(i) => u64::try_from(*i).ok(),
+            Self::UnsignedInteger(u) => Some(*u),
+            Self::BigInteger { value, is_signed } => {
+                big_integer_to_u128(value, *is_signed).and_then(|u| u64::try_from(u).ok())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the date, if this is a `Date`.
+    #[must_use]
+    pub fn as_date(&self) -> Option<Date> {
+        match self {
+            Self::Date(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Returns the time, if this is a `LocalTime`.
+    #[must_use]
+    pub fn as_local_time(&self) -> Option<LocalTime> {
+        match self {
+            Self::LocalTime(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    /// Returns the time, if this is a `ZonedTime`.
+    #[must_use]
+    pub fn as_zoned_time(&self) -> Option<ZonedTime> {
+        match self {
+            Self::ZonedTime(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    /// Returns the datetime, if this is a `LocalDateTime`.
+    #[must_use]
+    pub fn as_local_datetime(&self) -> Option<LocalDateTime> {
+        match self {
+            Self::LocalDateTime(dt) => Some(*dt),
+            _ => None,
+        }
+    }
+
+    /// Returns the datetime, if this is a `ZonedDateTime`.
+    #[must_use]
+    pub fn as_zoned_datetime(&self) -> Option<ZonedDateTime> {
+        match self {
+            Self::ZonedDateTime(dt) => Some(*dt),
+            _ => None,
+        }
+    }
+
+    /// Returns the duration, if this is a `Duration`.
+    #[must_use]
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            Self::Duration(d) => Some(*d),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Consuming accessors (fallible, Result-returning)
+// ============================================================================
+
+impl Value {
+    /// Converts into the string, if this is a `String`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_string(self) -> Result<std::string::String, ValueConversionError> {
+        self.try_into()
+    }
+
+    /// Converts into the bytes, if this is a `Bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_bytes(self) -> Result<Vec<u8>, ValueConversionError> {
+        self.try_into()
+    }
+
+    /// Converts into the elements, if this is a `List`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_list(self) -> Result<Vec<Value>, ValueConversionError> {
+        self.try_into()
+    }
+
+    /// Converts into the record, if this is a `Record`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_record(self) -> Result<Record, ValueConversionError> {
+        self.try_into()
+    }
+
+    /// Converts into the node, if this is a `Node`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_node(self) -> Result<Node, ValueConversionError> {
+        self.try_into()
+    }
+
+    /// Converts into the edge, if this is an `Edge`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_edge(self) -> Result<Edge, ValueConversionError> {
+        self.try_into()
+    }
+
+    /// Converts into the path, if this is a `Path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueConversionError::UnexpectedType`] for other variants.
+    pub fn try_into_path(self) -> Result<Path, ValueConversionError> {
+        self.try_into()
+    }
 }
 
 // ============================================================================
@@ -458,7 +644,12 @@ impl Value {
 impl From<proto::Value> for Value {
     fn from(pv: proto::Value) -> Self {
         match pv.kind {
-            None | Some(proto::value::Kind::NullValue(_)) => Self::Null,
+            // Dictionary references are resolved when a batch is decoded
+            // and never appear outside a dictionary-encoded row batch.
+            None
+            | Some(proto::value::Kind::NullValue(_) | proto::value::Kind::DictionaryRef(_)) => {
+                Self::Null
+            }
             Some(proto::value::Kind::BooleanValue(v)) => Self::Boolean(v),
             Some(proto::value::Kind::IntegerValue(v)) => Self::Integer(v),
             Some(proto::value::Kind::UnsignedIntegerValue(v)) => Self::UnsignedInteger(v),
@@ -490,6 +681,7 @@ impl From<proto::Value> for Value {
                 value: v.value,
                 width: v.width,
             },
+            Some(proto::value::Kind::EncryptedValue(v)) => Self::Encrypted(v.into()),
         }
     }
 }
@@ -535,6 +727,7 @@ impl From<Value> for proto::Value {
                     width,
                 }))
             }
+            Value::Encrypted(e) => Some(proto::value::Kind::EncryptedValue(e.into())),
         };
         proto::Value { kind }
     }
@@ -577,6 +770,7 @@ impl fmt::Display for Value {
             Self::BigFloat { value, width } => {
                 write!(f, "BigFloat(0x{}, {width}bit)", hex_encode(value))
             }
+            Self::Encrypted(e) => write!(f, "Encrypted(key={}, {})", e.key_id, e.algorithm),
         }
     }
 }
@@ -819,6 +1013,84 @@ mod tests {
         });
     }
 
+    #[test]
+    fn big_integers_convert_to_128_bit_integers() {
+        for v in [0, -1, i128::MIN, i128::MAX, 1 << 100] {
+            assert_eq!(i128::try_from(Value::from(v)).unwrap(), v);
+        }
+        assert_eq!(u128::try_from(Value::from(u128::MAX)).unwrap(), u128::MAX);
+        assert_eq!(i128::try_from(Value::Integer(-5)).unwrap(), -5);
+        assert_eq!(
+            Value::from(-2i128),
+            Value::BigInteger {
+                value: [vec![0xff; 15], vec![0xfe]].concat(),
+                is_signed: true,
+            }
+        );
+
+        // Short and sign-extended encodings decode too.
+        let short = Value::BigInteger {
+            value: vec![0xff, 0x00],
+            is_signed: true,
+        };
+        assert_eq!(i128::try_from(short).unwrap(), -256);
+        let wide = Value::BigInteger {
+            value: vec![0xff; 32],
+            is_signed: true,
+        };
+        assert_eq!(i128::try_from(wide).unwrap(), -1);
+
+        assert!(i128::try_from(Value::from(u128::MAX)).is_err());
+        assert!(u128::try_from(Value::from(-1i128)).is_err());
+        let huge = Value::BigInteger {
+            value: [vec![1], vec![0; 16]].concat(),
+            is_signed: true,
+        };
+        assert!(i128::try_from(huge).is_err());
+    }
+
+    #[test]
+    fn conversions_name_expected_and_actual_types() {
+        assert_eq!(
+            i64::try_from(Value::from("x")),
+            Err(ValueConversionError::UnexpectedType {
+                expected: "Integer",
+                actual: "String",
+            })
+        );
+        assert_eq!(
+            Value::Integer(1).try_into_node().unwrap_err().to_string(),
+            "expected Node, got Integer"
+        );
+        assert_eq!(
+            u8::try_from(Value::Integer(300)).unwrap_err().to_string(),
+            "300 out of range for u8"
+        );
+        assert_eq!(i32::try_from(Value::UnsignedInteger(7)).unwrap(), 7);
+        assert_eq!(
+            Value::List(vec![Value::Null]).try_into_list().unwrap(),
+            [Value::Null]
+        );
+    }
+
+    #[test]
+    fn accessors_check_variant_and_range() {
+        assert_eq!(Value::UnsignedInteger(5).as_i64(), Some(5));
+        assert_eq!(Value::UnsignedInteger(u64::MAX).as_i64(), None);
+        assert_eq!(Value::Integer(-1).as_u64(), None);
+        assert_eq!(Value::from(42i128).as_u64(), Some(42));
+        assert_eq!(Value::from("x").as_i64(), None);
+
+        let date = Date {
+            year: 2026,
+            month: 1,
+            day: 1,
+        };
+        assert_eq!(Value::Date(date).as_date(), Some(date));
+        assert_eq!(Value::Date(date).as_duration(), None);
+        assert_eq!(Date::try_from(Value::Date(date)).unwrap(), date);
+    }
+
     #[test]
     fn round_trip_big_float() {
         round_trip(&Value::BigFloat {
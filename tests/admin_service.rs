@@ -0,0 +1,567 @@
+//! Integration tests for the `AdminService` gRPC service.
+
+use std::time::Duration;
+
+use gwp::error::GqlError;
+use gwp::server::mock_backend::{Latency, MockBackend, MockOperation};
+use gwp::server::{
+    GraphConfig, GraphFormat, IndexDefinition, IndexState, Job, JobOutput, JobState,
+};
+use gwp::testing::TestServer;
+
+// =========================================================================
+// Graph statistics tests
+// =========================================================================
+
+#[tokio::test]
+async fn graph_stats_memory_breakdown() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let stats = client.get_stats("default").await.unwrap();
+    let breakdown = stats.memory_breakdown.unwrap();
+
+    assert_eq!(breakdown.node_store_bytes, 4096);
+    assert_eq!(breakdown.index_bytes, 1024);
+    assert_eq!(breakdown.wal_buffer_bytes, 512);
+    assert_eq!(
+        breakdown.node_store_bytes
+            + breakdown.edge_store_bytes
+            + breakdown.property_store_bytes
+            + breakdown.index_bytes
+            + breakdown.wal_buffer_bytes,
+        stats.memory_bytes
+    );
+}
+
+// =========================================================================
+// Label statistics tests
+// =========================================================================
+
+#[tokio::test]
+async fn label_stats_counts() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let stats = client.get_label_stats("default").await.unwrap();
+
+    let person = stats
+        .node_labels
+        .iter()
+        .find(|c| c.label == "Person")
+        .unwrap();
+    assert_eq!(person.count, 80);
+    assert_eq!(stats.node_labels.len(), 2);
+
+    let knows = stats
+        .edge_types
+        .iter()
+        .find(|c| c.label == "KNOWS")
+        .unwrap();
+    assert_eq!(knows.count, 40);
+}
+
+#[tokio::test]
+async fn label_stats_property_cardinality() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let stats = client.get_label_stats("default").await.unwrap();
+
+    let name = stats
+        .properties
+        .iter()
+        .find(|p| p.label == "Person" && p.property == "name")
+        .unwrap();
+    assert_eq!(name.non_null_count, 80);
+    assert_eq!(name.distinct_estimate, 75);
+}
+
+#[tokio::test]
+async fn label_stats_unknown_graph() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let err = client.get_label_stats("nonexistent").await.unwrap_err();
+    assert!(matches!(err, gwp::error::GqlError::Grpc(s) if s.code() == tonic::Code::NotFound));
+}
+
+#[tokio::test]
+async fn label_stats_requires_graph() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let err = client.get_label_stats("").await.unwrap_err();
+    assert!(
+        matches!(err, gwp::error::GqlError::Grpc(s) if s.code() == tonic::Code::InvalidArgument)
+    );
+}
+
+// =========================================================================
+// Configuration tests
+// =========================================================================
+
+#[tokio::test]
+async fn get_graph_config() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let config = client.get_config("default").await.unwrap();
+    assert_eq!(config.wal_durability.as_deref(), Some("sync"));
+    assert_eq!(config.threads, Some(4));
+    assert!(config.memory_limit_bytes.is_none());
+}
+
+#[tokio::test]
+async fn set_graph_config_changes_only_given_fields() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let config = client
+        .set_config(
+            "default",
+            GraphConfig {
+                memory_limit_bytes: Some(1 << 30),
+                ..GraphConfig::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(config.memory_limit_bytes, Some(1 << 30));
+    assert_eq!(config.wal_durability.as_deref(), Some("sync"));
+    assert_eq!(config.threads, Some(4));
+
+    let config = client.get_config("default").await.unwrap();
+    assert_eq!(config.memory_limit_bytes, Some(1 << 30));
+}
+
+#[tokio::test]
+async fn set_graph_config_rejects_invalid_value() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let err = client
+        .set_config(
+            "default",
+            GraphConfig {
+                wal_durability: Some("eventually".to_owned()),
+                ..GraphConfig::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, gwp::error::GqlError::Grpc(s) if s.code() == tonic::Code::InvalidArgument)
+    );
+}
+
+#[tokio::test]
+async fn get_graph_config_unknown_graph() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let err = client.get_config("nonexistent").await.unwrap_err();
+    assert!(matches!(err, gwp::error::GqlError::Grpc(s) if s.code() == tonic::Code::NotFound));
+}
+
+// =========================================================================
+// WAL, validation, and index tests
+// =========================================================================
+
+#[tokio::test]
+async fn wal_checkpoint_flushes_records() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let status = client.wal_status("default").await.unwrap();
+    assert!(status.enabled);
+    assert_eq!(status.record_count, 12);
+    assert_eq!(status.last_checkpoint, None);
+
+    client.wal_checkpoint("default").await.unwrap();
+    let status = client.wal_status("default").await.unwrap();
+    assert_eq!(status.record_count, 0);
+    assert_eq!(status.current_epoch, 2);
+    assert!(status.last_checkpoint.is_some());
+
+    assert!(client.wal_status("nonexistent").await.is_err());
+}
+
+#[tokio::test]
+async fn validate_reports_a_valid_graph() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+
+    let result = client.validate("default").await.unwrap();
+    assert!(result.valid);
+    assert!(result.errors.is_empty());
+}
+
+#[tokio::test]
+async fn indexes_are_created_and_dropped() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+    let text = || IndexDefinition::Text {
+        label: "Person".to_owned(),
+        property: "bio".to_owned(),
+    };
+
+    client.create_index("default", text()).await.unwrap();
+    let err = client.create_index("default", text()).await.unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::AlreadyExists));
+
+    client
+        .create_index(
+            "default",
+            IndexDefinition::Vector {
+                label: "Person".to_owned(),
+                property: "embedding".to_owned(),
+                dimensions: Some(384),
+                metric: Some("cosine".to_owned()),
+                m: None,
+                ef_construction: None,
+            },
+        )
+        .await
+        .unwrap();
+    let indexes = client.list_indexes("default").await.unwrap();
+    assert_eq!(indexes.len(), 2);
+    assert!(indexes.iter().all(|i| i.state == IndexState::Ready));
+    assert!(matches!(
+        &indexes[1].definition,
+        IndexDefinition::Vector { dimensions: Some(384), metric: Some(m), .. } if m == "cosine"
+    ));
+
+    assert!(client.drop_index("default", text()).await.unwrap());
+    assert!(!client.drop_index("default", text()).await.unwrap());
+    assert_eq!(client.list_indexes("default").await.unwrap().len(), 1);
+    assert!(client.list_indexes("nonexistent").await.is_err());
+}
+
+#[tokio::test]
+async fn indexes_are_built_in_the_background() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+    let text = || IndexDefinition::Text {
+        label: "Person".to_owned(),
+        property: "bio".to_owned(),
+    };
+
+    let job_id = client
+        .create_index_in_background("default", text())
+        .await
+        .unwrap();
+    let status = client
+        .watch_index_build(&job_id)
+        .await
+        .unwrap()
+        .finish()
+        .await
+        .unwrap();
+    assert_eq!(status.state, IndexState::Ready);
+    assert!((status.percent - 100.0).abs() < f64::EPSILON);
+    assert_eq!(client.list_indexes("default").await.unwrap().len(), 1);
+
+    let job_id = client
+        .create_index_in_background("default", text())
+        .await
+        .unwrap();
+    let status = client
+        .watch_index_build(&job_id)
+        .await
+        .unwrap()
+        .finish()
+        .await
+        .unwrap();
+    assert_eq!(status.state, IndexState::Failed);
+    assert!(status.error.is_some());
+
+    let err = client.watch_index_build("unknown").await.unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::NotFound));
+}
+
+#[tokio::test]
+async fn jobs_can_be_followed_and_cancelled() {
+    let backend = MockBackend::new();
+    backend.set_latency(
+        MockOperation::IndexBuild,
+        Latency::fixed(Duration::from_secs(30)),
+    );
+    let server = TestServer::start(backend).await.unwrap();
+    let mut client = server.admin_client();
+
+    let validation = client
+        .submit_job(Job::Validate {
+            graph: "default".to_owned(),
+        })
+        .await
+        .unwrap();
+    let build = client
+        .submit_job(Job::CreateIndex {
+            graph: "default".to_owned(),
+            index: IndexDefinition::Text {
+                label: "Person".to_owned(),
+                property: "bio".to_owned(),
+            },
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let status = client.job_status(&validation).await.unwrap();
+    assert_eq!(status.state, JobState::Succeeded);
+    assert!(matches!(status.output, Some(JobOutput::Validation(result)) if result.valid));
+    let status = client.job_status(&build).await.unwrap();
+    assert_eq!(status.state, JobState::Running);
+    assert!((status.percent - 50.0).abs() < f64::EPSILON);
+
+    assert!(client.cancel_job(&build).await.unwrap());
+    assert!(!client.cancel_job(&build).await.unwrap());
+    let progress = client.watch_index_build(&build).await.unwrap();
+    assert_eq!(progress.finish().await.unwrap().state, IndexState::Failed);
+    let jobs = client.list_jobs().await.unwrap();
+    let listed: Vec<_> = jobs.iter().map(|j| (j.job_id.as_str(), j.state)).collect();
+    assert_eq!(
+        listed,
+        [
+            (validation.as_str(), JobState::Succeeded),
+            (build.as_str(), JobState::Cancelled),
+        ]
+    );
+    assert!(client.list_indexes("default").await.unwrap().is_empty());
+
+    let err = client.job_status("unknown").await.unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::NotFound));
+}
+
+// =========================================================================
+// Query statistics tests
+// =========================================================================
+
+async fn run(session: &mut gwp::client::GqlSession, statement: &str) {
+    let mut cursor = session
+        .execute(statement, std::collections::HashMap::new())
+        .await
+        .unwrap();
+    cursor.collect_rows().await.unwrap();
+}
+
+#[tokio::test]
+async fn query_stats_group_normalized_statements() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.session().await.unwrap();
+    run(&mut session, "MATCH (n) WHERE n.age > 30 RETURN n").await;
+    run(&mut session, "MATCH (n) WHERE n.age > 40 RETURN n").await;
+    run(&mut session, "INSERT (:Person {name: 'Carol'})").await;
+    run(&mut session, "ERROR").await;
+
+    let mut client = server.admin_client();
+    let stats = client.get_query_stats(0).await.unwrap();
+    assert_eq!(stats.len(), 3);
+
+    let query = stats
+        .iter()
+        .find(|s| s.statement == "MATCH (n) WHERE n.age > ? RETURN n")
+        .unwrap();
+    assert_eq!(query.calls, 2);
+    assert_eq!(query.rows, 4);
+    assert_eq!(query.errors, 0);
+    assert!(query.p99_time >= query.p50_time);
+
+    let insert = stats
+        .iter()
+        .find(|s| s.statement == "INSERT (:Person {name: ?})")
+        .unwrap();
+    assert_eq!(insert.calls, 1);
+
+    let error = stats.iter().find(|s| s.statement == "ERROR").unwrap();
+    assert_eq!(error.errors, 1);
+}
+
+#[tokio::test]
+async fn query_stats_limit_and_reset() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.session().await.unwrap();
+    run(&mut session, "MATCH (n) RETURN n").await;
+    run(&mut session, "INSERT (:A)").await;
+
+    let mut client = server.admin_client();
+    assert_eq!(client.get_query_stats(1).await.unwrap().len(), 1);
+
+    client.reset_query_stats().await.unwrap();
+    assert!(client.get_query_stats(0).await.unwrap().is_empty());
+}
+
+// =========================================================================
+// Backup and restore tests
+// =========================================================================
+
+#[tokio::test]
+async fn backups_round_trip_through_files() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+    let dir = std::env::temp_dir();
+    let source = dir.join(format!("gwp-restore-{}.bak", std::process::id()));
+    let backup = dir.join(format!("gwp-backup-{}.bak", std::process::id()));
+    let image: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&source, &image).unwrap();
+
+    assert_eq!(client.restore_from_file(&source).await.unwrap(), 3000);
+    assert_eq!(client.backup_to_file(&backup).await.unwrap(), 3000);
+    assert_eq!(std::fs::read(&backup).unwrap(), image);
+
+    let missing = dir.join(format!("gwp-missing-{}.bak", std::process::id()));
+    let err = client.restore_from_file(&missing).await.unwrap_err();
+    assert!(matches!(err, GqlError::Io(_)));
+    assert_eq!(client.backup_to_file(&backup).await.unwrap(), 3000);
+
+    std::fs::remove_file(source).unwrap();
+    std::fs::remove_file(backup).unwrap();
+}
+
+#[tokio::test]
+async fn graphs_export_in_each_format() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+    let mut exports = Vec::new();
+    for format in [
+        GraphFormat::JsonLines,
+        GraphFormat::Csv,
+        GraphFormat::GraphMl,
+    ] {
+        let mut out = Vec::new();
+        let written = client
+            .export_graph("default", format, &mut out)
+            .await
+            .unwrap();
+        assert_eq!(written, out.len() as u64);
+        exports.push(String::from_utf8(out).unwrap());
+    }
+    let [jsonl, csv, graphml] = exports.try_into().unwrap();
+
+    let lines: Vec<_> = jsonl.lines().collect();
+    assert_eq!(lines.len(), 10);
+    assert_eq!(
+        lines[0],
+        r#"{"kind":"node","id":"0000000000000001","labels":["Person"],"properties":{"name":"'Alice'"}}"#
+    );
+    assert_eq!(
+        lines[9],
+        r#"{"kind":"edge","id":"0000000000000005","labels":["KNOWS"],"source":"0000000000000005","target":"0000000000000001","undirected":false,"properties":{"since":"2025"}}"#
+    );
+
+    assert_eq!(csv.lines().count(), 11);
+    assert!(csv.contains("\nnode,0000000000000002,Person,,,,{name: 'Bob'}\n"));
+
+    assert!(graphml.starts_with("<?xml"));
+    assert_eq!(graphml.matches("<node ").count(), 5);
+    assert_eq!(graphml.matches("<edge ").count(), 5);
+
+    let err = client
+        .export_graph("nonexistent", GraphFormat::Csv, &mut Vec::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::NotFound));
+}
+
+#[tokio::test]
+async fn graphs_import_with_a_report() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.admin_client();
+    let mut graphml = Vec::new();
+    client
+        .export_graph("default", GraphFormat::GraphMl, &mut graphml)
+        .await
+        .unwrap();
+
+    // Re-importing an export skips every element.
+    let report = client
+        .import_graph("default", GraphFormat::GraphMl, graphml.as_slice())
+        .await
+        .unwrap();
+    assert_eq!(report.nodes_created, 0);
+    assert_eq!(report.duplicates_skipped, 10);
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    let input = concat!(
+        r#"{"kind":"node","id":"0000000000000006","labels":["Person"],"properties":{"name":"'Frank'"}}"#,
+        "\n",
+        r#"{"kind":"edge","id":"0000000000000006","labels":["KNOWS"],"source":"0000000000000006","target":"0000000000000001","properties":{"since":"2026"}}"#,
+        "\n",
+        r#"{"kind":"edge","id":"0000000000000007","source":"0000000000000006","target":"0000000000000009"}"#,
+        "\n",
+        "{\"kind\":\"node\"\n",
+    );
+    let report = client
+        .import_graph("default", GraphFormat::JsonLines, input.as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(report.nodes_created, 1);
+    assert_eq!(report.edges_created, 1);
+    let codes: Vec<_> = report.errors.iter().map(|e| e.code.as_str()).collect();
+    assert_eq!(codes, ["PARSE_ERROR", "DANGLING_EDGE"]);
+    assert_eq!(report.errors[0].context.as_deref(), Some("line 4"));
+
+    let mut jsonl = Vec::new();
+    client
+        .export_graph("default", GraphFormat::JsonLines, &mut jsonl)
+        .await
+        .unwrap();
+    assert!(String::from_utf8(jsonl).unwrap().contains("'Frank'"));
+
+    let err = client
+        .import_graph("default", GraphFormat::Csv, "".as_bytes())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::InvalidArgument));
+}
+
+// =========================================================================
+// Session administration tests
+// =========================================================================
+
+#[tokio::test]
+async fn sessions_can_be_listed_and_killed() {
+    let backend = MockBackend::new();
+    backend.set_latency(
+        MockOperation::ExecuteFirstFrame,
+        Latency::fixed(Duration::from_millis(500)),
+    );
+    let server = TestServer::start(backend).await.unwrap();
+    let mut busy = server.session().await.unwrap();
+    let mut idle = server.session().await.unwrap();
+    idle.set_graph("social").await.unwrap();
+    let transaction = idle.begin_transaction().await.unwrap();
+    let busy_id = busy.session_id().to_owned();
+    let running = tokio::spawn(async move {
+        let mut cursor = busy.execute_simple("MATCH (n) RETURN n").await.unwrap();
+        cursor.collect_rows().await.unwrap();
+        busy
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut client = server.admin_client();
+    let sessions = client.list_sessions().await.unwrap();
+    assert_eq!(sessions.len(), 2);
+    let listed = |id: &str| sessions.iter().find(|s| s.session_id == id).unwrap();
+    assert_eq!(listed(&busy_id).running_statements, ["MATCH (n) RETURN n"]);
+    let info = listed(idle.session_id());
+    assert_eq!(info.graph.as_deref(), Some("social"));
+    assert_eq!(
+        info.transaction_id.as_deref(),
+        Some(transaction.transaction_id())
+    );
+    assert!(info.running_statements.is_empty());
+
+    client.kill_session(idle.session_id()).await.unwrap();
+    let sessions = client.list_sessions().await.unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].session_id, busy_id);
+
+    let err = client.kill_session(idle.session_id()).await.unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::NotFound));
+
+    let _busy = running.await.unwrap();
+    let sessions = client.list_sessions().await.unwrap();
+    assert!(sessions[0].running_statements.is_empty());
+}
@@ -0,0 +1,89 @@
+//! Round-trip property tests for the `testing` feature's generators.
+
+#![cfg(feature = "testing")]
+
+use proptest::prelude::*;
+use prost::Message;
+
+use gwp::dictionary;
+use gwp::proto;
+use gwp::server::ResultFrame;
+use gwp::testing::arbitrary::{result_stream, row_batch};
+use gwp::types::{Date, Duration, Edge, LocalDateTime, Node, Path, Value, ZonedDateTime};
+
+proptest! {
+    #[test]
+    fn value_proto_round_trip(value: Value) {
+        let back = Value::from(proto::Value::from(value.clone()));
+        prop_assert_eq!(back, value);
+    }
+
+    #[test]
+    fn value_encode_decode_round_trip(value: Value) {
+        let bytes = proto::Value::from(value.clone()).encode_to_vec();
+        let decoded = proto::Value::decode(bytes.as_slice()).unwrap();
+        prop_assert_eq!(Value::from(decoded), value);
+    }
+
+    #[test]
+    fn temporal_round_trips(
+        date: Date,
+        local: LocalDateTime,
+        zoned: ZonedDateTime,
+        duration: Duration,
+    ) {
+        prop_assert_eq!(Date::from(proto::Date::from(date)), date);
+        prop_assert_eq!(LocalDateTime::from(proto::LocalDateTime::from(local)), local);
+        prop_assert_eq!(ZonedDateTime::from(proto::ZonedDateTime::from(zoned)), zoned);
+        prop_assert_eq!(Duration::from(proto::Duration::from(duration)), duration);
+    }
+
+    #[test]
+    fn element_round_trips(node: Node, edge: Edge, path: Path) {
+        prop_assert_eq!(Node::from(proto::Node::from(node.clone())), node);
+        prop_assert_eq!(Edge::from(proto::Edge::from(edge.clone())), edge);
+        prop_assert_eq!(Path::from(proto::Path::from(path.clone())), path);
+    }
+
+    #[test]
+    fn path_edges_connect_nodes(path: Path) {
+        prop_assert_eq!(path.edges.len() + 1, path.nodes.len());
+        for (edge, pair) in path.edges.iter().zip(path.nodes.windows(2)) {
+            prop_assert_eq!(&edge.source_node_id, &pair[0].id);
+            prop_assert_eq!(&edge.target_node_id, &pair[1].id);
+        }
+    }
+
+    #[test]
+    fn dictionary_encoding_round_trip(batch in row_batch(3)) {
+        let mut encoded = batch.clone();
+        dictionary::encode(&mut encoded);
+        prop_assert!(dictionary::decode(&mut encoded));
+        prop_assert_eq!(encoded, batch);
+    }
+
+    #[test]
+    fn result_stream_frames_encode_decode(frames in result_stream()) {
+        let width = match frames.first() {
+            Some(ResultFrame::Header(header)) => header.columns.len(),
+            other => panic!("stream must start with a header, got {other:?}"),
+        };
+        prop_assert!(matches!(frames.last(), Some(ResultFrame::Summary(_))));
+
+        for frame in frames {
+            let response = proto::ExecuteResponse {
+                frame: Some(match frame {
+                    ResultFrame::Header(h) => proto::execute_response::Frame::Header(h),
+                    ResultFrame::Batch(b) => {
+                        prop_assert!(b.rows.iter().all(|row| row.values.len() == width));
+                        proto::execute_response::Frame::RowBatch(b)
+                    }
+                    ResultFrame::Warning(w) => proto::execute_response::Frame::Warning(w),
+                    ResultFrame::Summary(s) => proto::execute_response::Frame::Summary(s),
+                }),
+            };
+            let decoded = proto::ExecuteResponse::decode(response.encode_to_vec().as_slice()).unwrap();
+            prop_assert_eq!(decoded, response);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Arrow export tests: result cursors convert streamed batches into
+//! Arrow record batches.
+
+#![cfg(feature = "arrow")]
+
+use arrow_array::{Array, Float64Array, Int64Array, StringArray};
+use arrow_schema::DataType;
+
+use gwp::server::mock_backend::MockBackend;
+use gwp::testing::TestServer;
+use gwp::types::Value;
+
+#[tokio::test]
+async fn results_convert_to_record_batches() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let rows: Vec<Vec<Value>> = (0..5)
+        .map(|i| {
+            vec![
+                Value::Integer(i),
+                Value::from(format!("person-{i}")),
+                if i == 2 {
+                    Value::Null
+                } else {
+                    Value::Float(0.5)
+                },
+            ]
+        })
+        .collect();
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN p.id, p.name, p.score")
+        .respond_rows(["id", "name", "score"], rows);
+
+    let mut session = server.session().await.unwrap();
+    session.set_fetch_size(Some(2));
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN p.id, p.name, p.score")
+        .await
+        .unwrap();
+    let batches = cursor.to_arrow().await.unwrap();
+
+    // One record batch per row batch the server sent.
+    assert_eq!(
+        batches
+            .iter()
+            .map(arrow_array::RecordBatch::num_rows)
+            .collect::<Vec<_>>(),
+        [2, 2, 1]
+    );
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+    assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+    assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+
+    let ids = batches[2]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(ids.value(0), 4);
+    let names = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(names.value(1), "person-1");
+    let scores = batches[1]
+        .column(2)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert!(scores.is_null(0));
+}
+
+#[tokio::test]
+async fn failed_statements_fail_the_export() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n")
+        .respond_error("22000", "boom");
+
+    let mut session = server.session().await.unwrap();
+    let result = match session.execute_simple("MATCH (n) RETURN n").await {
+        Ok(mut cursor) => cursor.to_arrow().await.map(|_| ()),
+        Err(err) => Err(err),
+    };
+    assert!(result.is_err());
+}
@@ -1,61 +1,12 @@
 //! Integration tests for the `CatalogService` gRPC service.
 
-use std::net::SocketAddr;
-
 use gwp::proto;
 use gwp::proto::catalog_service_client::CatalogServiceClient;
 use gwp::server::mock_backend::MockBackend;
-use gwp::server::{
-    CatalogServiceImpl, GqlServiceImpl, SessionManager, SessionServiceImpl, TransactionManager,
-};
-
-/// Start a server with all services on a random port.
-async fn start_server() -> SocketAddr {
-    let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    let addr = listener.local_addr().unwrap();
-
-    tokio::spawn(async move {
-        let backend = std::sync::Arc::new(MockBackend::new());
-        let sessions = SessionManager::new();
-        let transactions = TransactionManager::new();
-
-        let session_svc = SessionServiceImpl::new(
-            std::sync::Arc::clone(&backend),
-            sessions.clone(),
-            transactions.clone(),
-            None,
-        );
-        let gql_svc = GqlServiceImpl::new(std::sync::Arc::clone(&backend), sessions, transactions);
-        let catalog_svc = CatalogServiceImpl::new(std::sync::Arc::clone(&backend));
-
-        let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
-
-        tonic::transport::Server::builder()
-            .add_service(proto::session_service_server::SessionServiceServer::new(
-                session_svc,
-            ))
-            .add_service(proto::gql_service_server::GqlServiceServer::new(gql_svc))
-            .add_service(proto::catalog_service_server::CatalogServiceServer::new(
-                catalog_svc,
-            ))
-            .serve_with_incoming(incoming)
-            .await
-            .unwrap();
-    });
-
-    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-    addr
-}
-
-async fn connect(addr: SocketAddr) -> CatalogServiceClient<tonic::transport::Channel> {
-    let channel = tonic::transport::Channel::from_shared(format!("http://{addr}"))
-        .unwrap()
-        .connect()
-        .await
-        .unwrap();
+use gwp::testing::TestServer;
 
-    CatalogServiceClient::new(channel)
+fn connect(server: &TestServer<MockBackend>) -> CatalogServiceClient<tonic::transport::Channel> {
+    CatalogServiceClient::new(server.connection().channel().clone())
 }
 
 // =========================================================================
@@ -64,8 +15,8 @@ async fn connect(addr: SocketAddr) -> CatalogServiceClient<tonic::transport::Cha
 
 #[tokio::test]
 async fn list_schemas() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .list_schemas(proto::ListSchemasRequest {})
@@ -80,8 +31,8 @@ async fn list_schemas() {
 
 #[tokio::test]
 async fn create_schema() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     client
         .create_schema(proto::CreateSchemaRequest {
@@ -94,8 +45,8 @@ async fn create_schema() {
 
 #[tokio::test]
 async fn create_schema_already_exists() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .create_schema(proto::CreateSchemaRequest {
@@ -110,8 +61,8 @@ async fn create_schema_already_exists() {
 
 #[tokio::test]
 async fn create_schema_if_not_exists() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     // Should succeed even though "default" already exists
     client
@@ -125,8 +76,8 @@ async fn create_schema_if_not_exists() {
 
 #[tokio::test]
 async fn drop_schema_default_fails() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .drop_schema(proto::DropSchemaRequest {
@@ -145,8 +96,8 @@ async fn drop_schema_default_fails() {
 
 #[tokio::test]
 async fn list_graphs() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .list_graphs(proto::ListGraphsRequest {
@@ -167,8 +118,8 @@ async fn list_graphs() {
 
 #[tokio::test]
 async fn create_graph() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .create_graph(proto::CreateGraphRequest {
@@ -194,8 +145,8 @@ async fn create_graph() {
 
 #[tokio::test]
 async fn create_graph_with_options() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .create_graph(proto::CreateGraphRequest {
@@ -224,8 +175,8 @@ async fn create_graph_with_options() {
 
 #[tokio::test]
 async fn create_graph_already_exists() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .create_graph(proto::CreateGraphRequest {
@@ -246,8 +197,8 @@ async fn create_graph_already_exists() {
 
 #[tokio::test]
 async fn create_graph_empty_name() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .create_graph(proto::CreateGraphRequest {
@@ -268,8 +219,8 @@ async fn create_graph_empty_name() {
 
 #[tokio::test]
 async fn drop_graph() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .drop_graph(proto::DropGraphRequest {
@@ -286,8 +237,8 @@ async fn drop_graph() {
 
 #[tokio::test]
 async fn drop_default_graph_fails() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .drop_graph(proto::DropGraphRequest {
@@ -303,8 +254,8 @@ async fn drop_default_graph_fails() {
 
 #[tokio::test]
 async fn get_graph_info() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .get_graph_info(proto::GetGraphInfoRequest {
@@ -323,8 +274,8 @@ async fn get_graph_info() {
 
 #[tokio::test]
 async fn get_graph_info_not_found() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .get_graph_info(proto::GetGraphInfoRequest {
@@ -337,14 +288,81 @@ async fn get_graph_info_not_found() {
     assert_eq!(result.unwrap_err().code(), tonic::Code::NotFound);
 }
 
+fn clone_request(source: &str, target: &str, if_not_exists: bool) -> proto::CloneGraphRequest {
+    proto::CloneGraphRequest {
+        source_schema: "default".to_owned(),
+        source_name: source.to_owned(),
+        target_schema: "default".to_owned(),
+        target_name: target.to_owned(),
+        options: Some(proto::CloneGraphOptions {
+            if_not_exists,
+            include_indexes: true,
+            storage_mode: None,
+        }),
+    }
+}
+
+#[tokio::test]
+async fn clone_graph() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .clone_graph(clone_request("default", "staging", false))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let graph = resp.graph.unwrap();
+    assert_eq!(graph.name, "staging");
+    assert_eq!(graph.node_count, 100);
+    assert_eq!(graph.edge_count, 50);
+}
+
+#[tokio::test]
+async fn clone_graph_source_not_found() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let result = client
+        .clone_graph(clone_request("nonexistent", "staging", false))
+        .await;
+
+    assert_eq!(result.unwrap_err().code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn clone_graph_target_exists() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let result = client
+        .clone_graph(clone_request("default", "test", false))
+        .await;
+
+    assert_eq!(result.unwrap_err().code(), tonic::Code::AlreadyExists);
+}
+
+#[tokio::test]
+async fn clone_graph_empty_target() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let result = client
+        .clone_graph(clone_request("default", "", false))
+        .await;
+
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
 // =========================================================================
 // Graph type tests
 // =========================================================================
 
 #[tokio::test]
 async fn list_graph_types() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .list_graph_types(proto::ListGraphTypesRequest {
@@ -360,8 +378,8 @@ async fn list_graph_types() {
 
 #[tokio::test]
 async fn create_graph_type() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     client
         .create_graph_type(proto::CreateGraphTypeRequest {
@@ -376,8 +394,8 @@ async fn create_graph_type() {
 
 #[tokio::test]
 async fn create_graph_type_already_exists() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let result = client
         .create_graph_type(proto::CreateGraphTypeRequest {
@@ -394,8 +412,8 @@ async fn create_graph_type_already_exists() {
 
 #[tokio::test]
 async fn drop_graph_type() {
-    let addr = start_server().await;
-    let mut client = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
 
     let resp = client
         .drop_graph_type(proto::DropGraphTypeRequest {
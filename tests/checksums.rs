@@ -0,0 +1,168 @@
+//! Integration tests for negotiated row batch checksums.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use gwp::checksum::row_batch_checksum;
+use gwp::client::GqlConnection;
+use gwp::error::GqlError;
+use gwp::proto;
+use gwp::server::mock_backend::MockBackend;
+use gwp::server::{
+    ExecutionContext, GqlBackend, ResetTarget, ResultFrame, ResultStream, SessionConfig,
+    SessionHandle, SessionProperty, TransactionHandle,
+};
+use gwp::testing::TestServer;
+use gwp::types::Value;
+
+/// A backend whose row batches carry a wrong checksum, as if a proxy had
+/// altered them after they were stamped.
+struct Corrupting(MockBackend);
+
+struct CorruptStream(Pin<Box<dyn ResultStream>>);
+
+impl ResultStream for CorruptStream {
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ResultFrame, GqlError>>> {
+        let poll = self.0.as_mut().poll_next(cx);
+        if let Poll::Ready(Some(Ok(ResultFrame::Batch(batch)))) = poll {
+            let checksum = row_batch_checksum(&batch.rows) ^ 1;
+            return Poll::Ready(Some(Ok(ResultFrame::Batch(proto::RowBatch {
+                checksum: Some(checksum),
+                ..batch
+            }))));
+        }
+        poll
+    }
+}
+
+#[tonic::async_trait]
+impl GqlBackend for Corrupting {
+    async fn create_session(&self, config: &SessionConfig) -> Result<SessionHandle, GqlError> {
+        self.0.create_session(config).await
+    }
+
+    async fn close_session(&self, session: &SessionHandle) -> Result<(), GqlError> {
+        self.0.close_session(session).await
+    }
+
+    async fn configure_session(
+        &self,
+        session: &SessionHandle,
+        property: SessionProperty,
+    ) -> Result<(), GqlError> {
+        self.0.configure_session(session, property).await
+    }
+
+    async fn reset_session(
+        &self,
+        session: &SessionHandle,
+        target: ResetTarget,
+    ) -> Result<(), GqlError> {
+        self.0.reset_session(session, target).await
+    }
+
+    async fn execute(
+        &self,
+        session: &SessionHandle,
+        statement: &str,
+        parameters: &HashMap<String, Value>,
+        transaction: Option<&TransactionHandle>,
+        context: &ExecutionContext,
+    ) -> Result<Pin<Box<dyn ResultStream>>, GqlError> {
+        let stream = self
+            .0
+            .execute(session, statement, parameters, transaction, context)
+            .await?;
+        Ok(Box::pin(CorruptStream(stream)))
+    }
+
+    async fn begin_transaction(
+        &self,
+        session: &SessionHandle,
+        mode: proto::TransactionMode,
+    ) -> Result<TransactionHandle, GqlError> {
+        self.0.begin_transaction(session, mode).await
+    }
+
+    async fn commit(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.0.commit(session, transaction).await
+    }
+
+    async fn rollback(
+        &self,
+        session: &SessionHandle,
+        transaction: &TransactionHandle,
+    ) -> Result<(), GqlError> {
+        self.0.rollback(session, transaction).await
+    }
+}
+
+const QUERY: &str = "MATCH (p:Person) RETURN p.name, p.age";
+
+#[tokio::test]
+async fn negotiated_checksums_are_verified() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = GqlConnection::connect(&server.endpoint())
+        .await
+        .unwrap()
+        .with_row_batch_checksums();
+    let mut session = conn.create_session().await.unwrap();
+    assert!(session.row_batch_checksums());
+
+    let mut cursor = session.execute_simple(QUERY).await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+
+    let mut tx = session.begin_transaction().await.unwrap();
+    let mut cursor = tx.execute_simple(QUERY).await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+    tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+async fn split_batches_carry_their_own_checksums() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = GqlConnection::connect(&server.endpoint())
+        .await
+        .unwrap()
+        .with_row_batch_checksums();
+    let mut session = conn.create_session().await.unwrap();
+    session.set_fetch_size(Some(1));
+
+    let mut cursor = session.execute_simple(QUERY).await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+
+    let mut tx = session.begin_transaction().await.unwrap();
+    let mut cursor = tx.execute_simple(QUERY).await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+    tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+async fn checksums_are_off_unless_requested() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    assert!(!session.row_batch_checksums());
+
+    let mut cursor = session.execute_simple(QUERY).await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn corrupted_batch_fails_the_cursor() {
+    let server = TestServer::start(Corrupting(MockBackend::new()))
+        .await
+        .unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let mut cursor = session.execute_simple(QUERY).await.unwrap();
+    let err = cursor.collect_rows().await.unwrap_err();
+    assert!(matches!(err, GqlError::Protocol(ref m) if m.contains("checksum mismatch")));
+}
@@ -1,61 +1,30 @@
 //! Integration tests for the high-level client API.
 
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use gwp::client::GqlConnection;
-use gwp::proto;
-use gwp::server::mock_backend::MockBackend;
+use gwp::client::{
+    EndpointConfig, FieldEncryption, FromRow, GqlConnection, LocalConnection, Params, QueryLog,
+    QueryRecord, REDACTED, Row, Severity, TxOptions, column,
+};
+use gwp::error::GqlError;
+use gwp::params;
+use gwp::proto::IsolationLevel;
+use gwp::server::mock_backend::{Latency, MockBackend, MockCall, MockOperation};
 use gwp::server::{
-    CatalogServiceImpl, CreateGraphConfig, GqlServiceImpl, SessionManager, SessionServiceImpl,
-    TransactionManager,
+    ADMIN_FEATURE, Authorizer, ChangeEvent, CreateDatabaseConfig, CreateGraphConfig, GqlServer,
+    HealthStatus, Operation, Principal, QueryLogEvent, RateLimits, ResultCache, ResultLimits,
+    SEARCH_FEATURE, SessionDefaults, SessionProperty, StatementLimits, TextSearchParams,
 };
-use gwp::types::Value;
-
-async fn start_server() -> SocketAddr {
-    let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    let addr = listener.local_addr().unwrap();
-
-    tokio::spawn(async move {
-        let backend = std::sync::Arc::new(MockBackend::new());
-        let sessions = SessionManager::new();
-        let transactions = TransactionManager::new();
-
-        let session_svc = SessionServiceImpl::new(
-            std::sync::Arc::clone(&backend),
-            sessions.clone(),
-            transactions.clone(),
-            None,
-        );
-        let gql_svc = GqlServiceImpl::new(std::sync::Arc::clone(&backend), sessions, transactions);
-        let catalog_svc = CatalogServiceImpl::new(std::sync::Arc::clone(&backend));
-
-        let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
-
-        tonic::transport::Server::builder()
-            .add_service(proto::session_service_server::SessionServiceServer::new(
-                session_svc,
-            ))
-            .add_service(proto::gql_service_server::GqlServiceServer::new(gql_svc))
-            .add_service(proto::catalog_service_server::CatalogServiceServer::new(
-                catalog_svc,
-            ))
-            .serve_with_incoming(incoming)
-            .await
-            .unwrap();
-    });
-
-    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-    addr
-}
+use gwp::status;
+use gwp::testing::TestServer;
+use gwp::types::{CipherError, Edge, EncryptedValue, Node, Value, ValueCipher};
 
 #[tokio::test]
 async fn client_session_lifecycle() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
-        .await
-        .unwrap();
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut session = conn.create_session().await.unwrap();
     assert!(!session.session_id().is_empty());
@@ -77,11 +46,119 @@ async fn client_session_lifecycle() {
 }
 
 #[tokio::test]
-async fn client_execute_query() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
+async fn session_state_reflects_configuration() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let initial = session.current_state().await.unwrap();
+    assert_eq!(initial.database, None);
+    assert_eq!(initial.transaction_id, None);
+
+    session.set_database("archive").await.unwrap();
+    session.set_schema("sales").await.unwrap();
+    session.set_graph("orders").await.unwrap();
+    session.set_time_zone(-300).await.unwrap();
+    let transaction = session.begin_transaction().await.unwrap();
+    let state = session.current_state().await.unwrap();
+    assert_eq!(state.schema.as_deref(), Some("sales"));
+    assert_eq!(state.graph.as_deref(), Some("orders"));
+    assert_eq!(state.time_zone_offset_minutes, -300);
+    assert_eq!(state.database.as_deref(), Some("archive"));
+    assert_eq!(
+        state.transaction_id.as_deref(),
+        Some(transaction.transaction_id())
+    );
+
+    transaction.rollback().await.unwrap();
+    session.reset().await.unwrap();
+    // The archive database has no defaults, so a reset clears the
+    // schema and graph rather than returning to the initial ones.
+    let reset = session.current_state().await.unwrap();
+    assert_eq!(reset.schema, None);
+    assert_eq!(reset.graph, None);
+    assert_eq!(reset.time_zone_offset_minutes, 0);
+    assert_eq!(reset.database.as_deref(), Some("archive"));
+    assert_eq!(reset.transaction_id, None);
+}
+
+#[tokio::test]
+async fn session_parameters_apply_to_statements() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    session.set_parameter("region", "emea").await.unwrap();
+    session.set_parameter("limit", 10_i64).await.unwrap();
+    let state = session.current_state().await.unwrap();
+    assert_eq!(state.parameters["region"], Value::from("emea"));
+
+    session
+        .execute("MATCH (n) RETURN n", params! { "limit" => 5_i64 }.into())
+        .await
+        .unwrap();
+    session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+
+    let executed: Vec<_> = server
+        .backend()
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            MockCall::Execute { parameters, .. } => Some(parameters),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(executed[0]["region"], Value::from("emea"));
+    assert_eq!(executed[0]["limit"], Value::Integer(5));
+    assert_eq!(executed[1]["limit"], Value::Integer(10));
+}
+
+#[tokio::test]
+async fn connection_accepts_endpoint_tuning() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let config = EndpointConfig::default()
+        .with_connect_timeout(Duration::from_secs(5))
+        .with_tcp_keepalive(Duration::from_secs(60))
+        .with_http2_keepalive(Duration::from_secs(30), Duration::from_secs(10), true)
+        .with_adaptive_window()
+        .with_concurrency_limit(8);
+    let conn = GqlConnection::connect_with(&server.endpoint(), &config)
         .await
         .unwrap();
+    let mut session = conn.create_session().await.unwrap();
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+    session.close().await.unwrap();
+
+    // Nothing listens on port 1, so connecting fails rather than hangs.
+    let config = EndpointConfig::default().with_connect_timeout(Duration::from_secs(1));
+    assert!(
+        GqlConnection::connect_with("http://127.0.0.1:1", &config)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn keepalive_pings_stop_idle_reaping() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.idle_timeout(Duration::from_millis(400))
+    })
+    .await
+    .unwrap();
+    let conn = server
+        .connection()
+        .with_session_keepalive(Duration::from_millis(100));
+    let mut session = conn.create_session().await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+
+    session.set_keepalive(None);
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert!(session.execute_simple("MATCH (n) RETURN n").await.is_err());
+}
+
+#[tokio::test]
+async fn client_execute_query() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut session = conn.create_session().await.unwrap();
 
@@ -106,12 +183,62 @@ async fn client_execute_query() {
     assert!(cursor.is_success().await.unwrap());
 }
 
+#[derive(Debug, PartialEq)]
+struct Person {
+    name: String,
+    age: u32,
+}
+
+impl FromRow for Person {
+    fn from_row(columns: &[String], mut values: Vec<Value>) -> Result<Self, GqlError> {
+        Ok(Self {
+            name: column(columns, &mut values, "name")?,
+            age: column(columns, &mut values, "age")?,
+        })
+    }
+}
+
 #[tokio::test]
-async fn client_transaction() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
+async fn execute_typed_converts_rows() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let statement = "MATCH (p:Person) WHERE p.name = $name RETURN p.name, p.age";
+
+    let people: Vec<Person> = session
+        .execute_typed(statement, params! { "name" => "Alice" })
+        .await
+        .unwrap();
+    assert_eq!(
+        people[0],
+        Person {
+            name: "Alice".to_owned(),
+            age: 30
+        }
+    );
+
+    let pairs: Vec<(String, i64)> = session
+        .execute_typed(statement, Params::new())
         .await
         .unwrap();
+    assert_eq!(pairs, [("Alice".to_owned(), 30), ("Bob".to_owned(), 25)]);
+
+    let err = session
+        .execute_typed::<(i64, i64)>(statement, HashMap::new())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("column `name`"), "{err}");
+
+    let err = session
+        .execute_typed::<Vec<Value>>("ERROR", Params::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GqlError::Status { .. }), "{err}");
+}
+
+#[tokio::test]
+async fn client_transaction() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut session = conn.create_session().await.unwrap();
 
@@ -135,10 +262,8 @@ async fn client_transaction() {
 
 #[tokio::test]
 async fn client_transaction_rollback() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
-        .await
-        .unwrap();
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut session = conn.create_session().await.unwrap();
 
@@ -148,10 +273,8 @@ async fn client_transaction_rollback() {
 
 #[tokio::test]
 async fn catalog_client_list_graphs() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
-        .await
-        .unwrap();
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut catalog = conn.create_catalog_client();
     let graphs = catalog.list_graphs("default").await.unwrap();
@@ -165,10 +288,8 @@ async fn catalog_client_list_graphs() {
 
 #[tokio::test]
 async fn catalog_client_create_graph() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
-        .await
-        .unwrap();
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut catalog = conn.create_catalog_client();
     let info = catalog
@@ -196,10 +317,8 @@ async fn catalog_client_create_graph() {
 
 #[tokio::test]
 async fn catalog_client_drop_graph() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
-        .await
-        .unwrap();
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut catalog = conn.create_catalog_client();
     let existed = catalog.drop_graph("default", "test", false).await.unwrap();
@@ -208,10 +327,8 @@ async fn catalog_client_drop_graph() {
 
 #[tokio::test]
 async fn catalog_client_get_graph_info() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
-        .await
-        .unwrap();
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut catalog = conn.create_catalog_client();
     let info = catalog.get_graph_info("default", "default").await.unwrap();
@@ -224,12 +341,1792 @@ async fn catalog_client_get_graph_info() {
 
 #[tokio::test]
 async fn catalog_client_get_graph_info_not_found() {
-    let addr = start_server().await;
-    let conn = GqlConnection::connect(&format!("http://{addr}"))
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+
+    let mut catalog = conn.create_catalog_client();
+    let result = catalog.get_graph_info("default", "nonexistent").await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn session_starts_on_defaults() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+
+    let session = conn.create_session().await.unwrap();
+    assert_eq!(session.default_schema(), Some("default"));
+    assert_eq!(session.default_graph(), Some("default"));
+}
+
+#[tokio::test]
+async fn catalog_client_database_defaults() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+
+    let mut catalog = conn.create_catalog_client();
+    let defaults = SessionDefaults {
+        schema: Some("default".to_owned()),
+        graph: Some("test".to_owned()),
+    };
+    let info = catalog
+        .create_database(CreateDatabaseConfig {
+            name: "sales".to_owned(),
+            if_not_exists: false,
+            defaults: defaults.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(info.defaults, defaults);
+    assert_eq!(
+        catalog.get_database_info("sales").await.unwrap().defaults,
+        defaults
+    );
+
+    // Sessions on the database start on its defaults; others do not.
+    let session = conn
+        .clone()
+        .with_database("sales")
+        .create_session()
+        .await
+        .unwrap();
+    assert_eq!(session.default_graph(), Some("test"));
+    let session = conn.create_session().await.unwrap();
+    assert_eq!(session.default_graph(), Some("default"));
+
+    // Altering the database changes where later sessions start.
+    let altered = SessionDefaults {
+        schema: Some("default".to_owned()),
+        graph: Some("default".to_owned()),
+    };
+    let info = catalog
+        .alter_database("sales", altered.clone())
         .await
         .unwrap();
+    assert_eq!(info.defaults, altered);
+    let session = conn.with_database("sales").create_session().await.unwrap();
+    assert_eq!(session.default_graph(), Some("default"));
+}
+
+#[tokio::test]
+async fn catalog_client_database_defaults_unknown_graph() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
 
     let mut catalog = conn.create_catalog_client();
-    let result = catalog.get_graph_info("default", "nonexistent").await;
+    let result = catalog
+        .create_database(CreateDatabaseConfig {
+            name: "sales".to_owned(),
+            if_not_exists: false,
+            defaults: SessionDefaults {
+                schema: None,
+                graph: Some("nonexistent".to_owned()),
+            },
+        })
+        .await;
     assert!(result.is_err());
+    assert!(catalog.get_database_info("sales").await.is_err());
+}
+
+#[tokio::test]
+async fn switching_database_applies_its_defaults() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+    let mut catalog = conn.create_catalog_client();
+    catalog
+        .create_database(CreateDatabaseConfig {
+            name: "sales".to_owned(),
+            if_not_exists: false,
+            defaults: SessionDefaults {
+                schema: Some("default".to_owned()),
+                graph: Some("test".to_owned()),
+            },
+        })
+        .await
+        .unwrap();
+
+    let mut session = conn.create_session().await.unwrap();
+    session.set_graph("orders").await.unwrap();
+    session.set_database("sales").await.unwrap();
+    assert_eq!(session.default_graph(), Some("test"));
+    let state = session.current_state().await.unwrap();
+    assert_eq!(state.graph.as_deref(), Some("test"));
+
+    // A reset returns to the new database's defaults.
+    session.set_graph("orders").await.unwrap();
+    session.reset().await.unwrap();
+    let state = session.current_state().await.unwrap();
+    assert_eq!(state.graph.as_deref(), Some("test"));
+    assert_eq!(state.database.as_deref(), Some("sales"));
+}
+
+#[tokio::test]
+async fn query_log_records_statements() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+    let records: Arc<Mutex<Vec<QueryRecord>>> = Arc::default();
+    let sink = Arc::clone(&records);
+
+    let mut session = conn.create_session().await.unwrap();
+    session.set_query_log(Some(
+        QueryLog::new(move |r: &QueryRecord| sink.lock().unwrap().push(r.clone()))
+            .redact_parameter("secret"),
+    ));
+
+    let parameters = HashMap::from([
+        ("name".to_owned(), Value::from("Alice")),
+        ("secret".to_owned(), Value::from("hunter2")),
+    ]);
+    let mut cursor = session
+        .execute("MATCH (p:Person) RETURN p.name", parameters)
+        .await
+        .unwrap();
+    cursor.collect_rows().await.unwrap();
+
+    let mut tx = session.begin_transaction().await.unwrap();
+    tx.execute_simple("INSERT (:Person)")
+        .await
+        .unwrap()
+        .rows_affected()
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let mut cursor = session.execute_simple("ERROR here").await.unwrap();
+    assert!(!cursor.is_success().await.unwrap());
+    drop(cursor);
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 3);
+
+    assert_eq!(records[0].session_id, session.session_id());
+    assert_eq!(records[0].statement, "MATCH (p:Person) RETURN p.name");
+    assert_eq!(records[0].parameters["name"], Value::from("Alice"));
+    assert_eq!(records[0].parameters["secret"], Value::from(REDACTED));
+    assert_eq!(records[0].status.as_deref(), Some("00000"));
+    assert_eq!(records[0].rows, 2);
+
+    assert!(records[1].transaction_id.is_some());
+    assert_eq!(records[1].rows_affected, 3);
+
+    assert_eq!(records[2].statement, "ERROR here");
+    assert_eq!(records[2].status.as_deref(), Some("42001"));
+}
+
+#[tokio::test]
+async fn shutdown_ends_running_results_with_notice() {
+    let backend = MockBackend::new();
+    backend.set_latency(
+        MockOperation::Batch,
+        Latency::fixed(Duration::from_secs(30)),
+    );
+    let server = TestServer::start(backend).await.unwrap();
+    let conn = server.connection();
+    let mut session = conn.create_session().await.unwrap();
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    assert!(cursor.header().await.unwrap().is_some());
+
+    let stopped = tokio::spawn(server.shutdown());
+    let summary = tokio::time::timeout(Duration::from_secs(5), cursor.summary())
+        .await
+        .unwrap()
+        .unwrap()
+        .cloned()
+        .unwrap();
+    assert_eq!(summary.status.unwrap().code, status::SERVER_SHUTDOWN);
+    drop(cursor);
+    drop(session);
+    drop(conn);
+    stopped.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn drain_notifies_sessions_then_closes_them() {
+    let mut drain = None;
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        drain = Some(builder.drain_handle());
+        builder.drain_grace_period(Duration::from_millis(100))
+    })
+    .await
+    .unwrap();
+    let drain = drain.unwrap();
+    let conn = server.connection();
+    let mut session = conn.create_session().await.unwrap();
+    let mut events = session.server_events().await.unwrap();
+    let tx = session.begin_transaction().await.unwrap();
+
+    drain.drain();
+    let event = events.next().await.unwrap().unwrap();
+    assert_eq!(
+        event,
+        gwp::proto::server_event::Event::Draining(gwp::proto::DrainNotice {
+            grace_period_ms: 100
+        })
+    );
+    let err = conn.create_session().await.err().unwrap();
+    assert!(matches!(err, GqlError::Grpc(ref s) if s.code() == tonic::Code::Unavailable));
+
+    tokio::time::timeout(Duration::from_secs(5), drain.drained())
+        .await
+        .unwrap();
+    assert!(events.next().await.unwrap().is_none());
+    server.backend().assert_transactions(0, 1);
+    assert!(tx.commit().await.is_err());
+    assert!(session.ping().await.is_err());
+}
+
+#[tokio::test]
+async fn result_cache_serves_repeated_reads_until_a_write() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.result_cache(16, Duration::from_secs(60))
+    })
+    .await
+    .unwrap();
+    let conn = server.connection();
+    let mut session = conn.create_session().await.unwrap();
+    let read = "MATCH (p:Person) RETURN p.name, p.age";
+
+    for _ in 0..2 {
+        let mut cursor = session.execute_simple(read).await.unwrap();
+        assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+        assert!(cursor.is_success().await.unwrap());
+    }
+    assert_eq!(server.backend().execute_count(read), 1);
+
+    session
+        .execute_simple("INSERT (:Person)")
+        .await
+        .unwrap()
+        .rows_affected()
+        .await
+        .unwrap();
+    let mut cursor = session.execute_simple(read).await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+    assert_eq!(server.backend().execute_count(read), 2);
+
+    // Reads in a transaction bypass the cache.
+    let mut tx = session.begin_transaction().await.unwrap();
+    tx.execute_simple(read)
+        .await
+        .unwrap()
+        .collect_rows()
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    assert_eq!(server.backend().execute_count(read), 3);
+}
+
+#[tokio::test]
+async fn shared_result_cache_is_invalidated_from_outside() {
+    let cache = ResultCache::new(16, Duration::from_secs(60));
+    let shared = cache.clone();
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.shared_result_cache(shared)
+    })
+    .await
+    .unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let read = "MATCH (p:Person) RETURN p.name, p.age";
+
+    let run = async |session: &mut gwp::client::GqlSession| {
+        let mut cursor = session.execute_simple(read).await.unwrap();
+        cursor.collect_rows().await.unwrap();
+    };
+    run(&mut session).await;
+    run(&mut session).await;
+    assert_eq!(cache.len(), 1);
+    assert_eq!(server.backend().execute_count(read), 1);
+
+    cache.invalidate("default/default");
+    assert!(cache.is_empty());
+    run(&mut session).await;
+    assert_eq!(server.backend().execute_count(read), 2);
+}
+
+#[tokio::test]
+async fn oversized_statements_never_reach_the_backend() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.statement_limits(StatementLimits {
+            max_statement_length: Some(64),
+            max_parameters: Some(2),
+        })
+    })
+    .await
+    .unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let long = format!("MATCH (n) WHERE n.name = '{}' RETURN n", "x".repeat(64));
+    let mut cursor = session.execute_simple(&long).await.unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    let status = summary.status.unwrap();
+    assert_eq!(status.code, status::STATEMENT_TOO_LARGE);
+    assert!(status.message.contains("over the limit of 64"));
+
+    let parameters = (0..3)
+        .map(|i| (format!("p{i}"), Value::Integer(i)))
+        .collect();
+    let mut cursor = session
+        .execute("MATCH (n) RETURN n", parameters)
+        .await
+        .unwrap();
+    assert!(!cursor.is_success().await.unwrap());
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    assert!(cursor.is_success().await.unwrap());
+    assert_eq!(server.backend().execute_count(&long), 0);
+    assert_eq!(server.backend().execute_count("MATCH (n) RETURN n"), 1);
+}
+
+#[tokio::test]
+async fn server_logs_every_statement() {
+    let events = Arc::new(Mutex::new(Vec::<QueryLogEvent>::new()));
+    let sink = Arc::clone(&events);
+    let server = TestServer::start_with(MockBackend::new(), move |builder| {
+        builder.query_logger(move |event: &QueryLogEvent| sink.lock().unwrap().push(event.clone()))
+    })
+    .await
+    .unwrap();
+    server
+        .backend()
+        .on_statement("MATCH (n) WHERE n.id = $id RETURN n")
+        .respond_rows(
+            ["n"],
+            vec![vec![Value::Integer(1)], vec![Value::Integer(2)]],
+        );
+    server
+        .backend()
+        .on_statement("RETURN 1")
+        .respond_error("22000", "boom");
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session
+        .execute(
+            "MATCH (n) WHERE n.id = $id RETURN n",
+            HashMap::from([("id".to_owned(), Value::Integer(7))]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+    let mut cursor = session.execute_simple("RETURN 1").await.unwrap();
+    assert!(!cursor.is_success().await.unwrap());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].session_id, session.session_id());
+    assert_eq!(events[0].statement, "MATCH (n) WHERE n.id = $id RETURN n");
+    assert!(events[0].parameters_redacted);
+    assert!(events[0].parameters.is_empty());
+    assert_eq!(events[0].status.as_deref(), Some(status::SUCCESS));
+    assert_eq!(events[0].rows, 2);
+    assert_eq!(events[1].status.as_deref(), Some("22000"));
+}
+
+#[tokio::test]
+async fn slow_statements_carry_a_warning() {
+    let backend = MockBackend::new();
+    backend.set_latency(
+        MockOperation::ExecuteFirstFrame,
+        Latency::fixed(Duration::from_millis(100)),
+    );
+    let server = TestServer::start_with(backend, |builder| {
+        builder.slow_query_threshold(Duration::from_millis(50))
+    })
+    .await
+    .unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert_eq!(summary.status.unwrap().code, status::SUCCESS);
+    assert_eq!(summary.warnings.len(), 1);
+    assert_eq!(summary.warnings[0].code, status::WARNING_SLOW_QUERY);
+    assert!(summary.warnings[0].message.contains("executing"));
+
+    server.backend().set_latency(
+        MockOperation::ExecuteFirstFrame,
+        Latency::fixed(Duration::ZERO),
+    );
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert!(summary.warnings.is_empty());
+}
+
+#[tokio::test]
+async fn cursor_surfaces_warnings_as_they_arrive() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.slow_query_threshold(Duration::ZERO)
+    })
+    .await
+    .unwrap();
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n.nmae")
+        .with_warning(status::warning_at(
+            status::WARNING,
+            "property `nmae` is never set",
+            gwp::proto::SourcePosition {
+                offset: 19,
+                line: 1,
+                column: 20,
+            },
+        ))
+        .respond_rows(["n.nmae"], vec![vec![Value::Null]; 3]);
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session
+        .execute_simple("MATCH (n) RETURN n.nmae")
+        .await
+        .unwrap();
+    assert!(cursor.warnings().is_empty());
+    cursor.next_row().await.unwrap().unwrap();
+    let [warning] = cursor.warnings() else {
+        panic!("expected one warning, got {:?}", cursor.warnings());
+    };
+    assert_eq!(warning.code, status::WARNING);
+    assert_eq!(warning.severity, Severity::Warning);
+    assert_eq!(warning.message, "property `nmae` is never set");
+    assert_eq!(
+        warning.position.map(|p| (p.offset, p.line, p.column)),
+        Some((19, 1, 20))
+    );
+
+    cursor.summary().await.unwrap();
+    let codes: Vec<&str> = cursor.warnings().iter().map(|w| w.code.as_str()).collect();
+    assert_eq!(codes, [status::WARNING, status::WARNING_SLOW_QUERY]);
+    assert_eq!(cursor.warnings()[1].position, None);
+}
+
+#[tokio::test]
+async fn rate_limits_reject_statements_and_pace_rows() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.rate_limits(RateLimits {
+            queries_per_second: Some(1),
+            rows_per_second: Some(10),
+            ..RateLimits::default()
+        })
+    })
+    .await
+    .unwrap();
+    let rows = (0..15).map(|i| vec![Value::Integer(i)]).collect();
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n")
+        .respond_rows(["n"], rows);
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert_eq!(summary.warnings.len(), 1);
+    assert_eq!(summary.warnings[0].code, status::WARNING_THROTTLED);
+
+    // Pacing took half a second, not enough to earn another statement.
+    let mut other = server.session().await.unwrap();
+    assert!(other.execute_simple("RETURN 1").await.is_ok());
+    let Err(err) = session.execute_simple("RETURN 1").await else {
+        panic!("statement over the rate limit was admitted");
+    };
+    assert!(matches!(err, GqlError::Grpc(ref s) if s.code() == tonic::Code::ResourceExhausted));
+}
+
+#[tokio::test]
+async fn results_past_their_limits_are_cut_off() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.result_limits(ResultLimits {
+            max_rows: Some(10),
+            max_bytes: None,
+        })
+    })
+    .await
+    .unwrap();
+    let rows: Vec<Vec<Value>> = (0..15).map(|i| vec![Value::Integer(i)]).collect();
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n")
+        .respond_rows(["n"], rows.clone());
+    server
+        .backend()
+        .on_statement("MATCH (m) RETURN m")
+        .respond_rows(["m"], rows[..5].to_vec());
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    let status = summary.status.unwrap();
+    assert_eq!(status.code, status::RESULT_TOO_LARGE);
+    assert!(status.message.contains("10 rows"));
+
+    // A request may lower the server's limit.
+    session.set_fetch_size(Some(2));
+    session.set_result_limits(ResultLimits {
+        max_rows: Some(3),
+        max_bytes: None,
+    });
+    let mut cursor = session.execute_simple("MATCH (m) RETURN m").await.unwrap();
+    let mut received = 0;
+    while let Some(_row) = cursor.next_row().await.unwrap() {
+        received += 1;
+    }
+    assert_eq!(received, 2);
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert_eq!(summary.status.unwrap().code, status::RESULT_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn oversized_batches_are_split_to_the_message_size() {
+    const MAX: usize = 4096;
+    let server =
+        TestServer::start_with(MockBackend::new(), |builder| builder.max_message_size(MAX))
+            .await
+            .unwrap();
+    let rows: Vec<Vec<Value>> = (0..100)
+        .map(|i| vec![Value::String(format!("{i:0>200}"))])
+        .collect();
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n")
+        .respond_rows(["n"], rows.clone());
+
+    // The 20 KiB batch would fail a client accepting 4 KiB messages.
+    let conn = server.connection().with_max_message_size(MAX);
+    let mut session = conn.create_session().await.unwrap();
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let received = cursor.collect_rows().await.unwrap();
+    assert_eq!(received, rows);
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert!(status::is_success(&summary.status.unwrap().code));
+}
+
+#[tokio::test]
+async fn optional_services_are_advertised() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let session = server.session().await.unwrap();
+    assert_eq!(session.server_features(), [ADMIN_FEATURE, SEARCH_FEATURE]);
+
+    let server = TestServer::start_with(MockBackend::new(), GqlServer::disable_admin)
+        .await
+        .unwrap();
+    let session = server.session().await.unwrap();
+    assert_eq!(session.server_features(), [SEARCH_FEATURE]);
+    let err = server
+        .admin_client()
+        .get_stats("default")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(s) if s.code() == tonic::Code::Unimplemented));
+    let params = TextSearchParams::new("default", "Person", "bio", "engineer", 1);
+    let results = server.search_client().text_search(params).await.unwrap();
+    assert_eq!(results.hits.len(), 1);
+}
+
+#[tokio::test]
+async fn health_follows_the_backend() {
+    let server = TestServer::start_with(MockBackend::new(), |server| {
+        server.health_check_interval(Duration::from_millis(20))
+    })
+    .await
+    .unwrap();
+    let conn = server.connection();
+    assert_eq!(conn.server_health().await.unwrap(), HealthStatus::Serving);
+
+    for (serving, expected) in [
+        (false, HealthStatus::NotServing),
+        (true, HealthStatus::Serving),
+    ] {
+        server.backend().set_serving(serving);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while conn.server_health().await.unwrap() != expected {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("health did not follow the backend");
+    }
+}
+
+#[tokio::test]
+async fn session_resumes_on_a_new_connection() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.session_resumption(Duration::from_secs(60))
+    })
+    .await
+    .unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    session.set_graph("social").await.unwrap();
+    let _tx = session.begin_transaction().await.unwrap();
+    let token = session.resumption_token().unwrap().to_owned();
+    assert!(!session.is_resumed());
+    session.detach();
+
+    let conn = GqlConnection::connect(&server.endpoint()).await.unwrap();
+    let mut resumed = conn.resume_session(&token).await.unwrap();
+    assert!(resumed.is_resumed());
+    assert_eq!(resumed.default_graph(), Some("social"));
+    assert_ne!(resumed.resumption_token(), Some(token.as_str()));
+    // The transaction open before the drop was rolled back.
+    resumed
+        .begin_transaction()
+        .await
+        .unwrap()
+        .commit()
+        .await
+        .unwrap();
+
+    // Tokens are single-use.
+    let fresh = conn.resume_session(&token).await.unwrap();
+    assert!(!fresh.is_resumed());
+    assert_ne!(fresh.session_id(), resumed.session_id());
+}
+
+#[tokio::test]
+async fn resume_keeps_the_open_transaction() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.session_resumption(Duration::from_secs(60))
+    })
+    .await
+    .unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let tx = session.begin_transaction().await.unwrap();
+    let transaction_id = tx.transaction_id().to_owned();
+    let token = session.resumption_token().unwrap().to_owned();
+    // The connection drops before the client can roll back or close.
+    std::mem::forget(tx);
+    session.detach();
+
+    let conn = GqlConnection::connect(&server.endpoint()).await.unwrap();
+    let mut resumed = conn.resume(&token).await.unwrap();
+    assert!(resumed.is_resumed());
+    let mut tx = resumed.take_resumed_transaction().unwrap();
+    assert_eq!(tx.transaction_id(), transaction_id);
+    assert!(resumed.take_resumed_transaction().is_none());
+    tx.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    tx.commit().await.unwrap();
+    server.backend().assert_transactions(1, 0);
+
+    // Unlike a resuming handshake, a spent token is an error.
+    let err = conn.resume(&token).await.err().unwrap();
+    assert!(matches!(err, GqlError::Grpc(ref s) if s.code() == tonic::Code::NotFound));
+}
+
+#[tokio::test]
+async fn close_gives_up_after_the_close_timeout() {
+    let backend = MockBackend::new();
+    backend.set_latency(
+        MockOperation::CloseSession,
+        Latency::fixed(Duration::from_secs(30)),
+    );
+    let server = TestServer::start(backend).await.unwrap();
+    let conn = server
+        .connection()
+        .with_close_timeout(Duration::from_millis(100));
+    let session = conn.create_session().await.unwrap();
+
+    let started = std::time::Instant::now();
+    let err = session.close().await.unwrap_err();
+    assert!(matches!(err, GqlError::Grpc(ref s) if s.code() == tonic::Code::DeadlineExceeded));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn dropped_sessions_are_closed_unless_detached() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+    let dropped = conn.create_session().await.unwrap();
+    let detached = conn.create_session().await.unwrap();
+    let dropped_id = dropped.session_id().to_owned();
+
+    drop(dropped);
+    detached.detach();
+    let closed = || {
+        server
+            .backend()
+            .calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                MockCall::CloseSession { session } => Some(session.0),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while closed().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    // Give a stray close for the detached session time to arrive.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(closed(), [dropped_id]);
+}
+
+#[tokio::test]
+async fn resumption_is_off_by_default() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let session = server.connection().create_session().await.unwrap();
+    assert!(session.resumption_token().is_none());
+
+    let other = server.connection().resume_session("bogus").await.unwrap();
+    assert!(!other.is_resumed());
+    assert!(server.connection().resume("bogus").await.is_err());
+}
+
+#[tokio::test]
+async fn prepared_transaction_commits_from_another_session() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = server.connection();
+    let mut participant = conn.create_session().await.unwrap();
+    let mut tx = participant.begin_transaction().await.unwrap();
+    tx.execute_simple("INSERT (:Person)")
+        .await
+        .unwrap()
+        .rows_affected()
+        .await
+        .unwrap();
+    let transaction_id = tx.prepare_commit().await.unwrap();
+
+    // The prepared transaction survives its session and frees it for
+    // another transaction.
+    participant
+        .begin_transaction()
+        .await
+        .unwrap()
+        .rollback()
+        .await
+        .unwrap();
+    participant.close().await.unwrap();
+
+    let mut coordinator = conn.create_session().await.unwrap();
+    coordinator.commit_prepared(&transaction_id).await.unwrap();
+    let err = coordinator
+        .commit_prepared(&transaction_id)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.gql_status().unwrap().code,
+        status::INVALID_TRANSACTION_STATE
+    );
+
+    let calls = server.backend().calls();
+    assert!(calls.iter().any(|call| matches!(
+        call,
+        MockCall::CommitPrepared { transaction, .. } if transaction.0 == transaction_id
+    )));
+    assert!(!calls.iter().any(|call| matches!(
+        call,
+        MockCall::Rollback { transaction, .. } if transaction.0 == transaction_id
+    )));
+}
+
+#[tokio::test]
+async fn isolation_level_reaches_the_backend() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let tx = session
+        .begin_transaction_with(TxOptions::new().isolation_level(IsolationLevel::Serializable))
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    session
+        .begin_transaction()
+        .await
+        .unwrap()
+        .commit()
+        .await
+        .unwrap();
+
+    let levels: Vec<_> = server
+        .backend()
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            MockCall::BeginTransaction {
+                isolation_level, ..
+            } => Some(isolation_level),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        levels,
+        [IsolationLevel::Serializable, IsolationLevel::Default]
+    );
+}
+
+#[tokio::test]
+async fn timed_out_transactions_are_rolled_back() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let tx = session
+        .begin_transaction_with(TxOptions::new().timeout(Duration::from_millis(20)))
+        .await
+        .unwrap();
+    let transaction_id = tx.transaction_id().to_owned();
+    for _ in 0..100 {
+        if server.backend().calls().iter().any(|call| {
+            matches!(
+                call,
+                MockCall::Rollback { transaction, .. } if transaction.0 == transaction_id
+            )
+        }) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let err = tx.commit().await.unwrap_err();
+    assert_eq!(
+        err.gql_status().unwrap().code,
+        status::INVALID_TRANSACTION_STATE
+    );
+    assert!(server.backend().calls().iter().any(|call| matches!(
+        call,
+        MockCall::BeginTransaction { timeout, .. } if *timeout == Some(Duration::from_millis(20))
+    )));
+
+    // The session is free to begin another transaction.
+    session
+        .begin_transaction()
+        .await
+        .unwrap()
+        .commit()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn run_in_transaction_retries_conflicts() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    server.backend().fail_next_commits(1);
+
+    let mut attempts = 0;
+    let succeeded_on = session
+        .run_in_transaction(async |tx| {
+            attempts += 1;
+            tx.execute_simple("INSERT (:Person)")
+                .await?
+                .collect_rows()
+                .await?;
+            Ok(attempts)
+        })
+        .await
+        .unwrap();
+    assert_eq!(succeeded_on, 2);
+    assert_eq!(server.backend().execute_count("INSERT (:Person)"), 2);
+    // The failed commit was rolled back before the retry.
+    server.backend().assert_transactions(2, 1);
+
+    // Failures other than conflicts end the work at once.
+    let mut attempts = 0;
+    let err = session
+        .run_in_transaction(async |tx| {
+            attempts += 1;
+            tx.execute_simple("ERROR here")
+                .await?
+                .collect_as::<Vec<Value>>()
+                .await
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.gql_status().unwrap().code, status::INVALID_SYNTAX);
+    assert_eq!(attempts, 1);
+    server.backend().assert_transactions(2, 2);
+}
+
+#[tokio::test]
+async fn prepared_statements_run_by_id() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let statement = "MATCH (p:Person) WHERE p.age > $age RETURN p.name, p.age";
+
+    let prepared = session.prepare(statement).await.unwrap();
+    assert_eq!(prepared.statement(), statement);
+    for age in [20, 26] {
+        let mut cursor = session
+            .execute_prepared(&prepared, params! { "age" => age }.into())
+            .await
+            .unwrap();
+        assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+    }
+    let mut tx = session.begin_transaction().await.unwrap();
+    let mut cursor = tx
+        .execute_prepared(&prepared, HashMap::new())
+        .await
+        .unwrap();
+    assert!(cursor.is_success().await.unwrap());
+    tx.commit().await.unwrap();
+    assert_eq!(server.backend().execute_count(statement), 3);
+
+    let calls = server.backend().calls();
+    let prepared_id = calls.iter().find_map(|call| match call {
+        MockCall::Prepare {
+            prepared,
+            statement: text,
+            ..
+        } if text == statement => Some(prepared.0.clone()),
+        _ => None,
+    });
+    assert_eq!(prepared_id.as_deref(), Some(prepared.statement_id()));
+
+    let stale = prepared.clone();
+    prepared.close().await.unwrap();
+    let mut cursor = session
+        .execute_prepared(&stale, HashMap::new())
+        .await
+        .unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert_eq!(
+        summary.status.unwrap().code,
+        status::PREPARED_STATEMENT_NOT_FOUND
+    );
+    assert!(stale.close().await.is_err());
+
+    let err = session.prepare("ERROR here").await.unwrap_err();
+    assert!(matches!(err, GqlError::Status { status } if status.code == status::INVALID_SYNTAX));
+}
+
+#[tokio::test]
+async fn batches_run_in_order_and_stop_at_the_first_failure() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let read = "MATCH (p:Person) RETURN p.name, p.age";
+
+    let mut batch = session
+        .execute_batch(&[
+            (
+                "INSERT (:Person {name: $name})",
+                params! { "name" => "Ada" }.into(),
+            ),
+            (read, HashMap::new()),
+            ("ERROR here", HashMap::new()),
+            ("INSERT (:Person)", HashMap::new()),
+        ])
+        .await
+        .unwrap();
+    let insert = batch.next_result().await.unwrap().unwrap();
+    assert_eq!((insert.index, insert.rows_affected()), (0, 3));
+    let rows = batch.next_result().await.unwrap().unwrap();
+    assert_eq!((rows.index, rows.rows.len()), (1, 2));
+    let failed = batch.next_result().await.unwrap().unwrap();
+    assert_eq!(failed.index, 2);
+    assert!(!failed.is_success());
+    assert!(batch.next_result().await.unwrap().is_none());
+    let status = batch.status().await.unwrap().cloned().unwrap();
+    assert_eq!(status.code, status::INVALID_SYNTAX);
+    assert_eq!(batch.failed_statement(), Some(2));
+    assert_eq!(server.backend().execute_count("INSERT (:Person)"), 0);
+
+    // An atomic batch rolls back its implicit transaction on failure.
+    let mut batch = session
+        .execute_batch_atomic(&[
+            ("INSERT (:Person)", HashMap::new()),
+            ("ERROR here", HashMap::new()),
+        ])
+        .await
+        .unwrap();
+    assert!(!batch.is_success().await.unwrap());
+    let mut batch = session
+        .execute_batch_atomic(&[("INSERT (:Person)", HashMap::new())])
+        .await
+        .unwrap();
+    assert!(batch.is_success().await.unwrap());
+    assert_eq!(batch.failed_statement(), None);
+
+    let calls = server.backend().calls();
+    let ended: Vec<_> = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::Commit { .. } => Some("commit"),
+            MockCall::Rollback { .. } => Some("rollback"),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(ended, ["rollback", "commit"]);
+    assert!(calls.iter().any(|call| matches!(
+        call,
+        MockCall::Execute { statement, transaction: Some(_), .. } if statement == "INSERT (:Person)"
+    )));
+}
+
+#[tokio::test]
+async fn bulk_load_streams_chunks_and_reports_totals() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let mut loader = session.bulk_load().await.unwrap();
+    let people: Vec<Node> = (0..3u8)
+        .map(|i| Node::new(vec![i + 1]).with_label("Person"))
+        .collect();
+    loader.send(people, Vec::new()).await.unwrap();
+    let knows = Edge::directed(vec![10], vec![1], vec![2]).with_label("KNOWS");
+    let dangling = Edge::directed(vec![11], vec![1], Vec::new());
+    loader
+        .send(vec![Node::new(Vec::new())], vec![knows, dangling])
+        .await
+        .unwrap();
+    let totals = loader.finish().await.unwrap();
+    assert_eq!(totals.nodes_loaded, 3);
+    assert_eq!(totals.edges_loaded, 1);
+    assert_eq!(totals.errors, 2);
+
+    let chunks = server
+        .backend()
+        .calls()
+        .into_iter()
+        .find_map(|call| match call {
+            MockCall::BulkLoad { chunks, .. } => Some(chunks),
+            _ => None,
+        })
+        .unwrap();
+    // The opening message carries only the session.
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[1].nodes[2].labels, ["Person"]);
+}
+
+/// Publish `event` until it reaches `subscribers` sessions, as
+/// subscribing and unsubscribing take effect in the background.
+async fn publish_to(server: &TestServer<MockBackend>, event: &ChangeEvent, subscribers: usize) {
+    for _ in 0..100 {
+        if server.backend().publish(event) == subscribers {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("event never reached {subscribers} subscribers");
+}
+
+#[tokio::test]
+async fn notifications_deliver_events_for_subscribed_topics() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let mut notifications = session.notifications().await.unwrap();
+    notifications.subscribe("graph/default").await.unwrap();
+    let event = ChangeEvent {
+        topic: "graph/default".to_owned(),
+        kind: "node_created".to_owned(),
+        attributes: HashMap::from([("label".to_owned(), Value::from("Person"))]),
+    };
+    let other = ChangeEvent {
+        topic: "graph/other".to_owned(),
+        ..event.clone()
+    };
+    assert_eq!(server.backend().publish(&other), 0);
+    publish_to(&server, &event, 1).await;
+    assert_eq!(notifications.next().await.unwrap(), Some(event.clone()));
+    assert!(server.backend().calls().iter().any(|call| matches!(
+        call,
+        MockCall::Subscribe { topic, .. } if topic == "graph/default"
+    )));
+
+    notifications.unsubscribe("graph/default").await.unwrap();
+    publish_to(&server, &event, 0).await;
+}
+
+#[tokio::test]
+async fn explain_returns_the_plan_without_executing() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let statement = "MATCH (n) RETURN n";
+
+    let plan = session
+        .explain(statement, params! { "limit" => 10 }.into())
+        .await
+        .unwrap();
+    assert_eq!(plan.operator, "Project");
+    assert_eq!(plan.children.len(), 1);
+    assert_eq!(plan.children[0].operator, "NodeScan");
+    assert!(plan.children[0].children.is_empty());
+    assert!(plan.cost >= plan.children[0].cost);
+    assert_eq!(server.backend().execute_count(statement), 0);
+    assert!(server.backend().calls().iter().any(|call| matches!(
+        call,
+        MockCall::Explain { statement: text, .. } if text == statement
+    )));
+
+    let err = session
+        .explain("ERROR here", HashMap::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GqlError::Status { status } if status.code == status::INVALID_SYNTAX));
+}
+
+#[tokio::test]
+async fn savepoints_roll_back_part_of_a_transaction() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let mut tx = session.begin_transaction().await.unwrap();
+
+    tx.savepoint("before_people").await.unwrap();
+    tx.execute_simple("INSERT (:Person)").await.unwrap();
+    tx.savepoint("before_edges").await.unwrap();
+    tx.rollback_to_savepoint("before_people").await.unwrap();
+    // Rolling back forgot the later savepoint but kept the one rolled back to.
+    let err = tx.release_savepoint("before_edges").await.unwrap_err();
+    assert_eq!(err.gql_status().unwrap().code, status::INVALID_REFERENCE);
+    tx.rollback_to_savepoint("before_people").await.unwrap();
+    tx.release_savepoint("before_people").await.unwrap();
+    tx.commit().await.unwrap();
+
+    let rollbacks: Vec<String> = server
+        .backend()
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            MockCall::RollbackToSavepoint { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(rollbacks, ["before_people", "before_people"]);
+    server.backend().assert_transactions(1, 0);
+}
+
+#[tokio::test]
+async fn failed_prepare_votes_to_abort() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    server.backend().fail_next_commits(1);
+    let tx = session.begin_transaction().await.unwrap();
+    let transaction_id = tx.transaction_id().to_owned();
+    let err = tx.prepare_commit().await.unwrap_err();
+    assert_eq!(
+        err.gql_status().unwrap().code,
+        status::SERIALIZATION_FAILURE
+    );
+
+    let err = session
+        .rollback_prepared(&transaction_id)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.gql_status().unwrap().code,
+        status::INVALID_TRANSACTION_STATE
+    );
+}
+
+/// XORs string bytes with a fixed key byte.
+struct Xor(u8);
+
+impl ValueCipher for Xor {
+    fn encrypt(&self, value: &Value) -> Result<EncryptedValue, CipherError> {
+        let plaintext = value.as_str().ok_or("only strings are encrypted")?;
+        Ok(EncryptedValue {
+            key_id: "xor".to_owned(),
+            algorithm: "XOR".to_owned(),
+            ciphertext: plaintext.bytes().map(|b| b ^ self.0).collect(),
+        })
+    }
+
+    fn decrypt(&self, value: &EncryptedValue) -> Result<Option<Value>, CipherError> {
+        if value.key_id != "xor" {
+            return Ok(None);
+        }
+        let plaintext = value.ciphertext.iter().map(|b| b ^ self.0).collect();
+        Ok(Some(Value::String(String::from_utf8(plaintext)?)))
+    }
+}
+
+#[tokio::test]
+async fn encrypted_fields_are_opaque_to_the_server() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    session.set_field_encryption(Some(
+        FieldEncryption::new(Xor(0x5A)).encrypt_parameter("ssn"),
+    ));
+
+    let parameters = HashMap::from([
+        ("name".to_owned(), Value::from("Alice")),
+        ("ssn".to_owned(), Value::from("123-45-6789")),
+    ]);
+    let mut tx = session.begin_transaction().await.unwrap();
+    tx.execute("INSERT (:Person {name: $name, ssn: $ssn})", parameters)
+        .await
+        .unwrap()
+        .rows_affected()
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let stored = server
+        .backend()
+        .calls()
+        .into_iter()
+        .find_map(|call| match call {
+            MockCall::Execute { parameters, .. } => Some(parameters),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(stored["name"], Value::from("Alice"));
+    let ssn = stored["ssn"].as_encrypted().unwrap();
+    assert_eq!(ssn.key_id, "xor");
+    assert!(!ssn.ciphertext.windows(3).any(|w| w == b"123"));
+
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN p.ssn")
+        .respond_rows(["ssn"], vec![vec![stored["ssn"].clone()]]);
+    let rows = session
+        .execute_simple("MATCH (p:Person) RETURN p.ssn")
+        .await
+        .unwrap()
+        .collect_rows()
+        .await
+        .unwrap();
+    assert_eq!(rows, vec![vec![Value::from("123-45-6789")]]);
+
+    session.set_field_encryption(None);
+    let rows = session
+        .execute_simple("MATCH (p:Person) RETURN p.ssn")
+        .await
+        .unwrap()
+        .collect_rows()
+        .await
+        .unwrap();
+    assert_eq!(rows, vec![vec![stored["ssn"].clone()]]);
+}
+
+#[tokio::test]
+async fn dictionary_encoded_results_decode_transparently() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = GqlConnection::connect(&server.endpoint())
+        .await
+        .unwrap()
+        .with_row_batch_checksums()
+        .with_dictionary_encoding();
+    let mut session = conn.create_session().await.unwrap();
+    assert!(session.dictionary_encoding());
+
+    let person = |id: u8, name: &str| {
+        Value::Node(
+            Node::new(vec![id])
+                .with_label("Person")
+                .with_property("name", name)
+                .with_property("city", "Utrecht"),
+        )
+    };
+    let rows = vec![
+        vec![person(1, "Alice"), Value::from("Utrecht")],
+        vec![person(2, "Bob"), Value::from("Utrecht")],
+    ];
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN p, p.city")
+        .respond_rows(["p", "city"], rows.clone());
+
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN p, p.city")
+        .await
+        .unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap(), rows);
+}
+
+#[tokio::test]
+async fn columnar_results_decode_transparently() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let conn = GqlConnection::connect(&server.endpoint())
+        .await
+        .unwrap()
+        .with_row_batch_checksums()
+        .with_dictionary_encoding()
+        .with_columnar_batches();
+    let mut session = conn.create_session().await.unwrap();
+    assert!(session.columnar_batches());
+
+    let rows: Vec<Vec<Value>> = (0..20)
+        .map(|i| {
+            vec![
+                Value::Integer(i),
+                if i % 3 == 0 {
+                    Value::Null
+                } else {
+                    Value::Float(0.5)
+                },
+                Value::from("Utrecht"),
+                Value::Node(Node::new(vec![1]).with_label("Person")),
+            ]
+        })
+        .collect();
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN p.id, p.score, p.city, p")
+        .respond_rows(["id", "score", "city", "p"], rows.clone());
+
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN p.id, p.score, p.city, p")
+        .await
+        .unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap(), rows);
+}
+
+#[tokio::test]
+async fn large_results_spill_to_disk() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let rows: Vec<Vec<Value>> = (0..500)
+        .map(|i| vec![Value::Integer(i), Value::from(format!("person {i}"))])
+        .collect();
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN id(p), p.name")
+        .respond_rows(["id", "name"], rows.clone());
+
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN id(p), p.name")
+        .await
+        .unwrap();
+    let spilled = cursor.collect_spilling(1024).await.unwrap();
+    assert!(cursor.is_success().await.unwrap());
+    assert!(spilled.is_spilled());
+    assert_eq!(spilled.len(), 500);
+    let collected: Vec<Vec<Value>> = spilled.map(Result::unwrap).collect();
+    assert_eq!(collected, rows);
+}
+
+#[tokio::test]
+async fn buffered_cursor_peeks_and_replays() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let rows: Vec<Vec<Value>> = (0..5).map(|i| vec![Value::Integer(i)]).collect();
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN id(n)")
+        .respond_rows(["id"], rows.clone());
+
+    let mut cursor = session
+        .execute_simple("MATCH (n) RETURN id(n)")
+        .await
+        .unwrap()
+        .buffered();
+    assert_eq!(cursor.peek_row().await.unwrap(), Some(rows[0].as_slice()));
+    assert_eq!(cursor.position(), 0);
+    assert_eq!(cursor.next_row().await.unwrap().unwrap(), rows[0]);
+    assert_eq!(cursor.next_row().await.unwrap().unwrap(), rows[1]);
+    assert_eq!(cursor.position(), 2);
+
+    cursor.rewind();
+    assert_eq!(cursor.position(), 0);
+    assert_eq!(cursor.peek_row().await.unwrap(), Some(rows[0].as_slice()));
+    assert_eq!(cursor.collect_rows().await.unwrap(), rows);
+    assert_eq!(cursor.peek_row().await.unwrap(), None);
+    assert!(cursor.is_success().await.unwrap());
+
+    cursor.rewind();
+    assert_eq!(cursor.collect_rows().await.unwrap(), rows);
+}
+
+#[tokio::test]
+async fn cursor_reports_update_counters_and_timings() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    server
+        .backend()
+        .on_statement("INSERT (:Person {name: 'Alix'})")
+        .respond_update_counters(gwp::proto::UpdateCounters {
+            nodes_created: 1,
+            properties_set: 1,
+            labels_added: 1,
+            ..Default::default()
+        });
+
+    let stats = session
+        .execute_simple("INSERT (:Person {name: 'Alix'})")
+        .await
+        .unwrap()
+        .stats()
+        .await
+        .unwrap();
+    assert_eq!(
+        (
+            stats.nodes_created,
+            stats.properties_set,
+            stats.labels_added
+        ),
+        (1, 1, 1)
+    );
+    assert_eq!((stats.nodes_deleted, stats.edges_created), (0, 0));
+    assert!(stats.planning.is_some());
+    assert!(stats.execution.is_some());
+    assert!(stats.streaming.is_some());
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    cursor.collect_rows().await.unwrap();
+    let stats = cursor.stats().await.unwrap();
+    assert_eq!(stats.rows_affected, 2);
+    assert_eq!(stats.nodes_created, 0);
+}
+
+#[tokio::test]
+async fn rows_read_columns_by_name() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN p.name, p.age")
+        .respond_rows(
+            ["name", "age"],
+            vec![
+                vec![Value::String("Alix".into()), Value::Integer(30)],
+                vec![Value::String("Gus".into()), Value::Null],
+            ],
+        );
+
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN p.name, p.age")
+        .await
+        .unwrap();
+    let rows = cursor.collect_rows().await.unwrap();
+    assert_eq!(rows[0].columns(), ["name", "age"]);
+    assert_eq!(rows[0].get::<String>("name").unwrap(), "Alix");
+    assert_eq!(rows[0].get::<i64>("age").unwrap(), 30);
+    assert_eq!(rows[1].get::<Option<i64>>("age").unwrap(), None);
+    assert_eq!(rows[1][0], Value::String("Gus".into()));
+    assert_eq!(rows[0].value("email"), None);
+
+    let err = rows[0].get::<i64>("email").unwrap_err();
+    assert!(err.to_string().contains("no column `email`"));
+    let err = rows[0].get::<i64>("name").unwrap_err();
+    assert!(err.to_string().contains("column `name`"));
+}
+
+#[tokio::test]
+async fn cursor_streams_rows() {
+    use tokio_stream::StreamExt;
+
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+    let rows: Vec<Vec<Value>> = (0..5)
+        .map(|i| vec![Value::Integer(i), Value::String(format!("p{i}"))])
+        .collect();
+    server
+        .backend()
+        .on_statement("MATCH (p:Person) RETURN id(p), p.name")
+        .respond_rows(["id", "name"], rows.clone());
+    server
+        .backend()
+        .on_statement("MATCH (p:Missing) RETURN p")
+        .respond_error("42001", "no such label");
+
+    let cursor = session
+        .execute_simple("MATCH (p:Person) RETURN id(p), p.name")
+        .await
+        .unwrap();
+    let streamed: Vec<Row> = cursor.map(Result::unwrap).collect().await;
+    assert_eq!(streamed, rows);
+
+    let mut typed = session
+        .execute_simple("MATCH (p:Person) RETURN id(p), p.name")
+        .await
+        .unwrap()
+        .into_stream::<(i64, String)>()
+        .filter_map(Result::ok)
+        .filter(|(id, _)| id % 2 == 0);
+    let mut names = Vec::new();
+    while let Some((_, name)) = typed.next().await {
+        names.push(name);
+    }
+    assert_eq!(names, ["p0", "p2", "p4"]);
+
+    let results: Vec<_> = session
+        .execute_simple("MATCH (p:Missing) RETURN p")
+        .await
+        .unwrap()
+        .into_stream::<Vec<Value>>()
+        .collect()
+        .await;
+    assert!(matches!(
+        results.as_slice(),
+        [Err(GqlError::Status { status })] if status.code == "42001"
+    ));
+}
+
+#[tokio::test]
+async fn servers_share_one_backend() {
+    let backend = Arc::new(MockBackend::new());
+    let mut sessions = Vec::new();
+    for _ in 0..2 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(GqlServer::builder_shared(Arc::clone(&backend)).serve_with_listener(listener));
+        let conn = GqlConnection::connect(&format!("http://{addr}"))
+            .await
+            .unwrap();
+        sessions.push(conn.create_session().await.unwrap());
+    }
+
+    for session in &mut sessions {
+        session.execute_simple("INSERT (:Person)").await.unwrap();
+    }
+    assert_eq!(backend.execute_count("INSERT (:Person)"), 2);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_domain_socket_transport() {
+    let path = std::env::temp_dir().join(format!("gwp-{}.sock", std::process::id()));
+    let backend = Arc::new(MockBackend::new());
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(
+        GqlServer::builder_shared(Arc::clone(&backend))
+            .shutdown(async {
+                let _ = stopped.await;
+            })
+            .serve_uds(path.clone()),
+    );
+
+    let conn = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Ok(conn) = GqlConnection::connect_uds(&path).await {
+                break conn;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let mut session = conn.create_session().await.unwrap();
+    session.execute_simple("INSERT (:Person)").await.unwrap();
+    session.close().await.unwrap();
+    assert_eq!(backend.execute_count("INSERT (:Person)"), 1);
+
+    drop(conn);
+    stop.send(()).unwrap();
+    server.await.unwrap().unwrap();
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn local_connections_skip_the_network() {
+    let backend = Arc::new(MockBackend::new());
+    let local = LocalConnection::start(GqlServer::builder_shared(Arc::clone(&backend)));
+
+    let mut first = local.create_session().await.unwrap();
+    let mut second = local.connection().create_session().await.unwrap();
+    first.execute_simple("INSERT (:Person)").await.unwrap();
+    second.execute_simple("INSERT (:Person)").await.unwrap();
+    assert_eq!(backend.execute_count("INSERT (:Person)"), 2);
+}
+
+#[tokio::test]
+async fn database_session_limits_protect_other_databases() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder
+            .max_sessions_per_database(2)
+            .database_session_limit("default/hot", 1)
+    })
+    .await
+    .unwrap();
+    let conn = server.connection();
+
+    let mut first = conn.create_session().await.unwrap();
+    first.set_graph("hot").await.unwrap();
+    let mut second = conn.create_session().await.unwrap();
+    let err = second.set_graph("hot").await.unwrap_err();
+    let gwp::error::GqlError::Grpc(status) = err else {
+        panic!("expected a gRPC status, got {err:?}");
+    };
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    assert!(status.message().contains("default/hot"));
+
+    // The default database has its own budget.
+    let _third = conn.create_session().await.unwrap();
+    assert!(conn.create_session().await.is_err());
+    assert!(first.reset().await.is_err());
+    second.set_graph("cold").await.unwrap();
+    first.reset().await.unwrap();
+}
+
+#[tokio::test]
+async fn sessions_are_routed_to_the_database_they_use() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.max_sessions_per_database(1)
+    })
+    .await
+    .unwrap();
+    let sales = server.connection().with_database("sales");
+
+    let mut first = sales.create_session().await.unwrap();
+    let Err(err) = sales.create_session().await else {
+        panic!("expected the sales database to be full");
+    };
+    assert!(err.to_string().contains("sales/"), "{err}");
+
+    // Other databases keep their own budgets.
+    let mut second = server.connection().create_session().await.unwrap();
+    second.set_database("hr").await.unwrap();
+    assert!(first.set_database("hr").await.is_err());
+    first.reset().await.unwrap();
+    assert!(sales.create_session().await.is_err());
+
+    let calls = server.backend().calls();
+    let handshakes: Vec<_> = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::CreateSession { database, .. } => Some(database.as_deref()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        handshakes,
+        [Some("sales"), Some("sales"), None, Some("sales")]
+    );
+    assert!(calls.iter().any(|call| matches!(
+        call,
+        MockCall::Configure { property: SessionProperty::Database(database), .. }
+            if database == "hr"
+    )));
+}
+
+/// Accepts any basic credentials; `admin` gets the admin role.
+struct RoleValidator;
+
+#[tonic::async_trait]
+impl gwp::server::AuthValidator for RoleValidator {
+    async fn validate(&self, _credentials: &gwp::proto::AuthCredentials) -> Result<(), GqlError> {
+        Ok(())
+    }
+
+    async fn authenticate(
+        &self,
+        credentials: &gwp::proto::AuthCredentials,
+    ) -> Result<Principal, GqlError> {
+        let principal = Principal::from_credentials(credentials);
+        Ok(if principal.name == "admin" {
+            principal.with_role("admin")
+        } else {
+            principal
+        })
+    }
+}
+
+/// Lets everyone read; only admins may do anything else.
+struct ReadOnlyUnlessAdmin;
+
+#[tonic::async_trait]
+impl Authorizer for ReadOnlyUnlessAdmin {
+    async fn authorize(
+        &self,
+        principal: Option<&Principal>,
+        operation: Operation,
+    ) -> Result<(), GqlError> {
+        if operation == Operation::Read || principal.is_some_and(|p| p.has_role("admin")) {
+            Ok(())
+        } else {
+            Err(GqlError::Session(format!("{operation:?} not allowed")))
+        }
+    }
+}
+
+fn basic(username: &str) -> gwp::proto::AuthCredentials {
+    gwp::proto::AuthCredentials {
+        method: Some(gwp::proto::auth_credentials::Method::Basic(
+            gwp::proto::BasicAuth {
+                username: username.to_owned(),
+                password: String::new(),
+            },
+        )),
+    }
+}
+
+#[tokio::test]
+async fn authorizer_sees_the_session_principal() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.auth(RoleValidator).authorizer(ReadOnlyUnlessAdmin)
+    })
+    .await
+    .unwrap();
+
+    let reader = server.connection().with_credentials(basic("alice"));
+    let mut session = reader.create_session().await.unwrap();
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    assert!(cursor.is_success().await.unwrap());
+    let mut cursor = session.execute_simple("INSERT (:Person)").await.unwrap();
+    let summary = cursor.summary().await.unwrap().cloned().unwrap();
+    assert_eq!(summary.status.unwrap().code, status::PERMISSION_DENIED);
+    assert_eq!(server.backend().execute_count("INSERT (:Person)"), 0);
+
+    let mut catalog = reader.create_catalog_client_for(&session);
+    catalog.list_schemas().await.unwrap();
+    let err = reader
+        .create_admin_client_for(&session)
+        .get_stats("default")
+        .await
+        .unwrap_err();
+    let GqlError::Grpc(status) = err else {
+        panic!("expected a gRPC status, got {err:?}");
+    };
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    // Calls not made for a session have no principal.
+    assert!(server.admin_client().get_stats("default").await.is_err());
+
+    let admin = server.connection().with_credentials(basic("admin"));
+    let mut session = admin.create_session().await.unwrap();
+    let mut cursor = session.execute_simple("INSERT (:Person)").await.unwrap();
+    assert!(cursor.is_success().await.unwrap());
+    admin
+        .create_admin_client_for(&session)
+        .get_stats("default")
+        .await
+        .unwrap();
+}
+
+/// Accepts bearer tokens of the form `user-generation`, except `expired`.
+struct TokenValidator;
+
+#[tonic::async_trait]
+impl gwp::server::AuthValidator for TokenValidator {
+    async fn validate(&self, credentials: &gwp::proto::AuthCredentials) -> Result<(), GqlError> {
+        self.authenticate(credentials).await.map(|_| ())
+    }
+
+    async fn authenticate(
+        &self,
+        credentials: &gwp::proto::AuthCredentials,
+    ) -> Result<Principal, GqlError> {
+        match &credentials.method {
+            Some(gwp::proto::auth_credentials::Method::BearerToken(token))
+                if token != "expired" =>
+            {
+                let user = token.split('-').next().unwrap_or_default();
+                Ok(Principal::new(user))
+            }
+            _ => Err(GqlError::Session("invalid token".into())),
+        }
+    }
+}
+
+fn bearer(token: &str) -> gwp::proto::AuthCredentials {
+    gwp::proto::AuthCredentials {
+        method: Some(gwp::proto::auth_credentials::Method::BearerToken(
+            token.to_owned(),
+        )),
+    }
+}
+
+#[tokio::test]
+async fn refreshed_credentials_keep_the_session_and_transaction() {
+    let server = TestServer::start_with(MockBackend::new(), |builder| builder.auth(TokenValidator))
+        .await
+        .unwrap();
+    let conn = server.connection().with_credentials(bearer("alice-1"));
+    let mut session = conn.create_session().await.unwrap();
+    let mut tx = session.begin_transaction().await.unwrap();
+
+    session.refresh_auth(bearer("alice-2")).await.unwrap();
+
+    let err = session.refresh_auth(bearer("expired")).await.unwrap_err();
+    let GqlError::Grpc(status) = err else {
+        panic!("expected a gRPC status, got {err:?}");
+    };
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    let err = session.refresh_auth(bearer("bob-1")).await.unwrap_err();
+    let GqlError::Grpc(status) = err else {
+        panic!("expected a gRPC status, got {err:?}");
+    };
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+    tx.execute_simple("INSERT (:Person)").await.unwrap();
+    tx.commit().await.unwrap();
+    session.ping().await.unwrap();
 }
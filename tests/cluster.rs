@@ -0,0 +1,238 @@
+//! Integration tests for read-replica routing in `ClusterConnection`.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use gwp::client::{CircuitBreaker, CircuitState, ClusterConnection, Topology};
+use gwp::server::mock_backend::{MockBackend, MockCall};
+use gwp::testing::TestServer;
+
+/// An endpoint nothing listens on.
+const UNREACHABLE: &str = "http://127.0.0.1:1";
+
+async fn server() -> TestServer<MockBackend> {
+    TestServer::start(MockBackend::new()).await.unwrap()
+}
+
+fn cluster(writer: &TestServer<MockBackend>, readers: &[String]) -> ClusterConnection {
+    ClusterConnection::new(&Topology {
+        writer: writer.endpoint(),
+        readers: readers.to_vec(),
+    })
+    .unwrap()
+}
+
+fn began_transaction(backend: &MockBackend) -> bool {
+    backend
+        .calls()
+        .iter()
+        .any(|call| matches!(call, MockCall::BeginTransaction { .. }))
+}
+
+#[tokio::test]
+async fn reads_go_to_reader_and_writes_to_writer() {
+    let writer = server().await;
+    let reader = server().await;
+    let conn = cluster(&writer, &[reader.endpoint()]);
+    let mut session = conn.create_session().await.unwrap();
+
+    session
+        .execute("INSERT (:Person)", HashMap::new())
+        .await
+        .unwrap();
+    session
+        .execute_read("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+    let tx = session.begin_read_only_transaction().await.unwrap();
+    tx.commit().await.unwrap();
+
+    writer.backend().assert_executed("INSERT (:Person)");
+    writer.backend().assert_not_executed("MATCH (n) RETURN n");
+    reader.backend().assert_executed("MATCH (n) RETURN n");
+    assert!(began_transaction(reader.backend()));
+    assert!(!began_transaction(writer.backend()));
+    assert_eq!(session.reader_endpoint(), Some(reader.endpoint().as_str()));
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn routing_table_from_handshake_routes_statements() {
+    let writer = server().await;
+    let reader = server().await;
+    let router = TestServer::start_with(MockBackend::new(), |server| {
+        server.routing_table(writer.endpoint(), [reader.endpoint()])
+    })
+    .await
+    .unwrap();
+
+    let conn = ClusterConnection::route(&router.endpoint()).await.unwrap();
+    assert_eq!(
+        conn.topology(),
+        Topology {
+            writer: writer.endpoint(),
+            readers: vec![reader.endpoint()],
+        }
+    );
+
+    let mut session = conn.create_session().await.unwrap();
+    session
+        .execute("INSERT (:Person)", HashMap::new())
+        .await
+        .unwrap();
+    session
+        .execute("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+
+    writer.backend().assert_executed("INSERT (:Person)");
+    writer.backend().assert_not_executed("MATCH (n) RETURN n");
+    reader.backend().assert_executed("MATCH (n) RETURN n");
+    reader.backend().assert_not_executed("INSERT (:Person)");
+    session.close().await.unwrap();
+
+    // The writer advertises no routing table, so refreshing from it
+    // leaves the writer alone.
+    conn.refresh_routing().await.unwrap();
+    assert_eq!(conn.topology().readers, Vec::<String>::new());
+}
+
+#[tokio::test]
+async fn unreachable_reader_fails_over_to_next_reader() {
+    let writer = server().await;
+    let reader = server().await;
+    let conn = cluster(&writer, &[UNREACHABLE.to_owned(), reader.endpoint()]);
+
+    for _ in 0..2 {
+        let mut session = conn.create_session().await.unwrap();
+        let mut cursor = session
+            .execute_read("MATCH (n) RETURN n", HashMap::new())
+            .await
+            .unwrap();
+        assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+        assert_eq!(session.reader_endpoint(), Some(reader.endpoint().as_str()));
+    }
+    assert_eq!(reader.backend().execute_count("MATCH (n) RETURN n"), 2);
+}
+
+#[tokio::test]
+async fn reads_fall_back_to_writer_without_reachable_readers() {
+    let writer = server().await;
+    let conn = cluster(&writer, &[UNREACHABLE.to_owned()]);
+    let mut session = conn.create_session().await.unwrap();
+
+    session
+        .execute_read("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+
+    writer.backend().assert_executed("MATCH (n) RETURN n");
+    assert_eq!(session.reader_endpoint(), None);
+}
+
+#[tokio::test]
+async fn topology_change_moves_reads_to_new_reader() {
+    let writer = server().await;
+    let old = server().await;
+    let new = server().await;
+    let conn = cluster(&writer, &[old.endpoint()]);
+    let mut session = conn.create_session().await.unwrap();
+
+    session
+        .execute_read("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(session.reader_endpoint(), Some(old.endpoint().as_str()));
+
+    conn.set_topology(&Topology {
+        writer: writer.endpoint(),
+        readers: vec![new.endpoint()],
+    })
+    .unwrap();
+    session
+        .execute_read("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+
+    assert_eq!(session.reader_endpoint(), Some(new.endpoint().as_str()));
+    assert_eq!(old.backend().execute_count("MATCH (n) RETURN n"), 1);
+    assert_eq!(new.backend().execute_count("MATCH (n) RETURN n"), 1);
+    assert!(
+        old.backend()
+            .calls()
+            .iter()
+            .any(|call| matches!(call, MockCall::CloseSession { .. }))
+    );
+    assert_eq!(conn.topology().readers, vec![new.endpoint()]);
+}
+
+#[tokio::test]
+async fn dns_endpoints_resolve_to_addresses() {
+    let writer = server().await;
+    let reader = server().await;
+    let conn = ClusterConnection::discover(
+        &Topology {
+            writer: writer.endpoint(),
+            readers: vec![format!("gwp+dns://localhost:{}", reader.addr().port())],
+        },
+        Duration::from_secs(30),
+    )
+    .await
+    .unwrap();
+    assert!(conn.topology().readers.contains(&reader.endpoint()));
+
+    let mut session = conn.create_session().await.unwrap();
+    session
+        .execute_read("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+    reader.backend().assert_executed("MATCH (n) RETURN n");
+    writer.backend().assert_not_executed("MATCH (n) RETURN n");
+}
+
+const TRIP_ON_FIRST_FAILURE: CircuitBreaker = CircuitBreaker {
+    failure_threshold: 1,
+    open_duration: Duration::from_secs(60),
+};
+
+#[tokio::test]
+async fn failing_reader_opens_its_circuit() {
+    let writer = server().await;
+    let reader = server().await;
+    let conn = cluster(&writer, &[UNREACHABLE.to_owned(), reader.endpoint()])
+        .with_circuit_breaker(TRIP_ON_FIRST_FAILURE);
+
+    for _ in 0..3 {
+        let mut session = conn.create_session().await.unwrap();
+        session
+            .execute_read("MATCH (n) RETURN n", HashMap::new())
+            .await
+            .unwrap();
+    }
+
+    let health = conn.endpoint_health();
+    assert_eq!(health[0].state, CircuitState::Closed);
+    assert!(health[0].latency.is_some());
+    assert_eq!(health[1].endpoint, UNREACHABLE);
+    assert_eq!(health[1].state, CircuitState::Open);
+    assert!(health[1].failure_rate > 0.0);
+    assert_eq!(health[2].state, CircuitState::Closed);
+    assert_eq!(reader.backend().execute_count("MATCH (n) RETURN n"), 3);
+}
+
+#[tokio::test]
+async fn open_writer_circuit_fails_fast() {
+    let conn = ClusterConnection::new(&Topology {
+        writer: UNREACHABLE.to_owned(),
+        readers: Vec::new(),
+    })
+    .unwrap()
+    .with_circuit_breaker(TRIP_ON_FIRST_FAILURE);
+
+    assert!(conn.create_session().await.is_err());
+    let Err(err) = conn.create_session().await else {
+        panic!("expected an open circuit");
+    };
+    assert!(err.to_string().contains("circuit open"), "{err}");
+}
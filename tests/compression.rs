@@ -0,0 +1,86 @@
+//! Compression tests: result streams are compressed when the server
+//! offers an encoding and the statement asks for it.
+
+#![cfg(feature = "gzip")]
+
+use std::collections::HashMap;
+
+use gwp::proto;
+use gwp::proto::gql_service_client::GqlServiceClient;
+use gwp::server::mock_backend::MockBackend;
+use gwp::testing::TestServer;
+use gwp::types::Value;
+
+const STATEMENT: &str = "MATCH (d:Document) RETURN d.body";
+
+async fn start() -> TestServer<MockBackend> {
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.compression(proto::Compression::Gzip)
+    })
+    .await
+    .unwrap();
+    let body = Value::from("lorem ipsum ".repeat(1_000));
+    server
+        .backend()
+        .on_statement(STATEMENT)
+        .respond_rows(["body"], vec![vec![body]; 10]);
+    server
+}
+
+/// The `grpc-encoding` of the result stream of [`STATEMENT`] run with
+/// `compression`.
+async fn result_encoding(
+    server: &TestServer<MockBackend>,
+    compression: Option<proto::Compression>,
+) -> Option<String> {
+    let session = server.session().await.unwrap();
+    let mut client = GqlServiceClient::new(server.connection().channel().clone());
+    if let Some(encoding) = compression.and_then(proto::Compression::encoding) {
+        client = client.accept_compressed(encoding);
+    }
+    let response = client
+        .execute(proto::ExecuteRequest {
+            session_id: session.session_id().to_owned(),
+            statement: STATEMENT.to_owned(),
+            parameters: HashMap::new(),
+            transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: compression.map(i32::from),
+            max_rows: None,
+            max_bytes: None,
+        })
+        .await
+        .unwrap();
+    response
+        .metadata()
+        .get("grpc-encoding")
+        .map(|value| value.to_str().unwrap().to_owned())
+}
+
+#[tokio::test]
+async fn result_streams_are_compressed_on_request() {
+    let server = start().await;
+
+    assert_eq!(
+        result_encoding(&server, Some(proto::Compression::Gzip))
+            .await
+            .as_deref(),
+        Some("gzip")
+    );
+    assert_eq!(result_encoding(&server, None).await, None);
+}
+
+#[tokio::test]
+async fn sessions_read_compressed_results() {
+    let server = start().await;
+    let conn = server
+        .connection()
+        .with_compression(proto::Compression::Gzip);
+
+    let mut session = conn.create_session().await.unwrap();
+    let mut cursor = session.execute_simple(STATEMENT).await.unwrap();
+    let rows = cursor.collect_rows().await.unwrap();
+    assert_eq!(rows.len(), 10);
+    assert_eq!(rows[0][0], Value::from("lorem ipsum ".repeat(1_000)));
+}
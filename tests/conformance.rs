@@ -0,0 +1,57 @@
+//! Integration tests for the backend conformance suite.
+
+use gwp::conformance::{self, CheckOutcome, ConformanceConfig};
+use gwp::server::mock_backend::MockBackend;
+use gwp::status;
+
+fn mock_config() -> ConformanceConfig {
+    ConformanceConfig {
+        invalid_statement: "ERROR".to_owned(),
+        ..ConformanceConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn mock_backend_conforms() {
+    let report = conformance::run_with_config(MockBackend::new(), &mock_config()).await;
+    report.assert_passed();
+
+    assert_eq!(
+        report.outcome("optional/catalog"),
+        Some(&CheckOutcome::Passed)
+    );
+    assert_eq!(
+        report.outcome("optional/admin"),
+        Some(&CheckOutcome::Passed)
+    );
+    assert_eq!(
+        report.outcome("optional/search"),
+        Some(&CheckOutcome::Passed)
+    );
+}
+
+#[tokio::test]
+async fn accepted_invalid_statement_fails() {
+    // The mock treats unknown statements as DDL, so the default invalid
+    // statement is not rejected.
+    let report = conformance::run(MockBackend::new()).await;
+
+    assert!(!report.is_success());
+    let failed: Vec<_> = report.failures().map(|r| r.name).collect();
+    assert_eq!(failed, vec!["errors/invalid_statement"]);
+}
+
+#[tokio::test]
+async fn failing_query_is_reported() {
+    let mock = MockBackend::new();
+    mock.on_statement("MATCH (n) RETURN n")
+        .respond_error(status::DATA_EXCEPTION, "broken");
+
+    let report = conformance::run_with_config(mock, &mock_config()).await;
+
+    assert!(matches!(
+        report.outcome("streaming/query_frames"),
+        Some(CheckOutcome::Failed(_))
+    ));
+    assert!(report.to_string().contains("FAIL  streaming/query_frames"));
+}
@@ -0,0 +1,139 @@
+//! Integration tests for server-side default deadlines.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use gwp::error::GqlError;
+use gwp::proto;
+use gwp::proto::gql_service_client::GqlServiceClient;
+use gwp::server::DefaultDeadlines;
+use gwp::server::mock_backend::{Latency, MockBackend, MockOperation};
+use gwp::status;
+use gwp::testing::TestServer;
+
+const SLOW: Duration = Duration::from_millis(300);
+const DEADLINE: Duration = Duration::from_millis(50);
+
+/// Start a server whose execute and commit take [`SLOW`] against a
+/// default deadline of [`DEADLINE`].
+async fn slow_server() -> TestServer<MockBackend> {
+    let backend = MockBackend::new();
+    backend.set_latency(MockOperation::ExecuteFirstFrame, Latency::fixed(SLOW));
+    backend.set_latency(MockOperation::Commit, Latency::fixed(SLOW));
+
+    TestServer::start_with(backend, |builder| {
+        builder.default_deadlines(DefaultDeadlines {
+            execute_first_frame: Some(DEADLINE),
+            commit: Some(DEADLINE),
+            ..DefaultDeadlines::default()
+        })
+    })
+    .await
+    .unwrap()
+}
+
+fn is_deadline_exceeded(result: Result<impl Sized, GqlError>) -> bool {
+    matches!(result, Err(GqlError::Grpc(status)) if status.code() == tonic::Code::DeadlineExceeded)
+}
+
+#[tokio::test]
+async fn default_deadline_applies_without_client_deadline() {
+    let server = slow_server().await;
+    let conn = server.connection();
+    let mut session = conn.create_session().await.unwrap();
+
+    let result = session.execute_simple("MATCH (n) RETURN n").await;
+    assert!(is_deadline_exceeded(result));
+
+    let tx = session.begin_transaction().await.unwrap();
+    assert!(is_deadline_exceeded(tx.commit().await));
+}
+
+#[tokio::test]
+async fn client_deadline_overrides_default() {
+    let server = slow_server().await;
+    let conn = server.connection();
+    let session = conn.create_session().await.unwrap();
+    let mut client = GqlServiceClient::new(conn.channel().clone());
+
+    let mut request = tonic::Request::new(proto::ExecuteRequest {
+        session_id: session.session_id().to_owned(),
+        statement: "MATCH (n) RETURN n".to_owned(),
+        parameters: HashMap::new(),
+        transaction_id: None,
+        fetch_size: None,
+        timeout_ms: None,
+        compression: None,
+        max_rows: None,
+        max_bytes: None,
+    });
+    request.set_timeout(Duration::from_secs(5));
+    let mut stream = client.execute(request).await.unwrap().into_inner();
+    assert!(stream.message().await.unwrap().is_some());
+}
+
+/// The status a statement's result ended with.
+async fn result_status(session: &mut gwp::client::GqlSession, statement: &str) -> String {
+    let mut cursor = session.execute_simple(statement).await.unwrap();
+    let summary = tokio::time::timeout(Duration::from_secs(5), cursor.summary())
+        .await
+        .unwrap()
+        .unwrap()
+        .cloned()
+        .unwrap();
+    summary.status.unwrap().code
+}
+
+#[tokio::test]
+async fn statement_timeout_ends_slow_results() {
+    let backend = MockBackend::new();
+    backend.set_latency(
+        MockOperation::Batch,
+        Latency::fixed(Duration::from_secs(30)),
+    );
+    let server = TestServer::start_with(backend, |builder| builder.statement_timeout(DEADLINE))
+        .await
+        .unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    let code = result_status(&mut session, "MATCH (n) RETURN n").await;
+    assert_eq!(code, status::STATEMENT_TIMEOUT);
+    assert_eq!(
+        result_status(&mut session, "INSERT (n)").await,
+        status::SUCCESS
+    );
+
+    server
+        .backend()
+        .set_latency(MockOperation::ExecuteFirstFrame, Latency::fixed(SLOW));
+    let code = result_status(&mut session, "INSERT (n)").await;
+    assert_eq!(code, status::STATEMENT_TIMEOUT);
+}
+
+#[tokio::test]
+async fn clients_can_only_shorten_the_statement_timeout() {
+    let backend = MockBackend::new();
+    backend.set_latency(MockOperation::ExecuteFirstFrame, Latency::fixed(SLOW));
+    let server = TestServer::start(backend).await.unwrap();
+    let mut session = server.connection().create_session().await.unwrap();
+
+    session.set_statement_timeout(Some(DEADLINE));
+    let code = result_status(&mut session, "MATCH (n) RETURN n").await;
+    assert_eq!(code, status::STATEMENT_TIMEOUT);
+    session.set_statement_timeout(None);
+    let code = result_status(&mut session, "MATCH (n) RETURN n").await;
+    assert_eq!(code, status::SUCCESS);
+
+    let server = TestServer::start_with(MockBackend::new(), |builder| {
+        builder.statement_timeout(DEADLINE)
+    })
+    .await
+    .unwrap();
+    server
+        .backend()
+        .set_latency(MockOperation::ExecuteFirstFrame, Latency::fixed(SLOW));
+    let mut session = server.connection().create_session().await.unwrap();
+    session.set_statement_timeout(Some(Duration::from_secs(60)));
+    let code = result_status(&mut session, "MATCH (n) RETURN n").await;
+    assert_eq!(code, status::STATEMENT_TIMEOUT);
+}
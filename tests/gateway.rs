@@ -0,0 +1,168 @@
+//! Integration tests for the HTTP/JSON gateway.
+
+#![cfg(feature = "gateway")]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use gwp::gateway::Gateway;
+use gwp::server::mock_backend::MockBackend;
+use gwp::testing::TestServer;
+
+async fn gateway() -> (TestServer<MockBackend>, Router) {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let router = Gateway::new(&server.connection()).router();
+    (server, router)
+}
+
+async fn send(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, String) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+async fn create_session(router: &Router) -> String {
+    let (status, body) = send(router, Method::POST, "/sessions", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    body["session_id"].as_str().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn execute_streams_ndjson_frames() {
+    let (_server, router) = gateway().await;
+    let session_id = create_session(&router).await;
+
+    let (status, body) = send(
+        &router,
+        Method::POST,
+        "/execute",
+        Some(json!({
+            "session_id": session_id,
+            "statement": "MATCH (n) RETURN n.name, n.age",
+            "parameters": {"min_age": 18},
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let frames: Vec<Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0]["header"]["columns"], json!(["name", "age"]));
+    assert_eq!(frames[1]["rows"], json!([["Alice", 30], ["Bob", 25]]));
+    assert_eq!(frames[2]["summary"]["code"], "00000");
+    assert!(frames[2]["summary"]["timings"]["planning_micros"].is_u64());
+
+    let (status, _) = send(
+        &router,
+        Method::DELETE,
+        &format!("/sessions/{session_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn unknown_session_maps_to_not_found() {
+    let (_server, router) = gateway().await;
+
+    let (status, body) = send(
+        &router,
+        Method::POST,
+        "/execute",
+        Some(json!({"session_id": "nope", "statement": "MATCH (n) RETURN n"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["error"]["code"], "NotFound");
+}
+
+#[tokio::test]
+async fn transaction_commit_over_http() {
+    let (server, router) = gateway().await;
+    let session_id = create_session(&router).await;
+
+    let (status, body) = send(
+        &router,
+        Method::POST,
+        "/transactions",
+        Some(json!({"session_id": session_id})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    let transaction_id = body["transaction_id"].as_str().unwrap();
+
+    let (status, body) = send(
+        &router,
+        Method::POST,
+        "/execute",
+        Some(json!({
+            "session_id": session_id,
+            "statement": "INSERT (:Person {name: 'Carol'})",
+            "transaction_id": transaction_id,
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("\"rows_affected\":3"));
+
+    let (status, _) = send(
+        &router,
+        Method::POST,
+        &format!("/transactions/{transaction_id}/commit"),
+        Some(json!({"session_id": session_id})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    server.backend().assert_transactions(1, 0);
+}
+
+#[tokio::test]
+async fn catalog_endpoints() {
+    let (_server, router) = gateway().await;
+
+    let (status, body) = send(&router, Method::GET, "/schemas", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["schemas"][0]["name"], "default");
+
+    let (status, body) = send(&router, Method::GET, "/schemas/default/graphs", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["graphs"].as_array().unwrap().len(), 2);
+
+    let (status, body) = send(
+        &router,
+        Method::GET,
+        "/schemas/default/graphs/default",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["node_count"], 100);
+}
@@ -2,68 +2,28 @@
 //! connect with tonic clients, and verify the full round-trip.
 
 use std::collections::HashMap;
-use std::net::SocketAddr;
 
 use gwp::proto;
 use gwp::proto::gql_service_client::GqlServiceClient;
 use gwp::proto::session_service_client::SessionServiceClient;
-use gwp::server::mock_backend::MockBackend;
-use gwp::server::{GqlServiceImpl, SessionManager, SessionServiceImpl, TransactionManager};
+use gwp::server::CLIENT_CERT_SUBJECT;
+use gwp::server::mock_backend::{MockBackend, MockCall};
 use gwp::status;
-
-/// Start a server on a random port and return the address.
-async fn start_server() -> SocketAddr {
-    let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    let addr = listener.local_addr().unwrap();
-
-    tokio::spawn(async move {
-        let backend = std::sync::Arc::new(MockBackend::new());
-        let sessions = SessionManager::new();
-        let transactions = TransactionManager::new();
-
-        let session_svc = SessionServiceImpl::new(
-            std::sync::Arc::clone(&backend),
-            sessions.clone(),
-            transactions.clone(),
-            None,
-        );
-        let gql_svc = GqlServiceImpl::new(backend, sessions, transactions);
-
-        let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
-
-        tonic::transport::Server::builder()
-            .add_service(proto::session_service_server::SessionServiceServer::new(
-                session_svc,
-            ))
-            .add_service(proto::gql_service_server::GqlServiceServer::new(gql_svc))
-            .serve_with_incoming(incoming)
-            .await
-            .unwrap();
-    });
-
-    // Give the server a moment to start
-    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-    addr
-}
+use gwp::testing::TestServer;
+use gwp::types::Value;
 
 /// Helper to connect clients to a running server.
-async fn connect(
-    addr: SocketAddr,
+fn connect(
+    server: &TestServer<MockBackend>,
 ) -> (
     SessionServiceClient<tonic::transport::Channel>,
     GqlServiceClient<tonic::transport::Channel>,
 ) {
-    let channel = tonic::transport::Channel::from_shared(format!("http://{addr}"))
-        .unwrap()
-        .connect()
-        .await
-        .unwrap();
-
-    let session_client = SessionServiceClient::new(channel.clone());
-    let gql_client = GqlServiceClient::new(channel);
-
-    (session_client, gql_client)
+    let channel = server.connection().channel().clone();
+    (
+        SessionServiceClient::new(channel.clone()),
+        GqlServiceClient::new(channel),
+    )
 }
 
 /// Perform a handshake and return the `session_id`.
@@ -73,6 +33,11 @@ async fn handshake(client: &mut SessionServiceClient<tonic::transport::Channel>)
             protocol_version: 1,
             credentials: None,
             client_info: HashMap::new(),
+            resumption_token: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
+            database: None,
         })
         .await
         .unwrap()
@@ -90,8 +55,8 @@ async fn handshake(client: &mut SessionServiceClient<tonic::transport::Channel>)
 
 #[tokio::test]
 async fn handshake_and_close() {
-    let addr = start_server().await;
-    let (mut session_client, _) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, _) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -115,8 +80,8 @@ async fn handshake_and_close() {
 
 #[tokio::test]
 async fn ping() {
-    let addr = start_server().await;
-    let (mut session_client, _) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, _) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -133,8 +98,8 @@ async fn ping() {
 
 #[tokio::test]
 async fn configure_and_reset() {
-    let addr = start_server().await;
-    let (mut session_client, _) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, _) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -161,8 +126,8 @@ async fn configure_and_reset() {
 
 #[tokio::test]
 async fn execute_query_streaming() {
-    let addr = start_server().await;
-    let (mut session_client, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -172,6 +137,11 @@ async fn execute_query_streaming() {
             statement: "MATCH (p:Person) RETURN p.name, p.age".to_owned(),
             parameters: HashMap::new(),
             transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
         })
         .await
         .unwrap()
@@ -209,10 +179,56 @@ async fn execute_query_streaming() {
     assert!(stream.message().await.unwrap().is_none());
 }
 
+#[tokio::test]
+async fn fetch_size_splits_row_batches() {
+    let backend = MockBackend::new();
+    let rows: Vec<Vec<Value>> = (0..10).map(|i| vec![Value::Integer(i)]).collect();
+    backend
+        .on_statement("MATCH (n) RETURN n")
+        .respond_rows(["n"], rows);
+    let server = TestServer::start_with(backend, |builder| builder.max_in_flight_frames(2))
+        .await
+        .unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
+    let session_id = handshake(&mut session_client).await;
+
+    let mut stream = gql_client
+        .execute(proto::ExecuteRequest {
+            session_id,
+            statement: "MATCH (n) RETURN n".to_owned(),
+            parameters: HashMap::new(),
+            transaction_id: None,
+            fetch_size: Some(4),
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let mut batch_sizes = Vec::new();
+    let mut values = Vec::new();
+    while let Some(msg) = stream.message().await.unwrap() {
+        if let Some(proto::execute_response::Frame::RowBatch(batch)) = msg.frame {
+            batch_sizes.push(batch.rows.len());
+            values.extend(
+                batch
+                    .rows
+                    .into_iter()
+                    .map(|row| Value::from(row.values[0].clone())),
+            );
+        }
+    }
+    assert_eq!(batch_sizes, [4, 4, 2]);
+    assert_eq!(values, (0..10).map(Value::Integer).collect::<Vec<_>>());
+}
+
 #[tokio::test]
 async fn execute_ddl() {
-    let addr = start_server().await;
-    let (mut session_client, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -222,6 +238,11 @@ async fn execute_ddl() {
             statement: "CREATE GRAPH my_graph".to_owned(),
             parameters: HashMap::new(),
             transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
         })
         .await
         .unwrap()
@@ -249,8 +270,8 @@ async fn execute_ddl() {
 
 #[tokio::test]
 async fn execute_error() {
-    let addr = start_server().await;
-    let (mut session_client, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -260,6 +281,11 @@ async fn execute_error() {
             statement: "ERROR this should fail".to_owned(),
             parameters: HashMap::new(),
             transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
         })
         .await
         .unwrap()
@@ -278,8 +304,8 @@ async fn execute_error() {
 
 #[tokio::test]
 async fn transaction_lifecycle() {
-    let addr = start_server().await;
-    let (mut session_client, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -288,6 +314,8 @@ async fn transaction_lifecycle() {
         .begin_transaction(proto::BeginRequest {
             session_id: session_id.clone(),
             mode: proto::TransactionMode::ReadWrite.into(),
+            isolation_level: proto::IsolationLevel::Default.into(),
+            timeout_ms: None,
         })
         .await
         .unwrap()
@@ -307,6 +335,11 @@ async fn transaction_lifecycle() {
             statement: "INSERT (:Person {name: 'Carol'})".to_owned(),
             parameters: HashMap::new(),
             transaction_id: Some(tx_id.clone()),
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
         })
         .await
         .unwrap()
@@ -332,8 +365,8 @@ async fn transaction_lifecycle() {
 
 #[tokio::test]
 async fn transaction_rollback() {
-    let addr = start_server().await;
-    let (mut session_client, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -342,6 +375,8 @@ async fn transaction_rollback() {
         .begin_transaction(proto::BeginRequest {
             session_id: session_id.clone(),
             mode: proto::TransactionMode::ReadWrite.into(),
+            isolation_level: proto::IsolationLevel::Default.into(),
+            timeout_ms: None,
         })
         .await
         .unwrap()
@@ -366,8 +401,8 @@ async fn transaction_rollback() {
 
 #[tokio::test]
 async fn double_begin_returns_gqlstatus_error() {
-    let addr = start_server().await;
-    let (mut session_client, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
 
     let session_id = handshake(&mut session_client).await;
 
@@ -376,6 +411,8 @@ async fn double_begin_returns_gqlstatus_error() {
         .begin_transaction(proto::BeginRequest {
             session_id: session_id.clone(),
             mode: proto::TransactionMode::ReadWrite.into(),
+            isolation_level: proto::IsolationLevel::Default.into(),
+            timeout_ms: None,
         })
         .await
         .unwrap();
@@ -385,6 +422,8 @@ async fn double_begin_returns_gqlstatus_error() {
         .begin_transaction(proto::BeginRequest {
             session_id: session_id.clone(),
             mode: proto::TransactionMode::ReadOnly.into(),
+            isolation_level: proto::IsolationLevel::Default.into(),
+            timeout_ms: None,
         })
         .await
         .unwrap()
@@ -396,8 +435,8 @@ async fn double_begin_returns_gqlstatus_error() {
 
 #[tokio::test]
 async fn invalid_session_returns_grpc_not_found() {
-    let addr = start_server().await;
-    let (_, mut gql_client) = connect(addr).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (_, mut gql_client) = connect(&server);
 
     let result = gql_client
         .execute(proto::ExecuteRequest {
@@ -405,9 +444,183 @@ async fn invalid_session_returns_grpc_not_found() {
             statement: "MATCH (n) RETURN n".to_owned(),
             parameters: HashMap::new(),
             transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
         })
         .await;
 
     assert!(result.is_err());
     assert_eq!(result.unwrap_err().code(), tonic::Code::NotFound);
 }
+
+#[tokio::test]
+async fn test_server_shutdown_stops_serving() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let endpoint = server.endpoint();
+    server.shutdown().await.unwrap();
+
+    let result = gwp::client::GqlConnection::connect(&endpoint).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn dictionary_encoded_batches_intern_repeated_strings() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
+    let session_id = session_client
+        .handshake(proto::HandshakeRequest {
+            protocol_version: 1,
+            credentials: None,
+            client_info: HashMap::new(),
+            resumption_token: None,
+            row_batch_checksums: false,
+            dictionary_encoding: true,
+            columnar_batches: false,
+            database: None,
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .session_id;
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n.city")
+        .respond_rows(
+            ["city"],
+            vec![vec![Value::from("Utrecht")], vec![Value::from("Utrecht")]],
+        );
+
+    let mut stream = gql_client
+        .execute(proto::ExecuteRequest {
+            session_id,
+            statement: "MATCH (n) RETURN n.city".to_owned(),
+            parameters: HashMap::new(),
+            transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    stream.message().await.unwrap().unwrap();
+    let batch = match stream.message().await.unwrap().unwrap().frame {
+        Some(proto::execute_response::Frame::RowBatch(b)) => b,
+        other => panic!("expected row batch, got {other:?}"),
+    };
+    assert_eq!(batch.dictionary, ["Utrecht"]);
+    assert!(
+        batch
+            .rows
+            .iter()
+            .all(|row| row.values[0].kind == Some(proto::value::Kind::DictionaryRef(0)))
+    );
+}
+
+#[tokio::test]
+async fn columnar_batches_send_rows_by_column() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, mut gql_client) = connect(&server);
+    let response = session_client
+        .handshake(proto::HandshakeRequest {
+            protocol_version: 1,
+            credentials: None,
+            client_info: HashMap::new(),
+            resumption_token: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: true,
+            database: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let features = response.server_info.unwrap().features;
+    assert!(features.iter().any(|f| f == gwp::columnar::FEATURE));
+    server
+        .backend()
+        .on_statement("MATCH (n) RETURN n.age")
+        .respond_rows(
+            ["age"],
+            vec![
+                vec![Value::Integer(42)],
+                vec![Value::Null],
+                vec![Value::Integer(7)],
+            ],
+        );
+
+    let mut stream = gql_client
+        .execute(proto::ExecuteRequest {
+            session_id: response.session_id,
+            statement: "MATCH (n) RETURN n.age".to_owned(),
+            parameters: HashMap::new(),
+            transaction_id: None,
+            fetch_size: None,
+            timeout_ms: None,
+            compression: None,
+            max_rows: None,
+            max_bytes: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    stream.message().await.unwrap().unwrap();
+    let batch = match stream.message().await.unwrap().unwrap().frame {
+        Some(proto::execute_response::Frame::RowBatch(b)) => b,
+        other => panic!("expected row batch, got {other:?}"),
+    };
+    assert!(batch.rows.is_empty());
+    let columns = batch.columns.unwrap();
+    assert_eq!(columns.row_count, 3);
+    assert_eq!(columns.columns[0].nulls, [0b010]);
+    assert_eq!(
+        columns.columns[0].values,
+        Some(proto::batch_column::Values::Integers(
+            proto::IntegerColumn {
+                values: vec![42, 7]
+            }
+        ))
+    );
+}
+
+#[tokio::test]
+async fn clients_cannot_claim_a_certificate_subject() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let (mut session_client, _) = connect(&server);
+
+    session_client
+        .handshake(proto::HandshakeRequest {
+            protocol_version: 1,
+            credentials: None,
+            client_info: HashMap::from([
+                (CLIENT_CERT_SUBJECT.to_owned(), "CN=admin".to_owned()),
+                ("app".to_owned(), "reports".to_owned()),
+            ]),
+            resumption_token: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
+            database: None,
+        })
+        .await
+        .unwrap();
+
+    let client_info = server
+        .backend()
+        .calls()
+        .into_iter()
+        .find_map(|call| match call {
+            MockCall::CreateSession { client_info, .. } => Some(client_info),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        client_info,
+        HashMap::from([("app".to_owned(), "reports".to_owned())])
+    );
+}
@@ -0,0 +1,432 @@
+//! Integration tests for scripting and call recording on `MockBackend`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use gwp::server::mock_backend::{Latency, MockBackend, MockCall, MockOperation};
+use gwp::testing::TestServer;
+use gwp::trace_context::TraceContext;
+use gwp::types::Value;
+
+// =========================================================================
+// Scripted response tests
+// =========================================================================
+
+#[tokio::test]
+async fn scripted_rows() {
+    let mock = Arc::new(MockBackend::new());
+    mock.on_statement("MATCH (c:City) RETURN c.name, c.population")
+        .respond_rows(
+            ["name", "population"],
+            vec![
+                vec![Value::from("Berlin"), Value::from(3_700_000_i64)],
+                vec![Value::from("Paris"), Value::from(2_100_000_i64)],
+                vec![Value::from("Rome"), Value::from(2_800_000_i64)],
+            ],
+        );
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session
+        .execute_simple("MATCH (c:City) RETURN c.name, c.population")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        cursor.column_names().await.unwrap(),
+        vec!["name", "population"]
+    );
+    let rows = cursor.collect_rows().await.unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1][0], Value::from("Paris"));
+}
+
+#[tokio::test]
+async fn scripted_error() {
+    let mock = Arc::new(MockBackend::new());
+    mock.on_statement("MATCH (n) RETURN n")
+        .respond_error(gwp::status::INVALID_SYNTAX, "scripted failure");
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let summary = cursor.summary().await.unwrap().unwrap();
+    let status = summary.status.as_ref().unwrap();
+    assert_eq!(status.code, gwp::status::INVALID_SYNTAX);
+    assert_eq!(status.message, "scripted failure");
+}
+
+#[tokio::test]
+async fn scripted_prefix_and_precedence() {
+    let mock = Arc::new(MockBackend::new());
+    mock.on_statement_prefix("insert").respond_rows_affected(7);
+    mock.on_statement("INSERT (:Person {name: 'Dan'})")
+        .respond_rows_affected(1);
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session
+        .execute_simple("INSERT (:City {name: 'Oslo'})")
+        .await
+        .unwrap();
+    let _ = cursor.collect_rows().await.unwrap();
+    assert_eq!(cursor.rows_affected().await.unwrap(), 7);
+
+    let mut cursor = session
+        .execute_simple("INSERT (:Person {name: 'Dan'})")
+        .await
+        .unwrap();
+    let _ = cursor.collect_rows().await.unwrap();
+    assert_eq!(cursor.rows_affected().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn unscripted_statements_use_defaults() {
+    let mock = Arc::new(MockBackend::new());
+    mock.on_statement("MATCH (c:City) RETURN c")
+        .respond_rows(["c"], Vec::new());
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN p.name, p.age")
+        .await
+        .unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+}
+
+// =========================================================================
+// Call recording tests
+// =========================================================================
+
+#[tokio::test]
+async fn records_executed_statements() {
+    let mock = Arc::new(MockBackend::new());
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut params = HashMap::new();
+    params.insert("name".to_owned(), Value::from("Alice"));
+    let mut cursor = session
+        .execute("MATCH (p:Person {name: $name}) RETURN p", params)
+        .await
+        .unwrap();
+    let _ = cursor.collect_rows().await.unwrap();
+
+    mock.assert_executed("MATCH (p:Person {name: $name}) RETURN p");
+    mock.assert_not_executed("MATCH (n) RETURN n");
+    assert_eq!(
+        mock.execute_count("MATCH (p:Person {name: $name}) RETURN p"),
+        1
+    );
+
+    let execute = mock
+        .calls()
+        .into_iter()
+        .find(|c| matches!(c, MockCall::Execute { .. }))
+        .unwrap();
+    let MockCall::Execute {
+        parameters,
+        transaction,
+        ..
+    } = execute
+    else {
+        unreachable!();
+    };
+    assert_eq!(parameters.get("name"), Some(&Value::from("Alice")));
+    assert!(transaction.is_none());
+}
+
+#[tokio::test]
+async fn records_transactions() {
+    let mock = Arc::new(MockBackend::new());
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut tx = session.begin_transaction().await.unwrap();
+    let mut cursor = tx
+        .execute("INSERT (:Person {name: 'Carol'})", HashMap::new())
+        .await
+        .unwrap();
+    let _ = cursor.collect_rows().await.unwrap();
+    tx.commit().await.unwrap();
+
+    let tx = session.begin_transaction().await.unwrap();
+    tx.rollback().await.unwrap();
+
+    mock.assert_transactions(1, 1);
+    assert!(mock.calls().iter().any(|c| matches!(
+        c,
+        MockCall::Execute {
+            transaction: Some(_),
+            ..
+        }
+    )));
+
+    mock.clear_calls();
+    assert!(mock.calls().is_empty());
+}
+
+#[tokio::test]
+async fn records_the_trace_context_of_executions() {
+    let mock = Arc::new(MockBackend::new());
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+    let upstream =
+        TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+    session.set_trace_context(upstream);
+
+    let mut cursor = session
+        .execute("MATCH (n) RETURN n", HashMap::new())
+        .await
+        .unwrap();
+    let _ = cursor.collect_rows().await.unwrap();
+
+    let context = mock
+        .calls()
+        .into_iter()
+        .find_map(|c| match c {
+            MockCall::Execute { context, .. } => Some(context),
+            _ => None,
+        })
+        .unwrap();
+    let trace = context.trace.unwrap();
+    assert_eq!(trace.trace_id(), upstream.trace_id());
+    assert_ne!(trace.span_id(), upstream.span_id());
+    assert!(context.query_id.unwrap().starts_with(session.session_id()));
+}
+
+// =========================================================================
+// Fault injection tests
+// =========================================================================
+
+/// Execute a statement and return the summary status code.
+async fn execute_status(session: &mut gwp::client::GqlSession, statement: &str) -> String {
+    let mut cursor = session.execute_simple(statement).await.unwrap();
+    let _ = cursor.collect_rows().await;
+    let summary = cursor.summary().await.unwrap().unwrap();
+    summary.status.as_ref().unwrap().code.clone()
+}
+
+#[tokio::test]
+async fn fail_nth_execute() {
+    let mock = Arc::new(MockBackend::new());
+    mock.fail_nth_execute(2, gwp::status::DATA_EXCEPTION, "injected");
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let first = execute_status(&mut session, "MATCH (n) RETURN n").await;
+    let second = execute_status(&mut session, "MATCH (n) RETURN n").await;
+    let third = execute_status(&mut session, "MATCH (n) RETURN n").await;
+
+    assert_eq!(first, gwp::status::SUCCESS);
+    assert_eq!(second, gwp::status::DATA_EXCEPTION);
+    assert_eq!(third, gwp::status::SUCCESS);
+}
+
+#[tokio::test]
+async fn fail_stream_mid_result() {
+    let mock = Arc::new(MockBackend::new());
+    mock.fail_streams_after_batches(1, gwp::status::DATA_EXCEPTION, "stream broke");
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let mut cursor = session
+        .execute_simple("MATCH (p:Person) RETURN p.name, p.age")
+        .await
+        .unwrap();
+
+    // The header and first batch arrive before the failure.
+    assert_eq!(cursor.column_names().await.unwrap(), vec!["name", "age"]);
+    let _ = cursor.collect_rows().await;
+    let summary = cursor.summary().await.unwrap().unwrap();
+    let status = summary.status.as_ref().unwrap();
+    assert_eq!(status.code, gwp::status::DATA_EXCEPTION);
+    assert_eq!(status.message, "stream broke");
+
+    mock.clear_faults();
+    let code = execute_status(&mut session, "MATCH (n) RETURN n").await;
+    assert_eq!(code, gwp::status::SUCCESS);
+}
+
+#[tokio::test]
+async fn fail_commit_with_serialization_error() {
+    let mock = Arc::new(MockBackend::new());
+    mock.fail_next_commits(1);
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let tx = session.begin_transaction().await.unwrap();
+    let err = tx.commit().await.unwrap_err();
+    assert_eq!(
+        err.gql_status().map(|s| s.code.as_str()),
+        Some(gwp::status::SERIALIZATION_FAILURE)
+    );
+
+    // Only the armed number of commits fail.
+    let mut session = server.session().await.unwrap();
+    let tx = session.begin_transaction().await.unwrap();
+    tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+async fn drop_sessions_is_deterministic() {
+    async fn run(seed: u64) -> Vec<bool> {
+        let mock = Arc::new(MockBackend::new());
+        mock.drop_sessions_randomly(0.3, seed);
+        let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+        let conn = server.connection();
+
+        let mut dropped = Vec::new();
+        for _ in 0..8 {
+            let mut session = conn.create_session().await.unwrap();
+            let code = execute_status(&mut session, "MATCH (n) RETURN n").await;
+            dropped.push(code != gwp::status::SUCCESS);
+            assert_eq!(
+                mock.is_session_dropped(session.session_id()),
+                code != gwp::status::SUCCESS
+            );
+        }
+        dropped
+    }
+
+    let first = run(42).await;
+    assert_eq!(first, run(42).await);
+    assert!(first.iter().any(|d| *d));
+    assert!(first.iter().any(|d| !*d));
+}
+
+// =========================================================================
+// Latency tests
+// =========================================================================
+
+#[tokio::test]
+async fn handshake_and_commit_latency() {
+    let mock = Arc::new(MockBackend::new());
+    mock.set_latency(
+        MockOperation::Handshake,
+        Latency::fixed(Duration::from_millis(100)),
+    );
+    mock.set_latency(
+        MockOperation::Commit,
+        Latency::fixed(Duration::from_millis(150)),
+    );
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let conn = server.connection();
+
+    let start = Instant::now();
+    let mut session = conn.create_session().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    let tx = session.begin_transaction().await.unwrap();
+    let start = Instant::now();
+    tx.commit().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn batch_latency_delays_rows() {
+    let mock = Arc::new(MockBackend::new());
+    mock.on_statement("MATCH (n) RETURN n")
+        .respond_rows(["n"], vec![vec![Value::from(1_i64)]]);
+    mock.set_latency(
+        MockOperation::Batch,
+        Latency::fixed(Duration::from_millis(150)),
+    );
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let start = Instant::now();
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    let rows = cursor.collect_rows().await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn execute_latency_trips_client_timeout() {
+    let mock = Arc::new(MockBackend::new());
+    mock.set_latency(
+        MockOperation::ExecuteFirstFrame,
+        Latency::fixed(Duration::from_millis(500)),
+    );
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let result = tokio::time::timeout(
+        Duration::from_millis(100),
+        session.execute_simple("MATCH (n) RETURN n"),
+    )
+    .await;
+    assert!(result.is_err());
+
+    mock.clear_latency();
+    let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+    assert_eq!(cursor.collect_rows().await.unwrap().len(), 2);
+}
+
+// =========================================================================
+// Echo mode tests
+// =========================================================================
+
+#[tokio::test]
+async fn echo_returns_statement_and_parameters() {
+    let mock = Arc::new(MockBackend::new());
+    mock.on_statement("RETURN $b, $a").respond_empty();
+    mock.set_echo(true);
+    let server = TestServer::start_shared(Arc::clone(&mock)).await.unwrap();
+    let mut session = server.session().await.unwrap();
+
+    let parameters = HashMap::from([
+        (
+            "b".to_owned(),
+            Value::List(vec![Value::Integer(-1), Value::Null]),
+        ),
+        ("a".to_owned(), Value::Bytes(vec![0, 255])),
+    ]);
+    let mut cursor = session
+        .execute("RETURN $b, $a", parameters.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        cursor.column_names().await.unwrap(),
+        ["statement", "parameter", "value"]
+    );
+    let rows = cursor.collect_rows().await.unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![
+                Value::from("RETURN $b, $a"),
+                Value::from("a"),
+                parameters["a"].clone()
+            ],
+            vec![
+                Value::from("RETURN $b, $a"),
+                Value::from("b"),
+                parameters["b"].clone()
+            ],
+        ]
+    );
+
+    let rows = session
+        .execute_simple("INSERT (:Person)")
+        .await
+        .unwrap()
+        .collect_rows()
+        .await
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![vec![
+            Value::from("INSERT (:Person)"),
+            Value::Null,
+            Value::Null
+        ]]
+    );
+
+    mock.set_echo(false);
+    let mut cursor = session.execute_simple("RETURN $b, $a").await.unwrap();
+    assert!(cursor.collect_rows().await.unwrap().is_empty());
+}
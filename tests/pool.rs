@@ -0,0 +1,37 @@
+//! Integration tests for `GqlPool`.
+
+use gwp::client::GqlPool;
+use gwp::server::mock_backend::MockBackend;
+use gwp::testing::TestServer;
+
+#[tokio::test]
+async fn sessions_share_the_pooled_channels() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let pool = GqlPool::connect(&server.endpoint(), 2).await.unwrap();
+    assert_eq!(pool.size(), 2);
+
+    let mut sessions = Vec::new();
+    for _ in 0..5 {
+        sessions.push(pool.create_session().await.unwrap());
+    }
+    for session in &mut sessions {
+        let mut cursor = session.execute_simple("MATCH (n) RETURN n").await.unwrap();
+        assert!(cursor.is_success().await.unwrap());
+    }
+    assert_eq!(pool.health_check().await, 2);
+}
+
+#[tokio::test]
+async fn unreachable_servers_replace_channels_and_fail() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let pool = GqlPool::connect(&server.endpoint(), 3).await.unwrap();
+    server.shutdown().await.unwrap();
+
+    assert_eq!(pool.health_check().await, 0);
+    assert!(pool.create_session().await.is_err());
+}
+
+#[tokio::test]
+async fn empty_pools_are_rejected() {
+    assert!(GqlPool::connect("http://127.0.0.1:1", 0).await.is_err());
+}
@@ -0,0 +1,295 @@
+//! Integration tests for the `SearchService` gRPC service.
+
+use std::collections::HashMap;
+
+use gwp::proto;
+use gwp::proto::search_service_client::SearchServiceClient;
+use gwp::server::mock_backend::MockBackend;
+use gwp::server::{HybridSearchParams, TextSearchParams, VectorSearchParams};
+use gwp::testing::TestServer;
+use gwp::types::{Edge, Node, Value};
+
+fn connect(server: &TestServer<MockBackend>) -> SearchServiceClient<tonic::transport::Channel> {
+    SearchServiceClient::new(server.connection().channel().clone())
+}
+
+fn vector_request(k: u32, offset: u32, page_token: Option<String>) -> proto::VectorSearchRequest {
+    proto::VectorSearchRequest {
+        graph: "default".to_owned(),
+        label: "Person".to_owned(),
+        property: "embedding".to_owned(),
+        query_vector: vec![0.1, 0.2, 0.3],
+        k,
+        ef: None,
+        filters: HashMap::new(),
+        offset,
+        page_token,
+        include_elements: false,
+        target: proto::SearchTarget::SearchNodes.into(),
+    }
+}
+
+// =========================================================================
+// Pagination tests
+// =========================================================================
+
+#[tokio::test]
+async fn vector_search_first_page() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .vector_search(vector_request(2, 0, None))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(resp.hits.len(), 2);
+    assert_eq!(resp.hits[0].node_id, 1);
+    assert_eq!(resp.hits[1].node_id, 2);
+    assert_eq!(resp.next_page_token.as_deref(), Some("2"));
+    assert_eq!(resp.total_hits_estimate, Some(5));
+}
+
+#[tokio::test]
+async fn vector_search_pages_with_token() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let mut token = None;
+    let mut ids = Vec::new();
+    loop {
+        let resp = client
+            .vector_search(vector_request(2, 0, token))
+            .await
+            .unwrap()
+            .into_inner();
+        ids.extend(resp.hits.iter().map(|h| h.node_id));
+        token = resp.next_page_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+}
+
+#[tokio::test]
+async fn vector_search_with_offset() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .vector_search(vector_request(10, 3, None))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(resp.hits.len(), 2);
+    assert_eq!(resp.hits[0].node_id, 4);
+    assert!(resp.next_page_token.is_none());
+}
+
+#[tokio::test]
+async fn vector_search_invalid_page_token() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let result = client
+        .vector_search(vector_request(2, 0, Some("bogus".to_owned())))
+        .await;
+
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn text_search_pagination() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .text_search(proto::TextSearchRequest {
+            graph: "default".to_owned(),
+            label: "Person".to_owned(),
+            property: "bio".to_owned(),
+            query: "graph".to_owned(),
+            k: 3,
+            offset: 0,
+            page_token: Some("3".to_owned()),
+            include_elements: false,
+            target: proto::SearchTarget::SearchNodes.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(resp.hits.len(), 2);
+    assert_eq!(resp.hits[0].node_id, 4);
+    assert!(resp.next_page_token.is_none());
+    assert_eq!(resp.total_hits_estimate, Some(5));
+}
+
+#[tokio::test]
+async fn hybrid_search_pagination() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .hybrid_search(proto::HybridSearchRequest {
+            graph: "default".to_owned(),
+            label: "Person".to_owned(),
+            text_property: "bio".to_owned(),
+            vector_property: "embedding".to_owned(),
+            query_text: "graph".to_owned(),
+            query_vector: vec![0.1, 0.2],
+            k: 1,
+            offset: 1,
+            page_token: None,
+            include_elements: false,
+            target: proto::SearchTarget::SearchNodes.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(resp.hits.len(), 1);
+    assert_eq!(resp.hits[0].node_id, 2);
+    assert_eq!(resp.next_page_token.as_deref(), Some("2"));
+}
+
+// =========================================================================
+// Element tests
+// =========================================================================
+
+#[tokio::test]
+async fn vector_search_without_elements() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .vector_search(vector_request(1, 0, None))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert!(resp.hits[0].node.is_none());
+}
+
+#[tokio::test]
+async fn vector_search_includes_elements() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let mut req = vector_request(1, 0, None);
+    req.include_elements = true;
+    let resp = client.vector_search(req).await.unwrap().into_inner();
+
+    let node = Node::from(resp.hits[0].node.clone().unwrap());
+    assert_eq!(node.id, 1_u64.to_be_bytes());
+    assert!(node.has_label("Person"));
+    assert_eq!(node.property("name"), Some(&Value::from("Alice")));
+}
+
+// =========================================================================
+// Edge search tests
+// =========================================================================
+
+#[tokio::test]
+async fn vector_search_nodes_have_no_endpoints() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .vector_search(vector_request(1, 0, None))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert!(resp.hits[0].source_node_id.is_none());
+    assert!(resp.hits[0].target_node_id.is_none());
+    assert!(resp.hits[0].edge.is_none());
+}
+
+#[tokio::test]
+async fn vector_search_edges_returns_endpoints() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let mut req = vector_request(5, 0, None);
+    req.label = "KNOWS".to_owned();
+    req.target = proto::SearchTarget::SearchEdges.into();
+    let resp = client.vector_search(req).await.unwrap().into_inner();
+
+    assert_eq!(resp.hits.len(), 5);
+    assert_eq!(resp.hits[0].source_node_id, Some(1));
+    assert_eq!(resp.hits[0].target_node_id, Some(2));
+    assert_eq!(resp.hits[4].source_node_id, Some(5));
+    assert_eq!(resp.hits[4].target_node_id, Some(1));
+    assert!(resp.hits[0].node.is_none());
+    assert!(resp.hits[0].edge.is_none());
+}
+
+#[tokio::test]
+async fn text_search_edges_includes_elements() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = connect(&server);
+
+    let resp = client
+        .text_search(proto::TextSearchRequest {
+            graph: "default".to_owned(),
+            label: "KNOWS".to_owned(),
+            property: "note".to_owned(),
+            query: "colleague".to_owned(),
+            k: 1,
+            offset: 0,
+            page_token: None,
+            include_elements: true,
+            target: proto::SearchTarget::SearchEdges.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let edge = Edge::from(resp.hits[0].edge.clone().unwrap());
+    assert_eq!(edge.id, 1_u64.to_be_bytes());
+    assert_eq!(edge.source_node_id, 1_u64.to_be_bytes());
+    assert_eq!(edge.target_node_id, 2_u64.to_be_bytes());
+    assert!(edge.labels.iter().any(|l| l == "KNOWS"));
+    assert_eq!(edge.property("since"), Some(&Value::from(2021_i64)));
+}
+
+// =========================================================================
+// SearchClient tests
+// =========================================================================
+
+#[tokio::test]
+async fn search_client_builds_requests() {
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let mut client = server.search_client();
+
+    let params = VectorSearchParams::new("default", "Person", "embedding", vec![0.1, 0.2], 5)
+        .ef(64)
+        .filter("name", "Carol");
+    let results = client.vector_search(params).await.unwrap();
+    assert_eq!(results.hits.len(), 1);
+    assert_eq!(results.hits[0].properties["name"], Value::from("Carol"));
+
+    let params = TextSearchParams::new("default", "Person", "bio", "engineer", 2)
+        .offset(1)
+        .include_elements();
+    let results = client.text_search(params).await.unwrap();
+    assert_eq!(results.hits[0].node_id, 2);
+    assert!(results.hits[0].node.is_some());
+
+    let params = HybridSearchParams::new("default", "KNOWS", "note", "embedding", "work", 1)
+        .query_vector(vec![0.5])
+        .edges();
+    let results = client.hybrid_search(params).await.unwrap();
+    assert!(results.hits[0].source_node_id.is_some());
+    let token = results.next_page_token.unwrap();
+
+    let params = HybridSearchParams::new("default", "KNOWS", "note", "embedding", "work", 1)
+        .page_token(token);
+    let results = client.hybrid_search(params).await.unwrap();
+    assert_eq!(results.hits[0].node_id, 2);
+}
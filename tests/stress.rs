@@ -15,34 +15,14 @@ use tonic::transport::Channel;
 use gwp::client::GqlConnection;
 use gwp::proto;
 use gwp::proto::session_service_client::SessionServiceClient;
+use gwp::server::CreateGraphConfig;
 use gwp::server::mock_backend::MockBackend;
-use gwp::server::{CreateGraphConfig, GqlServer};
+use gwp::testing::TestServer;
 
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
-/// Spin up a server with optional `max_sessions` and `idle_timeout`, return addr.
-async fn start_server(max_sessions: Option<usize>, idle_timeout: Option<Duration>) -> SocketAddr {
-    let backend = MockBackend::new();
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
-    let addr = listener.local_addr().unwrap();
-    drop(listener);
-
-    tokio::spawn(async move {
-        let mut builder = GqlServer::builder(backend);
-        if let Some(limit) = max_sessions {
-            builder = builder.max_sessions(limit);
-        }
-        if let Some(timeout) = idle_timeout {
-            builder = builder.idle_timeout(timeout);
-        }
-        builder.serve(addr).await.unwrap();
-    });
-    tokio::time::sleep(Duration::from_millis(100)).await;
-    addr
-}
-
 /// Create a handshake via raw gRPC and return (client, `session_id`).
 async fn handshake(addr: SocketAddr) -> (SessionServiceClient<Channel>, String) {
     let channel = Channel::from_shared(format!("http://{addr}"))
@@ -55,7 +35,12 @@ async fn handshake(addr: SocketAddr) -> (SessionServiceClient<Channel>, String)
         .handshake(proto::HandshakeRequest {
             protocol_version: 1,
             client_info: HashMap::new(),
+            resumption_token: None,
+            row_batch_checksums: false,
+            dictionary_encoding: false,
+            columnar_batches: false,
             credentials: None,
+            database: None,
         })
         .await
         .unwrap()
@@ -69,7 +54,8 @@ async fn handshake(addr: SocketAddr) -> (SessionServiceClient<Channel>, String)
 
 #[tokio::test]
 async fn stress_connection_churn() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let mut set = JoinSet::new();
 
     for _ in 0..200 {
@@ -97,7 +83,10 @@ async fn stress_connection_churn() {
 #[tokio::test]
 async fn stress_session_limit() {
     let limit = 50;
-    let addr = start_server(Some(limit), None).await;
+    let server = TestServer::start_with(MockBackend::new(), |b| b.max_sessions(limit))
+        .await
+        .unwrap();
+    let addr = server.addr();
 
     // Open `limit` sessions — keep connections alive
     let mut sessions = Vec::new();
@@ -146,7 +135,10 @@ async fn stress_session_limit() {
 #[tokio::test]
 async fn stress_concurrent_session_storm() {
     let limit = 100;
-    let addr = start_server(Some(limit), None).await;
+    let server = TestServer::start_with(MockBackend::new(), |b| b.max_sessions(limit))
+        .await
+        .unwrap();
+    let addr = server.addr();
     let barrier = Arc::new(Barrier::new(150));
 
     let mut set = JoinSet::new();
@@ -188,7 +180,10 @@ async fn stress_concurrent_session_storm() {
 #[tokio::test]
 async fn stress_idle_timeout_reaping() {
     let timeout = Duration::from_secs(2);
-    let addr = start_server(None, Some(timeout)).await;
+    let server = TestServer::start_with(MockBackend::new(), |b| b.idle_timeout(timeout))
+        .await
+        .unwrap();
+    let addr = server.addr();
 
     let (mut client, session_id) = handshake(addr).await;
 
@@ -215,7 +210,10 @@ async fn stress_idle_timeout_reaping() {
 #[tokio::test]
 async fn stress_idle_timeout_kept_alive_by_ping() {
     let timeout = Duration::from_secs(3);
-    let addr = start_server(None, Some(timeout)).await;
+    let server = TestServer::start_with(MockBackend::new(), |b| b.idle_timeout(timeout))
+        .await
+        .unwrap();
+    let addr = server.addr();
 
     let (mut client, session_id) = handshake(addr).await;
 
@@ -247,7 +245,8 @@ async fn stress_idle_timeout_kept_alive_by_ping() {
 
 #[tokio::test]
 async fn stress_transaction_double_begin() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let conn = GqlConnection::connect(&format!("http://{addr}"))
         .await
         .unwrap();
@@ -262,7 +261,8 @@ async fn stress_transaction_double_begin() {
 
 #[tokio::test]
 async fn stress_transaction_parallel_sessions() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let mut set = JoinSet::new();
 
     for _ in 0..50 {
@@ -299,7 +299,8 @@ async fn stress_transaction_parallel_sessions() {
 
 #[tokio::test]
 async fn stress_query_flood_sequential() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let conn = GqlConnection::connect(&format!("http://{addr}"))
         .await
         .unwrap();
@@ -326,7 +327,8 @@ async fn stress_query_flood_sequential() {
 
 #[tokio::test]
 async fn stress_query_flood_parallel_sessions() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let barrier = Arc::new(Barrier::new(20));
     let mut set = JoinSet::new();
 
@@ -369,7 +371,8 @@ async fn stress_query_flood_parallel_sessions() {
 
 #[tokio::test]
 async fn stress_config_churn() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let conn = GqlConnection::connect(&format!("http://{addr}"))
         .await
         .unwrap();
@@ -400,7 +403,8 @@ async fn stress_config_churn() {
 
 #[tokio::test]
 async fn stress_catalog_operations() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let conn = GqlConnection::connect(&format!("http://{addr}"))
         .await
         .unwrap();
@@ -444,7 +448,8 @@ async fn stress_catalog_operations() {
 
 #[tokio::test]
 async fn stress_mixed_workload() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let mut set = JoinSet::new();
 
     // Readers
@@ -549,7 +554,8 @@ async fn stress_mixed_workload() {
 
 #[tokio::test]
 async fn stress_close_with_active_transaction() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
 
     for _ in 0..50 {
         let conn = GqlConnection::connect(&format!("http://{addr}"))
@@ -575,7 +581,8 @@ async fn stress_close_with_active_transaction() {
 
 #[tokio::test]
 async fn stress_rapid_transaction_cycle() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let conn = GqlConnection::connect(&format!("http://{addr}"))
         .await
         .unwrap();
@@ -603,7 +610,8 @@ async fn stress_rapid_transaction_cycle() {
 
 #[tokio::test]
 async fn stress_ping_flood() {
-    let addr = start_server(None, None).await;
+    let server = TestServer::start(MockBackend::new()).await.unwrap();
+    let addr = server.addr();
     let conn = GqlConnection::connect(&format!("http://{addr}"))
         .await
         .unwrap();
@@ -0,0 +1,127 @@
+//! Mutual TLS tests: sessions created over a connection whose client
+//! presented a certificate run as the principal it names.
+
+#![cfg(feature = "tls")]
+
+use std::sync::Arc;
+
+use gwp::client::GqlConnection;
+use gwp::error::GqlError;
+use gwp::proto;
+use gwp::server::mock_backend::{MockBackend, MockCall};
+use gwp::server::{AuthValidator, CLIENT_CERT_SUBJECT, ClientCertificate, GqlServer, Principal};
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, DnType, IsCa, Issuer, KeyPair};
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+
+/// Accepts certificates issued to `alice` and rejects the rest.
+struct AliceOnly;
+
+#[tonic::async_trait]
+impl AuthValidator for AliceOnly {
+    async fn validate(&self, _credentials: &proto::AuthCredentials) -> Result<(), GqlError> {
+        Err(GqlError::Session("certificates only".to_owned()))
+    }
+
+    async fn validate_tls(&self, certificate: &ClientCertificate) -> Result<Principal, GqlError> {
+        match certificate.common_name.as_deref() {
+            Some("alice") => Ok(Principal::from_certificate(certificate)),
+            _ => Err(GqlError::Session("unknown client".to_owned())),
+        }
+    }
+}
+
+/// A certificate for `common_name` and its key, signed by `issuer`, in
+/// PEM.
+fn issue(issuer: &Issuer<'_, KeyPair>, common_name: &str) -> (String, String) {
+    let mut params = CertificateParams::new(vec![common_name.to_owned()]).unwrap();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    let key = KeyPair::generate().unwrap();
+    let cert = params.signed_by(&key, issuer).unwrap();
+    (cert.pem(), key.serialize_pem())
+}
+
+/// A test CA.
+fn authority() -> CertifiedIssuer<'static, KeyPair> {
+    let mut params = CertificateParams::new(Vec::new()).unwrap();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "gwp test CA");
+    CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap()
+}
+
+/// Start a server that requires client certificates from `ca`, returning
+/// its endpoint.
+async fn start(ca: &CertifiedIssuer<'static, KeyPair>, backend: Arc<MockBackend>) -> String {
+    let (cert, key) = issue(ca, "localhost");
+    let tls = ServerTlsConfig::new()
+        .identity(Identity::from_pem(cert, key))
+        .client_ca_root(Certificate::from_pem(ca.pem()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        GqlServer::builder_shared(backend)
+            .tls(tls)
+            .auth(AliceOnly)
+            .serve_with_listener(listener),
+    );
+    format!("https://{addr}")
+}
+
+/// Connect to `endpoint` presenting a certificate issued to `common_name`.
+async fn connect(
+    ca: &CertifiedIssuer<'static, KeyPair>,
+    endpoint: &str,
+    common_name: &str,
+) -> GqlConnection {
+    let (cert, key) = issue(ca, common_name);
+    let tls = ClientTlsConfig::new()
+        .ca_certificate(Certificate::from_pem(ca.pem()))
+        .identity(Identity::from_pem(cert, key))
+        .domain_name("localhost");
+    GqlConnection::connect_tls(endpoint, tls).await.unwrap()
+}
+
+#[tokio::test]
+async fn client_certificates_authenticate_sessions() {
+    let ca = authority();
+    let backend = Arc::new(MockBackend::new());
+    let endpoint = start(&ca, Arc::clone(&backend)).await;
+
+    let mut session = connect(&ca, &endpoint, "alice")
+        .await
+        .create_session()
+        .await
+        .unwrap();
+    session.ping().await.unwrap();
+
+    let client_info = backend
+        .calls()
+        .into_iter()
+        .find_map(|call| match call {
+            MockCall::CreateSession { client_info, .. } => Some(client_info),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(client_info[CLIENT_CERT_SUBJECT], "CN=alice");
+}
+
+#[tokio::test]
+async fn rejected_client_certificates_fail_the_handshake() {
+    let ca = authority();
+    let endpoint = start(&ca, Arc::new(MockBackend::new())).await;
+
+    let Err(err) = connect(&ca, &endpoint, "mallory")
+        .await
+        .create_session()
+        .await
+    else {
+        panic!("expected the handshake to fail");
+    };
+    let GqlError::Grpc(status) = err else {
+        panic!("expected a gRPC status, got {err:?}");
+    };
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+}
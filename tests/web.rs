@@ -0,0 +1,130 @@
+//! gRPC-Web tests: browser-style HTTP/1.1 requests reach the GQL services
+//! through the `web` layers.
+
+#![cfg(feature = "web")]
+
+use std::collections::HashMap;
+
+use http::HeaderName;
+use prost::Message;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use gwp::proto;
+use gwp::server::mock_backend::MockBackend;
+use gwp::testing::TestServer;
+
+const ORIGIN: &str = "https://console.example.com";
+
+async fn start() -> TestServer<MockBackend> {
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::exact(ORIGIN.parse().unwrap()))
+        .allow_headers([
+            HeaderName::from_static("content-type"),
+            HeaderName::from_static("x-grpc-web"),
+        ])
+        .expose_headers([
+            HeaderName::from_static("grpc-status"),
+            HeaderName::from_static("grpc-message"),
+        ]);
+    TestServer::start_with(MockBackend::new(), |builder| builder.grpc_web(cors))
+        .await
+        .unwrap()
+}
+
+/// Send a raw HTTP/1.1 request and return the response head and body.
+async fn send(server: &TestServer<MockBackend>, head: &str, body: &[u8]) -> (String, Vec<u8>) {
+    let mut stream = tokio::net::TcpStream::connect(server.addr()).await.unwrap();
+    let request = format!(
+        "{head}\r\nhost: localhost\r\norigin: {ORIGIN}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    stream.write_all(body).await.unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = String::from_utf8(response[..split].to_vec())
+        .unwrap()
+        .to_ascii_lowercase();
+    let mut body = response[split + 4..].to_vec();
+    if head.contains("transfer-encoding: chunked") {
+        body = dechunk(&body);
+    }
+    (head, body)
+}
+
+/// Decode an HTTP/1.1 chunked body.
+fn dechunk(mut chunked: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    loop {
+        let line = chunked.windows(2).position(|w| w == b"\r\n").unwrap();
+        let size = std::str::from_utf8(&chunked[..line]).unwrap();
+        let size = usize::from_str_radix(size.trim(), 16).unwrap();
+        if size == 0 {
+            return body;
+        }
+        let start = line + 2;
+        body.extend_from_slice(&chunked[start..start + size]);
+        chunked = &chunked[start + size + 2..];
+    }
+}
+
+#[tokio::test]
+async fn grpc_web_requests_reach_the_session_service() {
+    let server = start().await;
+    let request = proto::HandshakeRequest {
+        protocol_version: 1,
+        credentials: None,
+        client_info: HashMap::new(),
+        resumption_token: None,
+        row_batch_checksums: false,
+        dictionary_encoding: false,
+        columnar_batches: false,
+        database: None,
+    }
+    .encode_to_vec();
+    let mut frame = vec![0];
+    frame.extend_from_slice(&u32::try_from(request.len()).unwrap().to_be_bytes());
+    frame.extend_from_slice(&request);
+
+    let (head, body) = send(
+        &server,
+        "POST /gql.SessionService/Handshake HTTP/1.1\r\n\
+         content-type: application/grpc-web+proto\r\n\
+         x-grpc-web: 1",
+        &frame,
+    )
+    .await;
+
+    assert!(head.starts_with("http/1.1 200"), "{head}");
+    assert!(head.contains("content-type: application/grpc-web+proto"));
+    assert!(head.contains(&format!("access-control-allow-origin: {ORIGIN}")));
+    // A data frame with the response, then a trailers frame.
+    assert_eq!(body[0], 0);
+    let len = u32::from_be_bytes(body[1..5].try_into().unwrap()) as usize;
+    let response = proto::HandshakeResponse::decode(&body[5..5 + len]).unwrap();
+    assert!(!response.session_id.is_empty());
+    assert_eq!(body[5 + len], 0x80);
+    let trailers = String::from_utf8_lossy(&body[5 + len + 5..]).to_ascii_lowercase();
+    assert!(trailers.contains("grpc-status:0"), "{trailers}");
+}
+
+#[tokio::test]
+async fn cors_preflight_is_answered() {
+    let server = start().await;
+
+    let (head, _) = send(
+        &server,
+        "OPTIONS /gql.GqlService/Execute HTTP/1.1\r\n\
+         access-control-request-method: POST\r\n\
+         access-control-request-headers: content-type,x-grpc-web",
+        &[],
+    )
+    .await;
+
+    assert!(head.starts_with("http/1.1 200"), "{head}");
+    assert!(head.contains(&format!("access-control-allow-origin: {ORIGIN}")));
+    assert!(head.contains("access-control-allow-headers: content-type,x-grpc-web"));
+}